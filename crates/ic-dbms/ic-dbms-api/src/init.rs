@@ -26,16 +26,57 @@ impl IcDbmsCanisterArgs {
     }
 }
 
-#[derive(Debug, CandidType, Serialize, Deserialize)]
+#[derive(Debug, Default, CandidType, Serialize, Deserialize)]
 pub struct IcDbmsCanisterInitArgs {
     /// Initial admins to bootstrap the granular ACL with full perms
     /// (`admin` + `manage_acl` + `migrate` + every table perm). When
     /// `None` or empty, the deployer principal is used.
     pub allowed_principals: Option<Vec<candid::Principal>>,
+    /// Time-to-live of transactions, in nanoseconds. A transaction still
+    /// open past its TTL is dropped by the expiry sweep. When `None`,
+    /// transactions never expire.
+    pub transaction_ttl: Option<u64>,
+    /// Interval between two expiry sweeps, in nanoseconds. Ignored when
+    /// `transaction_ttl` is `None`; defaults to one minute.
+    pub transaction_sweep_interval: Option<u64>,
+    /// Maximum number of transactions a principal may keep open at once.
+    /// Defaults to 8.
+    pub max_open_transactions: Option<u32>,
+    /// Maximum size of a blob, in bytes. Defaults to 64 MiB.
+    pub max_blob_size: Option<u64>,
+    /// Maximum size of a chunk read or written by the `blob_read_chunk_*` and
+    /// `blob_write_chunk_*` endpoints, in bytes. Defaults to 1 MiB.
+    pub max_blob_chunk_size: Option<u64>,
+    /// Maximum number of rows deleted or updated by a single call of the
+    /// `delete_chunked_*` and `update_chunked_*` endpoints. Defaults to 10 000.
+    pub max_chunk_rows: Option<u64>,
 }
 
-#[derive(Debug, CandidType, Serialize, Deserialize)]
-pub struct IcDbmsCanisterUpgradeArgs;
+#[derive(Debug, Default, CandidType, Serialize, Deserialize)]
+pub struct IcDbmsCanisterUpgradeArgs {
+    /// Time-to-live of transactions, in nanoseconds. The TTL lives on the
+    /// heap, so it must be passed again on every upgrade; when `None`,
+    /// transactions never expire.
+    pub transaction_ttl: Option<u64>,
+    /// Interval between two expiry sweeps, in nanoseconds. Ignored when
+    /// `transaction_ttl` is `None`; defaults to one minute.
+    pub transaction_sweep_interval: Option<u64>,
+    /// Maximum number of transactions a principal may keep open at once.
+    /// Defaults to 8.
+    pub max_open_transactions: Option<u32>,
+    /// Maximum size of a blob, in bytes. Defaults to 64 MiB.
+    pub max_blob_size: Option<u64>,
+    /// Maximum size of a chunk read or written by the `blob_read_chunk_*` and
+    /// `blob_write_chunk_*` endpoints, in bytes. Defaults to 1 MiB.
+    pub max_blob_chunk_size: Option<u64>,
+    /// Maximum number of rows deleted or updated by a single call of the
+    /// `delete_chunked_*` and `update_chunked_*` endpoints. Defaults to 10 000.
+    pub max_chunk_rows: Option<u64>,
+    /// When `Some(true)`, schema changes are not applied on upgrade: the
+    /// canister enters drift state until `migrate` is called. By default,
+    /// additive changes are applied and any other change fails the upgrade.
+    pub manual_migration: Option<bool>,
+}
 
 #[cfg(test)]
 mod tests {
@@ -47,6 +88,7 @@ mod tests {
         let principals = vec![candid::Principal::anonymous()];
         let args = IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs {
             allowed_principals: Some(principals.clone()),
+            ..Default::default()
         });
         let init = args.unwrap_init();
         assert_eq!(init.allowed_principals, Some(principals));
@@ -54,14 +96,18 @@ mod tests {
 
     #[test]
     fn test_unwrap_update_on_upgrade_variant() {
-        let args = IcDbmsCanisterArgs::Upgrade(IcDbmsCanisterUpgradeArgs);
-        let _upgrade = args.unwrap_update();
+        let args = IcDbmsCanisterArgs::Upgrade(IcDbmsCanisterUpgradeArgs {
+            max_open_transactions: Some(4),
+            ..Default::default()
+        });
+        let upgrade = args.unwrap_update();
+        assert_eq!(upgrade.max_open_transactions, Some(4));
     }
 
     #[test]
     #[should_panic]
     fn test_unwrap_init_on_upgrade_variant_traps() {
-        let args = IcDbmsCanisterArgs::Upgrade(IcDbmsCanisterUpgradeArgs);
+        let args = IcDbmsCanisterArgs::Upgrade(IcDbmsCanisterUpgradeArgs::default());
         let _init = args.unwrap_init();
     }
 
@@ -70,6 +116,7 @@ mod tests {
     fn test_unwrap_update_on_init_variant_traps() {
         let args = IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs {
             allowed_principals: Some(vec![]),
+            ..Default::default()
         });
         let _upgrade = args.unwrap_update();
     }
@@ -78,24 +125,35 @@ mod tests {
     fn test_candid_roundtrip_init_args() {
         let args = IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs {
             allowed_principals: Some(vec![candid::Principal::anonymous()]),
+            transaction_ttl: Some(60_000_000_000),
+            transaction_sweep_interval: None,
+            max_open_transactions: Some(16),
+            max_blob_size: Some(1 << 20),
+            max_blob_chunk_size: None,
+            max_chunk_rows: Some(500),
         });
         let encoded = candid::encode_one(&args).expect("failed to encode");
         let decoded: IcDbmsCanisterArgs = candid::decode_one(&encoded).expect("failed to decode");
-        assert!(matches!(decoded, IcDbmsCanisterArgs::Init(_)));
+        let init = decoded.unwrap_init();
+        assert_eq!(init.transaction_ttl, Some(60_000_000_000));
+        assert_eq!(init.transaction_sweep_interval, None);
+        assert_eq!(init.max_open_transactions, Some(16));
+        assert_eq!(init.max_blob_size, Some(1 << 20));
+        assert_eq!(init.max_blob_chunk_size, None);
+        assert_eq!(init.max_chunk_rows, Some(500));
     }
 
     #[test]
     fn test_init_args_default_allowed_is_none() {
-        let args = IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs {
-            allowed_principals: None,
-        });
+        let args = IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs::default());
         let init = args.unwrap_init();
         assert!(init.allowed_principals.is_none());
+        assert!(init.transaction_ttl.is_none());
     }
 
     #[test]
     fn test_candid_roundtrip_upgrade_args() {
-        let args = IcDbmsCanisterArgs::Upgrade(IcDbmsCanisterUpgradeArgs);
+        let args = IcDbmsCanisterArgs::Upgrade(IcDbmsCanisterUpgradeArgs::default());
         let encoded = candid::encode_one(&args).expect("failed to encode");
         let decoded: IcDbmsCanisterArgs = candid::decode_one(&encoded).expect("failed to decode");
         assert!(matches!(decoded, IcDbmsCanisterArgs::Upgrade(_)));
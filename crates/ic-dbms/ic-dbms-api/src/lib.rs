@@ -9,8 +9,9 @@
 //! IC-specific API types for ic-dbms-canister.
 //!
 //! This crate re-exports all generic types from [`wasm_dbms_api`] and adds
-//! IC-specific types such as [`Principal`](crate::prelude::Principal) and
-//! canister init arguments.
+//! IC-specific types such as [`Principal`](crate::prelude::Principal),
+//! canister init arguments and the
+//! [`TimestampExt::now`](crate::prelude::TimestampExt::now) clock.
 //!
 //! Import all useful types and traits via the prelude:
 //!
@@ -36,5 +37,8 @@ mod error;
 mod init;
 pub mod prelude;
 mod principal;
+mod request;
 #[cfg(test)]
 mod tests;
+mod timestamp;
+mod transaction;
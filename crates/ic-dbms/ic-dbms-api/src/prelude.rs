@@ -7,3 +7,6 @@ pub use wasm_dbms_api::prelude::*;
 pub use crate::error::{IcDbmsError, IcDbmsResult};
 pub use crate::init::{IcDbmsCanisterArgs, IcDbmsCanisterInitArgs, IcDbmsCanisterUpgradeArgs};
 pub use crate::principal::Principal;
+pub use crate::request::RequestId;
+pub use crate::timestamp::TimestampExt;
+pub use crate::transaction::TransactionInfo;
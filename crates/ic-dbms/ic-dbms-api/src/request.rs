@@ -0,0 +1,7 @@
+/// Client-generated identifier of a write request.
+///
+/// When a write endpoint (`insert_<table>`, `update_<table>`,
+/// `delete_<table>`) receives a request ID, the DBMS canister remembers the
+/// response for a bounded window: a retried call carrying the same ID gets the
+/// remembered response instead of being applied twice.
+pub type RequestId = String;
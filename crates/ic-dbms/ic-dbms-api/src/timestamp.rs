@@ -0,0 +1,15 @@
+use wasm_dbms_api::prelude::Timestamp;
+
+/// IC-specific constructors for [`Timestamp`].
+pub trait TimestampExt {
+    /// Returns the current IC system time, in nanoseconds since the Unix epoch.
+    ///
+    /// Within a single message execution every call returns the same value.
+    fn now() -> Self;
+}
+
+impl TimestampExt for Timestamp {
+    fn now() -> Self {
+        Timestamp(ic_cdk::api::time())
+    }
+}
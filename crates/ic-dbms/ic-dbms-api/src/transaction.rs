@@ -0,0 +1,18 @@
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+use wasm_dbms_api::prelude::TransactionId;
+
+/// Metadata of a transaction open on the DBMS canister, as returned by the
+/// `list_active_transactions` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, CandidType, Serialize, Deserialize)]
+pub struct TransactionInfo {
+    /// ID of the transaction.
+    pub id: TransactionId,
+    /// Principal which began the transaction.
+    pub principal: Principal,
+    /// IC time the transaction was begun at, in nanoseconds since the Unix
+    /// epoch.
+    pub started_at: u64,
+    /// Number of operations recorded in the transaction so far.
+    pub operation_count: u64,
+}
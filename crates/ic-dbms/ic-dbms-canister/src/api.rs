@@ -3,19 +3,27 @@
 
 //! API generic interface to be used by different DBMS canisters.
 
+mod hooks;
+mod idempotency;
 mod inspect;
 
+use std::cell::Cell;
 use std::collections::HashSet;
 
 use candid::Principal;
 use ic_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, ColumnDef, Database, DbmsError, DeleteBehavior, Filter,
-    IcDbmsResult, IdentityPerms, InsertRecord, JoinColumnDef, MigrationOp, MigrationPolicy,
-    PermGrant, PermRevoke, Query, QueryError, RequiredPerm, TableFingerprint, TablePerms,
-    TableSchema, TransactionId, UpdateRecord, Value, fingerprint_for_name,
+    AggregateFunction, AggregatedRow, BlobChunk, ColumnDef, CompactionReport, ContinuationToken,
+    Database, DbmsError, DeleteBehavior, Filter, IcDbmsResult, IdentityPerms,
+    InsertConflictBehavior, InsertRecord, JoinColumnDef, MigrationError, MigrationOp,
+    MigrationPolicy, PermGrant, PermRevoke, Query, QueryError, RequestId, RequiredPerm,
+    RetentionPolicy, TableFingerprint, TablePerms, TableSchema, TableStats, TransactionId,
+    TransactionInfo, UpdateRecord, UpsertResult, VacuumReport, Value, fingerprint_for_name,
 };
 use wasm_dbms::prelude::{DatabaseSchema, WasmDbmsDatabase};
 
+pub use self::hooks::{DeleteHook, InsertHook, TableHooks, UpdateHook, set_table_hooks};
+use self::idempotency::WriteOp;
+pub use self::idempotency::{MAX_REMEMBERED_REQUESTS, REQUEST_ID_TTL};
 pub use self::inspect::inspect;
 use crate::memory::{DBMS_CONTEXT, IcAccessControlList, IcMemoryProvider};
 use crate::trap;
@@ -105,6 +113,20 @@ pub fn list_identities() -> IcDbmsResult<Vec<(Principal, IdentityPerms)>> {
     })
 }
 
+/// Lists every principal in the ACL, whatever its perms. Caller must be a
+/// controller of the canister, so it works even for a controller that is not
+/// in the ACL.
+pub fn acl_list_all_principals() -> IcDbmsResult<Vec<Principal>> {
+    let caller = crate::utils::caller();
+    if !crate::utils::is_controller(&caller) {
+        return Err(DbmsError::AccessDenied {
+            table: None,
+            required: RequiredPerm::Controller,
+        });
+    }
+    Ok(DBMS_CONTEXT.with(|ctx| ctx.acl_list_all()))
+}
+
 /// Returns the caller's own [`IdentityPerms`]. Always permitted.
 pub fn my_perms() -> IdentityPerms {
     let caller = crate::utils::caller();
@@ -139,13 +161,174 @@ fn apply_revoke(target: Principal, r: PermRevoke) -> IcDbmsResult<()> {
 
 // --- Transactions ----------------------------------------------------------
 
+/// Interval between two expiry sweeps when none is configured: one minute.
+const DEFAULT_TRANSACTION_SWEEP_INTERVAL: u64 = 60_000_000_000;
+
+thread_local! {
+    /// TTL given to new transactions, in nanoseconds.
+    static TRANSACTION_TTL: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Configures the TTL of new transactions and starts the periodic sweep that
+/// drops the expired ones. Called from the canister `init` and
+/// `post_upgrade`, as neither survives an upgrade.
+///
+/// Both values are in nanoseconds. With a `None` TTL transactions never
+/// expire and no sweep is scheduled; a `None` sweep interval defaults to one
+/// minute, so a transaction may outlive its TTL by up to one interval.
+pub fn init_transaction_expiry(ttl: Option<u64>, sweep_interval: Option<u64>) {
+    TRANSACTION_TTL.with(|cell| cell.set(ttl));
+    if ttl.is_none() {
+        return;
+    }
+
+    let interval = std::time::Duration::from_nanos(
+        sweep_interval.unwrap_or(DEFAULT_TRANSACTION_SWEEP_INTERVAL),
+    );
+    // timers only run on the IC; unit tests call `expire_transactions` directly
+    #[cfg(target_family = "wasm")]
+    ic_cdk_timers::set_timer_interval(interval, || async {
+        expire_transactions();
+    });
+    #[cfg(not(target_family = "wasm"))]
+    let _ = interval;
+}
+
+/// Drops every transaction past its TTL, reclaiming its overlay, and returns
+/// the IDs of the dropped transactions.
+pub fn expire_transactions() -> Vec<TransactionId> {
+    let now = crate::utils::time();
+    let expired = DBMS_CONTEXT.with(|ctx| ctx.expire_transactions(now));
+    for transaction_id in &expired {
+        idempotency::forget(*transaction_id);
+    }
+    expired
+}
+
+/// Maximum number of transactions a principal may keep open when none is
+/// configured.
+pub const DEFAULT_MAX_OPEN_TRANSACTIONS: u32 = 8;
+
+/// Sets the maximum number of transactions a principal may keep open,
+/// falling back to [`DEFAULT_MAX_OPEN_TRANSACTIONS`]. Called from the
+/// canister `init` and `post_upgrade`.
+pub fn set_max_open_transactions(limit: Option<u32>) {
+    let limit = limit.unwrap_or(DEFAULT_MAX_OPEN_TRANSACTIONS);
+    DBMS_CONTEXT.with(|ctx| ctx.set_max_open_transactions(Some(limit)));
+}
+
+/// Maximum size of a blob, in bytes, when none is configured.
+pub const DEFAULT_MAX_BLOB_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Maximum size of a blob chunk, in bytes, when none is configured. Keeps a
+/// chunk well below the ingress message limit.
+pub const DEFAULT_MAX_BLOB_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// Sets the maximum size of a blob and of a chunk read or written by
+/// [`blob_read_chunk`] and [`blob_write_chunk`], falling back to
+/// [`DEFAULT_MAX_BLOB_SIZE`] and [`DEFAULT_MAX_BLOB_CHUNK_SIZE`]. Called from
+/// the canister `init` and `post_upgrade`.
+pub fn set_blob_limits(max_blob_size: Option<u64>, max_chunk_size: Option<u64>) {
+    DBMS_CONTEXT.with(|ctx| {
+        ctx.set_max_blob_size(Some(max_blob_size.unwrap_or(DEFAULT_MAX_BLOB_SIZE)));
+        ctx.set_max_blob_chunk_size(Some(max_chunk_size.unwrap_or(DEFAULT_MAX_BLOB_CHUNK_SIZE)));
+    });
+}
+
+/// Maximum number of rows processed by a single call of [`delete_chunked`]
+/// or [`update_chunked`] when none is configured.
+pub const DEFAULT_MAX_CHUNK_ROWS: u64 = 10_000;
+
+/// Sets the maximum number of rows processed by a single call of
+/// [`delete_chunked`] and [`update_chunked`], falling back to
+/// [`DEFAULT_MAX_CHUNK_ROWS`]. Called from the canister `init` and
+/// `post_upgrade`.
+pub fn set_max_chunk_rows(limit: Option<u64>) {
+    let limit = limit.unwrap_or(DEFAULT_MAX_CHUNK_ROWS);
+    DBMS_CONTEXT.with(|ctx| ctx.set_max_chunk_rows(Some(limit)));
+}
+
+/// Registers the retention policies applied by [`vacuum`]. Called from the
+/// canister `init` and `post_upgrade`, as policies are not persisted.
+pub fn set_retention_policies(policies: &[RetentionPolicy]) {
+    DBMS_CONTEXT.with(|ctx| {
+        for policy in policies {
+            ctx.set_retention_policy(*policy);
+        }
+    });
+}
+
+/// Starts the periodic [`vacuum`] of the tables with a retention policy,
+/// every `interval` nanoseconds. Called from the canister `init` and
+/// `post_upgrade`, as timers don't survive an upgrade.
+pub fn init_vacuum<S>(interval: u64, database_schema: fn() -> S)
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    let interval = std::time::Duration::from_nanos(interval);
+    // timers only run on the IC; unit tests call `vacuum` directly
+    #[cfg(target_family = "wasm")]
+    ic_cdk_timers::set_timer_interval(interval, move || async move {
+        // a failed run is retried on the next tick
+        let _ = vacuum(database_schema());
+    });
+    #[cfg(not(target_family = "wasm"))]
+    let _ = (interval, database_schema);
+}
+
+/// Deletes the rows expired under the registered retention policies. Called
+/// by the vacuum timer, so it runs without any caller check.
+pub fn vacuum<S>(database_schema: S) -> IcDbmsResult<VacuumReport>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    DBMS_CONTEXT.with(|ctx| WasmDbmsDatabase::oneshot(ctx, database_schema).vacuum())
+}
+
 /// Begins a new transaction owned by the caller and returns its ID.
 ///
-/// Opening a transaction is unconditional — per-CRUD perm checks gate
-/// the data accesses inside the transaction.
+/// Opening a transaction requires no perm — per-CRUD perm checks gate
+/// the data accesses inside the transaction. The transaction expires after
+/// the TTL configured at init, if any.
+///
+/// Traps with [`TransactionError::TooManyOpenTransactions`] when the caller
+/// already holds the maximum number of open transactions.
+///
+/// [`TransactionError::TooManyOpenTransactions`]: ic_dbms_api::prelude::TransactionError::TooManyOpenTransactions
 pub fn begin_transaction() -> TransactionId {
     let owner = crate::utils::caller();
-    DBMS_CONTEXT.with(|ctx| ctx.begin_transaction(owner.as_slice().to_vec()))
+    let ttl = TRANSACTION_TTL.with(Cell::get);
+    let now = crate::utils::time();
+    match DBMS_CONTEXT
+        .with(|ctx| ctx.begin_transaction_with_ttl(owner.as_slice().to_vec(), now, ttl))
+    {
+        Ok(transaction_id) => transaction_id,
+        Err(err) => trap!("{err}"),
+    }
+}
+
+/// Lists the open transactions, sorted by ID, with their owner, start time
+/// and number of recorded operations. Caller must be a controller of the
+/// canister.
+pub fn list_active_transactions() -> IcDbmsResult<Vec<TransactionInfo>> {
+    let caller = crate::utils::caller();
+    if !crate::utils::is_controller(&caller) {
+        return Err(DbmsError::AccessDenied {
+            table: None,
+            required: RequiredPerm::Controller,
+        });
+    }
+    Ok(DBMS_CONTEXT.with(|ctx| {
+        ctx.list_active_transactions()
+            .into_iter()
+            .map(|info| TransactionInfo {
+                id: info.id,
+                principal: Principal::from_slice(&info.owner),
+                started_at: info.started_at.unwrap_or_default(),
+                operation_count: info.operation_count as u64,
+            })
+            .collect()
+    }))
 }
 
 /// Commits the transaction with the given ID. Caller must own the
@@ -158,7 +341,9 @@ where
     DBMS_CONTEXT.with(|ctx| {
         let mut db = WasmDbmsDatabase::from_transaction(ctx, database_schema, transaction_id);
         db.commit()
-    })
+    })?;
+    idempotency::commit(transaction_id);
+    Ok(())
 }
 
 /// Rolls back the transaction with the given ID. Caller must own the
@@ -171,7 +356,9 @@ where
     DBMS_CONTEXT.with(|ctx| {
         let mut db = WasmDbmsDatabase::from_transaction(ctx, database_schema, transaction_id);
         db.rollback()
-    })
+    })?;
+    idempotency::forget(transaction_id);
+    Ok(())
 }
 
 // --- CRUD ------------------------------------------------------------------
@@ -191,6 +378,23 @@ where
     with_database(transaction_id, database_schema, |db| db.select::<T>(query))
 }
 
+/// Returns the first record matching the query, optionally within a transaction.
+pub fn select_one<T, S>(
+    query: Query,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<Option<T::Record>>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::READ)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        db.select_one::<T>(query)
+    })
+}
+
 /// Executes a generic select query by table name, optionally within a transaction.
 ///
 /// Unlike [`select`], this method does not require a concrete table type.
@@ -250,10 +454,46 @@ where
     })
 }
 
+/// Counts the records matching the filter, optionally within a transaction.
+pub fn count<T, S>(
+    filter: Option<Filter>,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::READ)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| db.count::<T>(filter))
+}
+
+/// Returns whether any record matches the filter, optionally within a transaction.
+pub fn exists<T, S>(
+    filter: Filter,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<bool>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::READ)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| db.exists::<T>(filter))
+}
+
 /// Executes an insert query against the database schema, optionally within a transaction.
+///
+/// A retried call carrying the same `request_id` is not applied twice, see
+/// [`REQUEST_ID_TTL`]. On an `#[on_conflict = "update"]` table the insert may
+/// overwrite an existing row, so it requires the `UPDATE` perm on top of
+/// `INSERT`.
 pub fn insert<T, S>(
     record: T::Insert,
     transaction_id: Option<TransactionId>,
+    request_id: Option<RequestId>,
     database_schema: S,
 ) -> IcDbmsResult<()>
 where
@@ -261,242 +501,1773 @@ where
     T::Insert: InsertRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
 {
-    check_table_perm(T::fingerprint(), TablePerms::INSERT)?;
+    check_table_perm(T::fingerprint(), insert_perms::<T>(&record))?;
     assert_caller_owns_transaction(transaction_id.as_ref());
-    with_database(transaction_id, database_schema, |db| db.insert::<T>(record))
+    idempotency::run_once(
+        request_id,
+        transaction_id,
+        T::fingerprint(),
+        WriteOp::Insert,
+        || {
+            with_database(transaction_id, database_schema, |db| {
+                hooks::on_insert_or_conflict_update::<T>(db, &record)?;
+                db.insert::<T>(record)
+            })?;
+            Ok(0)
+        },
+    )
+    .map(|_| ())
 }
 
-/// Executes an update query against the database schema, optionally within a transaction.
-pub fn update<T, S>(
-    patch: T::Update,
+/// Executes an insert against the database schema, resolving conflicts with
+/// existing rows according to `behavior`, optionally within a transaction.
+///
+/// [`InsertConflictBehavior::Replace`] may overwrite an existing row, so it
+/// requires the `UPDATE` perm on top of `INSERT`.
+pub fn insert_with_behavior<T, S>(
+    record: T::Insert,
+    behavior: InsertConflictBehavior,
     transaction_id: Option<TransactionId>,
     database_schema: S,
-) -> IcDbmsResult<u64>
+) -> IcDbmsResult<()>
 where
     T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
     T::Update: UpdateRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
 {
-    check_table_perm(T::fingerprint(), TablePerms::UPDATE)?;
+    let required = match behavior {
+        InsertConflictBehavior::Replace => TablePerms::INSERT | TablePerms::UPDATE,
+        InsertConflictBehavior::Error | InsertConflictBehavior::Ignore => TablePerms::INSERT,
+    };
+    check_table_perm(T::fingerprint(), required)?;
     assert_caller_owns_transaction(transaction_id.as_ref());
-    with_database(transaction_id, database_schema, |db| db.update::<T>(patch))
+    with_database(transaction_id, database_schema, |db| {
+        hooks::on_insert_with_behavior::<T>(db, &record, behavior)?;
+        db.insert_with_behavior::<T>(record, behavior)
+    })
 }
 
-/// Executes a delete query against the database schema, optionally within a transaction.
-pub fn delete<T, S>(
-    behaviour: DeleteBehavior,
-    filter: Option<Filter>,
+/// Inserts a record, or overwrites the row sharing its primary key, optionally
+/// within a transaction.
+///
+/// Like [`InsertConflictBehavior::Replace`], it requires both the `INSERT`
+/// and `UPDATE` perms.
+pub fn upsert<T, S>(
+    record: T::Insert,
     transaction_id: Option<TransactionId>,
     database_schema: S,
-) -> IcDbmsResult<u64>
+) -> IcDbmsResult<UpsertResult>
 where
     T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
+    T::Update: UpdateRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
 {
-    check_table_perm(T::fingerprint(), TablePerms::DELETE)?;
+    check_table_perm(T::fingerprint(), TablePerms::INSERT | TablePerms::UPDATE)?;
     assert_caller_owns_transaction(transaction_id.as_ref());
     with_database(transaction_id, database_schema, |db| {
-        db.delete::<T>(behaviour, filter)
+        hooks::on_insert_with_behavior::<T>(db, &record, InsertConflictBehavior::Replace)?;
+        db.upsert::<T>(record)
     })
 }
 
-// --- Migration -------------------------------------------------------------
-
-/// Returns `true` if the persisted schema differs from the compiled one.
-/// Caller must hold the `migrate` flag.
-pub fn has_drift<S>(database_schema: S) -> IcDbmsResult<bool>
+/// Inserts a record, optionally within a transaction, and returns the row as
+/// stored, after sanitizers and generated defaults.
+///
+/// Since the row is sent back to the caller, it requires both the `INSERT`
+/// and `READ` perms, plus `UPDATE` like [`insert`] on an
+/// `#[on_conflict = "update"]` table.
+pub fn insert_returning<T, S>(
+    record: T::Insert,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<T::Record>
 where
+    T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
 {
-    check_migrate()?;
-    with_database(None, database_schema, |db| db.has_drift())
+    check_table_perm(
+        T::fingerprint(),
+        insert_perms::<T>(&record) | TablePerms::READ,
+    )?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        hooks::on_insert_or_conflict_update::<T>(db, &record)?;
+        db.insert_returning::<T>(record)
+    })
 }
 
-/// Returns the migration ops needed to bring the persisted schema in line
-/// with the compiled one, without applying anything.
-pub fn pending_migrations<S>(database_schema: S) -> IcDbmsResult<Vec<MigrationOp>>
+/// Executes a batch insert against the database schema, optionally within a
+/// transaction. Either every record is inserted or none is.
+pub fn insert_many<T, S>(
+    records: Vec<T::Insert>,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
 where
+    T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
 {
-    check_migrate()?;
-    with_database(None, database_schema, |db| db.pending_migrations())
+    check_table_perm(T::fingerprint(), TablePerms::INSERT)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    for record in &records {
+        hooks::on_insert::<T>(&record.clone().into_values())?;
+    }
+    with_database(transaction_id, database_schema, |db| {
+        db.insert_many::<T>(records)
+    })
 }
 
-/// Applies a planned migration under `policy`. Transactional: on failure the
-/// stored schema and data are unchanged.
-pub fn migrate<S>(policy: MigrationPolicy, database_schema: S) -> IcDbmsResult<()>
+/// Executes an update query against the database schema, optionally within a transaction.
+///
+/// A retried call carrying the same `request_id` is not applied twice and
+/// returns the count of the first call, see [`REQUEST_ID_TTL`].
+pub fn update<T, S>(
+    patch: T::Update,
+    transaction_id: Option<TransactionId>,
+    request_id: Option<RequestId>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
 where
+    T: TableSchema,
+    T::Update: UpdateRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
 {
-    check_migrate()?;
-    DBMS_CONTEXT.with(|ctx| {
-        let mut db = WasmDbmsDatabase::oneshot(ctx, database_schema);
-        db.migrate(policy)
-    })
-}
-
-// --- Helpers ---------------------------------------------------------------
-
-fn check_table_perm(table: TableFingerprint, required: TablePerms) -> IcDbmsResult<()> {
-    let caller = crate::utils::caller();
-    DBMS_CONTEXT.with(|ctx| {
-        if ctx.granted(&caller, table, required) {
-            Ok(())
-        } else {
-            Err(DbmsError::AccessDenied {
-                table: Some(table),
-                required: RequiredPerm::Table(required),
+    check_table_perm(T::fingerprint(), TablePerms::UPDATE)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    idempotency::run_once(
+        request_id,
+        transaction_id,
+        T::fingerprint(),
+        WriteOp::Update,
+        || {
+            with_database(transaction_id, database_schema, |db| {
+                hooks::on_update::<T>(db, &patch)?;
+                db.update::<T>(patch)
             })
-        }
-    })
+        },
+    )
 }
 
-fn check_table_read_by_name(table: &str) -> IcDbmsResult<()> {
-    let fingerprint = resolve_table_fingerprint(table)?;
-    check_table_perm(fingerprint, TablePerms::READ)
-}
+/// Updates the next chunk of the rows matching the patch, in primary key
+/// order, optionally within a transaction.
+///
+/// The chunk holds at most `max_rows` rows, capped by the threshold set with
+/// [`set_max_chunk_rows`]; when fewer rows match, they are all updated at
+/// once. Returns the number of updated rows and the token to pass to the
+/// next call, or `None` once every row has been processed.
+///
+/// Since the token carries the primary key of the last updated row, it
+/// requires both the `UPDATE` and `READ` perms.
+pub fn update_chunked<T, S>(
+    patch: T::Update,
+    max_rows: Option<u64>,
+    token: Option<ContinuationToken>,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<(u64, Option<ContinuationToken>)>
+where
+    T: TableSchema,
+    T::Update: UpdateRecord<Schema = T>,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::UPDATE | TablePerms::READ)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        let Some((filter, next)) =
+            db.next_chunk::<T>(patch.where_clause(), max_rows, token.as_ref())?
+        else {
+            return Ok((0, None));
+        };
+        // the hooks see the patch restricted to the chunk
+        let patch = T::Update::from_values(&patch.update_values(), filter);
+        hooks::on_update::<T>(db, &patch)?;
 
-fn check_join_read_perms(root_table: &str, query: &Query) -> IcDbmsResult<()> {
-    let mut checked = HashSet::new();
-    for table in
-        std::iter::once(root_table).chain(query.joins.iter().map(|join| join.table.as_str()))
-    {
-        let fingerprint = resolve_table_fingerprint(table)?;
-        if checked.insert(fingerprint) {
-            check_table_perm(fingerprint, TablePerms::READ)?;
-        }
-    }
-    Ok(())
+        Ok((db.update::<T>(patch)?, next))
+    })
 }
 
-fn resolve_table_fingerprint(table: &str) -> IcDbmsResult<TableFingerprint> {
-    DBMS_CONTEXT.with(|ctx| {
-        if ctx.has_table(table) {
-            Ok(fingerprint_for_name(table))
-        } else {
-            Err(DbmsError::Query(QueryError::TableNotFound(
-                table.to_string(),
-            )))
+/// Executes a batch of update queries in order, optionally within a
+/// transaction, and returns the number of rows updated by each patch.
+///
+/// Either every patch is applied or none is. With `ignore_errors`, a failing
+/// patch is skipped instead, counting `0` rows, and the others are applied.
+pub fn bulk_update<T, S>(
+    patches: Vec<T::Update>,
+    ignore_errors: bool,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<Vec<u64>>
+where
+    T: TableSchema,
+    T::Update: UpdateRecord<Schema = T>,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::UPDATE)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        let update = |patch: T::Update| {
+            hooks::on_update::<T>(db, &patch)?;
+            db.update::<T>(patch)
+        };
+        if ignore_errors {
+            return Ok(patches
+                .into_iter()
+                .map(|patch| update(patch).unwrap_or_default())
+                .collect());
         }
-    })
-}
 
-fn check_migrate() -> IcDbmsResult<()> {
-    let caller = crate::utils::caller();
-    DBMS_CONTEXT.with(|ctx| {
-        if ctx.granted_migrate(&caller) {
-            Ok(())
-        } else {
-            Err(DbmsError::AccessDenied {
-                table: None,
-                required: RequiredPerm::Migrate,
-            })
+        // inside a transaction, the patches are staged: a failing one drops
+        // the patches staged before it
+        let savepoint = match transaction_id {
+            Some(tx_id) => {
+                Some(DBMS_CONTEXT.with(|ctx| ctx.create_savepoint(&tx_id, "bulk_update"))?)
+            }
+            None => None,
+        };
+        let result = db.atomic(|_| patches.into_iter().map(update).collect());
+        if result.is_err()
+            && let (Some(tx_id), Some(savepoint)) = (transaction_id, savepoint)
+        {
+            DBMS_CONTEXT.with(|ctx| ctx.rollback_to_savepoint(&tx_id, savepoint))?;
         }
+        result
     })
 }
 
-/// Closure-based helper that creates a [`WasmDbmsDatabase`] inside the
-/// `DBMS_CONTEXT` thread-local and invokes `f` on it.
-fn with_database<S, F, R>(transaction_id: Option<TransactionId>, database_schema: S, f: F) -> R
+/// Executes an update query, optionally within a transaction, and returns the
+/// updated rows as stored.
+///
+/// Since the rows are sent back to the caller, it requires both the `UPDATE`
+/// and `READ` perms.
+pub fn update_returning<T, S>(
+    patch: T::Update,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<Vec<T::Record>>
 where
+    T: TableSchema,
+    T::Update: UpdateRecord<Schema = T>,
     S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
-    F: for<'a> FnOnce(&WasmDbmsDatabase<'a, IcMemoryProvider, IcAccessControlList>) -> R,
 {
-    DBMS_CONTEXT.with(|ctx| {
-        let db = match transaction_id {
-            Some(tx_id) => WasmDbmsDatabase::from_transaction(ctx, database_schema, tx_id),
-            None => WasmDbmsDatabase::oneshot(ctx, database_schema),
+    check_table_perm(T::fingerprint(), TablePerms::UPDATE | TablePerms::READ)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        hooks::on_update::<T>(db, &patch)?;
+        db.update_returning::<T>(patch)
+    })
+}
+
+/// Checks whether a record could be inserted, optionally within a
+/// transaction, without writing it.
+///
+/// It requires the `INSERT` perm, like the insert it validates.
+pub fn validate_insert<T, S>(
+    record: T::Insert,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::INSERT)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        db.validate_insert_only::<T>(record)
+    })
+}
+
+/// Checks whether an update could be applied, optionally within a
+/// transaction, without writing it.
+///
+/// It requires the `UPDATE` perm, like the update it validates.
+pub fn validate_update<T, S>(
+    patch: T::Update,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+    T::Update: UpdateRecord<Schema = T>,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::UPDATE)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        db.validate_update_only::<T>(patch)
+    })
+}
+
+/// Executes a delete query against the database schema, optionally within a transaction.
+///
+/// Only `TablePerms::DELETE` on `T` is checked: with
+/// [`DeleteBehavior::Cascade`], the rows of the referencing tables are
+/// removed even if the caller holds no perm on them.
+///
+/// A retried call carrying the same `request_id` is not applied twice and
+/// returns the count of the first call, see [`REQUEST_ID_TTL`].
+pub fn delete<T, S>(
+    behaviour: DeleteBehavior,
+    filter: Option<Filter>,
+    transaction_id: Option<TransactionId>,
+    request_id: Option<RequestId>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::DELETE)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    idempotency::run_once(
+        request_id,
+        transaction_id,
+        T::fingerprint(),
+        WriteOp::Delete,
+        || {
+            with_database(transaction_id, database_schema, |db| {
+                hooks::on_delete::<T>(db, filter.clone(), false)?;
+                db.delete::<T>(behaviour, filter)
+            })
+        },
+    )
+}
+
+/// Deletes the next chunk of the rows matching `filter`, in primary key
+/// order, optionally within a transaction.
+///
+/// The chunk holds at most `max_rows` rows, capped by the threshold set with
+/// [`set_max_chunk_rows`]; when fewer rows match, they are all deleted at
+/// once. Returns the number of deleted rows and the token to pass to the
+/// next call, or `None` once every row has been processed.
+///
+/// Since the token carries the primary key of the last deleted row, it
+/// requires both the `DELETE` and `READ` perms.
+pub fn delete_chunked<T, S>(
+    behaviour: DeleteBehavior,
+    filter: Option<Filter>,
+    max_rows: Option<u64>,
+    token: Option<ContinuationToken>,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<(u64, Option<ContinuationToken>)>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::DELETE | TablePerms::READ)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        let Some((filter, next)) = db.next_chunk::<T>(filter, max_rows, token.as_ref())? else {
+            return Ok((0, None));
         };
-        f(&db)
+        hooks::on_delete::<T>(db, filter.clone(), false)?;
+
+        Ok((db.delete::<T>(behaviour, filter)?, next))
     })
 }
 
-/// Asserts that the caller owns the given transaction ID. Traps on
-/// mismatch.
-fn assert_caller_owns_transaction(transaction_id: Option<&TransactionId>) {
-    let Some(tx_id) = transaction_id else {
-        return;
-    };
+/// Executes a delete query which physically removes the matching rows, even
+/// from a `#[soft_delete]` table, optionally within a transaction.
+pub fn force_delete<T, S>(
+    behaviour: DeleteBehavior,
+    filter: Option<Filter>,
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::DELETE)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        hooks::on_delete::<T>(db, filter.clone(), true)?;
+        db.force_delete::<T>(behaviour, filter)
+    })
+}
+
+/// Deletes every row of the table at once, optionally within a transaction.
+pub fn truncate<T, S>(
+    transaction_id: Option<TransactionId>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::DELETE)?;
+    assert_caller_owns_transaction(transaction_id.as_ref());
+    with_database(transaction_id, database_schema, |db| {
+        hooks::on_delete::<T>(db, None, true)?;
+        db.truncate::<T>()
+    })
+}
+
+/// Rewrites the records of the table named `table` contiguously, returning
+/// the number of record pages released. Caller must be a controller of the
+/// canister.
+pub fn defragment_table<S>(table: String, database_schema: S) -> IcDbmsResult<u64>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
     let caller = crate::utils::caller();
-    if !DBMS_CONTEXT.with(|ctx| ctx.has_transaction(tx_id, caller.as_slice())) {
-        trap!("Caller {caller} does not own transaction {tx_id}");
+    if !crate::utils::is_controller(&caller) {
+        return Err(DbmsError::AccessDenied {
+            table: None,
+            required: RequiredPerm::Controller,
+        });
+    }
+    with_database(None, database_schema, |db| db.defragment_table(&table))
+}
+
+/// Moves the records off the last record pages of the table named `table`,
+/// releasing at most `max_pages` pages. Each call is bounded by `max_pages`
+/// and leaves the table consistent: call again until the report is
+/// `complete`. Caller must be a controller of the canister.
+pub fn compact_table<S>(
+    table: String,
+    max_pages: u64,
+    database_schema: S,
+) -> IcDbmsResult<CompactionReport>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    let caller = crate::utils::caller();
+    if !crate::utils::is_controller(&caller) {
+        return Err(DbmsError::AccessDenied {
+            table: None,
+            required: RequiredPerm::Controller,
+        });
+    }
+    with_database(None, database_schema, |db| {
+        db.compact_table(&table, max_pages)
+    })
+}
+
+/// Reads a chunk of the blob `column` of the record with primary key `pk`.
+pub fn blob_read_chunk<T, S>(
+    pk: Value,
+    column: String,
+    offset: u64,
+    len: u64,
+    database_schema: S,
+) -> IcDbmsResult<BlobChunk>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::READ)?;
+    with_database(None, database_schema, |db| {
+        db.blob_read_chunk::<T>(pk, &column, offset, len)
+    })
+}
+
+/// Writes a chunk of the blob `column` of the record with primary key `pk`,
+/// returning the new length of the blob.
+pub fn blob_write_chunk<T, S>(
+    pk: Value,
+    column: String,
+    offset: u64,
+    bytes: Vec<u8>,
+    database_schema: S,
+) -> IcDbmsResult<u64>
+where
+    T: TableSchema,
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_table_perm(T::fingerprint(), TablePerms::UPDATE)?;
+    with_database(None, database_schema, |db| {
+        db.blob_write_chunk::<T>(pk, &column, offset, &bytes)
+    })
+}
+
+// --- Migration -------------------------------------------------------------
+
+/// Returns `true` if the persisted schema differs from the compiled one.
+/// Caller must hold the `migrate` flag.
+pub fn has_drift<S>(database_schema: S) -> IcDbmsResult<bool>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_migrate()?;
+    with_database(None, database_schema, |db| db.has_drift())
+}
+
+/// Returns the migration ops needed to bring the persisted schema in line
+/// with the compiled one, without applying anything.
+pub fn pending_migrations<S>(database_schema: S) -> IcDbmsResult<Vec<MigrationOp>>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_migrate()?;
+    with_database(None, database_schema, |db| db.pending_migrations())
+}
+
+/// Applies a planned migration under `policy`. Transactional: on failure the
+/// stored schema and data are unchanged.
+pub fn migrate<S>(policy: MigrationPolicy, database_schema: S) -> IcDbmsResult<()>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    check_migrate()?;
+    DBMS_CONTEXT.with(|ctx| {
+        let mut db = WasmDbmsDatabase::oneshot(ctx, database_schema);
+        db.migrate(policy)
+    })
+}
+
+/// Applies the schema changes of a canister upgrade. Called from the canister
+/// `post_upgrade`, so it runs without any caller check.
+///
+/// Only [additive](MigrationOp::is_additive) changes are applied: new tables,
+/// new columns and new indexes. Rows stored before the upgrade get `Null` (or
+/// the column default) in the new columns. When the stored schema differs in
+/// any other way, nothing is applied and
+/// [`MigrationError::ManualMigrationRequired`] is returned.
+pub fn migrate_on_upgrade<S>(database_schema: S) -> IcDbmsResult<()>
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+{
+    DBMS_CONTEXT.with(|ctx| {
+        let mut db = WasmDbmsDatabase::oneshot(ctx, database_schema);
+        let ops = db.pending_migrations()?;
+        if ops.is_empty() {
+            return Ok(());
+        }
+        if let Some(op) = ops.iter().find(|op| !op.is_additive()) {
+            return Err(DbmsError::Migration(
+                MigrationError::ManualMigrationRequired {
+                    op: format!("{op:?}"),
+                },
+            ));
+        }
+        db.migrate(MigrationPolicy::default())
+    })
+}
+
+// --- Statistics ------------------------------------------------------------
+
+/// Returns the storage statistics of the tables the caller can read, sorted
+/// by table name.
+pub fn table_stats() -> IcDbmsResult<Vec<TableStats>> {
+    let caller = crate::utils::caller();
+    DBMS_CONTEXT.with(|ctx| {
+        let stats = ctx.table_stats()?;
+        Ok(stats
+            .into_iter()
+            .filter(|stats| {
+                ctx.granted(
+                    &caller,
+                    fingerprint_for_name(&stats.table),
+                    TablePerms::READ,
+                )
+            })
+            .collect())
+    })
+}
+
+/// Returns the storage statistics of `table`.
+///
+/// Requires `READ` on the table.
+pub fn table_stats_of(table: String) -> IcDbmsResult<TableStats> {
+    let fingerprint = resolve_table_fingerprint(&table)?;
+    check_table_perm(fingerprint, TablePerms::READ)?;
+    DBMS_CONTEXT.with(|ctx| ctx.table_stats_of(fingerprint))
+}
+
+// --- Helpers ---------------------------------------------------------------
+
+fn check_table_perm(table: TableFingerprint, required: TablePerms) -> IcDbmsResult<()> {
+    let caller = crate::utils::caller();
+    DBMS_CONTEXT.with(|ctx| {
+        if ctx.granted(&caller, table, required) {
+            Ok(())
+        } else {
+            Err(DbmsError::AccessDenied {
+                table: Some(table),
+                required: RequiredPerm::Table(required),
+            })
+        }
+    })
+}
+
+fn check_table_read_by_name(table: &str) -> IcDbmsResult<()> {
+    let fingerprint = resolve_table_fingerprint(table)?;
+    check_table_perm(fingerprint, TablePerms::READ)
+}
+
+fn check_join_read_perms(root_table: &str, query: &Query) -> IcDbmsResult<()> {
+    let mut checked = HashSet::new();
+    for table in
+        std::iter::once(root_table).chain(query.joins.iter().map(|join| join.table.as_str()))
+    {
+        let fingerprint = resolve_table_fingerprint(table)?;
+        if checked.insert(fingerprint) {
+            check_table_perm(fingerprint, TablePerms::READ)?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_table_fingerprint(table: &str) -> IcDbmsResult<TableFingerprint> {
+    DBMS_CONTEXT.with(|ctx| {
+        if ctx.has_table(table) {
+            Ok(fingerprint_for_name(table))
+        } else {
+            Err(DbmsError::Query(QueryError::TableNotFound(
+                table.to_string(),
+            )))
+        }
+    })
+}
+
+fn check_migrate() -> IcDbmsResult<()> {
+    let caller = crate::utils::caller();
+    DBMS_CONTEXT.with(|ctx| {
+        if ctx.granted_migrate(&caller) {
+            Ok(())
+        } else {
+            Err(DbmsError::AccessDenied {
+                table: None,
+                required: RequiredPerm::Migrate,
+            })
+        }
+    })
+}
+
+/// Closure-based helper that creates a [`WasmDbmsDatabase`] inside the
+/// `DBMS_CONTEXT` thread-local and invokes `f` on it.
+fn with_database<S, F, R>(transaction_id: Option<TransactionId>, database_schema: S, f: F) -> R
+where
+    S: DatabaseSchema<IcMemoryProvider, IcAccessControlList> + 'static,
+    F: for<'a> FnOnce(&WasmDbmsDatabase<'a, IcMemoryProvider, IcAccessControlList>) -> R,
+{
+    DBMS_CONTEXT.with(|ctx| {
+        let db = match transaction_id {
+            Some(tx_id) => WasmDbmsDatabase::from_transaction(ctx, database_schema, tx_id),
+            None => WasmDbmsDatabase::oneshot(ctx, database_schema),
+        };
+        f(&db)
+    })
+}
+
+/// Returns the perms required to insert `record` into `T`.
+///
+/// An `#[on_conflict = "update"]` table turns an insert colliding on the
+/// primary key into an update of that row, so it also requires `UPDATE`.
+fn insert_perms<T>(record: &T::Insert) -> TablePerms
+where
+    T: TableSchema,
+{
+    if T::on_conflict_update(record).is_some() {
+        TablePerms::INSERT | TablePerms::UPDATE
+    } else {
+        TablePerms::INSERT
+    }
+}
+
+/// Asserts that the caller owns the given transaction ID. Traps on
+/// mismatch.
+///
+/// An expired transaction of the caller passes the check, so the operation
+/// fails with [`TransactionError::TransactionExpired`] instead of trapping.
+///
+/// [`TransactionError::TransactionExpired`]: ic_dbms_api::prelude::TransactionError::TransactionExpired
+fn assert_caller_owns_transaction(transaction_id: Option<&TransactionId>) {
+    let Some(tx_id) = transaction_id else {
+        return;
+    };
+    let caller = crate::utils::caller();
+    let owns = DBMS_CONTEXT.with(|ctx| {
+        ctx.has_transaction(tx_id, caller.as_slice())
+            || ctx.has_expired_transaction(tx_id, caller.as_slice())
+    });
+    if !owns {
+        trap!("Caller {caller} does not own transaction {tx_id}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ic_dbms_api::prelude::Uint32;
+
+    use super::*;
+    use crate::tests::{UserInsertRequest, load_fixtures};
+
+    fn alice() -> Principal {
+        crate::utils::caller()
+    }
+
+    fn init_acl() {
+        DBMS_CONTEXT.with(|ctx| {
+            crate::tests::TestDatabaseSchema::register_tables(ctx)
+                .expect("failed to register tables");
+            ctx.acl_grant(alice(), PermGrant::Admin).unwrap();
+            ctx.acl_grant(alice(), PermGrant::ManageAcl).unwrap();
+            ctx.acl_grant(alice(), PermGrant::Migrate).unwrap();
+            ctx.acl_grant(alice(), PermGrant::AllTables(TablePerms::all()))
+                .unwrap();
+        });
+    }
+
+    #[test]
+    fn test_should_grant_admin() {
+        init_acl();
+        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        grant_admin(bob).expect("failed to grant admin");
+        DBMS_CONTEXT.with(|ctx| assert!(ctx.granted_admin(&bob)));
+    }
+
+    #[test]
+    fn test_should_revoke_admin() {
+        init_acl();
+        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        grant_admin(bob).unwrap();
+        revoke_admin(bob).unwrap();
+        DBMS_CONTEXT.with(|ctx| assert!(!ctx.granted_admin(&bob)));
+    }
+
+    #[test]
+    fn test_should_list_identities() {
+        init_acl();
+        let identities = list_identities().expect("failed to list identities");
+        assert!(identities.iter().any(|(p, _)| *p == alice()));
+    }
+
+    #[test]
+    fn test_should_list_all_principals() {
+        init_acl();
+        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        grant_all_tables_perms(bob, TablePerms::READ).unwrap();
+        let principals = acl_list_all_principals().expect("failed to list principals");
+        assert!(principals.contains(&alice()));
+        assert!(principals.contains(&bob));
+    }
+
+    #[test]
+    fn test_should_my_perms_returns_callers() {
+        init_acl();
+        let perms = my_perms();
+        assert!(perms.admin && perms.manage_acl && perms.migrate);
+    }
+
+    #[test]
+    fn test_should_reject_unknown_table_acl_grant() {
+        init_acl();
+        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+        let err = grant_table_perms(bob, "missing".to_string(), TablePerms::READ)
+            .expect_err("unknown table must be rejected");
+        assert!(matches!(
+            err,
+            DbmsError::Query(QueryError::TableNotFound(table)) if table == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_should_require_update_perm_to_insert_into_on_conflict_update_table() {
+        init_acl();
+        let record = crate::tests::SettingInsertRequest {
+            id: 1u32.into(),
+            value: "dark".into(),
+        };
+        let res = DBMS_CONTEXT.with(|ctx| {
+            ctx.acl_revoke(&alice(), PermRevoke::Admin)
+                .expect("should revoke bootstrap admin bypass");
+            ctx.acl_revoke(&alice(), PermRevoke::AllTables(TablePerms::all()))
+                .expect("should revoke bootstrap table perms");
+            ctx.acl_grant(
+                alice(),
+                PermGrant::Table(crate::tests::Setting::fingerprint(), TablePerms::INSERT),
+            )
+            .expect("should grant insert");
+            insert::<crate::tests::Setting, _>(record, None, None, crate::tests::TestDatabaseSchema)
+        });
+        assert!(matches!(
+            res,
+            Err(DbmsError::AccessDenied {
+                required: RequiredPerm::Table(perms),
+                table: Some(table),
+            }) if perms == TablePerms::INSERT | TablePerms::UPDATE
+                && table == crate::tests::Setting::fingerprint()
+        ));
+    }
+
+    #[test]
+    fn test_should_deny_join_without_joined_table_read_perm() {
+        init_acl();
+        let query = Query::builder()
+            .all()
+            .inner_join("posts", "users.id", "posts.user")
+            .build();
+        let res = DBMS_CONTEXT.with(|ctx| {
+            ctx.acl_revoke(&alice(), PermRevoke::Admin)
+                .expect("should revoke bootstrap admin bypass");
+            ctx.acl_revoke(&alice(), PermRevoke::AllTables(TablePerms::all()))
+                .expect("should revoke bootstrap table perms");
+            ctx.acl_grant(
+                alice(),
+                PermGrant::Table(crate::tests::User::fingerprint(), TablePerms::READ),
+            )
+            .expect("should grant root-table read");
+            select_join("users", query, None, crate::tests::TestDatabaseSchema)
+        });
+        assert!(matches!(
+            res,
+            Err(DbmsError::AccessDenied {
+                required: RequiredPerm::Table(perms),
+                table: Some(table),
+            }) if perms == TablePerms::READ && table == crate::tests::Post::fingerprint()
+        ));
+    }
+
+    #[test]
+    fn test_should_return_table_stats_of_readable_tables() {
+        load_fixtures();
+        init_acl();
+        let stats = table_stats().expect("failed to get table stats");
+        let users = stats
+            .iter()
+            .find(|stats| stats.table == "users")
+            .expect("missing users stats");
+        assert_eq!(users.rows, crate::tests::USERS_FIXTURES.len() as u64);
+        assert!(stats.iter().any(|stats| stats.table == "posts"));
+
+        DBMS_CONTEXT.with(|ctx| {
+            ctx.acl_revoke(&alice(), PermRevoke::Admin)
+                .expect("should revoke bootstrap admin bypass");
+            ctx.acl_revoke(&alice(), PermRevoke::AllTables(TablePerms::all()))
+                .expect("should revoke bootstrap table perms");
+            ctx.acl_grant(
+                alice(),
+                PermGrant::Table(crate::tests::User::fingerprint(), TablePerms::READ),
+            )
+            .expect("should grant users read");
+        });
+        let stats = table_stats().expect("failed to get table stats");
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].table, "users");
+    }
+
+    #[test]
+    fn test_should_return_table_stats_of_single_table() {
+        load_fixtures();
+        init_acl();
+        let users = table_stats_of("users".to_string()).expect("failed to get users stats");
+        assert_eq!(users.table, "users");
+        assert_eq!(users.rows, crate::tests::USERS_FIXTURES.len() as u64);
+        assert!(matches!(
+            table_stats_of("missing".to_string()),
+            Err(DbmsError::Query(QueryError::TableNotFound(table))) if table == "missing"
+        ));
+
+        DBMS_CONTEXT.with(|ctx| {
+            ctx.acl_revoke(&alice(), PermRevoke::Admin)
+                .expect("should revoke bootstrap admin bypass");
+            ctx.acl_revoke(&alice(), PermRevoke::AllTables(TablePerms::all()))
+                .expect("should revoke bootstrap table perms");
+        });
+        assert!(matches!(
+            table_stats_of("users".to_string()),
+            Err(DbmsError::AccessDenied { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_begin_transaction() {
+        init_acl();
+        let _tx_id = begin_transaction();
+    }
+
+    #[test]
+    fn test_should_list_active_transactions() {
+        init_acl();
+        let tx_id = begin_transaction();
+        let closed = begin_transaction();
+        assert!(rollback(closed, crate::tests::TestDatabaseSchema).is_ok());
+
+        let transactions = list_active_transactions().expect("failed to list transactions");
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].id, tx_id);
+        assert_eq!(transactions[0].principal, crate::utils::caller());
+        assert!(transactions[0].started_at <= crate::utils::time());
+        assert_eq!(transactions[0].operation_count, 0);
+    }
+
+    #[test]
+    fn test_should_expire_transaction_past_ttl() {
+        init_acl();
+        init_transaction_expiry(Some(0), None);
+        let tx_id = begin_transaction();
+        init_transaction_expiry(None, None);
+        let untouched = begin_transaction();
+
+        assert_eq!(expire_transactions(), vec![tx_id]);
+        let res = commit(tx_id, crate::tests::TestDatabaseSchema);
+        assert!(matches!(
+            res,
+            Err(DbmsError::Transaction(
+                ic_dbms_api::prelude::TransactionError::TransactionExpired(id)
+            )) if id == tx_id
+        ));
+        assert!(commit(untouched, crate::tests::TestDatabaseSchema).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many open transactions (limit: 2)")]
+    fn test_should_limit_open_transactions() {
+        init_acl();
+        set_max_open_transactions(Some(2));
+        let first = begin_transaction();
+        let _second = begin_transaction();
+        // committing frees a slot
+        assert!(commit(first, crate::tests::TestDatabaseSchema).is_ok());
+        let _third = begin_transaction();
+
+        let _ = begin_transaction();
+    }
+
+    #[test]
+    fn test_should_commit_transaction() {
+        init_acl();
+        let tx_id = begin_transaction();
+        let res = commit(tx_id, crate::tests::TestDatabaseSchema);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_should_rollback_transaction() {
+        init_acl();
+        let tx_id = begin_transaction();
+        let res = rollback(tx_id, crate::tests::TestDatabaseSchema);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_should_insert_record() {
+        load_fixtures();
+        init_acl();
+        let record = UserInsertRequest {
+            id: 100u32.into(),
+            name: "Alice".to_string().into(),
+            email: "alice@example.com".into(),
+            age: 25u32.into(),
+        };
+
+        let res =
+            insert::<crate::tests::User, _>(record, None, None, crate::tests::TestDatabaseSchema);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_should_not_apply_retried_insert_twice() {
+        load_fixtures();
+        init_acl();
+        let record = || UserInsertRequest {
+            id: 101u32.into(),
+            name: "Alice".to_string().into(),
+            email: "alice@example.com".into(),
+            age: 25u32.into(),
+        };
+
+        for _ in 0..2 {
+            insert::<crate::tests::User, _>(
+                record(),
+                None,
+                Some("insert-101".to_string()),
+                crate::tests::TestDatabaseSchema,
+            )
+            .expect("a retried insert should return the first response");
+        }
+        let count = count::<crate::tests::User, _>(
+            Some(Filter::Eq("id".to_string(), Uint32::from(101u32).into())),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+
+        // without a request ID the duplicate is applied and fails
+        let res =
+            insert::<crate::tests::User, _>(record(), None, None, crate::tests::TestDatabaseSchema);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_should_apply_retried_insert_after_rollback() {
+        load_fixtures();
+        init_acl();
+        let record = || UserInsertRequest {
+            id: 102u32.into(),
+            name: "Alice".to_string().into(),
+            email: "alice@example.com".into(),
+            age: 25u32.into(),
+        };
+        let request_id = || Some("insert-102".to_string());
+
+        let tx_id = begin_transaction();
+        insert::<crate::tests::User, _>(
+            record(),
+            Some(tx_id),
+            request_id(),
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        rollback(tx_id, crate::tests::TestDatabaseSchema).unwrap();
+
+        // the rolled back insert was never applied, so the retry applies it
+        insert::<crate::tests::User, _>(
+            record(),
+            None,
+            request_id(),
+            crate::tests::TestDatabaseSchema,
+        )
+        .expect("a retried insert should be applied after a rollback");
+        let count = count::<crate::tests::User, _>(
+            Some(Filter::Eq("id".to_string(), Uint32::from(102u32).into())),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_should_not_apply_retried_insert_after_commit() {
+        load_fixtures();
+        init_acl();
+        let record = || UserInsertRequest {
+            id: 103u32.into(),
+            name: "Alice".to_string().into(),
+            email: "alice@example.com".into(),
+            age: 25u32.into(),
+        };
+        let request_id = || Some("insert-103".to_string());
+
+        let tx_id = begin_transaction();
+        insert::<crate::tests::User, _>(
+            record(),
+            Some(tx_id),
+            request_id(),
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        commit(tx_id, crate::tests::TestDatabaseSchema).unwrap();
+
+        insert::<crate::tests::User, _>(
+            record(),
+            None,
+            request_id(),
+            crate::tests::TestDatabaseSchema,
+        )
+        .expect("a retried insert should return the committed response");
+    }
+
+    #[test]
+    fn test_should_insert_with_behavior() {
+        load_fixtures();
+        init_acl();
+        let record = |name: &str| UserInsertRequest {
+            id: 300u32.into(),
+            name: name.to_string().into(),
+            email: "bob@example.com".into(),
+            age: 40u32.into(),
+        };
+
+        insert::<crate::tests::User, _>(
+            record("Bob"),
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let res = insert_with_behavior::<crate::tests::User, _>(
+            record("Robert"),
+            InsertConflictBehavior::Error,
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(res.is_err());
+        insert_with_behavior::<crate::tests::User, _>(
+            record("Robert"),
+            InsertConflictBehavior::Ignore,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        insert_with_behavior::<crate::tests::User, _>(
+            record("Robert"),
+            InsertConflictBehavior::Replace,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+
+        let user = select_one::<crate::tests::User, _>(
+            Query::builder()
+                .and_where(Filter::Eq("id".to_string(), Uint32::from(300u32).into()))
+                .build(),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap()
+        .expect("user should exist");
+        assert_eq!(user.name, Some("Robert".into()));
+    }
+
+    #[test]
+    fn test_should_insert_returning_record() {
+        load_fixtures();
+        init_acl();
+        let record = UserInsertRequest {
+            id: 302u32.into(),
+            name: "Dave".to_string().into(),
+            email: "dave@example.com".into(),
+            age: 41u32.into(),
+        };
+
+        let user = insert_returning::<crate::tests::User, _>(
+            record,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(user.id, Some(302u32.into()));
+        assert_eq!(user.name, Some("Dave".into()));
+        assert_eq!(user.age, Some(41u32.into()));
+    }
+
+    #[test]
+    fn test_should_upsert_record() {
+        load_fixtures();
+        init_acl();
+        let record = |name: &str| UserInsertRequest {
+            id: 301u32.into(),
+            name: name.to_string().into(),
+            email: "carol@example.com".into(),
+            age: 35u32.into(),
+        };
+
+        let res = upsert::<crate::tests::User, _>(
+            record("Carol"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(res, UpsertResult::Inserted);
+        let res = upsert::<crate::tests::User, _>(
+            record("Caroline"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(res, UpsertResult::Updated);
+
+        let user = select_one::<crate::tests::User, _>(
+            Query::builder()
+                .and_where(Filter::Eq("id".to_string(), Uint32::from(301u32).into()))
+                .build(),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap()
+        .expect("user should exist");
+        assert_eq!(user.name, Some("Caroline".into()));
+    }
+
+    #[test]
+    fn test_should_insert_many_records() {
+        load_fixtures();
+        init_acl();
+        let records = (200u32..203)
+            .map(|id| UserInsertRequest {
+                id: id.into(),
+                name: format!("User {id}").into(),
+                email: format!("user{id}@example.com").into(),
+                age: 30u32.into(),
+            })
+            .collect();
+
+        let res =
+            insert_many::<crate::tests::User, _>(records, None, crate::tests::TestDatabaseSchema);
+        assert_eq!(res.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_should_select_record() {
+        init_acl();
+        load_fixtures();
+        let query = Query::builder().all().limit(10).build();
+        let res = select::<crate::tests::User, _>(query, None, crate::tests::TestDatabaseSchema);
+        assert!(res.is_ok());
+        let records = res.unwrap();
+        assert!(!records.is_empty());
+    }
+
+    #[test]
+    fn test_should_count_records() {
+        init_acl();
+        load_fixtures();
+        let all = select::<crate::tests::User, _>(
+            Query::builder().all().build(),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let res = count::<crate::tests::User, _>(None, None, crate::tests::TestDatabaseSchema);
+        assert_eq!(res.unwrap(), all.len() as u64);
+
+        let filter = Some(Filter::Eq("id".to_string(), Uint32::from(1u32).into()));
+        let res = count::<crate::tests::User, _>(filter, None, crate::tests::TestDatabaseSchema);
+        assert_eq!(res.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_should_select_one_record() {
+        init_acl();
+        load_fixtures();
+        let query = Query::builder()
+            .all()
+            .and_where(Filter::Eq("id".to_string(), Uint32::from(1u32).into()))
+            .build();
+        let res =
+            select_one::<crate::tests::User, _>(query, None, crate::tests::TestDatabaseSchema);
+        assert!(res.unwrap().is_some());
+
+        let query = Query::builder()
+            .all()
+            .and_where(Filter::Eq("id".to_string(), Uint32::from(u32::MAX).into()))
+            .build();
+        let res =
+            select_one::<crate::tests::User, _>(query, None, crate::tests::TestDatabaseSchema);
+        assert!(res.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_should_check_record_exists() {
+        init_acl();
+        load_fixtures();
+        let filter = Filter::Eq("id".to_string(), Uint32::from(1u32).into());
+        let res = exists::<crate::tests::User, _>(filter, None, crate::tests::TestDatabaseSchema);
+        assert!(res.unwrap());
+
+        let filter = Filter::Eq("id".to_string(), Uint32::from(u32::MAX).into());
+        let res = exists::<crate::tests::User, _>(filter, None, crate::tests::TestDatabaseSchema);
+        assert!(!res.unwrap());
+    }
+
+    #[test]
+    fn test_should_update_record() {
+        init_acl();
+        load_fixtures();
+
+        let patch = crate::tests::UserUpdateRequest {
+            id: None,
+            name: Some("Robert".into()),
+            email: Some("robert@example.com".into()),
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(1u32).into())),
+        };
+        let res =
+            update::<crate::tests::User, _>(patch, None, None, crate::tests::TestDatabaseSchema);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_should_bulk_update_records() {
+        init_acl();
+        load_fixtures();
+
+        let patch = |id: u32, name: &str| crate::tests::UserUpdateRequest {
+            id: None,
+            name: Some(name.into()),
+            email: None,
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(id).into())),
+        };
+        let counts = bulk_update::<crate::tests::User, _>(
+            vec![patch(1, "Robert"), patch(100, "Nobody"), patch(2, "Chuck")],
+            false,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(counts, vec![1, 0, 1]);
+
+        let users = select::<crate::tests::User, _>(
+            Query::builder()
+                .filter(Some(Filter::In(
+                    "id".to_string(),
+                    vec![Uint32::from(1u32).into(), Uint32::from(2u32).into()],
+                )))
+                .order_by_asc("id")
+                .build(),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let names: Vec<_> = users.into_iter().map(|user| user.name.unwrap()).collect();
+        assert_eq!(names, vec!["Robert".into(), "Chuck".into()]);
+    }
+
+    #[test]
+    fn test_should_not_apply_failing_bulk_update() {
+        init_acl();
+        load_fixtures();
+
+        let patch = |id: u32, email: &str| crate::tests::UserUpdateRequest {
+            id: None,
+            name: None,
+            email: Some(email.into()),
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(id).into())),
+        };
+        let patches = || {
+            vec![
+                patch(1, "robert@example.com"),
+                patch(2, "not an email"),
+                patch(3, "diana@example.org"),
+            ]
+        };
+        let res = bulk_update::<crate::tests::User, _>(
+            patches(),
+            false,
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(res, Err(DbmsError::Validation(_))));
+        let filter = Filter::Eq("email".to_string(), "robert@example.com".into());
+        assert!(
+            !exists::<crate::tests::User, _>(
+                filter.clone(),
+                None,
+                crate::tests::TestDatabaseSchema
+            )
+            .unwrap()
+        );
+
+        // the failing patch is skipped
+        let counts = bulk_update::<crate::tests::User, _>(
+            patches(),
+            true,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(counts, vec![1, 0, 1]);
+        assert!(
+            exists::<crate::tests::User, _>(filter, None, crate::tests::TestDatabaseSchema)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_update_returning_records() {
+        init_acl();
+        load_fixtures();
+
+        let patch = crate::tests::UserUpdateRequest {
+            id: None,
+            name: Some("Robert".into()),
+            email: None,
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(1u32).into())),
+        };
+        let users = update_returning::<crate::tests::User, _>(
+            patch,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0].id, Some(1u32.into()));
+        assert_eq!(users[0].name, Some("Robert".into()));
+    }
+
+    #[test]
+    fn test_should_validate_insert_without_writing() {
+        init_acl();
+        load_fixtures();
+        let record = |id: u32, email: &str| UserInsertRequest {
+            id: id.into(),
+            name: "Alice".to_string().into(),
+            email: email.into(),
+            age: 25u32.into(),
+        };
+
+        validate_insert::<crate::tests::User, _>(
+            record(100, "alice@example.com"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let res = validate_insert::<crate::tests::User, _>(
+            record(100, "not an email"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(res, Err(DbmsError::Validation(_))));
+        let res = validate_insert::<crate::tests::User, _>(
+            record(1, "alice@example.com"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+        ));
+
+        let count =
+            count::<crate::tests::User, _>(None, None, crate::tests::TestDatabaseSchema).unwrap();
+        assert_eq!(count, crate::tests::USERS_FIXTURES.len() as u64);
+    }
+
+    #[test]
+    fn test_should_validate_update_without_writing() {
+        init_acl();
+        load_fixtures();
+        let patch = |email: &str| crate::tests::UserUpdateRequest {
+            id: None,
+            name: None,
+            email: Some(email.into()),
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(1u32).into())),
+        };
+
+        validate_update::<crate::tests::User, _>(
+            patch("robert@example.com"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let res = validate_update::<crate::tests::User, _>(
+            patch("not an email"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(res, Err(DbmsError::Validation(_))));
+
+        let users = select::<crate::tests::User, _>(
+            Query::builder()
+                .filter(Some(Filter::Eq(
+                    "id".to_string(),
+                    Uint32::from(1u32).into(),
+                )))
+                .build(),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_ne!(users[0].email, Some("robert@example.com".into()));
     }
-}
 
-#[cfg(test)]
-mod tests {
+    thread_local! {
+        static HOOK_CALLS: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
 
-    use ic_dbms_api::prelude::Uint32;
+    fn column(values: &[(ColumnDef, Value)], name: &str) -> String {
+        match values.iter().find(|(col, _)| col.name == name) {
+            Some((_, Value::Text(text))) => text.0.clone(),
+            _ => String::new(),
+        }
+    }
 
-    use super::*;
-    use crate::tests::{UserInsertRequest, load_fixtures};
+    fn reject_mallory(values: &[(ColumnDef, Value)]) -> IcDbmsResult<()> {
+        let name = column(values, "name");
+        if name == "Mallory" {
+            return Err(DbmsError::Validation("Mallory is not welcome".to_string()));
+        }
+        HOOK_CALLS.with_borrow_mut(|calls| calls.push(format!("insert {name}")));
+        Ok(())
+    }
 
-    fn alice() -> Principal {
-        crate::utils::caller()
+    fn record_update(old: &[(ColumnDef, Value)], new: &[(ColumnDef, Value)]) -> IcDbmsResult<()> {
+        let (old, new) = (column(old, "name"), column(new, "name"));
+        HOOK_CALLS.with_borrow_mut(|calls| calls.push(format!("update {old} -> {new}")));
+        Ok(())
     }
 
-    fn init_acl() {
-        DBMS_CONTEXT.with(|ctx| {
-            crate::tests::TestDatabaseSchema::register_tables(ctx)
-                .expect("failed to register tables");
-            ctx.acl_grant(alice(), PermGrant::Admin).unwrap();
-            ctx.acl_grant(alice(), PermGrant::ManageAcl).unwrap();
-            ctx.acl_grant(alice(), PermGrant::Migrate).unwrap();
-            ctx.acl_grant(alice(), PermGrant::AllTables(TablePerms::all()))
-                .unwrap();
-        });
+    fn record_delete(values: &[(ColumnDef, Value)]) -> IcDbmsResult<()> {
+        let name = column(values, "name");
+        HOOK_CALLS.with_borrow_mut(|calls| calls.push(format!("delete {name}")));
+        Ok(())
     }
 
     #[test]
-    fn test_should_grant_admin() {
+    fn test_should_run_table_hooks_before_writes() {
         init_acl();
-        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
-        grant_admin(bob).expect("failed to grant admin");
-        DBMS_CONTEXT.with(|ctx| assert!(ctx.granted_admin(&bob)));
+        load_fixtures();
+        set_table_hooks(&[TableHooks {
+            table: crate::tests::User::table_name(),
+            on_insert: Some(reject_mallory),
+            on_update: Some(record_update),
+            on_delete: Some(record_delete),
+        }]);
+        let record = |id: u32, name: &str| UserInsertRequest {
+            id: id.into(),
+            name: name.to_string().into(),
+            email: "user@example.com".into(),
+            age: 30u32.into(),
+        };
+
+        insert::<crate::tests::User, _>(
+            record(100, "Oscar"),
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let res = insert::<crate::tests::User, _>(
+            record(101, "Mallory"),
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(res, Err(DbmsError::Validation(_))));
+        let filter = Filter::Eq("id".to_string(), Uint32::from(101u32).into());
+        assert!(
+            !exists::<crate::tests::User, _>(filter, None, crate::tests::TestDatabaseSchema)
+                .unwrap()
+        );
+
+        let patch = crate::tests::UserUpdateRequest {
+            id: None,
+            name: Some("Oskar".to_string().into()),
+            email: None,
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(100u32).into())),
+        };
+        update::<crate::tests::User, _>(patch, None, None, crate::tests::TestDatabaseSchema)
+            .unwrap();
+        delete::<crate::tests::User, _>(
+            DeleteBehavior::Restrict,
+            Some(Filter::Eq("id".to_string(), Uint32::from(100u32).into())),
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+
+        HOOK_CALLS.with_borrow(|calls| {
+            assert_eq!(
+                calls,
+                &["insert Oscar", "update Oscar -> Oskar", "delete Oskar"]
+            );
+        });
     }
 
     #[test]
-    fn test_should_revoke_admin() {
+    fn test_should_run_update_hook_when_upsert_overwrites_a_row() {
         init_acl();
-        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
-        grant_admin(bob).unwrap();
-        revoke_admin(bob).unwrap();
-        DBMS_CONTEXT.with(|ctx| assert!(!ctx.granted_admin(&bob)));
+        load_fixtures();
+        set_table_hooks(&[TableHooks {
+            table: crate::tests::User::table_name(),
+            on_insert: Some(reject_mallory),
+            on_update: Some(record_update),
+            on_delete: None,
+        }]);
+        let record = |name: &str| UserInsertRequest {
+            id: 301u32.into(),
+            name: name.to_string().into(),
+            email: "carol@example.com".into(),
+            age: 35u32.into(),
+        };
+
+        upsert::<crate::tests::User, _>(record("Carol"), None, crate::tests::TestDatabaseSchema)
+            .unwrap();
+        let res = upsert::<crate::tests::User, _>(
+            record("Caroline"),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(res, UpsertResult::Updated);
+        insert_with_behavior::<crate::tests::User, _>(
+            record("Carla"),
+            InsertConflictBehavior::Ignore,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+
+        HOOK_CALLS.with_borrow(|calls| {
+            assert_eq!(calls, &["insert Carol", "update Carol -> Caroline"]);
+        });
     }
 
     #[test]
-    fn test_should_list_identities() {
+    fn test_should_drop_failing_bulk_update_from_transaction() {
         init_acl();
-        let identities = list_identities().expect("failed to list identities");
-        assert!(identities.iter().any(|(p, _)| *p == alice()));
+        load_fixtures();
+        set_table_hooks(&[TableHooks {
+            table: crate::tests::User::table_name(),
+            on_insert: None,
+            on_update: Some(|_, new| reject_mallory(new)),
+            on_delete: None,
+        }]);
+        let patch = |id: u32, name: &str| crate::tests::UserUpdateRequest {
+            id: None,
+            name: Some(name.into()),
+            email: None,
+            age: None,
+            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(id).into())),
+        };
+
+        let tx_id = begin_transaction();
+        let res = bulk_update::<crate::tests::User, _>(
+            vec![patch(1, "Robert"), patch(2, "Mallory")],
+            false,
+            Some(tx_id),
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(res, Err(DbmsError::Validation(_))));
+        let counts = bulk_update::<crate::tests::User, _>(
+            vec![patch(3, "Dee")],
+            false,
+            Some(tx_id),
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        assert_eq!(counts, vec![1]);
+        commit(tx_id, crate::tests::TestDatabaseSchema).unwrap();
+
+        // only the batch that succeeded is committed
+        let users = select::<crate::tests::User, _>(
+            Query::builder().order_by_asc("id").build(),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        let names: Vec<_> = users[1..=3]
+            .iter()
+            .map(|user| user.name.clone().unwrap())
+            .collect();
+        assert_eq!(names, vec!["Bob".into(), "Charlie".into(), "Dee".into()]);
     }
 
     #[test]
-    fn test_should_my_perms_returns_callers() {
+    fn test_should_return_restrict_violation_instead_of_trapping() {
         init_acl();
-        let perms = my_perms();
-        assert!(perms.admin && perms.manage_acl && perms.migrate);
+        load_fixtures();
+
+        let filter = Filter::Eq("id".to_string(), Uint32::from(1u32).into());
+        let res = delete::<crate::tests::User, _>(
+            DeleteBehavior::Restrict,
+            Some(filter.clone()),
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(
+                QueryError::ForeignKeyConstraintViolation { .. }
+            ))
+        ));
+        assert!(
+            exists::<crate::tests::User, _>(filter, None, crate::tests::TestDatabaseSchema)
+                .unwrap()
+        );
     }
 
     #[test]
-    fn test_should_reject_unknown_table_acl_grant() {
+    fn test_should_return_commit_error_instead_of_trapping() {
         init_acl();
-        let bob = Principal::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
-        let err = grant_table_perms(bob, "missing".to_string(), TablePerms::READ)
-            .expect_err("unknown table must be rejected");
+        load_fixtures();
+        let record = || UserInsertRequest {
+            id: 100u32.into(),
+            name: "Alice".to_string().into(),
+            email: "alice@example.com".into(),
+            age: 25u32.into(),
+        };
+
+        let tx_id = begin_transaction();
+        insert::<crate::tests::User, _>(
+            record(),
+            Some(tx_id),
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .unwrap();
+        // the same row is inserted outside of the transaction before it commits
+        insert::<crate::tests::User, _>(record(), None, None, crate::tests::TestDatabaseSchema)
+            .unwrap();
+
+        let res = commit(tx_id, crate::tests::TestDatabaseSchema);
         assert!(matches!(
-            err,
-            DbmsError::Query(QueryError::TableNotFound(table)) if table == "missing"
+            res,
+            Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
         ));
     }
 
     #[test]
-    fn test_should_deny_join_without_joined_table_read_perm() {
+    fn test_should_delete_record() {
         init_acl();
-        let query = Query::builder()
-            .all()
-            .inner_join("posts", "users.id", "posts.user")
-            .build();
+        load_fixtures();
+
+        let filter = Some(Filter::Eq("id".to_string(), Uint32::from(2u32).into()));
+        let res = delete::<crate::tests::User, _>(
+            DeleteBehavior::Cascade,
+            filter,
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_should_update_in_chunks_capped_by_threshold() {
+        init_acl();
+        load_fixtures();
+        set_max_chunk_rows(Some(3));
+
+        let patch = || crate::tests::UserUpdateRequest {
+            id: None,
+            name: None,
+            email: None,
+            age: Some(99u32.into()),
+            where_clause: None,
+        };
+        let mut token = None;
+        let mut calls = 0;
+        loop {
+            let (count, next) = update_chunked::<crate::tests::User, _>(
+                patch(),
+                Some(10),
+                token,
+                None,
+                crate::tests::TestDatabaseSchema,
+            )
+            .expect("failed to update chunk");
+            assert!(count <= 3);
+            calls += 1;
+            token = next;
+            if token.is_none() {
+                break;
+            }
+        }
+
+        // 10 users, at most 3 per call
+        assert_eq!(calls, 4);
+        let updated = count::<crate::tests::User, _>(
+            Some(Filter::Eq("age".to_string(), Uint32::from(99u32).into())),
+            None,
+            crate::tests::TestDatabaseSchema,
+        );
+        assert_eq!(updated.unwrap(), 10);
+    }
+
+    #[test]
+    fn test_should_require_read_perm_to_delete_in_chunks() {
+        init_acl();
+        load_fixtures();
         let res = DBMS_CONTEXT.with(|ctx| {
             ctx.acl_revoke(&alice(), PermRevoke::Admin)
                 .expect("should revoke bootstrap admin bypass");
@@ -504,97 +2275,204 @@ mod tests {
                 .expect("should revoke bootstrap table perms");
             ctx.acl_grant(
                 alice(),
-                PermGrant::Table(crate::tests::User::fingerprint(), TablePerms::READ),
+                PermGrant::Table(crate::tests::User::fingerprint(), TablePerms::DELETE),
+            )
+            .expect("should grant delete");
+            delete_chunked::<crate::tests::User, _>(
+                DeleteBehavior::Cascade,
+                None,
+                Some(2),
+                None,
+                None,
+                crate::tests::TestDatabaseSchema,
             )
-            .expect("should grant root-table read");
-            select_join("users", query, None, crate::tests::TestDatabaseSchema)
         });
         assert!(matches!(
             res,
             Err(DbmsError::AccessDenied {
                 required: RequiredPerm::Table(perms),
                 table: Some(table),
-            }) if perms == TablePerms::READ && table == crate::tests::Post::fingerprint()
+            }) if perms == TablePerms::DELETE | TablePerms::READ
+                && table == crate::tests::User::fingerprint()
         ));
     }
 
     #[test]
-    fn test_should_begin_transaction() {
+    fn test_should_cascade_delete_with_perm_on_target_table_only() {
         init_acl();
-        let _tx_id = begin_transaction();
+        load_fixtures();
+        DBMS_CONTEXT.with(|ctx| {
+            ctx.acl_revoke(&alice(), PermRevoke::Admin)
+                .expect("should revoke bootstrap admin bypass");
+            ctx.acl_revoke(&alice(), PermRevoke::AllTables(TablePerms::all()))
+                .expect("should revoke bootstrap table perms");
+            ctx.acl_grant(
+                alice(),
+                PermGrant::Table(crate::tests::User::fingerprint(), TablePerms::DELETE),
+            )
+            .expect("should grant users delete");
+        });
+
+        let filter = Some(Filter::Eq("id".to_string(), Uint32::from(2u32).into()));
+        let deleted = delete::<crate::tests::User, _>(
+            DeleteBehavior::Cascade,
+            filter,
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .expect("cascade delete should not check the referencing tables");
+        assert!(deleted > 1);
+
+        // posts stay unreadable, but the referencing ones are gone
+        let posts_filter = Some(Filter::Eq("user".to_string(), Uint32::from(2u32).into()));
+        assert!(matches!(
+            count::<crate::tests::Post, _>(
+                posts_filter.clone(),
+                None,
+                crate::tests::TestDatabaseSchema
+            ),
+            Err(DbmsError::AccessDenied { .. })
+        ));
+        DBMS_CONTEXT.with(|ctx| {
+            ctx.acl_grant(
+                alice(),
+                PermGrant::Table(crate::tests::Post::fingerprint(), TablePerms::READ),
+            )
+            .expect("should grant posts read");
+        });
+        let posts =
+            count::<crate::tests::Post, _>(posts_filter, None, crate::tests::TestDatabaseSchema)
+                .expect("failed to count posts");
+        assert_eq!(posts, 0);
     }
 
     #[test]
-    fn test_should_commit_transaction() {
+    fn test_should_cascade_delete_self_referencing_rows() {
         init_acl();
-        let tx_id = begin_transaction();
-        let res = commit(tx_id, crate::tests::TestDatabaseSchema);
-        assert!(res.is_ok());
+        load_fixtures();
+
+        // "Books" and its subcategories, children of children included
+        let filter = Some(Filter::Eq("id".to_string(), Uint32::from(0u32).into()));
+        let deleted = delete::<crate::tests::Category, _>(
+            DeleteBehavior::Cascade,
+            filter,
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .expect("failed to delete category");
+        assert_eq!(deleted, 5);
+
+        let remaining =
+            count::<crate::tests::Category, _>(None, None, crate::tests::TestDatabaseSchema)
+                .expect("failed to count categories");
+        assert_eq!(remaining, 1);
     }
 
     #[test]
-    fn test_should_rollback_transaction() {
+    fn test_should_cascade_delete_circular_references() {
         init_acl();
-        let tx_id = begin_transaction();
-        let res = rollback(tx_id, crate::tests::TestDatabaseSchema);
-        assert!(res.is_ok());
+        load_fixtures();
+
+        // the team, its players, and back to the team captained by one of them
+        let filter = Some(Filter::Eq("id".to_string(), Uint32::from(0u32).into()));
+        let deleted = delete::<crate::tests::Team, _>(
+            DeleteBehavior::Cascade,
+            filter,
+            None,
+            None,
+            crate::tests::TestDatabaseSchema,
+        )
+        .expect("failed to delete team");
+        assert_eq!(deleted, 3);
+
+        let teams = count::<crate::tests::Team, _>(None, None, crate::tests::TestDatabaseSchema)
+            .expect("failed to count teams");
+        assert_eq!(teams, 1);
+        let players =
+            count::<crate::tests::Player, _>(None, None, crate::tests::TestDatabaseSchema)
+                .expect("failed to count players");
+        assert_eq!(players, 2);
     }
 
     #[test]
-    fn test_should_insert_record() {
-        load_fixtures();
+    fn test_should_truncate_table() {
         init_acl();
-        let record = UserInsertRequest {
-            id: 100u32.into(),
-            name: "Alice".to_string().into(),
-            email: "alice@example.com".into(),
-            age: 25u32.into(),
-        };
+        load_fixtures();
 
-        let res = insert::<crate::tests::User, _>(record, None, crate::tests::TestDatabaseSchema);
-        assert!(res.is_ok());
+        let res = truncate::<crate::tests::Message, _>(None, crate::tests::TestDatabaseSchema);
+        assert_eq!(res.unwrap(), crate::tests::MESSAGES_FIXTURES.len() as u64);
+        let count = count::<crate::tests::Message, _>(None, None, crate::tests::TestDatabaseSchema)
+            .unwrap();
+        assert_eq!(count, 0);
     }
 
     #[test]
-    fn test_should_select_record() {
+    fn test_should_defragment_table() {
         init_acl();
         load_fixtures();
-        let query = Query::builder().all().limit(10).build();
-        let res = select::<crate::tests::User, _>(query, None, crate::tests::TestDatabaseSchema);
-        assert!(res.is_ok());
-        let records = res.unwrap();
-        assert!(!records.is_empty());
+
+        let released = defragment_table("messages".to_string(), crate::tests::TestDatabaseSchema)
+            .expect("failed to defragment");
+        assert_eq!(released, 0);
+        let count = count::<crate::tests::Message, _>(None, None, crate::tests::TestDatabaseSchema)
+            .unwrap();
+        assert_eq!(count, crate::tests::MESSAGES_FIXTURES.len() as u64);
+
+        let res = defragment_table("unknown".to_string(), crate::tests::TestDatabaseSchema);
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::TableNotFound(_)))
+        ));
     }
 
     #[test]
-    fn test_should_update_record() {
+    fn test_should_compact_table() {
         init_acl();
         load_fixtures();
 
-        let patch = crate::tests::UserUpdateRequest {
-            id: None,
-            name: Some("Robert".into()),
-            email: Some("robert@example.com".into()),
-            age: None,
-            where_clause: Some(Filter::Eq("id".to_string(), Uint32::from(1u32).into())),
-        };
-        let res = update::<crate::tests::User, _>(patch, None, crate::tests::TestDatabaseSchema);
-        assert!(res.is_ok());
+        let report = compact_table("messages".to_string(), 8, crate::tests::TestDatabaseSchema)
+            .expect("failed to compact");
+        assert!(report.complete);
+        assert_eq!(report.pages_after, report.pages_before);
+        assert_eq!(report.bytes_reclaimed, 0);
+        let count = count::<crate::tests::Message, _>(None, None, crate::tests::TestDatabaseSchema)
+            .unwrap();
+        assert_eq!(count, crate::tests::MESSAGES_FIXTURES.len() as u64);
+
+        let res = compact_table("unknown".to_string(), 8, crate::tests::TestDatabaseSchema);
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::TableNotFound(_)))
+        ));
     }
 
     #[test]
-    fn test_should_delete_record() {
+    fn test_should_reject_blob_chunk_on_non_blob_column() {
         init_acl();
         load_fixtures();
 
-        let filter = Some(Filter::Eq("id".to_string(), Uint32::from(2u32).into()));
-        let res = delete::<crate::tests::User, _>(
-            DeleteBehavior::Cascade,
-            filter,
-            None,
+        let res = blob_read_chunk::<crate::tests::User, _>(
+            Value::Uint32(Uint32(0)),
+            "name".to_string(),
+            0,
+            10,
             crate::tests::TestDatabaseSchema,
         );
-        assert!(res.is_ok());
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+        ));
+    }
+
+    #[test]
+    fn test_should_vacuum_without_retention_policies() {
+        init_acl();
+        load_fixtures();
+
+        let report = vacuum(crate::tests::TestDatabaseSchema).expect("failed to vacuum");
+        assert_eq!(report, VacuumReport::default());
     }
 
     #[test]
@@ -0,0 +1,230 @@
+//! Lifecycle hooks run by the write endpoints of a table.
+//!
+//! Hooks are declared per table in `#[tables(...)]` and registered by the
+//! canister `init` and `post_upgrade` with [`set_table_hooks`]. They run once
+//! per row, before the write, against the same database view (including the
+//! transaction overlay): a hook returning an error aborts the write, and the
+//! error is returned to the caller. Hooks are not run for the rows of other
+//! tables touched by a cascade.
+//!
+//! The hooks live on the heap, so they are registered again on upgrade.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use ic_dbms_api::prelude::{
+    ColumnDef, Database, Filter, IcDbmsResult, InsertConflictBehavior, InsertRecord, Query,
+    TableSchema, UpdateRecord, Value,
+};
+use wasm_dbms::prelude::WasmDbmsDatabase;
+
+use crate::memory::{IcAccessControlList, IcMemoryProvider};
+
+/// Hook run with the values of a row about to be inserted.
+pub type InsertHook = fn(&[(ColumnDef, Value)]) -> IcDbmsResult<()>;
+
+/// Hook run with the values of a row before and after it is updated.
+pub type UpdateHook = fn(&[(ColumnDef, Value)], &[(ColumnDef, Value)]) -> IcDbmsResult<()>;
+
+/// Hook run with the values of a row about to be deleted.
+pub type DeleteHook = fn(&[(ColumnDef, Value)]) -> IcDbmsResult<()>;
+
+/// The lifecycle hooks of a table.
+#[derive(Clone, Copy, Debug)]
+pub struct TableHooks {
+    /// Name of the table.
+    pub table: &'static str,
+    /// Run for every record handed to `insert`, `insert_many`,
+    /// `insert_with_behavior`, `insert_returning` and `upsert`, with the
+    /// record as sent, before sanitizers and generated values. Records
+    /// overwriting an existing row run `on_update` instead, and records
+    /// skipped by [`InsertConflictBehavior::Ignore`] run no hook.
+    pub on_insert: Option<InsertHook>,
+    /// Run for every row matched by `update`, `update_returning` and
+    /// `bulk_update`, or overwritten by an insert, with the row as stored and
+    /// the row with the patched columns replaced.
+    pub on_update: Option<UpdateHook>,
+    /// Run for every row removed by `delete`, `force_delete` and `truncate`.
+    pub on_delete: Option<DeleteHook>,
+}
+
+thread_local! {
+    static TABLE_HOOKS: RefCell<HashMap<&'static str, TableHooks>> = RefCell::new(HashMap::new());
+}
+
+/// Registers the lifecycle hooks of the tables. Called from the canister
+/// `init` and `post_upgrade`, as hooks are not persisted.
+pub fn set_table_hooks(hooks: &[TableHooks]) {
+    TABLE_HOOKS.with_borrow_mut(|registered| {
+        for table_hooks in hooks {
+            registered.insert(table_hooks.table, *table_hooks);
+        }
+    });
+}
+
+fn hooks_of<T>() -> Option<TableHooks>
+where
+    T: TableSchema,
+{
+    TABLE_HOOKS.with_borrow(|registered| registered.get(T::table_name()).copied())
+}
+
+/// Runs the `on_insert` hook of `T`, if any, on `values`.
+pub(super) fn on_insert<T>(values: &[(ColumnDef, Value)]) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+{
+    match hooks_of::<T>().and_then(|hooks| hooks.on_insert) {
+        Some(hook) => hook(values),
+        None => Ok(()),
+    }
+}
+
+/// Runs the hooks of `T` for an insert of `record`: `on_update` when an
+/// `#[on_conflict = "update"]` table updates the row sharing its primary key
+/// instead, `on_insert` otherwise.
+pub(super) fn on_insert_or_conflict_update<T>(
+    db: &WasmDbmsDatabase<'_, IcMemoryProvider, IcAccessControlList>,
+    record: &T::Insert,
+) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
+{
+    if let Some(patch) = T::on_conflict_update(record)
+        && let Some(pk_filter) = patch.where_clause()
+        && row_exists::<T>(db, pk_filter)?
+    {
+        return on_update::<T>(db, &patch);
+    }
+    on_insert::<T>(&record.clone().into_values())
+}
+
+/// Runs the hooks of `T` for an insert of `record` resolving conflicts with
+/// `behavior`: `on_update` when it replaces the row sharing its primary key,
+/// none when it is ignored because of that row, `on_insert` otherwise.
+pub(super) fn on_insert_with_behavior<T>(
+    db: &WasmDbmsDatabase<'_, IcMemoryProvider, IcAccessControlList>,
+    record: &T::Insert,
+    behavior: InsertConflictBehavior,
+) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+    T::Insert: InsertRecord<Schema = T>,
+{
+    let values = record.clone().into_values();
+    let conflict = match behavior {
+        InsertConflictBehavior::Error => None,
+        InsertConflictBehavior::Ignore | InsertConflictBehavior::Replace => {
+            primary_key_filter::<T>(&values)
+        }
+    };
+    let Some(pk_filter) = conflict else {
+        return on_insert::<T>(&values);
+    };
+    if !row_exists::<T>(db, pk_filter.clone())? {
+        return on_insert::<T>(&values);
+    }
+    match behavior {
+        InsertConflictBehavior::Replace => {
+            let patch_values = values
+                .into_iter()
+                .filter(|(column, _)| !column.primary_key)
+                .collect::<Vec<_>>();
+            on_update::<T>(db, &T::Update::from_values(&patch_values, Some(pk_filter)))
+        }
+        InsertConflictBehavior::Error | InsertConflictBehavior::Ignore => Ok(()),
+    }
+}
+
+/// Builds the filter matching the row sharing the primary key of `values`, or
+/// `None` when the key is left to be generated.
+fn primary_key_filter<T>(values: &[(ColumnDef, Value)]) -> Option<Filter>
+where
+    T: TableSchema,
+{
+    let mut filter: Option<Filter> = None;
+    for column in T::primary_keys() {
+        let value = values
+            .iter()
+            .find(|(col, _)| col.name == *column)
+            .map(|(_, value)| value.clone())?;
+        let eq = Filter::eq(column, value);
+        filter = Some(match filter {
+            Some(filter) => filter.and(eq),
+            None => eq,
+        });
+    }
+    filter
+}
+
+/// Returns whether a row of `T` matches `filter`, soft-deleted rows included
+/// like in the conflict checks of inserts.
+fn row_exists<T>(
+    db: &WasmDbmsDatabase<'_, IcMemoryProvider, IcAccessControlList>,
+    filter: Filter,
+) -> IcDbmsResult<bool>
+where
+    T: TableSchema,
+{
+    let query = Query::builder()
+        .include_deleted()
+        .filter(Some(filter))
+        .limit(1)
+        .build();
+    Ok(!db.select_raw(T::table_name(), query)?.is_empty())
+}
+
+/// Runs the `on_update` hook of `T`, if any, on every row matched by `patch`.
+pub(super) fn on_update<T>(
+    db: &WasmDbmsDatabase<'_, IcMemoryProvider, IcAccessControlList>,
+    patch: &T::Update,
+) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+    T::Update: UpdateRecord<Schema = T>,
+{
+    let Some(hook) = hooks_of::<T>().and_then(|hooks| hooks.on_update) else {
+        return Ok(());
+    };
+    // updates reach soft-deleted rows too
+    let query = Query::builder()
+        .include_deleted()
+        .filter(patch.where_clause())
+        .build();
+    let changes = patch.update_values();
+    for old in db.select_raw(T::table_name(), query)? {
+        let mut new = old.clone();
+        for (column, value) in &mut new {
+            if let Some((_, patched)) = changes.iter().find(|(col, _)| col.name == column.name) {
+                *value = patched.clone();
+            }
+        }
+        hook(&old, &new)?;
+    }
+    Ok(())
+}
+
+/// Runs the `on_delete` hook of `T`, if any, on every row matched by
+/// `filter`. Soft-deleted rows are only matched with `include_deleted`.
+pub(super) fn on_delete<T>(
+    db: &WasmDbmsDatabase<'_, IcMemoryProvider, IcAccessControlList>,
+    filter: Option<Filter>,
+    include_deleted: bool,
+) -> IcDbmsResult<()>
+where
+    T: TableSchema,
+{
+    let Some(hook) = hooks_of::<T>().and_then(|hooks| hooks.on_delete) else {
+        return Ok(());
+    };
+    let mut query = Query::builder().filter(filter);
+    if include_deleted {
+        query = query.include_deleted();
+    }
+    let query = query.build();
+    for row in db.select_raw(T::table_name(), query)? {
+        hook(&row)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,255 @@
+//! Idempotency of the write endpoints called with a client-generated
+//! [`RequestId`].
+//!
+//! The response of a successful write is remembered, keyed by caller, table,
+//! operation and request ID, for [`REQUEST_ID_TTL`] nanoseconds. A retried
+//! call carrying the same key gets the remembered response and is not applied
+//! again. At most [`MAX_REMEMBERED_REQUESTS`] responses are kept, the oldest
+//! being forgotten first.
+//!
+//! The response of a write staged in a transaction is remembered for that
+//! transaction only until it is committed, see [`commit`]. If the
+//! transaction is rolled back or expires, the write was never applied, so its
+//! response is forgotten with [`forget`] and a retry applies it.
+//!
+//! The responses live on the heap, so they are forgotten on upgrade.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+
+use candid::Principal;
+use ic_dbms_api::prelude::{IcDbmsResult, RequestId, TableFingerprint, TransactionId};
+
+/// How long a response is remembered: ten minutes, in nanoseconds.
+pub const REQUEST_ID_TTL: u64 = 10 * 60 * 1_000_000_000;
+
+/// Maximum number of responses remembered at once.
+pub const MAX_REMEMBERED_REQUESTS: usize = 4096;
+
+/// Write operation a [`RequestId`] is scoped to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WriteOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// Caller, table, operation and request ID of a write, with the transaction
+/// it is staged in until committed.
+type RequestKey = (
+    Principal,
+    TableFingerprint,
+    WriteOp,
+    RequestId,
+    Option<TransactionId>,
+);
+
+thread_local! {
+    static RESPONSES: RefCell<RememberedResponses> = RefCell::new(RememberedResponses::default());
+}
+
+/// Responses of the write requests, in the order they were remembered.
+#[derive(Default)]
+struct RememberedResponses {
+    responses: HashMap<RequestKey, u64>,
+    /// Keys with their expiry time; the TTL is fixed, so the front entry is
+    /// always the first to expire.
+    expiry: VecDeque<(u64, RequestKey)>,
+}
+
+impl RememberedResponses {
+    fn get(&mut self, key: &RequestKey, now: u64) -> Option<u64> {
+        self.evict(now);
+        self.responses.get(key).copied()
+    }
+
+    fn remember(&mut self, key: RequestKey, response: u64, now: u64) {
+        if self.responses.insert(key.clone(), response).is_none() {
+            self.expiry
+                .push_back((now.saturating_add(REQUEST_ID_TTL), key));
+        }
+        self.evict(now);
+    }
+
+    /// Remembers the responses of the writes staged in `transaction_id` as
+    /// applied.
+    fn commit(&mut self, transaction_id: TransactionId) {
+        for (_, key) in self.expiry.iter_mut() {
+            if key.4 != Some(transaction_id) {
+                continue;
+            }
+            if let Some(response) = self.responses.remove(key) {
+                key.4 = None;
+                self.responses.insert(key.clone(), response);
+            }
+        }
+    }
+
+    /// Forgets the responses of the writes staged in `transaction_id`.
+    fn forget(&mut self, transaction_id: TransactionId) {
+        self.expiry.retain(|(_, key)| key.4 != Some(transaction_id));
+        self.responses
+            .retain(|key, _| key.4 != Some(transaction_id));
+    }
+
+    /// Forgets the expired responses and the oldest ones beyond
+    /// [`MAX_REMEMBERED_REQUESTS`].
+    fn evict(&mut self, now: u64) {
+        while let Some((expires_at, _)) = self.expiry.front() {
+            if *expires_at > now && self.expiry.len() <= MAX_REMEMBERED_REQUESTS {
+                break;
+            }
+            if let Some((_, key)) = self.expiry.pop_front() {
+                self.responses.remove(&key);
+            }
+        }
+    }
+}
+
+/// Runs the write `f` at most once per `request_id`.
+///
+/// Without a request ID `f` is always run. Otherwise, the response remembered
+/// for the same caller, table and operation is returned if any, whether
+/// applied or staged in `transaction_id`; else `f` is run and its response
+/// remembered, unless it failed, so that a failed write can be retried.
+pub fn run_once<F>(
+    request_id: Option<RequestId>,
+    transaction_id: Option<TransactionId>,
+    table: TableFingerprint,
+    op: WriteOp,
+    f: F,
+) -> IcDbmsResult<u64>
+where
+    F: FnOnce() -> IcDbmsResult<u64>,
+{
+    let Some(request_id) = request_id else {
+        return f();
+    };
+
+    let key = (
+        crate::utils::caller(),
+        table,
+        op,
+        request_id,
+        transaction_id,
+    );
+    let now = crate::utils::time();
+    let remembered = RESPONSES.with_borrow_mut(|responses| {
+        let applied = (key.0, key.1, key.2, key.3.clone(), None);
+        responses
+            .get(&applied, now)
+            .or_else(|| responses.get(&key, now))
+    });
+    if let Some(response) = remembered {
+        return Ok(response);
+    }
+
+    let response = f()?;
+    RESPONSES.with_borrow_mut(|responses| responses.remember(key, response, now));
+    Ok(response)
+}
+
+/// Keeps the responses of the writes staged in the committed transaction
+/// `transaction_id`, which are now applied.
+pub fn commit(transaction_id: TransactionId) {
+    RESPONSES.with_borrow_mut(|responses| responses.commit(transaction_id));
+}
+
+/// Forgets the responses of the writes staged in the rolled back or expired
+/// transaction `transaction_id`, which were never applied.
+pub fn forget(transaction_id: TransactionId) {
+    RESPONSES.with_borrow_mut(|responses| responses.forget(transaction_id));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn key(request_id: &str) -> RequestKey {
+        (
+            Principal::anonymous(),
+            1,
+            WriteOp::Insert,
+            request_id.to_string(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_should_remember_response_until_ttl() {
+        let mut responses = RememberedResponses::default();
+        responses.remember(key("a"), 3, 100);
+
+        assert_eq!(responses.get(&key("a"), 100), Some(3));
+        assert_eq!(responses.get(&key("a"), 100 + REQUEST_ID_TTL - 1), Some(3));
+        assert_eq!(responses.get(&key("a"), 100 + REQUEST_ID_TTL), None);
+        assert!(responses.expiry.is_empty());
+    }
+
+    #[test]
+    fn test_should_forget_oldest_response_beyond_capacity() {
+        let mut responses = RememberedResponses::default();
+        for id in 0..=MAX_REMEMBERED_REQUESTS {
+            responses.remember(key(&id.to_string()), id as u64, 0);
+        }
+
+        assert_eq!(responses.responses.len(), MAX_REMEMBERED_REQUESTS);
+        assert_eq!(responses.get(&key("0"), 0), None);
+        assert_eq!(responses.get(&key("1"), 0), Some(1));
+    }
+
+    #[test]
+    fn test_should_keep_committed_responses_and_forget_rolled_back_ones() {
+        let staged = |request_id: &str, transaction_id| {
+            let mut key = key(request_id);
+            key.4 = Some(transaction_id);
+            key
+        };
+        let mut responses = RememberedResponses::default();
+        responses.remember(staged("a", 1), 3, 0);
+        responses.remember(staged("b", 2), 5, 0);
+
+        responses.commit(1);
+        assert_eq!(responses.get(&key("a"), 0), Some(3));
+        assert_eq!(responses.get(&staged("a", 1), 0), None);
+
+        responses.forget(2);
+        assert_eq!(responses.get(&staged("b", 2), 0), None);
+        assert_eq!(responses.expiry.len(), 1);
+    }
+
+    #[test]
+    fn test_should_run_write_once_per_request_id() {
+        let mut runs = 0;
+        for _ in 0..2 {
+            let response = run_once(Some("req-1".to_string()), None, 1, WriteOp::Update, || {
+                runs += 1;
+                Ok(7)
+            });
+            assert_eq!(response.unwrap(), 7);
+        }
+        assert_eq!(runs, 1);
+
+        // same ID, other operation
+        run_once(Some("req-1".to_string()), None, 1, WriteOp::Delete, || {
+            runs += 1;
+            Ok(0)
+        })
+        .unwrap();
+        assert_eq!(runs, 2);
+    }
+
+    #[test]
+    fn test_should_always_run_write_without_request_id() {
+        let mut runs = 0;
+        for _ in 0..2 {
+            run_once(None, None, 1, WriteOp::Insert, || {
+                runs += 1;
+                Ok(0)
+            })
+            .unwrap();
+        }
+        assert_eq!(runs, 2);
+    }
+}
@@ -74,15 +74,23 @@
 //!   acl_remove_principal : (principal) -> (Result);
 //!   begin_transaction : () -> (nat);
 //!   commit : (nat) -> (Result);
-//!   delete_posts : (DeleteBehavior, opt Filter_1, opt nat) -> (Result_1);
-//!   delete_users : (DeleteBehavior, opt Filter_1, opt nat) -> (Result_1);
-//!   insert_posts : (PostInsertRequest, opt nat) -> (Result);
-//!   insert_users : (UserInsertRequest, opt nat) -> (Result);
+//!   delete_posts : (DeleteBehavior, opt Filter_1, opt nat, opt text) -> (Result_1);
+//!   delete_users : (DeleteBehavior, opt Filter_1, opt nat, opt text) -> (Result_1);
+//!   delete_chunked_users : (DeleteBehavior, opt Filter_1, opt nat64, opt ContinuationToken, opt nat) -> (Result_5);
+//!   force_delete_users : (DeleteBehavior, opt Filter_1, opt nat) -> (Result_1);
+//!   truncate_users : (opt nat) -> (Result_1);
+//!   blob_read_chunk_users : (Value, text, nat64, nat64) -> (Result_4) query;
+//!   blob_write_chunk_users : (Value, text, nat64, vec nat8) -> (Result_1);
+//!   validate_insert_users : (UserInsertRequest, opt nat) -> (Result) query;
+//!   validate_update_users : (UserUpdateRequest, opt nat) -> (Result) query;
+//!   insert_posts : (PostInsertRequest, opt nat, opt text) -> (Result);
+//!   insert_users : (UserInsertRequest, opt nat, opt text) -> (Result);
 //!   rollback : (nat) -> (Result);
 //!   select_posts : (Query, opt nat) -> (Result_2) query;
 //!   select_users : (Query_1, opt nat) -> (Result_3) query;
-//!   update_posts : (PostUpdateRequest, opt nat) -> (Result_1);
-//!   update_users : (UserUpdateRequest, opt nat) -> (Result_1);
+//!   update_posts : (PostUpdateRequest, opt nat, opt text) -> (Result_1);
+//!   update_chunked_users : (UserUpdateRequest, opt nat64, opt ContinuationToken, opt nat) -> (Result_5);
+//!   update_users : (UserUpdateRequest, opt nat, opt text) -> (Result_1);
 //! }
 //! ```
 //!
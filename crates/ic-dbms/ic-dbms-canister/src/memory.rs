@@ -100,6 +100,9 @@ impl AccessControl for IcAccessControlList {
 }
 
 thread_local! {
-    pub static DBMS_CONTEXT: wasm_dbms::prelude::DbmsContext<IcMemoryProvider, IcAccessControlList> =
-        wasm_dbms::prelude::DbmsContext::with_acl(IcMemoryProvider::default());
+    pub static DBMS_CONTEXT: wasm_dbms::prelude::DbmsContext<IcMemoryProvider, IcAccessControlList> = {
+        let ctx = wasm_dbms::prelude::DbmsContext::with_acl(IcMemoryProvider::default());
+        ctx.set_clock(crate::utils::time);
+        ctx
+    };
 }
@@ -4,7 +4,7 @@ pub use ic_dbms_api::prelude::*;
 pub use ic_dbms_macros::DbmsCanister;
 pub use wasm_dbms::prelude::{
     DatabaseSchema, DbmsContext, InsertIntegrityValidator, UpdateIntegrityValidator,
-    WasmDbmsDatabase, get_referenced_tables,
+    WasmDbmsDatabase, get_referenced_tables, get_referencing_foreign_keys,
 };
 pub use wasm_dbms::transaction::session::TransactionSession;
 pub use wasm_dbms_macros::DatabaseSchema;
@@ -2,26 +2,50 @@
 
 //! Test types, fixtures and mocks.
 
+mod category;
 mod message;
+mod player;
 mod post;
+mod setting;
+mod team;
 mod user;
 
 use ic_dbms_api::prelude::Database as _;
 use wasm_dbms::prelude::WasmDbmsDatabase;
 
+#[allow(unused_imports)]
+pub use self::category::{
+    CATEGORIES_FIXTURES, Category, CategoryInsertRequest, CategoryRecord, CategoryUpdateRequest,
+};
 #[allow(unused_imports)]
 pub use self::message::{
     MESSAGES_FIXTURES, Message, MessageInsertRequest, MessageRecord, MessageUpdateRequest,
 };
 #[allow(unused_imports)]
+pub use self::player::{
+    PLAYERS_FIXTURES, Player, PlayerInsertRequest, PlayerRecord, PlayerUpdateRequest,
+};
+#[allow(unused_imports)]
 pub use self::post::{POSTS_FIXTURES, Post, PostInsertRequest, PostRecord, PostUpdateRequest};
 #[allow(unused_imports)]
+pub use self::setting::{Setting, SettingInsertRequest, SettingRecord, SettingUpdateRequest};
+#[allow(unused_imports)]
+pub use self::team::{TEAMS_FIXTURES, Team, TeamInsertRequest, TeamRecord, TeamUpdateRequest};
+#[allow(unused_imports)]
 pub use self::user::{USERS_FIXTURES, User, UserInsertRequest, UserRecord, UserUpdateRequest};
 use crate::memory::DBMS_CONTEXT;
 use crate::prelude::DatabaseSchema;
 
 #[derive(DatabaseSchema)]
-#[tables(User = "users", Post = "posts", Message = "messages")]
+#[tables(
+    User = "users",
+    Post = "posts",
+    Message = "messages",
+    Category = "categories",
+    Team = "teams",
+    Player = "players",
+    Setting = "settings"
+)]
 pub struct TestDatabaseSchema;
 
 /// Loads fixtures into the database for testing purposes.
@@ -71,5 +95,39 @@ pub fn load_fixtures() {
             db.insert::<Message>(record)
                 .expect("failed to insert message");
         }
+
+        // Insert categories, parents first
+        for (id, (name, parent_id)) in CATEGORIES_FIXTURES.iter().enumerate() {
+            let record = CategoryInsertRequest {
+                id: (id as u32).into(),
+                name: name.to_string().into(),
+                parent: parent_id.map_or(ic_dbms_api::prelude::Nullable::Null, |parent_id| {
+                    ic_dbms_api::prelude::Nullable::Value(parent_id.into())
+                }),
+            };
+            db.insert::<Category>(record)
+                .expect("failed to insert category");
+        }
+
+        // Insert teams before the players captaining them
+        for (id, (name, captain_id)) in TEAMS_FIXTURES.iter().enumerate() {
+            let record = TeamInsertRequest {
+                id: (id as u32).into(),
+                name: name.to_string().into(),
+                captain: (*captain_id).into(),
+            };
+            db.insert::<Team>(record).expect("failed to insert team");
+        }
+
+        // Insert players
+        for (id, (name, team_id)) in PLAYERS_FIXTURES.iter().enumerate() {
+            let record = PlayerInsertRequest {
+                id: (id as u32).into(),
+                name: name.to_string().into(),
+                team: (*team_id).into(),
+            };
+            db.insert::<Player>(record)
+                .expect("failed to insert player");
+        }
     });
 }
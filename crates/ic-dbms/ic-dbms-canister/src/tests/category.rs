@@ -0,0 +1,28 @@
+//! Category mock type; a category has many subcategories.
+
+use candid::CandidType;
+use ic_dbms_api::prelude::{Nullable, Text, Uint32};
+use wasm_dbms_macros::Table;
+
+/// A self-referencing category struct for testing purposes.
+///
+/// One [`Category`] has many subcategories, which have subcategories in turn.
+#[derive(Debug, Table, CandidType, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "categories"]
+pub struct Category {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    #[foreign_key(entity = "Category", table = "categories", column = "id")]
+    pub parent: Nullable<Uint32>,
+}
+
+pub const CATEGORIES_FIXTURES: &[(&str, Option<u32>)] = &[
+    ("Books", None),
+    ("Fiction", Some(0)),
+    ("Fantasy", Some(1)),
+    ("Science Fiction", Some(1)),
+    ("Non-fiction", Some(0)),
+    ("Music", None),
+];
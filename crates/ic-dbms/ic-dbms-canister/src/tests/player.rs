@@ -0,0 +1,23 @@
+//! Player mock type; 1 team has many players.
+
+use candid::CandidType;
+use ic_dbms_api::prelude::{Text, Uint32};
+use wasm_dbms_macros::Table;
+
+use crate::tests::{Team, TeamRecord};
+
+/// A player struct for testing purposes.
+///
+/// One [`super::Team`] has many [`Player`]s.
+#[derive(Debug, Table, CandidType, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "players"]
+pub struct Player {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    #[foreign_key(entity = "Team", table = "teams", column = "id")]
+    pub team: Uint32,
+}
+
+pub const PLAYERS_FIXTURES: &[(&str, u32)] = &[("Alice", 0), ("Bob", 0), ("Carol", 1), ("Dave", 1)];
@@ -0,0 +1,19 @@
+//! Setting mock type; inserting an existing key updates its value.
+
+use candid::CandidType;
+use ic_dbms_api::prelude::{Text, Uint32};
+use wasm_dbms_macros::Table;
+
+/// A key-value setting struct for testing purposes.
+///
+/// Declared with `#[on_conflict = "update"]`, so an insert whose key already
+/// exists overwrites the stored value.
+#[derive(Debug, Table, CandidType, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "settings"]
+#[on_conflict = "update"]
+pub struct Setting {
+    #[primary_key]
+    pub id: Uint32,
+    pub value: Text,
+}
@@ -0,0 +1,24 @@
+//! Team mock type; a team is captained by one of its players.
+
+use candid::CandidType;
+use ic_dbms_api::prelude::{Text, Uint32};
+use wasm_dbms_macros::Table;
+
+use crate::tests::{Player, PlayerRecord};
+
+/// A team struct for testing purposes.
+///
+/// [`Team`] and [`super::Player`] reference each other.
+#[derive(Debug, Table, CandidType, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "teams"]
+pub struct Team {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    // deferred, as the team is inserted before its players
+    #[foreign_key(entity = "Player", table = "players", column = "id", deferred)]
+    pub captain: Uint32,
+}
+
+pub const TEAMS_FIXTURES: &[(&str, u32)] = &[("Red", 0), ("Blue", 2)];
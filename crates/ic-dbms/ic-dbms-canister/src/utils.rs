@@ -17,6 +17,35 @@ pub fn caller() -> Principal {
     }
 }
 
+/// Returns whether `principal` is a controller of the canister.
+pub fn is_controller(principal: &Principal) -> bool {
+    #[cfg(target_family = "wasm")]
+    {
+        ic_cdk::api::is_controller(principal)
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        // the dummy caller acts as the controller on non-wasm targets
+        *principal == caller()
+    }
+}
+
+/// Returns the current time, in nanoseconds since the Unix epoch.
+pub fn time() -> u64 {
+    #[cfg(target_family = "wasm")]
+    {
+        ic_cdk::api::time()
+    }
+    #[cfg(not(target_family = "wasm"))]
+    {
+        // wall clock for non-wasm targets (e.g., during unit tests)
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default()
+    }
+}
+
 #[cfg(test)]
 mod test {
 
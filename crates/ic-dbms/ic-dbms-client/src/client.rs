@@ -2,25 +2,34 @@
 #[cfg_attr(docsrs, doc(cfg(feature = "ic-agent")))]
 mod agent;
 mod ic;
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+mod mock;
 #[cfg(feature = "pocket-ic")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pocket-ic")))]
 mod pocket_ic;
+mod retry;
 mod types;
 
 use candid::{CandidType, Principal};
 use ic_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, DeleteBehavior, Filter, IcDbmsResult, IdentityPerms,
-    InsertRecord, JoinColumnDef, MigrationOp, MigrationPolicy, Query, TablePerms, TableSchema,
-    TransactionId, UpdateRecord, Value,
+    AggregateFunction, AggregatedRow, BlobChunk, CompactionReport, ContinuationToken,
+    DeleteBehavior, Filter, IcDbmsResult, IdentityPerms, InsertConflictBehavior, InsertRecord,
+    JoinColumnDef, MigrationOp, MigrationPolicy, OrderDirection, PageCursor, Query, TablePerms,
+    TableSchema, TableStats, TransactionId, TransactionInfo, UpdateRecord, UpsertResult, Value,
 };
 
 #[cfg(feature = "ic-agent")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ic-agent")))]
 pub use self::agent::IcDbmsAgentClient;
 pub use self::ic::IcDbmsCanisterClient;
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub use self::mock::{MockCall, MockClient};
 #[cfg(feature = "pocket-ic")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pocket-ic")))]
 pub use self::pocket_ic::IcDbmsPocketIcClient;
+pub use self::retry::RetryPolicy;
 use crate::prelude::IcDbmsCanisterClientResult;
 
 type RawRecords = Vec<Vec<(JoinColumnDef, Value)>>;
@@ -111,6 +120,11 @@ pub trait Client {
         &self,
     ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Vec<(Principal, IdentityPerms)>>>>;
 
+    /// Lists every principal in the ACL. Only callable by a canister controller.
+    fn acl_list_all_principals(
+        &self,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Vec<Principal>>>>;
+
     /// Returns the caller's own [`IdentityPerms`].
     fn my_perms(&self) -> impl Future<Output = IcDbmsCanisterClientResult<IdentityPerms>>;
 
@@ -129,6 +143,12 @@ pub trait Client {
         transaction_id: TransactionId,
     ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<()>>>;
 
+    /// Lists the transactions open on the canister, sorted by ID. Only
+    /// callable by a canister controller.
+    fn list_active_transactions(
+        &self,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Vec<TransactionInfo>>>>;
+
     /// Executes a `SELECT` query on the IC DBMS Canister.
     fn select<T>(
         &self,
@@ -140,6 +160,62 @@ pub trait Client {
         T: TableSchema,
         T::Record: CandidType + for<'de> candid::Deserialize<'de>;
 
+    /// Returns the first record matching `query` on the IC DBMS Canister, if any.
+    ///
+    /// The canister ignores the query's `LIMIT` and stops scanning at the
+    /// first match, so only a single record crosses the wire.
+    fn select_one<T>(
+        &self,
+        table: &str,
+        query: Query,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Option<T::Record>>>>
+    where
+        T: TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>;
+
+    /// Executes a `SELECT` query on the IC DBMS Canister and returns one page
+    /// of records along with the [`PageCursor`] of the next page.
+    ///
+    /// Request the following page by passing the returned cursor back as
+    /// `cursor` with the same `query`; `None` requests the first page. The
+    /// cursor restricts the query to the records after it (see
+    /// [`PageCursor::apply`]), so rows inserted between two calls are
+    /// neither skipped nor returned twice, unlike with `OFFSET`. An
+    /// unordered query is sorted by the primary key, the cursor column.
+    #[allow(clippy::type_complexity)]
+    fn select_page<T>(
+        &self,
+        table: &str,
+        mut query: Query,
+        cursor: Option<PageCursor>,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<
+        Output = IcDbmsCanisterClientResult<IcDbmsResult<(Vec<T::Record>, Option<PageCursor>)>>,
+    >
+    where
+        T: TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        async move {
+            if let Some(cursor) = cursor {
+                query = cursor.apply(query);
+            }
+            if query.order_by.is_empty() {
+                query
+                    .order_by
+                    .push((T::primary_key().to_string(), OrderDirection::Ascending));
+            }
+            let records = self
+                .select::<T>(table, query.clone(), transaction_id)
+                .await?;
+            Ok(records.map(|records| {
+                let cursor = PageCursor::next_page::<T>(&query, &records);
+                (records, cursor)
+            }))
+        }
+    }
+
     /// Executes an aggregate query on the IC DBMS Canister.
     ///
     /// The `query` carries `WHERE`, `DISTINCT`, `GROUP BY`, `HAVING`,
@@ -155,6 +231,29 @@ pub trait Client {
     where
         T: TableSchema;
 
+    /// Counts the records of a table matching `filter` on the IC DBMS Canister.
+    ///
+    /// Rows are counted canister-side without being decoded or transferred;
+    /// `None` counts every row.
+    fn count<T>(
+        &self,
+        table: &str,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<u64>>>
+    where
+        T: TableSchema;
+
+    /// Returns whether any record of a table matches `filter` on the IC DBMS Canister.
+    fn exists<T>(
+        &self,
+        table: &str,
+        filter: Filter,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<bool>>>
+    where
+        T: TableSchema;
+
     /// Executes a `SELECT` query on the IC DBMS Canister and returns raw records (without deserialization).
     fn select_raw(
         &self,
@@ -174,6 +273,68 @@ pub trait Client {
         T: TableSchema,
         T::Insert: InsertRecord<Schema = T> + CandidType;
 
+    /// Executes a batch `INSERT` on the IC DBMS Canister.
+    ///
+    /// Either every record is inserted or none is; returns the number of
+    /// inserted rows.
+    fn insert_many<T>(
+        &self,
+        table: &str,
+        records: Vec<T::Insert>,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<u64>>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType;
+
+    /// Inserts a new record into the specified table, resolving a conflict
+    /// with an existing row according to `behavior`.
+    ///
+    /// [`InsertConflictBehavior::Ignore`] skips a record colliding on the
+    /// primary key or a unique column; [`InsertConflictBehavior::Replace`]
+    /// overwrites the row with the same primary key and requires the `UPDATE`
+    /// perm on the table as well.
+    fn insert_with_behavior<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        behavior: InsertConflictBehavior,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<()>>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType;
+
+    /// Inserts a record into the specified table and returns the row as
+    /// stored, after sanitizers and generated defaults are applied.
+    ///
+    /// Requires both the `INSERT` and `READ` perms on the table.
+    fn insert_returning<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<T::Record>>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>;
+
+    /// Inserts a record into the specified table, or overwrites the row with
+    /// the same primary key, in a single call.
+    ///
+    /// Returns whether the record was inserted or updated. Requires both the
+    /// `INSERT` and `UPDATE` perms on the table.
+    fn upsert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<UpsertResult>>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType;
+
     /// Executes an `UPDATE` query on the IC DBMS Canister.
     fn update<T>(
         &self,
@@ -185,6 +346,93 @@ pub trait Client {
         T: TableSchema,
         T::Update: UpdateRecord<Schema = T> + CandidType;
 
+    /// Executes an `UPDATE` query on at most `max_rows` rows of the table on
+    /// the IC DBMS Canister, resuming after `token`.
+    ///
+    /// Keeps each call within the instruction limit on a large table: the
+    /// canister selects the next chunk in primary key order and updates it in
+    /// the same call, capping its size with the `max_chunk_rows` threshold of
+    /// the canister. Returns the number of updated rows and the
+    /// [`ContinuationToken`] to pass back with the same `patch`, or `None`
+    /// once every matching row was visited. `None` as `max_rows` updates up
+    /// to the threshold. Requires both the `UPDATE` and `READ` perms on the
+    /// table.
+    #[allow(clippy::type_complexity)]
+    fn update_chunked<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<(u64, Option<ContinuationToken>)>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType;
+
+    /// Executes a batch of `UPDATE` queries, in order, on the IC DBMS
+    /// Canister and returns the number of rows updated by each patch.
+    ///
+    /// Either every patch is applied or none is. With `ignore_errors`, a
+    /// failing patch is skipped instead, counting `0` rows.
+    fn bulk_update<T>(
+        &self,
+        table: &str,
+        patches: Vec<T::Update>,
+        ignore_errors: bool,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Vec<u64>>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType;
+
+    /// Executes an `UPDATE` query on the IC DBMS Canister and returns the
+    /// updated rows as stored.
+    ///
+    /// Requires both the `UPDATE` and `READ` perms on the table.
+    fn update_returning<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Vec<T::Record>>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>;
+
+    /// Checks whether a record could be inserted into the specified table,
+    /// without writing it.
+    ///
+    /// Runs the sanitizers, validators and integrity checks of
+    /// [`insert`](Self::insert) as a query call, against the transaction when
+    /// one is given.
+    fn validate_insert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<()>>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType;
+
+    /// Checks whether an update could be applied to the specified table,
+    /// without writing it.
+    ///
+    /// Runs the sanitizers, validators and integrity checks of
+    /// [`update`](Self::update) on every matching row as a query call,
+    /// against the transaction when one is given.
+    fn validate_update<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<()>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType;
+
     /// Executes a `DELETE` query on the IC DBMS Canister.
     fn delete<T>(
         &self,
@@ -196,6 +444,86 @@ pub trait Client {
     where
         T: TableSchema;
 
+    /// Executes a `DELETE` query on at most `max_rows` rows of the table on
+    /// the IC DBMS Canister, resuming after `token`.
+    ///
+    /// Keeps each call within the instruction limit on a large table: the
+    /// canister selects the next chunk in primary key order and deletes it in
+    /// the same call, capping its size with the `max_chunk_rows` threshold of
+    /// the canister. Returns the number of deleted rows and the
+    /// [`ContinuationToken`] to pass back with the same `filter`, or `None`
+    /// once every matching row was visited. `None` as `max_rows` deletes up
+    /// to the threshold. Requires both the `DELETE` and `READ` perms on the
+    /// table.
+    #[allow(clippy::type_complexity)]
+    fn delete_chunked<T>(
+        &self,
+        table: &str,
+        behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<(u64, Option<ContinuationToken>)>>>
+    where
+        T: TableSchema;
+
+    /// Executes a `DELETE` query on the IC DBMS Canister which physically
+    /// removes the matching rows, even from a `#[soft_delete]` table.
+    fn force_delete<T>(
+        &self,
+        table: &str,
+        behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<u64>>>
+    where
+        T: TableSchema;
+
+    /// Deletes every row of the table on the IC DBMS Canister, returning the
+    /// number of deleted rows.
+    ///
+    /// Fails if another table references this one.
+    fn truncate<T>(
+        &self,
+        table: &str,
+        transaction_id: Option<TransactionId>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<u64>>>
+    where
+        T: TableSchema;
+
+    /// Reads up to `len` bytes at byte `offset` of the blob `column` of the
+    /// row with primary key `pk`.
+    ///
+    /// Selects return an empty placeholder for blobs stored out of line;
+    /// their content is read in chunks with this method.
+    fn blob_read_chunk<T>(
+        &self,
+        table: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<BlobChunk>>>
+    where
+        T: TableSchema;
+
+    /// Writes `bytes` at byte `offset` of the blob `column` of the row with
+    /// primary key `pk`, returning the new length of the blob.
+    ///
+    /// Writing at the end of the blob appends to it, so a large blob can be
+    /// uploaded in chunks after inserting the row with an empty blob.
+    fn blob_write_chunk<T>(
+        &self,
+        table: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<u64>>>
+    where
+        T: TableSchema;
+
     /// Returns `true` when the canister's persisted schema differs from the
     /// schema compiled into its binary.
     fn has_drift(&self) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<bool>>>;
@@ -211,4 +539,36 @@ pub trait Client {
         &self,
         policy: MigrationPolicy,
     ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<()>>>;
+
+    /// Returns the storage statistics of the tables the caller can read,
+    /// sorted by table name.
+    fn stats(
+        &self,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<Vec<TableStats>>>>;
+
+    /// Returns the storage statistics of `table`.
+    fn table_stats(
+        &self,
+        table: &str,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<TableStats>>>;
+
+    /// Rewrites the records of `table` contiguously, reclaiming the space
+    /// left by deleted rows, and returns the number of pages released.
+    ///
+    /// Caller must be a controller of the canister.
+    fn defragment_table(
+        &self,
+        table: &str,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<u64>>>;
+
+    /// Moves the records off the last record pages of `table`, releasing at
+    /// most `max_pages` pages to be reused by any table. Call again until the
+    /// report is `complete`.
+    ///
+    /// Caller must be a controller of the canister.
+    fn compact_table(
+        &self,
+        table: &str,
+        max_pages: u64,
+    ) -> impl Future<Output = IcDbmsCanisterClientResult<IcDbmsResult<CompactionReport>>>;
 }
@@ -7,9 +7,10 @@ use candid::utils::ArgumentEncoder;
 use candid::{CandidType, Decode, Principal};
 use ic_agent::Agent;
 use ic_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, DeleteBehavior, Filter, IcDbmsResult, IdentityPerms,
-    InsertRecord, MigrationOp, MigrationPolicy, Query, TablePerms, TableSchema, TransactionId,
-    UpdateRecord,
+    AggregateFunction, AggregatedRow, BlobChunk, CompactionReport, ContinuationToken,
+    DeleteBehavior, Filter, IcDbmsResult, IdentityPerms, InsertConflictBehavior, InsertRecord,
+    MigrationOp, MigrationPolicy, Query, TablePerms, TableSchema, TableStats, TransactionId,
+    TransactionInfo, UpdateRecord, UpsertResult, Value,
 };
 
 use crate::client::{Client, RawRecords};
@@ -175,6 +176,12 @@ impl Client for IcDbmsAgentClient<'_> {
         self.query("list_identities", ()).await
     }
 
+    async fn acl_list_all_principals(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<Principal>>> {
+        self.query("acl_list_all_principals", ()).await
+    }
+
     async fn my_perms(&self) -> IcDbmsCanisterClientResult<IdentityPerms> {
         self.query("my_perms", ()).await
     }
@@ -197,6 +204,12 @@ impl Client for IcDbmsAgentClient<'_> {
         self.update("rollback", (transaction_id,)).await
     }
 
+    async fn list_active_transactions(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<TransactionInfo>>> {
+        self.query("list_active_transactions", ()).await
+    }
+
     async fn select<T>(
         &self,
         table: &str,
@@ -214,6 +227,23 @@ impl Client for IcDbmsAgentClient<'_> {
         .await
     }
 
+    async fn select_one<T>(
+        &self,
+        table: &str,
+        query: Query,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Option<T::Record>>>
+    where
+        T: TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.query(
+            &crate::utils::table_method(table, "select_one"),
+            (query, transaction_id),
+        )
+        .await
+    }
+
     async fn select_raw(
         &self,
         table: &str,
@@ -240,6 +270,38 @@ impl Client for IcDbmsAgentClient<'_> {
         .await
     }
 
+    async fn count<T>(
+        &self,
+        table: &str,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.query(
+            &crate::utils::table_method(table, "count"),
+            (filter, transaction_id),
+        )
+        .await
+    }
+
+    async fn exists<T>(
+        &self,
+        table: &str,
+        filter: Filter,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>>
+    where
+        T: TableSchema,
+    {
+        self.query(
+            &crate::utils::table_method(table, "exists"),
+            (filter, transaction_id),
+        )
+        .await
+    }
+
     async fn insert<T>(
         &self,
         table: &str,
@@ -257,6 +319,76 @@ impl Client for IcDbmsAgentClient<'_> {
         .await
     }
 
+    async fn insert_many<T>(
+        &self,
+        table: &str,
+        records: Vec<T::Insert>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            &crate::utils::table_method(table, "insert_many"),
+            (records, transaction_id),
+        )
+        .await
+    }
+
+    async fn insert_with_behavior<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        behavior: InsertConflictBehavior,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            &crate::utils::table_method(table, "insert_with_behavior"),
+            (record, behavior, transaction_id),
+        )
+        .await
+    }
+
+    async fn insert_returning<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<T::Record>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.update(
+            &crate::utils::table_method(table, "insert_returning"),
+            (record, transaction_id),
+        )
+        .await
+    }
+
+    async fn upsert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<UpsertResult>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            &crate::utils::table_method(table, "upsert"),
+            (record, transaction_id),
+        )
+        .await
+    }
+
     async fn update<T>(
         &self,
         table: &str,
@@ -274,6 +406,95 @@ impl Client for IcDbmsAgentClient<'_> {
         .await
     }
 
+    async fn update_chunked<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<(u64, Option<ContinuationToken>)>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            &crate::utils::table_method(table, "update_chunked"),
+            (patch, max_rows, token, transaction_id),
+        )
+        .await
+    }
+
+    async fn bulk_update<T>(
+        &self,
+        table: &str,
+        patches: Vec<T::Update>,
+        ignore_errors: bool,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<u64>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            &crate::utils::table_method(table, "bulk_update"),
+            (patches, ignore_errors, transaction_id),
+        )
+        .await
+    }
+
+    async fn update_returning<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<T::Record>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.update(
+            &crate::utils::table_method(table, "update_returning"),
+            (patch, transaction_id),
+        )
+        .await
+    }
+
+    async fn validate_insert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.query(
+            &crate::utils::table_method(table, "validate_insert"),
+            (record, transaction_id),
+        )
+        .await
+    }
+
+    async fn validate_update<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.query(
+            &crate::utils::table_method(table, "validate_update"),
+            (patch, transaction_id),
+        )
+        .await
+    }
+
     async fn delete<T>(
         &self,
         table: &str,
@@ -291,6 +512,93 @@ impl Client for IcDbmsAgentClient<'_> {
         .await
     }
 
+    async fn delete_chunked<T>(
+        &self,
+        table: &str,
+        behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<(u64, Option<ContinuationToken>)>>
+    where
+        T: TableSchema,
+    {
+        self.update(
+            &crate::utils::table_method(table, "delete_chunked"),
+            (behaviour, filter, max_rows, token, transaction_id),
+        )
+        .await
+    }
+
+    async fn force_delete<T>(
+        &self,
+        table: &str,
+        behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.update(
+            &crate::utils::table_method(table, "force_delete"),
+            (behaviour, filter, transaction_id),
+        )
+        .await
+    }
+
+    async fn truncate<T>(
+        &self,
+        table: &str,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.update(
+            &crate::utils::table_method(table, "truncate"),
+            (transaction_id,),
+        )
+        .await
+    }
+
+    async fn blob_read_chunk<T>(
+        &self,
+        table: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<BlobChunk>>
+    where
+        T: TableSchema,
+    {
+        self.query(
+            &crate::utils::table_method(table, "blob_read_chunk"),
+            (pk, column.to_string(), offset, len),
+        )
+        .await
+    }
+
+    async fn blob_write_chunk<T>(
+        &self,
+        table: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.update(
+            &crate::utils::table_method(table, "blob_write_chunk"),
+            (pk, column.to_string(), offset, bytes),
+        )
+        .await
+    }
+
     async fn has_drift(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>> {
         self.query("has_drift", ()).await
     }
@@ -307,4 +615,27 @@ impl Client for IcDbmsAgentClient<'_> {
     ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
         self.update("migrate", (policy,)).await
     }
+
+    async fn stats(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<TableStats>>> {
+        self.query("table_stats", ()).await
+    }
+
+    async fn table_stats(
+        &self,
+        table: &str,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<TableStats>> {
+        self.query("table_stats_of", (table,)).await
+    }
+
+    async fn defragment_table(&self, table: &str) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>> {
+        self.update("defragment_table", (table,)).await
+    }
+
+    async fn compact_table(
+        &self,
+        table: &str,
+        max_pages: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<CompactionReport>> {
+        self.update("compact_table", (table, max_pages)).await
+    }
 }
@@ -1,19 +1,34 @@
+use std::cell::Cell;
 use std::time::Duration;
 
 use candid::utils::ArgumentEncoder;
 use candid::{CandidType, Principal};
-use ic_dbms_api::prelude::{IcDbmsResult, IdentityPerms, TablePerms};
+use ic_dbms_api::prelude::{
+    CompactionReport, IcDbmsResult, IdentityPerms, RequestId, TablePerms, TableStats,
+};
 
+use crate::client::retry::{self, RetryPolicy};
 use crate::client::{Client, RawRecords};
 use crate::prelude::IcDbmsCanisterClientResult;
 
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
 
+thread_local! {
+    /// Sequence number of the request IDs generated by this canister.
+    static NEXT_REQUEST_SEQ: Cell<u64> = const { Cell::new(0) };
+}
+
 /// Client to interact with an IC DBMS Canister.
+///
+/// Failed calls are retried according to its [`RetryPolicy`], which by
+/// default makes a single attempt. When retries are enabled, inserts,
+/// updates and deletes carry a request ID, so the canister doesn't apply a
+/// retried write twice.
 #[derive(Clone, Debug)]
 pub struct IcDbmsCanisterClient {
     canister_id: Principal,
     timeout: Duration,
+    retry_policy: RetryPolicy,
 }
 
 impl From<Principal> for IcDbmsCanisterClient {
@@ -21,6 +36,7 @@ impl From<Principal> for IcDbmsCanisterClient {
         Self {
             canister_id: principal,
             timeout: DEFAULT_TIMEOUT,
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
@@ -37,21 +53,77 @@ impl IcDbmsCanisterClient {
         self
     }
 
-    /// Calls a method on the IC DBMS Canister with the provided arguments and returns the result.
+    /// Sets the [`RetryPolicy`] of the calls made by this client.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Calls a method which may be safely applied more than once, such as a
+    /// query, retrying it according to the retry policy.
     async fn call<A, R>(&self, method: &str, args: &A) -> IcDbmsCanisterClientResult<R>
     where
         A: ArgumentEncoder,
         R: CandidType + for<'de> candid::Deserialize<'de>,
     {
-        let response = ic_cdk::call::Call::bounded_wait(self.canister_id, method)
-            .with_args(args)
-            .change_timeout(self.timeout.as_secs() as u32)
-            .into_future()
-            .await?;
+        self.call_with_retry(method, args, true).await
+    }
+
+    /// Calls a write method, retrying it only when the canister didn't
+    /// execute the failed attempt.
+    async fn call_write<A, R>(&self, method: &str, args: &A) -> IcDbmsCanisterClientResult<R>
+    where
+        A: ArgumentEncoder,
+        R: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.call_with_retry(method, args, false).await
+    }
+
+    /// Calls a method on the IC DBMS Canister with the provided arguments and returns the result.
+    async fn call_with_retry<A, R>(
+        &self,
+        method: &str,
+        args: &A,
+        idempotent: bool,
+    ) -> IcDbmsCanisterClientResult<R>
+    where
+        A: ArgumentEncoder,
+        R: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        let mut attempt = 1;
+        let response = loop {
+            match ic_cdk::call::Call::bounded_wait(self.canister_id, method)
+                .with_args(args)
+                .change_timeout(self.timeout.as_secs() as u32)
+                .into_future()
+                .await
+            {
+                Ok(response) => break response,
+                Err(err) if self.retry_policy.should_retry(attempt, &err, idempotent) => {
+                    attempt += 1;
+                    retry::sleep(self.retry_policy.backoff()).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        };
 
         let response: R = response.candid()?;
         Ok(response)
     }
+
+    /// Returns a new request ID when calls may be retried, so the canister
+    /// recognizes the retries of a write.
+    fn request_id(&self) -> Option<RequestId> {
+        if self.retry_policy.max_attempts() == 1 {
+            return None;
+        }
+        let seq = NEXT_REQUEST_SEQ.with(|next| {
+            let seq = next.get();
+            next.set(seq.wrapping_add(1));
+            seq
+        });
+        Some(format!("{:x}-{seq:x}", ic_cdk::api::time()))
+    }
 }
 
 impl Client for IcDbmsCanisterClient {
@@ -152,6 +224,12 @@ impl Client for IcDbmsCanisterClient {
         self.call("list_identities", &()).await
     }
 
+    async fn acl_list_all_principals(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<Principal>>> {
+        self.call("acl_list_all_principals", &()).await
+    }
+
     async fn my_perms(&self) -> IcDbmsCanisterClientResult<IdentityPerms> {
         self.call("my_perms", &()).await
     }
@@ -159,14 +237,14 @@ impl Client for IcDbmsCanisterClient {
     async fn begin_transaction(
         &self,
     ) -> IcDbmsCanisterClientResult<ic_dbms_api::prelude::TransactionId> {
-        self.call("begin_transaction", &()).await
+        self.call_write("begin_transaction", &()).await
     }
 
     async fn commit(
         &self,
         transaction_id: ic_dbms_api::prelude::TransactionId,
     ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
-        self.call("commit", &(transaction_id,)).await
+        self.call_write("commit", &(transaction_id,)).await
     }
 
     async fn rollback(
@@ -176,6 +254,12 @@ impl Client for IcDbmsCanisterClient {
         self.call("rollback", &(transaction_id,)).await
     }
 
+    async fn list_active_transactions(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<ic_dbms_api::prelude::TransactionInfo>>> {
+        self.call("list_active_transactions", &()).await
+    }
+
     async fn select<T>(
         &self,
         table: &str,
@@ -193,6 +277,23 @@ impl Client for IcDbmsCanisterClient {
         .await
     }
 
+    async fn select_one<T>(
+        &self,
+        table: &str,
+        query: ic_dbms_api::prelude::Query,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Option<T::Record>>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.call(
+            &crate::utils::table_method(table, "select_one"),
+            &(query, transaction_id),
+        )
+        .await
+    }
+
     async fn select_raw(
         &self,
         table: &str,
@@ -219,6 +320,38 @@ impl Client for IcDbmsCanisterClient {
         .await
     }
 
+    async fn count<T>(
+        &self,
+        table: &str,
+        filter: Option<ic_dbms_api::prelude::Filter>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call(
+            &crate::utils::table_method(table, "count"),
+            &(filter, transaction_id),
+        )
+        .await
+    }
+
+    async fn exists<T>(
+        &self,
+        table: &str,
+        filter: ic_dbms_api::prelude::Filter,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call(
+            &crate::utils::table_method(table, "exists"),
+            &(filter, transaction_id),
+        )
+        .await
+    }
+
     async fn insert<T>(
         &self,
         table: &str,
@@ -231,6 +364,76 @@ impl Client for IcDbmsCanisterClient {
     {
         self.call(
             &crate::utils::table_method(table, "insert"),
+            &(record, transaction_id, self.request_id()),
+        )
+        .await
+    }
+
+    async fn insert_many<T>(
+        &self,
+        table: &str,
+        records: Vec<T::Insert>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "insert_many"),
+            &(records, transaction_id),
+        )
+        .await
+    }
+
+    async fn insert_with_behavior<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        behavior: ic_dbms_api::prelude::InsertConflictBehavior,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "insert_with_behavior"),
+            &(record, behavior, transaction_id),
+        )
+        .await
+    }
+
+    async fn insert_returning<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<T::Record>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "insert_returning"),
+            &(record, transaction_id),
+        )
+        .await
+    }
+
+    async fn upsert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<ic_dbms_api::prelude::UpsertResult>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "upsert"),
             &(record, transaction_id),
         )
         .await
@@ -248,6 +451,97 @@ impl Client for IcDbmsCanisterClient {
     {
         self.call(
             &crate::utils::table_method(table, "update"),
+            &(patch, transaction_id, self.request_id()),
+        )
+        .await
+    }
+
+    async fn update_chunked<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ic_dbms_api::prelude::ContinuationToken>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<
+        IcDbmsResult<(u64, Option<ic_dbms_api::prelude::ContinuationToken>)>,
+    >
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "update_chunked"),
+            &(patch, max_rows, token, transaction_id),
+        )
+        .await
+    }
+
+    async fn bulk_update<T>(
+        &self,
+        table: &str,
+        patches: Vec<T::Update>,
+        ignore_errors: bool,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<u64>>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "bulk_update"),
+            &(patches, ignore_errors, transaction_id),
+        )
+        .await
+    }
+
+    async fn update_returning<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<T::Record>>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "update_returning"),
+            &(patch, transaction_id),
+        )
+        .await
+    }
+
+    async fn validate_insert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.call(
+            &crate::utils::table_method(table, "validate_insert"),
+            &(record, transaction_id),
+        )
+        .await
+    }
+
+    async fn validate_update<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+    {
+        self.call(
+            &crate::utils::table_method(table, "validate_update"),
             &(patch, transaction_id),
         )
         .await
@@ -265,11 +559,100 @@ impl Client for IcDbmsCanisterClient {
     {
         self.call(
             &crate::utils::table_method(table, "delete"),
+            &(behaviour, filter, transaction_id, self.request_id()),
+        )
+        .await
+    }
+
+    async fn delete_chunked<T>(
+        &self,
+        table: &str,
+        behaviour: ic_dbms_api::prelude::DeleteBehavior,
+        filter: Option<ic_dbms_api::prelude::Filter>,
+        max_rows: Option<u64>,
+        token: Option<ic_dbms_api::prelude::ContinuationToken>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<
+        IcDbmsResult<(u64, Option<ic_dbms_api::prelude::ContinuationToken>)>,
+    >
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "delete_chunked"),
+            &(behaviour, filter, max_rows, token, transaction_id),
+        )
+        .await
+    }
+
+    async fn force_delete<T>(
+        &self,
+        table: &str,
+        behaviour: ic_dbms_api::prelude::DeleteBehavior,
+        filter: Option<ic_dbms_api::prelude::Filter>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "force_delete"),
             &(behaviour, filter, transaction_id),
         )
         .await
     }
 
+    async fn truncate<T>(
+        &self,
+        table: &str,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "truncate"),
+            &(transaction_id,),
+        )
+        .await
+    }
+
+    async fn blob_read_chunk<T>(
+        &self,
+        table: &str,
+        pk: ic_dbms_api::prelude::Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<ic_dbms_api::prelude::BlobChunk>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call(
+            &crate::utils::table_method(table, "blob_read_chunk"),
+            &(pk, column.to_string(), offset, len),
+        )
+        .await
+    }
+
+    async fn blob_write_chunk<T>(
+        &self,
+        table: &str,
+        pk: ic_dbms_api::prelude::Value,
+        column: &str,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.call_write(
+            &crate::utils::table_method(table, "blob_write_chunk"),
+            &(pk, column.to_string(), offset, bytes),
+        )
+        .await
+    }
+
     async fn has_drift(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>> {
         self.call("has_drift", &()).await
     }
@@ -284,7 +667,30 @@ impl Client for IcDbmsCanisterClient {
         &self,
         policy: ic_dbms_api::prelude::MigrationPolicy,
     ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
-        self.call("migrate", &(policy,)).await
+        self.call_write("migrate", &(policy,)).await
+    }
+
+    async fn stats(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<TableStats>>> {
+        self.call("table_stats", &()).await
+    }
+
+    async fn table_stats(
+        &self,
+        table: &str,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<TableStats>> {
+        self.call("table_stats_of", &(table,)).await
+    }
+
+    async fn defragment_table(&self, table: &str) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>> {
+        self.call_write("defragment_table", &(table,)).await
+    }
+
+    async fn compact_table(
+        &self,
+        table: &str,
+        max_pages: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<CompactionReport>> {
+        self.call_write("compact_table", &(table, max_pages)).await
     }
 }
 
@@ -307,6 +713,18 @@ mod tests {
         assert_eq!(client.timeout, Duration::from_secs(600));
     }
 
+    #[test]
+    fn test_should_set_retry_policy() {
+        let principal = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+        let client = IcDbmsCanisterClient::new(principal);
+        assert_eq!(client.retry_policy, RetryPolicy::default());
+        assert!(client.request_id().is_none());
+
+        let policy = RetryPolicy::new(3).with_backoff(Duration::from_secs(1));
+        let client = client.with_retry_policy(policy);
+        assert_eq!(client.retry_policy, policy);
+    }
+
     #[test]
     fn test_from_principal() {
         let principal = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
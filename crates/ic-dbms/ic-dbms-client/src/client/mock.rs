@@ -0,0 +1,1486 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use candid::{CandidType, Principal};
+use ic_dbms_api::prelude::{
+    AggregateFunction, AggregatedRow, Blob, BlobChunk, ColumnDef, CompactionReport,
+    ContinuationToken, DbmsError, DeleteBehavior, Filter, IcDbmsResult, IdentityPerms,
+    InsertConflictBehavior, InsertRecord, JoinColumnDef, MigrationOp, MigrationPolicy,
+    OrderDirection, Query, QueryError, TablePerms, TableRecord, TableSchema, TableStats,
+    TransactionError, TransactionId, TransactionInfo, UpdateRecord, UpsertResult, Value,
+    ValuesSource,
+};
+
+use crate::client::{Client, RawRecords};
+use crate::prelude::IcDbmsCanisterClientResult;
+
+type Row = Vec<(ColumnDef, Value)>;
+type Tables = HashMap<String, Vec<Row>>;
+
+/// A call received by a [`MockClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// Name of the canister endpoint the call stands for, e.g. `"insert"`.
+    pub method: &'static str,
+    /// Table targeted by the call, if any.
+    pub table: Option<String>,
+    /// Transaction the call was made in, if any.
+    pub transaction_id: Option<TransactionId>,
+}
+
+/// In-memory IC DBMS Canister client, to unit test code built on [`Client`]
+/// without deploying a canister.
+///
+/// Records are kept as column values per table and queries run against them:
+///
+/// - filters, `ORDER BY`, `OFFSET`, `LIMIT` and column selection are applied,
+///   while joins, eager relations, `DISTINCT`, `GROUP BY` and aggregates fail
+///   with [`QueryError::InvalidQuery`];
+/// - inserts and `validate_insert` fail on a primary key conflict, but
+///   unique constraints, foreign keys, sanitizers and validators are not checked, and deletes do not
+///   cascade and always remove rows, even from `#[soft_delete]` tables;
+/// - writes are applied immediately, and rolling back a transaction restores
+///   the tables as they were when it began;
+/// - blobs are kept inline: selects return them in full, whatever their size;
+/// - ACL and migration calls succeed without any effect.
+///
+/// Every call is recorded and can be inspected with [`MockClient::calls`].
+#[derive(Debug)]
+pub struct MockClient {
+    principal: Principal,
+    tables: RefCell<Tables>,
+    transactions: RefCell<HashMap<TransactionId, Tables>>,
+    next_transaction_id: Cell<TransactionId>,
+    calls: RefCell<Vec<MockCall>>,
+}
+
+impl Default for MockClient {
+    fn default() -> Self {
+        Self::new(Principal::anonymous())
+    }
+}
+
+impl MockClient {
+    /// Creates an empty [`MockClient`] standing for the canister with the
+    /// given [`Principal`].
+    pub fn new(principal: Principal) -> Self {
+        Self {
+            principal,
+            tables: RefCell::default(),
+            transactions: RefCell::default(),
+            next_transaction_id: Cell::new(0),
+            calls: RefCell::default(),
+        }
+    }
+
+    /// Returns the calls received so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Forgets the calls received so far.
+    pub fn clear_calls(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    /// Returns the records stored in `table`, in insertion order.
+    pub fn records<T>(&self, table: &str) -> Vec<T::Record>
+    where
+        T: TableSchema,
+    {
+        self.tables
+            .borrow()
+            .get(table)
+            .map(|rows| rows.iter().cloned().map(into_record::<T>).collect())
+            .unwrap_or_default()
+    }
+
+    fn record_call(
+        &self,
+        method: &'static str,
+        table: Option<&str>,
+        transaction_id: Option<TransactionId>,
+    ) {
+        self.calls.borrow_mut().push(MockCall {
+            method,
+            table: table.map(str::to_string),
+            transaction_id,
+        });
+    }
+
+    /// Returns the rows of `table` matching `filter`.
+    fn matching_rows(&self, table: &str, filter: Option<&Filter>) -> IcDbmsResult<Vec<Row>> {
+        let tables = self.tables.borrow();
+        let rows = tables.get(table).map(Vec::as_slice).unwrap_or_default();
+        let matches = rows_matching(filter, rows)?;
+        Ok(rows
+            .iter()
+            .zip(matches)
+            .filter(|(_, matches)| *matches)
+            .map(|(row, _)| row.clone())
+            .collect())
+    }
+
+    /// Runs `query` on `table`, returning the selected columns of each row.
+    fn select_rows(&self, table: &str, query: &Query) -> IcDbmsResult<Vec<Row>> {
+        if query.has_joins()
+            || !query.eager_relations.is_empty()
+            || !query.distinct_by.is_empty()
+            || !query.group_by.is_empty()
+            || query.having.is_some()
+        {
+            return Err(unsupported("joins, eager relations, DISTINCT and GROUP BY"));
+        }
+
+        let mut rows = self.matching_rows(table, query.filter.as_ref())?;
+        // stable sorts from the last key to the first one order by every key
+        for (column, direction) in query.order_by.iter().rev() {
+            rows.sort_by(|a, b| {
+                let ordering = column_value(a, column).cmp(&column_value(b, column));
+                match direction {
+                    OrderDirection::Ascending => ordering,
+                    OrderDirection::Descending => ordering.reverse(),
+                }
+            });
+        }
+
+        let columns = query.raw_columns();
+        Ok(rows
+            .into_iter()
+            .skip(query.offset.unwrap_or_default())
+            .take(query.limit.unwrap_or(usize::MAX))
+            .map(|row| {
+                row.into_iter()
+                    .filter(|(column, _)| {
+                        query.all_selected() || columns.iter().any(|name| name == column.name)
+                    })
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Inserts `row` into `table`, resolving a primary key conflict according
+    /// to `behavior`.
+    ///
+    /// Returns `None` when the row was skipped.
+    fn insert_row<T>(
+        &self,
+        table: &str,
+        row: Row,
+        behavior: InsertConflictBehavior,
+    ) -> IcDbmsResult<Option<UpsertResult>>
+    where
+        T: TableSchema,
+    {
+        let mut tables = self.tables.borrow_mut();
+        let rows = tables.entry(table.to_string()).or_default();
+        let existing = rows
+            .iter()
+            .position(|stored| same_primary_key::<T>(stored, &row));
+        match (existing, behavior) {
+            (None, _) => {
+                rows.push(row);
+                Ok(Some(UpsertResult::Inserted))
+            }
+            (Some(_), InsertConflictBehavior::Error) => {
+                Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+            }
+            (Some(_), InsertConflictBehavior::Ignore) => Ok(None),
+            (Some(index), InsertConflictBehavior::Replace) => {
+                rows[index] = row;
+                Ok(Some(UpsertResult::Updated))
+            }
+        }
+    }
+
+    /// Applies `patch` to the matching rows of `table` and returns the
+    /// updated rows.
+    fn update_rows<T>(&self, table: &str, patch: &T::Update) -> IcDbmsResult<Vec<Row>>
+    where
+        T: TableSchema,
+    {
+        let values = patch.update_values();
+        let mut tables = self.tables.borrow_mut();
+        let Some(rows) = tables.get_mut(table) else {
+            return Ok(Vec::new());
+        };
+        let matches = rows_matching(patch.where_clause().as_ref(), rows)?;
+        let mut updated = Vec::new();
+        for (row, matches) in rows.iter_mut().zip(matches) {
+            if !matches {
+                continue;
+            }
+            for (column, value) in &values {
+                match row
+                    .iter_mut()
+                    .find(|(stored, _)| stored.name == column.name)
+                {
+                    Some((_, stored)) => *stored = value.clone(),
+                    None => row.push((*column, value.clone())),
+                }
+            }
+            updated.push(row.clone());
+        }
+        Ok(updated)
+    }
+
+    /// Removes the rows of `table` matching `filter` and returns how many
+    /// were removed.
+    /// Returns the filter restricting `filter` to the next chunk of at most
+    /// `max_rows` rows of `table` after `token`, with the token of the chunk
+    /// after it, or `None` if no row is left.
+    fn next_chunk<T>(
+        &self,
+        table: &str,
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<&ContinuationToken>,
+    ) -> IcDbmsResult<Option<(Option<Filter>, Option<ContinuationToken>)>>
+    where
+        T: TableSchema,
+    {
+        let Some(max_rows) = max_rows else {
+            return Ok(Some((ContinuationToken::resume::<T>(filter, token)?, None)));
+        };
+        if max_rows == 0 {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(
+                "max_rows must be greater than zero".to_string(),
+            )));
+        }
+
+        let query = ContinuationToken::chunk_query::<T>(filter.clone(), token, max_rows)?;
+        let keys: Vec<T::Record> = self
+            .select_rows(table, &query)?
+            .into_iter()
+            .map(into_record::<T>)
+            .collect();
+        let Some(end) = ContinuationToken::chunk_end::<T>(&keys) else {
+            return Ok(None);
+        };
+        let filter = end.chunk_filter::<T>(filter, token)?;
+        let next = (keys.len() as u64 >= max_rows).then_some(end);
+
+        Ok(Some((Some(filter), next)))
+    }
+
+    fn delete_rows(&self, table: &str, filter: Option<&Filter>) -> IcDbmsResult<u64> {
+        let mut tables = self.tables.borrow_mut();
+        let Some(rows) = tables.get_mut(table) else {
+            return Ok(0);
+        };
+        let mut matches = rows_matching(filter, rows)?.into_iter();
+        let before = rows.len();
+        rows.retain(|_| !matches.next().unwrap_or_default());
+        Ok((before - rows.len()) as u64)
+    }
+}
+
+impl Client for MockClient {
+    fn principal(&self) -> Principal {
+        self.principal
+    }
+
+    async fn grant_admin(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("grant_admin", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn revoke_admin(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("revoke_admin", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn grant_manage_acl(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("grant_manage_acl", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn revoke_manage_acl(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("revoke_manage_acl", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn grant_migrate(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("grant_migrate", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn revoke_migrate(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("revoke_migrate", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn grant_all_tables_perms(
+        &self,
+        _principal: Principal,
+        _perms: TablePerms,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("grant_all_tables_perms", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn revoke_all_tables_perms(
+        &self,
+        _principal: Principal,
+        _perms: TablePerms,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("revoke_all_tables_perms", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn grant_table_perms(
+        &self,
+        _principal: Principal,
+        table: &str,
+        _perms: TablePerms,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("grant_table_perms", Some(table), None);
+        Ok(Ok(()))
+    }
+
+    async fn revoke_table_perms(
+        &self,
+        _principal: Principal,
+        table: &str,
+        _perms: TablePerms,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("revoke_table_perms", Some(table), None);
+        Ok(Ok(()))
+    }
+
+    async fn remove_identity(
+        &self,
+        _principal: Principal,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("remove_identity", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn list_identities(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<(Principal, IdentityPerms)>>> {
+        self.record_call("list_identities", None, None);
+        Ok(Ok(Vec::new()))
+    }
+
+    async fn acl_list_all_principals(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<Principal>>> {
+        self.record_call("acl_list_all_principals", None, None);
+        Ok(Ok(Vec::new()))
+    }
+
+    async fn my_perms(&self) -> IcDbmsCanisterClientResult<IdentityPerms> {
+        self.record_call("my_perms", None, None);
+        Ok(IdentityPerms::fully_permissive())
+    }
+
+    async fn begin_transaction(&self) -> IcDbmsCanisterClientResult<TransactionId> {
+        let transaction_id = self.next_transaction_id.get();
+        self.next_transaction_id.set(transaction_id + 1);
+        self.record_call("begin_transaction", None, Some(transaction_id));
+        self.transactions
+            .borrow_mut()
+            .insert(transaction_id, self.tables.borrow().clone());
+        Ok(transaction_id)
+    }
+
+    async fn commit(
+        &self,
+        transaction_id: TransactionId,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("commit", None, Some(transaction_id));
+        let res = match self.transactions.borrow_mut().remove(&transaction_id) {
+            Some(_) => Ok(()),
+            None => Err(TransactionError::NoActiveTransaction.into()),
+        };
+        Ok(res)
+    }
+
+    async fn rollback(
+        &self,
+        transaction_id: TransactionId,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("rollback", None, Some(transaction_id));
+        let res = match self.transactions.borrow_mut().remove(&transaction_id) {
+            Some(tables) => {
+                *self.tables.borrow_mut() = tables;
+                Ok(())
+            }
+            None => Err(TransactionError::NoActiveTransaction.into()),
+        };
+        Ok(res)
+    }
+
+    async fn list_active_transactions(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<TransactionInfo>>> {
+        self.record_call("list_active_transactions", None, None);
+        let mut transactions: Vec<TransactionInfo> = self
+            .transactions
+            .borrow()
+            .keys()
+            .map(|id| TransactionInfo {
+                id: *id,
+                principal: Principal::anonymous(),
+                started_at: 0,
+                operation_count: 0,
+            })
+            .collect();
+        transactions.sort_unstable_by_key(|info| info.id);
+        Ok(Ok(transactions))
+    }
+
+    async fn select<T>(
+        &self,
+        table: &str,
+        query: Query,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<T::Record>>>
+    where
+        T: TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.record_call("select", Some(table), transaction_id);
+        Ok(self
+            .select_rows(table, &query)
+            .map(|rows| rows.into_iter().map(into_record::<T>).collect()))
+    }
+
+    async fn select_one<T>(
+        &self,
+        table: &str,
+        mut query: Query,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Option<T::Record>>>
+    where
+        T: TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.record_call("select_one", Some(table), transaction_id);
+        query.limit = Some(1);
+        Ok(self
+            .select_rows(table, &query)
+            .map(|rows| rows.into_iter().next().map(into_record::<T>)))
+    }
+
+    async fn aggregate<T>(
+        &self,
+        table: &str,
+        _query: Query,
+        _aggregates: Vec<AggregateFunction>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<AggregatedRow>>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("aggregate", Some(table), transaction_id);
+        Ok(Err(unsupported("aggregates")))
+    }
+
+    async fn count<T>(
+        &self,
+        table: &str,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("count", Some(table), transaction_id);
+        Ok(self
+            .matching_rows(table, filter.as_ref())
+            .map(|rows| rows.len() as u64))
+    }
+
+    async fn exists<T>(
+        &self,
+        table: &str,
+        filter: Filter,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("exists", Some(table), transaction_id);
+        Ok(self
+            .matching_rows(table, Some(&filter))
+            .map(|rows| !rows.is_empty()))
+    }
+
+    async fn select_raw(
+        &self,
+        table: &str,
+        query: Query,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<RawRecords>> {
+        self.record_call("select_raw", Some(table), transaction_id);
+        Ok(self.select_rows(table, &query).map(|rows| {
+            rows.into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|(column, value)| (JoinColumnDef::from(column), value))
+                        .collect()
+                })
+                .collect()
+        }))
+    }
+
+    async fn insert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.record_call("insert", Some(table), transaction_id);
+        Ok(self
+            .insert_row::<T>(table, record.into_values(), InsertConflictBehavior::Error)
+            .map(|_| ()))
+    }
+
+    async fn insert_many<T>(
+        &self,
+        table: &str,
+        records: Vec<T::Insert>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.record_call("insert_many", Some(table), transaction_id);
+        // all or nothing, like on the canister
+        let snapshot = self.tables.borrow().clone();
+        for record in records.iter().cloned() {
+            if let Err(err) =
+                self.insert_row::<T>(table, record.into_values(), InsertConflictBehavior::Error)
+            {
+                *self.tables.borrow_mut() = snapshot;
+                return Ok(Err(err));
+            }
+        }
+        Ok(Ok(records.len() as u64))
+    }
+
+    async fn insert_with_behavior<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        behavior: InsertConflictBehavior,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.record_call("insert_with_behavior", Some(table), transaction_id);
+        Ok(self
+            .insert_row::<T>(table, record.into_values(), behavior)
+            .map(|_| ()))
+    }
+
+    async fn insert_returning<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<T::Record>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.record_call("insert_returning", Some(table), transaction_id);
+        let row = record.into_values();
+        Ok(self
+            .insert_row::<T>(table, row.clone(), InsertConflictBehavior::Error)
+            .map(|_| into_record::<T>(row)))
+    }
+
+    async fn upsert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<UpsertResult>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.record_call("upsert", Some(table), transaction_id);
+        Ok(self
+            .insert_row::<T>(table, record.into_values(), InsertConflictBehavior::Replace)
+            .map(|result| result.unwrap_or(UpsertResult::Updated)))
+    }
+
+    async fn update<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.record_call("update", Some(table), transaction_id);
+        Ok(self
+            .update_rows::<T>(table, &patch)
+            .map(|rows| rows.len() as u64))
+    }
+
+    async fn update_chunked<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<(u64, Option<ContinuationToken>)>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.record_call("update_chunked", Some(table), transaction_id);
+        let (filter, next) =
+            match self.next_chunk::<T>(table, patch.where_clause(), max_rows, token.as_ref()) {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => return Ok(Ok((0, None))),
+                Err(err) => return Ok(Err(err)),
+            };
+        let patch = T::Update::from_values(&patch.update_values(), filter);
+        Ok(self
+            .update_rows::<T>(table, &patch)
+            .map(|rows| (rows.len() as u64, next)))
+    }
+
+    async fn bulk_update<T>(
+        &self,
+        table: &str,
+        patches: Vec<T::Update>,
+        ignore_errors: bool,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<u64>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.record_call("bulk_update", Some(table), transaction_id);
+        // all or nothing, like on the canister
+        let snapshot = self.tables.borrow().clone();
+        let mut counts = Vec::with_capacity(patches.len());
+        for patch in &patches {
+            match self.update_rows::<T>(table, patch) {
+                Ok(rows) => counts.push(rows.len() as u64),
+                Err(_) if ignore_errors => counts.push(0),
+                Err(err) => {
+                    *self.tables.borrow_mut() = snapshot;
+                    return Ok(Err(err));
+                }
+            }
+        }
+        Ok(Ok(counts))
+    }
+
+    async fn update_returning<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<T::Record>>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.record_call("update_returning", Some(table), transaction_id);
+        Ok(self
+            .update_rows::<T>(table, &patch)
+            .map(|rows| rows.into_iter().map(into_record::<T>).collect()))
+    }
+
+    async fn validate_insert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T> + CandidType,
+    {
+        self.record_call("validate_insert", Some(table), transaction_id);
+        let row = record.into_values();
+        let conflict = self.tables.borrow().get(table).is_some_and(|rows| {
+            rows.iter()
+                .any(|stored| same_primary_key::<T>(stored, &row))
+        });
+        if conflict {
+            return Ok(Err(DbmsError::Query(QueryError::PrimaryKeyConflict)));
+        }
+        Ok(Ok(()))
+    }
+
+    async fn validate_update<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T> + CandidType,
+    {
+        self.record_call("validate_update", Some(table), transaction_id);
+        let tables = self.tables.borrow();
+        let rows = tables.get(table).map(Vec::as_slice).unwrap_or_default();
+        Ok(rows_matching(patch.where_clause().as_ref(), rows).map(|_| ()))
+    }
+
+    async fn delete<T>(
+        &self,
+        table: &str,
+        _behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("delete", Some(table), transaction_id);
+        Ok(self.delete_rows(table, filter.as_ref()))
+    }
+
+    async fn delete_chunked<T>(
+        &self,
+        table: &str,
+        _behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<(u64, Option<ContinuationToken>)>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("delete_chunked", Some(table), transaction_id);
+        let (filter, next) = match self.next_chunk::<T>(table, filter, max_rows, token.as_ref()) {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return Ok(Ok((0, None))),
+            Err(err) => return Ok(Err(err)),
+        };
+        Ok(self
+            .delete_rows(table, filter.as_ref())
+            .map(|count| (count, next)))
+    }
+
+    async fn force_delete<T>(
+        &self,
+        table: &str,
+        _behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("force_delete", Some(table), transaction_id);
+        Ok(self.delete_rows(table, filter.as_ref()))
+    }
+
+    async fn truncate<T>(
+        &self,
+        table: &str,
+        transaction_id: Option<TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("truncate", Some(table), transaction_id);
+        Ok(self.delete_rows(table, None))
+    }
+
+    async fn blob_read_chunk<T>(
+        &self,
+        table: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<BlobChunk>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("blob_read_chunk", Some(table), None);
+        let rows = match self.matching_rows(table, Some(&Filter::eq(T::primary_key(), pk))) {
+            Ok(rows) => rows,
+            Err(err) => return Ok(Err(err)),
+        };
+        let blob = match rows.first().map(|row| stored_blob(row, column)) {
+            Some(Ok(blob)) => blob,
+            Some(Err(err)) => return Ok(Err(err)),
+            None => return Ok(Err(DbmsError::Query(QueryError::RecordNotFound))),
+        };
+        let total_len = blob.len() as u64;
+        if offset > total_len {
+            return Ok(Err(offset_past_end(offset, total_len)));
+        }
+        let end = offset.saturating_add(len).min(total_len);
+        Ok(Ok(BlobChunk {
+            total_len,
+            bytes: blob.0[offset as usize..end as usize].to_vec(),
+        }))
+    }
+
+    async fn blob_write_chunk<T>(
+        &self,
+        table: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: TableSchema,
+    {
+        self.record_call("blob_write_chunk", Some(table), None);
+        let filter = Filter::eq(T::primary_key(), pk);
+        let mut tables = self.tables.borrow_mut();
+        let rows = tables
+            .get_mut(table)
+            .map(Vec::as_mut_slice)
+            .unwrap_or_default();
+        let matches = match rows_matching(Some(&filter), rows) {
+            Ok(matches) => matches,
+            Err(err) => return Ok(Err(err)),
+        };
+        let Some(row) = rows
+            .iter_mut()
+            .zip(matches)
+            .find_map(|(row, matches)| matches.then_some(row))
+        else {
+            return Ok(Err(DbmsError::Query(QueryError::RecordNotFound)));
+        };
+        let mut blob = match stored_blob(row, column) {
+            Ok(blob) => blob,
+            Err(err) => return Ok(Err(err)),
+        };
+        if offset > blob.len() as u64 {
+            return Ok(Err(offset_past_end(offset, blob.len() as u64)));
+        }
+        let offset = offset as usize;
+        let end = offset + bytes.len();
+        if end > blob.0.len() {
+            blob.0.resize(end, 0);
+        }
+        blob.0[offset..end].copy_from_slice(&bytes);
+        let len = blob.len() as u64;
+        if let Some((_, value)) = row.iter_mut().find(|(def, _)| def.name == column) {
+            *value = Value::Blob(blob);
+        }
+        Ok(Ok(len))
+    }
+
+    async fn has_drift(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>> {
+        self.record_call("has_drift", None, None);
+        Ok(Ok(false))
+    }
+
+    async fn pending_migrations(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<MigrationOp>>> {
+        self.record_call("pending_migrations", None, None);
+        Ok(Ok(Vec::new()))
+    }
+
+    async fn migrate(
+        &self,
+        _policy: MigrationPolicy,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>> {
+        self.record_call("migrate", None, None);
+        Ok(Ok(()))
+    }
+
+    async fn stats(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<TableStats>>> {
+        self.record_call("stats", None, None);
+        let mut stats = self
+            .tables
+            .borrow()
+            .iter()
+            .map(|(table, rows)| TableStats {
+                table: table.clone(),
+                rows: rows.len() as u64,
+                pages: 0,
+                used_bytes: 0,
+                free_bytes: 0,
+                fragmented_bytes: 0,
+            })
+            .collect::<Vec<_>>();
+        stats.sort_by(|a, b| a.table.cmp(&b.table));
+        Ok(Ok(stats))
+    }
+
+    async fn table_stats(
+        &self,
+        table: &str,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<TableStats>> {
+        self.record_call("table_stats", Some(table), None);
+        let rows = self
+            .tables
+            .borrow()
+            .get(table)
+            .map_or(0, |rows| rows.len() as u64);
+        Ok(Ok(TableStats {
+            table: table.to_string(),
+            rows,
+            pages: 0,
+            used_bytes: 0,
+            free_bytes: 0,
+            fragmented_bytes: 0,
+        }))
+    }
+
+    async fn defragment_table(&self, table: &str) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>> {
+        self.record_call("defragment_table", Some(table), None);
+        // the mock keeps rows in memory, so there are no pages to release
+        Ok(Ok(0))
+    }
+
+    async fn compact_table(
+        &self,
+        table: &str,
+        _max_pages: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<CompactionReport>> {
+        self.record_call("compact_table", Some(table), None);
+        Ok(Ok(CompactionReport {
+            complete: true,
+            ..Default::default()
+        }))
+    }
+}
+
+/// Builds the record of `T` from the column values of a row.
+fn into_record<T>(row: Row) -> T::Record
+where
+    T: TableSchema,
+{
+    T::Record::from_values(vec![(ValuesSource::This, row)])
+}
+
+/// Evaluates `filter` on each row; every row matches a missing filter.
+fn rows_matching(filter: Option<&Filter>, rows: &[Row]) -> IcDbmsResult<Vec<bool>> {
+    rows.iter()
+        .map(|row| match filter {
+            Some(filter) => filter.matches(row).map_err(DbmsError::from),
+            None => Ok(true),
+        })
+        .collect()
+}
+
+/// Returns the value of `column` in `row`, if any.
+fn column_value<'a>(row: &'a [(ColumnDef, Value)], column: &str) -> Option<&'a Value> {
+    row.iter()
+        .find(|(def, _)| def.name == column)
+        .map(|(_, value)| value)
+}
+
+/// Returns whether rows `a` and `b` of table `T` have the same primary key.
+fn same_primary_key<T>(a: &[(ColumnDef, Value)], b: &[(ColumnDef, Value)]) -> bool
+where
+    T: TableSchema,
+{
+    T::primary_keys()
+        .iter()
+        .all(|column| column_value(a, column) == column_value(b, column))
+}
+
+/// Error returned for the query features the mock does not implement.
+/// Returns the blob stored in `column` of `row`.
+fn stored_blob(row: &[(ColumnDef, Value)], column: &str) -> IcDbmsResult<Blob> {
+    match column_value(row, column) {
+        Some(Value::Blob(blob)) => Ok(blob.clone()),
+        Some(_) => Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "column `{column}` is not a blob or is null"
+        )))),
+        None => Err(DbmsError::Query(QueryError::UnknownColumn(
+            column.to_string(),
+        ))),
+    }
+}
+
+fn offset_past_end(offset: u64, len: u64) -> DbmsError {
+    DbmsError::Query(QueryError::InvalidQuery(format!(
+        "offset {offset} is past the end of the blob of {len} bytes"
+    )))
+}
+
+fn unsupported(what: &str) -> DbmsError {
+    DbmsError::Query(QueryError::InvalidQuery(format!(
+        "{what} are not supported by MockClient"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use ic_dbms_api::prelude::{Text, Uint32};
+    use wasm_dbms_api::prelude::Table;
+
+    use super::*;
+
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[candid]
+    #[table = "users"]
+    pub struct User {
+        #[primary_key]
+        pub id: Uint32,
+        pub name: Text,
+    }
+
+    fn user(id: u32, name: &str) -> UserInsertRequest {
+        UserInsertRequest {
+            id: Uint32(id),
+            name: Text(name.to_string()),
+        }
+    }
+
+    async fn names(client: &MockClient, query: Query) -> Vec<String> {
+        client
+            .select::<User>("users", query, None)
+            .await
+            .unwrap()
+            .unwrap()
+            .into_iter()
+            .map(|record| record.name.unwrap().0)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_should_insert_update_and_delete_records() {
+        let client = MockClient::default();
+        for (id, name) in [(1, "alice"), (2, "bob"), (3, "carol")] {
+            client
+                .insert::<User>("users", user(id, name), None)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let patch = UserUpdateRequest {
+            name: Some(Text("robert".to_string())),
+            where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+            ..Default::default()
+        };
+        assert_eq!(
+            client
+                .update::<User>("users", patch, None)
+                .await
+                .unwrap()
+                .unwrap(),
+            1
+        );
+
+        let deleted = client
+            .delete::<User>(
+                "users",
+                DeleteBehavior::Restrict,
+                Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(deleted, 1);
+
+        assert_eq!(
+            names(&client, Query::builder().all().build()).await,
+            vec!["robert", "carol"]
+        );
+        assert_eq!(client.records::<User>("users").len(), 2);
+
+        let methods: Vec<_> = client.calls().iter().map(|call| call.method).collect();
+        assert_eq!(
+            methods,
+            vec!["insert", "insert", "insert", "update", "delete", "select"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_bulk_update_all_or_nothing() {
+        let client = MockClient::default();
+        for (id, name) in [(1, "alice"), (2, "bob")] {
+            client
+                .insert::<User>("users", user(id, name), None)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        let patch = |name: &str, filter: Filter| UserUpdateRequest {
+            name: Some(Text(name.to_string())),
+            where_clause: Some(filter),
+            ..Default::default()
+        };
+        let patches = || {
+            vec![
+                patch("robert", Filter::eq("id", Value::Uint32(Uint32(2)))),
+                patch("nobody", Filter::regex("name", "(")),
+            ]
+        };
+
+        let res = client
+            .bulk_update::<User>("users", patches(), false, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::InvalidRegex(_)))
+        ));
+        assert_eq!(names(&client, Query::default()).await, vec!["alice", "bob"]);
+
+        let counts = client
+            .bulk_update::<User>("users", patches(), true, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(counts, vec![1, 0]);
+        assert_eq!(
+            names(&client, Query::default()).await,
+            vec!["alice", "robert"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_filter_order_and_paginate() {
+        let client = MockClient::default();
+        for (id, name) in [(1, "carol"), (2, "alice"), (3, "bob"), (4, "dave")] {
+            client
+                .insert::<User>("users", user(id, name), None)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let query = Query::builder()
+            .all()
+            .and_where(Filter::gt("id", Value::Uint32(Uint32(1))))
+            .order_by_asc("name")
+            .offset(1)
+            .limit(2)
+            .build();
+        assert_eq!(names(&client, query).await, vec!["bob", "dave"]);
+
+        let count = client
+            .count::<User>(
+                "users",
+                Some(Filter::lt("id", Value::Uint32(Uint32(3)))),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let exists = client
+            .exists::<User>(
+                "users",
+                Filter::eq("name", Value::Text(Text::from("eve"))),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!exists);
+    }
+
+    #[tokio::test]
+    async fn test_should_walk_pages_with_cursor() {
+        let client = MockClient::default();
+        for (id, name) in [(1, "alice"), (3, "carol"), (4, "dave")] {
+            client
+                .insert::<User>("users", user(id, name), None)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let query = Query::builder().all().limit(2).build();
+        let (page, cursor) = client
+            .select_page::<User>("users", query.clone(), None, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.len(), 2);
+        let cursor = cursor.unwrap();
+        assert_eq!(cursor.value, Value::Uint32(Uint32(3)));
+
+        // a row inserted before the cursor neither shifts nor repeats the next page
+        client
+            .insert::<User>("users", user(2, "bob"), None)
+            .await
+            .unwrap()
+            .unwrap();
+        let (page, cursor) = client
+            .select_page::<User>("users", query, Some(cursor), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            page.into_iter()
+                .map(|user| user.name.unwrap().0)
+                .collect::<Vec<_>>(),
+            vec!["dave".to_string()]
+        );
+        assert!(cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_delete_in_chunks() {
+        let client = MockClient::default();
+        for (id, name) in [(1, "alice"), (2, "bob"), (3, "carol")] {
+            client
+                .insert::<User>("users", user(id, name), None)
+                .await
+                .unwrap()
+                .unwrap();
+        }
+
+        let (count, token) = client
+            .delete_chunked::<User>("users", DeleteBehavior::Restrict, None, Some(2), None, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+        let token = token.unwrap();
+        assert_eq!(token.key, vec![Value::Uint32(Uint32(2))]);
+
+        let (count, token) = client
+            .delete_chunked::<User>(
+                "users",
+                DeleteBehavior::Restrict,
+                None,
+                Some(2),
+                Some(token),
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 1);
+        assert!(token.is_none());
+        assert!(
+            client
+                .select::<User>("users", Query::builder().all().build(), None)
+                .await
+                .unwrap()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_resolve_primary_key_conflicts() {
+        let client = MockClient::default();
+        client
+            .insert::<User>("users", user(1, "alice"), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let res = client
+            .insert::<User>("users", user(1, "bob"), None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+        ));
+
+        client
+            .insert_with_behavior::<User>(
+                "users",
+                user(1, "bob"),
+                InsertConflictBehavior::Ignore,
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            names(&client, Query::default()).await,
+            vec!["alice".to_string()]
+        );
+
+        let res = client
+            .upsert::<User>("users", user(1, "carol"), None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(res, UpsertResult::Updated);
+        assert_eq!(
+            names(&client, Query::default()).await,
+            vec!["carol".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_validate_without_writing() {
+        let client = MockClient::default();
+        client
+            .insert::<User>("users", user(1, "alice"), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let res = client
+            .validate_insert::<User>("users", user(1, "bob"), None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+        ));
+        client
+            .validate_insert::<User>("users", user(2, "bob"), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let patch = UserUpdateRequest {
+            name: Some(Text("alicia".to_string())),
+            where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+            ..Default::default()
+        };
+        client
+            .validate_update::<User>("users", patch, None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            names(&client, Query::default()).await,
+            vec!["alice".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_restore_tables_on_rollback() {
+        let client = MockClient::default();
+        client
+            .insert::<User>("users", user(1, "alice"), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let transaction_id = client.begin_transaction().await.unwrap();
+        client
+            .insert::<User>("users", user(2, "bob"), Some(transaction_id))
+            .await
+            .unwrap()
+            .unwrap();
+        client.rollback(transaction_id).await.unwrap().unwrap();
+        assert_eq!(
+            names(&client, Query::default()).await,
+            vec!["alice".to_string()]
+        );
+
+        let res = client.commit(transaction_id).await.unwrap();
+        assert!(matches!(
+            res,
+            Err(DbmsError::Transaction(
+                TransactionError::NoActiveTransaction
+            ))
+        ));
+        assert_eq!(
+            client.calls()[2],
+            MockCall {
+                method: "insert",
+                table: Some("users".to_string()),
+                transaction_id: Some(transaction_id),
+            }
+        );
+    }
+
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[candid]
+    #[table = "files"]
+    pub struct File {
+        #[primary_key]
+        pub id: Uint32,
+        pub data: Blob,
+    }
+
+    #[tokio::test]
+    async fn test_should_read_and_write_blob_chunks() {
+        let client = MockClient::default();
+        client
+            .insert::<File>(
+                "files",
+                FileInsertRequest {
+                    id: Uint32(1),
+                    data: Blob(vec![1, 2, 3]),
+                },
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        let len = client
+            .blob_write_chunk::<File>("files", Uint32(1).into(), "data", 3, vec![4, 5])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(len, 5);
+        let chunk = client
+            .blob_read_chunk::<File>("files", Uint32(1).into(), "data", 2, 10)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            chunk,
+            BlobChunk {
+                total_len: 5,
+                bytes: vec![3, 4, 5],
+            }
+        );
+
+        let res = client
+            .blob_read_chunk::<File>("files", Uint32(2).into(), "data", 0, 10)
+            .await
+            .unwrap();
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::RecordNotFound))
+        ));
+        let res = client
+            .blob_write_chunk::<File>("files", Uint32(1).into(), "data", 6, vec![0])
+            .await
+            .unwrap();
+        assert!(matches!(
+            res,
+            Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+        ));
+    }
+}
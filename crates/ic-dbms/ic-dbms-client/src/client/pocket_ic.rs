@@ -1,11 +1,16 @@
 use candid::{CandidType, Decode, Encode, Principal};
-use ic_dbms_api::prelude::{IcDbmsResult, IdentityPerms, TablePerms};
+use ic_dbms_api::prelude::{CompactionReport, IcDbmsResult, IdentityPerms, TablePerms, TableStats};
 use pocket_ic::nonblocking::PocketIc;
 
 use crate::client::{Client, RawRecords};
 use crate::errors::{IcDbmsCanisterClientResult, PocketIcError};
 
 /// IC DBMS Canister client implementation for pocket-ic.
+///
+/// Methods backed by a query endpoint (`select`, `count`, `exists`,
+/// `table_stats`, ...) are sent with [`PocketIc::query_call`], which is faster
+/// and does not advance the state of the instance; the others are update
+/// calls.
 pub struct IcDbmsPocketIcClient<'a> {
     caller: Principal,
     principal: Principal,
@@ -242,6 +247,18 @@ impl Client for IcDbmsPocketIcClient<'_> {
             .await
     }
 
+    async fn acl_list_all_principals(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<Principal>>> {
+        self.query(
+            self.principal,
+            self.caller,
+            "acl_list_all_principals",
+            Vec::new(),
+        )
+        .await
+    }
+
     async fn my_perms(&self) -> IcDbmsCanisterClientResult<IdentityPerms> {
         self.query(self.principal, self.caller, "my_perms", Vec::new())
             .await
@@ -280,6 +297,18 @@ impl Client for IcDbmsPocketIcClient<'_> {
         .await
     }
 
+    async fn list_active_transactions(
+        &self,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<ic_dbms_api::prelude::TransactionInfo>>> {
+        self.query(
+            self.principal,
+            self.caller,
+            "list_active_transactions",
+            Vec::new(),
+        )
+        .await
+    }
+
     async fn select<T>(
         &self,
         table: &str,
@@ -299,6 +328,25 @@ impl Client for IcDbmsPocketIcClient<'_> {
         .await
     }
 
+    async fn select_one<T>(
+        &self,
+        table: &str,
+        query: ic_dbms_api::prelude::Query,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Option<T::Record>>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.query(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "select_one"),
+            Encode!(&query, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
     async fn select_raw(
         &self,
         table: &str,
@@ -333,6 +381,42 @@ impl Client for IcDbmsPocketIcClient<'_> {
         .await
     }
 
+    async fn count<T>(
+        &self,
+        table: &str,
+        filter: Option<ic_dbms_api::prelude::Filter>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.query(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "count"),
+            Encode!(&filter, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn exists<T>(
+        &self,
+        table: &str,
+        filter: ic_dbms_api::prelude::Filter,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.query(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "exists"),
+            Encode!(&filter, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
     async fn insert<T>(
         &self,
         table: &str,
@@ -352,6 +436,84 @@ impl Client for IcDbmsPocketIcClient<'_> {
         .await
     }
 
+    async fn insert_many<T>(
+        &self,
+        table: &str,
+        records: Vec<T::Insert>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "insert_many"),
+            Encode!(&records, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn insert_with_behavior<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        behavior: ic_dbms_api::prelude::InsertConflictBehavior,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "insert_with_behavior"),
+            Encode!(&record, &behavior, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn insert_returning<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<T::Record>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "insert_returning"),
+            Encode!(&record, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn upsert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<ic_dbms_api::prelude::UpsertResult>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "upsert"),
+            Encode!(&record, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
     async fn update<T>(
         &self,
         table: &str,
@@ -371,6 +533,107 @@ impl Client for IcDbmsPocketIcClient<'_> {
         .await
     }
 
+    async fn update_chunked<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ic_dbms_api::prelude::ContinuationToken>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<
+        IcDbmsResult<(u64, Option<ic_dbms_api::prelude::ContinuationToken>)>,
+    >
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "update_chunked"),
+            Encode!(&patch, &max_rows, &token, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn bulk_update<T>(
+        &self,
+        table: &str,
+        patches: Vec<T::Update>,
+        ignore_errors: bool,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<u64>>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "bulk_update"),
+            Encode!(&patches, &ignore_errors, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn update_returning<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<T::Record>>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+        T::Record: CandidType + for<'de> candid::Deserialize<'de>,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "update_returning"),
+            Encode!(&patch, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn validate_insert<T>(
+        &self,
+        table: &str,
+        record: T::Insert,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Insert: ic_dbms_api::prelude::InsertRecord<Schema = T> + CandidType,
+    {
+        self.query(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "validate_insert"),
+            Encode!(&record, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn validate_update<T>(
+        &self,
+        table: &str,
+        patch: T::Update,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<()>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+        T::Update: ic_dbms_api::prelude::UpdateRecord<Schema = T> + CandidType,
+    {
+        self.query(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "validate_update"),
+            Encode!(&patch, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
     async fn delete<T>(
         &self,
         table: &str,
@@ -390,6 +653,106 @@ impl Client for IcDbmsPocketIcClient<'_> {
         .await
     }
 
+    async fn delete_chunked<T>(
+        &self,
+        table: &str,
+        behaviour: ic_dbms_api::prelude::DeleteBehavior,
+        filter: Option<ic_dbms_api::prelude::Filter>,
+        max_rows: Option<u64>,
+        token: Option<ic_dbms_api::prelude::ContinuationToken>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<
+        IcDbmsResult<(u64, Option<ic_dbms_api::prelude::ContinuationToken>)>,
+    >
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "delete_chunked"),
+            Encode!(&behaviour, &filter, &max_rows, &token, &transaction_id)
+                .map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn force_delete<T>(
+        &self,
+        table: &str,
+        behaviour: ic_dbms_api::prelude::DeleteBehavior,
+        filter: Option<ic_dbms_api::prelude::Filter>,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "force_delete"),
+            Encode!(&behaviour, &filter, &transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn truncate<T>(
+        &self,
+        table: &str,
+        transaction_id: Option<ic_dbms_api::prelude::TransactionId>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "truncate"),
+            Encode!(&transaction_id).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn blob_read_chunk<T>(
+        &self,
+        table: &str,
+        pk: ic_dbms_api::prelude::Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<ic_dbms_api::prelude::BlobChunk>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.query(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "blob_read_chunk"),
+            Encode!(&pk, &column, &offset, &len).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn blob_write_chunk<T>(
+        &self,
+        table: &str,
+        pk: ic_dbms_api::prelude::Value,
+        column: &str,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>>
+    where
+        T: ic_dbms_api::prelude::TableSchema,
+    {
+        self.update(
+            self.principal,
+            self.caller,
+            &crate::utils::table_method(table, "blob_write_chunk"),
+            Encode!(&pk, &column, &offset, &bytes).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
     async fn has_drift(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<bool>> {
         self.query(self.principal, self.caller, "has_drift", Vec::new())
             .await
@@ -419,4 +782,46 @@ impl Client for IcDbmsPocketIcClient<'_> {
         )
         .await
     }
+
+    async fn stats(&self) -> IcDbmsCanisterClientResult<IcDbmsResult<Vec<TableStats>>> {
+        self.query(self.principal, self.caller, "table_stats", Vec::new())
+            .await
+    }
+
+    async fn table_stats(
+        &self,
+        table: &str,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<TableStats>> {
+        self.query(
+            self.principal,
+            self.caller,
+            "table_stats_of",
+            Encode!(&table).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn defragment_table(&self, table: &str) -> IcDbmsCanisterClientResult<IcDbmsResult<u64>> {
+        self.update(
+            self.principal,
+            self.caller,
+            "defragment_table",
+            Encode!(&table).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
+
+    async fn compact_table(
+        &self,
+        table: &str,
+        max_pages: u64,
+    ) -> IcDbmsCanisterClientResult<IcDbmsResult<CompactionReport>> {
+        self.update(
+            self.principal,
+            self.caller,
+            "compact_table",
+            Encode!(&table, &max_pages).map_err(PocketIcError::Candid)?,
+        )
+        .await
+    }
 }
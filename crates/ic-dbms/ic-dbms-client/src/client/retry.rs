@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use ic_cdk::call::{CallErrorExt as _, CallFailed};
+
+/// Retry policy of the [`IcDbmsCanisterClient`](super::IcDbmsCanisterClient)
+/// calls.
+///
+/// The default policy makes a single attempt, so no call is retried.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use ic_dbms_client::prelude::RetryPolicy;
+///
+/// let policy = RetryPolicy::new(3).with_backoff(Duration::from_secs(2));
+/// assert_eq!(policy.max_attempts(), 3);
+/// assert!(policy.transient_only());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    transient_only: bool,
+    backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy making up to `max_attempts` attempts per call, with no
+    /// backoff, retrying only on transient reject codes.
+    ///
+    /// A `max_attempts` of `0` is treated as `1`.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            transient_only: true,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    /// Sets whether only the calls failed with a transient reject code (e.g.
+    /// `SysTransient`, or the callee being busy) are retried.
+    ///
+    /// When `false`, any failed call is retried, including those which may
+    /// have been executed by the canister. Inserts, updates and deletes are
+    /// then retried with the same request ID, so they are not applied twice;
+    /// the other writes are only retried when the canister didn't execute
+    /// them.
+    pub fn with_transient_only(mut self, transient_only: bool) -> Self {
+        self.transient_only = transient_only;
+        self
+    }
+
+    /// Sets the time waited between two attempts.
+    ///
+    /// The wait is a timer, so the calling canister keeps processing other
+    /// messages meanwhile.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Returns the maximum number of attempts per call.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns whether only transient failures are retried.
+    pub fn transient_only(&self) -> bool {
+        self.transient_only
+    }
+
+    /// Returns the time waited between two attempts.
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    /// Returns whether a call is retried after its `attempt`-th attempt
+    /// failed with `err`.
+    ///
+    /// An `idempotent` call may be safely applied more than once.
+    pub(crate) fn should_retry(&self, attempt: u32, err: &CallFailed, idempotent: bool) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        if self.transient_only {
+            err.is_immediately_retryable()
+        } else {
+            idempotent || err.is_clean_reject()
+        }
+    }
+}
+
+/// Waits for `duration` on a canister timer.
+pub(crate) async fn sleep(duration: Duration) {
+    // timers only run on the IC
+    #[cfg(target_family = "wasm")]
+    if !duration.is_zero() {
+        timer::Sleep::new(duration).await;
+    }
+    #[cfg(not(target_family = "wasm"))]
+    let _ = duration;
+}
+
+#[cfg(target_family = "wasm")]
+mod timer {
+
+    use std::cell::RefCell;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::rc::Rc;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    /// Future resolved by a one-shot canister timer.
+    pub struct Sleep {
+        state: Rc<RefCell<SleepState>>,
+    }
+
+    #[derive(Default)]
+    struct SleepState {
+        elapsed: bool,
+        waker: Option<Waker>,
+    }
+
+    impl Sleep {
+        pub fn new(duration: Duration) -> Self {
+            let state = Rc::new(RefCell::new(SleepState::default()));
+            let timer_state = Rc::clone(&state);
+            ic_cdk_timers::set_timer(duration, async move {
+                let waker = {
+                    let mut state = timer_state.borrow_mut();
+                    state.elapsed = true;
+                    state.waker.take()
+                };
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            });
+
+            Self { state }
+        }
+    }
+
+    impl Future for Sleep {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let mut state = self.state.borrow_mut();
+            if state.elapsed {
+                Poll::Ready(())
+            } else {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_should_not_retry_by_default() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_attempts(), 1);
+        assert!(policy.transient_only());
+        assert_eq!(policy.backoff(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_should_build_retry_policy() {
+        let policy = RetryPolicy::new(5)
+            .with_transient_only(false)
+            .with_backoff(Duration::from_millis(500));
+        assert_eq!(policy.max_attempts(), 5);
+        assert!(!policy.transient_only());
+        assert_eq!(policy.backoff(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_should_make_at_least_one_attempt() {
+        assert_eq!(RetryPolicy::new(0).max_attempts(), 1);
+    }
+}
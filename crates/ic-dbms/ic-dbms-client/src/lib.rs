@@ -14,6 +14,7 @@
 //! - [`IcDbmsCanisterClient`](crate::prelude::IcDbmsCanisterClient): Client implementation to be used inside IC canisters.
 //! - [`IcDbmsAgentClient`](crate::prelude::IcDbmsAgentClient): Client implementation for external systems (frontend, backend services, CLI tools) using `ic-agent`. Requires the `ic-agent` feature.
 //! - [`IcDbmsPocketIcClient`](crate::prelude::IcDbmsPocketIcClient): Client implementation to be used in integration tests with the `pocket-ic` feature enabled.
+//! - [`MockClient`](crate::prelude::MockClient): In-memory client implementation to be used in unit tests without a canister. Requires the `mock` feature.
 //!
 //! The generic interface is provided by the [`Client`](crate::prelude::Client) trait.
 //!
@@ -52,11 +53,15 @@
 //!
 //! ### Types
 //!
+//! - [`ArrayOf`](crate::prelude::ArrayOf)
 //! - [`Blob`](crate::prelude::Blob)
 //! - [`Boolean`](crate::prelude::Boolean)
+//! - [`Char`](crate::prelude::Char)
 //! - [`Date`](crate::prelude::Date)
 //! - [`DateTime`](crate::prelude::DateTime)
 //! - [`Decimal`](crate::prelude::Decimal)
+//! - [`Float32`](crate::prelude::Float32)
+//! - [`Float64`](crate::prelude::Float64)
 //! - [`Int8`](crate::prelude::Int8)
 //! - [`Int16`](crate::prelude::Int16)
 //! - [`Int32`](crate::prelude::Int32)
@@ -65,11 +70,13 @@
 //! - [`Nullable`](crate::prelude::Nullable)
 //! - [`Principal`](crate::prelude::Principal)
 //! - [`Text`](crate::prelude::Text)
+//! - [`Timestamp`](crate::prelude::Timestamp)
 //! - [`Uint8`](crate::prelude::Uint8)
 //! - [`Uint16`](crate::prelude::Uint16)
 //! - [`Uint32`](crate::prelude::Uint32)
 //! - [`Uint64`](crate::prelude::Uint64)
 //! - [`Uuid`](crate::prelude::Uuid)
+//! - [`ZonedDateTime`](crate::prelude::ZonedDateTime)
 //!
 //! ### Value
 //!
@@ -1,11 +1,12 @@
 //! Prelude module for ic-dbms-client
 
 pub use ic_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, AggregatedValue, Blob, Boolean, CandidDataTypeKind,
-    ColumnDef, DataTypeKind, Date, DateTime, Decimal, DeleteBehavior, Filter, ForeignKeyDef,
-    InsertRecord, Int8, Int16, Int32, Int64, Json, JsonCmp, JsonFilter, Nullable, OrderDirection,
-    Principal, Query, QueryBuilder, Select, TableColumns, TableError, TableRecord, Text, Uint8,
-    Uint16, Uint32, Uint64, UpdateRecord, Uuid, Value, ValuesSource,
+    AggregateFunction, AggregatedRow, AggregatedValue, ArrayOf, Blob, Boolean, CandidDataTypeKind,
+    Char, ColumnDef, DataTypeKind, Date, DateTime, Decimal, DeleteBehavior, Filter, Float32,
+    Float64, ForeignKeyDef, InsertRecord, Int8, Int16, Int32, Int64, Json, JsonCmp, JsonFilter,
+    Nullable, OrderDirection, Principal, Query, QueryBuilder, Select, TableColumns, TableError,
+    TableRecord, Text, Timestamp, Uint8, Uint16, Uint32, Uint64, UpdateRecord, Uuid, Value,
+    ValuesSource, ZonedDateTime,
 };
 
 #[cfg(feature = "ic-agent")]
@@ -14,7 +15,10 @@ pub use crate::client::IcDbmsAgentClient;
 #[cfg(feature = "pocket-ic")]
 #[cfg_attr(docsrs, doc(cfg(feature = "pocket-ic")))]
 pub use crate::client::IcDbmsPocketIcClient;
-pub use crate::client::{Client, IcDbmsCanisterClient};
+pub use crate::client::{Client, IcDbmsCanisterClient, RetryPolicy};
+#[cfg(feature = "mock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+pub use crate::client::{MockCall, MockClient};
 #[cfg(feature = "ic-agent")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ic-agent")))]
 pub use crate::errors::IcAgentError;
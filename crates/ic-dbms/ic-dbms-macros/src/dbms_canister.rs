@@ -4,19 +4,20 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::format_ident;
 use syn::DeriveInput;
 
-use self::metadata::TableMetadata;
+use self::metadata::{CanisterMetadata, TableMetadata};
 
 pub fn dbms_canister(input: DeriveInput) -> syn::Result<TokenStream2> {
     let metadata = self::metadata::collect_canister_metadata(&input.attrs)?;
     let struct_ident = &input.ident;
 
-    let init_fn = impl_init(&metadata.tables);
+    let init_fn = impl_init(&metadata, struct_ident);
     let inspect_fn = impl_inspect();
     let acl_api = impl_acl_api();
     let transaction_api = impl_transaction_api(struct_ident);
     let tables_api = impl_tables_api(&metadata.tables, struct_ident);
     let select_raw_api = impl_select_raw_api(struct_ident);
     let migration_api = impl_migration_api(struct_ident);
+    let stats_api = impl_stats_api(struct_ident);
 
     Ok(quote::quote! {
         #init_fn
@@ -26,12 +27,13 @@ pub fn dbms_canister(input: DeriveInput) -> syn::Result<TokenStream2> {
         #tables_api
         #select_raw_api
         #migration_api
+        #stats_api
     })
 }
 
-fn impl_init(tables: &[TableMetadata]) -> TokenStream2 {
+fn impl_init(metadata: &CanisterMetadata, struct_ident: &syn::Ident) -> TokenStream2 {
     let mut init_tables = vec![];
-    for table in tables {
+    for table in &metadata.tables {
         let table_name = &table.table;
         let table_str = table_name.to_string();
         init_tables.push(quote::quote! {
@@ -46,6 +48,52 @@ fn impl_init(tables: &[TableMetadata]) -> TokenStream2 {
         });
     }
 
+    // policies, hooks and timers live on the heap, so both init and
+    // post_upgrade set them up
+    let policies = metadata.retention_policies.iter().map(|policy| {
+        let table = &policy.table;
+        let column = &policy.column;
+        let max_age_nanos = policy.max_age_nanos;
+        quote::quote! {
+            ::ic_dbms_api::prelude::RetentionPolicy {
+                table: #table,
+                column: #column,
+                max_age_nanos: #max_age_nanos,
+            }
+        }
+    });
+    let hooks = metadata
+        .tables
+        .iter()
+        .filter(|table| !table.hooks.is_empty())
+        .map(|table| {
+            let entity = &table.table;
+            let hook = |path: &Option<syn::Path>| match path {
+                Some(path) => quote::quote! { Some(#path) },
+                None => quote::quote! { None },
+            };
+            let on_insert = hook(&table.hooks.on_insert);
+            let on_update = hook(&table.hooks.on_update);
+            let on_delete = hook(&table.hooks.on_delete);
+            quote::quote! {
+                ::ic_dbms_canister::api::TableHooks {
+                    table: <#entity as ::ic_dbms_api::prelude::TableSchema>::table_name(),
+                    on_insert: #on_insert,
+                    on_update: #on_update,
+                    on_delete: #on_delete,
+                }
+            }
+        });
+    let mut init_heap_state = quote::quote! {
+        ::ic_dbms_canister::api::set_retention_policies(&[#(#policies),*]);
+        ::ic_dbms_canister::api::set_table_hooks(&[#(#hooks),*]);
+    };
+    if let Some(interval) = metadata.vacuum_interval {
+        init_heap_state.extend(quote::quote! {
+            ::ic_dbms_canister::api::init_vacuum(#interval, || #struct_ident);
+        });
+    }
+
     quote::quote! {
         #[::ic_cdk::init]
         fn init(args: ::ic_dbms_api::prelude::IcDbmsCanisterArgs) {
@@ -75,6 +123,35 @@ fn impl_init(tables: &[TableMetadata]) -> TokenStream2 {
                 }
             });
             #(#init_tables)*
+            ::ic_dbms_canister::api::init_transaction_expiry(
+                args.transaction_ttl,
+                args.transaction_sweep_interval,
+            );
+            ::ic_dbms_canister::api::set_max_open_transactions(args.max_open_transactions);
+            ::ic_dbms_canister::api::set_blob_limits(args.max_blob_size, args.max_blob_chunk_size);
+            ::ic_dbms_canister::api::set_max_chunk_rows(args.max_chunk_rows);
+            #init_heap_state
+        }
+
+        #[::ic_cdk::post_upgrade]
+        fn post_upgrade(args: Option<::ic_dbms_api::prelude::IcDbmsCanisterArgs>) {
+            let args = args.map(|args| args.unwrap_update()).unwrap_or_default();
+            if !args.manual_migration.unwrap_or_default() {
+                if let Err(err) = ::ic_dbms_canister::api::migrate_on_upgrade(#struct_ident) {
+                    ::ic_cdk::trap(&format!(
+                        "Failed to migrate schema during upgrade: {}",
+                        err
+                    ));
+                }
+            }
+            ::ic_dbms_canister::api::init_transaction_expiry(
+                args.transaction_ttl,
+                args.transaction_sweep_interval,
+            );
+            ::ic_dbms_canister::api::set_max_open_transactions(args.max_open_transactions);
+            ::ic_dbms_canister::api::set_blob_limits(args.max_blob_size, args.max_blob_chunk_size);
+            ::ic_dbms_canister::api::set_max_chunk_rows(args.max_chunk_rows);
+            #init_heap_state
         }
     }
 }
@@ -155,6 +232,11 @@ fn impl_acl_api() -> TokenStream2 {
             ::ic_dbms_canister::api::list_identities()
         }
 
+        #[::ic_cdk::query]
+        fn acl_list_all_principals() -> ::ic_dbms_api::prelude::IcDbmsResult<Vec<::candid::Principal>> {
+            ::ic_dbms_canister::api::acl_list_all_principals()
+        }
+
         #[::ic_cdk::query]
         fn my_perms() -> ::ic_dbms_api::prelude::IdentityPerms {
             ::ic_dbms_canister::api::my_perms()
@@ -198,6 +280,11 @@ fn impl_transaction_api(struct_ident: &syn::Ident) -> TokenStream2 {
         fn rollback(transaction_id: ::ic_dbms_api::prelude::TransactionId) -> ::ic_dbms_api::prelude::IcDbmsResult<()> {
             ::ic_dbms_canister::api::rollback(transaction_id, #struct_ident)
         }
+
+        #[::ic_cdk::query]
+        fn list_active_transactions() -> ::ic_dbms_api::prelude::IcDbmsResult<Vec<::ic_dbms_api::prelude::TransactionInfo>> {
+            ::ic_dbms_canister::api::list_active_transactions()
+        }
     }
 }
 
@@ -246,6 +333,33 @@ fn impl_migration_api(struct_ident: &syn::Ident) -> TokenStream2 {
     }
 }
 
+fn impl_stats_api(struct_ident: &syn::Ident) -> TokenStream2 {
+    quote::quote! {
+        #[::ic_cdk::query]
+        fn table_stats() -> ::ic_dbms_api::prelude::IcDbmsResult<Vec<::ic_dbms_api::prelude::TableStats>> {
+            ::ic_dbms_canister::api::table_stats()
+        }
+
+        #[::ic_cdk::query]
+        fn table_stats_of(table: String) -> ::ic_dbms_api::prelude::IcDbmsResult<::ic_dbms_api::prelude::TableStats> {
+            ::ic_dbms_canister::api::table_stats_of(table)
+        }
+
+        #[::ic_cdk::update]
+        fn defragment_table(table_name: String) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::defragment_table(table_name, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn compact_table(
+            table_name: String,
+            max_pages: u64,
+        ) -> ::ic_dbms_api::prelude::IcDbmsResult<::ic_dbms_api::prelude::CompactionReport> {
+            ::ic_dbms_canister::api::compact_table(table_name, max_pages, #struct_ident)
+        }
+    }
+}
+
 fn impl_table_api(table: &TableMetadata, struct_ident: &syn::Ident) -> TokenStream2 {
     let table_name = &table.name;
     let entity = &table.table;
@@ -254,9 +368,26 @@ fn impl_table_api(table: &TableMetadata, struct_ident: &syn::Ident) -> TokenStre
     let update = &table.update;
     let select_fn_name = format_ident!("select_{}", table_name);
     let aggregate_fn_name = format_ident!("aggregate_{}", table_name);
+    let count_fn_name = format_ident!("count_{}", table_name);
+    let select_one_fn_name = format_ident!("select_one_{}", table_name);
+    let exists_fn_name = format_ident!("exists_{}", table_name);
     let insert_fn_name = format_ident!("insert_{}", table_name);
+    let insert_many_fn_name = format_ident!("insert_many_{}", table_name);
+    let insert_with_behavior_fn_name = format_ident!("insert_with_behavior_{}", table_name);
+    let insert_returning_fn_name = format_ident!("insert_returning_{}", table_name);
+    let upsert_fn_name = format_ident!("upsert_{}", table_name);
+    let update_returning_fn_name = format_ident!("update_returning_{}", table_name);
     let update_fn_name = format_ident!("update_{}", table_name);
+    let bulk_update_fn_name = format_ident!("bulk_update_{}", table_name);
+    let update_chunked_fn_name = format_ident!("update_chunked_{}", table_name);
+    let validate_insert_fn_name = format_ident!("validate_insert_{}", table_name);
+    let validate_update_fn_name = format_ident!("validate_update_{}", table_name);
     let delete_fn_name = format_ident!("delete_{}", table_name);
+    let delete_chunked_fn_name = format_ident!("delete_chunked_{}", table_name);
+    let force_delete_fn_name = format_ident!("force_delete_{}", table_name);
+    let truncate_fn_name = format_ident!("truncate_{}", table_name);
+    let blob_read_chunk_fn_name = format_ident!("blob_read_chunk_{}", table_name);
+    let blob_write_chunk_fn_name = format_ident!("blob_write_chunk_{}", table_name);
 
     quote::quote! {
         #[::ic_cdk::query]
@@ -264,6 +395,11 @@ fn impl_table_api(table: &TableMetadata, struct_ident: &syn::Ident) -> TokenStre
             ::ic_dbms_canister::api::select::<#entity, #struct_ident>(query, transaction_id, #struct_ident)
         }
 
+        #[::ic_cdk::query]
+        fn #select_one_fn_name(query: ::ic_dbms_api::prelude::Query, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<Option<#record>> {
+            ::ic_dbms_canister::api::select_one::<#entity, #struct_ident>(query, transaction_id, #struct_ident)
+        }
+
         #[::ic_cdk::query]
         fn #aggregate_fn_name(
             query: ::ic_dbms_api::prelude::Query,
@@ -273,19 +409,114 @@ fn impl_table_api(table: &TableMetadata, struct_ident: &syn::Ident) -> TokenStre
             ::ic_dbms_canister::api::aggregate::<#entity, #struct_ident>(query, aggregates, transaction_id, #struct_ident)
         }
 
+        #[::ic_cdk::query]
+        fn #count_fn_name(filter: Option<::ic_dbms_api::prelude::Filter>, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::count::<#entity, #struct_ident>(filter, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::query]
+        fn #exists_fn_name(filter: ::ic_dbms_api::prelude::Filter, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<bool> {
+            ::ic_dbms_canister::api::exists::<#entity, #struct_ident>(filter, transaction_id, #struct_ident)
+        }
+
         #[::ic_cdk::update]
-        fn #insert_fn_name(record: #insert, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<()> {
-            ::ic_dbms_canister::api::insert::<#entity, #struct_ident>(record, transaction_id, #struct_ident)
+        fn #insert_fn_name(record: #insert, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>, request_id: Option<::ic_dbms_api::prelude::RequestId>) -> ::ic_dbms_api::prelude::IcDbmsResult<()> {
+            ::ic_dbms_canister::api::insert::<#entity, #struct_ident>(record, transaction_id, request_id, #struct_ident)
+        }
+
+        #[::ic_cdk::query]
+        fn #validate_insert_fn_name(record: #insert, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<()> {
+            ::ic_dbms_canister::api::validate_insert::<#entity, #struct_ident>(record, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::query]
+        fn #validate_update_fn_name(patch: #update, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<()> {
+            ::ic_dbms_canister::api::validate_update::<#entity, #struct_ident>(patch, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #insert_with_behavior_fn_name(
+            record: #insert,
+            behavior: ::ic_dbms_api::prelude::InsertConflictBehavior,
+            transaction_id: Option<::ic_dbms_api::prelude::TransactionId>,
+        ) -> ::ic_dbms_api::prelude::IcDbmsResult<()> {
+            ::ic_dbms_canister::api::insert_with_behavior::<#entity, #struct_ident>(record, behavior, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #insert_returning_fn_name(record: #insert, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<#record> {
+            ::ic_dbms_canister::api::insert_returning::<#entity, #struct_ident>(record, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #upsert_fn_name(record: #insert, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<::ic_dbms_api::prelude::UpsertResult> {
+            ::ic_dbms_canister::api::upsert::<#entity, #struct_ident>(record, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #insert_many_fn_name(records: Vec<#insert>, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::insert_many::<#entity, #struct_ident>(records, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #update_fn_name(patch: #update, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>, request_id: Option<::ic_dbms_api::prelude::RequestId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::update::<#entity, #struct_ident>(patch, transaction_id, request_id, #struct_ident)
         }
 
         #[::ic_cdk::update]
-        fn #update_fn_name(patch: #update, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
-            ::ic_dbms_canister::api::update::<#entity, #struct_ident>(patch, transaction_id, #struct_ident)
+        fn #bulk_update_fn_name(patches: Vec<#update>, ignore_errors: bool, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<Vec<u64>> {
+            ::ic_dbms_canister::api::bulk_update::<#entity, #struct_ident>(patches, ignore_errors, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #update_chunked_fn_name(
+            patch: #update,
+            max_rows: Option<u64>,
+            token: Option<::ic_dbms_api::prelude::ContinuationToken>,
+            transaction_id: Option<::ic_dbms_api::prelude::TransactionId>,
+        ) -> ::ic_dbms_api::prelude::IcDbmsResult<(u64, Option<::ic_dbms_api::prelude::ContinuationToken>)> {
+            ::ic_dbms_canister::api::update_chunked::<#entity, #struct_ident>(patch, max_rows, token, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #update_returning_fn_name(patch: #update, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<Vec<#record>> {
+            ::ic_dbms_canister::api::update_returning::<#entity, #struct_ident>(patch, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #delete_fn_name(delete_behavior: ::ic_dbms_api::prelude::DeleteBehavior, filter: Option<::ic_dbms_api::prelude::Filter>, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>, request_id: Option<::ic_dbms_api::prelude::RequestId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::delete::<#entity, #struct_ident>(delete_behavior, filter, transaction_id, request_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #delete_chunked_fn_name(
+            delete_behavior: ::ic_dbms_api::prelude::DeleteBehavior,
+            filter: Option<::ic_dbms_api::prelude::Filter>,
+            max_rows: Option<u64>,
+            token: Option<::ic_dbms_api::prelude::ContinuationToken>,
+            transaction_id: Option<::ic_dbms_api::prelude::TransactionId>,
+        ) -> ::ic_dbms_api::prelude::IcDbmsResult<(u64, Option<::ic_dbms_api::prelude::ContinuationToken>)> {
+            ::ic_dbms_canister::api::delete_chunked::<#entity, #struct_ident>(delete_behavior, filter, max_rows, token, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #force_delete_fn_name(delete_behavior: ::ic_dbms_api::prelude::DeleteBehavior, filter: Option<::ic_dbms_api::prelude::Filter>, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::force_delete::<#entity, #struct_ident>(delete_behavior, filter, transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::update]
+        fn #truncate_fn_name(transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::truncate::<#entity, #struct_ident>(transaction_id, #struct_ident)
+        }
+
+        #[::ic_cdk::query]
+        fn #blob_read_chunk_fn_name(pk: ::ic_dbms_api::prelude::Value, column: String, offset: u64, len: u64) -> ::ic_dbms_api::prelude::IcDbmsResult<::ic_dbms_api::prelude::BlobChunk> {
+            ::ic_dbms_canister::api::blob_read_chunk::<#entity, #struct_ident>(pk, column, offset, len, #struct_ident)
         }
 
         #[::ic_cdk::update]
-        fn #delete_fn_name(delete_behavior: ::ic_dbms_api::prelude::DeleteBehavior, filter: Option<::ic_dbms_api::prelude::Filter>, transaction_id: Option<::ic_dbms_api::prelude::TransactionId>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
-            ::ic_dbms_canister::api::delete::<#entity, #struct_ident>(delete_behavior, filter, transaction_id, #struct_ident)
+        fn #blob_write_chunk_fn_name(pk: ::ic_dbms_api::prelude::Value, column: String, offset: u64, bytes: Vec<u8>) -> ::ic_dbms_api::prelude::IcDbmsResult<u64> {
+            ::ic_dbms_canister::api::blob_write_chunk::<#entity, #struct_ident>(pk, column, offset, bytes, #struct_ident)
         }
     }
 }
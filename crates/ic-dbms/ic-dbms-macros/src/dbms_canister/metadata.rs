@@ -1,9 +1,21 @@
 use syn::Ident;
 
 const ATTRIBUTE_TABLES: &str = "tables";
+const ATTRIBUTE_RETENTION: &str = "retention";
+const ATTRIBUTE_VACUUM: &str = "vacuum";
 
 pub struct CanisterMetadata {
     pub tables: Vec<TableMetadata>,
+    pub retention_policies: Vec<RetentionMetadata>,
+    /// Interval of the vacuum timer, in nanoseconds.
+    pub vacuum_interval: Option<u64>,
+}
+
+/// A `#[retention(table = "...", column = "...", max_age_nanos = ...)]` attribute.
+pub struct RetentionMetadata {
+    pub table: String,
+    pub column: String,
+    pub max_age_nanos: u64,
 }
 
 pub struct TableMetadata {
@@ -12,12 +24,29 @@ pub struct TableMetadata {
     pub record: Ident,
     pub insert: Ident,
     pub update: Ident,
+    pub hooks: HooksMetadata,
+}
+
+/// The `on_insert`, `on_update` and `on_delete` hooks of a `#[tables]` entry.
+#[derive(Default)]
+pub struct HooksMetadata {
+    pub on_insert: Option<syn::Path>,
+    pub on_update: Option<syn::Path>,
+    pub on_delete: Option<syn::Path>,
+}
+
+impl HooksMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.on_insert.is_none() && self.on_update.is_none() && self.on_delete.is_none()
+    }
 }
 
 /// Collects canister metadata from the given attributes.
 pub fn collect_canister_metadata(attrs: &[syn::Attribute]) -> syn::Result<CanisterMetadata> {
     let mut tables = Vec::new();
     let mut names = vec![];
+    let mut retention_policies = Vec::new();
+    let mut vacuum_interval = None;
 
     for attr in attrs {
         if attr.path().is_ident(ATTRIBUTE_TABLES) {
@@ -27,26 +56,103 @@ pub fn collect_canister_metadata(attrs: &[syn::Attribute]) -> syn::Result<Canist
                     .get_ident()
                     .cloned()
                     .ok_or_else(|| meta.error("expected identifier"))?;
-                let value: syn::LitStr = meta.value()?.parse()?;
-                let value = value.value();
-
-                names.push((ident, value));
+                // `Table = "name"` or `Table(name = "name", on_insert = "hook", ...)`
+                if meta.input.peek(syn::token::Paren) {
+                    let mut name = None;
+                    let mut hooks = HooksMetadata::default();
+                    meta.parse_nested_meta(|entry| {
+                        let value: syn::LitStr = entry.value()?.parse()?;
+                        if entry.path.is_ident("name") {
+                            name = Some(value.value());
+                        } else if entry.path.is_ident("on_insert") {
+                            hooks.on_insert = Some(value.parse()?);
+                        } else if entry.path.is_ident("on_update") {
+                            hooks.on_update = Some(value.parse()?);
+                        } else if entry.path.is_ident("on_delete") {
+                            hooks.on_delete = Some(value.parse()?);
+                        } else {
+                            return Err(entry.error(
+                                "expected `name`, `on_insert`, `on_update` or `on_delete`",
+                            ));
+                        }
+                        Ok(())
+                    })?;
+                    let name = name.ok_or_else(|| meta.error("missing `name`"))?;
+                    names.push((ident, name, hooks));
+                } else {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    names.push((ident, value.value(), HooksMetadata::default()));
+                }
 
                 Ok(())
             })
             .expect("invalid syntax in #[tables]");
+        } else if attr.path().is_ident(ATTRIBUTE_RETENTION) {
+            retention_policies.push(collect_retention_metadata(attr)?);
+        } else if attr.path().is_ident(ATTRIBUTE_VACUUM) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("interval_nanos") {
+                    let value: syn::LitInt = meta.value()?.parse()?;
+                    vacuum_interval = Some(value.base10_parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `interval_nanos`"))
+                }
+            })?;
         }
     }
 
-    for (ident, name) in names {
-        tables.push(collect_table_metadata(ident, name)?);
+    for (ident, name, hooks) in names {
+        tables.push(collect_table_metadata(ident, name, hooks)?);
     }
 
-    Ok(CanisterMetadata { tables })
+    Ok(CanisterMetadata {
+        tables,
+        retention_policies,
+        vacuum_interval,
+    })
+}
+
+/// Collects a retention policy from a `#[retention(...)]` attribute.
+fn collect_retention_metadata(attr: &syn::Attribute) -> syn::Result<RetentionMetadata> {
+    let mut table = None;
+    let mut column = None;
+    let mut max_age_nanos = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("table") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            table = Some(value.value());
+        } else if meta.path.is_ident("column") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            column = Some(value.value());
+        } else if meta.path.is_ident("max_age_nanos") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            max_age_nanos = Some(value.base10_parse()?);
+        } else {
+            return Err(meta.error("expected `table`, `column` or `max_age_nanos`"));
+        }
+        Ok(())
+    })?;
+
+    match (table, column, max_age_nanos) {
+        (Some(table), Some(column), Some(max_age_nanos)) => Ok(RetentionMetadata {
+            table,
+            column,
+            max_age_nanos,
+        }),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            "`#[retention]` requires `table`, `column` and `max_age_nanos`",
+        )),
+    }
 }
 
-/// Collects metadata for a database table from its name.
-fn collect_table_metadata(table: Ident, name: String) -> syn::Result<TableMetadata> {
+/// Collects metadata for a database table from its name and hooks.
+fn collect_table_metadata(
+    table: Ident,
+    name: String,
+    hooks: HooksMetadata,
+) -> syn::Result<TableMetadata> {
     let record_ident = Ident::new(&format!("{table}Record"), table.span());
     let insert_ident = Ident::new(&format!("{table}InsertRequest"), table.span());
     let update_ident = Ident::new(&format!("{table}UpdateRequest"), table.span());
@@ -57,5 +163,6 @@ fn collect_table_metadata(table: Ident, name: String) -> syn::Result<TableMetada
         insert: insert_ident,
         update: update_ident,
         name,
+        hooks,
     })
 }
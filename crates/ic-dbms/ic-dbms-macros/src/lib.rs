@@ -13,7 +13,8 @@
 //!
 //! - `DbmsCanister`: Automatically implements the API for the ic-dbms-canister.
 //!
-//! All other derive macros (`Encode`, `Table`, `DatabaseSchema`, `CustomDataType`)
+//! All other derive macros (`Encode`, `Table`, `DatabaseSchema`, `CustomDataType`,
+//! `DbmsEnum`)
 //! are provided by `wasm-dbms-macros` and re-exported through the
 //! `ic-dbms-api` and `ic-dbms-canister` preludes.
 
@@ -32,7 +33,7 @@ mod dbms_canister;
 
 /// Automatically implements the api for the ic-dbms-canister with all the required methods to interact with the ACL and
 /// the defined tables.
-#[proc_macro_derive(DbmsCanister, attributes(tables))]
+#[proc_macro_derive(DbmsCanister, attributes(tables, retention, vacuum))]
 pub fn derive_dbms_canister(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     self::dbms_canister::dbms_canister(input)
@@ -6,9 +6,10 @@ use std::cell::RefCell;
 
 use candid::{CandidType, Deserialize, Principal};
 use ic_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, DeleteBehavior, Filter, IcDbmsResult, IdentityPerms,
-    JoinColumnDef, MigrationOp, MigrationPolicy, Query, Table, TablePerms, Text, TransactionId,
-    Uint32, Value,
+    AggregateFunction, AggregatedRow, BlobChunk, CompactionReport, DeleteBehavior, Filter,
+    IcDbmsResult, IdentityPerms, InsertConflictBehavior, JoinColumnDef, MigrationOp,
+    MigrationPolicy, Query, Table, TablePerms, Text, TransactionId, TransactionInfo, Uint32,
+    UpsertResult, Value,
 };
 use ic_dbms_client::prelude::{Client as _, IcDbmsCanisterClient};
 
@@ -187,6 +188,15 @@ pub async fn rollback(
         .map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn list_active_transactions() -> Result<IcDbmsResult<Vec<TransactionInfo>>, String> {
+    let client = new_client();
+    client
+        .list_active_transactions()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[ic_cdk::update]
 pub async fn select(
     query: Query,
@@ -199,6 +209,18 @@ pub async fn select(
         .map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn select_one(
+    query: Query,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<Option<UserRecord>>, String> {
+    let client = new_client();
+    client
+        .select_one::<User>("users", query, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[ic_cdk::update]
 pub async fn select_raw(
     query: Query,
@@ -224,6 +246,30 @@ pub async fn aggregate(
         .map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn count(
+    filter: Option<Filter>,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<u64>, String> {
+    let client = new_client();
+    client
+        .count::<User>("users", filter, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn exists(
+    filter: Filter,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<bool>, String> {
+    let client = new_client();
+    client
+        .exists::<User>("users", filter, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[ic_cdk::update]
 pub async fn insert(
     record: UserInsertRequest,
@@ -236,6 +282,55 @@ pub async fn insert(
         .map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn insert_with_behavior(
+    record: UserInsertRequest,
+    behavior: InsertConflictBehavior,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<()>, String> {
+    let client = new_client();
+    client
+        .insert_with_behavior::<User>("users", record, behavior, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn insert_returning(
+    record: UserInsertRequest,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<UserRecord>, String> {
+    let client = new_client();
+    client
+        .insert_returning::<User>("users", record, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn upsert(
+    record: UserInsertRequest,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<UpsertResult>, String> {
+    let client = new_client();
+    client
+        .upsert::<User>("users", record, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn insert_many(
+    records: Vec<UserInsertRequest>,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<u64>, String> {
+    let client = new_client();
+    client
+        .insert_many::<User>("users", records, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[ic_cdk::update]
 pub async fn update(
     patch: UserUpdateRequest,
@@ -248,6 +343,55 @@ pub async fn update(
         .map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn bulk_update(
+    patches: Vec<UserUpdateRequest>,
+    ignore_errors: bool,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<Vec<u64>>, String> {
+    let client = new_client();
+    client
+        .bulk_update::<User>("users", patches, ignore_errors, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn update_returning(
+    patch: UserUpdateRequest,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<Vec<UserRecord>>, String> {
+    let client = new_client();
+    client
+        .update_returning::<User>("users", patch, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn validate_insert(
+    record: UserInsertRequest,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<()>, String> {
+    let client = new_client();
+    client
+        .validate_insert::<User>("users", record, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn validate_update(
+    patch: UserUpdateRequest,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<()>, String> {
+    let client = new_client();
+    client
+        .validate_update::<User>("users", patch, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[ic_cdk::update]
 pub async fn delete(
     behaviour: DeleteBehavior,
@@ -261,6 +405,56 @@ pub async fn delete(
         .map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn force_delete(
+    behaviour: DeleteBehavior,
+    filter: Option<Filter>,
+    transaction_id: Option<TransactionId>,
+) -> Result<IcDbmsResult<u64>, String> {
+    let client = new_client();
+    client
+        .force_delete::<User>("users", behaviour, filter, transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn truncate(transaction_id: Option<TransactionId>) -> Result<IcDbmsResult<u64>, String> {
+    let client = new_client();
+    client
+        .truncate::<User>("users", transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn blob_read_chunk(
+    pk: Value,
+    column: String,
+    offset: u64,
+    len: u64,
+) -> Result<IcDbmsResult<BlobChunk>, String> {
+    let client = new_client();
+    client
+        .blob_read_chunk::<User>("users", pk, &column, offset, len)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn blob_write_chunk(
+    pk: Value,
+    column: String,
+    offset: u64,
+    bytes: Vec<u8>,
+) -> Result<IcDbmsResult<u64>, String> {
+    let client = new_client();
+    client
+        .blob_write_chunk::<User>("users", pk, &column, offset, bytes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[ic_cdk::update]
 pub async fn has_drift() -> Result<IcDbmsResult<bool>, String> {
     let client = new_client();
@@ -279,6 +473,27 @@ pub async fn migrate(policy: MigrationPolicy) -> Result<IcDbmsResult<()>, String
     client.migrate(policy).await.map_err(|e| e.to_string())
 }
 
+#[ic_cdk::update]
+pub async fn defragment_table(table: String) -> Result<IcDbmsResult<u64>, String> {
+    let client = new_client();
+    client
+        .defragment_table(&table)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[ic_cdk::update]
+pub async fn compact_table(
+    table: String,
+    max_pages: u64,
+) -> Result<IcDbmsResult<CompactionReport>, String> {
+    let client = new_client();
+    client
+        .compact_table(&table, max_pages)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[inline]
 fn new_client() -> IcDbmsCanisterClient {
     let canister_id = IC_DBMS_CANISTER.with_borrow(|c| *c);
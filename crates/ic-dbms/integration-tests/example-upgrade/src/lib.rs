@@ -0,0 +1,57 @@
+//! The `example` canister with a nullable `bio` column appended to `users`.
+//!
+//! Upgrading `example` to this canister exercises the automatic migration of
+//! additive schema changes; upgrading it back drops the column instead.
+
+use candid::CandidType;
+use ic_dbms_api::prelude::{Nullable, Principal, Text, Uint32};
+use ic_dbms_canister::prelude::{
+    DatabaseSchema, DbmsCanister, EmailValidator, LowerCaseSanitizer, MaxStrlenValidator, Table,
+    TrimSanitizer,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Table, CandidType, Deserialize, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "users"]
+pub struct User {
+    #[primary_key]
+    pub id: Uint32,
+    #[sanitizer(TrimSanitizer)]
+    #[validate(MaxStrlenValidator(20))]
+    pub name: Text,
+    #[sanitizer(LowerCaseSanitizer)]
+    #[validate(EmailValidator)]
+    pub email: Text,
+    pub bio: Nullable<Text>,
+}
+
+#[derive(Debug, Table, CandidType, Deserialize, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "posts"]
+pub struct Post {
+    #[primary_key]
+    pub id: Uint32,
+    pub title: Text,
+    pub content: Text,
+    #[foreign_key(entity = "User", table = "users", column = "id")]
+    pub user: Uint32,
+}
+
+#[derive(Debug, Table, CandidType, Deserialize, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "projects"]
+pub struct Project {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    #[index]
+    #[custom_type]
+    pub owner: Principal,
+}
+
+#[derive(DatabaseSchema, DbmsCanister)]
+#[tables(User = "users", Post = "posts", Project = "projects")]
+pub struct IcDbmsCanisterGenerator;
+
+ic_cdk::export_candid!();
@@ -14,6 +14,9 @@ pub use self::client::PocketIcClient;
 pub enum TestCanister {
     DbmsCanister,
     DbmsCanisterClientIntegration,
+    /// [`Self::DbmsCanister`] with a nullable `bio` column added to `users`.
+    /// Not installed by the test setup: only used as an upgrade target.
+    DbmsCanisterUpgrade,
 }
 
 impl Canister for TestCanister {
@@ -27,6 +30,10 @@ impl Canister for TestCanister {
                 env!("CARGO_MANIFEST_DIR"),
                 "/../../../../.artifact/dbms_canister_client_integration.wasm.gz"
             )),
+            TestCanister::DbmsCanisterUpgrade => std::path::Path::new(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../../../../.artifact/example_upgrade.wasm.gz"
+            )),
         }
     }
 
@@ -68,6 +75,7 @@ impl CanisterSetup for TestCanisterSetup {
         // install dbms-canister
         let init_arg = Encode!(&IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs {
             allowed_principals: Some(vec![admin(), dbms_canister_client_integration_canister]),
+            ..Default::default()
         }))
         .expect("failed to encode dbms canister init args");
         env.install_canister(TestCanister::DbmsCanister, init_arg)
@@ -173,3 +173,40 @@ async fn test_should_not_perform_transaction_not_owned(env: PocketIcTestEnv<Test
         .await;
     assert!(result.is_err());
 }
+
+#[pocket_ic_harness::test]
+async fn test_should_limit_open_transactions_per_principal(
+    env: PocketIcTestEnv<TestCanisterSetup>,
+) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+
+    // the test canister is installed with the default limit of 8
+    let mut transactions = vec![];
+    for _ in 0..8 {
+        let transaction_id = client
+            .begin_transaction()
+            .await
+            .expect("failed to begin transaction");
+        transactions.push(transaction_id);
+    }
+
+    let err = client
+        .begin_transaction()
+        .await
+        .expect_err("the 9th transaction should be rejected");
+    assert!(
+        err.to_string().contains("Too many open transactions"),
+        "unexpected error: {err}"
+    );
+
+    // committing frees a slot
+    client
+        .commit(transactions.pop().expect("no transaction"))
+        .await
+        .expect("failed to call canister")
+        .expect("failed to commit transaction");
+    client
+        .begin_transaction()
+        .await
+        .expect("failed to begin transaction after commit");
+}
@@ -0,0 +1,99 @@
+use candid::Encode;
+use ic_dbms_api::prelude::{Filter, IcDbmsResult, TableSchema, TransactionId, Uint32, Value};
+use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
+use pocket_ic_harness::PocketIcTestEnv;
+use pocket_ic_tests::table::{User, UserInsertRequest};
+use pocket_ic_tests::{PocketIcClient, TestCanisterSetup, TestEnvExt as _, admin};
+
+async fn seed_users(client: &IcDbmsPocketIcClient<'_>) {
+    for (id, name, email) in [
+        (1u32, "alice", "alice@example.com"),
+        (2u32, "bob", "bob@example.com"),
+        (3u32, "carol", "carol@example.com"),
+    ] {
+        let req = UserInsertRequest {
+            id: Uint32::from(id),
+            name: name.into(),
+            email: email.into(),
+        };
+        client
+            .insert::<User>(User::table_name(), req, None)
+            .await
+            .expect("call failed")
+            .expect("insert failed");
+    }
+}
+
+#[pocket_ic_harness::test]
+async fn test_count_via_pocket_ic_client(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&client).await;
+
+    let total = client
+        .count::<User>(User::table_name(), None, None)
+        .await
+        .expect("call failed")
+        .expect("count failed");
+    assert_eq!(total, 3);
+
+    let filtered = client
+        .count::<User>(
+            User::table_name(),
+            Some(Filter::ge("id", Value::Uint32(Uint32::from(2u32)))),
+            None,
+        )
+        .await
+        .expect("call failed")
+        .expect("count failed");
+    assert_eq!(filtered, 2);
+}
+
+#[pocket_ic_harness::test]
+async fn test_count_reflects_open_transaction(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&client).await;
+
+    let transaction_id = client
+        .begin_transaction()
+        .await
+        .expect("failed to call canister");
+
+    let req = UserInsertRequest {
+        id: Uint32::from(4u32),
+        name: "dave".into(),
+        email: "dave@example.com".into(),
+    };
+    client
+        .insert::<User>(User::table_name(), req, Some(transaction_id))
+        .await
+        .expect("call failed")
+        .expect("insert failed");
+
+    let in_tx = client
+        .count::<User>(User::table_name(), None, Some(transaction_id))
+        .await
+        .expect("call failed")
+        .expect("count failed");
+    assert_eq!(in_tx, 4);
+
+    let outside_tx = client
+        .count::<User>(User::table_name(), None, None)
+        .await
+        .expect("call failed")
+        .expect("count failed");
+    assert_eq!(outside_tx, 3);
+}
+
+#[pocket_ic_harness::test]
+async fn test_count_via_integration_canister(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let dbms = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&dbms).await;
+
+    let client = PocketIcClient::new(env.dbms_canister_client_integration(), admin(), &env.pic);
+
+    let payload = Encode!(&Option::<Filter>::None, &Option::<TransactionId>::None).expect("encode");
+    let res: Result<IcDbmsResult<u64>, String> =
+        client.update("count", payload).await.expect("call failed");
+
+    assert_eq!(res.expect("client error").expect("count failed"), 3);
+}
@@ -1,7 +1,10 @@
-use ic_dbms_api::prelude::{DeleteBehavior, Filter, Query, TableSchema, Text, Uint32, Value};
+use ic_dbms_api::prelude::{
+    DbmsError, DeleteBehavior, Filter, InsertConflictBehavior, Query, QueryError, TableSchema,
+    Text, Uint32, UpsertResult, Value,
+};
 use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
 use pocket_ic_harness::PocketIcTestEnv;
-use pocket_ic_tests::table::{User, UserInsertRequest, UserUpdateRequest};
+use pocket_ic_tests::table::{Post, PostInsertRequest, User, UserInsertRequest, UserUpdateRequest};
 use pocket_ic_tests::{TestCanisterSetup, TestEnvExt as _, admin, alice};
 
 #[pocket_ic_harness::test]
@@ -68,6 +71,56 @@ async fn test_should_delete_a_user(env: PocketIcTestEnv<TestCanisterSetup>) {
         .expect("failed to delete user");
 }
 
+#[pocket_ic_harness::test]
+async fn test_should_return_restrict_violation_on_delete(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+
+    client
+        .insert::<User>(
+            User::table_name(),
+            UserInsertRequest {
+                id: Uint32::from(7),
+                name: "Grace".into(),
+                email: "grace@example.com".into(),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user");
+    client
+        .insert::<Post>(
+            Post::table_name(),
+            PostInsertRequest {
+                id: Uint32::from(7),
+                user: Uint32::from(7),
+                title: "Hello".into(),
+                content: "Referencing Grace.".into(),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert post");
+
+    // the violation is returned as an error, not as a canister reject
+    let res = client
+        .delete::<User>(
+            User::table_name(),
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(7.into()))),
+            None,
+        )
+        .await
+        .expect("failed to call canister");
+    assert!(matches!(
+        res,
+        Err(DbmsError::Query(
+            QueryError::ForeignKeyConstraintViolation { .. }
+        ))
+    ));
+}
+
 #[pocket_ic_harness::test]
 async fn test_should_update_a_user(env: PocketIcTestEnv<TestCanisterSetup>) {
     let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
@@ -135,3 +188,257 @@ async fn test_should_not_allow_unauthorized_call(env: PocketIcTestEnv<TestCanist
         Err(ic_dbms_api::prelude::DbmsError::AccessDenied { .. })
     ));
 }
+
+#[pocket_ic_harness::test]
+async fn test_should_insert_many_users(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+
+    let records = (1u32..=100)
+        .map(|id| UserInsertRequest {
+            id: Uint32::from(id),
+            name: format!("User {id}").into(),
+            email: format!("user{id}@example.com").into(),
+        })
+        .collect();
+    let inserted = client
+        .insert_many::<User>(User::table_name(), records, None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert users");
+    assert_eq!(inserted, 100);
+
+    // a batch with a conflicting record is rejected as a whole
+    let records = vec![
+        UserInsertRequest {
+            id: Uint32::from(101),
+            name: "Frank".into(),
+            email: "frank@example.com".into(),
+        },
+        UserInsertRequest {
+            id: Uint32::from(1),
+            name: "Duplicate".into(),
+            email: "duplicate@example.com".into(),
+        },
+    ];
+    let result = client
+        .insert_many::<User>(User::table_name(), records, None)
+        .await
+        .expect("failed to call canister");
+    assert!(matches!(
+        result,
+        Err(ic_dbms_api::prelude::DbmsError::Query(
+            ic_dbms_api::prelude::QueryError::PrimaryKeyConflict
+        ))
+    ));
+
+    let users = client
+        .select::<User>(User::table_name(), Query::builder().all().build(), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query users");
+    assert_eq!(users.len(), 100);
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_bulk_update_users(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+
+    for (id, name) in [(1u32, "Ada"), (2, "Grace")] {
+        client
+            .insert::<User>(
+                User::table_name(),
+                UserInsertRequest {
+                    id: Uint32::from(id),
+                    name: name.into(),
+                    email: format!("{}@example.com", name.to_lowercase()).into(),
+                },
+                None,
+            )
+            .await
+            .expect("failed to call canister")
+            .expect("failed to insert user");
+    }
+    let patch = |id: u32, name: &str| UserUpdateRequest {
+        id: None,
+        name: Some(name.into()),
+        email: None,
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32::from(id)))),
+    };
+
+    let counts = client
+        .bulk_update::<User>(
+            User::table_name(),
+            vec![
+                patch(1, "Ada Lovelace"),
+                patch(3, "Nobody"),
+                patch(2, "Grace Hopper"),
+            ],
+            false,
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to update users");
+    assert_eq!(counts, vec![1, 0, 1]);
+
+    let users = client
+        .select::<User>(
+            User::table_name(),
+            Query::builder().all().order_by_asc("id").build(),
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query users");
+    let names: Vec<_> = users.into_iter().map(|user| user.name.unwrap()).collect();
+    assert_eq!(
+        names,
+        vec![Text::from("Ada Lovelace"), Text::from("Grace Hopper")]
+    );
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_insert_with_conflict_behavior(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    let user = |name: &str| UserInsertRequest {
+        id: Uint32::from(500),
+        name: name.into(),
+        email: "grace@example.com".into(),
+    };
+    client
+        .insert::<User>(User::table_name(), user("Grace"), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user");
+
+    let result = client
+        .insert_with_behavior::<User>(
+            User::table_name(),
+            user("Ignored"),
+            InsertConflictBehavior::Error,
+            None,
+        )
+        .await
+        .expect("failed to call canister");
+    assert!(matches!(
+        result,
+        Err(ic_dbms_api::prelude::DbmsError::Query(
+            ic_dbms_api::prelude::QueryError::PrimaryKeyConflict
+        ))
+    ));
+    client
+        .insert_with_behavior::<User>(
+            User::table_name(),
+            user("Ignored"),
+            InsertConflictBehavior::Ignore,
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("ignore should not fail");
+
+    let find_grace = Query::builder()
+        .all()
+        .and_where(Filter::eq("id", Value::Uint32(Uint32::from(500))))
+        .build();
+    let stored = client
+        .select_one::<User>(User::table_name(), find_grace.clone(), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query user")
+        .expect("user should exist");
+    assert_eq!(stored.name, Some(Text::from("Grace")));
+
+    client
+        .insert_with_behavior::<User>(
+            User::table_name(),
+            user("Grace Hopper"),
+            InsertConflictBehavior::Replace,
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("replace should not fail");
+    let stored = client
+        .select_one::<User>(User::table_name(), find_grace, None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query user")
+        .expect("user should exist");
+    assert_eq!(stored.name, Some(Text::from("Grace Hopper")));
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_upsert_user(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    let user = |name: &str| UserInsertRequest {
+        id: Uint32::from(600),
+        name: name.into(),
+        email: "ada@example.com".into(),
+    };
+
+    let result = client
+        .upsert::<User>(User::table_name(), user("Ada"), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to upsert user");
+    assert_eq!(result, UpsertResult::Inserted);
+    let result = client
+        .upsert::<User>(User::table_name(), user("Ada Lovelace"), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to upsert user");
+    assert_eq!(result, UpsertResult::Updated);
+
+    let stored = client
+        .select_one::<User>(
+            User::table_name(),
+            Query::builder()
+                .all()
+                .and_where(Filter::eq("id", Value::Uint32(Uint32::from(600))))
+                .build(),
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query user")
+        .expect("user should exist");
+    assert_eq!(stored.name, Some(Text::from("Ada Lovelace")));
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_return_inserted_and_updated_users(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+
+    let inserted = client
+        .insert_returning::<User>(
+            User::table_name(),
+            UserInsertRequest {
+                id: Uint32::from(700),
+                name: "Edsger".into(),
+                email: "edsger@example.com".into(),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user");
+    assert_eq!(inserted.id, Some(Uint32::from(700)));
+    assert_eq!(inserted.name, Some(Text::from("Edsger")));
+
+    let patch = UserUpdateRequest {
+        id: None,
+        name: Some("Edsger Dijkstra".into()),
+        email: None,
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32::from(700)))),
+    };
+    let updated = client
+        .update_returning::<User>(User::table_name(), patch, None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to update user");
+    assert_eq!(updated.len(), 1);
+    assert_eq!(updated[0].id, Some(Uint32::from(700)));
+    assert_eq!(updated[0].name, Some(Text::from("Edsger Dijkstra")));
+    assert_eq!(updated[0].email, Some(Text::from("edsger@example.com")));
+}
@@ -0,0 +1,153 @@
+use candid::Encode;
+use ic_dbms_api::prelude::{
+    Filter, IcDbmsResult, Query, TableSchema, Text, TransactionId, Uint32, Value,
+};
+use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
+use pocket_ic_harness::PocketIcTestEnv;
+use pocket_ic_tests::table::{User, UserInsertRequest, UserRecord};
+use pocket_ic_tests::{PocketIcClient, TestCanisterSetup, TestEnvExt as _, admin};
+
+async fn seed_users(client: &IcDbmsPocketIcClient<'_>) {
+    for (id, name, email) in [
+        (1u32, "alice", "alice@example.com"),
+        (2u32, "bob", "bob@example.com"),
+        (3u32, "carol", "carol@example.com"),
+    ] {
+        let req = UserInsertRequest {
+            id: Uint32::from(id),
+            name: name.into(),
+            email: email.into(),
+        };
+        client
+            .insert::<User>(User::table_name(), req, None)
+            .await
+            .expect("call failed")
+            .expect("insert failed");
+    }
+}
+
+#[pocket_ic_harness::test]
+async fn test_select_one_via_pocket_ic_client(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&client).await;
+
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::eq("name", Value::Text("bob".into())))
+        .build();
+    let user = client
+        .select_one::<User>(User::table_name(), query, None)
+        .await
+        .expect("call failed")
+        .expect("select_one failed")
+        .expect("bob should exist");
+    assert_eq!(user.id, Some(Uint32::from(2u32)));
+    assert_eq!(user.name, Some(Text::from("bob")));
+
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::eq("name", Value::Text("dave".into())))
+        .build();
+    let user = client
+        .select_one::<User>(User::table_name(), query, None)
+        .await
+        .expect("call failed")
+        .expect("select_one failed");
+    assert!(user.is_none());
+}
+
+#[pocket_ic_harness::test]
+async fn test_exists_via_pocket_ic_client(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&client).await;
+
+    let found = client
+        .exists::<User>(
+            User::table_name(),
+            Filter::eq("id", Value::Uint32(Uint32::from(3u32))),
+            None,
+        )
+        .await
+        .expect("call failed")
+        .expect("exists failed");
+    assert!(found);
+
+    let found = client
+        .exists::<User>(
+            User::table_name(),
+            Filter::eq("id", Value::Uint32(Uint32::from(42u32))),
+            None,
+        )
+        .await
+        .expect("call failed")
+        .expect("exists failed");
+    assert!(!found);
+}
+
+#[pocket_ic_harness::test]
+async fn test_exists_reflects_open_transaction(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&client).await;
+
+    let transaction_id = client
+        .begin_transaction()
+        .await
+        .expect("failed to call canister");
+
+    let req = UserInsertRequest {
+        id: Uint32::from(4u32),
+        name: "dave".into(),
+        email: "dave@example.com".into(),
+    };
+    client
+        .insert::<User>(User::table_name(), req, Some(transaction_id))
+        .await
+        .expect("call failed")
+        .expect("insert failed");
+
+    let dave = Filter::eq("id", Value::Uint32(Uint32::from(4u32)));
+    let in_tx = client
+        .exists::<User>(User::table_name(), dave.clone(), Some(transaction_id))
+        .await
+        .expect("call failed")
+        .expect("exists failed");
+    assert!(in_tx);
+
+    let outside_tx = client
+        .exists::<User>(User::table_name(), dave, None)
+        .await
+        .expect("call failed")
+        .expect("exists failed");
+    assert!(!outside_tx);
+}
+
+#[pocket_ic_harness::test]
+async fn test_select_one_and_exists_via_integration_canister(
+    env: PocketIcTestEnv<TestCanisterSetup>,
+) {
+    let dbms = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    seed_users(&dbms).await;
+
+    let client = PocketIcClient::new(env.dbms_canister_client_integration(), admin(), &env.pic);
+
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::eq("id", Value::Uint32(Uint32::from(1u32))))
+        .build();
+    let payload = Encode!(&query, &Option::<TransactionId>::None).expect("encode");
+    let res: Result<IcDbmsResult<Option<UserRecord>>, String> = client
+        .update("select_one", payload)
+        .await
+        .expect("call failed");
+    let user = res
+        .expect("client error")
+        .expect("select_one failed")
+        .expect("alice should exist");
+    assert_eq!(user.name, Some(Text::from("alice")));
+
+    let filter = Filter::eq("id", Value::Uint32(Uint32::from(1u32)));
+    let payload = Encode!(&filter, &Option::<TransactionId>::None).expect("encode");
+    let res: Result<IcDbmsResult<bool>, String> =
+        client.update("exists", payload).await.expect("call failed");
+    assert!(res.expect("client error").expect("exists failed"));
+}
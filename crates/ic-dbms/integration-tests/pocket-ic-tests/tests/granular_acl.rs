@@ -1,7 +1,8 @@
 use candid::Encode;
 use ic_dbms_api::prelude::{
-    DbmsError, DeleteBehavior, Filter, IcDbmsCanisterArgs, IcDbmsCanisterInitArgs, JoinColumnDef,
-    Query, RequiredPerm, TablePerms, TableSchema, Text, Uint32, Value,
+    DbmsError, DeleteBehavior, Filter, IcDbmsCanisterArgs, IcDbmsCanisterInitArgs,
+    InsertConflictBehavior, JoinColumnDef, Query, RequiredPerm, TablePerms, TableSchema, Text,
+    Uint32, Value,
 };
 use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
 use pocket_ic_harness::{CanisterSetup, PocketIcTestEnv};
@@ -93,6 +94,54 @@ async fn test_all_tables_grant_unions_with_per_table(env: PocketIcTestEnv<TestCa
         .expect("insert via per_table");
 }
 
+#[pocket_ic_harness::test]
+async fn test_insert_replace_requires_update_perm(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let admin_client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    admin_client
+        .grant_table_perms(bob(), "users", TablePerms::INSERT)
+        .await
+        .expect("call")
+        .expect("grant");
+
+    let bob_client = IcDbmsPocketIcClient::new(env.dbms_canister(), bob(), &env.pic);
+    bob_client
+        .insert_with_behavior::<User>(
+            User::table_name(),
+            user_record(60, "bob"),
+            InsertConflictBehavior::Ignore,
+            None,
+        )
+        .await
+        .expect("call")
+        .expect("ignore needs only INSERT");
+    let res = bob_client
+        .insert_with_behavior::<User>(
+            User::table_name(),
+            user_record(60, "robert"),
+            InsertConflictBehavior::Replace,
+            None,
+        )
+        .await
+        .expect("call");
+    assert!(matches!(res, Err(DbmsError::AccessDenied { .. })));
+
+    admin_client
+        .grant_table_perms(bob(), "users", TablePerms::UPDATE)
+        .await
+        .expect("call")
+        .expect("grant");
+    bob_client
+        .insert_with_behavior::<User>(
+            User::table_name(),
+            user_record(60, "robert"),
+            InsertConflictBehavior::Replace,
+            None,
+        )
+        .await
+        .expect("call")
+        .expect("replace with INSERT | UPDATE");
+}
+
 #[pocket_ic_harness::test]
 async fn test_migration_bot_only_has_migrate(env: PocketIcTestEnv<TestCanisterSetup>) {
     let admin_client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
@@ -302,6 +351,7 @@ impl CanisterSetup for EmptyInitCanisterSetup {
         let dbms_canister = env.canister_id(&TestCanister::DbmsCanister);
         let init_arg = Encode!(&IcDbmsCanisterArgs::Init(IcDbmsCanisterInitArgs {
             allowed_principals: None,
+            ..Default::default()
         }))
         .expect("failed to encode dbms canister init args");
         env.install_canister(TestCanister::DbmsCanister, init_arg)
@@ -333,3 +383,26 @@ async fn test_empty_init_bootstraps_deployer_as_full_admin(
             .any(|(p, perms)| *p == admin() && perms.admin && perms.manage_acl && perms.migrate)
     );
 }
+
+#[pocket_ic_harness::test]
+async fn test_acl_list_all_principals_is_controller_only(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let controller = env.pic.get_controllers(env.dbms_canister()).await[0];
+    let controller_client = IcDbmsPocketIcClient::new(env.dbms_canister(), controller, &env.pic);
+    let principals = controller_client
+        .acl_list_all_principals()
+        .await
+        .expect("call")
+        .expect("list");
+    assert!(principals.contains(&admin()));
+    assert!(principals.contains(&env.dbms_canister_client_integration()));
+
+    let bob_client = IcDbmsPocketIcClient::new(env.dbms_canister(), bob(), &env.pic);
+    let res = bob_client.acl_list_all_principals().await.expect("call");
+    assert!(matches!(
+        res,
+        Err(DbmsError::AccessDenied {
+            required: RequiredPerm::Controller,
+            ..
+        })
+    ));
+}
@@ -1,8 +1,10 @@
 mod acid;
 mod agent_client;
 mod aggregate;
+mod count;
 mod crud;
 mod custom_types;
+mod exists;
 mod granular_acl;
 mod ic_dbms_canister_client;
 mod migrations;
@@ -0,0 +1,43 @@
+use ic_dbms_api::prelude::{Query, TableSchema, Uint32};
+use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
+use pocket_ic_harness::PocketIcTestEnv;
+use pocket_ic_tests::table::{User, UserInsertRequest};
+use pocket_ic_tests::{TestCanisterSetup, TestEnvExt as _, admin};
+
+#[pocket_ic_harness::test]
+async fn test_should_walk_pages_with_cursor(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    for id in [4u32, 1, 5, 3, 2] {
+        client
+            .insert::<User>(
+                User::table_name(),
+                UserInsertRequest {
+                    id: Uint32::from(id),
+                    name: format!("user{id}").into(),
+                    email: format!("user{id}@example.com").into(),
+                },
+                None,
+            )
+            .await
+            .expect("call failed")
+            .expect("insert failed");
+    }
+
+    let mut ids = Vec::new();
+    let query = Query::builder().all().limit(2).build();
+    let mut cursor = None;
+    loop {
+        let (users, next) = client
+            .select_page::<User>(User::table_name(), query.clone(), cursor, None)
+            .await
+            .expect("call failed")
+            .expect("select_page failed");
+        ids.extend(users.iter().map(|user| user.id.unwrap()));
+        cursor = next;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(ids, (1..=5).map(Uint32::from).collect::<Vec<_>>());
+}
@@ -0,0 +1,54 @@
+use ic_dbms_api::prelude::{TableSchema, Uint32};
+use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
+use pocket_ic_harness::PocketIcTestEnv;
+use pocket_ic_tests::table::{Post, User, UserInsertRequest};
+use pocket_ic_tests::{TestCanisterSetup, TestEnvExt as _, admin};
+
+#[pocket_ic_harness::test]
+async fn test_should_report_table_stats(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    for id in 1u32..=3 {
+        client
+            .insert::<User>(
+                User::table_name(),
+                UserInsertRequest {
+                    id: Uint32::from(id),
+                    name: format!("user{id}").into(),
+                    email: format!("user{id}@example.com").into(),
+                },
+                None,
+            )
+            .await
+            .expect("call failed")
+            .expect("insert failed");
+    }
+
+    let stats = client
+        .stats()
+        .await
+        .expect("call failed")
+        .expect("stats failed");
+
+    let users = stats
+        .iter()
+        .find(|stats| stats.table == User::table_name())
+        .expect("missing users stats");
+    assert_eq!(users.rows, 3);
+    assert_eq!(users.pages, 1);
+    assert!(users.used_bytes > 0);
+
+    // empty tables are reported too
+    let posts = stats
+        .iter()
+        .find(|stats| stats.table == Post::table_name())
+        .expect("missing posts stats");
+    assert_eq!(posts.rows, 0);
+
+    let single = client
+        .table_stats(User::table_name())
+        .await
+        .expect("call failed")
+        .expect("table stats failed");
+    assert_eq!(&single, users);
+    assert_eq!(single.average_row_size(), single.used_bytes / 3);
+}
@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use candid::{CandidType, Encode};
+use ic_dbms_api::prelude::{
+    DbmsError, Filter, IcDbmsCanisterArgs, IcDbmsCanisterUpgradeArgs, Nullable, Query, Table,
+    TableSchema, Text, TransactionError, Uint32, Value,
+};
+use ic_dbms_client::prelude::{Client as _, IcDbmsPocketIcClient};
+use pocket_ic::RejectResponse;
+use pocket_ic_harness::{Canister as _, PocketIcTestEnv};
+use pocket_ic_tests::table::{User, UserInsertRequest};
+use pocket_ic_tests::{TestCanister, TestCanisterSetup, TestEnvExt as _, admin};
+use serde::Deserialize;
+
+#[pocket_ic_harness::test]
+async fn test_should_keep_schema_and_data_across_upgrade(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+
+    client
+        .insert::<User>(
+            User::table_name(),
+            UserInsertRequest {
+                id: Uint32::from(1),
+                name: "Alice".into(),
+                email: "alice@example.com".into(),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user");
+
+    let wasm = std::fs::read(TestCanister::DbmsCanister.as_path()).expect("failed to read wasm");
+    let upgrade_arg = Encode!(&IcDbmsCanisterArgs::Upgrade(
+        IcDbmsCanisterUpgradeArgs::default()
+    ))
+    .expect("failed to encode upgrade args");
+    env.pic
+        .upgrade_canister(env.dbms_canister(), wasm, upgrade_arg, None)
+        .await
+        .expect("failed to upgrade canister");
+
+    // the schema registry is reloaded from stable memory: no drift
+    let drift = client
+        .has_drift()
+        .await
+        .expect("failed to call canister")
+        .expect("failed to check drift");
+    assert!(!drift);
+
+    // the ACL survived too, so admin can still read its rows
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::eq("id", Value::Uint32(1.into())))
+        .build();
+    let users = client
+        .select::<User>(User::table_name(), query, None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query user");
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name.as_ref().unwrap(), &Text::from("Alice"));
+
+    // and the table keeps accepting writes
+    client
+        .insert::<User>(
+            User::table_name(),
+            UserInsertRequest {
+                id: Uint32::from(2),
+                name: "Bob".into(),
+                email: "bob@example.com".into(),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user after upgrade");
+}
+
+/// `users` as declared by [`TestCanister::DbmsCanisterUpgrade`].
+#[derive(Debug, Table, CandidType, Deserialize, Clone, PartialEq, Eq)]
+#[candid]
+#[table = "users"]
+pub struct UserWithBio {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    pub email: Text,
+    pub bio: Nullable<Text>,
+}
+
+async fn upgrade(
+    env: &PocketIcTestEnv<TestCanisterSetup>,
+    canister: TestCanister,
+    args: IcDbmsCanisterUpgradeArgs,
+) -> Result<(), RejectResponse> {
+    let wasm = std::fs::read(canister.as_path()).expect("failed to read wasm");
+    let upgrade_arg =
+        Encode!(&IcDbmsCanisterArgs::Upgrade(args)).expect("failed to encode upgrade args");
+    env.pic
+        .upgrade_canister(env.dbms_canister(), wasm, upgrade_arg, None)
+        .await
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_add_nullable_column_on_upgrade(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    client
+        .insert::<User>(
+            User::table_name(),
+            UserInsertRequest {
+                id: Uint32::from(1),
+                name: "Alice".into(),
+                email: "alice@example.com".into(),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user");
+
+    upgrade(
+        &env,
+        TestCanister::DbmsCanisterUpgrade,
+        IcDbmsCanisterUpgradeArgs::default(),
+    )
+    .await
+    .expect("failed to upgrade canister");
+
+    // the new column was added by post_upgrade: no drift left
+    let drift = client
+        .has_drift()
+        .await
+        .expect("failed to call canister")
+        .expect("failed to check drift");
+    assert!(!drift);
+
+    // the row stored before the upgrade reads back with a null bio
+    let users = client
+        .select::<UserWithBio>(UserWithBio::table_name(), Query::default(), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query users");
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name, Some(Text::from("Alice")));
+    assert_eq!(users[0].bio, Some(Nullable::Null));
+
+    client
+        .insert::<UserWithBio>(
+            UserWithBio::table_name(),
+            UserWithBioInsertRequest {
+                id: Uint32::from(2),
+                name: "Bob".into(),
+                email: "bob@example.com".into(),
+                bio: Nullable::Value("hello".into()),
+            },
+            None,
+        )
+        .await
+        .expect("failed to call canister")
+        .expect("failed to insert user with bio");
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_reject_non_additive_upgrade(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    upgrade(
+        &env,
+        TestCanister::DbmsCanisterUpgrade,
+        IcDbmsCanisterUpgradeArgs::default(),
+    )
+    .await
+    .expect("failed to upgrade canister");
+
+    // going back drops the `bio` column, which is never applied automatically
+    let err = upgrade(
+        &env,
+        TestCanister::DbmsCanister,
+        IcDbmsCanisterUpgradeArgs::default(),
+    )
+    .await
+    .expect_err("upgrade dropping a column should trap");
+    assert!(
+        err.reject_message
+            .contains("Schema change requires a manual migration"),
+        "unexpected reject message: {}",
+        err.reject_message
+    );
+
+    // the trapped upgrade was rolled back
+    client
+        .select::<UserWithBio>(UserWithBio::table_name(), Query::default(), None)
+        .await
+        .expect("failed to call canister")
+        .expect("failed to query users");
+
+    // opting out leaves the drift for an explicit migration
+    upgrade(
+        &env,
+        TestCanister::DbmsCanister,
+        IcDbmsCanisterUpgradeArgs {
+            manual_migration: Some(true),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("failed to upgrade canister");
+    let drift = client
+        .has_drift()
+        .await
+        .expect("failed to call canister")
+        .expect("failed to check drift");
+    assert!(drift);
+}
+
+#[pocket_ic_harness::test]
+async fn test_should_expire_transactions_after_upgrade(env: PocketIcTestEnv<TestCanisterSetup>) {
+    let client = IcDbmsPocketIcClient::new(env.dbms_canister(), admin(), &env.pic);
+    upgrade(
+        &env,
+        TestCanister::DbmsCanister,
+        IcDbmsCanisterUpgradeArgs {
+            transaction_ttl: Some(1_000_000_000),
+            transaction_sweep_interval: Some(1_000_000_000),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("failed to upgrade canister");
+
+    let transaction_id = client
+        .begin_transaction()
+        .await
+        .expect("failed to call canister");
+
+    // let the sweep armed by post_upgrade run past the TTL
+    env.pic.advance_time(Duration::from_secs(5)).await;
+    for _ in 0..3 {
+        env.pic.tick().await;
+    }
+
+    let err = client
+        .commit(transaction_id)
+        .await
+        .expect("failed to call canister")
+        .expect_err("commit of an expired transaction should fail");
+    assert!(matches!(
+        err,
+        DbmsError::Transaction(TransactionError::TransactionExpired(id)) if id == transaction_id
+    ));
+}
@@ -45,6 +45,7 @@ where
             let provider =
                 FileMemoryProvider::new(DB_FILE_PATH).expect("Failed to open database file");
             let dbms_ctx = DbmsContext::with_acl(provider);
+            dbms_ctx.set_clock(wasi_clock);
             ExampleDatabaseSchema::register_tables(&dbms_ctx).expect("Failed to register tables");
             *ctx = Some(dbms_ctx);
         }
@@ -52,6 +53,14 @@ where
     })
 }
 
+/// Current WASI wall-clock time, in nanoseconds since the Unix epoch.
+fn wasi_clock() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
 // ── Value conversion ────────────────────────────────────────────────
 
 fn wit_value_to_dbms(v: wit::Value) -> Value {
@@ -66,6 +75,8 @@ fn wit_value_to_dbms(v: wit::Value) -> Value {
         wit::Value::I16Val(n) => Value::Int16(t::Int16(n)),
         wit::Value::I32Val(n) => Value::Int32(t::Int32(n)),
         wit::Value::I64Val(n) => Value::Int64(t::Int64(n)),
+        wit::Value::F32Val(n) => Value::Float32(t::Float32(n)),
+        wit::Value::F64Val(n) => Value::Float64(t::Float64(n)),
         wit::Value::TextVal(s) => Value::Text(t::Text(s)),
         wit::Value::BlobVal(b) => Value::Blob(t::Blob(b)),
         wit::Value::DecimalVal(s) => Value::Decimal(t::Decimal(
@@ -85,11 +96,22 @@ fn wit_value_to_dbms(v: wit::Value) -> Value {
             // Same rationale as DateTime — round-trip via Text instead.
             Value::Null
         }
+        wit::Value::TimestampVal(n) => Value::Timestamp(t::Timestamp(n)),
+        wit::Value::CharVal(c) => Value::Char(t::Char(c)),
+        wit::Value::ZonedDatetimeVal(z) => {
+            Value::ZonedDateTime(t::ZonedDateTime::new(z.instant, z.offset_minutes))
+        }
         wit::Value::CustomVal(c) => Value::Custom(t::CustomValue {
             type_tag: c.type_tag,
             encoded: c.encoded,
             display: c.display,
         }),
+        wit::Value::ArrayVal(s) => serde_json::from_str::<Vec<Value>>(&s)
+            .map(Value::Array)
+            .unwrap_or(Value::Null),
+        wit::Value::JsonPatchVal(s) => serde_json::from_str::<Vec<JsonPatchOp>>(&s)
+            .map(Value::JsonPatch)
+            .unwrap_or(Value::Null),
         wit::Value::NullVal => Value::Null,
     }
 }
@@ -117,6 +139,8 @@ fn dbms_value_to_wit(v: Value) -> wit::Value {
         Value::Int16(n) => wit::Value::I16Val(n.0),
         Value::Int32(n) => wit::Value::I32Val(n.0),
         Value::Int64(n) => wit::Value::I64Val(n.0),
+        Value::Float32(n) => wit::Value::F32Val(n.0),
+        Value::Float64(n) => wit::Value::F64Val(n.0),
         Value::Text(s) => wit::Value::TextVal(s.0),
         Value::Blob(b) => wit::Value::BlobVal(b.0),
         Value::Null => wit::Value::NullVal,
@@ -125,11 +149,23 @@ fn dbms_value_to_wit(v: Value) -> wit::Value {
         Value::DateTime(dt) => wit::Value::DatetimeVal(dt.to_string()),
         Value::Json(j) => wit::Value::JsonVal(j.value().to_string()),
         Value::Uuid(u) => wit::Value::UuidVal(u.0.to_string()),
+        Value::Timestamp(ts) => wit::Value::TimestampVal(ts.0),
+        Value::Char(c) => wit::Value::CharVal(c.0),
+        Value::ZonedDateTime(z) => wit::Value::ZonedDatetimeVal(wit::ZonedDatetime {
+            instant: z.instant,
+            offset_minutes: z.offset_minutes,
+        }),
         Value::Custom(c) => wit::Value::CustomVal(wit::CustomValue {
             type_tag: c.type_tag,
             encoded: c.encoded,
             display: c.display,
         }),
+        Value::Array(values) => {
+            wit::Value::ArrayVal(serde_json::to_string(&values).unwrap_or_default())
+        }
+        Value::JsonPatch(ops) => {
+            wit::Value::JsonPatchVal(serde_json::to_string(&ops).unwrap_or_default())
+        }
     }
 }
 
@@ -153,22 +189,48 @@ fn dbms_error_to_wit(e: DbmsError) -> wit::DbmsError {
 fn query_error_to_wit(q: QueryError) -> wit::DbmsError {
     match q {
         QueryError::PrimaryKeyConflict => wit::DbmsError::PrimaryKeyConflict,
-        QueryError::UniqueConstraintViolation { field } => {
-            wit::DbmsError::UniqueConstraintViolation(field)
+        QueryError::UniqueConstraintViolation { table, column } => {
+            wit::DbmsError::UniqueConstraintViolation(format!("{table}.{column}"))
         }
         QueryError::BrokenForeignKeyReference { table, key } => {
             wit::DbmsError::BrokenForeignKeyReference(format!("{table}: {key:?}"))
         }
+        QueryError::ForeignKeyViolation {
+            table,
+            column,
+            value,
+        } => wit::DbmsError::BrokenForeignKeyReference(format!("{table}.{column}: {value:?}")),
         QueryError::ForeignKeyConstraintViolation {
             referencing_table,
             field,
         } => wit::DbmsError::ForeignKeyConstraintViolation(format!("{referencing_table}.{field}")),
+        QueryError::CascadeTooDeep { table, depth } => wit::DbmsError::ConstraintViolation(
+            format!("cascade delete from {table} exceeds depth {depth}"),
+        ),
         QueryError::UnknownColumn(c) => wit::DbmsError::UnknownColumn(c),
         QueryError::MissingNonNullableField(f) => wit::DbmsError::MissingNonNullableField(f),
         QueryError::TransactionNotFound => wit::DbmsError::TransactionNotFound,
         QueryError::InvalidQuery(msg) => wit::DbmsError::InvalidQuery(msg),
+        QueryError::InvalidRegex(msg) => wit::DbmsError::InvalidRegex(msg),
+        QueryError::TypeMismatch { expected, found } => {
+            wit::DbmsError::TypeMismatch(format!("expected {expected}, found {found}"))
+        }
         QueryError::JoinInsideTypedSelect => wit::DbmsError::JoinInsideTypedSelect,
         QueryError::AggregateClauseInSelect => wit::DbmsError::AggregateClauseInSelect,
+        QueryError::CheckConstraintViolation { field, message } => {
+            wit::DbmsError::CheckConstraintViolation(format!("{field}: {message}"))
+        }
+        QueryError::ValueTooLarge {
+            column,
+            max,
+            actual,
+        } => wit::DbmsError::ValueTooLarge(format!("{column}: {actual} > {max} bytes")),
+        QueryError::InvalidEnumValue {
+            column,
+            discriminant,
+        } => wit::DbmsError::ConstraintViolation(format!(
+            "{column}: discriminant {discriminant} has no variant"
+        )),
         QueryError::ConstraintViolation(msg) => wit::DbmsError::ConstraintViolation(msg),
         QueryError::MemoryError(m) => wit::DbmsError::MemoryError(m.to_string()),
         QueryError::TableNotFound(t) => wit::DbmsError::TableNotFound(t),
@@ -199,6 +261,11 @@ fn wit_query_to_dbms(q: wit::Query) -> Result<Query, String> {
         builder = builder.with(relation);
     }
 
+    for relation in &q.eager_columns {
+        let columns: Vec<&str> = relation.columns.iter().map(String::as_str).collect();
+        builder = builder.with_fields(&relation.relation, &columns);
+    }
+
     for join_json in &q.joins {
         let join = serde_json::from_str::<Join>(join_json)
             .map_err(|e| format!("invalid join JSON: {e}"))?;
@@ -243,6 +310,10 @@ fn wit_query_to_dbms(q: wit::Query) -> Result<Query, String> {
         builder = builder.offset(offset as usize);
     }
 
+    if q.include_deleted {
+        builder = builder.include_deleted();
+    }
+
     Ok(builder.build())
 }
 
@@ -290,6 +361,22 @@ fn wit_delete_behavior(b: wit::DeleteBehavior) -> DeleteBehavior {
     match b {
         wit::DeleteBehavior::Restrict => DeleteBehavior::Restrict,
         wit::DeleteBehavior::Cascade => DeleteBehavior::Cascade,
+        wit::DeleteBehavior::SetNull => DeleteBehavior::SetNull,
+    }
+}
+
+fn wit_insert_conflict_behavior(b: wit::InsertConflictBehavior) -> InsertConflictBehavior {
+    match b {
+        wit::InsertConflictBehavior::Error => InsertConflictBehavior::Error,
+        wit::InsertConflictBehavior::Ignore => InsertConflictBehavior::Ignore,
+        wit::InsertConflictBehavior::Replace => InsertConflictBehavior::Replace,
+    }
+}
+
+fn upsert_result_to_wit(r: UpsertResult) -> wit::UpsertResult {
+    match r {
+        UpsertResult::Inserted => wit::UpsertResult::Inserted,
+        UpsertResult::Updated => wit::UpsertResult::Updated,
     }
 }
 
@@ -378,10 +465,16 @@ fn data_type_to_wit(t: DataTypeSnapshot) -> wit::DataTypeSnapshot {
         DataTypeSnapshot::Boolean => wit::DataTypeSnapshot::Boolean,
         DataTypeSnapshot::Date => wit::DataTypeSnapshot::Date,
         DataTypeSnapshot::Datetime => wit::DataTypeSnapshot::Datetime,
+        DataTypeSnapshot::Timestamp => wit::DataTypeSnapshot::Timestamp,
+        DataTypeSnapshot::ZonedDatetime => wit::DataTypeSnapshot::ZonedDatetime,
         DataTypeSnapshot::Blob => wit::DataTypeSnapshot::Blob,
+        DataTypeSnapshot::Char => wit::DataTypeSnapshot::Char,
         DataTypeSnapshot::Text => wit::DataTypeSnapshot::Text,
         DataTypeSnapshot::Uuid => wit::DataTypeSnapshot::Uuid,
         DataTypeSnapshot::Json => wit::DataTypeSnapshot::Json,
+        DataTypeSnapshot::Array(elem) => {
+            wit::DataTypeSnapshot::Array(serde_json::to_string(&elem).unwrap_or_default())
+        }
         DataTypeSnapshot::Custom(meta) => {
             wit::DataTypeSnapshot::Custom(wit::CustomDataTypeSnapshot {
                 tag: meta.tag.clone(),
@@ -400,6 +493,7 @@ fn on_delete_to_wit(d: OnDeleteSnapshot) -> wit::OnDeleteSnapshot {
     match d {
         OnDeleteSnapshot::Restrict => wit::OnDeleteSnapshot::Restrict,
         OnDeleteSnapshot::Cascade => wit::OnDeleteSnapshot::Cascade,
+        OnDeleteSnapshot::SetNull => wit::OnDeleteSnapshot::SetNull,
     }
 }
 
@@ -539,6 +633,17 @@ impl exports::wasm_dbms::dbms::database::Guest for GuestDbms {
         })
     }
 
+    fn select_one(table: String, query: wit::Query) -> Result<Option<wit::Row>, wit::DbmsError> {
+        let mut query = wit_query_to_dbms(query).map_err(wit::DbmsError::InvalidQuery)?;
+        query.limit = Some(1);
+        with_dbms(|ctx| {
+            let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+            db.select_raw(&table, query)
+                .map(|rows| rows.into_iter().next().map(dbms_row_to_wit))
+                .map_err(dbms_error_to_wit)
+        })
+    }
+
     fn insert(
         table: String,
         values: wit::Row,
@@ -563,6 +668,101 @@ impl exports::wasm_dbms::dbms::database::Guest for GuestDbms {
         })
     }
 
+    fn insert_returning(
+        table: String,
+        values: wit::Row,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<wit::Row, wit::DbmsError> {
+        let named_values = wit_row_to_named_values(values);
+        with_dbms(|ctx| {
+            let col_values = match_column_defs(&table, named_values).map_err(dbms_error_to_wit)?;
+            let table_name = intern_str(&table);
+
+            let result = if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema.insert_returning(&db, table_name, &col_values)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema.insert_returning(&db, table_name, &col_values)
+            };
+            result.map(dbms_row_to_wit).map_err(dbms_error_to_wit)
+        })
+    }
+
+    fn insert_with_behavior(
+        table: String,
+        values: wit::Row,
+        behavior: wit::InsertConflictBehavior,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<(), wit::DbmsError> {
+        let named_values = wit_row_to_named_values(values);
+        let behavior = wit_insert_conflict_behavior(behavior);
+        with_dbms(|ctx| {
+            let col_values = match_column_defs(&table, named_values).map_err(dbms_error_to_wit)?;
+            let table_name = intern_str(&table);
+
+            if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema
+                    .insert_with_behavior(&db, table_name, &col_values, behavior)
+                    .map_err(dbms_error_to_wit)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema
+                    .insert_with_behavior(&db, table_name, &col_values, behavior)
+                    .map_err(dbms_error_to_wit)
+            }
+        })
+    }
+
+    fn upsert(
+        table: String,
+        values: wit::Row,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<wit::UpsertResult, wit::DbmsError> {
+        let named_values = wit_row_to_named_values(values);
+        with_dbms(|ctx| {
+            let col_values = match_column_defs(&table, named_values).map_err(dbms_error_to_wit)?;
+            let table_name = intern_str(&table);
+
+            let result = if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema.upsert(&db, table_name, &col_values)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema.upsert(&db, table_name, &col_values)
+            };
+            result.map(upsert_result_to_wit).map_err(dbms_error_to_wit)
+        })
+    }
+
+    fn insert_many(
+        table: String,
+        rows: Vec<wit::Row>,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<u64, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let rows = rows
+                .into_iter()
+                .map(|row| match_column_defs(&table, wit_row_to_named_values(row)))
+                .collect::<DbmsResult<Vec<_>>>()
+                .map_err(dbms_error_to_wit)?;
+            let table_name = intern_str(&table);
+
+            if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema
+                    .insert_many(&db, table_name, &rows)
+                    .map_err(dbms_error_to_wit)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema
+                    .insert_many(&db, table_name, &rows)
+                    .map_err(dbms_error_to_wit)
+            }
+        })
+    }
+
     fn aggregate(
         table: String,
         query: wit::Query,
@@ -581,6 +781,53 @@ impl exports::wasm_dbms::dbms::database::Guest for GuestDbms {
         })
     }
 
+    fn count(
+        table: String,
+        filter: Option<String>,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<u64, wit::DbmsError> {
+        let filter = parse_filter_json(filter)?;
+        with_dbms(|ctx| {
+            let table_name = intern_str(&table);
+
+            if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema
+                    .count(&db, table_name, filter)
+                    .map_err(dbms_error_to_wit)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema
+                    .count(&db, table_name, filter)
+                    .map_err(dbms_error_to_wit)
+            }
+        })
+    }
+
+    fn exists(
+        table: String,
+        filter: String,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<bool, wit::DbmsError> {
+        let filter = serde_json::from_str::<Filter>(&filter)
+            .map_err(|e| wit::DbmsError::InvalidQuery(format!("invalid filter JSON: {e}")))?;
+        with_dbms(|ctx| {
+            let table_name = intern_str(&table);
+
+            if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema
+                    .exists(&db, table_name, filter)
+                    .map_err(dbms_error_to_wit)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema
+                    .exists(&db, table_name, filter)
+                    .map_err(dbms_error_to_wit)
+            }
+        })
+    }
+
     fn update(
         table: String,
         values: wit::Row,
@@ -607,6 +854,31 @@ impl exports::wasm_dbms::dbms::database::Guest for GuestDbms {
         })
     }
 
+    fn update_returning(
+        table: String,
+        values: wit::Row,
+        filter: Option<String>,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<Vec<wit::Row>, wit::DbmsError> {
+        let filter = parse_filter_json(filter)?;
+        let named_values = wit_row_to_named_values(values);
+        with_dbms(|ctx| {
+            let col_values = match_column_defs(&table, named_values).map_err(dbms_error_to_wit)?;
+            let table_name = intern_str(&table);
+
+            let result = if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema.update_returning(&db, table_name, &col_values, filter)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema.update_returning(&db, table_name, &col_values, filter)
+            };
+            result
+                .map(|rows| rows.into_iter().map(dbms_row_to_wit).collect())
+                .map_err(dbms_error_to_wit)
+        })
+    }
+
     fn delete(
         table: String,
         behavior: wit::DeleteBehavior,
@@ -632,6 +904,116 @@ impl exports::wasm_dbms::dbms::database::Guest for GuestDbms {
         })
     }
 
+    fn force_delete(
+        table: String,
+        behavior: wit::DeleteBehavior,
+        filter: Option<String>,
+        tx: Option<wit::TransactionId>,
+    ) -> Result<u64, wit::DbmsError> {
+        let filter = parse_filter_json(filter)?;
+        let behavior = wit_delete_behavior(behavior);
+        with_dbms(|ctx| {
+            let table_name = intern_str(&table);
+
+            if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema
+                    .force_delete(&db, table_name, behavior, filter)
+                    .map_err(dbms_error_to_wit)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema
+                    .force_delete(&db, table_name, behavior, filter)
+                    .map_err(dbms_error_to_wit)
+            }
+        })
+    }
+
+    fn truncate(table: String, tx: Option<wit::TransactionId>) -> Result<u64, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let table_name = intern_str(&table);
+
+            if let Some(tx_id) = tx {
+                let db = WasmDbmsDatabase::from_transaction(ctx, ExampleDatabaseSchema, tx_id);
+                ExampleDatabaseSchema
+                    .truncate(&db, table_name)
+                    .map_err(dbms_error_to_wit)
+            } else {
+                let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+                ExampleDatabaseSchema
+                    .truncate(&db, table_name)
+                    .map_err(dbms_error_to_wit)
+            }
+        })
+    }
+
+    fn blob_read_chunk(
+        table: String,
+        pk: wit::Value,
+        column: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<wit::BlobChunk, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+            let chunk = ExampleDatabaseSchema
+                .blob_read_chunk(&db, &table, wit_value_to_dbms(pk), &column, offset, len)
+                .map_err(dbms_error_to_wit)?;
+            Ok(wit::BlobChunk {
+                total_len: chunk.total_len,
+                bytes: chunk.bytes,
+            })
+        })
+    }
+
+    fn blob_write_chunk(
+        table: String,
+        pk: wit::Value,
+        column: String,
+        offset: u64,
+        bytes: Vec<u8>,
+    ) -> Result<u64, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+            ExampleDatabaseSchema
+                .blob_write_chunk(&db, &table, wit_value_to_dbms(pk), &column, offset, &bytes)
+                .map_err(dbms_error_to_wit)
+        })
+    }
+
+    fn defragment(table: String) -> Result<u64, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+            db.defragment_table(&table).map_err(dbms_error_to_wit)
+        })
+    }
+
+    fn compact(table: String, max_pages: u64) -> Result<wit::CompactionReport, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+            let report = db
+                .compact_table(&table, max_pages)
+                .map_err(dbms_error_to_wit)?;
+            Ok(wit::CompactionReport {
+                pages_before: report.pages_before,
+                pages_after: report.pages_after,
+                bytes_reclaimed: report.bytes_reclaimed,
+                complete: report.complete,
+            })
+        })
+    }
+
+    fn vacuum() -> Result<wit::VacuumReport, wit::DbmsError> {
+        with_dbms(|ctx| {
+            let db = WasmDbmsDatabase::oneshot(ctx, ExampleDatabaseSchema);
+            let report = db.vacuum().map_err(dbms_error_to_wit)?;
+            Ok(wit::VacuumReport {
+                tables_vacuumed: report.tables_vacuumed,
+                rows_deleted: report.rows_deleted,
+            })
+        })
+    }
+
     fn begin_transaction() -> Result<wit::TransactionId, wit::DbmsError> {
         with_dbms(|ctx| Ok(ctx.begin_transaction(vec![0u8])))
     }
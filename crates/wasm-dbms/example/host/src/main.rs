@@ -66,12 +66,14 @@ fn empty_query() -> Query {
         filter: None,
         distinct_by: vec![],
         eager_relations: vec![],
+        eager_columns: vec![],
         joins: vec![],
         group_by: vec![],
         having: None,
         order_by: vec![],
         limit: None,
         offset: None,
+        include_deleted: false,
     }
 }
 
@@ -115,6 +117,8 @@ fn format_column_value(cv: &ColumnValue) -> String {
         Value::I16Val(n) => n.to_string(),
         Value::I32Val(n) => n.to_string(),
         Value::I64Val(n) => n.to_string(),
+        Value::F32Val(n) => n.to_string(),
+        Value::F64Val(n) => n.to_string(),
         Value::TextVal(s) => format!("\"{s}\""),
         Value::BlobVal(b) => format!("<blob {} bytes>", b.len()),
         Value::DecimalVal(s) => s.clone(),
@@ -122,7 +126,12 @@ fn format_column_value(cv: &ColumnValue) -> String {
         Value::DatetimeVal(s) => s.clone(),
         Value::JsonVal(s) => s.clone(),
         Value::UuidVal(s) => s.clone(),
+        Value::TimestampVal(n) => n.to_string(),
+        Value::CharVal(c) => format!("'{c}'"),
+        Value::ZonedDatetimeVal(z) => format!("{}ns{:+}min", z.instant, z.offset_minutes),
         Value::CustomVal(c) => format!("<custom {}: {}>", c.type_tag, c.display),
+        Value::ArrayVal(s) => s.clone(),
+        Value::JsonPatchVal(s) => format!("<json patch {s}>"),
         Value::NullVal => "NULL".to_string(),
     };
     format!("{}: {val}", cv.name)
@@ -7,6 +7,7 @@ pub mod database;
 pub mod foreign_fetcher;
 pub mod migration;
 pub mod query;
+pub mod retention;
 pub mod sanitize;
 pub mod table;
 pub mod transaction;
@@ -59,6 +59,8 @@ pub enum RequiredPerm {
     ManageAcl,
     /// The `migrate` operational flag was required.
     Migrate,
+    /// The caller had to be a controller of the canister (IC only).
+    Controller,
 }
 
 /// Effective permission set carried by a single identity.
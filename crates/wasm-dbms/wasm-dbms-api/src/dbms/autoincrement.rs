@@ -32,6 +32,20 @@ where
     }
 }
 
+impl<T> From<Option<T>> for Autoincrement<T>
+where
+    T: std::fmt::Debug + Clone + PartialEq + Eq,
+{
+    /// Converts [`Option::None`] into [`Autoincrement::Auto`] and
+    /// [`Option::Some`] into [`Autoincrement::Value`].
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => Autoincrement::Value(v),
+            None => Autoincrement::Auto,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -45,4 +59,10 @@ mod tests {
         assert_eq!(auto.into_option(), None);
         assert_eq!(value.into_option(), Some(42));
     }
+
+    #[test]
+    fn test_autoincrement_from_option() {
+        assert_eq!(Autoincrement::<i32>::from(None), Autoincrement::Auto);
+        assert_eq!(Autoincrement::from(Some(42)), Autoincrement::Value(42));
+    }
 }
@@ -183,7 +183,8 @@ mod test {
             Inactive,
         }
 
-        // Manual Encode impl since #[derive(Encode)] only supports structs
+        // Manual Encode impl, mirroring the one `#[derive(Encode)]` generates
+        // for unit enums
         impl memory::Encode for TestStatus {
             const SIZE: DataSize = DataSize::Fixed(1);
             const ALIGNMENT: PageOffset = 1;
@@ -1,7 +1,9 @@
 use crate::error::DbmsResult;
 use crate::prelude::{
-    AggregateFunction, AggregatedRow, ColumnDef, DeleteBehavior, Filter, InsertRecord,
-    JoinColumnDef, MigrationOp, MigrationPolicy, Query, TableSchema, UpdateRecord, Value,
+    AggregateFunction, AggregatedRow, AggregatedValue, BlobChunk, ColumnDef, CompactionReport,
+    ContinuationToken, DbmsError, DeleteBehavior, Filter, InsertConflictBehavior, InsertRecord,
+    JoinColumnDef, MigrationOp, MigrationPolicy, OrderDirection, PageCursor, Query, QueryError,
+    TableSchema, UpdateRecord, UpsertResult, VacuumReport, Value,
 };
 
 /// CRUD, aggregate, and transaction operations exposed by a wasm-dbms session.
@@ -29,7 +31,8 @@ pub trait Database {
     ///
     /// A `Vec<T::Record>` containing one entry per matching row, in the order
     /// produced by the query pipeline (see the
-    /// [Query API reference](crate::prelude::Query)).
+    /// [Query API reference](crate::prelude::Query)). Without `order_by`, rows
+    /// are in primary key order, ascending, regardless of how they are stored.
     ///
     /// # Errors
     ///
@@ -48,6 +51,91 @@ pub trait Database {
     where
         T: TableSchema;
 
+    /// Runs a typed `SELECT` for table `T` and returns the first matching
+    /// record, if any.
+    ///
+    /// The query's `LIMIT` is overridden to `1`. Without `ORDER BY` or
+    /// `DISTINCT` the table scan stops at the first match instead of
+    /// filtering the whole table; with them, every row must be considered
+    /// before the first one is known.
+    ///
+    /// # Arguments
+    ///
+    /// - `query` - The [`Query`] to execute. Must not contain joins.
+    ///
+    /// # Returns
+    ///
+    /// `Some(record)` for the first matching row, or `None` when no row
+    /// matches.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`select`](Self::select).
+    fn select_one<T>(&self, query: Query) -> DbmsResult<Option<T::Record>>
+    where
+        T: TableSchema;
+
+    /// Runs a typed `SELECT` for table `T` and returns one page of records
+    /// along with the cursor of the next page.
+    ///
+    /// Meant for keyset pagination: set a `LIMIT` on `query`, then request
+    /// the following page with
+    /// [`QueryBuilder::after`](crate::prelude::QueryBuilder::after) fed from
+    /// the returned [`PageCursor`]. Unlike `OFFSET`, which still walks every
+    /// skipped row, the next page is selected by a filter on the ordering
+    /// column, so an index or the primary key starts the scan at the cursor.
+    ///
+    /// # Arguments
+    ///
+    /// - `query` - The [`Query`] to execute. Its first `ORDER BY` column is
+    ///   the cursor column; an unordered query is sorted by the primary key,
+    ///   which then serves as the cursor column.
+    ///
+    /// # Returns
+    ///
+    /// The records of the page and, unless it is the last page, the cursor
+    /// of the next one (see [`PageCursor::next_page`]).
+    ///
+    /// # Errors
+    ///
+    /// Same as [`select`](Self::select).
+    fn select_page<T>(&self, mut query: Query) -> DbmsResult<(Vec<T::Record>, Option<PageCursor>)>
+    where
+        T: TableSchema,
+    {
+        if query.order_by.is_empty() {
+            query
+                .order_by
+                .push((T::primary_key().to_string(), OrderDirection::Ascending));
+        }
+        let records = self.select::<T>(query.clone())?;
+        let cursor = PageCursor::next_page::<T>(&query, &records);
+        Ok((records, cursor))
+    }
+
+    /// Returns whether at least one row of table `T` matches `filter`.
+    ///
+    /// Stops at the first match and never decodes rows into `T::Record`.
+    /// Inside a transaction, rows inserted, updated, or deleted by the
+    /// transaction are reflected.
+    ///
+    /// # Arguments
+    ///
+    /// - `filter` - Predicate the row must satisfy, e.g.
+    ///   `Filter::eq("id", pk)` to test for a primary key.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::UnknownColumn`] — `filter` references a column not on
+    ///   `T`.
+    /// - [`QueryError::TableNotFound`] — `T::table_name()` was never registered.
+    ///
+    /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
+    /// [`QueryError::TableNotFound`]: crate::prelude::QueryError::TableNotFound
+    fn exists<T>(&self, filter: Filter) -> DbmsResult<bool>
+    where
+        T: TableSchema;
+
     /// Runs a `SELECT` against a table identified by name, returning raw
     /// column-value pairs instead of typed records.
     ///
@@ -159,6 +247,84 @@ pub trait Database {
     where
         T: TableSchema;
 
+    /// Counts the rows of table `T` per value of the single `GROUP BY`
+    /// column of `query`, e.g. "posts per user".
+    ///
+    /// Shorthand for [`aggregate`](Self::aggregate) with a single
+    /// [`AggregateFunction::Count`]; `HAVING` and `ORDER BY` may reference
+    /// the count as `agg0`.
+    ///
+    /// # Arguments
+    ///
+    /// - `query` - The [`Query`] providing `WHERE`, `GROUP BY`, `HAVING`,
+    ///   `ORDER BY`, `LIMIT`, and `OFFSET`. Exactly one `group_by` column
+    ///   must be set.
+    ///
+    /// # Returns
+    ///
+    /// One `(group value, count)` pair per distinct value of the grouping
+    /// column.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::InvalidQuery`] — `query` does not group by exactly one
+    ///   column, or any error of [`aggregate`](Self::aggregate).
+    /// - [`QueryError::UnknownColumn`] — the grouping column is not on `T`.
+    ///
+    /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
+    /// [`QueryError::InvalidQuery`]: crate::prelude::QueryError::InvalidQuery
+    fn group_count<T>(&self, query: Query) -> DbmsResult<Vec<(Value, u64)>>
+    where
+        T: TableSchema,
+    {
+        if query.group_by.len() != 1 {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(
+                "group_count requires exactly one group_by column".to_string(),
+            )));
+        }
+
+        let rows = self.aggregate::<T>(query, &[AggregateFunction::Count(None)])?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let key = row.group_keys.into_iter().next().unwrap_or(Value::Null);
+                let count = match row.values.first() {
+                    Some(AggregatedValue::Count(count)) => *count,
+                    _ => 0,
+                };
+                (key, count)
+            })
+            .collect())
+    }
+
+    /// Counts the rows of table `T` matching `filter`.
+    ///
+    /// Only the filter is evaluated: rows are never decoded into `T::Record`
+    /// and no relation is loaded, which makes this far cheaper than
+    /// `select(..).len()` on large tables. Inside a transaction, rows
+    /// inserted, updated, or deleted by the transaction are reflected.
+    ///
+    /// # Arguments
+    ///
+    /// - `filter` - Predicate selecting the rows to count; `None` counts every
+    ///   row.
+    ///
+    /// # Returns
+    ///
+    /// The number of matching rows.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::UnknownColumn`] — `filter` references a column not on
+    ///   `T`.
+    /// - [`QueryError::TableNotFound`] — `T::table_name()` was never registered.
+    ///
+    /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
+    /// [`QueryError::TableNotFound`]: crate::prelude::QueryError::TableNotFound
+    fn count<T>(&self, filter: Option<Filter>) -> DbmsResult<u64>
+    where
+        T: TableSchema;
+
     /// Inserts a single record into table `T`.
     ///
     /// Auto-increment columns left unset are filled before insertion.
@@ -171,6 +337,10 @@ pub trait Database {
     /// transaction overlay and becomes visible to subsequent reads on the
     /// same transaction.
     ///
+    /// On a table declared with `#[on_conflict = "update"]`, a record whose
+    /// primary key already exists updates that row with the fields it sets
+    /// instead (see [`TableSchema::on_conflict_update`]).
+    ///
     /// # Arguments
     ///
     /// - `record` - The insert payload, typically built from
@@ -179,11 +349,12 @@ pub trait Database {
     ///
     /// # Errors
     ///
-    /// - [`QueryError::PrimaryKeyConflict`] — the row's PK already exists.
+    /// - [`QueryError::PrimaryKeyConflict`] — the row's PK already exists
+    ///   and the table does not update on conflict.
     /// - [`QueryError::UniqueConstraintViolation`] — a `#[unique]` column
     ///   collides with an existing row.
-    /// - [`QueryError::BrokenForeignKeyReference`] — a foreign key points at
-    ///   a row that does not exist.
+    /// - [`QueryError::ForeignKeyViolation`] — a foreign key points at a row
+    ///   that does not exist.
     /// - [`QueryError::MissingNonNullableField`] — a required column was
     ///   omitted.
     /// - [`DbmsError::Validation`] / [`DbmsError::Sanitize`] — a column
@@ -191,7 +362,7 @@ pub trait Database {
     ///
     /// [`QueryError::PrimaryKeyConflict`]: crate::prelude::QueryError::PrimaryKeyConflict
     /// [`QueryError::UniqueConstraintViolation`]: crate::prelude::QueryError::UniqueConstraintViolation
-    /// [`QueryError::BrokenForeignKeyReference`]: crate::prelude::QueryError::BrokenForeignKeyReference
+    /// [`QueryError::ForeignKeyViolation`]: crate::prelude::QueryError::ForeignKeyViolation
     /// [`QueryError::MissingNonNullableField`]: crate::prelude::QueryError::MissingNonNullableField
     /// [`DbmsError::Validation`]: crate::prelude::DbmsError
     /// [`DbmsError::Sanitize`]: crate::prelude::DbmsError
@@ -200,6 +371,122 @@ pub trait Database {
         T: TableSchema,
         T::Insert: InsertRecord<Schema = T>;
 
+    /// Inserts a record into table `T` and returns it as stored.
+    ///
+    /// Behaves like [`insert`](Self::insert), but returns the record built
+    /// from the values that were written, after sanitizers, defaults and
+    /// auto-increment, without reading the table again. Foreign key
+    /// relations are not loaded.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`insert`](Self::insert).
+    fn insert_returning<T>(&self, record: T::Insert) -> DbmsResult<T::Record>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>;
+
+    /// Inserts a batch of records into table `T` in a single atomic pass.
+    ///
+    /// The table registry is loaded once; every record is auto-incremented,
+    /// sanitized, and validated before any row is written, so either all
+    /// rows are inserted or none is. Besides the checks performed by
+    /// [`insert`](Self::insert), records of the batch are checked against
+    /// each other for primary key and unique constraint collisions.
+    ///
+    /// Inside a transaction the whole batch is recorded as a single
+    /// operation in the transaction overlay.
+    ///
+    /// # Arguments
+    ///
+    /// - `records` - The insert payloads, in insertion order.
+    ///
+    /// # Returns
+    ///
+    /// The number of inserted rows.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`insert`](Self::insert). [`QueryError::PrimaryKeyConflict`]
+    /// and [`QueryError::UniqueConstraintViolation`] are also returned when
+    /// two records of the batch collide.
+    ///
+    /// [`QueryError::PrimaryKeyConflict`]: crate::prelude::QueryError::PrimaryKeyConflict
+    /// [`QueryError::UniqueConstraintViolation`]: crate::prelude::QueryError::UniqueConstraintViolation
+    fn insert_many<T>(&self, records: Vec<T::Insert>) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>;
+
+    /// Inserts a single record into table `T`, resolving conflicts with
+    /// existing rows according to `behavior`.
+    ///
+    /// - [`InsertConflictBehavior::Error`] behaves exactly like
+    ///   [`insert`](Self::insert).
+    /// - [`InsertConflictBehavior::Ignore`] skips the record when it collides
+    ///   with an existing row on the primary key or on a `#[unique]` column.
+    /// - [`InsertConflictBehavior::Replace`] overwrites the row sharing the
+    ///   record's primary key. The new values go through the
+    ///   [`update`](Self::update) path, so sanitizers, validators, and
+    ///   integrity checks run as for a regular update. If no row has that
+    ///   primary key the record is inserted.
+    ///
+    /// # Arguments
+    ///
+    /// - `record` - The insert payload.
+    /// - `behavior` - How to handle a conflict with an existing row.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`insert`](Self::insert), except for the conflicts handled by
+    /// `behavior`. With [`InsertConflictBehavior::Replace`], a `#[unique]`
+    /// collision with a row other than the replaced one is still returned as
+    /// [`QueryError::UniqueConstraintViolation`].
+    ///
+    /// [`QueryError::UniqueConstraintViolation`]: crate::prelude::QueryError::UniqueConstraintViolation
+    fn insert_with_behavior<T>(
+        &self,
+        record: T::Insert,
+        behavior: InsertConflictBehavior,
+    ) -> DbmsResult<()>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+        T::Update: UpdateRecord<Schema = T>;
+
+    /// Inserts a record into table `T`, or overwrites the row sharing its
+    /// primary key.
+    ///
+    /// This is [`insert_with_behavior`](Self::insert_with_behavior) with
+    /// [`InsertConflictBehavior::Replace`], reporting which of the two
+    /// happened. Sanitizers and validators run as for an insert when the
+    /// record is new, and as for an update when it overwrites a row. Inside a
+    /// transaction the choice is made again at commit, against the rows
+    /// committed by then.
+    ///
+    /// # Arguments
+    ///
+    /// - `record` - The insert payload.
+    ///
+    /// # Returns
+    ///
+    /// [`UpsertResult::Inserted`] or [`UpsertResult::Updated`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`insert`](Self::insert), except for
+    /// [`QueryError::PrimaryKeyConflict`]. A `#[unique]` collision with a row
+    /// other than the overwritten one is returned as
+    /// [`QueryError::UniqueConstraintViolation`].
+    ///
+    /// [`QueryError::PrimaryKeyConflict`]: crate::prelude::QueryError::PrimaryKeyConflict
+    /// [`QueryError::UniqueConstraintViolation`]: crate::prelude::QueryError::UniqueConstraintViolation
+    fn upsert<T>(&self, record: T::Insert) -> DbmsResult<UpsertResult>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+        T::Update: UpdateRecord<Schema = T>;
+
     /// Updates rows of table `T` matching the patch's `where_clause`.
     ///
     /// The set of columns to write and the row predicate are both carried by
@@ -222,7 +509,9 @@ pub trait Database {
     ///
     /// # Returns
     ///
-    /// Number of rows updated. A return of `0` means no row matched the
+    /// Number of rows updated, including the referencing rows rewritten by a
+    /// primary-key cascade. Inside a transaction this is the number of rows
+    /// the commit will change. A return of `0` means no row matched the
     /// filter — not an error.
     ///
     /// # Errors
@@ -231,17 +520,106 @@ pub trait Database {
     ///   an existing row.
     /// - [`QueryError::UniqueConstraintViolation`] — the new value collides
     ///   with another row's `#[unique]` column.
-    /// - [`QueryError::BrokenForeignKeyReference`] — a new FK value points at
-    ///   a non-existent parent row.
+    /// - [`QueryError::ForeignKeyViolation`] — a new FK value points at a
+    ///   non-existent parent row.
     ///
     /// [`QueryError::PrimaryKeyConflict`]: crate::prelude::QueryError::PrimaryKeyConflict
     /// [`QueryError::UniqueConstraintViolation`]: crate::prelude::QueryError::UniqueConstraintViolation
-    /// [`QueryError::BrokenForeignKeyReference`]: crate::prelude::QueryError::BrokenForeignKeyReference
+    /// [`QueryError::ForeignKeyViolation`]: crate::prelude::QueryError::ForeignKeyViolation
     fn update<T>(&self, patch: T::Update) -> DbmsResult<u64>
     where
         T: TableSchema,
         T::Update: UpdateRecord<Schema = T>;
 
+    /// Updates rows of table `T` matching the patch's `where_clause` and
+    /// returns them as stored.
+    ///
+    /// Behaves like [`update`](Self::update), but returns the updated rows,
+    /// built from the values that were written, without reading the table
+    /// again. Inside a transaction the rows are returned as seen through the
+    /// transaction overlay, sanitized as they will be written at commit. Rows
+    /// of other tables updated by a primary key cascade are not returned, and
+    /// foreign key relations are not loaded.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`update`](Self::update).
+    fn update_returning<T>(&self, patch: T::Update) -> DbmsResult<Vec<T::Record>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>;
+
+    /// Updates at most `max_rows` rows of table `T` matching the patch's
+    /// `where_clause`, resuming after `token`.
+    ///
+    /// Bounds the work of a single call on a large table: rows are visited
+    /// in primary key order, and the returned [`ContinuationToken`] is the
+    /// scan position to resume from with the same `patch`. The rows of a
+    /// chunk are selected and updated in the same call, as one
+    /// [`update`](Self::update), so outside a transaction the chunks already
+    /// applied are kept when a later one fails. Rows whose primary key the
+    /// patch moves past the token are visited again. Implementations may cap
+    /// the chunk size, so a call without `max_rows` may still return a token.
+    ///
+    /// # Arguments
+    ///
+    /// - `patch` - The update payload, as for [`update`](Self::update).
+    /// - `max_rows` - The maximum number of matching rows to update; `None`
+    ///   updates every remaining row at once, up to the implementation cap.
+    /// - `token` - The token returned by the previous chunk; `None` starts
+    ///   from the first row.
+    ///
+    /// # Returns
+    ///
+    /// The number of rows updated by this chunk, counted as by
+    /// [`update`](Self::update), and the token of the next chunk, or `None`
+    /// once every matching row was visited.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`update`](Self::update), plus [`QueryError::InvalidQuery`]
+    /// if `max_rows` is zero or `token` is malformed.
+    fn update_chunked<T>(
+        &self,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+    ) -> DbmsResult<(u64, Option<ContinuationToken>)>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>;
+
+    /// Checks whether `record` could be inserted into table `T`, without
+    /// writing anything.
+    ///
+    /// Runs the sanitizers, validators and integrity checks of
+    /// [`insert`](Self::insert) through the same code path, against the
+    /// transaction overlay when a transaction is active. Neither the table
+    /// nor the auto-increment counters are modified.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`insert`](Self::insert).
+    fn validate_insert_only<T>(&self, record: T::Insert) -> DbmsResult<()>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>;
+
+    /// Checks whether `patch` could be applied to the rows of table `T`
+    /// matching its `where_clause`, without writing anything.
+    ///
+    /// Every matching row is patched, sanitized and validated like in
+    /// [`update`](Self::update), against the transaction overlay when a
+    /// transaction is active. A patch matching no row is valid.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`update`](Self::update).
+    fn validate_update_only<T>(&self, patch: T::Update) -> DbmsResult<()>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>;
+
     /// Deletes rows of table `T` matching `filter`.
     ///
     /// `behaviour` controls the foreign-key handling:
@@ -251,6 +629,11 @@ pub trait Database {
     ///
     /// A `None` filter targets every row in the table.
     ///
+    /// On a `#[soft_delete]` table the matching rows are kept: their
+    /// [`TableSchema::soft_delete_column`] is set to the current time and
+    /// `behaviour` is ignored, since no referenced row goes away. Use
+    /// [`force_delete`](Self::force_delete) to remove them.
+    ///
     /// Outside a transaction the delete (and any cascade) is journaled and
     /// applied atomically; inside a transaction the deletion is staged on the
     /// overlay.
@@ -265,21 +648,248 @@ pub trait Database {
     /// # Returns
     ///
     /// Total rows deleted, including rows removed by cascade. `0` means no
-    /// row matched the filter.
+    /// row matched the filter. Rows already soft-deleted are not counted.
     ///
     /// # Errors
     ///
     /// - [`QueryError::ForeignKeyConstraintViolation`] — a referenced row
     ///   exists and `behaviour` is [`DeleteBehavior::Restrict`].
+    /// - [`QueryError::CascadeTooDeep`] — the cascade reaches too many levels
+    ///   of referencing rows.
     /// - [`QueryError::UnknownColumn`] — `filter` references a column not on
     ///   `T`.
     ///
     /// [`QueryError::ForeignKeyConstraintViolation`]: crate::prelude::QueryError::ForeignKeyConstraintViolation
+    /// [`QueryError::CascadeTooDeep`]: crate::prelude::QueryError::CascadeTooDeep
     /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
     fn delete<T>(&self, behaviour: DeleteBehavior, filter: Option<Filter>) -> DbmsResult<u64>
     where
         T: TableSchema;
 
+    /// Deletes at most `max_rows` rows of table `T` matching `filter`,
+    /// resuming after `token`.
+    ///
+    /// Bounds the work of a single call on a large table: rows are visited
+    /// in primary key order, and the returned [`ContinuationToken`] is the
+    /// scan position to resume from with the same `filter`. The rows of a
+    /// chunk are selected and deleted in the same call, as one
+    /// [`delete`](Self::delete), so outside a transaction the chunks already
+    /// applied are kept when a later one fails. Implementations may cap the
+    /// chunk size, so a call without `max_rows` may still return a token.
+    ///
+    /// # Arguments
+    ///
+    /// - `behaviour` - Foreign-key handling, as for [`delete`](Self::delete).
+    /// - `filter` - Predicate selecting rows to delete; `None` matches every
+    ///   row.
+    /// - `max_rows` - The maximum number of matching rows to delete; `None`
+    ///   deletes every remaining row at once, up to the implementation cap.
+    /// - `token` - The token returned by the previous chunk; `None` starts
+    ///   from the first row.
+    ///
+    /// # Returns
+    ///
+    /// The number of rows deleted by this chunk, counted as by
+    /// [`delete`](Self::delete), and the token of the next chunk, or `None`
+    /// once every matching row was visited.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`delete`](Self::delete), plus [`QueryError::InvalidQuery`]
+    /// if `max_rows` is zero or `token` is malformed.
+    fn delete_chunked<T>(
+        &self,
+        behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+    ) -> DbmsResult<(u64, Option<ContinuationToken>)>
+    where
+        T: TableSchema;
+
+    /// Physically deletes rows of table `T` matching `filter`, soft-deleted
+    /// or not.
+    ///
+    /// Same as [`delete`](Self::delete) for a table without `#[soft_delete]`.
+    /// Rows removed by [`DeleteBehavior::Cascade`] are physically deleted
+    /// too.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`delete`](Self::delete).
+    fn force_delete<T>(&self, behaviour: DeleteBehavior, filter: Option<Filter>) -> DbmsResult<u64>
+    where
+        T: TableSchema;
+
+    /// Physically deletes every row of table `T`, soft-deleted or not.
+    ///
+    /// Unlike [`force_delete`](Self::force_delete) with no filter, the rows
+    /// are not visited one by one: the table's pages and indexes are released
+    /// at once. Auto-increment counters are not reset.
+    ///
+    /// Inside a transaction the truncation is staged as a
+    /// [`DeleteBehavior::Restrict`] delete of every row, applied row by row
+    /// on commit.
+    ///
+    /// # Returns
+    ///
+    /// Number of rows deleted.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::ForeignKeyConstraintViolation`] — a row of another
+    ///   table references a row of `T`.
+    ///
+    /// [`QueryError::ForeignKeyConstraintViolation`]: crate::prelude::QueryError::ForeignKeyConstraintViolation
+    fn truncate<T>(&self) -> DbmsResult<u64>
+    where
+        T: TableSchema;
+
+    /// Reads up to `len` bytes of the blob column `column` of the row of
+    /// table `T` with primary key `pk`, starting at byte `offset`.
+    ///
+    /// Works both on blobs stored inline and on blobs stored out of line,
+    /// which selects return as an empty placeholder. Reading past the end of
+    /// the blob returns fewer bytes.
+    ///
+    /// # Returns
+    ///
+    /// The [`BlobChunk`] read, along with the total length of the blob.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::RecordNotFound`] — no row has primary key `pk`.
+    /// - [`QueryError::UnknownColumn`] — `column` is not on `T`.
+    /// - [`QueryError::InvalidQuery`] — `column` is not a blob column, is
+    ///   null, `offset` is past the end of the blob or `len` exceeds the
+    ///   maximum chunk size.
+    ///
+    /// [`QueryError::RecordNotFound`]: crate::prelude::QueryError::RecordNotFound
+    /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
+    /// [`QueryError::InvalidQuery`]: crate::prelude::QueryError::InvalidQuery
+    fn blob_read_chunk<T>(
+        &self,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> DbmsResult<BlobChunk>
+    where
+        T: TableSchema;
+
+    /// Writes `bytes` at byte `offset` of the blob column `column` of the row
+    /// of table `T` with primary key `pk`.
+    ///
+    /// Writing at the end of the blob appends to it, so a large blob can be
+    /// uploaded in chunks after inserting the row with an empty blob. The
+    /// blob is moved out of line once it grows past the inline threshold.
+    /// Not available inside a transaction.
+    ///
+    /// # Returns
+    ///
+    /// The new total length of the blob.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::RecordNotFound`] — no row has primary key `pk`.
+    /// - [`QueryError::UnknownColumn`] — `column` is not on `T`.
+    /// - [`QueryError::InvalidQuery`] — `column` is not a blob column, is
+    ///   null, `offset` is past the end of the blob, `bytes` exceeds the
+    ///   maximum chunk size, the blob would exceed the maximum blob size, or
+    ///   a transaction is active.
+    ///
+    /// [`QueryError::RecordNotFound`]: crate::prelude::QueryError::RecordNotFound
+    /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
+    /// [`QueryError::InvalidQuery`]: crate::prelude::QueryError::InvalidQuery
+    fn blob_write_chunk<T>(
+        &self,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        bytes: &[u8],
+    ) -> DbmsResult<u64>
+    where
+        T: TableSchema;
+
+    /// Rewrites the records of table `T` contiguously, reclaiming the space
+    /// left behind by deleted rows.
+    ///
+    /// Every live record is read back, the table's record pages are released
+    /// and the records are written again from the first free page, after
+    /// which the indexes of `T` are rebuilt. The work is `O(N)` in the number
+    /// of rows and runs atomically: on error, the table is left untouched.
+    /// Not available inside a transaction.
+    ///
+    /// # Returns
+    ///
+    /// Number of record pages released.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::InvalidQuery`] — a transaction is active.
+    ///
+    /// [`QueryError::InvalidQuery`]: crate::prelude::QueryError::InvalidQuery
+    fn defragment<T>(&self) -> DbmsResult<u64>
+    where
+        T: TableSchema;
+
+    /// Moves the records off the last record pages of table `T` into the
+    /// space left behind by deleted rows, releasing at most `max_pages`
+    /// pages.
+    ///
+    /// Pages are emptied from the last one backwards, and each released page
+    /// is handed to the unclaimed-pages ledger, from which any table can
+    /// reuse it. The call stops at the first record that fits in no other
+    /// page. Every call runs atomically and leaves the table consistent, so
+    /// a large table can be compacted over several calls, each bounded by
+    /// `max_pages`, until the report is `complete`. Records are placed
+    /// first-fit: [`defragment`](Self::defragment) packs them tighter, at
+    /// the cost of rewriting the whole table in one call. Not available
+    /// inside a transaction.
+    ///
+    /// # Returns
+    ///
+    /// A [`CompactionReport`] with the page counts before and after the call
+    /// and the bytes reclaimed.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::InvalidQuery`] — a transaction is active.
+    ///
+    /// [`QueryError::InvalidQuery`]: crate::prelude::QueryError::InvalidQuery
+    fn compact<T>(&self, max_pages: u64) -> DbmsResult<CompactionReport>
+    where
+        T: TableSchema;
+
+    /// Deletes the records expired under the registered retention policies.
+    ///
+    /// For each [`RetentionPolicy`], the rows of its table whose timestamp
+    /// column is older than the current time minus the policy's maximum age
+    /// are physically deleted, as by [`force_delete`](Self::force_delete)
+    /// with [`DeleteBehavior::Restrict`]. Tables are vacuumed one at a time:
+    /// on error, the tables vacuumed before stay vacuumed.
+    ///
+    /// # Returns
+    ///
+    /// A [`VacuumReport`] with the number of tables vacuumed and of rows
+    /// deleted.
+    ///
+    /// # Errors
+    ///
+    /// - [`QueryError::TableNotFound`] — a policy names an unknown table.
+    /// - [`QueryError::UnknownColumn`] — a policy names an unknown column.
+    /// - [`QueryError::InvalidQuery`] — a policy's column is not a
+    ///   timestamp column.
+    /// - [`QueryError::ForeignKeyConstraintViolation`] — an expired row is
+    ///   still referenced.
+    ///
+    /// [`RetentionPolicy`]: crate::prelude::RetentionPolicy
+    /// [`QueryError::TableNotFound`]: crate::prelude::QueryError::TableNotFound
+    /// [`QueryError::UnknownColumn`]: crate::prelude::QueryError::UnknownColumn
+    /// [`QueryError::InvalidQuery`]: crate::prelude::QueryError::InvalidQuery
+    /// [`QueryError::ForeignKeyConstraintViolation`]: crate::prelude::QueryError::ForeignKeyConstraintViolation
+    fn vacuum(&self) -> DbmsResult<VacuumReport>;
+
     /// Commits the active transaction, replaying its operations against
     /// stable storage under a single write-ahead journal.
     ///
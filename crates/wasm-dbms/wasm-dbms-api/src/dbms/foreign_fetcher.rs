@@ -7,15 +7,19 @@ use crate::prelude::{ColumnDef, Database};
 
 /// Fetches related records from foreign tables referenced by foreign keys.
 ///
-/// This trait provides two methods:
+/// This trait provides three methods:
 ///
-/// - [`ForeignFetcher::fetch`] retrieves a single foreign record by primary key.
-///   Used during integrity checks (insert/update validation) to verify that a
-///   foreign key reference points to an existing record.
+/// - [`ForeignFetcher::fetch`] retrieves a single foreign record by the values
+///   of a foreign key, one per referenced column. Used during integrity checks
+///   (insert/update validation) to verify that a foreign key reference points
+///   to an existing record, and to load the relations of composite foreign keys.
 ///
 /// - [`ForeignFetcher::fetch_batch`] retrieves multiple foreign records in one
 ///   query using `Filter::In`. Used during eager relation loading to resolve the
 ///   N+1 query problem by batching all FK lookups for a result set.
+///
+/// - [`ForeignFetcher::columns`] returns the columns of a foreign table. Used
+///   to validate the columns selected for an eager relation.
 pub trait ForeignFetcher: Default {
     /// Fetches a single foreign record for integrity validation.
     ///
@@ -24,7 +28,8 @@ pub trait ForeignFetcher: Default {
     /// * `database` - The database from which to fetch the data.
     /// * `table` - The name of the foreign table to query.
     /// * `local_column` - The local column that holds the foreign key reference.
-    /// * `pk_value` - The primary key value to look up in the foreign table.
+    /// * `key` - The values of the foreign key columns, matched by position
+    ///   with the referenced columns of the foreign table.
     ///
     /// # Returns
     ///
@@ -34,7 +39,7 @@ pub trait ForeignFetcher: Default {
         database: &impl Database,
         table: &str,
         local_column: &'static str,
-        pk_value: Value,
+        key: &[Value],
     ) -> DbmsResult<TableColumns>;
 
     /// Batch-fetches foreign records for eager relation loading.
@@ -57,6 +62,14 @@ pub trait ForeignFetcher: Default {
         table: &str,
         pk_values: &[Value],
     ) -> DbmsResult<HashMap<Value, Vec<(ColumnDef, Value)>>>;
+
+    /// Returns the columns of the foreign `table`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QueryError::InvalidQuery`](crate::prelude::QueryError::InvalidQuery)
+    /// if `table` is not referenced by a foreign key.
+    fn columns(&self, table: &str) -> DbmsResult<&'static [ColumnDef]>;
 }
 
 /// A no-op foreign fetcher that does not perform any fetching.
@@ -69,7 +82,7 @@ impl ForeignFetcher for NoForeignFetcher {
         _database: &impl Database,
         _table: &str,
         _local_column: &'static str,
-        _pk_value: Value,
+        _key: &[Value],
     ) -> DbmsResult<TableColumns> {
         unimplemented!("NoForeignFetcher should have a table without foreign keys");
     }
@@ -82,6 +95,14 @@ impl ForeignFetcher for NoForeignFetcher {
     ) -> DbmsResult<HashMap<Value, Vec<(ColumnDef, Value)>>> {
         unimplemented!("NoForeignFetcher should have a table without foreign keys");
     }
+
+    fn columns(&self, table: &str) -> DbmsResult<&'static [ColumnDef]> {
+        Err(crate::prelude::DbmsError::Query(
+            crate::prelude::QueryError::InvalidQuery(format!(
+                "ForeignFetcher: unknown table '{table}' for a table without foreign keys"
+            )),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -96,7 +117,7 @@ mod tests {
             &MockDatabase,
             "some_table",
             "some_column",
-            Value::Uint32(1.into()),
+            &[Value::Uint32(1.into())],
         );
     }
 
@@ -107,6 +128,16 @@ mod tests {
         let _ = fetcher.fetch_batch(&MockDatabase, "some_table", &[Value::Uint32(1.into())]);
     }
 
+    #[test]
+    fn test_no_foreign_fetcher_columns() {
+        assert!(matches!(
+            NoForeignFetcher.columns("some_table"),
+            Err(crate::prelude::DbmsError::Query(
+                crate::prelude::QueryError::InvalidQuery(_)
+            ))
+        ));
+    }
+
     struct MockDatabase;
 
     impl Database for MockDatabase {
@@ -117,6 +148,20 @@ mod tests {
             unimplemented!()
         }
 
+        fn select_one<T>(&self, _query: crate::prelude::Query) -> DbmsResult<Option<T::Record>>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn exists<T>(&self, _filter: crate::prelude::Filter) -> DbmsResult<bool>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
         fn insert<T>(&self, _record: T::Insert) -> DbmsResult<()>
         where
             T: crate::prelude::TableSchema,
@@ -125,6 +170,44 @@ mod tests {
             unimplemented!()
         }
 
+        fn insert_returning<T>(&self, _record: T::Insert) -> DbmsResult<T::Record>
+        where
+            T: crate::prelude::TableSchema,
+            T::Insert: crate::prelude::InsertRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
+        fn insert_many<T>(&self, _records: Vec<T::Insert>) -> DbmsResult<u64>
+        where
+            T: crate::prelude::TableSchema,
+            T::Insert: crate::prelude::InsertRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
+        fn insert_with_behavior<T>(
+            &self,
+            _record: T::Insert,
+            _behavior: crate::prelude::InsertConflictBehavior,
+        ) -> DbmsResult<()>
+        where
+            T: crate::prelude::TableSchema,
+            T::Insert: crate::prelude::InsertRecord<Schema = T>,
+            T::Update: crate::prelude::UpdateRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
+        fn upsert<T>(&self, _record: T::Insert) -> DbmsResult<crate::prelude::UpsertResult>
+        where
+            T: crate::prelude::TableSchema,
+            T::Insert: crate::prelude::InsertRecord<Schema = T>,
+            T::Update: crate::prelude::UpdateRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
         fn update<T>(&self, _patch: T::Update) -> DbmsResult<u64>
         where
             T: crate::prelude::TableSchema,
@@ -133,6 +216,43 @@ mod tests {
             unimplemented!()
         }
 
+        fn update_chunked<T>(
+            &self,
+            _patch: T::Update,
+            _max_rows: Option<u64>,
+            _token: Option<crate::prelude::ContinuationToken>,
+        ) -> DbmsResult<(u64, Option<crate::prelude::ContinuationToken>)>
+        where
+            T: crate::prelude::TableSchema,
+            T::Update: crate::prelude::UpdateRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
+        fn update_returning<T>(&self, _patch: T::Update) -> DbmsResult<Vec<T::Record>>
+        where
+            T: crate::prelude::TableSchema,
+            T::Update: crate::prelude::UpdateRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
+        fn validate_insert_only<T>(&self, _record: T::Insert) -> DbmsResult<()>
+        where
+            T: crate::prelude::TableSchema,
+            T::Insert: crate::prelude::InsertRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
+        fn validate_update_only<T>(&self, _patch: T::Update) -> DbmsResult<()>
+        where
+            T: crate::prelude::TableSchema,
+            T::Update: crate::prelude::UpdateRecord<Schema = T>,
+        {
+            unimplemented!()
+        }
+
         fn aggregate<T>(
             &self,
             _query: crate::prelude::Query,
@@ -144,6 +264,13 @@ mod tests {
             todo!();
         }
 
+        fn count<T>(&self, _filter: Option<crate::prelude::Filter>) -> DbmsResult<u64>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            todo!();
+        }
+
         fn select_raw(
             &self,
             _table: &str,
@@ -171,6 +298,81 @@ mod tests {
             unimplemented!()
         }
 
+        fn delete_chunked<T>(
+            &self,
+            _behaviour: crate::prelude::DeleteBehavior,
+            _filter: Option<crate::prelude::Filter>,
+            _max_rows: Option<u64>,
+            _token: Option<crate::prelude::ContinuationToken>,
+        ) -> DbmsResult<(u64, Option<crate::prelude::ContinuationToken>)>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn force_delete<T>(
+            &self,
+            _behaviour: crate::prelude::DeleteBehavior,
+            _filter: Option<crate::prelude::Filter>,
+        ) -> DbmsResult<u64>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn truncate<T>(&self) -> DbmsResult<u64>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn blob_read_chunk<T>(
+            &self,
+            _pk: Value,
+            _column: &str,
+            _offset: u64,
+            _len: u64,
+        ) -> DbmsResult<crate::prelude::BlobChunk>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn blob_write_chunk<T>(
+            &self,
+            _pk: Value,
+            _column: &str,
+            _offset: u64,
+            _bytes: &[u8],
+        ) -> DbmsResult<u64>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn defragment<T>(&self) -> DbmsResult<u64>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn compact<T>(&self, _max_pages: u64) -> DbmsResult<crate::prelude::CompactionReport>
+        where
+            T: crate::prelude::TableSchema,
+        {
+            unimplemented!()
+        }
+
+        fn vacuum(&self) -> DbmsResult<crate::prelude::VacuumReport> {
+            unimplemented!()
+        }
+
         fn commit(&mut self) -> DbmsResult<()> {
             unimplemented!()
         }
@@ -151,6 +151,20 @@ pub enum MigrationOp {
     },
 }
 
+impl MigrationOp {
+    /// Returns `true` when the op only adds to the schema: a new table, a new
+    /// column or a new index.
+    ///
+    /// Additive ops never rewrite or discard the data already stored, so they
+    /// are safe to apply without operator review, e.g. on canister upgrade.
+    pub fn is_additive(&self) -> bool {
+        matches!(
+            self,
+            Self::CreateTable { .. } | Self::AddColumn { .. } | Self::AddIndex { .. }
+        )
+    }
+}
+
 /// Bundle of constraint-flag deltas for an [`MigrationOp::AlterColumn`].
 ///
 /// Each field is `Some(new_value)` only when that flag changed between the
@@ -241,6 +255,13 @@ pub enum MigrationError {
         /// Short tag for the offending op (e.g. `"DropTable"`, `"DropColumn"`).
         op: String,
     },
+    /// An automatic migration found an op that is not
+    /// [additive](MigrationOp::is_additive) and must be applied explicitly.
+    #[error("Schema change requires a manual migration: {op}")]
+    ManualMigrationRequired {
+        /// Short tag for the offending op (e.g. `"DropColumn(users.email)"`).
+        op: String,
+    },
     /// User-supplied [`Migrate::transform_column`] returned `Err`.
     #[error("Migration transform aborted for column `{column}` in table `{table}`: {reason}")]
     TransformAborted {
@@ -356,6 +377,59 @@ mod test {
             reason: "negative ids unsupported".into(),
         };
         assert!(err.to_string().contains("Migration transform aborted"));
+
+        let err = MigrationError::ManualMigrationRequired {
+            op: "DropColumn(users.email)".into(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Schema change requires a manual migration: DropColumn(users.email)"
+        );
+    }
+
+    #[test]
+    fn test_should_tell_additive_migration_ops() {
+        let column = ColumnSnapshot {
+            name: "bio".into(),
+            data_type: DataTypeSnapshot::Text,
+            nullable: true,
+            auto_increment: false,
+            unique: false,
+            primary_key: false,
+            foreign_key: None,
+            default: None,
+        };
+        assert!(
+            MigrationOp::AddColumn {
+                table: "users".into(),
+                column,
+            }
+            .is_additive()
+        );
+        assert!(
+            !MigrationOp::DropColumn {
+                table: "users".into(),
+                column: "bio".into(),
+            }
+            .is_additive()
+        );
+        assert!(
+            !MigrationOp::RenameColumn {
+                table: "users".into(),
+                old: "bio".into(),
+                new: "about".into(),
+            }
+            .is_additive()
+        );
+        assert!(
+            !MigrationOp::WidenColumn {
+                table: "users".into(),
+                column: "id".into(),
+                old_type: DataTypeSnapshot::Uint32,
+                new_type: DataTypeSnapshot::Uint64,
+            }
+            .is_additive()
+        );
     }
 
     #[test]
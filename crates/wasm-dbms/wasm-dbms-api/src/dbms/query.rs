@@ -1,19 +1,29 @@
 //! This module exposes all the types related to queries that can be performed on the DBMS.
 
 mod aggregate;
+mod blob;
 mod builder;
+mod continuation;
+mod cursor;
 mod delete;
 mod filter;
+mod insert;
 mod join;
+mod json_patch;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub use self::aggregate::{AggregateFunction, AggregatedRow, AggregatedValue};
+pub use self::blob::BlobChunk;
 pub use self::builder::QueryBuilder;
+pub use self::continuation::ContinuationToken;
+pub use self::cursor::PageCursor;
 pub use self::delete::DeleteBehavior;
-pub use self::filter::{Filter, JsonCmp, JsonFilter};
+pub use self::filter::{ColumnCmp, Filter, JsonCmp, JsonFilter};
+pub use self::insert::{InsertConflictBehavior, UpsertResult};
 pub use self::join::{Join, JoinType};
+pub use self::json_patch::{JsonPatchOp, apply_json_patch};
 use crate::dbms::table::TableSchema;
 use crate::dbms::value::Value;
 use crate::memory::MemoryError;
@@ -30,13 +40,28 @@ pub enum QueryError {
     PrimaryKeyConflict,
 
     /// A unique constraint was violated (e.g., UNIQUE index, CHECK constraint, etc.)
-    #[error("Unique constraint violation on field '{field}'")]
-    UniqueConstraintViolation { field: String },
+    ///
+    /// For composite `#[unique(group = "...")]` constraints, `column` lists the
+    /// columns of the group separated by `", "`.
+    #[error("Unique constraint violation on table '{table}' for column '{column}'")]
+    UniqueConstraintViolation { table: String, column: String },
 
     /// A foreign key references a non-existent record in another table.
     #[error("Broken foreign key reference to table '{table}' with key '{key:?}'")]
     BrokenForeignKeyReference { table: String, key: Value },
 
+    /// An inserted or updated foreign key `column` holds a `value` matching
+    /// no row of the referenced `table`, including the rows written earlier
+    /// in the same transaction.
+    #[error(
+        "Foreign key violation: column '{column}' references '{value:?}', missing from table '{table}'"
+    )]
+    ForeignKeyViolation {
+        table: String,
+        column: String,
+        value: Value,
+    },
+
     /// Tried to delete or update a record that is referenced by another table's foreign key.
     #[error("Foreign key constraint violation on table '{referencing_table}' for field '{field}'")]
     ForeignKeyConstraintViolation {
@@ -44,6 +69,10 @@ pub enum QueryError {
         field: String,
     },
 
+    /// A cascade delete reached more than `depth` levels of referencing rows.
+    #[error("Cascade delete from table '{table}' exceeds the maximum depth of {depth}")]
+    CascadeTooDeep { table: String, depth: usize },
+
     /// Tried to reference a column that does not exist in the table schema.
     #[error("Unknown column: {0}")]
     UnknownColumn(String),
@@ -60,6 +89,15 @@ pub enum QueryError {
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
 
+    /// A `Filter::Regex` pattern could not be compiled.
+    #[error("Invalid regex: {0}")]
+    InvalidRegex(String),
+
+    /// Two values that must share a data type (e.g. the bounds of a
+    /// `Filter::Between`) have different types.
+    #[error("Type mismatch: expected '{expected}', found '{found}'")]
+    TypeMismatch { expected: String, found: String },
+
     /// Join inside a typed select operation
     #[error("Join cannot be used on type select")]
     JoinInsideTypedSelect,
@@ -69,6 +107,28 @@ pub enum QueryError {
     #[error("GROUP BY / HAVING require aggregate(); use Database::aggregate")]
     AggregateClauseInSelect,
 
+    /// A `#[check]` constraint does not hold for the record.
+    ///
+    /// `field` is the column of a field-level `#[check = "..."]`, or the name
+    /// of a table-level `#[check(...)]`; `message` is the source of the
+    /// failing check expression.
+    #[error("Check constraint violation on field '{field}': {message}")]
+    CheckConstraintViolation { field: String, message: String },
+
+    /// A value of `column` is larger than the column allows (see
+    /// [`ColumnDef::max_size`](crate::prelude::ColumnDef::max_size)).
+    #[error("Value too large for column '{column}': {actual} bytes exceed the maximum of {max}")]
+    ValueTooLarge {
+        column: String,
+        max: u64,
+        actual: u64,
+    },
+
+    /// A value of the enum column `column` has a discriminant with no variant
+    /// (see [`ColumnDef::allowed_values`](crate::prelude::ColumnDef::allowed_values())).
+    #[error("Invalid value for enum column '{column}': discriminant {discriminant} has no variant")]
+    InvalidEnumValue { column: String, discriminant: u8 },
+
     /// Generic constraint violation (e.g., UNIQUE, CHECK, etc.)
     #[error("Constraint violation: {0}")]
     ConstraintViolation(String),
@@ -120,6 +180,9 @@ pub struct Query {
     pub distinct_by: Vec<String>,
     /// Relations to eagerly load with the main records.
     pub eager_relations: Vec<String>,
+    /// Columns to select for some of the eager relations, as
+    /// `(relation, columns)` pairs. Relations not listed get every column.
+    pub eager_columns: Vec<(String, Vec<String>)>,
     /// [`Filter`] to apply to the query.
     pub filter: Option<Filter>,
     /// Group by fields for aggregate queries.
@@ -134,6 +197,9 @@ pub struct Query {
     pub offset: Option<usize>,
     /// Order by clauses for sorting the results.
     pub order_by: Vec<(String, OrderDirection)>,
+    /// Whether to return the rows of a `#[soft_delete]` table whose deletion
+    /// timestamp is set. Ignored for other tables.
+    pub include_deleted: bool,
 }
 
 #[cfg(feature = "candid")]
@@ -144,6 +210,7 @@ impl candid::CandidType for Query {
             candid::field! { columns: Select::_ty() },
             candid::field! { distinct_by: <Vec<String>>::_ty() },
             candid::field! { eager_relations: <Vec<String>>::_ty() },
+            candid::field! { eager_columns: <Vec<(String, Vec<String>)>>::_ty() },
             candid::field! { filter: <Option<Filter>>::_ty() },
             candid::field! { group_by: <Vec<String>>::_ty() },
             candid::field! { having: <Option<Filter>>::_ty() },
@@ -151,6 +218,7 @@ impl candid::CandidType for Query {
             candid::field! { limit: <Option<usize>>::_ty() },
             candid::field! { offset: <Option<usize>>::_ty() },
             candid::field! { order_by: <Vec<(String, OrderDirection)>>::_ty() },
+            candid::field! { include_deleted: bool::_ty() },
         ];
 
         fields.sort_by_key(|f| f.id.clone());
@@ -165,8 +233,10 @@ impl candid::CandidType for Query {
         // Fields must be serialized in Candid field hash order. The order
         // below matches the ascending hash of each field name (idl_hash).
         let mut record_serializer = serializer.serialize_struct()?;
+        record_serializer.serialize_element(&self.include_deleted)?;
         record_serializer.serialize_element(&self.eager_relations)?;
         record_serializer.serialize_element(&self.distinct_by)?;
+        record_serializer.serialize_element(&self.eager_columns)?;
         record_serializer.serialize_element(&self.joins)?;
         record_serializer.serialize_element(&self.offset)?;
         record_serializer.serialize_element(&self.limit)?;
@@ -204,6 +274,15 @@ impl Query {
         }
     }
 
+    /// Returns the columns to select for the eager `relation`, or `None` if
+    /// every column is selected.
+    pub fn eager_columns(&self, relation: &str) -> Option<&[String]> {
+        self.eager_columns
+            .iter()
+            .find(|(r, _)| r == relation)
+            .map(|(_, columns)| columns.as_slice())
+    }
+
     /// Returns whether the query has any joins.
     pub fn has_joins(&self) -> bool {
         !self.joins.is_empty()
@@ -265,16 +344,31 @@ mod tests {
         let query = Query::builder()
             .field("id")
             .with("posts")
+            .with_fields("users", &["name"])
             .and_where(Filter::eq("name", Value::Text("Alice".into())))
             .order_by_asc("id")
             .limit(10)
             .offset(5)
+            .include_deleted()
             .build();
         let encoded = candid::encode_one(&query).unwrap();
         let decoded: Query = candid::decode_one(&encoded).unwrap();
         assert_eq!(query, decoded);
     }
 
+    #[test]
+    fn test_should_get_eager_columns() {
+        let query = Query::builder()
+            .with("posts")
+            .with_fields("users", &["id", "name"])
+            .build();
+        assert_eq!(query.eager_columns("posts"), None);
+        assert_eq!(
+            query.eager_columns("users"),
+            Some(["id".to_string(), "name".to_string()].as_slice())
+        );
+    }
+
     #[test]
     fn test_should_build_query_with_joins() {
         let query = Query::builder()
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// A chunk of a blob column read with
+/// [`Database::blob_read_chunk`](crate::prelude::Database::blob_read_chunk).
+///
+/// Blobs larger than the inline threshold are stored out of line and are
+/// returned by selects as an empty placeholder; their content is streamed in
+/// chunks instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct BlobChunk {
+    /// Total length of the blob in bytes.
+    pub total_len: u64,
+    /// The bytes read, shorter than requested when the end of the blob is
+    /// reached.
+    pub bytes: Vec<u8>,
+}
+
+impl BlobChunk {
+    /// Returns whether the chunk reaches the end of a blob read from `offset`.
+    pub fn is_last(&self, offset: u64) -> bool {
+        offset + self.bytes.len() as u64 >= self.total_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_should_tell_last_chunk() {
+        let chunk = BlobChunk {
+            total_len: 10,
+            bytes: vec![0; 4],
+        };
+        assert!(!chunk.is_last(0));
+        assert!(chunk.is_last(6));
+        assert!(
+            BlobChunk {
+                total_len: 0,
+                bytes: vec![],
+            }
+            .is_last(0)
+        );
+    }
+}
@@ -1,16 +1,29 @@
 use crate::dbms::query::{Filter, OrderDirection, Query};
-use crate::prelude::{Join, JoinType};
+use crate::dbms::value::Value;
+use crate::prelude::{Join, JoinType, PageCursor};
 
 /// A builder for constructing database [`Query`]es.
 #[derive(Debug, Default, Clone)]
 pub struct QueryBuilder {
     query: Query,
+    /// Whether to deduplicate by the selected fields, resolved on [`Self::build`].
+    distinct_selected: bool,
+    /// Keyset pagination bound set by [`Self::after`], resolved on [`Self::build`].
+    after: Option<(String, Value)>,
 }
 
 impl QueryBuilder {
     /// Builds and returns a [`Query`] object based on the current state of the [`QueryBuilder`].
-    pub fn build(self) -> Query {
-        self.query
+    pub fn build(mut self) -> Query {
+        if self.distinct_selected
+            && let crate::dbms::query::Select::Columns(cols) = &self.query.columns
+        {
+            self.query.distinct_by = cols.clone();
+        }
+        match self.after.take() {
+            Some((column, value)) => PageCursor { column, value }.apply(self.query),
+            None => self.query,
+        }
     }
 
     /// Adds a field to select in the query.
@@ -45,8 +58,12 @@ impl QueryBuilder {
         self
     }
 
-    /// Adds a relation to eagerly load with the main records.
+    /// Adds a relation to eagerly load with the main records, selecting all
+    /// of its fields.
     pub fn with(mut self, table_relation: &str) -> Self {
+        self.query
+            .eager_columns
+            .retain(|(relation, _)| relation != table_relation);
         let table_relation = table_relation.to_string();
         if !self.query.eager_relations.contains(&table_relation) {
             self.query.eager_relations.push(table_relation);
@@ -54,6 +71,19 @@ impl QueryBuilder {
         self
     }
 
+    /// Adds a relation to eagerly load with the main records, selecting only
+    /// `fields` of it.
+    ///
+    /// The fields left out are `None` in the loaded foreign record.
+    pub fn with_fields(mut self, table_relation: &str, fields: &[&str]) -> Self {
+        self = self.with(table_relation);
+        self.query.eager_columns.push((
+            table_relation.to_string(),
+            fields.iter().map(|field| field.to_string()).collect(),
+        ));
+        self
+    }
+
     /// Adds an INNER JOIN operation to this query
     pub fn inner_join(self, table: &str, left_col: &str, right_col: &str) -> Self {
         self.join(JoinType::Inner, table, left_col, right_col)
@@ -77,6 +107,18 @@ impl QueryBuilder {
     /// Adds a DISTINCT clause to the query for the specified fields.
     pub fn distinct<S: ToString>(mut self, fields: &[S]) -> Self {
         self.query.distinct_by = fields.iter().map(|s| s.to_string()).collect();
+        self.distinct_selected = false;
+        self
+    }
+
+    /// Adds a DISTINCT clause over the selected fields.
+    ///
+    /// The selected fields become the `distinct_by` columns when the query is
+    /// built, so the call may appear before or after [`Self::field`]. When all
+    /// fields are selected this is a no-op, since the primary key already makes
+    /// every full record distinct.
+    pub fn distinct_selected(mut self) -> Self {
+        self.distinct_selected = true;
         self
     }
 
@@ -120,6 +162,27 @@ impl QueryBuilder {
         self
     }
 
+    /// Starts the page after `value` on the ordered `column` (keyset pagination).
+    ///
+    /// Adds an exclusive bound on `column` to the filter when the query is
+    /// built: `column > value` when the column is sorted ascending,
+    /// `column < value` when sorted descending. When `column` is not among the
+    /// `ORDER BY` columns it becomes the first one, sorted ascending. `column`
+    /// should be unique (e.g. the primary key), or records sharing `value`
+    /// are skipped. The value usually comes from a
+    /// [`PageCursor`](crate::prelude::PageCursor).
+    pub fn after<S: ToString>(mut self, column: S, value: Value) -> Self {
+        self.after = Some((column.to_string(), value));
+        self
+    }
+
+    /// Includes the soft-deleted rows of a `#[soft_delete]` table, which are
+    /// otherwise left out of the results.
+    pub fn include_deleted(mut self) -> Self {
+        self.query.include_deleted = true;
+        self
+    }
+
     /// Sets a filter for the query, replacing any existing filter.
     pub fn filter(mut self, filter: Option<Filter>) -> Self {
         self.query.filter = filter;
@@ -213,6 +276,30 @@ mod tests {
         assert_eq!(query.eager_relations, vec!["posts"]);
     }
 
+    #[test]
+    fn test_should_add_eager_relation_with_fields() {
+        let query = QueryBuilder::default()
+            .with_fields("posts", &["title"])
+            .with_fields("posts", &["id", "title"])
+            .build();
+        assert_eq!(query.eager_relations, vec!["posts"]);
+        assert_eq!(
+            query.eager_columns,
+            vec![(
+                "posts".to_string(),
+                vec!["id".to_string(), "title".to_string()]
+            )]
+        );
+
+        // `with` selects every field again
+        let query = QueryBuilder::default()
+            .with_fields("posts", &["title"])
+            .with("posts")
+            .build();
+        assert_eq!(query.eager_relations, vec!["posts"]);
+        assert!(query.eager_columns.is_empty());
+    }
+
     #[test]
     fn test_should_add_order_by_clauses() {
         let query_builder = QueryBuilder::default()
@@ -238,6 +325,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_distinct_by_selected_fields() {
+        let query = QueryBuilder::default()
+            .distinct_selected()
+            .field("name")
+            .field("email")
+            .build();
+        assert_eq!(
+            query.distinct_by,
+            vec!["name".to_string(), "email".to_string()]
+        );
+
+        let query = QueryBuilder::default().all().distinct_selected().build();
+        assert!(query.distinct_by.is_empty());
+
+        let query = QueryBuilder::default()
+            .field("name")
+            .distinct_selected()
+            .distinct(&["email"])
+            .build();
+        assert_eq!(query.distinct_by, vec!["email".to_string()]);
+    }
+
     #[test]
     fn test_should_set_group_by_fields() {
         let query_builder = QueryBuilder::default().group_by(&["category", "status"]);
@@ -248,6 +358,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_set_after_bound() {
+        let query = QueryBuilder::default()
+            .and_where(Filter::eq("name", Value::Text("alice".into())))
+            .after("id", Value::Uint32(5u32.into()))
+            .limit(10)
+            .build();
+        assert_eq!(
+            query.filter,
+            Some(
+                Filter::eq("name", Value::Text("alice".into()))
+                    .and(Filter::gt("id", Value::Uint32(5u32.into())))
+            )
+        );
+        assert_eq!(
+            query.order_by,
+            vec![("id".to_string(), OrderDirection::Ascending)]
+        );
+    }
+
+    #[test]
+    fn test_should_set_after_bound_on_descending_column() {
+        let query = QueryBuilder::default()
+            .after("name", Value::Text("bob".into()))
+            .order_by_desc("name")
+            .build();
+        assert_eq!(
+            query.filter,
+            Some(Filter::lt("name", Value::Text("bob".into())))
+        );
+        assert_eq!(
+            query.order_by,
+            vec![("name".to_string(), OrderDirection::Descending)]
+        );
+    }
+
     #[test]
     fn test_should_set_having_filter() {
         let filter = Filter::gt("count", Value::Uint32(10u32.into()));
@@ -256,6 +402,13 @@ mod tests {
         assert_eq!(query.having, Some(filter));
     }
 
+    #[test]
+    fn test_should_include_deleted_rows() {
+        assert!(!QueryBuilder::default().build().include_deleted);
+        let query = QueryBuilder::default().include_deleted().build();
+        assert!(query.include_deleted);
+    }
+
     #[test]
     fn test_should_set_limit_and_offset() {
         let query_builder = QueryBuilder::default().limit(10).offset(5);
@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dbms::query::{Filter, Query, QueryError};
+use crate::dbms::table::{TableRecord, TableSchema};
+use crate::dbms::value::Value;
+use crate::error::{DbmsError, DbmsResult};
+
+/// Scan position of a chunked delete or update: the primary key of the last
+/// row processed by the previous chunk.
+///
+/// Returned by [`Database::delete_chunked`](crate::prelude::Database::delete_chunked)
+/// and [`Database::update_chunked`](crate::prelude::Database::update_chunked)
+/// while rows may remain; pass it back with the same filter to process the
+/// next chunk. Rows are visited in primary key order, so the next chunk starts
+/// right after [`Self::key`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct ContinuationToken {
+    /// The values of the primary key columns of the last processed row, in
+    /// the order of [`TableSchema::primary_keys`].
+    pub key: Vec<Value>,
+}
+
+impl ContinuationToken {
+    /// Returns the query selecting the primary keys of the next chunk: at
+    /// most `max_rows` rows of `T` matching `filter` after `token`, in
+    /// primary key order.
+    ///
+    /// Fails with [`QueryError::InvalidQuery`] if `token` is malformed.
+    pub fn chunk_query<T>(
+        filter: Option<Filter>,
+        token: Option<&Self>,
+        max_rows: u64,
+    ) -> DbmsResult<Query>
+    where
+        T: TableSchema,
+    {
+        let mut builder = Query::builder()
+            .fields(T::primary_keys().iter().copied())
+            .filter(Self::resume::<T>(filter, token)?)
+            .limit(usize::try_from(max_rows).unwrap_or(usize::MAX));
+        for column in T::primary_keys() {
+            builder = builder.order_by_asc(column);
+        }
+        Ok(builder.build())
+    }
+
+    /// Returns the token of the last row of a chunk selected by
+    /// [`Self::chunk_query`], or `None` when the chunk is empty.
+    pub fn chunk_end<T>(records: &[T::Record]) -> Option<Self>
+    where
+        T: TableSchema,
+    {
+        let values = records.last()?.to_values();
+        let key = T::primary_keys()
+            .iter()
+            .map(|column| {
+                values
+                    .iter()
+                    .find(|(col_def, _)| col_def.name == *column)
+                    .map(|(_, value)| value.clone())
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self { key })
+    }
+
+    /// Restricts `filter` to the rows of `T` after `token`, or returns it as
+    /// is without a token.
+    ///
+    /// Fails with [`QueryError::InvalidQuery`] if `token` is malformed.
+    pub fn resume<T>(filter: Option<Filter>, token: Option<&Self>) -> DbmsResult<Option<Filter>>
+    where
+        T: TableSchema,
+    {
+        let Some(token) = token else {
+            return Ok(filter);
+        };
+        let bound = token.key_bound::<T>(Filter::gt, Filter::gt)?;
+        Ok(Some(match filter {
+            Some(filter) => filter.and(bound),
+            None => bound,
+        }))
+    }
+
+    /// Restricts `filter` to the rows of `T` after `token` up to this token,
+    /// included: the rows of the chunk ending here.
+    ///
+    /// Fails with [`QueryError::InvalidQuery`] if either token is malformed.
+    pub fn chunk_filter<T>(
+        &self,
+        filter: Option<Filter>,
+        token: Option<&Self>,
+    ) -> DbmsResult<Filter>
+    where
+        T: TableSchema,
+    {
+        let bound = self.key_bound::<T>(Filter::lt, Filter::le)?;
+        Ok(match Self::resume::<T>(filter, token)? {
+            Some(filter) => filter.and(bound),
+            None => bound,
+        })
+    }
+
+    /// Builds the lexicographic comparison of the primary key of `T` against
+    /// [`Self::key`].
+    ///
+    /// Fails with [`QueryError::InvalidQuery`] if the key doesn't hold one
+    /// value per primary key column, as for a token deserialized from a
+    /// tampered payload.
+    fn key_bound<T>(
+        &self,
+        strict: fn(&str, Value) -> Filter,
+        last: fn(&str, Value) -> Filter,
+    ) -> DbmsResult<Filter>
+    where
+        T: TableSchema,
+    {
+        let columns = T::primary_keys();
+        if self.key.len() != columns.len() {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                "continuation token holds {} key values, but table `{}` has {} primary key columns",
+                self.key.len(),
+                T::table_name(),
+                columns.len()
+            ))));
+        }
+        key_bound(columns, &self.key, strict, last).ok_or_else(|| {
+            DbmsError::Query(QueryError::InvalidQuery(format!(
+                "table `{}` has no primary key to resume from",
+                T::table_name()
+            )))
+        })
+    }
+}
+
+/// Builds the lexicographic comparison of the `columns` against `key`:
+/// `strict` on every column but the last one, compared with `last`.
+///
+/// Returns `None` if `columns` or `key` is empty.
+fn key_bound(
+    columns: &[&str],
+    key: &[Value],
+    strict: fn(&str, Value) -> Filter,
+    last: fn(&str, Value) -> Filter,
+) -> Option<Filter> {
+    match (columns, key) {
+        ([column], [value, ..]) | ([column, ..], [value]) => Some(last(column, value.clone())),
+        ([column, columns @ ..], [value, key @ ..]) => Some(
+            strict(column, value.clone())
+                .or(Filter::eq(column, value.clone()).and(key_bound(columns, key, strict, last)?)),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::prelude::{OrderDirection, Text, Uint32};
+    use crate::tests::{User, UserRecord};
+
+    #[test]
+    fn test_should_build_chunk_query() {
+        let token = ContinuationToken {
+            key: vec![Value::Uint32(Uint32(2))],
+        };
+        let filter = Filter::eq("name", Value::Text(Text("bob".to_string())));
+
+        let query =
+            ContinuationToken::chunk_query::<User>(Some(filter.clone()), Some(&token), 10).unwrap();
+        assert!(!query.all_selected());
+        assert_eq!(query.columns::<User>(), vec!["id".to_string()]);
+        assert_eq!(
+            query.filter,
+            Some(filter.and(Filter::gt("id", Value::Uint32(Uint32(2)))))
+        );
+        assert_eq!(
+            query.order_by,
+            vec![("id".to_string(), OrderDirection::Ascending)]
+        );
+        assert_eq!(query.limit, Some(10));
+    }
+
+    #[test]
+    fn test_should_return_chunk_end() {
+        let records = vec![
+            UserRecord {
+                id: Some(Uint32(1)),
+                name: None,
+            },
+            UserRecord {
+                id: Some(Uint32(4)),
+                name: None,
+            },
+        ];
+
+        let token = ContinuationToken::chunk_end::<User>(&records).unwrap();
+        assert_eq!(token.key, vec![Value::Uint32(Uint32(4))]);
+        assert!(ContinuationToken::chunk_end::<User>(&[]).is_none());
+    }
+
+    #[test]
+    fn test_should_build_chunk_filter() {
+        let after = ContinuationToken {
+            key: vec![Value::Uint32(Uint32(2))],
+        };
+        let end = ContinuationToken {
+            key: vec![Value::Uint32(Uint32(8))],
+        };
+
+        assert_eq!(
+            end.chunk_filter::<User>(None, Some(&after)).unwrap(),
+            Filter::gt("id", Value::Uint32(Uint32(2)))
+                .and(Filter::le("id", Value::Uint32(Uint32(8))))
+        );
+        assert_eq!(
+            end.chunk_filter::<User>(None, None).unwrap(),
+            Filter::le("id", Value::Uint32(Uint32(8)))
+        );
+    }
+
+    #[test]
+    fn test_should_compare_composite_keys_lexicographically() {
+        let bound = key_bound(
+            &["tenant", "id"],
+            &[Value::Uint32(Uint32(1)), Value::Uint32(Uint32(5))],
+            Filter::gt,
+            Filter::gt,
+        );
+
+        assert_eq!(
+            bound,
+            Some(
+                Filter::gt("tenant", Value::Uint32(Uint32(1))).or(Filter::eq(
+                    "tenant",
+                    Value::Uint32(Uint32(1))
+                )
+                .and(Filter::gt("id", Value::Uint32(Uint32(5)))))
+            )
+        );
+        assert_eq!(key_bound(&["id"], &[], Filter::gt, Filter::gt), None);
+    }
+
+    #[test]
+    fn test_should_reject_malformed_token() {
+        for key in [
+            vec![],
+            vec![Value::Uint32(Uint32(1)), Value::Uint32(Uint32(2))],
+        ] {
+            let token = ContinuationToken { key };
+            for result in [
+                ContinuationToken::resume::<User>(None, Some(&token)),
+                ContinuationToken::chunk_query::<User>(None, Some(&token), 10).map(|q| q.filter),
+                token.chunk_filter::<User>(None, None).map(Some),
+            ] {
+                assert!(matches!(
+                    result,
+                    Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+                ));
+            }
+        }
+    }
+}
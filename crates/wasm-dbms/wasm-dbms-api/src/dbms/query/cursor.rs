@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dbms::query::{Filter, OrderDirection, Query};
+use crate::dbms::table::{TableRecord, TableSchema};
+use crate::dbms::value::Value;
+
+/// Keyset pagination cursor: the value of the ordering column on the last
+/// record of a page.
+///
+/// Feed it back through [`QueryBuilder::after`](crate::prelude::QueryBuilder::after)
+/// to request the next page. Unlike `OFFSET`, the next page is selected with a
+/// filter on the ordering column, so an index on that column (or the primary
+/// key) lets the scan start at the cursor instead of at the first record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct PageCursor {
+    /// The ordering column.
+    pub column: String,
+    /// The value of [`Self::column`] on the last record of the page.
+    pub value: Value,
+}
+
+impl PageCursor {
+    /// Restricts `query` to the records following this cursor.
+    ///
+    /// Adds an exclusive bound on [`Self::column`] to the filter:
+    /// `column > value` when the column is sorted ascending, `column < value`
+    /// when sorted descending. When the column is not among the `ORDER BY`
+    /// columns it becomes the first one, sorted ascending. This is what
+    /// [`QueryBuilder::after`](crate::prelude::QueryBuilder::after) does on
+    /// build.
+    pub fn apply(self, mut query: Query) -> Query {
+        let direction = match query.order_by.iter().find(|(col, _)| *col == self.column) {
+            Some((_, direction)) => *direction,
+            None => {
+                query
+                    .order_by
+                    .insert(0, (self.column.clone(), OrderDirection::Ascending));
+                OrderDirection::Ascending
+            }
+        };
+        let bound = match direction {
+            OrderDirection::Ascending => Filter::gt(&self.column, self.value),
+            OrderDirection::Descending => Filter::lt(&self.column, self.value),
+        };
+        query.filter = Some(match query.filter.take() {
+            Some(filter) => filter.and(bound),
+            None => bound,
+        });
+        query
+    }
+
+    /// Returns the cursor of the page following `records`, the result of
+    /// `query` on table `T`.
+    ///
+    /// The ordering column is the first `ORDER BY` column of `query`, or the
+    /// primary key of `T` when the query is unordered. Returns `None` when the
+    /// page is the last one (`query` has no `LIMIT`, or fewer than `LIMIT`
+    /// records were returned) and when the last record carries no value for
+    /// the ordering column: it was not selected, is null, or is a foreign key.
+    pub fn next_page<T>(query: &Query, records: &[T::Record]) -> Option<Self>
+    where
+        T: TableSchema,
+    {
+        let limit = query.limit?;
+        if limit == 0 || records.len() < limit {
+            return None;
+        }
+
+        let column = query
+            .order_by
+            .first()
+            .map(|(column, _)| column.as_str())
+            .unwrap_or(T::primary_key());
+        let value = records
+            .last()?
+            .to_values()
+            .into_iter()
+            .find(|(col_def, _)| col_def.name == column)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_null())?;
+
+        Some(Self {
+            column: column.to_string(),
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::prelude::{Text, Uint32};
+    use crate::tests::{User, UserRecord};
+
+    fn user(id: u32, name: &str) -> UserRecord {
+        UserRecord {
+            id: Some(Uint32(id)),
+            name: Some(Text(name.to_string())),
+        }
+    }
+
+    #[test]
+    fn test_should_return_cursor_on_primary_key() {
+        let query = Query::builder().limit(2).build();
+        let records = vec![user(1, "alice"), user(2, "bob")];
+
+        let cursor = PageCursor::next_page::<User>(&query, &records).unwrap();
+        assert_eq!(cursor.column, "id");
+        assert_eq!(cursor.value, Value::Uint32(Uint32(2)));
+    }
+
+    #[test]
+    fn test_should_return_cursor_on_order_by_column() {
+        let query = Query::builder().order_by_desc("name").limit(1).build();
+        let records = vec![user(3, "carol")];
+
+        let cursor = PageCursor::next_page::<User>(&query, &records).unwrap();
+        assert_eq!(cursor.column, "name");
+        assert_eq!(cursor.value, Value::Text(Text("carol".to_string())));
+    }
+
+    #[test]
+    fn test_should_apply_cursor_to_query() {
+        let cursor = PageCursor {
+            column: "id".to_string(),
+            value: Value::Uint32(Uint32(2)),
+        };
+        let query = cursor.apply(
+            Query::builder()
+                .and_where(Filter::eq("name", Value::Text(Text("bob".to_string()))))
+                .limit(2)
+                .build(),
+        );
+        assert_eq!(
+            query.filter,
+            Some(
+                Filter::eq("name", Value::Text(Text("bob".to_string())))
+                    .and(Filter::gt("id", Value::Uint32(Uint32(2))))
+            )
+        );
+        assert_eq!(
+            query.order_by,
+            vec![("id".to_string(), OrderDirection::Ascending)]
+        );
+        assert_eq!(query.limit, Some(2));
+    }
+
+    #[test]
+    fn test_should_not_return_cursor_on_last_page() {
+        let records = vec![user(1, "alice")];
+
+        let unlimited = Query::default();
+        assert!(PageCursor::next_page::<User>(&unlimited, &records).is_none());
+
+        let short_page = Query::builder().limit(2).build();
+        assert!(PageCursor::next_page::<User>(&short_page, &records).is_none());
+    }
+}
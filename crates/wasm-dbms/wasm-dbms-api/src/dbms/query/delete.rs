@@ -10,6 +10,9 @@ pub enum DeleteBehavior {
     /// Cascade delete to related records.
     /// Any records that reference the deleted records via foreign keys will also be deleted.
     Cascade,
+    /// Set to null the foreign keys of the records referencing the deleted records.
+    /// If a referencing foreign key column is not nullable, the operation will fail.
+    SetNull,
 }
 
 #[cfg(test)]
@@ -21,9 +24,11 @@ mod test {
     fn test_should_create_delete_behavior_variants() {
         let restrict = DeleteBehavior::Restrict;
         let cascade = DeleteBehavior::Cascade;
+        let set_null = DeleteBehavior::SetNull;
 
         assert_eq!(restrict, DeleteBehavior::Restrict);
         assert_eq!(cascade, DeleteBehavior::Cascade);
+        assert_eq!(set_null, DeleteBehavior::SetNull);
     }
 
     #[test]
@@ -58,7 +63,11 @@ mod test {
     #[cfg(feature = "candid")]
     #[test]
     fn test_should_candid_encode_decode_delete_behavior() {
-        for behavior in [DeleteBehavior::Restrict, DeleteBehavior::Cascade] {
+        for behavior in [
+            DeleteBehavior::Restrict,
+            DeleteBehavior::Cascade,
+            DeleteBehavior::SetNull,
+        ] {
             let encoded = candid::encode_one(behavior).expect("failed to encode");
             let decoded: DeleteBehavior = candid::decode_one(&encoded).expect("failed to decode");
             assert_eq!(behavior, decoded);
@@ -1,15 +1,47 @@
-mod json_filter;
+pub(super) mod json_filter;
 mod like;
+mod regex;
+
+use std::cmp::Ordering;
 
 use serde::{Deserialize, Serialize};
 
+use self::json_filter::json_path_matches;
 pub use self::json_filter::{JsonCmp, JsonFilter};
 use crate::dbms::query::QueryResult;
-use crate::dbms::table::ColumnDef;
-use crate::dbms::types::Text;
+use crate::dbms::table::{ColumnDef, ValuesSource};
+use crate::dbms::types::{Text, Uint64};
 use crate::dbms::value::Value;
 use crate::prelude::QueryError;
 
+/// Comparison between two columns of a row, used by
+/// [`Filter::CompareColumns`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub enum ColumnCmp {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+}
+
+impl ColumnCmp {
+    /// Returns whether `ordering`, of the left column against the right one,
+    /// satisfies the comparison.
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            ColumnCmp::Eq => ordering.is_eq(),
+            ColumnCmp::Ne => ordering.is_ne(),
+            ColumnCmp::Gt => ordering.is_gt(),
+            ColumnCmp::Lt => ordering.is_lt(),
+            ColumnCmp::Ge => ordering.is_ge(),
+            ColumnCmp::Le => ordering.is_le(),
+        }
+    }
+}
+
 /// [`super::Query`] filters.
 ///
 /// The first value refers to the column name, and the second to the value to compare against.
@@ -22,10 +54,48 @@ pub enum Filter {
     Lt(String, Value),
     Ge(String, Value),
     In(String, Vec<Value>),
+    /// Matches an `Array` column with at least one element equal to the value.
+    AnyOf(String, Value),
+    /// Matches an `Array` column containing every element of the value.
+    ///
+    /// The value is either a [`Value::Array`] or a single element.
+    AllOf(String, Value),
     /// JSON filter applied to a column.
     Json(String, JsonFilter),
+    /// Matches a `Json` column where any value selected by the JSONPath
+    /// expression equals the value.
+    ///
+    /// The expression starts with `$` and supports `.name`, `['name']`,
+    /// `[0]`, `[*]` and `.*`: `$.users[*].name`. JSON integers compare as
+    /// [`Value::Int64`], floats as [`Value::Decimal`], strings as
+    /// [`Value::Text`], and arrays and objects as [`Value::Json`].
+    JsonPath(String, String, Value),
     Le(String, Value),
+    /// Inclusive range filter: `low <= column <= high`.
+    Between(String, Value, Value),
     Like(String, String),
+    /// Case-insensitive LIKE filter applied to a `Text` column.
+    ILike(String, String),
+    /// Matches a `Text` column starting with the given prefix.
+    StartsWith(String, String),
+    /// Matches a `Text` column ending with the given suffix.
+    EndsWith(String, String),
+    /// Regular expression match on a `Text` column. The pattern is unanchored.
+    Regex(String, String),
+    /// Applies the inner filter to the length in characters of a `Text` column.
+    ///
+    /// The inner filter refers to the length, a [`Value::Uint64`], by the
+    /// column name.
+    StrLen(String, Box<Filter>),
+    /// Applies the inner filter to the length in bytes of a `Blob` column.
+    ///
+    /// The inner filter refers to the length, a [`Value::Uint64`], by the
+    /// column name.
+    BlobLen(String, Box<Filter>),
+    /// Compares two columns of the same row: `left cmp right`.
+    ///
+    /// Mostly used by table checks, e.g. `end_date >= start_date`.
+    CompareColumns(String, ColumnCmp, String),
     NotNull(String),
     IsNull(String),
     And(Box<Filter>, Box<Filter>),
@@ -64,16 +134,89 @@ impl Filter {
         Filter::Le(field.to_string(), value)
     }
 
+    /// Creates a BETWEEN filter, inclusive on both ends.
+    pub fn between(field: &str, low: Value, high: Value) -> Self {
+        Filter::Between(field.to_string(), low, high)
+    }
+
     /// Creates an IN filter.
     pub fn in_list(field: &str, values: Vec<Value>) -> Self {
         Filter::In(field.to_string(), values)
     }
 
+    /// Creates a filter matching an `Array` column containing `value`.
+    pub fn any_of(field: &str, value: Value) -> Self {
+        Filter::AnyOf(field.to_string(), value)
+    }
+
+    /// Creates a filter matching an `Array` column containing all of `values`.
+    pub fn all_of(field: &str, values: Vec<Value>) -> Self {
+        Filter::AllOf(field.to_string(), Value::Array(values))
+    }
+
     /// Creates a LIKE filter.
     pub fn like(field: &str, pattern: &str) -> Self {
         Filter::Like(field.to_string(), pattern.to_string())
     }
 
+    /// Creates a case-insensitive LIKE filter.
+    ///
+    /// Both the column text and the literal parts of the pattern are
+    /// lowercased before matching.
+    pub fn ilike(field: &str, pattern: &str) -> Self {
+        Filter::ILike(field.to_string(), pattern.to_string())
+    }
+
+    /// Creates a filter matching a `Text` column starting with `prefix`.
+    ///
+    /// Unlike [`Filter::like`], the prefix is taken literally.
+    pub fn starts_with(field: &str, prefix: &str) -> Self {
+        Filter::StartsWith(field.to_string(), prefix.to_string())
+    }
+
+    /// Creates a filter matching a `Text` column ending with `suffix`.
+    ///
+    /// Unlike [`Filter::like`], the suffix is taken literally.
+    pub fn ends_with(field: &str, suffix: &str) -> Self {
+        Filter::EndsWith(field.to_string(), suffix.to_string())
+    }
+
+    /// Creates a regular expression filter.
+    ///
+    /// The pattern is unanchored: use `^` and `$` to match the whole value.
+    pub fn regex(field: &str, pattern: &str) -> Self {
+        Filter::Regex(field.to_string(), pattern.to_string())
+    }
+
+    /// Creates a filter applying `filter` to the length of a `Text` column.
+    ///
+    /// ```rust
+    /// use wasm_dbms_api::prelude::{Filter, Uint64, Value};
+    ///
+    /// // names longer than 20 characters
+    /// let filter = Filter::str_len("name", Filter::gt("name", Value::Uint64(Uint64(20))));
+    /// ```
+    pub fn str_len(field: &str, filter: Filter) -> Self {
+        Filter::StrLen(field.to_string(), Box::new(filter))
+    }
+
+    /// Creates a filter applying `filter` to the length of a `Blob` column.
+    pub fn blob_len(field: &str, filter: Filter) -> Self {
+        Filter::BlobLen(field.to_string(), Box::new(filter))
+    }
+
+    /// Creates a filter comparing the columns `left` and `right` of a row.
+    ///
+    /// ```rust
+    /// use wasm_dbms_api::prelude::{ColumnCmp, Filter};
+    ///
+    /// // rows ending on or after their start
+    /// let filter = Filter::compare_columns("end_date", ColumnCmp::Ge, "start_date");
+    /// ```
+    pub fn compare_columns(left: &str, cmp: ColumnCmp, right: &str) -> Self {
+        Filter::CompareColumns(left.to_string(), cmp, right.to_string())
+    }
+
     /// Creates a NOT NULL filter.
     pub fn not_null(field: &str) -> Self {
         Filter::NotNull(field.to_string())
@@ -89,6 +232,20 @@ impl Filter {
         Filter::Json(field.to_string(), json_filter)
     }
 
+    /// Creates a JSONPath equality filter.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use wasm_dbms_api::prelude::{Filter, Value};
+    ///
+    /// // rows where any user is named "alice"
+    /// let filter = Filter::json_path("data", "$.users[*].name", Value::Text("alice".into()));
+    /// ```
+    pub fn json_path(field: &str, path: &str, value: Value) -> Self {
+        Filter::JsonPath(field.to_string(), path.to_string(), value)
+    }
+
     /// Chain two filters with AND.
     pub fn and(self, other: Filter) -> Self {
         Filter::And(Box::new(self), Box::new(other))
@@ -125,24 +282,43 @@ impl Filter {
             }
             Filter::Gt(field, value) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
-                col_value.is_some_and(|v| v > value)
+                Self::compare(col_value, value, Ordering::is_gt)?
             }
             Filter::Lt(field, value) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
-                col_value.is_some_and(|v| v < value)
+                Self::compare(col_value, value, Ordering::is_lt)?
             }
             Filter::Ge(field, value) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
-                col_value.is_some_and(|v| v >= value)
+                Self::compare(col_value, value, Ordering::is_ge)?
             }
             Filter::Le(field, value) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
-                col_value.is_some_and(|v| v <= value)
+                Self::compare(col_value, value, Ordering::is_le)?
+            }
+            Filter::Between(field, low, high) => {
+                Self::check_between_bounds(low, high)?;
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                Self::is_between(col_value, low, high)?
             }
             Filter::In(field, list) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
                 col_value.is_some_and(|v| list.iter().any(|item| item == v))
             }
+            Filter::AnyOf(field, value) => {
+                match Self::resolve_joined_column(field, table_groups)? {
+                    Some(col_value) => {
+                        Self::array_contains(col_value, std::slice::from_ref(value))?
+                    }
+                    None => false,
+                }
+            }
+            Filter::AllOf(field, value) => {
+                match Self::resolve_joined_column(field, table_groups)? {
+                    Some(col_value) => Self::array_contains(col_value, Self::array_items(value))?,
+                    None => false,
+                }
+            }
             Filter::Json(field, json_filter) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
                 let json = col_value.and_then(|v| v.as_json()).ok_or_else(|| {
@@ -150,6 +326,13 @@ impl Filter {
                 })?;
                 return json_filter.matches(json);
             }
+            Filter::JsonPath(field, path, value) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                let json = col_value.and_then(|v| v.as_json()).ok_or_else(|| {
+                    QueryError::InvalidQuery(format!("Column '{field}' is not a Json type"))
+                })?;
+                return json_path_matches(json, path, value);
+            }
             Filter::Like(field, pattern) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
                 if let Some(Value::Text(Text(text))) = col_value {
@@ -166,6 +349,61 @@ impl Filter {
                 }
                 false
             }
+            Filter::ILike(field, pattern) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                if let Some(Value::Text(Text(text))) = col_value {
+                    return Ok(like::Like::parse_case_insensitive(pattern)
+                        .map_err(|e| {
+                            QueryError::InvalidQuery(format!(
+                                "Invalid ILIKE pattern {pattern}: {e}"
+                            ))
+                        })?
+                        .matches(text.to_lowercase()));
+                }
+                if col_value.is_some() {
+                    return Err(QueryError::InvalidQuery(
+                        "ILIKE operator can only be applied to Text values".to_string(),
+                    ));
+                }
+                false
+            }
+            Filter::StartsWith(field, prefix) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                return Self::text_matches(col_value, "STARTS WITH", |text| {
+                    text.starts_with(prefix.as_str())
+                });
+            }
+            Filter::EndsWith(field, suffix) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                return Self::text_matches(col_value, "ENDS WITH", |text| {
+                    text.ends_with(suffix.as_str())
+                });
+            }
+            Filter::Regex(field, pattern) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                if let Some(Value::Text(Text(text))) = col_value {
+                    return regex::is_match(pattern, text);
+                }
+                if col_value.is_some_and(|v| !v.is_null()) {
+                    return Err(QueryError::InvalidQuery(
+                        "REGEX operator can only be applied to Text values".to_string(),
+                    ));
+                }
+                false
+            }
+            Filter::StrLen(field, inner) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                Self::length_matches(field, inner, Self::text_length(col_value)?)?
+            }
+            Filter::BlobLen(field, inner) => {
+                let col_value = Self::resolve_joined_column(field, table_groups)?;
+                Self::length_matches(field, inner, Self::blob_length(col_value)?)?
+            }
+            Filter::CompareColumns(left, cmp, right) => {
+                let left_value = Self::resolve_joined_column(left, table_groups)?;
+                let right_value = Self::resolve_joined_column(right, table_groups)?;
+                Self::compare_columns_values(left_value, *cmp, right_value)?
+            }
             Filter::NotNull(field) => {
                 let col_value = Self::resolve_joined_column(field, table_groups)?;
                 col_value.is_some_and(|v| !v.is_null())
@@ -228,68 +466,324 @@ impl Filter {
         }
     }
 
+    /// Ensures both bounds of a [`Filter::Between`] share the same data type.
+    fn check_between_bounds(low: &Value, high: &Value) -> QueryResult<()> {
+        if low.type_name() != high.type_name() {
+            return Err(QueryError::TypeMismatch {
+                expected: low.type_name().to_string(),
+                found: high.type_name().to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Compares `col_value` with `value` through [`Value::compare_typed`] and
+    /// applies `f` to the ordering.
+    ///
+    /// A missing column or `NULL` on either side never matches; values of
+    /// different data types are an error.
+    fn compare(
+        col_value: Option<&Value>,
+        value: &Value,
+        f: impl FnOnce(Ordering) -> bool,
+    ) -> QueryResult<bool> {
+        match col_value {
+            Some(col_value) if !col_value.is_null() && !value.is_null() => {
+                col_value.compare_typed(value).map(f)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Compares the values of the two columns of a [`Filter::CompareColumns`].
+    ///
+    /// A missing column or `NULL` on either side never matches; values of
+    /// different data types are an error.
+    fn compare_columns_values(
+        left: Option<&Value>,
+        cmp: ColumnCmp,
+        right: Option<&Value>,
+    ) -> QueryResult<bool> {
+        match right {
+            Some(right) => Self::compare(left, right, |ordering| cmp.holds(ordering)),
+            None => Ok(false),
+        }
+    }
+
+    /// Returns whether `col_value` lies in the inclusive `[low, high]` range.
+    ///
+    /// `NULL` never matches.
+    fn is_between(col_value: Option<&Value>, low: &Value, high: &Value) -> QueryResult<bool> {
+        Ok(Self::compare(col_value, low, Ordering::is_ge)?
+            && Self::compare(col_value, high, Ordering::is_le)?)
+    }
+
+    /// Returns whether the `Array` value `col_value` contains every item of `items`.
+    ///
+    /// `NULL` never matches; any other non-array value is an error.
+    fn array_contains(col_value: &Value, items: &[Value]) -> QueryResult<bool> {
+        match col_value {
+            Value::Array(elems) => Ok(items.iter().all(|item| elems.contains(item))),
+            Value::Null => Ok(false),
+            _ => Err(QueryError::InvalidQuery(
+                "ANY OF / ALL OF operators can only be applied to Array values".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the items of a [`Filter::AllOf`] value, treating a non-array value as a single item.
+    fn array_items(value: &Value) -> &[Value] {
+        match value {
+            Value::Array(items) => items,
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    /// Applies the `Text` predicate `f` of the `operator` filter to `col_value`.
+    ///
+    /// A missing column or `NULL` never matches; any other non-text value is an error.
+    fn text_matches(
+        col_value: Option<&Value>,
+        operator: &str,
+        f: impl FnOnce(&str) -> bool,
+    ) -> QueryResult<bool> {
+        match col_value {
+            Some(Value::Text(Text(text))) => Ok(f(text)),
+            None | Some(Value::Null) => Ok(false),
+            Some(_) => Err(QueryError::InvalidQuery(format!(
+                "{operator} operator can only be applied to Text values"
+            ))),
+        }
+    }
+
+    /// Returns the length in characters of the `Text` value `col_value`.
+    ///
+    /// A missing column or `NULL` has no length; any other non-text value is an error.
+    fn text_length(col_value: Option<&Value>) -> QueryResult<Option<usize>> {
+        match col_value {
+            Some(Value::Text(text)) => Ok(Some(text.len())),
+            None | Some(Value::Null) => Ok(None),
+            Some(_) => Err(QueryError::InvalidQuery(
+                "STRLEN operator can only be applied to Text values".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the length in bytes of the `Blob` value `col_value`.
+    ///
+    /// A missing column or `NULL` has no length; any other non-blob value is an error.
+    fn blob_length(col_value: Option<&Value>) -> QueryResult<Option<usize>> {
+        match col_value {
+            Some(Value::Blob(blob)) => Ok(Some(blob.len())),
+            None | Some(Value::Null) => Ok(None),
+            Some(_) => Err(QueryError::InvalidQuery(
+                "BLOBLEN operator can only be applied to Blob values".to_string(),
+            )),
+        }
+    }
+
+    /// Applies the `inner` filter of a [`Filter::StrLen`] or [`Filter::BlobLen`]
+    /// to `len`, exposed under the `field` name.
+    ///
+    /// A missing length never matches.
+    fn length_matches(field: &str, inner: &Filter, len: Option<usize>) -> QueryResult<bool> {
+        let Some(len) = len else {
+            return Ok(false);
+        };
+        Self::matches_single_value(inner, field, &Value::Uint64(Uint64(len as u64)))
+    }
+
+    /// Checks `filter` against a row made of the single column `field`.
+    fn matches_single_value<'a>(
+        filter: &Filter,
+        field: &str,
+        value: &'a Value,
+    ) -> QueryResult<bool> {
+        let lookup: &dyn Fn(&str) -> Option<&'a Value> = &|name| (name == field).then_some(value);
+        filter.matches_by(lookup)
+    }
+
     /// Checks if the given values match the filter.
     pub fn matches(&self, values: &[(ColumnDef, Value)]) -> QueryResult<bool> {
-        let res = match self {
-            Filter::Eq(field, value) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && val == value),
-            Filter::Ne(field, value) => values
+        self.matches_by(&|field| {
+            values
                 .iter()
-                .any(|(col, val)| col.name == *field && val != value),
-            Filter::Gt(field, value) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && val > value),
-            Filter::Lt(field, value) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && val < value),
-            Filter::Ge(field, value) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && val >= value),
-            Filter::Le(field, value) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && val <= value),
-            Filter::In(field, list) => values
+                .find(|(col, _)| col.name == field)
+                .map(|(_, val)| val)
+        })
+    }
+
+    /// Checks if a row made of the single column `field`, holding `value`,
+    /// matches the filter.
+    pub fn matches_value(&self, field: &str, value: &Value) -> QueryResult<bool> {
+        Self::matches_single_value(self, field, value)
+    }
+
+    /// Checks if the given record, along with its eagerly loaded relations,
+    /// matches the filter.
+    ///
+    /// Fields qualified as `relation.column` are looked up among the
+    /// [`ValuesSource::Foreign`] columns loaded for `relation`; any other field
+    /// among the [`ValuesSource::This`] columns. A relation which is not loaded
+    /// for the record, e.g. because its foreign key is `NULL`, never matches.
+    pub fn matches_record(
+        &self,
+        record: &[(ValuesSource, Vec<(ColumnDef, Value)>)],
+    ) -> QueryResult<bool> {
+        self.matches_by(&|field| {
+            let (source, column) = match field.split_once('.') {
+                Some((relation, column)) => (Some(relation), column),
+                None => (None, field),
+            };
+            record
                 .iter()
-                .any(|(col, val)| col.name == *field && list.iter().any(|v| v == val)),
+                .find(|(src, _)| match (src, source) {
+                    (ValuesSource::This, None) => true,
+                    (ValuesSource::Foreign { table, .. }, Some(relation)) => table == relation,
+                    _ => false,
+                })
+                .and_then(|(_, cols)| cols.iter().find(|(col, _)| col.name == column))
+                .map(|(_, val)| val)
+        })
+    }
+
+    /// Returns mutable references to the names of the columns referenced by
+    /// the filter.
+    pub fn fields_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Filter::Eq(field, _)
+            | Filter::Ne(field, _)
+            | Filter::Gt(field, _)
+            | Filter::Lt(field, _)
+            | Filter::Ge(field, _)
+            | Filter::Le(field, _)
+            | Filter::Between(field, _, _)
+            | Filter::In(field, _)
+            | Filter::AnyOf(field, _)
+            | Filter::AllOf(field, _)
+            | Filter::Json(field, _)
+            | Filter::JsonPath(field, _, _)
+            | Filter::Like(field, _)
+            | Filter::ILike(field, _)
+            | Filter::StartsWith(field, _)
+            | Filter::EndsWith(field, _)
+            | Filter::Regex(field, _)
+            | Filter::NotNull(field)
+            | Filter::IsNull(field) => vec![field],
+            Filter::StrLen(field, inner) | Filter::BlobLen(field, inner) => {
+                let mut fields = vec![field];
+                fields.extend(inner.fields_mut());
+                fields
+            }
+            Filter::CompareColumns(left, _, right) => vec![left, right],
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                let mut fields = left.fields_mut();
+                fields.extend(right.fields_mut());
+                fields
+            }
+            Filter::Not(inner) => inner.fields_mut(),
+        }
+    }
+
+    /// Checks the filter against the column values resolved by `lookup`.
+    fn matches_by<'a, F>(&self, lookup: &F) -> QueryResult<bool>
+    where
+        F: Fn(&str) -> Option<&'a Value> + ?Sized,
+    {
+        let res = match self {
+            Filter::Eq(field, value) => lookup(field).is_some_and(|val| val == value),
+            Filter::Ne(field, value) => lookup(field).is_some_and(|val| val != value),
+            Filter::Gt(field, value) => Self::compare(lookup(field), value, Ordering::is_gt)?,
+            Filter::Lt(field, value) => Self::compare(lookup(field), value, Ordering::is_lt)?,
+            Filter::Ge(field, value) => Self::compare(lookup(field), value, Ordering::is_ge)?,
+            Filter::Le(field, value) => Self::compare(lookup(field), value, Ordering::is_le)?,
+            Filter::Between(field, low, high) => {
+                Self::check_between_bounds(low, high)?;
+                Self::is_between(lookup(field), low, high)?
+            }
+            Filter::In(field, list) => {
+                lookup(field).is_some_and(|val| list.iter().any(|v| v == val))
+            }
+            Filter::AnyOf(field, value) => match lookup(field) {
+                Some(val) => Self::array_contains(val, std::slice::from_ref(value))?,
+                None => false,
+            },
+            Filter::AllOf(field, value) => match lookup(field) {
+                Some(val) => Self::array_contains(val, Self::array_items(value))?,
+                None => false,
+            },
             Filter::Json(field, json_filter) => {
-                let json = values
-                    .iter()
-                    .find(|(col, _)| col.name == *field)
-                    .and_then(|(_, val)| val.as_json())
-                    .ok_or_else(|| {
-                        QueryError::InvalidQuery(format!("Column '{field}' is not a Json type"))
-                    })?;
+                let json = lookup(field).and_then(|val| val.as_json()).ok_or_else(|| {
+                    QueryError::InvalidQuery(format!("Column '{field}' is not a Json type"))
+                })?;
                 return json_filter.matches(json);
             }
-            Filter::Like(field, pattern) => {
-                for (col, val) in values {
-                    if col.name == *field {
-                        if let Value::Text(Text(text)) = val {
-                            return Ok(like::Like::parse(pattern)
-                                .map_err(|e| {
-                                    QueryError::InvalidQuery(format!(
-                                        "Invalid LIKE pattern {pattern}: {e}"
-                                    ))
-                                })?
-                                .matches(text));
-                        }
-                        return Err(QueryError::InvalidQuery(
-                            "LIKE operator can only be applied to Text values".to_string(),
-                        ));
-                    }
+            Filter::JsonPath(field, path, value) => {
+                let json = lookup(field).and_then(|val| val.as_json()).ok_or_else(|| {
+                    QueryError::InvalidQuery(format!("Column '{field}' is not a Json type"))
+                })?;
+                return json_path_matches(json, path, value);
+            }
+            Filter::Like(field, pattern) => match lookup(field) {
+                Some(Value::Text(Text(text))) => like::Like::parse(pattern)
+                    .map_err(|e| {
+                        QueryError::InvalidQuery(format!("Invalid LIKE pattern {pattern}: {e}"))
+                    })?
+                    .matches(text),
+                Some(_) => {
+                    return Err(QueryError::InvalidQuery(
+                        "LIKE operator can only be applied to Text values".to_string(),
+                    ));
                 }
-                false
+                None => false,
+            },
+            Filter::ILike(field, pattern) => match lookup(field) {
+                Some(Value::Text(Text(text))) => like::Like::parse_case_insensitive(pattern)
+                    .map_err(|e| {
+                        QueryError::InvalidQuery(format!("Invalid ILIKE pattern {pattern}: {e}"))
+                    })?
+                    .matches(text.to_lowercase()),
+                Some(_) => {
+                    return Err(QueryError::InvalidQuery(
+                        "ILIKE operator can only be applied to Text values".to_string(),
+                    ));
+                }
+                None => false,
+            },
+            Filter::StartsWith(field, prefix) => {
+                return Self::text_matches(lookup(field), "STARTS WITH", |text| {
+                    text.starts_with(prefix.as_str())
+                });
             }
-            Filter::NotNull(field) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && !val.is_null()),
-            Filter::IsNull(field) => values
-                .iter()
-                .any(|(col, val)| col.name == *field && val.is_null()),
-            Filter::And(left, right) => left.matches(values)? && right.matches(values)?,
-            Filter::Or(left, right) => left.matches(values)? || right.matches(values)?,
-            Filter::Not(inner) => !inner.matches(values)?,
+            Filter::EndsWith(field, suffix) => {
+                return Self::text_matches(lookup(field), "ENDS WITH", |text| {
+                    text.ends_with(suffix.as_str())
+                });
+            }
+            Filter::Regex(field, pattern) => match lookup(field) {
+                Some(Value::Text(Text(text))) => return regex::is_match(pattern, text),
+                Some(val) if val.is_null() => false,
+                Some(_) => {
+                    return Err(QueryError::InvalidQuery(
+                        "REGEX operator can only be applied to Text values".to_string(),
+                    ));
+                }
+                None => false,
+            },
+            Filter::StrLen(field, inner) => {
+                Self::length_matches(field, inner, Self::text_length(lookup(field))?)?
+            }
+            Filter::BlobLen(field, inner) => {
+                Self::length_matches(field, inner, Self::blob_length(lookup(field))?)?
+            }
+            Filter::CompareColumns(left, cmp, right) => {
+                Self::compare_columns_values(lookup(left), *cmp, lookup(right))?
+            }
+            Filter::NotNull(field) => lookup(field).is_some_and(|val| !val.is_null()),
+            Filter::IsNull(field) => lookup(field).is_some_and(|val| val.is_null()),
+            Filter::And(left, right) => left.matches_by(lookup)? && right.matches_by(lookup)?,
+            Filter::Or(left, right) => left.matches_by(lookup)? || right.matches_by(lookup)?,
+            Filter::Not(inner) => !inner.matches_by(lookup)?,
         };
 
         Ok(res)
@@ -300,7 +794,7 @@ impl Filter {
 mod tests {
 
     use super::*;
-    use crate::dbms::types::DataTypeKind;
+    use crate::dbms::types::{DataTypeKind, Date, DateTime, Decimal};
 
     #[test]
     fn test_should_check_eq() {
@@ -316,6 +810,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(30.into()),
         )];
@@ -334,6 +829,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(35.into()),
         )];
@@ -342,6 +838,50 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn test_should_match_record_with_foreign_values() {
+        let column = |name| ColumnDef {
+            name,
+            data_type: DataTypeKind::Text,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let record = vec![
+            (
+                ValuesSource::This,
+                vec![(column("name"), Value::Text("post".into()))],
+            ),
+            (
+                ValuesSource::Foreign {
+                    table: "users".to_string(),
+                    column: "user_id".to_string(),
+                },
+                vec![(column("name"), Value::Text("alice".into()))],
+            ),
+        ];
+
+        let filter = Filter::eq("users.name", Value::Text("alice".into()))
+            .and(Filter::eq("name", Value::Text("post".into())));
+        assert!(filter.matches_record(&record).unwrap());
+        assert!(
+            !Filter::eq("name", Value::Text("alice".into()))
+                .matches_record(&record)
+                .unwrap()
+        );
+        // relation not loaded
+        assert!(
+            !Filter::not_null("roles.name")
+                .matches_record(&record)
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_should_check_ne() {
         let filter = Filter::ne("id", Value::Int32(30.into()));
@@ -356,6 +896,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(25.into()),
         )];
@@ -374,6 +915,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(30.into()),
         )];
@@ -396,6 +938,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(25.into()),
         )];
@@ -414,6 +957,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(10.into()),
         )];
@@ -436,6 +980,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(25.into()),
         )];
@@ -454,6 +999,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(40.into()),
         )];
@@ -476,6 +1022,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(25.into()),
         )];
@@ -495,6 +1042,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(30.into()),
         )];
@@ -514,6 +1062,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(20.into()),
         )];
@@ -536,6 +1085,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(25.into()),
         )];
@@ -555,6 +1105,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(20.into()),
         )];
@@ -574,6 +1125,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(35.into()),
         )];
@@ -582,6 +1134,286 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn test_should_check_between_dates() {
+        let column = ColumnDef {
+            name: "born_on",
+            data_type: DataTypeKind::Date,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let date = |year, month, day| Value::Date(Date { year, month, day });
+        let filter = Filter::between("born_on", date(2020, 1, 1), date(2020, 12, 31));
+
+        for (value, expected) in [
+            (date(2020, 1, 1), true),
+            (date(2020, 6, 15), true),
+            (date(2020, 12, 31), true),
+            (date(2019, 12, 31), false),
+            (date(2021, 1, 1), false),
+            (Value::Null, false),
+        ] {
+            let values = vec![(column, value.clone())];
+            assert_eq!(
+                filter.matches(&values).unwrap(),
+                expected,
+                "value: {value:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_should_check_between_datetimes() {
+        let column = ColumnDef {
+            name: "created_at",
+            data_type: DataTypeKind::DateTime,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let at = |hour, minute| {
+            Value::DateTime(DateTime {
+                year: 2024,
+                month: 3,
+                day: 10,
+                hour,
+                minute,
+                second: 0,
+                microsecond: 0,
+                timezone_offset_minutes: 0,
+            })
+        };
+        let filter = Filter::between("created_at", at(9, 0), at(17, 30));
+
+        assert!(filter.matches(&[(column, at(9, 0))]).unwrap());
+        assert!(filter.matches(&[(column, at(12, 45))]).unwrap());
+        assert!(filter.matches(&[(column, at(17, 30))]).unwrap());
+        assert!(!filter.matches(&[(column, at(8, 59))]).unwrap());
+        assert!(!filter.matches(&[(column, at(17, 31))]).unwrap());
+    }
+
+    #[test]
+    fn test_should_check_between_decimals() {
+        let column = ColumnDef {
+            name: "price",
+            data_type: DataTypeKind::Decimal,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let decimal = |n, scale| Value::Decimal(Decimal(rust_decimal::Decimal::new(n, scale)));
+        let filter = Filter::between("price", decimal(1050, 2), decimal(20, 0));
+
+        assert!(filter.matches(&[(column, decimal(1050, 2))]).unwrap());
+        assert!(filter.matches(&[(column, decimal(155, 1))]).unwrap());
+        assert!(filter.matches(&[(column, decimal(2000, 2))]).unwrap());
+        assert!(!filter.matches(&[(column, decimal(1049, 2))]).unwrap());
+        assert!(!filter.matches(&[(column, decimal(2001, 2))]).unwrap());
+    }
+
+    #[test]
+    fn test_should_not_match_between_with_inverted_bounds() {
+        let column = ColumnDef {
+            name: "id",
+            data_type: DataTypeKind::Int32,
+            auto_increment: false,
+            nullable: false,
+            primary_key: true,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let filter = Filter::between("id", Value::Int32(30.into()), Value::Int32(10.into()));
+
+        assert!(
+            !filter
+                .matches(&[(column, Value::Int32(20.into()))])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_error_between_with_mismatched_bounds() {
+        let column = ColumnDef {
+            name: "id",
+            data_type: DataTypeKind::Int32,
+            auto_increment: false,
+            nullable: false,
+            primary_key: true,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let filter = Filter::between("id", Value::Int32(1.into()), Value::Int64(10.into()));
+
+        let result = filter.matches(&[(column, Value::Int32(5.into()))]);
+        assert!(matches!(
+            result,
+            Err(QueryError::TypeMismatch { ref expected, ref found })
+                if expected == "Int32" && found == "Int64"
+        ));
+    }
+
+    #[test]
+    fn test_should_compose_between_in_and_or() {
+        let id = ColumnDef {
+            name: "id",
+            data_type: DataTypeKind::Int32,
+            auto_increment: false,
+            nullable: false,
+            primary_key: true,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let name = ColumnDef {
+            name: "name",
+            data_type: DataTypeKind::Text,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let row = |i: i32, n: &str| {
+            vec![
+                (id, Value::Int32(i.into())),
+                (name, Value::Text(Text(n.to_string()))),
+            ]
+        };
+        let low_ids = Filter::between("id", Value::Int32(1.into()), Value::Int32(10.into()));
+        let high_ids = Filter::between("id", Value::Int32(100.into()), Value::Int32(200.into()));
+
+        let filter = low_ids
+            .clone()
+            .and(Filter::eq("name", Value::Text(Text("alice".to_string()))));
+        assert!(filter.matches(&row(5, "alice")).unwrap());
+        assert!(!filter.matches(&row(5, "bob")).unwrap());
+        assert!(!filter.matches(&row(50, "alice")).unwrap());
+
+        let filter = low_ids.clone().or(high_ids);
+        assert!(filter.matches(&row(10, "bob")).unwrap());
+        assert!(filter.matches(&row(100, "bob")).unwrap());
+        assert!(!filter.matches(&row(50, "bob")).unwrap());
+
+        let filter = low_ids.not();
+        assert!(filter.matches(&row(50, "bob")).unwrap());
+        assert!(!filter.matches(&row(1, "bob")).unwrap());
+
+        // a type mismatch deep in the tree is still reported
+        let filter = Filter::eq("name", Value::Text(Text("bob".to_string()))).and(Filter::between(
+            "id",
+            Value::Int32(1.into()),
+            Value::Uint32(10.into()),
+        ));
+        assert!(matches!(
+            filter.matches(&row(5, "bob")),
+            Err(QueryError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_reject_comparison_between_different_types() {
+        let column = ColumnDef {
+            name: "age",
+            data_type: DataTypeKind::Uint32,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let row = [(column, Value::Uint32(30.into()))];
+
+        for filter in [
+            Filter::gt("age", Value::Text(Text("20".to_string()))),
+            Filter::lt("age", Value::Uint64(40.into())),
+            Filter::ge("age", Value::Int32(30.into())),
+            Filter::le("age", Value::Text(Text("40".to_string()))),
+        ] {
+            let result = filter.matches(&row);
+            assert!(
+                matches!(
+                    &result,
+                    Err(QueryError::TypeMismatch { expected, .. }) if expected == "Uint32"
+                ),
+                "{filter:?} should be rejected, got {result:?}"
+            );
+            let result = filter.matches_joined_row(&[("people", row.to_vec())]);
+            assert!(matches!(result, Err(QueryError::TypeMismatch { .. })));
+        }
+
+        assert!(
+            Filter::ge("age", Value::Uint32(30.into()))
+                .matches(&row)
+                .unwrap()
+        );
+        assert!(
+            !Filter::gt("age", Value::Uint32(30.into()))
+                .matches(&row)
+                .unwrap()
+        );
+        // NULL on either side never matches, whatever the type
+        let null_row = [(column, Value::Null)];
+        assert!(
+            !Filter::lt("age", Value::Uint32(30.into()))
+                .matches(&null_row)
+                .unwrap()
+        );
+        assert!(!Filter::ge("age", Value::Null).matches(&row).unwrap());
+        assert!(
+            !Filter::gt("missing", Value::Text(Text("x".to_string())))
+                .matches(&row)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_serde_roundtrip_between() {
+        let filter = Filter::between("id", Value::Int32(1.into()), Value::Int32(10.into()))
+            .or(Filter::eq("id", Value::Int32(42.into())));
+        let json = serde_json::to_string(&filter).expect("serialization failed");
+        let decoded: Filter = serde_json::from_str(&json).expect("deserialization failed");
+        assert_eq!(filter, decoded);
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode_between() {
+        let filter = Filter::between("id", Value::Int32(1.into()), Value::Int32(10.into()));
+        let buf = candid::encode_one(&filter).expect("Candid encoding failed");
+        let decoded: Filter = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(filter, decoded);
+    }
+
     #[test]
     fn test_should_check_is_null() {
         let filter = Filter::is_null("name");
@@ -596,6 +1428,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Null,
         )];
@@ -614,6 +1447,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Text(Text("Alice".to_string())),
         )];
@@ -636,6 +1470,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Text(Text("Alice".to_string())),
         )];
@@ -653,6 +1488,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Null,
         )];
@@ -661,8 +1497,276 @@ mod tests {
     }
 
     #[test]
-    fn test_should_check_like() {
-        let filter = Filter::like("name", "%ohn%");
+    fn test_should_check_like() {
+        let filter = Filter::like("name", "%ohn%");
+        let values = vec![(
+            ColumnDef {
+                name: "name",
+                data_type: DataTypeKind::Text,
+                auto_increment: false,
+                nullable: false,
+                primary_key: false,
+                unique: false,
+                foreign_key: None,
+                default: None,
+                renamed_from: &[],
+                compress: false,
+            },
+            Value::Text(Text("Johnathan".to_string())),
+        )];
+
+        let result = filter.matches(&values).unwrap();
+        assert!(result);
+
+        let values = vec![(
+            ColumnDef {
+                name: "name",
+                data_type: DataTypeKind::Text,
+                auto_increment: false,
+                nullable: false,
+                primary_key: false,
+                unique: false,
+                foreign_key: None,
+                default: None,
+                renamed_from: &[],
+                compress: false,
+            },
+            Value::Text(Text("Alice".to_string())),
+        )];
+
+        let result = filter.matches(&values).expect("LIKE match failed");
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_should_raise_error_or_like_on_non_text() {
+        let filter = Filter::like("age", "%30%");
+        let values = vec![(
+            ColumnDef {
+                name: "age",
+                data_type: DataTypeKind::Int32,
+                auto_increment: false,
+                nullable: false,
+                primary_key: false,
+                unique: false,
+                foreign_key: None,
+                default: None,
+                renamed_from: &[],
+                compress: false,
+            },
+            Value::Int32(30.into()),
+        )];
+
+        let result = filter.matches(&values);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_should_check_regex() {
+        let column = |data_type| ColumnDef {
+            name: "email",
+            data_type,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let filter = Filter::regex("email", r"^[a-z]+@example\.(com|org)$");
+
+        let values = vec![(
+            column(DataTypeKind::Text),
+            Value::Text(Text("alice@example.org".to_string())),
+        )];
+        assert!(filter.matches(&values).unwrap());
+
+        let values = vec![(
+            column(DataTypeKind::Text),
+            Value::Text(Text("alice@example.net".to_string())),
+        )];
+        assert!(!filter.matches(&values).unwrap());
+
+        let values = vec![(column(DataTypeKind::Text), Value::Null)];
+        assert!(!filter.matches(&values).unwrap());
+
+        let values = vec![(column(DataTypeKind::Int32), Value::Int32(1.into()))];
+        assert!(filter.matches(&values).is_err());
+    }
+
+    #[test]
+    fn test_should_check_starts_with_and_ends_with() {
+        let column = |data_type| ColumnDef {
+            name: "name",
+            data_type,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let values = vec![(
+            column(DataTypeKind::Text),
+            Value::Text(Text("100% pure".to_string())),
+        )];
+        assert!(
+            Filter::starts_with("name", "100%")
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            !Filter::starts_with("name", "pure")
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(Filter::ends_with("name", "pure").matches(&values).unwrap());
+        assert!(!Filter::ends_with("name", "100%").matches(&values).unwrap());
+        // wildcards are taken literally
+        assert!(!Filter::starts_with("name", "%").matches(&values).unwrap());
+
+        let values = vec![(column(DataTypeKind::Text), Value::Null)];
+        assert!(!Filter::starts_with("name", "").matches(&values).unwrap());
+        assert!(!Filter::ends_with("name", "").matches(&values).unwrap());
+
+        let values = vec![(column(DataTypeKind::Int32), Value::Int32(1.into()))];
+        assert!(Filter::starts_with("name", "1").matches(&values).is_err());
+        assert!(Filter::ends_with("name", "1").matches(&values).is_err());
+    }
+
+    #[test]
+    fn test_should_compare_columns() {
+        let column = |name| ColumnDef {
+            name,
+            data_type: DataTypeKind::Int32,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let values = vec![
+            (column("price"), Value::Int32(100.into())),
+            (column("discount"), Value::Int32(20.into())),
+            (column("cost"), Value::Int32(100.into())),
+            (column("rebate"), Value::Null),
+        ];
+        let cmp = |left, cmp, right| Filter::compare_columns(left, cmp, right).matches(&values);
+
+        assert!(cmp("discount", ColumnCmp::Le, "price").unwrap());
+        assert!(cmp("price", ColumnCmp::Gt, "discount").unwrap());
+        assert!(cmp("price", ColumnCmp::Eq, "cost").unwrap());
+        assert!(cmp("price", ColumnCmp::Ge, "cost").unwrap());
+        assert!(!cmp("price", ColumnCmp::Ne, "cost").unwrap());
+        assert!(!cmp("price", ColumnCmp::Lt, "discount").unwrap());
+        // NULL and missing columns never match
+        assert!(!cmp("rebate", ColumnCmp::Le, "price").unwrap());
+        assert!(!cmp("price", ColumnCmp::Ne, "missing").unwrap());
+
+        let values = vec![
+            (column("price"), Value::Int32(100.into())),
+            (column("name"), Value::Text(Text("100".to_string()))),
+        ];
+        assert!(
+            Filter::compare_columns("price", ColumnCmp::Eq, "name")
+                .matches(&values)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_should_check_str_len_and_blob_len() {
+        let column = |name, data_type| ColumnDef {
+            name,
+            data_type,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let len = |n: u64| Value::Uint64(Uint64(n));
+        let values = vec![
+            (
+                column("name", DataTypeKind::Text),
+                Value::Text(Text("città".to_string())),
+            ),
+            (
+                column("data", DataTypeKind::Blob),
+                Value::Blob(vec![0xC3, 0xA0].into()),
+            ),
+        ];
+        assert!(
+            Filter::str_len("name", Filter::eq("name", len(5)))
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            Filter::str_len("name", Filter::between("name", len(1), len(3)).not())
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            !Filter::str_len("name", Filter::gt("name", len(5)))
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            Filter::blob_len("data", Filter::le("data", len(2)))
+                .matches(&values)
+                .unwrap()
+        );
+        // the inner filter only sees the length
+        assert!(
+            !Filter::str_len("name", Filter::not_null("data"))
+                .matches(&values)
+                .unwrap()
+        );
+        // nested length filters
+        assert!(
+            Filter::str_len("name", Filter::str_len("name", Filter::eq("name", len(1))))
+                .matches(&values)
+                .is_err()
+        );
+
+        let values = vec![(column("name", DataTypeKind::Text), Value::Null)];
+        assert!(
+            !Filter::str_len("name", Filter::ge("name", len(0)))
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            !Filter::str_len("missing", Filter::ge("missing", len(0)))
+                .matches(&values)
+                .unwrap()
+        );
+
+        let values = vec![(column("name", DataTypeKind::Int32), Value::Int32(1.into()))];
+        assert!(
+            Filter::str_len("name", Filter::ge("name", len(0)))
+                .matches(&values)
+                .is_err()
+        );
+        assert!(
+            Filter::blob_len("name", Filter::ge("name", len(0)))
+                .matches(&values)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_should_raise_invalid_regex() {
+        let filter = Filter::regex("name", "[a-z");
         let values = vec![(
             ColumnDef {
                 name: "name",
@@ -674,13 +1778,20 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
-            Value::Text(Text("Johnathan".to_string())),
+            Value::Text(Text("abc".to_string())),
         )];
 
-        let result = filter.matches(&values).unwrap();
-        assert!(result);
+        assert!(matches!(
+            filter.matches(&values),
+            Err(QueryError::InvalidRegex(_))
+        ));
+    }
 
+    #[test]
+    fn test_should_escape_like() {
+        let filter = Filter::like("name", "100%% match");
         let values = vec![(
             ColumnDef {
                 name: "name",
@@ -692,56 +1803,111 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
-            Value::Text(Text("Alice".to_string())),
+            Value::Text(Text("100% match".to_string())),
         )];
 
-        let result = filter.matches(&values).expect("LIKE match failed");
-        assert!(!result);
+        let result = filter.matches(&values).unwrap();
+        assert!(result);
     }
 
     #[test]
-    fn test_should_raise_error_or_like_on_non_text() {
-        let filter = Filter::like("age", "%30%");
-        let values = vec![(
-            ColumnDef {
-                name: "age",
-                data_type: DataTypeKind::Int32,
-                auto_increment: false,
-                nullable: false,
-                primary_key: false,
-                unique: false,
-                foreign_key: None,
-                default: None,
-                renamed_from: &[],
-            },
-            Value::Int32(30.into()),
-        )];
+    fn test_should_check_ilike() {
+        let column = |data_type| ColumnDef {
+            name: "name",
+            data_type,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let text = |s: &str| vec![(column(DataTypeKind::Text), Value::Text(Text(s.to_string())))];
+
+        let filter = Filter::ilike("name", "%OHN%");
+        assert!(filter.matches(&text("Johnathan")).unwrap());
+        assert!(filter.matches(&text("JOHN")).unwrap());
+        assert!(!filter.matches(&text("Alice")).unwrap());
+
+        // wildcards keep their meaning
+        let filter = Filter::ilike("name", "j_HN");
+        assert!(filter.matches(&text("John")).unwrap());
+        assert!(!filter.matches(&text("Jhn")).unwrap());
+
+        // escapes are kept
+        let filter = Filter::ilike("name", "100\\% MATCH");
+        assert!(filter.matches(&text("100% match")).unwrap());
+        assert!(!filter.matches(&text("1000 match")).unwrap());
+
+        // unicode case folding
+        let filter = Filter::ilike("name", "\u{00c9}MILE%");
+        assert!(filter.matches(&text("\u{00e9}mile")).unwrap());
+
+        // LIKE is still case sensitive
+        assert!(
+            !Filter::like("name", "%OHN%")
+                .matches(&text("John"))
+                .unwrap()
+        );
 
-        let result = filter.matches(&values);
-        assert!(result.is_err());
+        // missing column
+        assert!(
+            !Filter::ilike("surname", "%")
+                .matches(&text("John"))
+                .unwrap()
+        );
+
+        // non-text column
+        let values = vec![(column(DataTypeKind::Int32), Value::Int32(30.into()))];
+        assert!(Filter::ilike("name", "%30%").matches(&values).is_err());
+        let values = vec![(column(DataTypeKind::Text), Value::Null)];
+        assert!(Filter::ilike("name", "%").matches(&values).is_err());
     }
 
     #[test]
-    fn test_should_escape_like() {
-        let filter = Filter::like("name", "100%% match");
-        let values = vec![(
-            ColumnDef {
-                name: "name",
-                data_type: DataTypeKind::Text,
-                auto_increment: false,
-                nullable: false,
-                primary_key: false,
-                unique: false,
-                foreign_key: None,
-                default: None,
-                renamed_from: &[],
-            },
-            Value::Text(Text("100% match".to_string())),
+    fn test_should_match_ilike_filter_on_joined_row() {
+        let column = |name, data_type| ColumnDef {
+            name,
+            data_type,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let values: Vec<(&str, Vec<(ColumnDef, Value)>)> = vec![(
+            "posts",
+            vec![
+                (
+                    column("title", DataTypeKind::Text),
+                    Value::Text(Text("Hello World".to_string())),
+                ),
+                (column("id", DataTypeKind::Int32), Value::Int32(1.into())),
+            ],
         )];
 
-        let result = filter.matches(&values).unwrap();
-        assert!(result);
+        assert!(
+            Filter::ilike("posts.title", "%ELLO w%")
+                .matches_joined_row(&values)
+                .unwrap()
+        );
+        assert!(
+            !Filter::ilike("posts.title", "%xyz%")
+                .matches_joined_row(&values)
+                .unwrap()
+        );
+        assert!(
+            Filter::ilike("posts.id", "%1%")
+                .matches_joined_row(&values)
+                .is_err()
+        );
     }
 
     #[test]
@@ -762,6 +1928,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(30.into()),
             ),
@@ -776,6 +1943,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(20.into()),
             ),
@@ -790,6 +1958,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text(Text("Alice".to_string())),
             ),
@@ -811,6 +1980,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(25.into()),
             ),
@@ -825,6 +1995,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(16.into()),
             ),
@@ -839,6 +2010,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Null,
             ),
@@ -862,6 +2034,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Null,
         )];
@@ -880,6 +2053,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Text(Text("Bob".to_string())),
         )];
@@ -909,6 +2083,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(20.into()),
         )];
@@ -926,6 +2101,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Int32(40.into()),
         )];
@@ -933,6 +2109,106 @@ mod tests {
         assert!(!result);
     }
 
+    fn tags_column(value: Value) -> Vec<(ColumnDef, Value)> {
+        vec![(
+            ColumnDef {
+                name: "tags",
+                data_type: DataTypeKind::Array(&DataTypeKind::Text),
+                auto_increment: false,
+                nullable: true,
+                primary_key: false,
+                unique: false,
+                foreign_key: None,
+                default: None,
+                renamed_from: &[],
+                compress: false,
+            },
+            value,
+        )]
+    }
+
+    #[test]
+    fn test_should_check_any_of() {
+        let values = tags_column(Value::Array(vec![
+            Value::Text("rust".into()),
+            Value::Text("wasm".into()),
+        ]));
+        assert!(
+            Filter::any_of("tags", Value::Text("wasm".into()))
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            !Filter::any_of("tags", Value::Text("go".into()))
+                .matches(&values)
+                .unwrap()
+        );
+        assert!(
+            !Filter::any_of("tags", Value::Text("rust".into()))
+                .matches(&tags_column(Value::Null))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_check_all_of() {
+        let values = tags_column(Value::Array(vec![
+            Value::Text("rust".into()),
+            Value::Text("wasm".into()),
+            Value::Text("ic".into()),
+        ]));
+        assert!(
+            Filter::all_of(
+                "tags",
+                vec![Value::Text("ic".into()), Value::Text("rust".into())]
+            )
+            .matches(&values)
+            .unwrap()
+        );
+        assert!(
+            !Filter::all_of(
+                "tags",
+                vec![Value::Text("ic".into()), Value::Text("go".into())]
+            )
+            .matches(&values)
+            .unwrap()
+        );
+        assert!(Filter::all_of("tags", vec![]).matches(&values).unwrap());
+        assert!(
+            Filter::AllOf("tags".to_string(), Value::Text("wasm".into()))
+                .matches(&values)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_raise_error_any_of_on_non_array() {
+        let values = tags_column(Value::Text("rust".into()));
+        let result = Filter::any_of("tags", Value::Text("rust".into())).matches(&values);
+        assert!(matches!(result, Err(QueryError::InvalidQuery(_))));
+    }
+
+    #[test]
+    fn test_should_match_any_of_filter_on_joined_row() {
+        let groups = [(
+            "posts",
+            tags_column(Value::Array(vec![Value::Text("rust".into())])),
+        )];
+        assert!(
+            Filter::any_of("posts.tags", Value::Text("rust".into()))
+                .matches_joined_row(&groups)
+                .unwrap()
+        );
+        assert!(
+            !Filter::all_of(
+                "tags",
+                vec![Value::Text("rust".into()), Value::Text("go".into())]
+            )
+            .matches_joined_row(&groups)
+            .unwrap()
+        );
+    }
+
     #[test]
     fn test_should_check_json_extract() {
         use std::str::FromStr;
@@ -955,6 +2231,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -985,6 +2262,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1013,6 +2291,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1040,6 +2319,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1060,6 +2340,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1082,6 +2363,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Text(Text("Alice".to_string())),
         )];
@@ -1090,6 +2372,82 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_should_check_json_path() {
+        use std::str::FromStr;
+
+        use crate::dbms::types::Json;
+
+        let json_value = Json::from_str(
+            r#"{"users": [{"name": "Alice", "age": 30}, {"name": "Bob", "tags": ["x", "y"]}]}"#,
+        )
+        .unwrap();
+        let values = vec![(
+            ColumnDef {
+                name: "data",
+                data_type: DataTypeKind::Json,
+                auto_increment: false,
+                nullable: false,
+                primary_key: false,
+                unique: false,
+                foreign_key: None,
+                default: None,
+                renamed_from: &[],
+                compress: false,
+            },
+            Value::Json(json_value),
+        )];
+        let matches = |path: &str, value: Value| {
+            Filter::json_path("data", path, value)
+                .matches(&values)
+                .unwrap()
+        };
+
+        assert!(matches("$.users[*].name", Value::Text("Bob".into())));
+        assert!(matches("$.users[0].age", Value::Int64(30.into())));
+        assert!(matches("$['users'][1].tags[*]", Value::Text("y".into())));
+        assert!(matches(
+            "$.users[1].tags",
+            Value::Json(Json::from_str(r#"["x", "y"]"#).unwrap())
+        ));
+        assert!(!matches("$.users[1].tags", Value::Text("x".into())));
+        assert!(!matches("$.users[*].name", Value::Text("Carol".into())));
+        assert!(!matches("$.users[5].name", Value::Text("Alice".into())));
+    }
+
+    #[test]
+    fn test_should_error_invalid_json_path() {
+        use std::str::FromStr;
+
+        use crate::dbms::types::Json;
+
+        let values = vec![(
+            ColumnDef {
+                name: "data",
+                data_type: DataTypeKind::Json,
+                auto_increment: false,
+                nullable: false,
+                primary_key: false,
+                unique: false,
+                foreign_key: None,
+                default: None,
+                renamed_from: &[],
+                compress: false,
+            },
+            Value::Json(Json::from_str(r#"{"a": 1}"#).unwrap()),
+        )];
+
+        for path in ["a", "$..a", "$[?(@.a)]"] {
+            let filter = Filter::json_path("data", path, Value::Int64(1.into()));
+            assert!(matches!(
+                filter.matches(&values),
+                Err(QueryError::InvalidQuery(_))
+            ));
+        }
+        let filter = Filter::json_path("data", "$.a", Value::Int64(1.into()));
+        assert!(filter.matches(&values).unwrap());
+    }
+
     #[test]
     fn test_should_check_json_combined_with_and() {
         use std::str::FromStr;
@@ -1115,6 +2473,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1152,6 +2511,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1170,6 +2530,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1188,6 +2549,7 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             Value::Json(json_value),
         )];
@@ -1218,6 +2580,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             ),
@@ -1232,6 +2595,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Json(json_value),
             ),
@@ -1254,6 +2618,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(2.into()),
             ),
@@ -1268,6 +2633,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Json(json_value),
             ),
@@ -1295,6 +2661,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             )],
@@ -1319,6 +2686,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Int32(1.into()),
                 )],
@@ -1336,6 +2704,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text(Text("Hello".to_string())),
                 )],
@@ -1361,6 +2730,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Int32(1.into()),
                 )],
@@ -1378,6 +2748,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Int32(2.into()),
                 )],
@@ -1406,6 +2777,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Int32(1.into()),
                 )],
@@ -1423,6 +2795,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text(Text("Hello".to_string())),
                 )],
@@ -1447,6 +2820,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             )],
@@ -1470,6 +2844,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             )],
@@ -1493,6 +2868,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(25.into()),
             )],
@@ -1519,6 +2895,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(25.into()),
             )],
@@ -1545,6 +2922,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(25.into()),
             )],
@@ -1571,6 +2949,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(25.into()),
             )],
@@ -1581,6 +2960,48 @@ mod tests {
         assert!(!filter.matches_joined_row(&values).unwrap());
     }
 
+    #[test]
+    fn test_should_match_between_filter_on_joined_row() {
+        let filter = Filter::between(
+            "users.age",
+            Value::Int32(18.into()),
+            Value::Int32(30.into()),
+        );
+        let values: Vec<(&str, Vec<(ColumnDef, Value)>)> = vec![(
+            "users",
+            vec![(
+                ColumnDef {
+                    name: "age",
+                    data_type: DataTypeKind::Int32,
+                    auto_increment: false,
+                    nullable: false,
+                    primary_key: false,
+                    unique: false,
+                    foreign_key: None,
+                    default: None,
+                    renamed_from: &[],
+                    compress: false,
+                },
+                Value::Int32(30.into()),
+            )],
+        )];
+        assert!(filter.matches_joined_row(&values).unwrap());
+
+        let filter = Filter::between(
+            "users.age",
+            Value::Int32(31.into()),
+            Value::Int32(40.into()),
+        );
+        assert!(!filter.matches_joined_row(&values).unwrap());
+
+        let filter = Filter::between(
+            "users.age",
+            Value::Int32(18.into()),
+            Value::Text(Text("40".to_string())),
+        );
+        assert!(filter.matches_joined_row(&values).is_err());
+    }
+
     #[test]
     fn test_should_match_in_filter_on_joined_row() {
         let filter = Filter::in_list(
@@ -1604,6 +3025,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(2.into()),
             )],
@@ -1633,6 +3055,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text(Text("Hello World".to_string())),
             )],
@@ -1643,6 +3066,90 @@ mod tests {
         assert!(!filter.matches_joined_row(&values).unwrap());
     }
 
+    #[test]
+    fn test_should_match_regex_filter_on_joined_row() {
+        let filter = Filter::regex("posts.title", r"^Hello\s+\w+$");
+        let values: Vec<(&str, Vec<(ColumnDef, Value)>)> = vec![(
+            "posts",
+            vec![(
+                ColumnDef {
+                    name: "title",
+                    data_type: DataTypeKind::Text,
+                    auto_increment: false,
+                    nullable: false,
+                    primary_key: false,
+                    unique: false,
+                    foreign_key: None,
+                    default: None,
+                    renamed_from: &[],
+                    compress: false,
+                },
+                Value::Text(Text("Hello World".to_string())),
+            )],
+        )];
+        assert!(filter.matches_joined_row(&values).unwrap());
+
+        let filter = Filter::regex("posts.title", "^World");
+        assert!(!filter.matches_joined_row(&values).unwrap());
+    }
+
+    #[test]
+    fn test_should_match_starts_with_and_ends_with_on_joined_row() {
+        let values: Vec<(&str, Vec<(ColumnDef, Value)>)> = vec![(
+            "posts",
+            vec![(
+                ColumnDef {
+                    name: "title",
+                    data_type: DataTypeKind::Text,
+                    auto_increment: false,
+                    nullable: false,
+                    primary_key: false,
+                    unique: false,
+                    foreign_key: None,
+                    default: None,
+                    renamed_from: &[],
+                    compress: false,
+                },
+                Value::Text(Text("Hello World".to_string())),
+            )],
+        )];
+        let filter = Filter::starts_with("posts.title", "Hello");
+        assert!(filter.matches_joined_row(&values).unwrap());
+        let filter = Filter::ends_with("title", "World");
+        assert!(filter.matches_joined_row(&values).unwrap());
+        let filter = Filter::ends_with("posts.title", "Hello");
+        assert!(!filter.matches_joined_row(&values).unwrap());
+    }
+
+    #[test]
+    fn test_should_match_str_len_filter_on_joined_row() {
+        let values: Vec<(&str, Vec<(ColumnDef, Value)>)> = vec![(
+            "posts",
+            vec![(
+                ColumnDef {
+                    name: "title",
+                    data_type: DataTypeKind::Text,
+                    auto_increment: false,
+                    nullable: false,
+                    primary_key: false,
+                    unique: false,
+                    foreign_key: None,
+                    default: None,
+                    renamed_from: &[],
+                    compress: false,
+                },
+                Value::Text(Text("Hello World".to_string())),
+            )],
+        )];
+        let filter = Filter::str_len(
+            "posts.title",
+            Filter::eq("posts.title", Value::Uint64(Uint64(11))),
+        );
+        assert!(filter.matches_joined_row(&values).unwrap());
+        let filter = Filter::str_len("title", Filter::lt("title", Value::Uint64(Uint64(11))));
+        assert!(!filter.matches_joined_row(&values).unwrap());
+    }
+
     #[test]
     fn test_should_error_like_on_non_text_in_joined_row() {
         let filter = Filter::like("users.id", "%1%");
@@ -1659,6 +3166,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             )],
@@ -1682,6 +3190,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text(Text("Alice".to_string())),
             )],
@@ -1701,6 +3210,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Null,
             )],
@@ -1724,6 +3234,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Null,
             )],
@@ -1743,6 +3254,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text(Text("Alice".to_string())),
             )],
@@ -1767,6 +3279,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(2.into()),
             )],
@@ -1786,6 +3299,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(99.into()),
             )],
@@ -1809,6 +3323,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(99.into()),
             )],
@@ -1828,6 +3343,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             )],
@@ -1859,6 +3375,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Json(json_value),
             )],
@@ -1882,6 +3399,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text(Text("Alice".to_string())),
             )],
@@ -1905,6 +3423,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Int32(1.into()),
             )],
@@ -1928,6 +3447,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text(Text("hello".to_string())),
             )],
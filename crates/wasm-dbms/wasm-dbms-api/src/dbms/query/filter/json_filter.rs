@@ -1,7 +1,7 @@
 //! This module contains utilities for filtering JSON data in the DBMS API.
 //!
 //! It provides JSON-specific filter operations including structural containment,
-//! path-based value extraction, and key existence checks.
+//! path-based value extraction, key existence checks, and JSONPath equality.
 
 mod contains;
 mod extract;
@@ -10,14 +10,15 @@ pub mod path;
 use serde::{Deserialize, Serialize};
 
 use self::contains::json_contains;
-use self::extract::extract_at_path;
-use self::path::parse_path;
-use crate::dbms::query::QueryResult;
+use self::extract::{extract_any_at_path, extract_at_path, extract_nodes_at_path};
+use self::path::{PathSegment, parse_json_path, parse_path};
+use crate::dbms::query::{QueryError, QueryResult};
 use crate::prelude::{Json, Value};
 
 /// Represents comparison operations for JSON values.
 ///
-/// Used with [`JsonFilter::Extract`] to compare extracted values against targets.
+/// Used with [`JsonFilter::Extract`] and [`JsonFilter::AnyExtract`] to compare
+/// extracted values against targets.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
 pub enum JsonCmp {
@@ -69,13 +70,34 @@ impl JsonCmp {
             (Some(v), JsonCmp::In(list)) => list.contains(&v),
         }
     }
+
+    /// Matches an array element extracted by [`JsonFilter::AnyExtract`].
+    ///
+    /// Unlike [`JsonCmp::matches`], an ordering comparison never matches an
+    /// element of another type than its target, so that `Gt(Int64(3))` does
+    /// not match the `"a"` of a mixed array.
+    fn matches_element(&self, value: Value) -> bool {
+        match self {
+            JsonCmp::Gt(target)
+            | JsonCmp::Lt(target)
+            | JsonCmp::Ge(target)
+            | JsonCmp::Le(target)
+                if value.type_name() != target.type_name() =>
+            {
+                false
+            }
+            _ => self.matches(Some(value)),
+        }
+    }
 }
 
 /// JSON-specific filter operations.
 ///
-/// Provides three types of filtering on JSON columns:
+/// Provides four types of filtering on JSON columns:
 /// - **Contains**: Structural containment check (PostgreSQL `@>` style)
 /// - **Extract**: Extract value at path and apply comparison
+/// - **AnyExtract**: Extract the array elements at path and match if any
+///   satisfies the comparison
 /// - **HasKey**: Check if a path exists in the JSON
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
@@ -91,6 +113,14 @@ pub enum JsonFilter {
     ///
     /// Paths use dot notation with bracket array indices: `user.items[0].name`
     Extract(String, JsonCmp),
+    /// Extract the values at the specified JSON path and match if any of them
+    /// satisfies the comparison.
+    ///
+    /// When the path resolves to an array, the comparison is applied to each
+    /// of its elements; otherwise it behaves like [`JsonFilter::Extract`].
+    /// The path may hold `[*]` wildcard segments, which fan out to every
+    /// element of an array: `items[*].status`.
+    AnyExtract(String, JsonCmp),
     /// Check whether a path/key exists in the JSON structure.
     ///
     /// Paths use dot notation with bracket array indices: `user.items[0].name`
@@ -111,17 +141,27 @@ impl JsonFilter {
     ///
     /// # Errors
     ///
-    /// Returns [`QueryError::InvalidQuery`] if the path syntax is invalid.
+    /// Returns [`QueryError::InvalidQuery`] if the path syntax is invalid, or
+    /// if a path other than the one of [`JsonFilter::AnyExtract`] holds a
+    /// `[*]` wildcard.
     pub fn matches(&self, json: &Json) -> QueryResult<bool> {
         match self {
             JsonFilter::Contains(pattern) => Ok(json_contains(json.value(), pattern.value())),
             JsonFilter::Extract(path, cmp) => {
-                let segments = parse_path(path)?;
+                let segments = parse_single_value_path(path)?;
                 let extracted = extract_at_path(json, &segments);
                 Ok(cmp.matches(extracted))
             }
-            JsonFilter::HasKey(path) => {
+            JsonFilter::AnyExtract(path, cmp) => {
                 let segments = parse_path(path)?;
+                let matches = match extract_any_at_path(json, &segments) {
+                    Some(values) => values.into_iter().any(|value| cmp.matches_element(value)),
+                    None => cmp.matches(None),
+                };
+                Ok(matches)
+            }
+            JsonFilter::HasKey(path) => {
+                let segments = parse_single_value_path(path)?;
                 Ok(extract_at_path(json, &segments).is_some())
             }
         }
@@ -177,12 +217,45 @@ impl JsonFilter {
         JsonFilter::Extract(path.to_string(), JsonCmp::NotNull)
     }
 
+    /// Creates an `AnyExtract` filter, matching if any of the values at `path`
+    /// satisfies `cmp`.
+    pub fn extract_any(path: &str, cmp: JsonCmp) -> Self {
+        JsonFilter::AnyExtract(path.to_string(), cmp)
+    }
+
     /// Creates a `HasKey` filter.
     pub fn has_key(path: &str) -> Self {
         JsonFilter::HasKey(path.to_string())
     }
 }
 
+/// Returns whether any of the values the JSONPath `path` selects in `json`
+/// equals `target`, as [`Filter::JsonPath`](crate::prelude::Filter::JsonPath)
+/// matches.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidQuery`] if `path` is not a valid JSONPath
+/// expression, see [`parse_json_path`].
+pub fn json_path_matches(json: &Json, path: &str, target: &Value) -> QueryResult<bool> {
+    let segments = parse_json_path(path)?;
+    Ok(extract_nodes_at_path(json, &segments)
+        .iter()
+        .any(|value| value == target))
+}
+
+/// Parses a path which must resolve to a single value, so holds no `[*]`
+/// wildcard.
+fn parse_single_value_path(path: &str) -> QueryResult<Vec<PathSegment>> {
+    let segments = parse_path(path)?;
+    if segments.contains(&PathSegment::Wildcard) {
+        return Err(QueryError::InvalidQuery(format!(
+            "Wildcard in JSON path '{path}' is only supported by AnyExtract"
+        )));
+    }
+    Ok(segments)
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -517,6 +590,131 @@ mod tests {
         assert!(matches!(filter, JsonFilter::HasKey(_)));
     }
 
+    #[test]
+    fn test_extract_any_builder() {
+        let filter = JsonFilter::extract_any("tags", JsonCmp::Eq(Value::Text("rust".into())));
+        assert!(matches!(filter, JsonFilter::AnyExtract(_, JsonCmp::Eq(_))));
+    }
+
+    // ===== AnyExtract Tests =====
+
+    #[test]
+    fn test_any_extract_matches_any_array_element() {
+        let json = j(json!({"tags": ["rust", "db"]}));
+        let filter =
+            |tag: &str| JsonFilter::extract_any("tags", JsonCmp::Eq(Value::Text(tag.into())));
+
+        assert!(filter("rust").matches(&json).unwrap());
+        assert!(filter("db").matches(&json).unwrap());
+        assert!(!filter("wasm").matches(&json).unwrap());
+        // Extract compares the array as a whole
+        assert!(
+            !JsonFilter::extract_eq("tags", Value::Text("rust".into()))
+                .matches(&json)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_any_extract_on_scalar_behaves_like_extract() {
+        let json = j(json!({"age": 30, "name": null}));
+        for cmp in [
+            JsonCmp::Eq(Value::Int64(30.into())),
+            JsonCmp::Gt(Value::Int64(18.into())),
+            JsonCmp::Lt(Value::Int64(18.into())),
+            JsonCmp::IsNull,
+            JsonCmp::NotNull,
+        ] {
+            for path in ["age", "missing"] {
+                assert_eq!(
+                    JsonFilter::extract_any(path, cmp.clone())
+                        .matches(&json)
+                        .unwrap(),
+                    JsonFilter::Extract(path.to_string(), cmp.clone())
+                        .matches(&json)
+                        .unwrap(),
+                    "{path} {cmp:?}"
+                );
+            }
+        }
+
+        let matches = |cmp| JsonFilter::extract_any("name", cmp).matches(&json).unwrap();
+        assert!(matches(JsonCmp::IsNull));
+        assert!(!matches(JsonCmp::NotNull));
+        // null is not ordered against numbers
+        assert!(!matches(JsonCmp::Gt(Value::Int64(18.into()))));
+    }
+
+    #[test]
+    fn test_any_extract_with_wildcard() {
+        let json = j(json!({"items": [
+            {"status": "open", "qty": 1},
+            {"status": "closed", "qty": 5},
+        ]}));
+
+        let filter =
+            JsonFilter::extract_any("items[*].status", JsonCmp::Eq(Value::Text("closed".into())));
+        assert!(filter.matches(&json).unwrap());
+        let filter = JsonFilter::extract_any("items[*].qty", JsonCmp::Gt(Value::Int64(3.into())));
+        assert!(filter.matches(&json).unwrap());
+        let filter = JsonFilter::extract_any("items[*].qty", JsonCmp::Gt(Value::Int64(5.into())));
+        assert!(!filter.matches(&json).unwrap());
+    }
+
+    #[test]
+    fn test_any_extract_nested_arrays() {
+        let json = j(json!({"matrix": [[1, 2], [3]]}));
+
+        let filter = JsonFilter::extract_any("matrix[*]", JsonCmp::Eq(Value::Int64(3.into())));
+        assert!(filter.matches(&json).unwrap());
+        // without the wildcard the elements are the inner arrays
+        let filter = JsonFilter::extract_any("matrix", JsonCmp::Eq(Value::Int64(3.into())));
+        assert!(!filter.matches(&json).unwrap());
+        let filter = JsonFilter::extract_any("matrix[1]", JsonCmp::Eq(Value::Int64(3.into())));
+        assert!(filter.matches(&json).unwrap());
+    }
+
+    #[test]
+    fn test_any_extract_type_mismatch() {
+        let json = j(json!({"values": ["a", true, 2]}));
+
+        let filter = JsonFilter::extract_any("values", JsonCmp::Gt(Value::Int64(1.into())));
+        assert!(filter.matches(&json).unwrap());
+        // neither "a" nor true is compared with a number
+        let filter = JsonFilter::extract_any("values", JsonCmp::Gt(Value::Int64(3.into())));
+        assert!(!filter.matches(&json).unwrap());
+        let filter = JsonFilter::extract_any("values", JsonCmp::Eq(Value::Text("2".into())));
+        assert!(!filter.matches(&json).unwrap());
+        let filter = JsonFilter::extract_any(
+            "values",
+            JsonCmp::In(vec![Value::Boolean(true.into()), Value::Int64(9.into())]),
+        );
+        assert!(filter.matches(&json).unwrap());
+    }
+
+    #[test]
+    fn test_any_extract_empty_array_and_missing_path() {
+        let json = j(json!({"tags": []}));
+
+        let filter = JsonFilter::extract_any("tags", JsonCmp::NotNull);
+        assert!(!filter.matches(&json).unwrap());
+        let filter = JsonFilter::extract_any("tags", JsonCmp::IsNull);
+        assert!(!filter.matches(&json).unwrap());
+        // a missing path matches IsNull, like Extract
+        let filter = JsonFilter::extract_any("labels[*]", JsonCmp::IsNull);
+        assert!(filter.matches(&json).unwrap());
+    }
+
+    #[test]
+    fn test_wildcard_only_supported_by_any_extract() {
+        let json = j(json!({"tags": ["rust"]}));
+
+        let result = JsonFilter::extract_eq("tags[*]", Value::Text("rust".into())).matches(&json);
+        assert!(matches!(result, Err(QueryError::InvalidQuery(msg)) if msg.contains("AnyExtract")));
+        let result = JsonFilter::has_key("tags[*]").matches(&json);
+        assert!(matches!(result, Err(QueryError::InvalidQuery(_))));
+    }
+
     // ===== Integration Tests =====
 
     #[test]
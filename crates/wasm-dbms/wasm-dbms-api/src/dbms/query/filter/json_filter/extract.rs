@@ -22,6 +22,8 @@ use crate::dbms::value::Value;
 /// # Returns
 ///
 /// `Some(Value)` if the path exists and contains a value, `None` otherwise.
+/// A path holding a [`PathSegment::Wildcard`] never resolves to a single
+/// value, so it returns `None`; use [`extract_any_at_path`] instead.
 ///
 /// # Type Conversion
 ///
@@ -47,12 +49,82 @@ pub fn extract_at_path(json: &Json, segments: &[PathSegment]) -> Option<Value> {
             PathSegment::Index(idx) => {
                 current = current.as_array()?.get(*idx)?;
             }
+            PathSegment::Wildcard => return None,
         }
     }
 
     Some(json_value_to_dbms_value(current))
 }
 
+/// Extracts every value a path resolves to, for array-any matching.
+///
+/// A [`PathSegment::Wildcard`] fans out to every element of the array it is
+/// applied to. When the path resolves to an array, its elements are returned
+/// in place of the array itself, so `tags` and `tags[*]` are equivalent.
+///
+/// Returns `None` if the path resolves to nothing, and an empty list if it
+/// only resolves to empty arrays.
+pub fn extract_any_at_path(json: &Json, segments: &[PathSegment]) -> Option<Vec<Value>> {
+    let nodes = resolve_nodes(json, segments);
+    if nodes.is_empty() {
+        return None;
+    }
+
+    let values = nodes
+        .into_iter()
+        .flat_map(|value| match value {
+            JsonValue::Array(elements) => elements.iter().map(json_value_to_dbms_value).collect(),
+            value => vec![json_value_to_dbms_value(value)],
+        })
+        .collect();
+    Some(values)
+}
+
+/// Extracts every value a JSONPath resolves to, as
+/// [`Filter::JsonPath`](crate::prelude::Filter::JsonPath) selects them.
+///
+/// Unlike [`extract_any_at_path`], an array the path resolves to is returned
+/// as a whole, as a [`Value::Json`]: only [`PathSegment::Wildcard`] selects
+/// its elements. Returns an empty list if the path resolves to nothing.
+pub fn extract_nodes_at_path(json: &Json, segments: &[PathSegment]) -> Vec<Value> {
+    resolve_nodes(json, segments)
+        .into_iter()
+        .map(json_value_to_dbms_value)
+        .collect()
+}
+
+/// Follows `segments` from the root of `json`, a [`PathSegment::Wildcard`]
+/// fanning out to every element of the array it is applied to.
+fn resolve_nodes<'a>(json: &'a Json, segments: &[PathSegment]) -> Vec<&'a JsonValue> {
+    let mut current = vec![json.value()];
+
+    for segment in segments {
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&JsonValue> {
+                match segment {
+                    PathSegment::Key(key) => value
+                        .as_object()
+                        .and_then(|object| object.get(key))
+                        .into_iter()
+                        .collect(),
+                    PathSegment::Index(idx) => value
+                        .as_array()
+                        .and_then(|array| array.get(*idx))
+                        .into_iter()
+                        .collect(),
+                    PathSegment::Wildcard => value
+                        .as_array()
+                        .map(|array| array.iter().collect())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+    }
+
+    current
+}
+
 /// Converts a `serde_json::Value` to a DBMS `Value`.
 ///
 /// # Type Mapping
@@ -397,4 +469,157 @@ mod tests {
         let result = extract_at_path(&json, &segments);
         assert!(matches!(result, Some(Value::Json(_))));
     }
+
+    // ===== extract_any_at_path Tests =====
+
+    #[test]
+    fn test_extract_nodes_keeps_arrays_whole() {
+        let json = j(json!({"users": [{"tags": ["a", "b"]}, {"tags": ["c"]}]}));
+        let tags = vec![
+            PathSegment::Key("users".to_string()),
+            PathSegment::Wildcard,
+            PathSegment::Key("tags".to_string()),
+        ];
+
+        assert_eq!(
+            extract_nodes_at_path(&json, &tags),
+            vec![
+                Value::Json(j(json!(["a", "b"]))),
+                Value::Json(j(json!(["c"]))),
+            ]
+        );
+        let mut each_tag = tags.clone();
+        each_tag.push(PathSegment::Wildcard);
+        assert_eq!(
+            extract_nodes_at_path(&json, &each_tag),
+            vec![
+                Value::Text("a".into()),
+                Value::Text("b".into()),
+                Value::Text("c".into()),
+            ]
+        );
+        assert_eq!(extract_nodes_at_path(&json, &[]), vec![Value::Json(json)]);
+    }
+
+    #[test]
+    fn test_extract_nodes_missing_path() {
+        let json = j(json!({"users": []}));
+        let segments = vec![
+            PathSegment::Key("users".to_string()),
+            PathSegment::Wildcard,
+            PathSegment::Key("name".to_string()),
+        ];
+
+        assert!(extract_nodes_at_path(&json, &segments).is_empty());
+    }
+
+    #[test]
+    fn test_extract_any_trailing_array() {
+        let json = j(json!({"tags": ["rust", "db"]}));
+        let segments = vec![PathSegment::Key("tags".to_string())];
+
+        let result = extract_any_at_path(&json, &segments);
+        assert_eq!(
+            result,
+            Some(vec![Value::Text("rust".into()), Value::Text("db".into())])
+        );
+        // a wildcard on the last segment is equivalent
+        let segments = vec![PathSegment::Key("tags".to_string()), PathSegment::Wildcard];
+        assert_eq!(extract_any_at_path(&json, &segments), result);
+    }
+
+    #[test]
+    fn test_extract_any_scalar() {
+        let json = j(json!({"name": "Alice"}));
+        let segments = vec![PathSegment::Key("name".to_string())];
+
+        let result = extract_any_at_path(&json, &segments);
+        assert_eq!(result, Some(vec![Value::Text("Alice".into())]));
+    }
+
+    #[test]
+    fn test_extract_any_wildcard_in_the_middle() {
+        let json = j(json!({"items": [
+            {"status": "open"},
+            {"other": 1},
+            {"status": "closed"},
+        ]}));
+        let segments = vec![
+            PathSegment::Key("items".to_string()),
+            PathSegment::Wildcard,
+            PathSegment::Key("status".to_string()),
+        ];
+
+        let result = extract_any_at_path(&json, &segments);
+        assert_eq!(
+            result,
+            Some(vec![
+                Value::Text("open".into()),
+                Value::Text("closed".into())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extract_any_nested_arrays() {
+        let json = j(json!({"matrix": [[1, 2], [3], []]}));
+
+        // only the trailing array is flattened
+        let segments = vec![PathSegment::Key("matrix".to_string())];
+        let result = extract_any_at_path(&json, &segments).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|value| matches!(value, Value::Json(_))));
+
+        let segments = vec![
+            PathSegment::Key("matrix".to_string()),
+            PathSegment::Wildcard,
+        ];
+        let result = extract_any_at_path(&json, &segments);
+        assert_eq!(
+            result,
+            Some(vec![
+                Value::Int64(1.into()),
+                Value::Int64(2.into()),
+                Value::Int64(3.into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extract_any_missing_path() {
+        let json = j(json!({"items": [{"a": 1}], "name": "Alice"}));
+        let missing = |segments: Vec<PathSegment>| extract_any_at_path(&json, &segments);
+
+        assert_eq!(missing(vec![PathSegment::Key("tags".to_string())]), None);
+        // wildcard on a non-array value
+        assert_eq!(
+            missing(vec![
+                PathSegment::Key("name".to_string()),
+                PathSegment::Wildcard
+            ]),
+            None
+        );
+        assert_eq!(
+            missing(vec![
+                PathSegment::Key("items".to_string()),
+                PathSegment::Wildcard,
+                PathSegment::Key("b".to_string()),
+            ]),
+            None
+        );
+        // an empty array resolves to no value
+        let json = j(json!({"tags": []}));
+        assert_eq!(
+            extract_any_at_path(&json, &[PathSegment::Key("tags".to_string())]),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_extract_at_path_with_wildcard_is_none() {
+        let json = j(json!({"tags": ["rust"]}));
+        let segments = vec![PathSegment::Key("tags".to_string()), PathSegment::Wildcard];
+
+        assert_eq!(extract_at_path(&json, &segments), None);
+    }
 }
@@ -1,21 +1,26 @@
 //! JSON path parsing utilities.
 //!
 //! This module provides functionality for parsing JSON paths in dot notation
-//! with bracket array indices (e.g., `user.items[0].name`).
+//! with bracket array indices (e.g., `user.items[0].name`), and JSONPath
+//! expressions (e.g., `$.users[*].name`).
 
 use crate::dbms::query::{QueryError, QueryResult};
 
 /// Represents a segment in a JSON path.
 ///
 /// A JSON path is composed of a sequence of segments, where each segment
-/// is either a key (for accessing object fields) or an index (for accessing
-/// array elements).
+/// is either a key (for accessing object fields), an index (for accessing
+/// array elements) or a wildcard (for accessing every array element).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PathSegment {
     /// An object field key.
     Key(String),
     /// An array index.
     Index(usize),
+    /// Every element of an array (`[*]`).
+    ///
+    /// Only supported by [`JsonFilter::AnyExtract`](super::JsonFilter::AnyExtract).
+    Wildcard,
 }
 
 /// Parses a JSON path string into a sequence of path segments.
@@ -32,6 +37,7 @@ pub enum PathSegment {
 /// | `"users[0].name"` | `name` field of first user |
 /// | `"data[0][1]"` | Nested array access |
 /// | `"[0]"` | First element of root array |
+/// | `"items[*].status"` | `status` field of every element of `items` |
 ///
 /// # Errors
 ///
@@ -121,6 +127,11 @@ pub fn parse_path(path: &str) -> QueryResult<Vec<PathSegment>> {
                     ));
                 }
 
+                if index_str == "*" {
+                    segments.push(PathSegment::Wildcard);
+                    continue;
+                }
+
                 // Check for negative index
                 if index_str.starts_with('-') {
                     return Err(QueryError::InvalidQuery(
@@ -161,6 +172,114 @@ pub fn parse_path(path: &str) -> QueryResult<Vec<PathSegment>> {
     Ok(segments)
 }
 
+/// Parses a JSONPath expression into a sequence of path segments.
+///
+/// # Path Syntax
+///
+/// The expression starts with the root `$`, followed by any number of
+/// child selectors:
+///
+/// | Selector | Meaning |
+/// |----------|---------|
+/// | `.name` | Field `name` of an object |
+/// | `['name']`, `["name"]` | Field `name`, which may hold any character but the quote |
+/// | `[0]` | First element of an array |
+/// | `[*]`, `.*` | Every element of an array |
+///
+/// Recursive descent (`..`), filter expressions (`[?(...)]`), slices and
+/// unions are not supported.
+///
+/// # Errors
+///
+/// Returns `QueryError::InvalidQuery` for invalid or unsupported expressions:
+/// - Missing root: `"users"`
+/// - Recursive descent: `"$..name"`
+/// - Empty field name: `"$."`, `"$.a..b"`, `"$['']"`
+/// - Unclosed bracket or quote: `"$[0"`, `"$['name]"`
+/// - Non-numeric or negative index: `"$[abc]"`, `"$[-1]"`
+///
+/// # Examples
+///
+/// ```ignore
+/// use wasm_dbms_api::dbms::query::filter::json_filter::path::{parse_json_path, PathSegment};
+///
+/// let segments = parse_json_path("$.users[*]['first name']").unwrap();
+/// assert_eq!(segments, vec![
+///     PathSegment::Key("users".to_string()),
+///     PathSegment::Wildcard,
+///     PathSegment::Key("first name".to_string()),
+/// ]);
+/// ```
+pub fn parse_json_path(path: &str) -> QueryResult<Vec<PathSegment>> {
+    let invalid = |reason: &str| {
+        Err(QueryError::InvalidQuery(format!(
+            "Invalid JSONPath '{path}': {reason}"
+        )))
+    };
+
+    let Some(mut rest) = path.strip_prefix('$') else {
+        return invalid("must start with '$'");
+    };
+
+    let mut segments = Vec::new();
+    while let Some(ch) = rest.chars().next() {
+        match ch {
+            '.' => {
+                rest = &rest[1..];
+                if rest.starts_with('.') {
+                    return invalid("recursive descent '..' is not supported");
+                }
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let name = &rest[..end];
+                match name {
+                    "" => return invalid("empty field name"),
+                    "*" => segments.push(PathSegment::Wildcard),
+                    name => segments.push(PathSegment::Key(name.to_string())),
+                }
+                rest = &rest[end..];
+            }
+            '[' => {
+                rest = &rest[1..];
+                if let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') {
+                    let Some(end) = rest[1..].find(quote) else {
+                        return invalid("unclosed quote");
+                    };
+                    let name = &rest[1..end + 1];
+                    if name.is_empty() {
+                        return invalid("empty field name");
+                    }
+                    rest = &rest[end + 2..];
+                    let Some(after) = rest.strip_prefix(']') else {
+                        return invalid("expected ']' after quoted field name");
+                    };
+                    segments.push(PathSegment::Key(name.to_string()));
+                    rest = after;
+                    continue;
+                }
+
+                let Some(end) = rest.find(']') else {
+                    return invalid("unclosed bracket");
+                };
+                let selector = rest[..end].trim();
+                if selector == "*" {
+                    segments.push(PathSegment::Wildcard);
+                } else if selector.starts_with('-') {
+                    return invalid("negative array indices are not supported");
+                } else {
+                    let Ok(index) = selector.parse::<usize>() else {
+                        return invalid(&format!("unsupported selector '[{selector}]'"));
+                    };
+                    segments.push(PathSegment::Index(index));
+                }
+                rest = &rest[end + 1..];
+            }
+            _ => return invalid(&format!("unexpected character '{ch}'")),
+        }
+    }
+
+    Ok(segments)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -375,4 +494,85 @@ mod tests {
             matches!(err, QueryError::InvalidQuery(msg) if msg.contains("Invalid array index"))
         );
     }
+
+    #[test]
+    fn test_parse_wildcard() {
+        let segments = parse_path("items[*].status").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                PathSegment::Key("items".to_string()),
+                PathSegment::Wildcard,
+                PathSegment::Key("status".to_string()),
+            ]
+        );
+
+        let segments = parse_path("[*][0]").unwrap();
+        assert_eq!(segments, vec![PathSegment::Wildcard, PathSegment::Index(0)]);
+    }
+
+    #[test]
+    fn test_parse_invalid_wildcard() {
+        let err = parse_path("items[**]").unwrap_err();
+        assert!(
+            matches!(err, QueryError::InvalidQuery(msg) if msg.contains("Invalid array index"))
+        );
+    }
+
+    // ===== JSONPath Tests =====
+
+    #[test]
+    fn test_parse_json_path_root() {
+        assert_eq!(parse_json_path("$").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_parse_json_path_selectors() {
+        let segments = parse_json_path("$.users[*].name").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                PathSegment::Key("users".to_string()),
+                PathSegment::Wildcard,
+                PathSegment::Key("name".to_string()),
+            ]
+        );
+
+        let segments = parse_json_path("$['first name'][\"a.b\"][2].*").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                PathSegment::Key("first name".to_string()),
+                PathSegment::Key("a.b".to_string()),
+                PathSegment::Index(2),
+                PathSegment::Wildcard,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_json_path() {
+        for path in [
+            "",
+            "users",
+            "$..name",
+            "$.",
+            "$.a..b",
+            "$['']",
+            "$[0",
+            "$['name]",
+            "$['name'",
+            "$[-1]",
+            "$[abc]",
+            "$[?(@.a)]",
+            "$[0:2]",
+            "$name",
+        ] {
+            let result = parse_json_path(path);
+            assert!(
+                matches!(result, Err(QueryError::InvalidQuery(_))),
+                "{path} should be invalid"
+            );
+        }
+    }
 }
@@ -83,6 +83,20 @@ impl Like {
         Ok(Self { pattern })
     }
 
+    /// Parses a SQL ILIKE pattern into a [`Like`] struct.
+    ///
+    /// The literal parts of the pattern are lowercased, so the input must be
+    /// lowercased as well before calling [`Like::matches`].
+    pub fn parse_case_insensitive(pattern: impl AsRef<str>) -> QueryResult<Self> {
+        let mut pattern = Pattern::from_str(pattern.as_ref())?;
+        for token in &mut pattern.tokens {
+            if let PatternToken::Literal(literal) = token {
+                *literal = literal.to_lowercase();
+            }
+        }
+        Ok(Self { pattern })
+    }
+
     /// Returns whether the input string matches the LIKE pattern.
     ///
     /// Uses an iterative two-pointer algorithm with single-backtrack-point
@@ -301,4 +315,36 @@ mod tests {
         assert!(pattern.matches("\u{1f600}\u{1f60d}\u{1f600}"));
         assert!(!pattern.matches("\u{1f600}\u{1f600}"));
     }
+
+    #[test]
+    fn test_should_parse_case_insensitive_pattern() {
+        let pattern =
+            Like::parse_case_insensitive("H_LLO%W\\%rld").expect("failed to parse pattern");
+        assert_eq!(
+            pattern.pattern.tokens,
+            vec![
+                PatternToken::Literal("h".to_string()),
+                PatternToken::WildcardSingle,
+                PatternToken::Literal("llo".to_string()),
+                PatternToken::WildcardMulti,
+                PatternToken::Literal("w%rld".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_should_match_case_insensitive_pattern() {
+        let pattern = Like::parse_case_insensitive("H%O_w%RLD").expect("failed to parse pattern");
+        assert!(pattern.matches("hello world".to_lowercase()));
+        assert!(pattern.matches("HELLO WORLD".to_lowercase()));
+        assert!(pattern.matches("HeLlO_wOrLd".to_lowercase()));
+        assert!(!pattern.matches("hello word".to_lowercase()));
+
+        // unicode case folding
+        let pattern =
+            Like::parse_case_insensitive("CAF\u{00c9}%").expect("failed to parse pattern");
+        assert!(pattern.matches("caf\u{00e9} latte".to_lowercase()));
+        assert!(pattern.matches("CAF\u{00c9}".to_lowercase()));
+        assert!(!pattern.matches("cafe".to_lowercase()));
+    }
 }
@@ -0,0 +1,89 @@
+//! Regular expression matching for [`super::Filter::Regex`].
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use lazy_regex::{Regex, RegexBuilder};
+
+use crate::dbms::query::QueryResult;
+use crate::prelude::QueryError;
+
+/// Maximum number of compiled patterns kept in the cache.
+///
+/// The cache is cleared when full, so a query cycling through many distinct
+/// patterns cannot grow it without bound.
+const CACHE_CAPACITY: usize = 64;
+
+/// Maximum size in bytes of a compiled pattern, to reject patterns that would
+/// be too expensive to build.
+const COMPILED_SIZE_LIMIT: usize = 1 << 20;
+
+thread_local! {
+    /// Compiled patterns by source string, so a filter evaluated against every
+    /// record of a table compiles its pattern only once.
+    static CACHE: RefCell<HashMap<String, Regex>> = RefCell::new(HashMap::new());
+}
+
+/// Returns whether `text` matches the regular expression `pattern`.
+///
+/// The pattern is unanchored: use `^` and `$` to match the whole text.
+/// Fails with [`QueryError::InvalidRegex`] if `pattern` cannot be compiled.
+pub fn is_match(pattern: &str, text: &str) -> QueryResult<bool> {
+    CACHE.with(|cache| {
+        if let Some(regex) = cache.borrow().get(pattern) {
+            return Ok(regex.is_match(text));
+        }
+
+        let regex = compile(pattern)?;
+        let matched = regex.is_match(text);
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), regex);
+        Ok(matched)
+    })
+}
+
+/// Compiles `pattern`, mapping failures to [`QueryError::InvalidRegex`].
+fn compile(pattern: &str) -> QueryResult<Regex> {
+    RegexBuilder::new(pattern)
+        .size_limit(COMPILED_SIZE_LIMIT)
+        .build()
+        .map_err(|e| QueryError::InvalidRegex(format!("{pattern}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_should_match_regex() {
+        assert!(is_match(r"^[a-z]+@example\.com$", "alice@example.com").unwrap());
+        assert!(!is_match(r"^[a-z]+@example\.com$", "Alice@example.com").unwrap());
+        assert!(is_match(r"\d{3}", "order-123-x").unwrap());
+    }
+
+    #[test]
+    fn test_should_reuse_cached_regex() {
+        let pattern = r"^cached-\d+$";
+        assert!(is_match(pattern, "cached-1").unwrap());
+        assert!(CACHE.with(|cache| cache.borrow().contains_key(pattern)));
+        assert!(!is_match(pattern, "cached-x").unwrap());
+    }
+
+    #[test]
+    fn test_should_bound_cache_size() {
+        for i in 0..CACHE_CAPACITY * 2 {
+            is_match(&format!("^p{i}$"), "p0").unwrap();
+        }
+        assert!(CACHE.with(|cache| cache.borrow().len()) <= CACHE_CAPACITY);
+    }
+
+    #[test]
+    fn test_should_reject_invalid_regex() {
+        let err = is_match("(unclosed", "text").unwrap_err();
+        assert!(matches!(err, QueryError::InvalidRegex(msg) if msg.starts_with("(unclosed")));
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// Defines the behavior of an insert when the new record conflicts with an existing one.
+///
+/// A conflict is a primary key collision or a `#[unique]` constraint violation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub enum InsertConflictBehavior {
+    /// Fail the insert with the conflict error.
+    #[default]
+    Error,
+    /// Silently skip the record if it conflicts with an existing one.
+    Ignore,
+    /// Overwrite the existing record with the same primary key.
+    /// The new values go through the same checks as an update.
+    Replace,
+}
+
+/// Outcome of [`Database::upsert`](crate::prelude::Database::upsert).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub enum UpsertResult {
+    /// No row had the record's primary key, so the record was inserted.
+    Inserted,
+    /// The row with the record's primary key was overwritten.
+    Updated,
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn test_should_default_to_error() {
+        assert_eq!(
+            InsertConflictBehavior::default(),
+            InsertConflictBehavior::Error
+        );
+    }
+
+    #[test]
+    fn test_should_compare_insert_conflict_behaviors() {
+        assert_eq!(
+            InsertConflictBehavior::Ignore,
+            InsertConflictBehavior::Ignore
+        );
+        assert_ne!(
+            InsertConflictBehavior::Ignore,
+            InsertConflictBehavior::Replace
+        );
+        assert_ne!(
+            InsertConflictBehavior::Error,
+            InsertConflictBehavior::Replace
+        );
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode_insert_conflict_behavior() {
+        for behavior in [
+            InsertConflictBehavior::Error,
+            InsertConflictBehavior::Ignore,
+            InsertConflictBehavior::Replace,
+        ] {
+            let encoded = candid::encode_one(behavior).expect("failed to encode");
+            let decoded: InsertConflictBehavior =
+                candid::decode_one(&encoded).expect("failed to decode");
+            assert_eq!(behavior, decoded);
+        }
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode_upsert_result() {
+        for result in [UpsertResult::Inserted, UpsertResult::Updated] {
+            let encoded = candid::encode_one(result).expect("failed to encode");
+            let decoded: UpsertResult = candid::decode_one(&encoded).expect("failed to decode");
+            assert_eq!(result, decoded);
+        }
+    }
+}
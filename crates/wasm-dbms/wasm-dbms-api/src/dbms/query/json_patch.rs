@@ -0,0 +1,343 @@
+//! Partial updates of JSON documents.
+//!
+//! A [`JsonPatchOp`] edits a single path of a stored [`Json`] document, so an
+//! update can change part of a document without rewriting the whole of it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::filter::json_filter::path::{PathSegment, parse_path};
+use crate::dbms::query::{QueryError, QueryResult};
+use crate::prelude::{Json, Value};
+
+/// An operation of a JSON patch, applied to a stored [`Json`] document by an
+/// update carrying a [`Value::JsonPatch`].
+///
+/// Paths use the syntax of [`JsonFilter`](super::JsonFilter) paths, except
+/// for the `[*]` wildcard, which is rejected.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub enum JsonPatchOp {
+    /// Sets the value at the path.
+    ///
+    /// The parent of the path must exist: a missing object key is added, while
+    /// an array index must point to an existing element.
+    Set(String, Value),
+    /// Removes the value at the path, if any.
+    Remove(String),
+    /// Appends a value to the array at the path.
+    Append(String, Value),
+}
+
+impl JsonPatchOp {
+    /// Creates a [`JsonPatchOp::Set`] operation.
+    pub fn set(path: &str, value: impl Into<Value>) -> Self {
+        Self::Set(path.to_string(), value.into())
+    }
+
+    /// Creates a [`JsonPatchOp::Remove`] operation.
+    pub fn remove(path: &str) -> Self {
+        Self::Remove(path.to_string())
+    }
+
+    /// Creates a [`JsonPatchOp::Append`] operation.
+    pub fn append(path: &str, value: impl Into<Value>) -> Self {
+        Self::Append(path.to_string(), value.into())
+    }
+
+    /// Applies the operation to `doc`.
+    fn apply(&self, doc: &mut JsonValue) -> QueryResult<()> {
+        match self {
+            Self::Set(path, value) => {
+                let value = dbms_value_to_json_value(value)?;
+                let (parent, last) = parse_patch_path(path)?;
+                let Some(parent) = value_at_path_mut(doc, &parent) else {
+                    return Err(missing_path(path));
+                };
+                match (parent, last) {
+                    (JsonValue::Object(map), PathSegment::Key(key)) => {
+                        map.insert(key, value);
+                    }
+                    (JsonValue::Array(elements), PathSegment::Index(index))
+                        if index < elements.len() =>
+                    {
+                        elements[index] = value;
+                    }
+                    _ => return Err(missing_path(path)),
+                }
+            }
+            Self::Remove(path) => {
+                let (parent, last) = parse_patch_path(path)?;
+                match (value_at_path_mut(doc, &parent), last) {
+                    (Some(JsonValue::Object(map)), PathSegment::Key(key)) => {
+                        map.remove(&key);
+                    }
+                    (Some(JsonValue::Array(elements)), PathSegment::Index(index))
+                        if index < elements.len() =>
+                    {
+                        elements.remove(index);
+                    }
+                    _ => {}
+                }
+            }
+            Self::Append(path, value) => {
+                let value = dbms_value_to_json_value(value)?;
+                let segments = parse_path(path)?;
+                check_no_wildcard(&segments)?;
+                match value_at_path_mut(doc, &segments) {
+                    Some(JsonValue::Array(elements)) => elements.push(value),
+                    Some(_) => {
+                        return Err(QueryError::InvalidQuery(format!(
+                            "JSON path '{path}' is not an array"
+                        )));
+                    }
+                    None => return Err(missing_path(path)),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Applies the `ops` in order to a copy of `json`.
+///
+/// # Errors
+///
+/// Returns [`QueryError::InvalidQuery`] if a path is invalid or can't be
+/// reached, if [`JsonPatchOp::Append`] targets a value which is not an array,
+/// or if a value can't be written into a JSON document.
+pub fn apply_json_patch(json: &Json, ops: &[JsonPatchOp]) -> QueryResult<Json> {
+    let mut doc = json.value().clone();
+    for op in ops {
+        op.apply(&mut doc)?;
+    }
+
+    Ok(Json::from(doc))
+}
+
+/// Parses the path of a [`JsonPatchOp::Set`] or [`JsonPatchOp::Remove`],
+/// splitting the segments of the parent from the last one.
+fn parse_patch_path(path: &str) -> QueryResult<(Vec<PathSegment>, PathSegment)> {
+    let mut segments = parse_path(path)?;
+    check_no_wildcard(&segments)?;
+    let last = segments.pop().expect("a parsed path is never empty");
+
+    Ok((segments, last))
+}
+
+fn check_no_wildcard(segments: &[PathSegment]) -> QueryResult<()> {
+    if segments.contains(&PathSegment::Wildcard) {
+        return Err(QueryError::InvalidQuery(
+            "Wildcard JSON paths are not supported by JSON patches".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn missing_path(path: &str) -> QueryError {
+    QueryError::InvalidQuery(format!("JSON path '{path}' does not exist"))
+}
+
+/// Returns the value at the given path, if any.
+fn value_at_path_mut<'a>(
+    mut doc: &'a mut JsonValue,
+    segments: &[PathSegment],
+) -> Option<&'a mut JsonValue> {
+    for segment in segments {
+        doc = match segment {
+            PathSegment::Key(key) => doc.as_object_mut()?.get_mut(key)?,
+            PathSegment::Index(index) => doc.as_array_mut()?.get_mut(*index)?,
+            PathSegment::Wildcard => return None,
+        };
+    }
+
+    Some(doc)
+}
+
+/// Converts a DBMS [`Value`] to the JSON value written by a patch.
+///
+/// Dates, date-times and UUIDs are written as strings; blobs and custom
+/// values can't be written into a JSON document.
+fn dbms_value_to_json_value(value: &Value) -> QueryResult<JsonValue> {
+    let json = match value {
+        Value::Array(values) => JsonValue::Array(
+            values
+                .iter()
+                .map(dbms_value_to_json_value)
+                .collect::<QueryResult<_>>()?,
+        ),
+        Value::Boolean(v) => JsonValue::Bool(v.0),
+        Value::Char(v) => JsonValue::String(v.0.to_string()),
+        Value::Date(v) => JsonValue::String(v.to_string()),
+        Value::DateTime(v) => JsonValue::String(v.to_string()),
+        Value::Decimal(v) => float_to_json_value(v.0.to_string().parse().unwrap_or(f64::NAN))?,
+        Value::Float32(v) => float_to_json_value(v.0.into())?,
+        Value::Float64(v) => float_to_json_value(v.0)?,
+        Value::Int8(v) => v.0.into(),
+        Value::Int16(v) => v.0.into(),
+        Value::Int32(v) => v.0.into(),
+        Value::Int64(v) => v.0.into(),
+        Value::Json(v) => v.value().clone(),
+        Value::Null => JsonValue::Null,
+        Value::Text(v) => JsonValue::String(v.0.clone()),
+        Value::Timestamp(v) => v.0.into(),
+        Value::Uint8(v) => v.0.into(),
+        Value::Uint16(v) => v.0.into(),
+        Value::Uint32(v) => v.0.into(),
+        Value::Uint64(v) => v.0.into(),
+        Value::Uuid(v) => JsonValue::String(v.to_string()),
+        Value::ZonedDateTime(v) => JsonValue::String(v.to_string()),
+        Value::Blob(_) | Value::JsonPatch(_) | Value::Custom(_) => {
+            return Err(QueryError::InvalidQuery(format!(
+                "{} values can't be written into a JSON document",
+                value.type_name()
+            )));
+        }
+    };
+
+    Ok(json)
+}
+
+fn float_to_json_value(value: f64) -> QueryResult<JsonValue> {
+    serde_json::Number::from_f64(value)
+        .map(JsonValue::Number)
+        .ok_or_else(|| {
+            QueryError::InvalidQuery(format!("{value} can't be written into a JSON document"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::str::FromStr as _;
+
+    use super::*;
+
+    fn doc() -> Json {
+        Json::from_str(r#"{"name": "Alice", "tags": ["a", "b"], "address": {"city": "Rome"}}"#)
+            .unwrap()
+    }
+
+    fn patched(ops: &[JsonPatchOp]) -> QueryResult<String> {
+        apply_json_patch(&doc(), ops).map(|json| json.to_string())
+    }
+
+    #[test]
+    fn test_should_set_values() {
+        let json = apply_json_patch(
+            &doc(),
+            &[
+                JsonPatchOp::set("name", "Bob"),
+                JsonPatchOp::set("address.zip", 100u32),
+                JsonPatchOp::set("tags[1]", Value::Null),
+                JsonPatchOp::set("active", true),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            json,
+            Json::from_str(
+                r#"{"name": "Bob", "tags": ["a", null], "address": {"city": "Rome", "zip": 100}, "active": true}"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_set_nested_json() {
+        let address = Json::from_str(r#"{"city": "Paris"}"#).unwrap();
+        let json =
+            apply_json_patch(&doc(), &[JsonPatchOp::set("address", address.clone())]).unwrap();
+        assert_eq!(json.value()["address"], *address.value());
+    }
+
+    #[test]
+    fn test_should_remove_values() {
+        let json = apply_json_patch(
+            &doc(),
+            &[
+                JsonPatchOp::remove("address.city"),
+                JsonPatchOp::remove("tags[0]"),
+                JsonPatchOp::remove("missing.key"),
+                JsonPatchOp::remove("tags[9]"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            json,
+            Json::from_str(r#"{"name": "Alice", "tags": ["b"], "address": {}}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_append_to_arrays() {
+        let json = apply_json_patch(
+            &doc(),
+            &[
+                JsonPatchOp::append("tags", "c"),
+                JsonPatchOp::append("tags", 1i32),
+            ],
+        )
+        .unwrap();
+        assert_eq!(json.value()["tags"], serde_json::json!(["a", "b", "c", 1]));
+    }
+
+    #[test]
+    fn test_should_reject_append_to_non_array() {
+        let err = patched(&[JsonPatchOp::append("name", "x")]).unwrap_err();
+        assert!(
+            matches!(&err, QueryError::InvalidQuery(message) if message.contains("not an array")),
+            "unexpected error: {err:?}"
+        );
+        let err = patched(&[JsonPatchOp::append("missing", "x")]).unwrap_err();
+        assert!(matches!(err, QueryError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_should_reject_invalid_paths() {
+        for op in [
+            JsonPatchOp::set("", 1i32),
+            JsonPatchOp::set("tags[*]", 1i32),
+            JsonPatchOp::remove("tags[*]"),
+            JsonPatchOp::set("missing.key", 1i32),
+            JsonPatchOp::set("tags[5]", 1i32),
+            JsonPatchOp::set("name.first", 1i32),
+        ] {
+            assert!(
+                matches!(
+                    patched(std::slice::from_ref(&op)),
+                    Err(QueryError::InvalidQuery(_))
+                ),
+                "{op:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_should_reject_values_not_representable_in_json() {
+        let err = patched(&[JsonPatchOp::set("data", Value::Blob(vec![1, 2].into()))]).unwrap_err();
+        assert!(matches!(err, QueryError::InvalidQuery(_)));
+        let err = patched(&[JsonPatchOp::set("ratio", f64::NAN)]).unwrap_err();
+        assert!(matches!(err, QueryError::InvalidQuery(_)));
+    }
+
+    #[test]
+    fn test_should_leave_document_untouched_on_error() {
+        let json = doc();
+        assert!(
+            apply_json_patch(
+                &json,
+                &[
+                    JsonPatchOp::set("name", "Bob"),
+                    JsonPatchOp::append("name", "x")
+                ]
+            )
+            .is_err()
+        );
+        assert_eq!(json, doc());
+    }
+}
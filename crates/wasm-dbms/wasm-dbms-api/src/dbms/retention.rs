@@ -0,0 +1,30 @@
+//! Retention policies, expiring old records on
+//! [`Database::vacuum`](crate::prelude::Database::vacuum).
+
+use serde::{Deserialize, Serialize};
+
+/// Deletes the records of a table older than a maximum age.
+///
+/// A record is expired when its `column`, a timestamp column, is older than
+/// the current time minus `max_age_nanos`. Records whose column is `NULL`
+/// never expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetentionPolicy {
+    /// Name of the table the policy applies to.
+    pub table: &'static str,
+    /// Name of the `Timestamp`, `DateTime` or `ZonedDateTime` column holding
+    /// the age of the records.
+    pub column: &'static str,
+    /// Maximum age of a record, in nanoseconds.
+    pub max_age_nanos: u64,
+}
+
+/// Outcome of a [`Database::vacuum`](crate::prelude::Database::vacuum) run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct VacuumReport {
+    /// Number of tables with a retention policy that were vacuumed.
+    pub tables_vacuumed: u32,
+    /// Total number of expired rows deleted.
+    pub rows_deleted: u64,
+}
@@ -1,10 +1,11 @@
 //! This module contains all the built-in sanitizers which can be applied to columns.
 //!
-//! Each validation function takes a [`crate::prelude::Value`] as input and returns a `DbmsResult<crate::prelude::Value>` with the sanitized
+//! Each validation function takes a [`crate::prelude::Value`] as input and returns a `Result<crate::prelude::Value, SanitizeError>` with the sanitized
 //! value or an error if the value could not be sanitized.
 //!
 //! This module contains the [`Sanitize`] trait which should be implemented by all sanitizers.
 
+mod chained;
 mod clamp;
 mod collapse_whitespace;
 mod lowercase;
@@ -16,6 +17,7 @@ mod trim;
 mod uppercase;
 mod url_encoding;
 
+pub use self::chained::ChainedSanitizer;
 pub use self::clamp::{ClampSanitizer, ClampUnsignedSanitizer};
 pub use self::collapse_whitespace::CollapseWhitespaceSanitizer;
 pub use self::lowercase::LowerCaseSanitizer;
@@ -26,14 +28,29 @@ pub use self::timezone::{TimezoneSanitizer, UtcSanitizer};
 pub use self::trim::TrimSanitizer;
 pub use self::uppercase::UpperCaseSanitizer;
 pub use self::url_encoding::UrlEncodingSanitizer;
-use crate::prelude::{DbmsResult, Value};
+use crate::prelude::{DbmsError, Value};
+
+/// Error returned by a [`Sanitize`] implementation which cannot sanitize a
+/// value.
+///
+/// It is reported as a [`DbmsError::Sanitize`] by the database.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct SanitizeError(pub String);
+
+impl From<SanitizeError> for DbmsError {
+    fn from(error: SanitizeError) -> Self {
+        DbmsError::Sanitize(error.0)
+    }
+}
 
 /// Trait for sanitizing [`Value`]s.
+///
+/// The trait is object safe, so sanitizers defined in other crates can be
+/// attached to columns with `#[sanitizer(...)]` like the built-in ones.
 pub trait Sanitize {
     /// Sanitizes the given [`Value`].
     ///
-    /// In case of error it should return a [`crate::prelude::DbmsError::Sanitize`] error.
-    ///
     /// Sanitizers should not return error if the value is not of the expected type, they should just return the value as is.
-    fn sanitize(&self, value: Value) -> DbmsResult<Value>;
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError>;
 }
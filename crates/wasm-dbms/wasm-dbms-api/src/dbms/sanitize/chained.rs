@@ -0,0 +1,88 @@
+use crate::prelude::{Sanitize, SanitizeError, Value};
+
+/// Sanitizer that applies a sequence of sanitizers in order.
+///
+/// Each sanitizer receives the output of the previous one. The first error
+/// stops the chain.
+///
+/// This is what `#[sanitizer(A, B, ...)]` generates when a column lists more
+/// than one sanitizer.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_dbms_api::prelude::{
+///     ChainedSanitizer, LowerCaseSanitizer, Sanitize as _, TrimSanitizer, Value,
+/// };
+///
+/// let sanitizer = ChainedSanitizer::new(vec![
+///     Box::new(TrimSanitizer),
+///     Box::new(LowerCaseSanitizer),
+/// ]);
+/// let value = sanitizer.sanitize(Value::Text("  Alice@Example.COM ".into())).unwrap();
+/// assert_eq!(value, Value::Text("alice@example.com".into()));
+/// ```
+pub struct ChainedSanitizer {
+    sanitizers: Vec<Box<dyn Sanitize>>,
+}
+
+impl ChainedSanitizer {
+    /// Creates a sanitizer running `sanitizers` in order.
+    pub fn new(sanitizers: Vec<Box<dyn Sanitize>>) -> Self {
+        Self { sanitizers }
+    }
+}
+
+impl Sanitize for ChainedSanitizer {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
+        self.sanitizers
+            .iter()
+            .try_fold(value, |value, sanitizer| sanitizer.sanitize(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::prelude::{LowerCaseSanitizer, TrimSanitizer};
+
+    struct FailingSanitizer;
+
+    impl Sanitize for FailingSanitizer {
+        fn sanitize(&self, _value: Value) -> Result<Value, SanitizeError> {
+            Err(SanitizeError("failed".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_chained_sanitizer_applies_in_order() {
+        let sanitizer =
+            ChainedSanitizer::new(vec![Box::new(TrimSanitizer), Box::new(LowerCaseSanitizer)]);
+
+        let value = sanitizer
+            .sanitize(Value::Text("  Hello World  ".into()))
+            .unwrap();
+        assert_eq!(value, Value::Text("hello world".into()));
+    }
+
+    #[test]
+    fn test_chained_sanitizer_stops_at_first_error() {
+        let sanitizer =
+            ChainedSanitizer::new(vec![Box::new(FailingSanitizer), Box::new(TrimSanitizer)]);
+
+        assert!(matches!(
+            sanitizer.sanitize(Value::Text(" a ".into())),
+            Err(SanitizeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_chained_sanitizer_is_identity() {
+        let sanitizer = ChainedSanitizer::new(Vec::new());
+        assert_eq!(
+            sanitizer.sanitize(Value::Int32(1.into())).unwrap(),
+            Value::Int32(1.into())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer that clamps integer values within a specified range.
 ///
@@ -18,16 +18,14 @@ pub struct ClampSanitizer {
 }
 
 impl Sanitize for ClampSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Int32(num) => {
                 let clamped = (num.0 as i64).clamp(self.min, self.max);
                 let clamped: Result<i32, _> = clamped.try_into();
                 match clamped {
                     Ok(clamped_i32) => Ok(Value::Int32(clamped_i32.into())),
-                    Err(_) => Err(crate::prelude::DbmsError::Sanitize(
-                        "Clamped value out of Int32 range".into(),
-                    )),
+                    Err(_) => Err(SanitizeError("Clamped value out of Int32 range".into())),
                 }
             }
             Value::Int64(num) => {
@@ -57,16 +55,14 @@ pub struct ClampUnsignedSanitizer {
 }
 
 impl Sanitize for ClampUnsignedSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Uint32(num) => {
                 let clamped = (num.0 as u64).clamp(self.min, self.max);
                 let clamped: Result<u32, _> = clamped.try_into();
                 match clamped {
                     Ok(clamped_u32) => Ok(Value::Uint32(clamped_u32.into())),
-                    Err(_) => Err(crate::prelude::DbmsError::Sanitize(
-                        "Clamped value out of Uint32 range".into(),
-                    )),
+                    Err(_) => Err(SanitizeError("Clamped value out of Uint32 range".into())),
                 }
             }
             Value::Uint64(num) => {
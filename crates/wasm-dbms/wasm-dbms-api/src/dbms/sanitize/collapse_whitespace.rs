@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer that collapses multiple whitespace characters into a single space in strings.
 ///
@@ -15,7 +15,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct CollapseWhitespaceSanitizer;
 
 impl Sanitize for CollapseWhitespaceSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) => Ok(Value::Text(
                 text.as_str()
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer that converts strings to lowercase.
 ///
@@ -15,7 +15,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct LowerCaseSanitizer;
 
 impl Sanitize for LowerCaseSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) => Ok(Value::Text(text.as_str().to_lowercase().into())),
             other => Ok(other),
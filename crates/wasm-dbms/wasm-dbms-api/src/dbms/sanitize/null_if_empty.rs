@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// The [`NullIfEmptySanitizer`] struct is used to sanitize input by converting empty strings to null values.
 ///
@@ -17,7 +17,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct NullIfEmptySanitizer;
 
 impl Sanitize for NullIfEmptySanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) if text.as_str().is_empty() => Ok(Value::Null),
             other => Ok(other),
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer that rounds [`rust_decimal::Decimal`] values to a specified scale.
 ///
@@ -16,7 +16,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct RoundToScaleSanitizer(pub u32);
 
 impl Sanitize for RoundToScaleSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Decimal(num) => {
                 let rounded = num.0.round_dp(self.0);
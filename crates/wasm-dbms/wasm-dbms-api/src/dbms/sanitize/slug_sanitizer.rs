@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer sluggifies strings by converting them to lowercase, replacing spaces with hyphens,
 /// and removing non-alphanumeric characters.
@@ -16,7 +16,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct SlugSanitizer;
 
 impl Sanitize for SlugSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) => {
                 let slug = text
@@ -1,4 +1,4 @@
-use crate::prelude::{DateTime, DbmsResult, Sanitize, Value};
+use crate::prelude::{DateTime, Sanitize, SanitizeError, Value};
 
 /// Sanitizer that ensures that all [`crate::prelude::DateTime`] values are within a specific timezone.
 ///
@@ -20,7 +20,7 @@ use crate::prelude::{DateTime, DbmsResult, Sanitize, Value};
 pub struct TimezoneSanitizer(pub i16);
 
 impl Sanitize for TimezoneSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::DateTime(dt) => {
                 let delta_minutes = self.0 - dt.timezone_offset_minutes;
@@ -53,7 +53,7 @@ impl Sanitize for TimezoneSanitizer {
 pub struct UtcSanitizer;
 
 impl Sanitize for UtcSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         TimezoneSanitizer(0).sanitize(value)
     }
 }
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer that trims leading and trailing whitespace from strings.
 ///
@@ -15,7 +15,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct TrimSanitizer;
 
 impl Sanitize for TrimSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) => Ok(Value::Text(text.as_str().trim().into())),
             other => Ok(other),
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer that converts strings to uppercase.
 ///
@@ -15,7 +15,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct UpperCaseSanitizer;
 
 impl Sanitize for UpperCaseSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) => Ok(Value::Text(text.as_str().to_uppercase().into())),
             other => Ok(other),
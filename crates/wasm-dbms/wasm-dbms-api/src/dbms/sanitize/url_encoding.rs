@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsResult, Sanitize, Value};
+use crate::prelude::{Sanitize, SanitizeError, Value};
 
 /// Sanitizer URL-encodes strings by converting them to percent-encoded format.
 ///
@@ -15,7 +15,7 @@ use crate::prelude::{DbmsResult, Sanitize, Value};
 pub struct UrlEncodingSanitizer;
 
 impl Sanitize for UrlEncodingSanitizer {
-    fn sanitize(&self, value: Value) -> DbmsResult<Value> {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
         match value {
             Value::Text(text) => {
                 let encoded = percent_encoding::utf8_percent_encode(
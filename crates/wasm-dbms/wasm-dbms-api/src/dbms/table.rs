@@ -3,21 +3,24 @@
 mod column_def;
 mod record;
 mod schema;
+mod stats;
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub use self::column_def::{
-    CandidDataTypeKind, CandidForeignKeyDef, ColumnDef, ForeignKeyDef, IndexDef, JoinColumnDef,
+    CandidDataTypeKind, CandidForeignKeyDef, CheckDef, ColumnDef, ComputedColumnDef, ForeignKeyDef,
+    IndexDef, JoinColumnDef, UniqueConstraintDef,
 };
 pub use self::record::{
     InsertRecord, TableColumns, TableRecord, UpdateRecord, ValuesSource, flatten_table_columns,
 };
 pub use self::schema::{
-    ColumnSnapshot, CustomDataTypeSnapshot, DataTypeSnapshot, ForeignKeySnapshot, IndexSnapshot,
-    OnDeleteSnapshot, TableFingerprint, TableSchema, TableSchemaSnapshot, WireSize,
+    AutoTimestamp, ColumnSnapshot, CustomDataTypeSnapshot, DataTypeSnapshot, ForeignKeySnapshot,
+    IndexSnapshot, OnDeleteSnapshot, TableFingerprint, TableSchema, TableSchemaSnapshot, WireSize,
     fingerprint_for_name,
 };
+pub use self::stats::{CompactionReport, TableStats};
 
 /// Table related errors
 #[derive(Debug, Error, Deserialize, Serialize)]
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::dbms::types::DataTypeKind;
+use crate::dbms::query::{DeleteBehavior, Filter};
+use crate::dbms::types::{DataTypeKind, Text};
 use crate::dbms::value::Value;
 
 /// Constructor for a column's default value.
@@ -42,6 +43,38 @@ pub struct ColumnDef {
     /// migration planner walks this list to detect a `RenameColumn` op when a
     /// stored column with one of these names matches the compiled column.
     pub renamed_from: &'static [&'static str],
+    /// Indicates if the column values are stored LZ4-compressed.
+    ///
+    /// Populated by the `#[compress]` attribute; only `Text` and `Blob` columns
+    /// can be compressed.
+    pub compress: bool,
+}
+
+impl ColumnDef {
+    /// Returns the maximum size, in bytes, of the values of the column, or
+    /// `None` when the size is bounded by the data type alone.
+    ///
+    /// Inserts and updates of larger values fail with
+    /// [`QueryError::ValueTooLarge`](crate::prelude::QueryError::ValueTooLarge).
+    pub fn max_size(&self) -> Option<u64> {
+        match self.data_type {
+            DataTypeKind::Text => Some(Text::MAX_BYTES as u64),
+            _ => None,
+        }
+    }
+
+    /// Returns the names of the variants an enum column accepts, indexed by
+    /// the stored byte, or `None` when the column is not a
+    /// [`DataTypeKind::Enum`].
+    ///
+    /// Inserts and updates of a byte outside the list fail with
+    /// [`QueryError::InvalidEnumValue`](crate::prelude::QueryError::InvalidEnumValue).
+    pub fn allowed_values(&self) -> Option<&'static [&'static str]> {
+        match self.data_type {
+            DataTypeKind::Enum(variants) => Some(variants),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for ColumnDef {
@@ -55,6 +88,7 @@ impl PartialEq for ColumnDef {
             && self.foreign_key == other.foreign_key
             && self.default.map(|f| f as usize) == other.default.map(|f| f as usize)
             && self.renamed_from == other.renamed_from
+            && self.compress == other.compress
     }
 }
 
@@ -65,10 +99,69 @@ impl Eq for ColumnDef {}
 pub struct ForeignKeyDef {
     /// Name of the local column that holds the foreign key (es: "user_id")
     pub local_column: &'static str,
+    /// Names of all the local columns of the foreign key, starting with
+    /// [`Self::local_column`]; a single column unless the key references a
+    /// composite primary key (e.g., `["tenant_id", "user_id"]`)
+    pub local_columns: &'static [&'static str],
     /// Name of the foreign table (e.g., "users")
     pub foreign_table: &'static str,
     /// Name of the foreign column that the FK points to (e.g., "id")
     pub foreign_column: &'static str,
+    /// Names of all the foreign columns the FK points to, starting with
+    /// [`Self::foreign_column`] and matched by position with
+    /// [`Self::local_columns`]
+    pub foreign_columns: &'static [&'static str],
+    /// Action declared with `#[foreign_key(..., on_delete = "...")]`, applied
+    /// when a referenced row is deleted whatever the [`DeleteBehavior`] of the
+    /// delete. `None` defers to the behavior of the delete.
+    pub on_delete: Option<DeleteBehavior>,
+    /// Whether the existence of the referenced row is not checked on insert
+    /// and update, declared with `#[foreign_key(..., deferred)]` for bulk
+    /// loads writing children before their parents.
+    pub deferred: bool,
+}
+
+impl ForeignKeyDef {
+    /// Returns whether the foreign key spans several columns.
+    pub fn is_composite(&self) -> bool {
+        self.local_columns.len() > 1
+    }
+
+    /// Returns the values of [`Self::local_columns`] in `record_values`, in
+    /// order.
+    ///
+    /// Returns `None` if any of them is missing or null: a foreign key with a
+    /// null column references nothing.
+    pub fn local_values(&self, record_values: &[(ColumnDef, Value)]) -> Option<Vec<Value>> {
+        self.local_columns
+            .iter()
+            .map(|column| {
+                record_values
+                    .iter()
+                    .find(|(col_def, value)| col_def.name == *column && !value.is_null())
+                    .map(|(_, value)| value.clone())
+            })
+            .collect()
+    }
+
+    /// Returns the filter matching the rows holding this foreign key which
+    /// reference the row of [`Self::foreign_table`] with `referenced_values`.
+    ///
+    /// Returns `None` if any of [`Self::foreign_columns`] is missing from
+    /// `referenced_values`.
+    pub fn referencing_filter(&self, referenced_values: &[(ColumnDef, Value)]) -> Option<Filter> {
+        self.local_columns
+            .iter()
+            .zip(self.foreign_columns)
+            .map(|(local, foreign)| {
+                referenced_values
+                    .iter()
+                    .find(|(col_def, _)| col_def.name == *foreign)
+                    .map(|(_, value)| Filter::eq(local, value.clone()))
+            })
+            .reduce(|filter, eq| Some(filter?.and(eq?)))
+            .flatten()
+    }
 }
 
 /// Defines an index on one or more columns of a table.
@@ -84,6 +177,47 @@ impl IndexDef {
     }
 }
 
+/// Defines a composite unique constraint spanning several columns of a table.
+///
+/// Built from fields sharing the same `#[unique(group = "name")]` attribute; the
+/// combination of values in `columns` must be unique across all records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UniqueConstraintDef {
+    /// Name of the constraint group.
+    pub name: &'static str,
+    /// Column names that make up the constraint, in field declaration order.
+    pub columns: &'static [&'static str],
+}
+
+/// Defines a table-level check constraint spanning one or more columns.
+///
+/// Built from the struct-level `#[check(...)]` attribute; every inserted or
+/// updated record must match the filter built by `filter`.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckDef {
+    /// Name of the constraint, reported when it is violated.
+    pub name: &'static str,
+    /// Source of the check expression.
+    pub expression: &'static str,
+    /// Builds the filter every record must match.
+    pub filter: fn() -> Filter,
+}
+
+/// Defines a computed column, which is not stored but evaluated from other
+/// columns when a record is read.
+///
+/// Built from the field-level `#[computed = "<expr>"]` attribute. Computed
+/// columns are not listed among the [`ColumnDef`]s of the table, and cannot be
+/// filtered on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputedColumnDef {
+    /// Name of the computed column.
+    pub name: &'static str,
+    /// Stored columns the expression reads, which are loaded whenever the
+    /// computed column is selected.
+    pub depends_on: &'static [&'static str],
+}
+
 /// Serializable data type kind for API boundaries.
 ///
 /// Mirrors [`DataTypeKind`] but uses owned `String` for the `Custom` variant,
@@ -91,44 +225,60 @@ impl IndexDef {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
 pub enum CandidDataTypeKind {
+    Array(Box<CandidDataTypeKind>),
     Blob,
     Boolean,
+    Char,
     Date,
     DateTime,
     Decimal,
+    Float32,
+    Float64,
     Int8,
     Int16,
     Int32,
     Int64,
     Json,
     Text,
+    Timestamp,
     Uint8,
     Uint16,
     Uint32,
     Uint64,
     Uuid,
+    ZonedDateTime,
+    Enum(Vec<String>),
     Custom(String),
 }
 
 impl From<DataTypeKind> for CandidDataTypeKind {
     fn from(kind: DataTypeKind) -> Self {
         match kind {
+            DataTypeKind::Array(elem) => Self::Array(Box::new(Self::from(*elem))),
             DataTypeKind::Blob => Self::Blob,
             DataTypeKind::Boolean => Self::Boolean,
+            DataTypeKind::Char => Self::Char,
             DataTypeKind::Date => Self::Date,
             DataTypeKind::DateTime => Self::DateTime,
             DataTypeKind::Decimal => Self::Decimal,
+            DataTypeKind::Float32 => Self::Float32,
+            DataTypeKind::Float64 => Self::Float64,
             DataTypeKind::Int8 => Self::Int8,
             DataTypeKind::Int16 => Self::Int16,
             DataTypeKind::Int32 => Self::Int32,
             DataTypeKind::Int64 => Self::Int64,
             DataTypeKind::Json => Self::Json,
             DataTypeKind::Text => Self::Text,
+            DataTypeKind::Timestamp => Self::Timestamp,
             DataTypeKind::Uint8 => Self::Uint8,
             DataTypeKind::Uint16 => Self::Uint16,
             DataTypeKind::Uint32 => Self::Uint32,
             DataTypeKind::Uint64 => Self::Uint64,
             DataTypeKind::Uuid => Self::Uuid,
+            DataTypeKind::ZonedDateTime => Self::ZonedDateTime,
+            DataTypeKind::Enum(variants) => {
+                Self::Enum(variants.iter().map(|variant| variant.to_string()).collect())
+            }
             DataTypeKind::Custom { tag, .. } => Self::Custom(tag.to_string()),
         }
     }
@@ -211,6 +361,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
 
         assert_eq!(column.name, "id");
@@ -222,12 +373,36 @@ mod test {
         assert!(column.foreign_key.is_none());
     }
 
+    #[test]
+    fn test_should_return_max_size_of_text_columns() {
+        let mut column = ColumnDef {
+            name: "body",
+            data_type: DataTypeKind::Text,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        assert_eq!(column.max_size(), Some(Text::MAX_BYTES as u64));
+
+        column.data_type = DataTypeKind::Uint32;
+        assert_eq!(column.max_size(), None);
+    }
+
     #[test]
     fn test_should_create_column_def_with_foreign_key() {
         let fk = ForeignKeyDef {
             local_column: "user_id",
+            local_columns: &["user_id"],
             foreign_table: "users",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
         };
 
         let column = ColumnDef {
@@ -240,6 +415,7 @@ mod test {
             foreign_key: Some(fk),
             default: None,
             renamed_from: &[],
+            compress: false,
         };
 
         assert_eq!(column.name, "user_id");
@@ -263,6 +439,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
 
         let cloned = column.clone();
@@ -281,6 +458,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
 
         let column2 = ColumnDef {
@@ -293,6 +471,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
 
         let column3 = ColumnDef {
@@ -305,6 +484,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
 
         assert_eq!(column1, column2);
@@ -315,8 +495,12 @@ mod test {
     fn test_should_create_foreign_key_def() {
         let fk = ForeignKeyDef {
             local_column: "post_id",
+            local_columns: &["post_id"],
             foreign_table: "posts",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
         };
 
         assert_eq!(fk.local_column, "post_id");
@@ -324,13 +508,70 @@ mod test {
         assert_eq!(fk.foreign_column, "id");
     }
 
+    #[test]
+    fn test_should_read_composite_foreign_key_values() {
+        let fk = ForeignKeyDef {
+            local_column: "tenant_id",
+            local_columns: &["tenant_id", "member_id"],
+            foreign_table: "users",
+            foreign_column: "tenant_id",
+            foreign_columns: &["tenant_id", "user_id"],
+            on_delete: None,
+            deferred: false,
+        };
+        let column = |name| ColumnDef {
+            name,
+            data_type: DataTypeKind::Uint32,
+            auto_increment: false,
+            nullable: true,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+
+        let local = vec![
+            (column("member_id"), Value::Uint32(2.into())),
+            (column("tenant_id"), Value::Uint32(1.into())),
+        ];
+        assert!(fk.is_composite());
+        assert_eq!(
+            fk.local_values(&local),
+            Some(vec![Value::Uint32(1.into()), Value::Uint32(2.into())])
+        );
+        let null_member = vec![
+            (column("member_id"), Value::Null),
+            (column("tenant_id"), Value::Uint32(1.into())),
+        ];
+        assert_eq!(fk.local_values(&null_member), None);
+
+        let referenced = vec![
+            (column("tenant_id"), Value::Uint32(1.into())),
+            (column("user_id"), Value::Uint32(2.into())),
+        ];
+        assert_eq!(
+            fk.referencing_filter(&referenced),
+            Some(
+                Filter::eq("tenant_id", Value::Uint32(1.into()))
+                    .and(Filter::eq("member_id", Value::Uint32(2.into())))
+            )
+        );
+        assert_eq!(fk.referencing_filter(&referenced[..1]), None);
+    }
+
     #[test]
     #[allow(clippy::clone_on_copy)]
     fn test_should_clone_foreign_key_def() {
         let fk = ForeignKeyDef {
             local_column: "author_id",
+            local_columns: &["author_id"],
             foreign_table: "authors",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
         };
 
         let cloned = fk.clone();
@@ -341,20 +582,32 @@ mod test {
     fn test_should_compare_foreign_key_defs() {
         let fk1 = ForeignKeyDef {
             local_column: "user_id",
+            local_columns: &["user_id"],
             foreign_table: "users",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
         };
 
         let fk2 = ForeignKeyDef {
             local_column: "user_id",
+            local_columns: &["user_id"],
             foreign_table: "users",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
         };
 
         let fk3 = ForeignKeyDef {
             local_column: "category_id",
+            local_columns: &["category_id"],
             foreign_table: "categories",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
         };
 
         assert_eq!(fk1, fk2);
@@ -386,6 +639,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
         let candid_col = JoinColumnDef::from(col);
         assert_eq!(candid_col.table, None);
@@ -426,6 +680,7 @@ mod test {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         };
         let candid_col = JoinColumnDef::from(col);
         assert_eq!(
@@ -55,7 +55,16 @@ pub trait InsertRecord: Sized + Clone {
     fn into_values(self) -> Vec<(ColumnDef, Value)>;
 
     /// Converts the insert record into the corresponding table record.
+    ///
+    /// `#[auto_timestamp]` columns are not part of the insert record, so they
+    /// are left at their default value.
     fn into_record(self) -> Self::Schema;
+
+    /// Creates the table record from a complete list of column [`Value`]s,
+    /// including the columns stamped by the engine.
+    fn record_from_values(values: &[(ColumnDef, Value)]) -> DbmsResult<Self::Schema> {
+        Ok(Self::from_values(values)?.into_record())
+    }
 }
 
 /// This trait represents a record for updating a table.
@@ -7,11 +7,13 @@ pub use self::snapshot::{
     OnDeleteSnapshot, TableSchemaSnapshot, WireSize,
 };
 use crate::dbms::foreign_fetcher::ForeignFetcher;
-use crate::dbms::table::column_def::{ColumnDef, IndexDef};
+use crate::dbms::table::column_def::{
+    CheckDef, ColumnDef, ComputedColumnDef, IndexDef, UniqueConstraintDef,
+};
 use crate::dbms::table::{InsertRecord, TableRecord, UpdateRecord};
 use crate::dbms::types::DataTypeKind;
 use crate::memory::Encode;
-use crate::prelude::{Sanitize, Validate};
+use crate::prelude::{DbmsResult, Sanitize, Validate, Value};
 
 /// A type representing a unique fingerprint for a table schema.
 pub type TableFingerprint = u64;
@@ -19,22 +21,31 @@ pub type TableFingerprint = u64;
 /// Maps a runtime [`DataTypeKind`] into its stable [`DataTypeSnapshot`] counterpart.
 fn data_type_to_snapshot(kind: &DataTypeKind) -> DataTypeSnapshot {
     match kind {
+        DataTypeKind::Array(elem) => DataTypeSnapshot::Array(Box::new(data_type_to_snapshot(elem))),
         DataTypeKind::Blob => DataTypeSnapshot::Blob,
         DataTypeKind::Boolean => DataTypeSnapshot::Boolean,
+        DataTypeKind::Char => DataTypeSnapshot::Char,
         DataTypeKind::Date => DataTypeSnapshot::Date,
         DataTypeKind::DateTime => DataTypeSnapshot::Datetime,
         DataTypeKind::Decimal => DataTypeSnapshot::Decimal,
+        DataTypeKind::Float32 => DataTypeSnapshot::Float32,
+        DataTypeKind::Float64 => DataTypeSnapshot::Float64,
         DataTypeKind::Int8 => DataTypeSnapshot::Int8,
         DataTypeKind::Int16 => DataTypeSnapshot::Int16,
         DataTypeKind::Int32 => DataTypeSnapshot::Int32,
         DataTypeKind::Int64 => DataTypeSnapshot::Int64,
         DataTypeKind::Json => DataTypeSnapshot::Json,
         DataTypeKind::Text => DataTypeSnapshot::Text,
+        DataTypeKind::Timestamp => DataTypeSnapshot::Timestamp,
         DataTypeKind::Uint8 => DataTypeSnapshot::Uint8,
         DataTypeKind::Uint16 => DataTypeSnapshot::Uint16,
         DataTypeKind::Uint32 => DataTypeSnapshot::Uint32,
         DataTypeKind::Uint64 => DataTypeSnapshot::Uint64,
         DataTypeKind::Uuid => DataTypeSnapshot::Uuid,
+        DataTypeKind::ZonedDateTime => DataTypeSnapshot::ZonedDatetime,
+        // the discriminant is stored as a plain byte, so appending variants
+        // leaves the snapshot unchanged
+        DataTypeKind::Enum(_) => DataTypeSnapshot::Uint8,
         DataTypeKind::Custom { tag, wire_size } => {
             DataTypeSnapshot::Custom(Box::new(CustomDataTypeSnapshot {
                 tag: (*tag).to_string(),
@@ -44,6 +55,15 @@ fn data_type_to_snapshot(kind: &DataTypeKind) -> DataTypeSnapshot {
     }
 }
 
+/// When the engine stamps an `#[auto_timestamp]` column with the current time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoTimestamp {
+    /// Stamped once, when the row is inserted (e.g. `created_at`).
+    Insert,
+    /// Stamped when the row is inserted and on every update (e.g. `updated_at`).
+    Update,
+}
+
 /// Table schema representation.
 ///
 /// It is used to define the structure of a database table.
@@ -67,8 +87,19 @@ where
     /// Returns the column definitions of the table.
     fn columns() -> &'static [ColumnDef];
 
+    /// Returns the names of the primary key columns, in declaration order.
+    ///
+    /// A composite primary key lists more than one column; the combination of
+    /// their values identifies a record.
+    fn primary_keys() -> &'static [&'static str];
+
     /// Returns the name of the primary key column.
-    fn primary_key() -> &'static str;
+    ///
+    /// For a composite primary key this is the first column of
+    /// [`Self::primary_keys`].
+    fn primary_key() -> &'static str {
+        Self::primary_keys()[0]
+    }
 
     /// Returns the list of indexes defined on the table, where each index
     /// is represented by the list of column names it includes.
@@ -76,14 +107,86 @@ where
         &[]
     }
 
+    /// Returns the composite unique constraints defined on the table.
+    ///
+    /// Single-column constraints are expressed through [`ColumnDef::unique`];
+    /// this only lists the `#[unique(group = "...")]` groups.
+    fn unique_constraints() -> &'static [UniqueConstraintDef] {
+        &[]
+    }
+
+    /// Returns the table-level check constraints defined on the table.
+    ///
+    /// Declared with the struct-level `#[check(...)]` attribute; each one is a
+    /// [`Filter`](crate::prelude::Filter) every record must match.
+    fn checks() -> &'static [CheckDef] {
+        &[]
+    }
+
+    /// Returns the computed columns of the table.
+    ///
+    /// Declared with the field-level `#[computed = "<expr>"]` attribute; they
+    /// are only part of the [`TableRecord`], not of [`Self::columns`].
+    fn computed_columns() -> &'static [ComputedColumnDef] {
+        &[]
+    }
+
+    /// Returns the `#[deleted_at]` column of a `#[soft_delete]` table.
+    ///
+    /// Deleting from such a table sets this column to the current time instead
+    /// of removing the rows, and queries leave out the rows where it is set
+    /// unless they [include the deleted rows](crate::prelude::QueryBuilder::include_deleted).
+    fn soft_delete_column() -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the `#[auto_timestamp]` columns of the table, with the event
+    /// stamping them.
+    ///
+    /// These columns are left out of the insert and update requests: the
+    /// engine sets them to the current time right before sanitization.
+    fn auto_timestamps() -> &'static [(&'static str, AutoTimestamp)] {
+        &[]
+    }
+
+    /// Returns the update applied instead of inserting `record` when a row
+    /// with its primary key already exists, for tables declared with
+    /// `#[on_conflict = "update"]`.
+    ///
+    /// The patch sets the fields given on `record`, while omitted default and
+    /// auto-increment fields keep their stored value, and matches the row by
+    /// primary key. Returns `None` for the other tables, whose inserts fail
+    /// with [`QueryError::PrimaryKeyConflict`](crate::prelude::QueryError::PrimaryKeyConflict),
+    /// and when `record` leaves its primary key to be generated.
+    fn on_conflict_update(_record: &Self::Insert) -> Option<Self::Update> {
+        None
+    }
+
     /// Converts itself into a vector of column-value pairs.
     fn to_values(self) -> Vec<(ColumnDef, crate::dbms::value::Value)>;
 
-    /// Returns the [`Sanitize`] implementation for the given column name, if any.
-    fn sanitizer(column_name: &'static str) -> Option<Box<dyn Sanitize>>;
+    /// Returns the [`Sanitize`] implementations for the given column name, in
+    /// the order they are applied.
+    fn sanitizer(column_name: &'static str) -> Vec<Box<dyn Sanitize>>;
 
-    /// Returns the [`Validate`] implementation for the given column name, if any.
-    fn validator(column_name: &'static str) -> Option<Box<dyn Validate>>;
+    /// Returns the [`Validate`] implementations for the given column name, in
+    /// the order they are run.
+    fn validator(column_name: &'static str) -> Vec<Box<dyn Validate>>;
+
+    /// Evaluates the `#[check]` constraints and then the `#[cross_validate]`
+    /// invariants of the table against a complete record.
+    ///
+    /// Fails with [`QueryError::CheckConstraintViolation`] for the first check
+    /// that does not hold, or with [`DbmsError::Validation`] listing every
+    /// invariant that does not hold. The default implementation accepts every
+    /// record.
+    ///
+    /// [`QueryError::CheckConstraintViolation`]: crate::prelude::QueryError::CheckConstraintViolation
+    /// [`DbmsError::Validation`]: crate::prelude::DbmsError::Validation
+    fn check_record(record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
+        let _ = record_values;
+        Ok(())
+    }
 
     /// Returns an instance of the [`ForeignFetcher`] for this table schema.
     fn foreign_fetcher() -> Self::ForeignFetcher {
@@ -115,7 +218,7 @@ where
                 foreign_key: c.foreign_key.as_ref().map(|fk| ForeignKeySnapshot {
                     table: fk.foreign_table.to_string(),
                     column: fk.foreign_column.to_string(),
-                    on_delete: OnDeleteSnapshot::Restrict,
+                    on_delete: fk.on_delete.map_or(OnDeleteSnapshot::Restrict, Into::into),
                 }),
                 default: c.default.map(|f| f()),
             })
@@ -142,7 +245,7 @@ where
         TableSchemaSnapshot {
             version: TableSchemaSnapshot::latest_version(),
             name: Self::table_name().to_string(),
-            primary_key: Self::primary_key().to_string(),
+            primary_key: Self::primary_keys().join(","),
             alignment: <Self as Encode>::ALIGNMENT as u32,
             columns,
             indexes,
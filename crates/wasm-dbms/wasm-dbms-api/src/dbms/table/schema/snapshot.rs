@@ -4,9 +4,10 @@
 //! order to trigger necessary migrations.
 
 use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
 
 use crate::memory::{DecodeError, MemoryError};
-use crate::prelude::{DataSize, Encode, PageOffset, Value};
+use crate::prelude::{DataSize, DeleteBehavior, Encode, PageOffset, Value};
 
 /// Current binary version of the [`TableSchemaSnapshot`] format.
 ///
@@ -24,7 +25,8 @@ pub struct TableSchemaSnapshot {
     pub version: u8,
     /// Name of the table this snapshot was taken from.
     pub name: String,
-    /// Name of the column declared as primary key.
+    /// Name of the column declared as primary key; comma-separated for a
+    /// composite primary key.
     pub primary_key: String,
     /// Record alignment, in bytes, used for on-disk layout.
     pub alignment: u32,
@@ -109,10 +111,14 @@ impl WireSize {
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
 #[repr(u8)]
 pub enum DataTypeSnapshot {
+    /// Variable-length array of elements of the inner data type.
+    Array(Box<DataTypeSnapshot>) = 0x70,
     /// Arbitrary binary blob.
     Blob = 0x50,
     /// Boolean value.
     Boolean = 0x30,
+    /// Single Unicode scalar value.
+    Char = 0x53,
     /// User-defined custom data type, identified by name + on-disk wire layout.
     Custom(Box<CustomDataTypeSnapshot>) = 0xF0,
     /// Calendar date with no time component.
@@ -137,6 +143,8 @@ pub enum DataTypeSnapshot {
     Json = 0x60,
     /// UTF-8 text string.
     Text = 0x51,
+    /// Nanoseconds since the Unix epoch.
+    Timestamp = 0x42,
     /// UUID value.
     Uuid = 0x52,
     /// Unsigned 16-bit integer.
@@ -147,6 +155,8 @@ pub enum DataTypeSnapshot {
     Uint64 = 0x13,
     /// Unsigned 8-bit integer.
     Uint8 = 0x10,
+    /// Instant with the UTC offset of its local time.
+    ZonedDatetime = 0x43,
 }
 
 /// Snapshot of a secondary index defined on a table.
@@ -173,7 +183,7 @@ pub struct ForeignKeySnapshot {
 
 /// Stable, tag-keyed encoding of the `ON DELETE` referential action.
 ///
-/// Mirrors [`DeleteBehavior`](crate::dbms::query::delete::DeleteBehavior). Discriminants are part of the on-disk format and must not be reused
+/// Mirrors [`DeleteBehavior`]. Discriminants are part of the on-disk format and must not be reused
 /// or reordered; new variants must take a fresh tag.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
@@ -183,6 +193,18 @@ pub enum OnDeleteSnapshot {
     Restrict = 0x01,
     /// Delete dependent rows together with referenced row.
     Cascade = 0x02,
+    /// Set the foreign key of dependent rows to `NULL`.
+    SetNull = 0x03,
+}
+
+impl From<DeleteBehavior> for OnDeleteSnapshot {
+    fn from(behavior: DeleteBehavior) -> Self {
+        match behavior {
+            DeleteBehavior::Restrict => Self::Restrict,
+            DeleteBehavior::Cascade => Self::Cascade,
+            DeleteBehavior::SetNull => Self::SetNull,
+        }
+    }
 }
 
 impl TableSchemaSnapshot {
@@ -190,6 +212,22 @@ impl TableSchemaSnapshot {
     pub fn latest_version() -> u8 {
         SCHEMA_SNAPSHOT_VERSION
     }
+
+    /// Returns the 8-bit schema version of the table.
+    ///
+    /// Derived from a hash of the column names and data types, so it changes
+    /// whenever a column is added, removed, renamed or retyped. Constraints,
+    /// defaults and indexes do not contribute.
+    pub fn schema_version(&self) -> u8 {
+        let mut bytes = Vec::new();
+        for column in &self.columns {
+            bytes.extend_from_slice(&(column.name.len() as u64).to_le_bytes());
+            bytes.extend_from_slice(column.name.as_bytes());
+            bytes.extend_from_slice(&column.data_type.encode());
+        }
+        let hash = xxh3_64(&bytes);
+        hash.to_le_bytes().iter().fold(0, |acc, byte| acc ^ byte)
+    }
 }
 
 impl Encode for IndexSnapshot {
@@ -305,6 +343,7 @@ impl Encode for ForeignKeySnapshot {
         let on_delete = match data[offset] {
             0x01 => OnDeleteSnapshot::Restrict,
             0x02 => OnDeleteSnapshot::Cascade,
+            0x03 => OnDeleteSnapshot::SetNull,
             value => {
                 return Err(MemoryError::DecodeError(DecodeError::IdentityDecodeError(
                     format!("Unknown `OnDeleteSnapshot`: {value:#x}"),
@@ -327,6 +366,8 @@ impl Encode for DataTypeSnapshot {
 
     fn size(&self) -> crate::prelude::MSize {
         match self {
+            // 1 tag + element data type
+            DataTypeSnapshot::Array(elem) => 1 + elem.size(),
             // 1 tag + wire_size header + 1 name_len + name bytes
             DataTypeSnapshot::Custom(meta) => {
                 let ws_bytes: crate::prelude::MSize = match meta.wire_size {
@@ -344,8 +385,10 @@ impl Encode for DataTypeSnapshot {
 
     fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
         let tag = match self {
-            DataTypeSnapshot::Blob => 0x50u8,
+            DataTypeSnapshot::Array(_) => 0x70u8,
+            DataTypeSnapshot::Blob => 0x50,
             DataTypeSnapshot::Boolean => 0x30,
+            DataTypeSnapshot::Char => 0x53,
             DataTypeSnapshot::Custom(_) => 0xF0,
             DataTypeSnapshot::Date => 0x40,
             DataTypeSnapshot::Datetime => 0x41,
@@ -358,14 +401,22 @@ impl Encode for DataTypeSnapshot {
             DataTypeSnapshot::Int8 => 0x01,
             DataTypeSnapshot::Json => 0x60,
             DataTypeSnapshot::Text => 0x51,
+            DataTypeSnapshot::Timestamp => 0x42,
             DataTypeSnapshot::Uuid => 0x52,
             DataTypeSnapshot::Uint16 => 0x11,
             DataTypeSnapshot::Uint32 => 0x12,
             DataTypeSnapshot::Uint64 => 0x13,
             DataTypeSnapshot::Uint8 => 0x10,
+            DataTypeSnapshot::ZonedDatetime => 0x43,
         };
 
         match self {
+            DataTypeSnapshot::Array(elem) => {
+                let mut bytes = Vec::with_capacity(self.size() as usize);
+                bytes.push(tag);
+                bytes.extend_from_slice(&elem.encode());
+                std::borrow::Cow::Owned(bytes)
+            }
             DataTypeSnapshot::Custom(meta) => {
                 let mut bytes = Vec::with_capacity(self.size() as usize);
                 bytes.push(tag);
@@ -410,10 +461,17 @@ impl Encode for DataTypeSnapshot {
             0x30 => Ok(DataTypeSnapshot::Boolean),
             0x40 => Ok(DataTypeSnapshot::Date),
             0x41 => Ok(DataTypeSnapshot::Datetime),
+            0x42 => Ok(DataTypeSnapshot::Timestamp),
+            0x43 => Ok(DataTypeSnapshot::ZonedDatetime),
             0x50 => Ok(DataTypeSnapshot::Blob),
             0x51 => Ok(DataTypeSnapshot::Text),
             0x52 => Ok(DataTypeSnapshot::Uuid),
+            0x53 => Ok(DataTypeSnapshot::Char),
             0x60 => Ok(DataTypeSnapshot::Json),
+            0x70 => {
+                let elem = DataTypeSnapshot::decode(std::borrow::Cow::Owned(data[1..].to_vec()))?;
+                Ok(DataTypeSnapshot::Array(Box::new(elem)))
+            }
             0xF0 => {
                 if data.len() < 2 {
                     return Err(MemoryError::DecodeError(DecodeError::TooShort));
@@ -545,37 +603,12 @@ impl Encode for ColumnSnapshot {
         let name = String::from_utf8(data[offset..offset + name_len].to_vec())?;
         offset += name_len;
 
-        // data_type: peek tag, derive consumed length
+        // data_type: decode from the remaining bytes, then skip its encoded length
         if data.len() < offset + 1 {
             return Err(MemoryError::DecodeError(DecodeError::TooShort));
         }
-        let dt_consumed = if data[offset] == 0xF0 {
-            if data.len() < offset + 2 {
-                return Err(MemoryError::DecodeError(DecodeError::TooShort));
-            }
-            let header = match data[offset + 1] {
-                0x01 => 6,
-                0x02 => 2,
-                v => {
-                    return Err(MemoryError::DecodeError(DecodeError::IdentityDecodeError(
-                        format!("Unknown WireSize tag: {v:#x}"),
-                    )));
-                }
-            };
-            if data.len() < offset + header + 1 {
-                return Err(MemoryError::DecodeError(DecodeError::TooShort));
-            }
-            header + 1 + data[offset + header] as usize
-        } else {
-            1
-        };
-        if data.len() < offset + dt_consumed {
-            return Err(MemoryError::DecodeError(DecodeError::TooShort));
-        }
-        let data_type = DataTypeSnapshot::decode(std::borrow::Cow::Owned(
-            data[offset..offset + dt_consumed].to_vec(),
-        ))?;
-        offset += dt_consumed;
+        let data_type = DataTypeSnapshot::decode(std::borrow::Cow::Borrowed(&data[offset..]))?;
+        offset += data_type.size() as usize;
 
         if data.len() < offset + 1 {
             return Err(MemoryError::DecodeError(DecodeError::TooShort));
@@ -852,7 +885,11 @@ mod tests {
 
     #[test]
     fn test_foreign_key_snapshot_roundtrip() {
-        for on_delete in [OnDeleteSnapshot::Restrict, OnDeleteSnapshot::Cascade] {
+        for on_delete in [
+            OnDeleteSnapshot::Restrict,
+            OnDeleteSnapshot::Cascade,
+            OnDeleteSnapshot::SetNull,
+        ] {
             let fk = ForeignKeySnapshot {
                 table: "users".to_string(),
                 column: "id".to_string(),
@@ -875,8 +912,16 @@ mod tests {
     #[test]
     fn test_data_type_snapshot_roundtrip_all_variants() {
         let cases = [
+            DataTypeSnapshot::Array(Box::new(DataTypeSnapshot::Uint32)),
+            DataTypeSnapshot::Array(Box::new(DataTypeSnapshot::Custom(Box::new(
+                CustomDataTypeSnapshot {
+                    tag: "Money".to_string(),
+                    wire_size: WireSize::Fixed(16),
+                },
+            )))),
             DataTypeSnapshot::Blob,
             DataTypeSnapshot::Boolean,
+            DataTypeSnapshot::Char,
             DataTypeSnapshot::Date,
             DataTypeSnapshot::Datetime,
             DataTypeSnapshot::Decimal,
@@ -888,11 +933,13 @@ mod tests {
             DataTypeSnapshot::Int64,
             DataTypeSnapshot::Json,
             DataTypeSnapshot::Text,
+            DataTypeSnapshot::Timestamp,
             DataTypeSnapshot::Uint8,
             DataTypeSnapshot::Uint16,
             DataTypeSnapshot::Uint32,
             DataTypeSnapshot::Uint64,
             DataTypeSnapshot::Uuid,
+            DataTypeSnapshot::ZonedDatetime,
             DataTypeSnapshot::Custom(Box::new(CustomDataTypeSnapshot {
                 tag: "Money".to_string(),
                 wire_size: WireSize::Fixed(16),
@@ -960,10 +1007,19 @@ mod tests {
         assert_eq!(DataTypeSnapshot::Boolean.encode()[0], 0x30);
         assert_eq!(DataTypeSnapshot::Date.encode()[0], 0x40);
         assert_eq!(DataTypeSnapshot::Datetime.encode()[0], 0x41);
+        assert_eq!(DataTypeSnapshot::Timestamp.encode()[0], 0x42);
+        assert_eq!(DataTypeSnapshot::ZonedDatetime.encode()[0], 0x43);
         assert_eq!(DataTypeSnapshot::Blob.encode()[0], 0x50);
         assert_eq!(DataTypeSnapshot::Text.encode()[0], 0x51);
         assert_eq!(DataTypeSnapshot::Uuid.encode()[0], 0x52);
+        assert_eq!(DataTypeSnapshot::Char.encode()[0], 0x53);
         assert_eq!(DataTypeSnapshot::Json.encode()[0], 0x60);
+        assert_eq!(
+            DataTypeSnapshot::Array(Box::new(DataTypeSnapshot::Text))
+                .encode()
+                .as_ref(),
+            &[0x70, 0x51]
+        );
         assert_eq!(
             DataTypeSnapshot::Custom(Box::new(CustomDataTypeSnapshot {
                 tag: "x".into(),
@@ -993,6 +1049,15 @@ mod tests {
         assert_eq!(roundtrip(col.clone()), col);
     }
 
+    #[test]
+    fn test_column_snapshot_array_roundtrip() {
+        let col = ColumnSnapshot {
+            data_type: DataTypeSnapshot::Array(Box::new(DataTypeSnapshot::Text)),
+            ..sample_column("tags")
+        };
+        assert_eq!(roundtrip(col.clone()), col);
+    }
+
     #[test]
     fn test_column_snapshot_all_flags_roundtrip() {
         let col = ColumnSnapshot {
@@ -1187,4 +1252,38 @@ mod tests {
             SCHEMA_SNAPSHOT_VERSION
         );
     }
+
+    #[test]
+    fn test_schema_version_tracks_column_names_and_types() {
+        let snap = TableSchemaSnapshot {
+            version: TableSchemaSnapshot::latest_version(),
+            name: "users".to_string(),
+            primary_key: "id".to_string(),
+            alignment: 32,
+            columns: vec![sample_column("id"), sample_column("name")],
+            indexes: vec![],
+        };
+        let version = snap.schema_version();
+
+        let mut constrained = snap.clone();
+        constrained.columns[1].nullable = true;
+        constrained.columns[1].unique = true;
+        constrained.indexes.push(IndexSnapshot {
+            columns: vec!["name".to_string()],
+            unique: true,
+        });
+        assert_eq!(constrained.schema_version(), version);
+
+        let mut renamed = snap.clone();
+        renamed.columns[1].name = "email".to_string();
+        assert_ne!(renamed.schema_version(), version);
+
+        let mut retyped = snap.clone();
+        retyped.columns[1].data_type = DataTypeSnapshot::Text;
+        assert_ne!(retyped.schema_version(), version);
+
+        let mut extended = snap;
+        extended.columns.push(sample_column("age"));
+        assert_ne!(extended.schema_version(), version);
+    }
 }
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Storage statistics of a single table.
+///
+/// The byte counters cover the pages holding the table records only; the
+/// pages used by the table ledgers and indexes are not accounted for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct TableStats {
+    /// Name of the table.
+    pub table: String,
+    /// Number of records stored in the table.
+    pub rows: u64,
+    /// Number of pages holding the table records.
+    pub pages: u64,
+    /// Bytes taken by live records, including their headers and padding.
+    pub used_bytes: u64,
+    /// Bytes never written at the end of the table pages.
+    pub free_bytes: u64,
+    /// Bytes released by deleted or relocated records, which can be reused
+    /// by new records that fit in them.
+    pub fragmented_bytes: u64,
+}
+
+impl TableStats {
+    /// Returns the bytes of the pages holding the table records, whether used,
+    /// free or fragmented.
+    pub fn allocated_bytes(&self) -> u64 {
+        self.used_bytes + self.free_bytes + self.fragmented_bytes
+    }
+
+    /// Returns the average number of bytes taken by a record, or `0` if the
+    /// table is empty.
+    pub fn average_row_size(&self) -> u64 {
+        self.used_bytes.checked_div(self.rows).unwrap_or_default()
+    }
+}
+
+/// Outcome of a [`Database::compact`](crate::prelude::Database::compact) call.
+///
+/// The page counters cover the pages holding the table records only, as for
+/// [`TableStats::pages`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct CompactionReport {
+    /// Number of pages holding the table records before the call.
+    pub pages_before: u64,
+    /// Number of pages holding the table records after the call.
+    pub pages_after: u64,
+    /// Bytes of the released pages, which any table can now reuse.
+    pub bytes_reclaimed: u64,
+    /// Whether no further page can be released. When `false`, the call
+    /// stopped at its page limit, and calling again releases more pages.
+    pub complete: bool,
+}
@@ -3,12 +3,25 @@ use serde::{Deserialize, Serialize};
 /// Type alias for Transaction ID
 pub type TransactionId = u64;
 
+/// Type alias for Savepoint ID, unique within its transaction.
+pub type SavepointId = u64;
+
 /// An enum representing possible errors that can occur during transaction operations.
 #[derive(Debug, thiserror::Error, Serialize, Deserialize)]
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
 pub enum TransactionError {
     #[error("No active transaction")]
     NoActiveTransaction,
+    /// The savepoint does not exist in the transaction, or was discarded by
+    /// rolling back to an earlier savepoint.
+    #[error("Savepoint not found: {0}")]
+    SavepointNotFound(SavepointId),
+    /// The transaction outlived its TTL and was dropped by the expiry sweep.
+    #[error("Transaction expired: {0}")]
+    TransactionExpired(TransactionId),
+    /// The owner already holds the maximum number of open transactions.
+    #[error("Too many open transactions (limit: {limit})")]
+    TooManyOpenTransactions { limit: u32 },
 }
 
 #[cfg(test)]
@@ -20,6 +33,15 @@ mod test {
     fn test_should_display_transaction_error() {
         let error = TransactionError::NoActiveTransaction;
         assert_eq!(error.to_string(), "No active transaction");
+
+        let error = TransactionError::SavepointNotFound(3);
+        assert_eq!(error.to_string(), "Savepoint not found: 3");
+
+        let error = TransactionError::TransactionExpired(7);
+        assert_eq!(error.to_string(), "Transaction expired: 7");
+
+        let error = TransactionError::TooManyOpenTransactions { limit: 8 };
+        assert_eq!(error.to_string(), "Too many open transactions (limit: 8)");
     }
 
     #[cfg(feature = "candid")]
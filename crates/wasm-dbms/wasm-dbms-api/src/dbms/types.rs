@@ -5,27 +5,39 @@ use serde::{Deserialize, Serialize};
 use crate::dbms::value::Value;
 use crate::memory::Encode;
 
+mod array;
 mod blob;
 mod boolean;
+mod character;
 mod date;
 mod datetime;
 mod decimal;
+mod float;
 mod integers;
 mod json;
 mod nullable;
 mod text;
+mod timestamp;
+mod unit_enum;
 mod uuid;
+mod zoned_datetime;
 
+pub use self::array::{ArrayElement, ArrayOf};
 pub use self::blob::Blob;
 pub use self::boolean::Boolean;
+pub use self::character::Char;
 pub use self::date::Date;
 pub use self::datetime::DateTime;
 pub use self::decimal::Decimal;
+pub use self::float::{Float32, Float64};
 pub use self::integers::{Int8, Int16, Int32, Int64, Uint8, Uint16, Uint32, Uint64};
 pub use self::json::Json;
 pub use self::nullable::Nullable;
 pub use self::text::Text;
+pub use self::timestamp::Timestamp;
+pub use self::unit_enum::{unit_enum_from_value, unit_enum_to_value};
 pub use self::uuid::Uuid;
+pub use self::zoned_datetime::ZonedDateTime;
 
 /// A trait representing a data type that can be stored in the DBMS.
 ///
@@ -66,27 +78,44 @@ pub trait DataType:
 pub trait CustomDataType: DataType {
     /// Unique string identifier for this type (e.g., `"principal"`, `"role"`).
     const TYPE_TAG: &'static str;
+
+    /// Names of the variants of an enum type, in declaration order, or `None`
+    /// for any other type.
+    ///
+    /// Set by `#[derive(DbmsEnum)]`; columns of such types get the
+    /// [`DataTypeKind::Enum`] kind and reject discriminants out of range.
+    const VARIANTS: Option<&'static [&'static str]> = None;
 }
 
 /// An enumeration of all supported data type kinds in the DBMS.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DataTypeKind {
+    /// A variable-length array of elements of the given kind.
+    Array(&'static DataTypeKind),
     Blob,
     Boolean,
+    Char,
     Date,
     DateTime,
     Decimal,
+    Float32,
+    Float64,
     Int8,
     Int16,
     Int32,
     Int64,
     Json,
     Text,
+    Timestamp,
     Uint8,
     Uint16,
     Uint32,
     Uint64,
     Uuid,
+    ZonedDateTime,
+    /// A `#[derive(DbmsEnum)]` type, stored as the one-byte declaration index
+    /// of the variant. Carries the names of the variants, in declaration order.
+    Enum(&'static [&'static str]),
     /// A user-defined custom type. Carries the stable [`CustomDataType::TYPE_TAG`]
     /// and a [`crate::dbms::table::WireSize`] descriptor so the migration codec
     /// can slice column bytes without invoking the user's `Encode::decode`.
@@ -106,25 +135,32 @@ mod test {
     #[test]
     fn test_should_create_all_data_type_kind_variants() {
         let kinds = [
+            DataTypeKind::Array(&DataTypeKind::Uint32),
             DataTypeKind::Blob,
             DataTypeKind::Boolean,
+            DataTypeKind::Char,
             DataTypeKind::Date,
             DataTypeKind::DateTime,
             DataTypeKind::Decimal,
+            DataTypeKind::Float32,
+            DataTypeKind::Float64,
             DataTypeKind::Int8,
             DataTypeKind::Int16,
             DataTypeKind::Int32,
             DataTypeKind::Int64,
             DataTypeKind::Json,
             DataTypeKind::Text,
+            DataTypeKind::Timestamp,
             DataTypeKind::Uint8,
             DataTypeKind::Uint16,
             DataTypeKind::Uint32,
             DataTypeKind::Uint64,
             DataTypeKind::Uuid,
+            DataTypeKind::ZonedDateTime,
+            DataTypeKind::Enum(&["Active", "Inactive"]),
         ];
 
-        assert_eq!(kinds.len(), 16);
+        assert_eq!(kinds.len(), 23);
     }
 
     #[test]
@@ -171,6 +207,8 @@ mod test {
         assert_eq!(format!("{:?}", DataTypeKind::Date), "Date");
         assert_eq!(format!("{:?}", DataTypeKind::DateTime), "DateTime");
         assert_eq!(format!("{:?}", DataTypeKind::Decimal), "Decimal");
+        assert_eq!(format!("{:?}", DataTypeKind::Float32), "Float32");
+        assert_eq!(format!("{:?}", DataTypeKind::Float64), "Float64");
         assert_eq!(format!("{:?}", DataTypeKind::Int8), "Int8");
         assert_eq!(format!("{:?}", DataTypeKind::Int16), "Int16");
         assert_eq!(format!("{:?}", DataTypeKind::Int32), "Int32");
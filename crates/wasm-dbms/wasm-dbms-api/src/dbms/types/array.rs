@@ -0,0 +1,252 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dbms::types::{self, DataType};
+use crate::dbms::value::Value;
+use crate::memory::{DEFAULT_ALIGNMENT, DataSize, Encode, PageOffset};
+
+/// Variable-length array data type for the DBMS.
+///
+/// Stores a list of primitive values of the same type `T` in a single column.
+/// It is encoded as a 2-byte little-endian element count followed by the
+/// encoding of each element.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+#[serde(bound(deserialize = "T: ArrayElement"))]
+pub struct ArrayOf<T>(pub Vec<T>)
+where
+    T: ArrayElement;
+
+/// A [`DataType`] which can be stored as an element of an [`ArrayOf`].
+///
+/// Implemented for the primitive data types of the DBMS.
+pub trait ArrayElement: DataType {
+    /// Extracts the element from a [`Value`] of the matching variant.
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+macro_rules! impl_array_element {
+    ($ty:ident, $accessor:ident) => {
+        impl ArrayElement for types::$ty {
+            fn from_value(value: &Value) -> Option<Self> {
+                value.$accessor().cloned()
+            }
+        }
+    };
+}
+
+impl_array_element!(Blob, as_blob);
+impl_array_element!(Boolean, as_boolean);
+impl_array_element!(Char, as_char);
+impl_array_element!(Date, as_date);
+impl_array_element!(DateTime, as_datetime);
+impl_array_element!(Decimal, as_decimal);
+impl_array_element!(Float32, as_float32);
+impl_array_element!(Float64, as_float64);
+impl_array_element!(Int8, as_int8);
+impl_array_element!(Int16, as_int16);
+impl_array_element!(Int32, as_int32);
+impl_array_element!(Int64, as_int64);
+impl_array_element!(Json, as_json);
+impl_array_element!(Text, as_text);
+impl_array_element!(Timestamp, as_timestamp);
+impl_array_element!(Uint8, as_uint8);
+impl_array_element!(Uint16, as_uint16);
+impl_array_element!(Uint32, as_uint32);
+impl_array_element!(Uint64, as_uint64);
+impl_array_element!(Uuid, as_uuid);
+impl_array_element!(ZonedDateTime, as_zoned_datetime);
+
+impl<T> ArrayOf<T>
+where
+    T: ArrayElement,
+{
+    /// Extracts an array from a [`Value::Array`] whose elements all match `T`.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        value
+            .as_array()?
+            .iter()
+            .map(T::from_value)
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+    }
+
+    /// Returns the elements of the array as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    /// Returns the number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<T> fmt::Display for ArrayOf<T>
+where
+    T: ArrayElement,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, elem) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{elem}")?;
+        }
+        write!(f, "]")
+    }
+}
+
+impl<T> From<Vec<T>> for ArrayOf<T>
+where
+    T: ArrayElement,
+{
+    fn from(value: Vec<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<ArrayOf<T>> for Value
+where
+    T: ArrayElement,
+{
+    fn from(array: ArrayOf<T>) -> Self {
+        Value::Array(array.0.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T> Encode for ArrayOf<T>
+where
+    T: ArrayElement,
+{
+    const SIZE: DataSize = DataSize::Dynamic;
+
+    const ALIGNMENT: PageOffset = DEFAULT_ALIGNMENT;
+
+    fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+        let mut bytes = Vec::with_capacity(self.size() as usize);
+        // put 2 bytes for the number of elements
+        let len = self.0.len() as u16;
+        bytes.extend_from_slice(&len.to_le_bytes());
+        for elem in &self.0 {
+            bytes.extend_from_slice(&elem.encode());
+        }
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn decode(data: std::borrow::Cow<[u8]>) -> crate::memory::MemoryResult<Self>
+    where
+        Self: Sized,
+    {
+        if data.len() < 2 {
+            return Err(crate::memory::MemoryError::DecodeError(
+                crate::memory::DecodeError::TooShort,
+            ));
+        }
+
+        // read number of elements
+        let count = u16::from_le_bytes([data[0], data[1]]) as usize;
+        let mut offset = 2;
+        let mut elems = Vec::with_capacity(count);
+        for _ in 0..count {
+            if offset > data.len() {
+                return Err(crate::memory::MemoryError::DecodeError(
+                    crate::memory::DecodeError::TooShort,
+                ));
+            }
+            let elem = T::decode(std::borrow::Cow::Borrowed(&data[offset..]))?;
+            offset += elem.size() as usize;
+            elems.push(elem);
+        }
+
+        Ok(Self(elems))
+    }
+
+    fn size(&self) -> crate::memory::MSize {
+        2 + self
+            .0
+            .iter()
+            .map(Encode::size)
+            .sum::<crate::memory::MSize>()
+    }
+}
+
+impl<T> DataType for ArrayOf<T> where T: ArrayElement {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::dbms::types::{Text, Uint32};
+
+    #[test]
+    fn test_should_encode_and_decode_array() {
+        let array = ArrayOf(vec![Uint32(1), Uint32(2), Uint32(3)]);
+        let encoded = array.encode();
+        assert_eq!(encoded.len(), 2 + 3 * 4);
+        assert_eq!(&encoded[0..2], &3u16.to_le_bytes());
+        let decoded = ArrayOf::<Uint32>::decode(encoded).unwrap();
+        assert_eq!(array, decoded);
+    }
+
+    #[test]
+    fn test_should_encode_and_decode_dynamic_elements() {
+        let array = ArrayOf(vec![
+            Text("foo".into()),
+            Text::default(),
+            Text("bar".into()),
+        ]);
+        let decoded = ArrayOf::<Text>::decode(array.encode()).unwrap();
+        assert_eq!(array, decoded);
+        assert_eq!(decoded.size() as usize, array.encode().len());
+    }
+
+    #[test]
+    fn test_should_encode_and_decode_empty_array() {
+        let array = ArrayOf::<Uint32>::default();
+        let decoded = ArrayOf::<Uint32>::decode(array.encode()).unwrap();
+        assert!(decoded.is_empty());
+    }
+
+    #[test]
+    fn test_should_fail_to_decode_truncated_array() {
+        let array = ArrayOf(vec![Uint32(1), Uint32(2)]);
+        let encoded = array.encode();
+        assert!(ArrayOf::<Uint32>::decode(std::borrow::Cow::Borrowed(&encoded[..5])).is_err());
+    }
+
+    #[test]
+    fn test_should_convert_array_to_and_from_value() {
+        let array = ArrayOf(vec![Uint32(7), Uint32(9)]);
+        let value = Value::from(array.clone());
+        assert_eq!(
+            value,
+            Value::Array(vec![Value::Uint32(Uint32(7)), Value::Uint32(Uint32(9))])
+        );
+        assert_eq!(ArrayOf::<Uint32>::from_value(&value), Some(array));
+        assert_eq!(ArrayOf::<Text>::from_value(&value), None);
+        assert_eq!(ArrayOf::<Uint32>::from_value(&Value::Null), None);
+    }
+
+    #[test]
+    fn test_should_display_array() {
+        let array = ArrayOf(vec![Uint32(1), Uint32(2)]);
+        assert_eq!(array.to_string(), "[1, 2]");
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode() {
+        let src = ArrayOf(vec![Uint32(10), Uint32(20)]);
+        let buf = candid::encode_one(&src).expect("Candid encoding failed");
+        let decoded: ArrayOf<Uint32> = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(src, decoded);
+    }
+}
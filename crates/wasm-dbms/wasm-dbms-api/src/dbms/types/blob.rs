@@ -10,6 +10,18 @@ use crate::memory::{DEFAULT_ALIGNMENT, DataSize, Encode, PageOffset};
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
 pub struct Blob(pub Vec<u8>);
 
+impl Blob {
+    /// Returns the length of the blob in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the blob is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
 impl fmt::Display for Blob {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Blob(len={})", self.0.len())
@@ -90,6 +102,12 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn test_blob_len() {
+        assert_eq!(Blob(vec![1, 2, 3]).len(), 3);
+        assert!(Blob::default().is_empty());
+    }
+
     #[cfg(feature = "candid")]
     #[test]
     fn test_should_candid_encode_decode() {
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dbms::types::DataType;
+use crate::memory::{DataSize, DecodeError, Encode, MSize, MemoryError, MemoryResult, PageOffset};
+
+const CHAR_SIZE: usize = 4;
+
+/// Char data type for the DBMS: a single Unicode scalar value.
+///
+/// Unlike a one-character [`Text`](super::Text), a `Char` is stored as exactly
+/// 4 bytes (UTF-32 LE) with no length prefix, so it suits single-character
+/// codes such as grade letters or currency symbols.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Char(pub char);
+
+impl fmt::Display for Char {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<char> for Char {
+    fn from(c: char) -> Self {
+        Self(c)
+    }
+}
+
+impl From<Char> for char {
+    fn from(c: Char) -> Self {
+        c.0
+    }
+}
+
+#[cfg(feature = "candid")]
+impl candid::CandidType for Char {
+    fn _ty() -> candid::types::Type {
+        candid::types::Type(std::rc::Rc::new(candid::types::TypeInner::Text))
+    }
+
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        serializer.serialize_text(self.0.encode_utf8(&mut [0u8; CHAR_SIZE]))
+    }
+}
+
+impl Serialize for Char {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.encode_utf8(&mut [0u8; CHAR_SIZE]))
+    }
+}
+
+impl<'de> Deserialize<'de> for Char {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(Self(c)),
+            _ => Err(serde::de::Error::custom(
+                "Char must be exactly one Unicode scalar value",
+            )),
+        }
+    }
+}
+
+impl Encode for Char {
+    const SIZE: DataSize = DataSize::Fixed(CHAR_SIZE as MSize);
+
+    const ALIGNMENT: PageOffset = CHAR_SIZE as PageOffset;
+
+    fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+        std::borrow::Cow::Owned(u32::from(self.0).to_le_bytes().to_vec())
+    }
+
+    fn decode(data: std::borrow::Cow<[u8]>) -> MemoryResult<Self>
+    where
+        Self: Sized,
+    {
+        let Some(bytes) = data.get(..CHAR_SIZE) else {
+            return Err(MemoryError::DecodeError(DecodeError::TooShort));
+        };
+
+        let mut array = [0u8; CHAR_SIZE];
+        array.copy_from_slice(bytes);
+        let code_point = u32::from_le_bytes(array);
+        char::from_u32(code_point).map(Self).ok_or_else(|| {
+            MemoryError::DecodeError(DecodeError::Utf8Error(format!(
+                "invalid Unicode scalar value {code_point:#x}"
+            )))
+        })
+    }
+
+    fn size(&self) -> MSize {
+        Self::SIZE.get_fixed_size().expect("should be fixed")
+    }
+}
+
+impl DataType for Char {}
+
+/// Lets the `Table` derive store Rust `char` fields as `Char` columns.
+impl Encode for char {
+    const SIZE: DataSize = Char::SIZE;
+
+    const ALIGNMENT: PageOffset = Char::ALIGNMENT;
+
+    fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+        std::borrow::Cow::Owned(Char(*self).encode().into_owned())
+    }
+
+    fn decode(data: std::borrow::Cow<[u8]>) -> MemoryResult<Self>
+    where
+        Self: Sized,
+    {
+        Char::decode(data).map(char::from)
+    }
+
+    fn size(&self) -> MSize {
+        Char::SIZE.get_fixed_size().expect("should be fixed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_char_encode_decode() {
+        for c in ['A', 'é', '€', '🦀'] {
+            let value = Char(c);
+            let encoded = value.encode();
+            assert_eq!(encoded.len(), CHAR_SIZE);
+            assert_eq!(value.size(), CHAR_SIZE as MSize);
+            assert_eq!(encoded.as_ref(), u32::from(c).to_le_bytes());
+            let decoded = Char::decode(encoded).unwrap();
+            assert_eq!(value, decoded);
+        }
+    }
+
+    #[test]
+    fn test_char_decode_invalid_scalar_value() {
+        let surrogate = 0xD800u32.to_le_bytes();
+        let result = Char::decode(std::borrow::Cow::Borrowed(&surrogate));
+        assert!(matches!(
+            result,
+            Err(MemoryError::DecodeError(DecodeError::Utf8Error(_)))
+        ));
+
+        let result = Char::decode(std::borrow::Cow::Borrowed(&[0u8; 2]));
+        assert!(matches!(
+            result,
+            Err(MemoryError::DecodeError(DecodeError::TooShort))
+        ));
+    }
+
+    #[test]
+    fn test_char_conversions_and_ordering() {
+        assert!(Char::from('a') < Char::from('b'));
+        assert_eq!(char::from(Char('z')), 'z');
+        assert_eq!(Char('€').to_string(), "€");
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode() {
+        let src = Char('€');
+        let buf = candid::encode_one(src).expect("Candid encoding failed");
+        let decoded: Char = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(src, decoded);
+        let as_text: String = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(as_text, "€");
+    }
+}
@@ -2,7 +2,7 @@ use core::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use crate::dbms::types::DataType;
+use crate::dbms::types::{DataType, Timestamp};
 use crate::memory::{DataSize, Encode, MSize, PageOffset};
 
 const TYPE_SIZE: usize = 2 + 1 + 1 + 1 + 1 + 1 + 4 + 2; // year + month + day + hour + minute + second + microsecond + timezone_offset_minutes
@@ -43,6 +43,42 @@ impl fmt::Display for DateTime {
 
 impl DataType for DateTime {}
 
+impl From<Timestamp> for DateTime {
+    /// Converts a [`Timestamp`] into the matching UTC date time.
+    fn from(timestamp: Timestamp) -> Self {
+        const NANOS_PER_SECOND: u64 = 1_000_000_000;
+        const SECONDS_PER_DAY: u64 = 86_400;
+
+        let nanos = timestamp.as_nanos();
+        let seconds = nanos / NANOS_PER_SECOND;
+        let days = seconds / SECONDS_PER_DAY;
+        let seconds_of_day = seconds % SECONDS_PER_DAY;
+
+        // civil date from days since the epoch (proleptic gregorian calendar)
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds_of_day / 3_600) as u8,
+            minute: (seconds_of_day % 3_600 / 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+            microsecond: (nanos % NANOS_PER_SECOND / 1_000) as u32,
+            timezone_offset_minutes: 0,
+        }
+    }
+}
+
 impl Encode for DateTime {
     const SIZE: DataSize = DataSize::Fixed(TYPE_SIZE as MSize);
 
@@ -119,6 +155,34 @@ mod tests {
         assert_eq!(value, decoded);
     }
 
+    #[test]
+    fn test_should_convert_timestamp_into_datetime() {
+        assert_eq!(
+            DateTime::from(Timestamp(0)),
+            DateTime {
+                year: 1970,
+                month: 1,
+                day: 1,
+                ..Default::default()
+            }
+        );
+        // 2024-02-29T23:59:58.123456Z
+        let timestamp = Timestamp(1_709_251_198_123_456_789);
+        assert_eq!(
+            DateTime::from(timestamp),
+            DateTime {
+                year: 2024,
+                month: 2,
+                day: 29,
+                hour: 23,
+                minute: 59,
+                second: 58,
+                microsecond: 123_456,
+                timezone_offset_minutes: 0,
+            }
+        );
+    }
+
     #[test]
     fn test_date_display() {
         let date = DateTime {
@@ -1,6 +1,7 @@
 use rust_decimal::Decimal as RustDecimal;
 use serde::{Deserialize, Serialize};
 
+use crate::dbms::types::DataType;
 use crate::memory::{DataSize, DecodeError, Encode, MSize, PageOffset};
 
 const RUST_DECIMAL_ENCODE_SIZE: MSize = 16;
@@ -21,6 +22,65 @@ impl From<Decimal> for RustDecimal {
     }
 }
 
+impl Decimal {
+    /// Returns the number of fractional digits of the value, trailing zeros
+    /// included.
+    pub fn scale(&self) -> u32 {
+        self.0.scale()
+    }
+
+    /// Returns whether the value fits a SQL `DECIMAL(precision, scale)`: at most
+    /// `scale` fractional digits and `precision - scale` integer digits.
+    ///
+    /// Trailing zeros of the fractional part are not counted.
+    pub fn fits(&self, precision: u32, scale: u32) -> bool {
+        let normalized = self.0.normalize();
+        if normalized.scale() > scale {
+            return false;
+        }
+        let integer = normalized.trunc().abs();
+        let integer_digits = if integer.is_zero() {
+            0
+        } else {
+            integer.to_string().len() as u32
+        };
+
+        integer_digits <= precision.saturating_sub(scale)
+    }
+
+    /// Rounds the value to `scale` fractional digits, using banker's rounding.
+    pub fn round_to_scale(&self, scale: u32) -> Self {
+        Self(self.0.round_dp(scale))
+    }
+
+    /// Adds `other`, returning `None` on overflow.
+    ///
+    /// The scale of the result is the larger of the two scales.
+    pub fn checked_add(&self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtracts `other`, returning `None` on overflow.
+    ///
+    /// The scale of the result is the larger of the two scales.
+    pub fn checked_sub(&self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Multiplies by `other`, returning `None` on overflow.
+    ///
+    /// The scale of the result is the sum of the two scales, reduced when it
+    /// doesn't fit the 28 digits of a `Decimal`.
+    pub fn checked_mul(&self, other: Self) -> Option<Self> {
+        self.0.checked_mul(other.0).map(Self)
+    }
+
+    /// Divides by `other`, returning `None` on overflow or division by zero.
+    pub fn checked_div(&self, other: Self) -> Option<Self> {
+        self.0.checked_div(other.0).map(Self)
+    }
+}
+
 impl std::fmt::Display for Decimal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -75,7 +135,7 @@ impl Encode for Decimal {
     where
         Self: Sized,
     {
-        if data.len() != RUST_DECIMAL_ENCODE_SIZE as usize {
+        if data.len() < RUST_DECIMAL_ENCODE_SIZE as usize {
             return Err(crate::memory::MemoryError::DecodeError(
                 DecodeError::TooShort,
             ));
@@ -95,11 +155,39 @@ impl Encode for Decimal {
     }
 }
 
+impl DataType for Decimal {}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_decimal_fits_precision_and_scale() {
+        assert!(Decimal(RustDecimal::new(99999, 2)).fits(5, 2));
+        assert!(Decimal(RustDecimal::new(-1230, 3)).fits(3, 2)); // -1.230
+        assert!(Decimal(RustDecimal::ZERO).fits(2, 2));
+        assert!(!Decimal(RustDecimal::new(12345, 3)).fits(5, 2));
+        assert!(!Decimal(RustDecimal::new(100, 0)).fits(4, 2));
+    }
+
+    #[test]
+    fn test_decimal_checked_arithmetic() {
+        let a = Decimal(RustDecimal::new(150, 2)); // 1.50
+        let b = Decimal(RustDecimal::new(25, 1)); // 2.5
+        assert_eq!(a.checked_add(b), Some(Decimal(RustDecimal::new(400, 2))));
+        assert_eq!(a.checked_sub(b), Some(Decimal(RustDecimal::new(-100, 2))));
+        let product = a.checked_mul(b).unwrap();
+        assert_eq!(product, Decimal(RustDecimal::new(375, 2)));
+        assert_eq!(product.scale(), 3);
+        assert_eq!(a.checked_div(Decimal(RustDecimal::ZERO)), None);
+        assert_eq!(Decimal(RustDecimal::MAX).checked_add(a), None);
+        assert_eq!(
+            Decimal(RustDecimal::new(12345, 3)).round_to_scale(2),
+            Decimal(RustDecimal::new(1234, 2))
+        );
+    }
+
     #[test]
     fn test_decimal_encode_decode() {
         let original_decimal = Decimal(RustDecimal::new(12345, 2)); // Represents 123.45
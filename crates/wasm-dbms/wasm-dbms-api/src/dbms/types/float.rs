@@ -0,0 +1,212 @@
+//! This module defines the IEEE 754 floating-point types.
+//!
+//! Floats don't implement [`Eq`], [`Ord`] and [`Hash`] natively, so equality,
+//! ordering and hashing are all derived from [`f32::total_cmp`] /
+//! [`f64::total_cmp`] and the raw bit pattern. This makes `NaN` deterministic:
+//! it equals itself and sorts after every other value (negative `NaN` sorts
+//! first), and `-0.0` is ordered before `0.0`.
+
+/// a macro to define float types with common traits and implementations
+macro_rules! float_type {
+    ($name:ident, $candid_type:path, $candid_serialize:ident, $primitive:ty, $tests_name:ident) => {
+        /// A float type wrapper around a primitive floating-point type.
+        #[derive(Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+        pub struct $name(pub $primitive);
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.to_bits() == other.0.to_bits()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl std::hash::Hash for $name {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state);
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        #[cfg(feature = "candid")]
+        impl candid::CandidType for $name {
+            fn _ty() -> candid::types::Type {
+                candid::types::Type(std::rc::Rc::new($candid_type))
+            }
+
+            fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+            where
+                S: candid::types::Serializer,
+            {
+                serializer.$candid_serialize(self.0)
+            }
+        }
+
+        impl $name {
+            const MEM_SIZE: usize = std::mem::size_of::<$primitive>();
+        }
+
+        impl $crate::prelude::Encode for $name {
+            const SIZE: $crate::prelude::DataSize =
+                $crate::prelude::DataSize::Fixed(Self::MEM_SIZE as $crate::prelude::MSize);
+
+            const ALIGNMENT: $crate::prelude::PageOffset =
+                std::mem::align_of::<$primitive>() as $crate::prelude::PageOffset;
+
+            fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+                std::borrow::Cow::Owned(self.0.to_le_bytes().to_vec())
+            }
+
+            fn decode(data: std::borrow::Cow<[u8]>) -> crate::memory::MemoryResult<Self>
+            where
+                Self: Sized,
+            {
+                if data.len() < Self::MEM_SIZE {
+                    return Err(crate::memory::MemoryError::DecodeError(
+                        crate::memory::DecodeError::TooShort,
+                    ));
+                }
+
+                let mut array = <$primitive>::to_le_bytes(0.0);
+                array.copy_from_slice(&data[..Self::MEM_SIZE]);
+                Ok(Self(<$primitive>::from_le_bytes(array)))
+            }
+
+            fn size(&self) -> crate::memory::MSize {
+                Self::SIZE.get_fixed_size().expect("should be fixed")
+            }
+        }
+
+        impl $crate::prelude::DataType for $name {}
+
+        impl From<$primitive> for $name {
+            fn from(value: $primitive) -> Self {
+                $name(value)
+            }
+        }
+
+        #[cfg(test)]
+        mod $tests_name {
+
+            use std::collections::HashSet;
+
+            use super::*;
+
+            #[test]
+            fn test_constants() {
+                use $crate::prelude::Encode;
+
+                let value = $name(0.0);
+                assert_eq!(value.size() as usize, std::mem::size_of::<$primitive>());
+                assert_eq!(
+                    $name::ALIGNMENT,
+                    std::mem::align_of::<$primitive>() as $crate::prelude::PageOffset
+                );
+            }
+
+            #[test]
+            fn test_encode_decode() {
+                use $crate::prelude::Encode;
+
+                for num in [
+                    1.5 as $primitive,
+                    -0.0,
+                    <$primitive>::MAX,
+                    <$primitive>::INFINITY,
+                    <$primitive>::NAN,
+                ] {
+                    let value = $name(num);
+                    let encoded = value.encode();
+                    assert_eq!(encoded.as_ref(), num.to_le_bytes().as_slice());
+                    let decoded = $name::decode(encoded).unwrap();
+                    assert_eq!(value, decoded);
+                }
+            }
+
+            #[test]
+            fn test_should_not_decode_short_buffer() {
+                use $crate::prelude::Encode;
+
+                assert!($name::decode(std::borrow::Cow::Borrowed(&[0u8])).is_err());
+            }
+
+            #[test]
+            fn test_should_order_with_total_cmp() {
+                let mut values = vec![
+                    $name(<$primitive>::NAN),
+                    $name(1.0),
+                    $name(<$primitive>::INFINITY),
+                    $name(0.0),
+                    $name(-0.0),
+                    $name(<$primitive>::NEG_INFINITY),
+                    $name(-1.0),
+                ];
+                values.sort();
+                assert_eq!(
+                    values,
+                    vec![
+                        $name(<$primitive>::NEG_INFINITY),
+                        $name(-1.0),
+                        $name(-0.0),
+                        $name(0.0),
+                        $name(1.0),
+                        $name(<$primitive>::INFINITY),
+                        $name(<$primitive>::NAN),
+                    ]
+                );
+            }
+
+            #[test]
+            fn test_nan_should_equal_and_hash_to_itself() {
+                let nan = $name(<$primitive>::NAN);
+                assert_eq!(nan, nan);
+                assert_ne!($name(0.0), $name(-0.0));
+
+                let mut set = HashSet::new();
+                set.insert(nan);
+                assert!(set.contains(&$name(<$primitive>::NAN)));
+            }
+
+            #[cfg(feature = "candid")]
+            #[test]
+            fn test_should_candid_encode_decode() {
+                let src = $name(1.25);
+                let buf = candid::encode_one(src).expect("Candid encoding failed");
+                let decoded: $name = candid::decode_one(&buf).expect("Candid decoding failed");
+                assert_eq!(src, decoded);
+            }
+        }
+    };
+}
+
+float_type!(
+    Float32,
+    candid::types::TypeInner::Float32,
+    serialize_float32,
+    f32,
+    tests_float32
+);
+float_type!(
+    Float64,
+    candid::types::TypeInner::Float64,
+    serialize_float64,
+    f64,
+    tests_float64
+);
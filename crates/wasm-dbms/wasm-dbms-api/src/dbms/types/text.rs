@@ -12,10 +12,26 @@ use crate::memory::{DEFAULT_ALIGNMENT, DataSize, Encode, PageOffset};
 pub struct Text(pub String);
 
 impl Text {
+    /// Maximum size of a text value, in bytes.
+    ///
+    /// A record, including the 2-byte length prefix of the text and the 2-byte
+    /// record header, must fit in a 64 KiB page.
+    pub const MAX_BYTES: usize = 65_532;
+
     /// Returns the string slice.
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Returns the length of the text in characters.
+    pub fn len(&self) -> usize {
+        self.0.chars().count()
+    }
+
+    /// Returns whether the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl fmt::Display for Text {
@@ -107,6 +123,14 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn test_text_len_counts_characters() {
+        assert_eq!(Text("hello".to_string()).len(), 5);
+        assert_eq!(Text("città".to_string()).len(), 5);
+        assert!(Text::default().is_empty());
+        assert!(!Text("a".to_string()).is_empty());
+    }
+
     #[cfg(feature = "candid")]
     #[test]
     fn test_should_candid_encode_decode() {
@@ -0,0 +1,132 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dbms::types::DataType;
+use crate::memory::{DataSize, DecodeError, Encode, MSize, MemoryError, MemoryResult, PageOffset};
+
+const TIMESTAMP_SIZE: usize = 8;
+
+/// Timestamp data type for the DBMS: nanoseconds elapsed since the Unix epoch.
+///
+/// This is the representation of the Internet Computer system time, so values
+/// compare and sort as plain integers across canisters, with no time zone or
+/// string parsing involved.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Timestamp(pub u64);
+
+impl Timestamp {
+    /// Returns the number of nanoseconds since the Unix epoch.
+    pub fn as_nanos(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl From<u64> for Timestamp {
+    fn from(nanos: u64) -> Self {
+        Self(nanos)
+    }
+}
+
+impl From<Timestamp> for u64 {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+#[cfg(feature = "candid")]
+impl candid::CandidType for Timestamp {
+    fn _ty() -> candid::types::Type {
+        candid::types::Type(std::rc::Rc::new(candid::types::TypeInner::Nat64))
+    }
+
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: candid::types::Serializer,
+    {
+        serializer.serialize_nat64(self.0)
+    }
+}
+
+impl Encode for Timestamp {
+    const SIZE: DataSize = DataSize::Fixed(TIMESTAMP_SIZE as MSize);
+
+    const ALIGNMENT: PageOffset = TIMESTAMP_SIZE as PageOffset;
+
+    fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+        std::borrow::Cow::Owned(self.0.to_le_bytes().to_vec())
+    }
+
+    fn decode(data: std::borrow::Cow<[u8]>) -> MemoryResult<Self>
+    where
+        Self: Sized,
+    {
+        let Some(bytes) = data.get(..TIMESTAMP_SIZE) else {
+            return Err(MemoryError::DecodeError(DecodeError::TooShort));
+        };
+
+        let mut array = [0u8; TIMESTAMP_SIZE];
+        array.copy_from_slice(bytes);
+        Ok(Self(u64::from_le_bytes(array)))
+    }
+
+    fn size(&self) -> MSize {
+        Self::SIZE.get_fixed_size().expect("should be fixed")
+    }
+}
+
+impl DataType for Timestamp {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_timestamp_encode_decode() {
+        let value = Timestamp(1_718_409_600_123_456_789);
+        let encoded = value.encode();
+        assert_eq!(encoded.len(), TIMESTAMP_SIZE);
+        assert_eq!(value.size(), TIMESTAMP_SIZE as MSize);
+        let decoded = Timestamp::decode(encoded).unwrap();
+        assert_eq!(value, decoded);
+    }
+
+    #[test]
+    fn test_timestamp_decode_too_short() {
+        let result = Timestamp::decode(std::borrow::Cow::Borrowed(&[0u8; 4]));
+        assert!(matches!(
+            result,
+            Err(MemoryError::DecodeError(DecodeError::TooShort))
+        ));
+    }
+
+    #[test]
+    fn test_timestamp_conversions_and_ordering() {
+        let earlier = Timestamp::from(1_000);
+        let later = Timestamp::from(2_000);
+        assert!(earlier < later);
+        assert_eq!(u64::from(later), 2_000);
+        assert_eq!(later.as_nanos(), 2_000);
+        assert_eq!(later.to_string(), "2000");
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode() {
+        let src = Timestamp(1_718_409_600_000_000_000);
+        let buf = candid::encode_one(src).expect("Candid encoding failed");
+        let decoded: Timestamp = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(src, decoded);
+        let as_nat64: u64 = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(as_nat64, src.0);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::dbms::types::Uint8;
+use crate::dbms::value::Value;
+use crate::memory::Encode;
+
+/// Returns the [`Value::Uint8`] holding the discriminant of `value`.
+///
+/// `T` is an enum whose variants are all unit, deriving `Encode`, which
+/// encodes it as a single discriminant byte. Used by `#[derive(Table)]` for
+/// `#[unit_enum]` fields.
+pub fn unit_enum_to_value<T>(value: &T) -> Value
+where
+    T: Encode,
+{
+    let discriminant = value.encode().first().copied().unwrap_or_default();
+    Value::Uint8(Uint8(discriminant))
+}
+
+/// Returns the unit enum `T` whose discriminant is held by the
+/// [`Value::Uint8`] `value`.
+///
+/// Returns `None` if `value` is of another variant or doesn't hold the
+/// discriminant of any variant of `T`.
+pub fn unit_enum_from_value<T>(value: &Value) -> Option<T>
+where
+    T: Encode,
+{
+    let discriminant = value.as_uint8()?;
+    T::decode(std::borrow::Cow::Owned(vec![discriminant.0])).ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::prelude::Encode;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Encode)]
+    enum Status {
+        Active,
+        Inactive = 4,
+        Pending,
+    }
+
+    #[test]
+    fn test_should_encode_unit_enum_as_discriminant() {
+        assert_eq!(Status::SIZE, crate::prelude::DataSize::Fixed(1));
+        assert_eq!(Status::Active.encode().as_ref(), &[0]);
+        assert_eq!(Status::Inactive.encode().as_ref(), &[4]);
+        assert_eq!(Status::Pending.encode().as_ref(), &[5]);
+        assert_eq!(Status::Pending.size(), 1);
+        assert_eq!(
+            Status::decode(std::borrow::Cow::Borrowed(&[4])).unwrap(),
+            Status::Inactive
+        );
+    }
+
+    #[test]
+    fn test_should_not_decode_unknown_discriminant() {
+        assert!(matches!(
+            Status::decode(std::borrow::Cow::Borrowed(&[1])),
+            Err(crate::prelude::MemoryError::DecodeError(
+                crate::prelude::DecodeError::InvalidDiscriminant(1)
+            ))
+        ));
+        assert!(matches!(
+            Status::decode(std::borrow::Cow::Borrowed(&[])),
+            Err(crate::prelude::MemoryError::DecodeError(
+                crate::prelude::DecodeError::TooShort
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_should_convert_unit_enum_to_and_from_value() {
+        let value = unit_enum_to_value(&Status::Pending);
+        assert_eq!(value, Value::Uint8(Uint8(5)));
+        assert_eq!(
+            unit_enum_from_value::<Status>(&value),
+            Some(Status::Pending)
+        );
+        assert_eq!(
+            unit_enum_from_value::<Status>(&Value::Uint8(Uint8(2))),
+            None
+        );
+        assert_eq!(
+            unit_enum_from_value::<Status>(&Value::Uint16(5.into())),
+            None
+        );
+    }
+}
@@ -71,13 +71,13 @@ impl Encode for Uuid {
     where
         Self: Sized,
     {
-        if data.len() != UUID_SIZE {
+        if data.len() < UUID_SIZE {
             return Err(crate::memory::MemoryError::DecodeError(
                 crate::memory::DecodeError::TooShort,
             ));
         }
 
-        uuid::Uuid::from_slice(&data)
+        uuid::Uuid::from_slice(&data[..UUID_SIZE])
             .map(Uuid)
             .map_err(MemoryError::from)
     }
@@ -0,0 +1,365 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dbms::types::{DataType, DateTime, Timestamp};
+use crate::memory::{DataSize, DecodeError, Encode, MSize, MemoryError, MemoryResult, PageOffset};
+
+const TYPE_SIZE: usize = 8 + 2; // instant + offset_minutes
+
+const NANOS_PER_MICROSECOND: i128 = 1_000;
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_MINUTE: i128 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_DAY: i128 = 86_400 * NANOS_PER_SECOND;
+
+/// Date time data type for the DBMS which keeps the offset of the zone it was
+/// recorded in.
+///
+/// A `ZonedDateTime` is an instant, in nanoseconds since the Unix epoch (UTC),
+/// paired with the offset from UTC of the local time it was taken in, so the
+/// original wall-clock time can be displayed back. Values are ordered by
+/// instant first, so two values for the same moment in different zones sort
+/// next to each other, and by offset to break ties.
+///
+/// The instant range of an `i64` spans the years 1677 to 2262.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "candid", derive(candid::CandidType))]
+pub struct ZonedDateTime {
+    /// Nanoseconds since the Unix epoch (UTC).
+    pub instant: i64,
+    /// Offset from UTC of the local time, in minutes.
+    pub offset_minutes: i16,
+}
+
+impl ZonedDateTime {
+    /// Creates a new [`ZonedDateTime`] from an instant, in nanoseconds since
+    /// the Unix epoch, and the offset from UTC in minutes.
+    pub const fn new(instant: i64, offset_minutes: i16) -> Self {
+        Self {
+            instant,
+            offset_minutes,
+        }
+    }
+
+    /// Creates a new [`ZonedDateTime`] at UTC.
+    pub const fn utc(instant: i64) -> Self {
+        Self::new(instant, 0)
+    }
+
+    /// Returns the same instant seen from the zone at `offset_minutes`.
+    pub const fn with_offset(self, offset_minutes: i16) -> Self {
+        Self::new(self.instant, offset_minutes)
+    }
+
+    /// Returns the instant as a UTC [`DateTime`].
+    ///
+    /// Sub-microsecond precision is truncated.
+    pub fn to_utc(self) -> DateTime {
+        self.with_offset(0).to_local()
+    }
+
+    /// Returns the local wall-clock time as a [`DateTime`] carrying the offset.
+    ///
+    /// Sub-microsecond precision is truncated.
+    pub fn to_local(self) -> DateTime {
+        let local = i128::from(self.instant) + i128::from(self.offset_minutes) * NANOS_PER_MINUTE;
+        let days = local.div_euclid(NANOS_PER_DAY);
+        let nanos_of_day = local.rem_euclid(NANOS_PER_DAY);
+        let (year, month, day) = civil_from_days(days as i64);
+        let seconds_of_day = nanos_of_day / NANOS_PER_SECOND;
+
+        DateTime {
+            year: year as u16,
+            month,
+            day,
+            hour: (seconds_of_day / 3_600) as u8,
+            minute: (seconds_of_day % 3_600 / 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+            microsecond: (nanos_of_day % NANOS_PER_SECOND / NANOS_PER_MICROSECOND) as u32,
+            timezone_offset_minutes: self.offset_minutes,
+        }
+    }
+}
+
+impl fmt::Display for ZonedDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let local = self.to_local();
+        let nanos = i128::from(self.instant).rem_euclid(NANOS_PER_SECOND);
+        let sign = if self.offset_minutes < 0 { '-' } else { '+' };
+        let offset = self.offset_minutes.unsigned_abs();
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:09}{sign}{:02}:{:02}",
+            local.year,
+            local.month,
+            local.day,
+            local.hour,
+            local.minute,
+            local.second,
+            nanos,
+            offset / 60,
+            offset % 60
+        )
+    }
+}
+
+impl DataType for ZonedDateTime {}
+
+impl From<DateTime> for ZonedDateTime {
+    /// Converts a [`DateTime`], read as the local time at its
+    /// `timezone_offset_minutes`, keeping the offset.
+    ///
+    /// Date times outside the range of the instant saturate to its bounds.
+    fn from(datetime: DateTime) -> Self {
+        let days = days_from_civil(
+            i64::from(datetime.year),
+            u32::from(datetime.month),
+            u32::from(datetime.day),
+        );
+        let seconds_of_day = i128::from(datetime.hour) * 3_600
+            + i128::from(datetime.minute) * 60
+            + i128::from(datetime.second);
+        let local = i128::from(days) * NANOS_PER_DAY
+            + seconds_of_day * NANOS_PER_SECOND
+            + i128::from(datetime.microsecond) * NANOS_PER_MICROSECOND;
+        let instant = local - i128::from(datetime.timezone_offset_minutes) * NANOS_PER_MINUTE;
+
+        Self::new(
+            instant.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64,
+            datetime.timezone_offset_minutes,
+        )
+    }
+}
+
+impl From<ZonedDateTime> for DateTime {
+    /// Converts into the local wall-clock time; see [`ZonedDateTime::to_local`].
+    fn from(zoned: ZonedDateTime) -> Self {
+        zoned.to_local()
+    }
+}
+
+impl From<Timestamp> for ZonedDateTime {
+    /// Converts a [`Timestamp`] into a UTC zoned date time, saturating
+    /// timestamps beyond the year 2262.
+    fn from(timestamp: Timestamp) -> Self {
+        Self::utc(i64::try_from(timestamp.as_nanos()).unwrap_or(i64::MAX))
+    }
+}
+
+impl Encode for ZonedDateTime {
+    const SIZE: DataSize = DataSize::Fixed(TYPE_SIZE as MSize);
+
+    const ALIGNMENT: PageOffset = TYPE_SIZE as PageOffset;
+
+    fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+        let mut bytes = Vec::with_capacity(TYPE_SIZE);
+        bytes.extend_from_slice(&self.instant.to_le_bytes());
+        bytes.extend_from_slice(&self.offset_minutes.to_le_bytes());
+        std::borrow::Cow::Owned(bytes)
+    }
+
+    fn decode(data: std::borrow::Cow<[u8]>) -> MemoryResult<Self>
+    where
+        Self: Sized,
+    {
+        if data.len() < TYPE_SIZE {
+            return Err(MemoryError::DecodeError(DecodeError::TooShort));
+        }
+
+        let mut instant = [0u8; 8];
+        instant.copy_from_slice(&data[..8]);
+        let offset_minutes = i16::from_le_bytes([data[8], data[9]]);
+
+        Ok(Self::new(i64::from_le_bytes(instant), offset_minutes))
+    }
+
+    fn size(&self) -> MSize {
+        Self::SIZE.get_fixed_size().expect("should be fixed")
+    }
+}
+
+/// Days since the Unix epoch of a date of the proleptic gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = i64::from((month + 9) % 12);
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of the proleptic gregorian calendar from days since the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month as u8, day as u8)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn datetime(
+        (year, month, day): (u16, u8, u8),
+        (hour, minute, second): (u8, u8, u8),
+        timezone_offset_minutes: i16,
+    ) -> DateTime {
+        DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            microsecond: 0,
+            timezone_offset_minutes,
+        }
+    }
+
+    #[test]
+    fn test_zoned_datetime_encode_decode() {
+        for value in [
+            ZonedDateTime::new(0, 0),
+            ZonedDateTime::new(1_718_454_645_123_456_789, 120),
+            ZonedDateTime::new(-1, -1),
+            ZonedDateTime::new(i64::MIN, i16::MIN),
+            ZonedDateTime::new(i64::MAX, i16::MAX),
+            ZonedDateTime::new(1_718_454_645_000_000_000, 14 * 60),
+            ZonedDateTime::new(1_718_454_645_000_000_000, -12 * 60),
+        ] {
+            let encoded = value.encode();
+            assert_eq!(encoded.len(), TYPE_SIZE);
+            assert_eq!(value.size(), TYPE_SIZE as MSize);
+            let decoded = ZonedDateTime::decode(encoded).unwrap();
+            assert_eq!(value, decoded);
+        }
+
+        let result = ZonedDateTime::decode(std::borrow::Cow::Borrowed(&[0u8; 9]));
+        assert!(matches!(
+            result,
+            Err(MemoryError::DecodeError(DecodeError::TooShort))
+        ));
+    }
+
+    #[test]
+    fn test_should_order_by_instant() {
+        // 12:00 in Rome (+02:00) is before 11:30 in London (+01:00)
+        let rome = ZonedDateTime::from(datetime((2024, 6, 15), (12, 0, 0), 120));
+        let london = ZonedDateTime::from(datetime((2024, 6, 15), (11, 30, 0), 60));
+        let new_york = ZonedDateTime::from(datetime((2024, 6, 15), (6, 30, 0), -240));
+        assert!(rome < london);
+        assert_eq!(london.instant, new_york.instant);
+        assert!(new_york < london);
+        assert_ne!(london, new_york);
+    }
+
+    #[test]
+    fn test_should_convert_datetime_both_ways() {
+        let local = DateTime {
+            microsecond: 123_456,
+            ..datetime((2024, 2, 29), (1, 30, 0), 330)
+        };
+        let zoned = ZonedDateTime::from(local);
+        assert_eq!(zoned.offset_minutes, 330);
+        // 2024-02-28T20:00:00.123456Z
+        assert_eq!(zoned.instant, 1_709_150_400_123_456_000);
+        assert_eq!(DateTime::from(zoned), local);
+        assert_eq!(
+            zoned.to_utc(),
+            DateTime {
+                microsecond: 123_456,
+                ..datetime((2024, 2, 28), (20, 0, 0), 0)
+            }
+        );
+        assert_eq!(ZonedDateTime::from(zoned.to_utc()), zoned.with_offset(0));
+    }
+
+    #[test]
+    fn test_should_convert_across_dst_style_boundaries() {
+        // Clocks go forward: 01:59 at +01:00 is followed by 03:00 at +02:00.
+        let before = ZonedDateTime::from(datetime((2024, 3, 31), (1, 59, 0), 60));
+        let after = ZonedDateTime::from(datetime((2024, 3, 31), (3, 0, 0), 120));
+        assert_eq!(after.instant - before.instant, 60 * 1_000_000_000);
+        assert!(before < after);
+
+        // Clocks go back: 02:30 happens twice, first at +02:00 then at +01:00.
+        let first = ZonedDateTime::from(datetime((2024, 10, 27), (2, 30, 0), 120));
+        let second = ZonedDateTime::from(datetime((2024, 10, 27), (2, 30, 0), 60));
+        assert_eq!(second.instant - first.instant, 3_600 * 1_000_000_000);
+        assert!(first < second);
+        assert_eq!(first.to_local().hour, 2);
+        assert_eq!(second.to_local().hour, 2);
+        assert_eq!(first.to_utc().hour, 0);
+        assert_eq!(second.to_utc().hour, 1);
+    }
+
+    #[test]
+    fn test_should_convert_at_extreme_offsets() {
+        let instant = 1_704_067_200_000_000_000; // 2024-01-01T00:00:00Z
+        let kiribati = ZonedDateTime::new(instant, 14 * 60);
+        assert_eq!(
+            kiribati.to_local(),
+            datetime((2024, 1, 1), (14, 0, 0), 14 * 60)
+        );
+        let baker = ZonedDateTime::new(instant, -12 * 60);
+        assert_eq!(
+            baker.to_local(),
+            datetime((2023, 12, 31), (12, 0, 0), -12 * 60)
+        );
+        assert_eq!(ZonedDateTime::from(kiribati.to_local()), kiribati);
+        assert_eq!(ZonedDateTime::from(baker.to_local()), baker);
+
+        // before the epoch
+        let zoned = ZonedDateTime::new(-1, -90);
+        assert_eq!(
+            zoned.to_local(),
+            DateTime {
+                microsecond: 999_999,
+                ..datetime((1969, 12, 31), (22, 29, 59), -90)
+            }
+        );
+    }
+
+    #[test]
+    fn test_should_saturate_out_of_range_datetimes() {
+        let zoned = ZonedDateTime::from(datetime((9999, 12, 31), (23, 59, 59), 0));
+        assert_eq!(zoned.instant, i64::MAX);
+        let zoned = ZonedDateTime::from(Timestamp(u64::MAX));
+        assert_eq!(zoned, ZonedDateTime::utc(i64::MAX));
+        assert_eq!(
+            ZonedDateTime::from(Timestamp(1_000)),
+            ZonedDateTime::utc(1_000)
+        );
+    }
+
+    #[test]
+    fn test_zoned_datetime_display() {
+        let zoned = ZonedDateTime::new(1_718_454_645_123_456_789, -150);
+        assert_eq!(zoned.to_string(), "2024-06-15T10:00:45.123456789-02:30");
+        let zoned = ZonedDateTime::new(0, 60);
+        assert_eq!(zoned.to_string(), "1970-01-01T01:00:00.000000000+01:00");
+    }
+
+    #[cfg(feature = "candid")]
+    #[test]
+    fn test_should_candid_encode_decode() {
+        let src = ZonedDateTime::new(1_718_454_645_123_456_789, -150);
+        let buf = candid::encode_one(src).expect("Candid encoding failed");
+        let decoded: ZonedDateTime = candid::decode_one(&buf).expect("Candid decoding failed");
+        assert_eq!(src, decoded);
+    }
+}
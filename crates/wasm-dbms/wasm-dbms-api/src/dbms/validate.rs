@@ -1,10 +1,12 @@
 //! This module contains all the built-in validations which can be applied to columns.
 //!
-//! Each validation function takes a [`&crate::prelude::Value`] as input and returns a `DbmsResult<()>` indicating
+//! Each validation function takes a [`&crate::prelude::Value`] as input and returns a `Result<(), ValidationError>` indicating
 //! whether the value passes the validation or not.
 
 mod case;
+mod chained;
 mod color;
+mod decimal;
 mod email;
 mod locale;
 mod phone;
@@ -12,18 +14,35 @@ mod strlen;
 mod web;
 
 pub use self::case::{CamelCaseValidator, KebabCaseValidator, SnakeCaseValidator};
+pub use self::chained::ChainedValidator;
 pub use self::color::RgbColorValidator;
+pub use self::decimal::DecimalPrecisionValidator;
 pub use self::email::EmailValidator;
 pub use self::locale::{CountryIso639Validator, CountryIso3166Validator};
 pub use self::phone::PhoneNumberValidator;
 pub use self::strlen::{MaxStrlenValidator, MinStrlenValidator, RangeStrlenValidator};
 pub use self::web::{MimeTypeValidator, UrlValidator};
-use crate::error::DbmsResult;
+use crate::error::DbmsError;
+
+/// Error returned by a [`Validate`] implementation which rejects a value.
+///
+/// It is reported as a [`DbmsError::Validation`] by the database.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct ValidationError(pub String);
+
+impl From<ValidationError> for DbmsError {
+    fn from(error: ValidationError) -> Self {
+        DbmsError::Validation(error.0)
+    }
+}
 
 /// Trait for validating [`crate::prelude::Value`]s.
+///
+/// The trait is object safe, so validators defined in other crates can be
+/// attached to columns with `#[validate(...)]` like the built-in ones.
 pub trait Validate {
-    /// Validates the given [`crate::prelude::Value`].
-    ///
-    /// In case of error it should return a [`crate::prelude::DbmsError::Validation`] error.
-    fn validate(&self, value: &crate::prelude::Value) -> DbmsResult<()>;
+    /// Validates the given [`crate::prelude::Value`], returning a
+    /// [`ValidationError`] describing why it is rejected.
+    fn validate(&self, value: &crate::prelude::Value) -> Result<(), ValidationError>;
 }
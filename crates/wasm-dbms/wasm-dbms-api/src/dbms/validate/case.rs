@@ -1,4 +1,4 @@
-use crate::prelude::{Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 /// A validator for `snake_case` strings.
 ///
@@ -30,9 +30,9 @@ use crate::prelude::{Validate, Value};
 pub struct SnakeCaseValidator;
 
 impl Validate for SnakeCaseValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "RGB color validation requires a text value".to_string(),
             ));
         };
@@ -40,13 +40,12 @@ impl Validate for SnakeCaseValidator {
         let s = &text.0;
 
         // first must be lowercase letter or underscore
-        let first_char = s.chars().next().ok_or_else(|| {
-            crate::prelude::DbmsError::Validation(
-                "Empty string is not valid snake_case".to_string(),
-            )
-        })?;
+        let first_char = s
+            .chars()
+            .next()
+            .ok_or_else(|| ValidationError("Empty string is not valid snake_case".to_string()))?;
         if !first_char.is_lowercase() && first_char != '_' {
-            return Err(crate::prelude::DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "Value '{s}' is not in snake_case format",
             )));
         }
@@ -56,7 +55,7 @@ impl Validate for SnakeCaseValidator {
         {
             Ok(())
         } else {
-            Err(crate::prelude::DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "Value '{s}' is not in snake_case format",
             )))
         }
@@ -91,9 +90,9 @@ impl Validate for SnakeCaseValidator {
 pub struct KebabCaseValidator;
 
 impl Validate for KebabCaseValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "RGB color validation requires a text value".to_string(),
             ));
         };
@@ -101,13 +100,12 @@ impl Validate for KebabCaseValidator {
         let s = &text.0;
 
         // first must be lowercase letter
-        let first_char = s.chars().next().ok_or_else(|| {
-            crate::prelude::DbmsError::Validation(
-                "Empty string is not valid kebab-case".to_string(),
-            )
-        })?;
+        let first_char = s
+            .chars()
+            .next()
+            .ok_or_else(|| ValidationError("Empty string is not valid kebab-case".to_string()))?;
         if !first_char.is_lowercase() {
-            return Err(crate::prelude::DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "Value '{s}' is not in kebab-case format",
             )));
         }
@@ -117,7 +115,7 @@ impl Validate for KebabCaseValidator {
         {
             Ok(())
         } else {
-            Err(crate::prelude::DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "Value '{s}' is not in kebab-case format",
             )))
         }
@@ -151,9 +149,9 @@ impl Validate for KebabCaseValidator {
 pub struct CamelCaseValidator;
 
 impl Validate for CamelCaseValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "CamelCase validation requires a text value".to_string(),
             ));
         };
@@ -161,11 +159,11 @@ impl Validate for CamelCaseValidator {
         let s = &text.0;
 
         let mut chars = s.chars();
-        let first_char = chars.next().ok_or_else(|| {
-            crate::prelude::DbmsError::Validation("Empty string is not valid CamelCase".to_string())
-        })?;
+        let first_char = chars
+            .next()
+            .ok_or_else(|| ValidationError("Empty string is not valid CamelCase".to_string()))?;
         if !first_char.is_uppercase() {
-            return Err(crate::prelude::DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "Value '{s}' is not in CamelCase format"
             )));
         }
@@ -173,7 +171,7 @@ impl Validate for CamelCaseValidator {
         if s.chars().all(|c| c.is_alphanumeric()) {
             Ok(())
         } else {
-            Err(crate::prelude::DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "Value '{}' is not in CamelCase format",
                 s
             )))
@@ -0,0 +1,82 @@
+use crate::prelude::{Validate, ValidationError, Value};
+
+/// Validator that runs a sequence of validators in order.
+///
+/// The value is valid if every validator accepts it; the error of the first
+/// failing validator is returned.
+///
+/// This is what `#[validate(A, B, ...)]` generates when a column lists more
+/// than one validator.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_dbms_api::prelude::{
+///     ChainedValidator, EmailValidator, MaxStrlenValidator, Validate as _, Value,
+/// };
+///
+/// let validator = ChainedValidator::new(vec![
+///     Box::new(MaxStrlenValidator(254)),
+///     Box::new(EmailValidator),
+/// ]);
+/// assert!(validator.validate(&Value::Text("alice@example.com".into())).is_ok());
+/// assert!(validator.validate(&Value::Text("not-an-email".into())).is_err());
+/// ```
+pub struct ChainedValidator {
+    validators: Vec<Box<dyn Validate>>,
+}
+
+impl ChainedValidator {
+    /// Creates a validator running `validators` in order.
+    pub fn new(validators: Vec<Box<dyn Validate>>) -> Self {
+        Self { validators }
+    }
+}
+
+impl Validate for ChainedValidator {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator.validate(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::prelude::{EmailValidator, MaxStrlenValidator, MinStrlenValidator};
+
+    #[test]
+    fn test_chained_validator_accepts_valid_value() {
+        let validator = ChainedValidator::new(vec![
+            Box::new(MinStrlenValidator(3)),
+            Box::new(MaxStrlenValidator(254)),
+            Box::new(EmailValidator),
+        ]);
+
+        assert!(
+            validator
+                .validate(&Value::Text("alice@example.com".into()))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_chained_validator_returns_first_error() {
+        let validator = ChainedValidator::new(vec![
+            Box::new(MinStrlenValidator(3)),
+            Box::new(EmailValidator),
+        ]);
+
+        let Err(ValidationError(first)) = validator.validate(&Value::Text("a".into())) else {
+            panic!("expected validation error");
+        };
+        let Err(ValidationError(min_len)) =
+            MinStrlenValidator(3).validate(&Value::Text("a".into()))
+        else {
+            panic!("expected validation error");
+        };
+        assert_eq!(first, min_len);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsError, Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 /// A validator for RGB color strings.
 ///
@@ -17,24 +17,20 @@ use crate::prelude::{DbmsError, Validate, Value};
 pub struct RgbColorValidator;
 
 impl Validate for RgbColorValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(DbmsError::Validation(
+            return Err(ValidationError(
                 "RGB color validation requires a text value".to_string(),
             ));
         };
 
         let s = &text.0;
         if s.len() != 7 || !s.starts_with('#') {
-            return Err(DbmsError::Validation(
-                "Invalid RGB color format".to_string(),
-            ));
+            return Err(ValidationError("Invalid RGB color format".to_string()));
         }
         for c in s.chars().skip(1) {
             if !c.is_ascii_hexdigit() {
-                return Err(DbmsError::Validation(
-                    "Invalid RGB color format".to_string(),
-                ));
+                return Err(ValidationError("Invalid RGB color format".to_string()));
             }
         }
 
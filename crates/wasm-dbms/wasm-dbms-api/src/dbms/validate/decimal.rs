@@ -0,0 +1,77 @@
+use crate::prelude::{Validate, ValidationError, Value};
+
+/// A validator that checks if a decimal fits a `precision` and a `scale`.
+///
+/// As in SQL `DECIMAL(precision, scale)`, the value can hold at most `scale`
+/// fractional digits and `precision - scale` integer digits. Trailing zeros of
+/// the fractional part are not counted, and `NULL` is accepted.
+///
+/// The `Table` derive wires it for the fields declared with
+/// `#[decimal(precision = P, scale = S)]`.
+///
+/// # Example
+///
+/// ```rust
+/// use rust_decimal::Decimal;
+/// use wasm_dbms_api::prelude::{DecimalPrecisionValidator, Validate, Value};
+///
+/// let validator = DecimalPrecisionValidator(5, 2);
+/// assert!(validator.validate(&Value::Decimal(Decimal::new(99999, 2).into())).is_ok()); // 999.99
+/// assert!(validator.validate(&Value::Decimal(Decimal::new(12345, 3).into())).is_err()); // 12.345
+/// assert!(validator.validate(&Value::Decimal(Decimal::new(1000, 0).into())).is_err()); // 1000
+/// ```
+pub struct DecimalPrecisionValidator(pub u32, pub u32);
+
+impl Validate for DecimalPrecisionValidator {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        let decimal = match value {
+            Value::Decimal(decimal) => decimal,
+            Value::Null => return Ok(()),
+            _ => {
+                return Err(ValidationError("Value is not a `Decimal`".to_string()));
+            }
+        };
+
+        let Self(precision, scale) = *self;
+        if decimal.fits(precision, scale) {
+            Ok(())
+        } else {
+            Err(ValidationError(format!(
+                "Decimal {decimal} exceeds precision {precision} and scale {scale}"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    fn decimal(num: i64, scale: u32) -> Value {
+        Value::Decimal(Decimal::new(num, scale).into())
+    }
+
+    #[test]
+    fn test_decimal_precision_validator() {
+        let validator = DecimalPrecisionValidator(5, 2);
+        for value in [
+            decimal(99999, 2),
+            decimal(-99999, 2),
+            decimal(12300, 4),
+            decimal(0, 0),
+            Value::Null,
+        ] {
+            assert!(validator.validate(&value).is_ok(), "{value:?} should fit");
+        }
+        for value in [decimal(12345, 3), decimal(100000, 2), decimal(-1000, 0)] {
+            assert!(
+                matches!(validator.validate(&value), Err(ValidationError(_))),
+                "{value:?} should not fit"
+            );
+        }
+        assert!(validator.validate(&Value::Int32(1.into())).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use lazy_regex::{Lazy, Regex, lazy_regex};
 
-use crate::prelude::{Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 static EMAIL_REGEX: Lazy<Regex> =
     lazy_regex!(r"^[A-Za-z0-9]{1}[A-Za-z0-9._%+-]*@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$");
@@ -24,9 +24,9 @@ static EMAIL_REGEX: Lazy<Regex> =
 pub struct EmailValidator;
 
 impl Validate for EmailValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "EmailValidator can only be applied to Text values".to_string(),
             ));
         };
@@ -34,7 +34,7 @@ impl Validate for EmailValidator {
         if EMAIL_REGEX.is_match(text.as_str()) {
             Ok(())
         } else {
-            Err(crate::prelude::DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "Value '{text}' is not a valid email address",
             )))
         }
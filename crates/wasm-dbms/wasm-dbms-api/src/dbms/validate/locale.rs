@@ -1,4 +1,4 @@
-use crate::prelude::{Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 const ISO_3166_COUNTRIES: &[&str] = &[
     "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
@@ -51,15 +51,15 @@ const ISO_639_1_COUNTRIES: &[&str] = &[
 pub struct CountryIso3166Validator;
 
 impl Validate for CountryIso3166Validator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "Country ISO3166 validator only works on text values".to_string(),
             ));
         };
 
         if !ISO_3166_COUNTRIES.contains(&text.as_str()) {
-            return Err(crate::prelude::DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "Value '{}' is not a valid ISO3166 country code",
                 text
             )));
@@ -84,15 +84,15 @@ impl Validate for CountryIso3166Validator {
 pub struct CountryIso639Validator;
 
 impl Validate for CountryIso639Validator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "Country ISO639 validator only works on text values".to_string(),
             ));
         };
 
         if !ISO_639_1_COUNTRIES.contains(&text.as_str()) {
-            return Err(crate::prelude::DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "Value '{}' is not a valid ISO639 country code",
                 text
             )));
@@ -1,6 +1,6 @@
 use lazy_regex::{Lazy, Regex, lazy_regex};
 
-use crate::prelude::{Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 static PHONE_REGEX: Lazy<Regex> = lazy_regex!(r"^\+?[0-9\s().-]{7,20}$");
 
@@ -29,9 +29,9 @@ static PHONE_REGEX: Lazy<Regex> = lazy_regex!(r"^\+?[0-9\s().-]{7,20}$");
 pub struct PhoneNumberValidator;
 
 impl Validate for PhoneNumberValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(crate::prelude::DbmsError::Validation(
+            return Err(ValidationError(
                 "PhoneNumberValidator can only be applied to Text values".to_string(),
             ));
         };
@@ -39,7 +39,7 @@ impl Validate for PhoneNumberValidator {
         if PHONE_REGEX.is_match(text.as_str()) {
             Ok(())
         } else {
-            Err(crate::prelude::DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "Value '{text}' is not a valid phone number",
             )))
         }
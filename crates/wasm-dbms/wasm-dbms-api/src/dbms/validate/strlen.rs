@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsError, Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 /// A validator that checks if the length of a string does not exceed a maximum length.
 ///
@@ -15,9 +15,9 @@ use crate::prelude::{DbmsError, Validate, Value};
 pub struct MaxStrlenValidator(pub usize);
 
 impl Validate for MaxStrlenValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(DbmsError::Validation("Value is not a `Text`".to_string()));
+            return Err(ValidationError("Value is not a `Text`".to_string()));
         };
 
         let s = &text.0;
@@ -25,7 +25,7 @@ impl Validate for MaxStrlenValidator {
         if s.len() <= self.0 {
             Ok(())
         } else {
-            Err(DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "String length {} exceeds maximum allowed length of {}",
                 s.len(),
                 self.0
@@ -48,9 +48,9 @@ impl Validate for MaxStrlenValidator {
 pub struct MinStrlenValidator(pub usize);
 
 impl Validate for MinStrlenValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(DbmsError::Validation("Value is not a `Text`".to_string()));
+            return Err(ValidationError("Value is not a `Text`".to_string()));
         };
 
         let s = &text.0;
@@ -58,7 +58,7 @@ impl Validate for MinStrlenValidator {
         if s.len() >= self.0 {
             Ok(())
         } else {
-            Err(DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "String length {} is less than minimum required length of {}",
                 s.len(),
                 self.0
@@ -84,9 +84,9 @@ impl Validate for MinStrlenValidator {
 pub struct RangeStrlenValidator(pub usize, pub usize);
 
 impl Validate for RangeStrlenValidator {
-    fn validate(&self, value: &Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(DbmsError::Validation("Value is not a `Text`".to_string()));
+            return Err(ValidationError("Value is not a `Text`".to_string()));
         };
 
         let s = &text.0;
@@ -95,7 +95,7 @@ impl Validate for RangeStrlenValidator {
         if len >= self.0 && len <= self.1 {
             Ok(())
         } else {
-            Err(DbmsError::Validation(format!(
+            Err(ValidationError(format!(
                 "String length {} is not within the allowed range of {} to {}",
                 len, self.0, self.1
             )))
@@ -1,4 +1,4 @@
-use crate::prelude::{DbmsError, Validate, Value};
+use crate::prelude::{Validate, ValidationError, Value};
 
 /// A validator that checks if a string is a valid MIME type.
 ///
@@ -16,9 +16,9 @@ use crate::prelude::{DbmsError, Validate, Value};
 pub struct MimeTypeValidator;
 
 impl Validate for MimeTypeValidator {
-    fn validate(&self, value: &crate::prelude::Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &crate::prelude::Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(DbmsError::Validation("Value is not a Text".to_string()));
+            return Err(ValidationError("Value is not a Text".to_string()));
         };
 
         let s = &text.0;
@@ -26,7 +26,7 @@ impl Validate for MimeTypeValidator {
         // must have exactly '/' character
         let parts: Vec<&str> = s.split('/').collect();
         if parts.len() != 2 {
-            return Err(DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "MIME type '{s}' must contain exactly one '/'"
             )));
         }
@@ -39,12 +39,12 @@ impl Validate for MimeTypeValidator {
         };
 
         if !is_valid_part(parts[0]) {
-            return Err(DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "MIME type '{s}' has invalid type part"
             )));
         }
         if !is_valid_part(parts[1]) {
-            return Err(DbmsError::Validation(format!(
+            return Err(ValidationError(format!(
                 "MIME type '{s}' has invalid subtype part"
             )));
         }
@@ -66,17 +66,15 @@ impl Validate for MimeTypeValidator {
 pub struct UrlValidator;
 
 impl Validate for UrlValidator {
-    fn validate(&self, value: &crate::prelude::Value) -> crate::prelude::DbmsResult<()> {
+    fn validate(&self, value: &crate::prelude::Value) -> Result<(), ValidationError> {
         let Value::Text(text) = value else {
-            return Err(DbmsError::Validation("Value is not a Text".to_string()));
+            return Err(ValidationError("Value is not a Text".to_string()));
         };
 
         let s = &text.0;
 
         if url::Url::parse(s).is_err() {
-            return Err(DbmsError::Validation(format!(
-                "Value '{s}' is not a valid URL"
-            )));
+            return Err(ValidationError(format!("Value '{s}' is not a valid URL")));
         }
 
         Ok(())
@@ -1,12 +1,14 @@
 mod discriminant;
 
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::str::FromStr;
 use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 
 use super::types;
+use crate::dbms::query::{QueryError, QueryResult};
 use crate::memory::{
     DEFAULT_ALIGNMENT, DataSize, DecodeError, Encode, MSize, MemoryError, MemoryResult, PageOffset,
 };
@@ -15,23 +17,32 @@ use crate::memory::{
 #[cfg_attr(feature = "candid", derive(candid::CandidType))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Value {
+    Array(Vec<Value>),
     Blob(types::Blob),
     Boolean(types::Boolean),
+    Char(types::Char),
     Date(types::Date),
     DateTime(types::DateTime),
     Decimal(types::Decimal),
+    Float32(types::Float32),
+    Float64(types::Float64),
     Int8(types::Int8),
     Int16(types::Int16),
     Int32(types::Int32),
     Int64(types::Int64),
     Json(types::Json),
+    /// Operations applied to the stored document of a `Json` column by an
+    /// update, in place of a whole new document.
+    JsonPatch(Vec<crate::dbms::query::JsonPatchOp>),
     Null,
     Text(types::Text),
+    Timestamp(types::Timestamp),
     Uint8(types::Uint8),
     Uint16(types::Uint16),
     Uint32(types::Uint32),
     Uint64(types::Uint64),
     Uuid(types::Uuid),
+    ZonedDateTime(types::ZonedDateTime),
     Custom(crate::dbms::custom_value::CustomValue),
 }
 
@@ -109,26 +120,39 @@ macro_rules! value_from_primitive {
 // implement conversions for all Value variants
 impl_conv_for_value!(Blob, types::Blob, as_blob, tests_blob);
 impl_conv_for_value!(Boolean, types::Boolean, as_boolean, tests_boolean);
+impl_conv_for_value!(Char, types::Char, as_char, tests_char);
 impl_conv_for_value!(Date, types::Date, as_date, tests_date);
 impl_conv_for_value!(DateTime, types::DateTime, as_datetime, tests_datetime);
 impl_conv_for_value!(Decimal, types::Decimal, as_decimal, tests_decimal);
+impl_conv_for_value!(Float32, types::Float32, as_float32, tests_float32);
+impl_conv_for_value!(Float64, types::Float64, as_float64, tests_float64);
 impl_conv_for_value!(Int8, types::Int8, as_int8, tests_int8);
 impl_conv_for_value!(Int16, types::Int16, as_int16, tests_int16);
 impl_conv_for_value!(Int32, types::Int32, as_int32, tests_int32);
 impl_conv_for_value!(Int64, types::Int64, as_int64, tests_int64);
 impl_conv_for_value!(Json, types::Json, as_json, tests_json);
 impl_conv_for_value!(Text, types::Text, as_text, tests_text);
+impl_conv_for_value!(Timestamp, types::Timestamp, as_timestamp, tests_timestamp);
 impl_conv_for_value!(Uint8, types::Uint8, as_uint8, tests_uint8);
 impl_conv_for_value!(Uint16, types::Uint16, as_uint16, tests_uint16);
 impl_conv_for_value!(Uint32, types::Uint32, as_uint32, tests_uint32);
 impl_conv_for_value!(Uint64, types::Uint64, as_uint64, tests_uint64);
 impl_conv_for_value!(Uuid, types::Uuid, as_uuid, tests_uuid);
+impl_conv_for_value!(
+    ZonedDateTime,
+    types::ZonedDateTime,
+    as_zoned_datetime,
+    tests_zoned_datetime
+);
 
 // from inner values of types
 value_from_primitive!(Blob, &[u8], tests_blob_primitive_slice);
 value_from_primitive!(Blob, Vec<u8>, tests_blob_primitive);
 value_from_primitive!(Boolean, bool, tests_boolean_primitive);
+value_from_primitive!(Char, char, tests_char_primitive);
 value_from_primitive!(Decimal, rust_decimal::Decimal, tests_decimal_primitive);
+value_from_primitive!(Float32, f32, tests_float32_primitive);
+value_from_primitive!(Float64, f64, tests_float64_primitive);
 value_from_primitive!(Int8, i8, tests_int8_primitive);
 value_from_primitive!(Int16, i16, tests_int16_primitive);
 value_from_primitive!(Int32, i32, tests_int32_primitive);
@@ -147,26 +171,54 @@ impl Value {
         matches!(self, Value::Null)
     }
 
+    /// Compares the value with another value of the same data type.
+    ///
+    /// Unlike the [`Ord`] implementation, which orders values of different
+    /// data types by variant, this fails with [`QueryError::TypeMismatch`]
+    /// when `other` has a different data type than `self`. Custom values
+    /// must share the type tag, and `Null` only compares with `Null`.
+    pub fn compare_typed(&self, other: &Value) -> QueryResult<Ordering> {
+        let same_type = match (self, other) {
+            (Value::Custom(a), Value::Custom(b)) => a.type_tag == b.type_tag,
+            (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
+        };
+        if !same_type {
+            return Err(QueryError::TypeMismatch {
+                expected: self.type_name().to_string(),
+                found: other.type_name().to_string(),
+            });
+        }
+
+        Ok(self.cmp(other))
+    }
+
     /// Returns the type name of the value as a string.
     pub fn type_name(&self) -> &'static str {
         match self {
+            Value::Array(_) => "Array",
             Value::Blob(_) => "Blob",
             Value::Boolean(_) => "Boolean",
+            Value::Char(_) => "Char",
             Value::Date(_) => "Date",
             Value::DateTime(_) => "DateTime",
             Value::Decimal(_) => "Decimal",
+            Value::Float32(_) => "Float32",
+            Value::Float64(_) => "Float64",
             Value::Int8(_) => "Int8",
             Value::Int16(_) => "Int16",
             Value::Int32(_) => "Int32",
             Value::Int64(_) => "Int64",
             Value::Json(_) => "Json",
+            Value::JsonPatch(_) => "JsonPatch",
             Value::Null => "Null",
             Value::Text(_) => "Text",
+            Value::Timestamp(_) => "Timestamp",
             Value::Uint8(_) => "Uint8",
             Value::Uint16(_) => "Uint16",
             Value::Uint32(_) => "Uint32",
             Value::Uint64(_) => "Uint64",
             Value::Uuid(_) => "Uuid",
+            Value::ZonedDateTime(_) => "ZonedDateTime",
             Value::Custom(cv) => {
                 // Cache custom type names to avoid repeated allocations.
                 // The number of unique type tags is bounded at compile time,
@@ -191,6 +243,14 @@ impl Value {
         }
     }
 
+    /// Returns the elements of the value if this is an `Array` variant.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
     /// Returns reference to the inner [`CustomValue`] if this is a `Custom` variant.
     pub fn as_custom(&self) -> Option<&crate::dbms::custom_value::CustomValue> {
         match self {
@@ -213,6 +273,9 @@ impl Value {
 /// Encodes a [`Value`] as `[discriminant: u8] + [inner_type.encode()]`.
 ///
 /// For `Null`, only the discriminant byte is written.
+/// For `Array`, the encoding is `[discriminant] + [count: u16 LE] + [for each element: element.encode()]`.
+/// For `JsonPatch`, the encoding is `[discriminant] + [count: u16 LE] + [for each op: [op: u8] + path.encode() + value.encode()]`,
+/// where the value is omitted for `Remove`.
 /// For `Custom`, the encoding is `[discriminant] + [tag_len: u16 LE] + [tag_bytes] + [data_len: u16 LE] + [encoded_bytes]`.
 impl Encode for Value {
     const SIZE: DataSize = DataSize::Dynamic;
@@ -220,23 +283,53 @@ impl Encode for Value {
 
     fn encode(&'_ self) -> Cow<'_, [u8]> {
         match self {
+            Value::Array(values) => {
+                let mut buf = Vec::with_capacity(Encode::size(self) as usize);
+                buf.push(discriminant::ARRAY);
+                buf.extend_from_slice(&(values.len() as u16).to_le_bytes());
+                for value in values {
+                    buf.extend_from_slice(&Encode::encode(value));
+                }
+                Cow::Owned(buf)
+            }
             Value::Blob(v) => encode_with_discriminant(discriminant::BLOB, v.encode()),
             Value::Boolean(v) => encode_with_discriminant(discriminant::BOOLEAN, v.encode()),
+            Value::Char(v) => encode_with_discriminant(discriminant::CHAR, v.encode()),
             Value::Date(v) => encode_with_discriminant(discriminant::DATE, v.encode()),
             Value::DateTime(v) => encode_with_discriminant(discriminant::DATE_TIME, v.encode()),
             Value::Decimal(v) => encode_with_discriminant(discriminant::DECIMAL, v.encode()),
+            Value::Float32(v) => encode_with_discriminant(discriminant::FLOAT32, v.encode()),
+            Value::Float64(v) => encode_with_discriminant(discriminant::FLOAT64, v.encode()),
             Value::Int8(v) => encode_with_discriminant(discriminant::INT8, v.encode()),
             Value::Int16(v) => encode_with_discriminant(discriminant::INT16, v.encode()),
             Value::Int32(v) => encode_with_discriminant(discriminant::INT32, v.encode()),
             Value::Int64(v) => encode_with_discriminant(discriminant::INT64, v.encode()),
             Value::Json(v) => encode_with_discriminant(discriminant::JSON, v.encode()),
+            Value::JsonPatch(ops) => {
+                let mut buf = Vec::with_capacity(Encode::size(self) as usize);
+                buf.push(discriminant::JSON_PATCH);
+                buf.extend_from_slice(&(ops.len() as u16).to_le_bytes());
+                for op in ops {
+                    let (tag, path, value) = json_patch_op_parts(op);
+                    buf.push(tag);
+                    buf.extend_from_slice(&types::Text(path.to_string()).encode());
+                    if let Some(value) = value {
+                        buf.extend_from_slice(&Encode::encode(value));
+                    }
+                }
+                Cow::Owned(buf)
+            }
             Value::Null => Cow::Owned(vec![discriminant::NULL]),
             Value::Text(v) => encode_with_discriminant(discriminant::TEXT, v.encode()),
+            Value::Timestamp(v) => encode_with_discriminant(discriminant::TIMESTAMP, v.encode()),
             Value::Uint8(v) => encode_with_discriminant(discriminant::UINT8, v.encode()),
             Value::Uint16(v) => encode_with_discriminant(discriminant::UINT16, v.encode()),
             Value::Uint32(v) => encode_with_discriminant(discriminant::UINT32, v.encode()),
             Value::Uint64(v) => encode_with_discriminant(discriminant::UINT64, v.encode()),
             Value::Uuid(v) => encode_with_discriminant(discriminant::UUID, v.encode()),
+            Value::ZonedDateTime(v) => {
+                encode_with_discriminant(discriminant::ZONED_DATE_TIME, v.encode())
+            }
             Value::Custom(cv) => {
                 let tag_bytes = cv.type_tag.as_bytes();
                 let tag_len = tag_bytes.len() as u16;
@@ -262,23 +355,32 @@ impl Encode for Value {
         let rest = Cow::Owned(data[1..].to_vec());
 
         match disc {
+            discriminant::ARRAY => decode_array_value(&data[1..]),
             discriminant::BLOB => types::Blob::decode(rest).map(Value::Blob),
             discriminant::BOOLEAN => types::Boolean::decode(rest).map(Value::Boolean),
+            discriminant::CHAR => types::Char::decode(rest).map(Value::Char),
             discriminant::DATE => types::Date::decode(rest).map(Value::Date),
             discriminant::DATE_TIME => types::DateTime::decode(rest).map(Value::DateTime),
             discriminant::DECIMAL => types::Decimal::decode(rest).map(Value::Decimal),
+            discriminant::FLOAT32 => types::Float32::decode(rest).map(Value::Float32),
+            discriminant::FLOAT64 => types::Float64::decode(rest).map(Value::Float64),
             discriminant::INT8 => types::Int8::decode(rest).map(Value::Int8),
             discriminant::INT16 => types::Int16::decode(rest).map(Value::Int16),
             discriminant::INT32 => types::Int32::decode(rest).map(Value::Int32),
             discriminant::INT64 => types::Int64::decode(rest).map(Value::Int64),
             discriminant::JSON => types::Json::decode(rest).map(Value::Json),
+            discriminant::JSON_PATCH => decode_json_patch_value(&data[1..]),
             discriminant::NULL => Ok(Value::Null),
             discriminant::TEXT => types::Text::decode(rest).map(Value::Text),
+            discriminant::TIMESTAMP => types::Timestamp::decode(rest).map(Value::Timestamp),
             discriminant::UINT8 => types::Uint8::decode(rest).map(Value::Uint8),
             discriminant::UINT16 => types::Uint16::decode(rest).map(Value::Uint16),
             discriminant::UINT32 => types::Uint32::decode(rest).map(Value::Uint32),
             discriminant::UINT64 => types::Uint64::decode(rest).map(Value::Uint64),
             discriminant::UUID => types::Uuid::decode(rest).map(Value::Uuid),
+            discriminant::ZONED_DATE_TIME => {
+                types::ZonedDateTime::decode(rest).map(Value::ZonedDateTime)
+            }
             discriminant::CUSTOM => decode_custom_value(&data[1..]),
             other => Err(MemoryError::DecodeError(DecodeError::InvalidDiscriminant(
                 other,
@@ -288,23 +390,38 @@ impl Encode for Value {
 
     fn size(&self) -> MSize {
         1 + match self {
+            Value::Array(values) => 2 + values.iter().map(Encode::size).sum::<MSize>(),
             Value::Blob(v) => Encode::size(v),
             Value::Boolean(v) => Encode::size(v),
+            Value::Char(v) => Encode::size(v),
             Value::Date(v) => Encode::size(v),
             Value::DateTime(v) => Encode::size(v),
             Value::Decimal(v) => Encode::size(v),
+            Value::Float32(v) => Encode::size(v),
+            Value::Float64(v) => Encode::size(v),
             Value::Int8(v) => Encode::size(v),
             Value::Int16(v) => Encode::size(v),
             Value::Int32(v) => Encode::size(v),
             Value::Int64(v) => Encode::size(v),
             Value::Json(v) => Encode::size(v),
+            Value::JsonPatch(ops) => {
+                2 + ops
+                    .iter()
+                    .map(|op| {
+                        let (_, path, value) = json_patch_op_parts(op);
+                        1 + types::Text(path.to_string()).size() + value.map_or(0, Encode::size)
+                    })
+                    .sum::<MSize>()
+            }
             Value::Null => 0,
             Value::Text(v) => Encode::size(v),
+            Value::Timestamp(v) => Encode::size(v),
             Value::Uint8(v) => Encode::size(v),
             Value::Uint16(v) => Encode::size(v),
             Value::Uint32(v) => Encode::size(v),
             Value::Uint64(v) => Encode::size(v),
             Value::Uuid(v) => Encode::size(v),
+            Value::ZonedDateTime(v) => Encode::size(v),
             Value::Custom(cv) => {
                 // tag_len(2) + tag_bytes + data_len(2) + encoded_bytes
                 (2 + cv.type_tag.len() + 2 + cv.encoded.len()) as MSize
@@ -321,6 +438,77 @@ fn encode_with_discriminant(disc: u8, inner: Cow<[u8]>) -> Cow<'static, [u8]> {
     Cow::Owned(buf)
 }
 
+/// Decodes a `Value::Array` from the bytes after the discriminant.
+fn decode_array_value(data: &[u8]) -> MemoryResult<Value> {
+    if data.len() < 2 {
+        return Err(MemoryError::DecodeError(DecodeError::TooShort));
+    }
+    let count = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let mut offset = 2;
+    let mut values = Vec::with_capacity(count);
+    for _ in 0..count {
+        if offset >= data.len() {
+            return Err(MemoryError::DecodeError(DecodeError::TooShort));
+        }
+        let value = Value::decode(Cow::Borrowed(&data[offset..]))?;
+        offset += Encode::size(&value) as usize;
+        values.push(value);
+    }
+    Ok(Value::Array(values))
+}
+
+/// Returns the tag, the path and the value of a [`JsonPatchOp`](crate::dbms::query::JsonPatchOp),
+/// as encoded in a `Value::JsonPatch`.
+fn json_patch_op_parts(op: &crate::dbms::query::JsonPatchOp) -> (u8, &str, Option<&Value>) {
+    use crate::dbms::query::JsonPatchOp;
+
+    match op {
+        JsonPatchOp::Set(path, value) => (0, path, Some(value)),
+        JsonPatchOp::Remove(path) => (1, path, None),
+        JsonPatchOp::Append(path, value) => (2, path, Some(value)),
+    }
+}
+
+/// Decodes a `Value::JsonPatch` from the bytes after the discriminant.
+fn decode_json_patch_value(data: &[u8]) -> MemoryResult<Value> {
+    use crate::dbms::query::JsonPatchOp;
+
+    if data.len() < 2 {
+        return Err(MemoryError::DecodeError(DecodeError::TooShort));
+    }
+    let count = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let mut offset = 2;
+    let mut ops = Vec::with_capacity(count);
+    for _ in 0..count {
+        let Some(&tag) = data.get(offset) else {
+            return Err(MemoryError::DecodeError(DecodeError::TooShort));
+        };
+        offset += 1;
+        let path = types::Text::decode(Cow::Borrowed(data.get(offset..).unwrap_or_default()))?;
+        offset += path.size() as usize;
+        let mut next_value = || -> MemoryResult<Value> {
+            if offset >= data.len() {
+                return Err(MemoryError::DecodeError(DecodeError::TooShort));
+            }
+            let value = Value::decode(Cow::Borrowed(&data[offset..]))?;
+            offset += Encode::size(&value) as usize;
+            Ok(value)
+        };
+        let op = match tag {
+            0 => JsonPatchOp::Set(path.0, next_value()?),
+            1 => JsonPatchOp::Remove(path.0),
+            2 => JsonPatchOp::Append(path.0, next_value()?),
+            other => {
+                return Err(MemoryError::DecodeError(DecodeError::InvalidDiscriminant(
+                    other,
+                )));
+            }
+        };
+        ops.push(op);
+    }
+    Ok(Value::JsonPatch(ops))
+}
+
 /// Decodes a [`CustomValue`](crate::dbms::custom_value::CustomValue) from the bytes after the discriminant.
 fn decode_custom_value(data: &[u8]) -> MemoryResult<Value> {
     if data.len() < 2 {
@@ -655,6 +843,73 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn test_encode_decode_timestamp() {
+        let original = Value::Timestamp(types::Timestamp(1_774_794_600_000_000_000));
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded[0], discriminant::TIMESTAMP);
+        let decoded = Value::decode(encoded).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn test_encode_decode_zoned_datetime() {
+        let original = Value::ZonedDateTime(types::ZonedDateTime::new(-1_000, -90));
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded[0], discriminant::ZONED_DATE_TIME);
+        assert_eq!(encoded.len(), 1 + 8 + 2);
+        let decoded = Value::decode(encoded).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn test_encode_decode_array() {
+        let original = Value::Array(vec![
+            Value::Uuid(types::Uuid::default()),
+            Value::Text(types::Text("foo".to_string())),
+            Value::Decimal(types::Decimal(rust_decimal::Decimal::new(12345, 2))),
+        ]);
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded[0], discriminant::ARRAY);
+        assert_eq!(Encode::size(&original) as usize, encoded.len());
+        let decoded = Value::decode(encoded).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn test_should_fail_to_decode_truncated_array() {
+        let original = Value::Array(vec![Value::Uint32(types::Uint32(1))]);
+        let encoded = Encode::encode(&original);
+        assert!(Value::decode(Cow::Owned(encoded[..encoded.len() - 1].to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_json_patch() {
+        use crate::dbms::query::JsonPatchOp;
+
+        let original = Value::JsonPatch(vec![
+            JsonPatchOp::set("user.name", "Alice"),
+            JsonPatchOp::remove("user.age"),
+            JsonPatchOp::append("tags", Value::Array(vec![Value::Int32(types::Int32(1))])),
+        ]);
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded[0], discriminant::JSON_PATCH);
+        assert_eq!(Encode::size(&original) as usize, encoded.len());
+        let decoded = Value::decode(encoded.clone()).unwrap();
+        assert_eq!(original, decoded);
+        assert!(Value::decode(Cow::Owned(encoded[..encoded.len() - 1].to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_char() {
+        let original = Value::Char(types::Char('€'));
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded[0], discriminant::CHAR);
+        assert_eq!(encoded.len(), 1 + 4);
+        let decoded = Value::decode(encoded).unwrap();
+        assert_eq!(original, decoded);
+    }
+
     #[test]
     fn test_encode_decode_decimal() {
         let original = Value::Decimal(types::Decimal(rust_decimal::Decimal::new(12345, 2)));
@@ -663,6 +918,24 @@ mod tests {
         assert_eq!(original, decoded);
     }
 
+    #[test]
+    fn test_encode_decode_float32() {
+        let original = Value::Float32(types::Float32(3.25));
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded.len(), 5);
+        let decoded = Value::decode(encoded).unwrap();
+        assert_eq!(original, decoded);
+    }
+
+    #[test]
+    fn test_encode_decode_float64() {
+        let original = Value::Float64(types::Float64(-1e300));
+        let encoded = Encode::encode(&original);
+        assert_eq!(encoded.len(), 9);
+        let decoded = Value::decode(encoded).unwrap();
+        assert_eq!(original, decoded);
+    }
+
     #[test]
     fn test_encode_decode_int8() {
         let original = Value::Int8(types::Int8(-42));
@@ -814,4 +1087,62 @@ mod tests {
         let encoded = Encode::encode(&original);
         assert_eq!(Encode::size(&original) as usize, encoded.len());
     }
+
+    #[test]
+    fn test_compare_typed_same_type() {
+        let one = Value::Uint32(types::Uint32(1));
+        let two = Value::Uint32(types::Uint32(2));
+        assert_eq!(one.compare_typed(&two).unwrap(), Ordering::Less);
+        assert_eq!(two.compare_typed(&one).unwrap(), Ordering::Greater);
+        assert_eq!(one.compare_typed(&one).unwrap(), Ordering::Equal);
+        assert_eq!(
+            Value::Null.compare_typed(&Value::Null).unwrap(),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::Text("b".into())
+                .compare_typed(&Value::Text("a".into()))
+                .unwrap(),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_compare_typed_rejects_mismatched_types() {
+        for (left, right) in [
+            (Value::Uint32(types::Uint32(1)), Value::Text("1".into())),
+            (
+                Value::Uint32(types::Uint32(1)),
+                Value::Uint64(types::Uint64(1)),
+            ),
+            (Value::Int32(types::Int32(1)), Value::Null),
+            (Value::Null, Value::Boolean(types::Boolean(true))),
+        ] {
+            let err = left.compare_typed(&right).unwrap_err();
+            assert!(
+                matches!(
+                    &err,
+                    QueryError::TypeMismatch { expected, found }
+                        if expected == left.type_name() && found == right.type_name()
+                ),
+                "unexpected error: {err:?}"
+            );
+        }
+
+        let custom = |type_tag: &str| {
+            Value::Custom(crate::dbms::custom_value::CustomValue {
+                type_tag: type_tag.to_string(),
+                encoded: vec![1],
+                display: "1".to_string(),
+            })
+        };
+        assert_eq!(
+            custom("money").compare_typed(&custom("money")).unwrap(),
+            Ordering::Equal
+        );
+        assert!(matches!(
+            custom("money").compare_typed(&custom("weight")),
+            Err(QueryError::TypeMismatch { .. })
+        ));
+    }
 }
@@ -18,3 +18,10 @@ pub const UINT32: u8 = 14;
 pub const UINT64: u8 = 15;
 pub const UUID: u8 = 16;
 pub const CUSTOM: u8 = 17;
+pub const FLOAT32: u8 = 18;
+pub const FLOAT64: u8 = 19;
+pub const TIMESTAMP: u8 = 20;
+pub const ARRAY: u8 = 21;
+pub const CHAR: u8 = 22;
+pub const JSON_PATCH: u8 = 23;
+pub const ZONED_DATE_TIME: u8 = 24;
@@ -1,6 +1,10 @@
+mod compression;
 mod encode;
 mod error;
 
+pub use self::compression::{
+    COMPRESSION_MAGIC, compressed_size, decode_compressed, encode_compressed,
+};
 pub use self::encode::{DEFAULT_ALIGNMENT, DataSize, Encode};
 pub use self::error::{DecodeError, MemoryError};
 
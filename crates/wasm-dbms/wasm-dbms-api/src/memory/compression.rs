@@ -0,0 +1,153 @@
+//! LZ4 compression for `#[compress]` columns.
+//!
+//! A compressed value is stored as
+//! `[0xFF 0xFF][compressed length: u16 LE][LZ4 block with the uncompressed size prepended]`.
+//!
+//! The `0xFFFF` magic sits where a plain [`Text`](crate::prelude::Text) or
+//! [`Blob`](crate::prelude::Blob) stores its length prefix. A record must fit in
+//! a 64 KiB page, so a plain value can never carry that length: values written
+//! before the column was marked `#[compress]` keep decoding as they are.
+
+use std::borrow::Cow;
+
+use crate::memory::{DecodeError, Encode, MSize, MemoryError, MemoryResult};
+
+/// Magic value stored in place of the length prefix of a compressed value.
+pub const COMPRESSION_MAGIC: u16 = u16::MAX;
+
+/// Size of the magic plus the compressed length prefix.
+const COMPRESSED_HEADER_SIZE: usize = 4;
+
+/// Encodes `value` and compresses it with LZ4.
+///
+/// The plain encoding is returned when compression does not make the value
+/// smaller, which is usually the case for short values.
+///
+/// Only meant for length-prefixed types (`Text` and `Blob`).
+pub fn encode_compressed<E>(value: &E) -> Cow<'_, [u8]>
+where
+    E: Encode,
+{
+    let plain = value.encode();
+    let compressed = lz4_flex::compress_prepend_size(&plain);
+    let encoded_len = COMPRESSED_HEADER_SIZE + compressed.len();
+    if encoded_len >= plain.len() {
+        return plain;
+    }
+
+    let mut encoded = Vec::with_capacity(encoded_len);
+    encoded.extend_from_slice(&COMPRESSION_MAGIC.to_le_bytes());
+    encoded.extend_from_slice(&(compressed.len() as u16).to_le_bytes());
+    encoded.extend_from_slice(&compressed);
+    Cow::Owned(encoded)
+}
+
+/// Returns the size in bytes of the value encoded with [`encode_compressed`].
+pub fn compressed_size<E>(value: &E) -> MSize
+where
+    E: Encode,
+{
+    encode_compressed(value).len() as MSize
+}
+
+/// Decodes a value written by [`encode_compressed`].
+///
+/// Plain encodings are decoded as they are. Returns the value along with the
+/// number of bytes it occupied in `data`.
+pub fn decode_compressed<E>(data: Cow<[u8]>) -> MemoryResult<(E, MSize)>
+where
+    E: Encode,
+{
+    if !is_compressed(&data) {
+        let value = E::decode(data)?;
+        let size = value.size();
+        return Ok((value, size));
+    }
+
+    if data.len() < COMPRESSED_HEADER_SIZE {
+        return Err(MemoryError::DecodeError(DecodeError::TooShort));
+    }
+    let compressed_len = u16::from_le_bytes([data[2], data[3]]) as usize;
+    let end = COMPRESSED_HEADER_SIZE + compressed_len;
+    if data.len() < end {
+        return Err(MemoryError::DecodeError(DecodeError::TooShort));
+    }
+
+    let plain = lz4_flex::decompress_size_prepended(&data[COMPRESSED_HEADER_SIZE..end])
+        .map_err(|err| MemoryError::DecodeError(DecodeError::Decompress(err.to_string())))?;
+    let value = E::decode(Cow::Owned(plain))?;
+
+    Ok((value, end as MSize))
+}
+
+/// Returns whether `data` starts with the [`COMPRESSION_MAGIC`].
+fn is_compressed(data: &[u8]) -> bool {
+    data.len() >= 2 && u16::from_le_bytes([data[0], data[1]]) == COMPRESSION_MAGIC
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Blob, Text};
+
+    #[test]
+    fn test_should_compress_repetitive_text() {
+        let text = Text("hello world ".repeat(100));
+        let encoded = encode_compressed(&text);
+
+        assert!(encoded.len() < text.size() as usize);
+        assert_eq!(&encoded[0..2], &COMPRESSION_MAGIC.to_le_bytes());
+        assert_eq!(compressed_size(&text) as usize, encoded.len());
+
+        let (decoded, size) = decode_compressed::<Text>(encoded.clone()).unwrap();
+        assert_eq!(decoded, text);
+        assert_eq!(size as usize, encoded.len());
+    }
+
+    #[test]
+    fn test_should_keep_short_values_plain() {
+        let blob = Blob(vec![1, 2, 3]);
+        let encoded = encode_compressed(&blob);
+
+        assert_eq!(encoded, blob.encode());
+
+        let (decoded, size) = decode_compressed::<Blob>(encoded).unwrap();
+        assert_eq!(decoded, blob);
+        assert_eq!(size, blob.size());
+    }
+
+    #[test]
+    fn test_should_decode_plain_value_followed_by_other_data() {
+        let text = Text("plain".to_string());
+        let mut data = text.encode().into_owned();
+        data.extend_from_slice(&[0xAA, 0xBB]);
+
+        let (decoded, size) = decode_compressed::<Text>(Cow::Owned(data)).unwrap();
+        assert_eq!(decoded, text);
+        assert_eq!(size, text.size());
+    }
+
+    #[test]
+    fn test_should_fail_decoding_truncated_compressed_value() {
+        let text = Text("abc".repeat(200));
+        let encoded = encode_compressed(&text);
+        let truncated = encoded[..encoded.len() - 1].to_vec();
+
+        let result = decode_compressed::<Text>(Cow::Owned(truncated));
+        assert!(matches!(
+            result,
+            Err(MemoryError::DecodeError(DecodeError::TooShort))
+        ));
+    }
+
+    #[test]
+    fn test_should_fail_decoding_corrupted_compressed_value() {
+        let data = vec![0xFF, 0xFF, 0x02, 0x00, 0xFF, 0xFF];
+
+        let result = decode_compressed::<Text>(Cow::Owned(data));
+        assert!(matches!(
+            result,
+            Err(MemoryError::DecodeError(DecodeError::Decompress(_)))
+        ));
+    }
+}
@@ -99,6 +99,9 @@ pub enum DecodeError {
     /// Error when the raw record header is invalid.
     #[error("Bad raw record header")]
     BadRawRecordHeader,
+    /// Error when a compressed value cannot be decompressed.
+    #[error("Failed to decompress data: {0}")]
+    Decompress(String),
     /// Error when JSON is invalid.
     #[error("Invalid JSON: {0}")]
     InvalidJson(String),
@@ -120,6 +123,10 @@ pub enum DecodeError {
     /// UUID error
     #[error("UUID error: {0}")]
     UuidError(String),
+    /// Error when the schema version stored in a table header differs from
+    /// the version of the schema compiled into the code.
+    #[error("Schema version mismatch: stored {stored}, current {current}")]
+    VersionMismatch { stored: u8, current: u8 },
 }
 
 impl From<uuid::Error> for DecodeError {
@@ -1,7 +1,7 @@
 //! Prelude exposes all public types for the `wasm-dbms-api` crate.
 
 // Re-export derive macros from wasm-dbms-macros.
-pub use wasm_dbms_macros::{CustomDataType, DatabaseSchema, Encode, Table};
+pub use wasm_dbms_macros::{CustomDataType, DatabaseSchema, DbmsEnum, Encode, Table};
 
 pub use crate::dbms::acl::{IdentityPerms, PermGrant, PermRevoke, RequiredPerm, TablePerms};
 pub use crate::dbms::autoincrement::Autoincrement;
@@ -12,18 +12,21 @@ pub use crate::dbms::migration::{
     ColumnChanges, Migrate, MigrationError, MigrationOp, MigrationPolicy,
 };
 pub use crate::dbms::query::{
-    AggregateFunction, AggregatedRow, AggregatedValue, DeleteBehavior, Filter, Join, JoinType,
-    JsonCmp, JsonFilter, OrderDirection, Query, QueryBuilder, QueryError, QueryResult, Select,
+    AggregateFunction, AggregatedRow, AggregatedValue, BlobChunk, ColumnCmp, ContinuationToken,
+    DeleteBehavior, Filter, InsertConflictBehavior, Join, JoinType, JsonCmp, JsonFilter,
+    JsonPatchOp, OrderDirection, PageCursor, Query, QueryBuilder, QueryError, QueryResult, Select,
+    UpsertResult, apply_json_patch,
 };
+pub use crate::dbms::retention::{RetentionPolicy, VacuumReport};
 pub use crate::dbms::sanitize::*;
 pub use crate::dbms::table::*;
-pub use crate::dbms::transaction::{TransactionError, TransactionId};
+pub use crate::dbms::transaction::{SavepointId, TransactionError, TransactionId};
 pub use crate::dbms::types::*;
 pub use crate::dbms::validate::*;
 pub use crate::dbms::value::Value;
 pub use crate::error::{DbmsError, DbmsResult};
 pub use crate::memory::{
-    DEFAULT_ALIGNMENT, DataSize, DecodeError, Encode, MSize, MemoryError, MemoryResult, Page,
-    PageOffset,
+    COMPRESSION_MAGIC, DEFAULT_ALIGNMENT, DataSize, DecodeError, Encode, MSize, MemoryError,
+    MemoryResult, Page, PageOffset, compressed_size, decode_compressed, encode_compressed,
 };
 pub use crate::utils::self_reference_values;
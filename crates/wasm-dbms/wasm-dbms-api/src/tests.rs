@@ -155,6 +155,7 @@ impl UpdateRecord for UserUpdateRequest {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 crate::dbms::value::Value::Uint32(id),
             ));
@@ -171,6 +172,7 @@ impl UpdateRecord for UserUpdateRequest {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 crate::dbms::value::Value::Text(name.clone()),
             ));
@@ -254,6 +256,7 @@ impl TableSchema for User {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
             ColumnDef {
                 name: "name",
@@ -265,20 +268,21 @@ impl TableSchema for User {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             },
         ]
     }
 
-    fn primary_key() -> &'static str {
-        "id"
+    fn primary_keys() -> &'static [&'static str] {
+        &["id"]
     }
 
-    fn sanitizer(_column_name: &'static str) -> Option<Box<dyn crate::prelude::Sanitize>> {
-        None
+    fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn crate::prelude::Sanitize>> {
+        Vec::new()
     }
 
-    fn validator(_column_name: &'static str) -> Option<Box<dyn Validate>> {
-        None
+    fn validator(_column_name: &'static str) -> Vec<Box<dyn Validate>> {
+        Vec::new()
     }
 
     fn to_values(self) -> Vec<(ColumnDef, Value)> {
@@ -26,6 +26,16 @@ pub fn custom_data_type(input: &DeriveInput) -> Result<TokenStream> {
 ///
 /// Uses the same NameValue parsing pattern as `#[table = "..."]` in `metadata.rs`.
 fn extract_type_tag(input: &DeriveInput) -> Result<String> {
+    find_type_tag(input)?.ok_or_else(|| {
+        syn::Error::new_spanned(
+            input,
+            "CustomDataType requires a `#[type_tag = \"...\"]` attribute",
+        )
+    })
+}
+
+/// Find the optional `#[type_tag = "..."]` attribute.
+pub fn find_type_tag(input: &DeriveInput) -> Result<Option<String>> {
     for attr in &input.attrs {
         if attr.path().is_ident("type_tag") {
             let name_value = attr.meta.require_name_value().map_err(|_| {
@@ -37,7 +47,7 @@ fn extract_type_tag(input: &DeriveInput) -> Result<String> {
                 ..
             }) = &name_value.value
             {
-                return Ok(lit.value());
+                return Ok(Some(lit.value()));
             } else {
                 return Err(syn::Error::new_spanned(
                     &name_value.value,
@@ -47,8 +57,5 @@ fn extract_type_tag(input: &DeriveInput) -> Result<String> {
         }
     }
 
-    Err(syn::Error::new_spanned(
-        input,
-        "CustomDataType requires a `#[type_tag = \"...\"]` attribute",
-    ))
+    Ok(None)
 }
@@ -31,10 +31,23 @@ pub fn database_schema(input: DeriveInput) -> syn::Result<TokenStream2> {
 fn impl_database_schema(struct_ident: &syn::Ident, tables: &[TableEntry]) -> TokenStream2 {
     let select_fn = impl_select(tables);
     let aggregate_fn = impl_aggregate(tables);
+    let count_fn = impl_count(tables);
+    let exists_fn = impl_exists(tables);
     let referenced_tables_fn = impl_referenced_tables(tables);
     let insert_fn = impl_insert(tables);
+    let insert_many_fn = impl_insert_many(tables);
+    let insert_with_behavior_fn = impl_insert_with_behavior(tables);
+    let insert_returning_fn = impl_insert_returning(tables);
+    let upsert_fn = impl_upsert(tables);
     let delete_fn = impl_delete(tables);
+    let force_delete_fn = impl_force_delete(tables);
+    let truncate_fn = impl_truncate(tables);
+    let defragment_fn = impl_defragment(tables);
+    let compact_fn = impl_compact(tables);
+    let blob_read_chunk_fn = impl_blob_read_chunk(tables);
+    let blob_write_chunk_fn = impl_blob_write_chunk(tables);
     let update_fn = impl_update(tables);
+    let update_returning_fn = impl_update_returning(tables);
     let validate_insert_fn = impl_validate_insert(tables);
     let validate_update_fn = impl_validate_update(tables);
     let migrate_default_fn = impl_migrate_default(tables);
@@ -53,10 +66,23 @@ fn impl_database_schema(struct_ident: &syn::Ident, tables: &[TableEntry]) -> Tok
         {
             #select_fn
             #aggregate_fn
+            #count_fn
+            #exists_fn
             #referenced_tables_fn
             #insert_fn
+            #insert_many_fn
+            #insert_with_behavior_fn
+            #insert_returning_fn
+            #upsert_fn
             #delete_fn
+            #force_delete_fn
+            #truncate_fn
+            #defragment_fn
+            #compact_fn
+            #blob_read_chunk_fn
+            #blob_write_chunk_fn
             #update_fn
+            #update_returning_fn
             #validate_insert_fn
             #validate_update_fn
             #migrate_default_fn
@@ -101,7 +127,7 @@ fn impl_select(tables: &[TableEntry]) -> TokenStream2 {
             let entity = &t.table;
             quote::quote! {
                 name if name == #entity::table_name() => {
-                    let results = dbms.select_columns::<#entity>(query)?;
+                    let results = dbms.select_live_columns::<#entity>(query)?;
                     Ok(::wasm_dbms_api::prelude::flatten_table_columns(results))
                 }
             }
@@ -161,6 +187,72 @@ fn impl_aggregate(tables: &[TableEntry]) -> TokenStream2 {
     }
 }
 
+fn impl_count(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.count::<#entity>(filter)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn count(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &str,
+            filter: Option<::wasm_dbms_api::prelude::Filter>,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_exists(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.exists::<#entity>(filter)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn exists(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &str,
+            filter: ::wasm_dbms_api::prelude::Filter,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<bool> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
 fn impl_referenced_tables(tables: &[TableEntry]) -> TokenStream2 {
     let table_tuples: Vec<_> = tables
         .iter()
@@ -183,6 +275,17 @@ fn impl_referenced_tables(tables: &[TableEntry]) -> TokenStream2 {
             ];
             ::wasm_dbms::prelude::get_referenced_tables(table, tables)
         }
+
+        fn referencing_foreign_keys(
+            &self,
+            table: &'static str,
+        ) -> Vec<(&'static str, ::wasm_dbms_api::prelude::ForeignKeyDef)> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            let tables = &[
+                #(#table_tuples),*
+            ];
+            ::wasm_dbms::prelude::get_referencing_foreign_keys(table, tables)
+        }
     }
 }
 
@@ -222,6 +325,153 @@ fn impl_insert(tables: &[TableEntry]) -> TokenStream2 {
     }
 }
 
+fn impl_insert_with_behavior(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            let insert = &t.insert;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    let insert_request = #insert::from_values(record_values)?;
+                    dbms.insert_with_behavior::<#entity>(insert_request, behavior)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn insert_with_behavior(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+            record_values: &[(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)],
+            behavior: ::wasm_dbms_api::prelude::InsertConflictBehavior,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<()> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::InsertRecord as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_insert_returning(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            let insert = &t.insert;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    let insert_request = #insert::from_values(record_values)?;
+                    dbms.insert_values::<#entity>(insert_request)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn insert_returning(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+            record_values: &[(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)],
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<Vec<(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)>> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::InsertRecord as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_upsert(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            let insert = &t.insert;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    let insert_request = #insert::from_values(record_values)?;
+                    dbms.upsert::<#entity>(insert_request)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn upsert(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+            record_values: &[(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)],
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<::wasm_dbms_api::prelude::UpsertResult> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::InsertRecord as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_insert_many(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            let insert = &t.insert;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    let records = rows
+                        .iter()
+                        .map(|record_values| #insert::from_values(record_values))
+                        .collect::<::wasm_dbms_api::prelude::DbmsResult<Vec<_>>>()?;
+                    dbms.insert_many::<#entity>(records)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn insert_many(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+            rows: &[Vec<(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)>],
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::InsertRecord as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
 fn impl_delete(tables: &[TableEntry]) -> TokenStream2 {
     let match_arms: Vec<_> = tables
         .iter()
@@ -256,16 +506,217 @@ fn impl_delete(tables: &[TableEntry]) -> TokenStream2 {
     }
 }
 
+fn impl_force_delete(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.force_delete::<#entity>(delete_behavior, filter)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn force_delete(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+            delete_behavior: ::wasm_dbms_api::prelude::DeleteBehavior,
+            filter: Option<::wasm_dbms_api::prelude::Filter>,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_truncate(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.truncate::<#entity>()
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn truncate(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_compact(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.compact::<#entity>(max_pages)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn compact(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &str,
+            max_pages: u64,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<::wasm_dbms_api::prelude::CompactionReport> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_defragment(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.defragment::<#entity>()
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn defragment(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &str,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_blob_read_chunk(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.blob_read_chunk::<#entity>(pk, column, offset, len)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn blob_read_chunk(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &str,
+            pk: ::wasm_dbms_api::prelude::Value,
+            column: &str,
+            offset: u64,
+            len: u64,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<::wasm_dbms_api::prelude::BlobChunk> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_blob_write_chunk(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.blob_write_chunk::<#entity>(pk, column, offset, bytes)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn blob_write_chunk(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &str,
+            pk: ::wasm_dbms_api::prelude::Value,
+            column: &str,
+            offset: u64,
+            bytes: &[u8],
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
 fn impl_update(tables: &[TableEntry]) -> TokenStream2 {
     let match_arms: Vec<_> = tables
         .iter()
         .map(|t| {
             let entity = &t.table;
-            let update = &t.update;
             quote::quote! {
                 name if name == #entity::table_name() => {
-                    let update_request = #update::from_values(patch_values, filter);
-                    dbms.update::<#entity>(update_request)
+                    dbms.update_from_values::<#entity>(patch_values, filter).map(|(count, _)| count)
                 }
             }
         })
@@ -280,8 +731,39 @@ fn impl_update(tables: &[TableEntry]) -> TokenStream2 {
             filter: Option<::wasm_dbms_api::prelude::Filter>,
         ) -> ::wasm_dbms_api::prelude::DbmsResult<u64> {
             use ::wasm_dbms_api::prelude::TableSchema as _;
-            use ::wasm_dbms_api::prelude::UpdateRecord as _;
-            use ::wasm_dbms_api::prelude::Database as _;
+
+            match table_name {
+                #(#match_arms)*
+                _ => Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                    ::wasm_dbms_api::prelude::QueryError::TableNotFound(table_name.to_string()),
+                )),
+            }
+        }
+    }
+}
+
+fn impl_update_returning(tables: &[TableEntry]) -> TokenStream2 {
+    let match_arms: Vec<_> = tables
+        .iter()
+        .map(|t| {
+            let entity = &t.table;
+            quote::quote! {
+                name if name == #entity::table_name() => {
+                    dbms.update_from_values::<#entity>(patch_values, filter).map(|(_, rows)| rows)
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        fn update_returning(
+            &self,
+            dbms: &::wasm_dbms::prelude::WasmDbmsDatabase<'_, M, A>,
+            table_name: &'static str,
+            patch_values: &[(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)],
+            filter: Option<::wasm_dbms_api::prelude::Filter>,
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<Vec<Vec<(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)>>> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
 
             match table_name {
                 #(#match_arms)*
@@ -21,8 +21,6 @@ pub struct TableEntry {
     pub table: Ident,
     /// Generated insert request type identifier (e.g. `UserInsertRequest`).
     pub insert: Ident,
-    /// Generated update request type identifier (e.g. `UserUpdateRequest`).
-    pub update: Ident,
 }
 
 /// Parses `#[tables(User = "users", Post = "posts")]` attributes into
@@ -39,6 +37,21 @@ pub fn collect_schema_metadata(attrs: &[syn::Attribute]) -> syn::Result<SchemaMe
                     .get_ident()
                     .cloned()
                     .ok_or_else(|| meta.error("expected identifier"))?;
+                // `Table(name = "...", ...)` entries carry options read by other
+                // derives (e.g. the `DbmsCanister` hooks), which are skipped here
+                if meta.input.peek(syn::token::Paren) {
+                    let mut name = None;
+                    meta.parse_nested_meta(|entry| {
+                        let value: syn::LitStr = entry.value()?.parse()?;
+                        if entry.path.is_ident("name") {
+                            name = Some(value.value());
+                        }
+                        Ok(())
+                    })?;
+                    let name = name.ok_or_else(|| meta.error("missing `name`"))?;
+                    names.push((ident, name));
+                    return Ok(());
+                }
                 let value: syn::LitStr = meta.value()?.parse()?;
                 let value = value.value();
 
@@ -59,11 +72,9 @@ pub fn collect_schema_metadata(attrs: &[syn::Attribute]) -> syn::Result<SchemaMe
 /// Derives associated type identifiers for a single table entry.
 fn collect_table_entry(table: Ident) -> syn::Result<TableEntry> {
     let insert_ident = Ident::new(&format!("{table}InsertRequest"), table.span());
-    let update_ident = Ident::new(&format!("{table}UpdateRequest"), table.span());
 
     Ok(TableEntry {
         table: table.clone(),
         insert: insert_ident,
-        update: update_ident,
     })
 }
@@ -0,0 +1,77 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Result};
+
+use crate::custom_data_type::find_type_tag;
+use crate::encode::{UnitEnumByte, encode_unit_enum};
+
+pub fn dbms_enum(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let Data::Enum(enum_data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`DbmsEnum` can only be derived for enums",
+        ));
+    };
+
+    // the stored byte is the declaration index of the variant, so it indexes
+    // the variant names and values are ordered as the variants are declared,
+    // whatever their discriminants
+    let encode = encode_unit_enum(name, &input.generics, enum_data, UnitEnumByte::Index)?;
+    let type_tag = find_type_tag(input)?.unwrap_or_else(|| name.to_string());
+    let variants = enum_data
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let variant_names = variants
+        .iter()
+        .map(|variant| variant.to_string())
+        .collect::<Vec<_>>();
+    let expected = format!("Custom({type_tag})");
+
+    Ok(quote! {
+        #encode
+
+        impl ::std::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let name = match self {
+                    #(Self::#variants => #variant_names,)*
+                };
+                f.write_str(name)
+            }
+        }
+
+        impl ::wasm_dbms_api::prelude::DataType for #name {}
+
+        impl ::wasm_dbms_api::prelude::CustomDataType for #name {
+            const TYPE_TAG: &'static str = #type_tag;
+            const VARIANTS: Option<&'static [&'static str]> = Some(&[#(#variant_names),*]);
+        }
+
+        impl ::std::convert::From<#name> for ::wasm_dbms_api::prelude::Value {
+            fn from(val: #name) -> ::wasm_dbms_api::prelude::Value {
+                ::wasm_dbms_api::prelude::Value::Custom(::wasm_dbms_api::prelude::CustomValue::new(&val))
+            }
+        }
+
+        impl ::std::convert::TryFrom<::wasm_dbms_api::prelude::Value> for #name {
+            type Error = ::wasm_dbms_api::prelude::QueryError;
+
+            fn try_from(value: ::wasm_dbms_api::prelude::Value) -> ::std::result::Result<Self, Self::Error> {
+                match value.as_custom() {
+                    Some(custom) if custom.type_tag == #type_tag => {
+                        <Self as ::wasm_dbms_api::prelude::Encode>::decode(
+                            ::std::borrow::Cow::Borrowed(&custom.encoded),
+                        )
+                        .map_err(::wasm_dbms_api::prelude::QueryError::MemoryError)
+                    }
+                    _ => Err(::wasm_dbms_api::prelude::QueryError::TypeMismatch {
+                        expected: #expected.to_string(),
+                        found: value.type_name().to_string(),
+                    }),
+                }
+            }
+        }
+    })
+}
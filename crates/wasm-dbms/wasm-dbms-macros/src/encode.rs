@@ -1,11 +1,15 @@
 use proc_macro2::TokenStream as TokenStream2;
-use syn::{DataStruct, DeriveInput};
+use syn::{DataEnum, DataStruct, DeriveInput, Fields, Ident};
 
 use crate::utils;
 
 /// Generate implementation of `Encode` trait.
 ///
 /// If `alignment` is `Some` and the data size is not `FIXED`, the alignment will be set to the provided value.
+///
+/// Fields listed in `compressed` are stored LZ4-compressed. Fields listed in
+/// `computed` are not stored: `decode` evaluates their expression, which can
+/// read the decoded fields by name.
 pub fn encode(
     DeriveInput {
         ident,
@@ -14,19 +18,38 @@ pub fn encode(
         ..
     }: DeriveInput,
     alignment: Option<u16>,
+    compressed: &[Ident],
+    computed: &[(Ident, TokenStream2)],
 ) -> syn::Result<TokenStream2> {
-    let syn::Data::Struct(struct_data) = data else {
-        return Err(syn::Error::new_spanned(
-            ident,
-            "`Encode` can only be derived for structs",
-        ));
+    let mut struct_data = match data {
+        syn::Data::Struct(struct_data) => struct_data,
+        syn::Data::Enum(enum_data) => {
+            return encode_unit_enum(&ident, &generics, &enum_data, UnitEnumByte::Discriminant);
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "`Encode` can only be derived for structs and unit enums",
+            ));
+        }
     };
 
+    if let Fields::Named(fields) = &mut struct_data.fields {
+        fields.named = std::mem::take(&mut fields.named)
+            .into_iter()
+            .filter(|field| {
+                !computed
+                    .iter()
+                    .any(|(name, _)| field.ident.as_ref() == Some(name))
+            })
+            .collect();
+    }
+
     let data_size = impl_size_const(&struct_data);
     let alignment = impl_alignment_const(&struct_data, alignment);
-    let size = impl_size(&struct_data);
-    let encode = impl_encode(&struct_data);
-    let decode = impl_decode(&struct_data);
+    let size = impl_size(&struct_data, compressed);
+    let encode = impl_encode(&struct_data, compressed);
+    let decode = impl_decode(&struct_data, compressed, computed);
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     Ok(quote::quote! {
@@ -43,6 +66,129 @@ pub fn encode(
     })
 }
 
+/// The byte [`encode_unit_enum`] stores for a variant.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UnitEnumByte {
+    /// The discriminant of the variant, so explicit discriminants must fit in
+    /// a `u8`.
+    Discriminant,
+    /// The position of the variant in declaration order, whatever its
+    /// discriminant, so the enum can have at most 256 variants.
+    Index,
+}
+
+/// Generate implementation of `Encode` trait for an enum whose variants are
+/// all unit.
+///
+/// The enum is encoded as a single byte identifying the variant, as selected
+/// by `byte`.
+pub fn encode_unit_enum(
+    ident: &Ident,
+    generics: &syn::Generics,
+    enum_data: &DataEnum,
+    byte: UnitEnumByte,
+) -> syn::Result<TokenStream2> {
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            generics,
+            "`Encode` cannot be derived for generic enums",
+        ));
+    }
+    if enum_data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "`Encode` cannot be derived for enums without variants",
+        ));
+    }
+    if let Some(variant) = enum_data
+        .variants
+        .iter()
+        .find(|variant| !matches!(variant.fields, Fields::Unit))
+    {
+        return Err(syn::Error::new_spanned(
+            variant,
+            "`Encode` can only be derived for enums whose variants are all unit",
+        ));
+    }
+
+    let variants = enum_data
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let (bytes, checks) = match byte {
+        UnitEnumByte::Discriminant => {
+            let out_of_range = format!("`{ident}` discriminants must fit in a `u8`");
+            let bytes = variants
+                .iter()
+                .map(|variant| quote::quote! { Self::#variant as u8 })
+                .collect::<Vec<_>>();
+            let checks = quote::quote! {
+                const _: () = {
+                    #(
+                        assert!(
+                            #ident::#variants as isize >= 0 && #ident::#variants as isize <= u8::MAX as isize,
+                            #out_of_range
+                        );
+                    )*
+                };
+            };
+            (bytes, checks)
+        }
+        UnitEnumByte::Index => {
+            if variants.len() > usize::from(u8::MAX) + 1 {
+                return Err(syn::Error::new_spanned(
+                    ident,
+                    format!("`{ident}` cannot have more than 256 variants"),
+                ));
+            }
+            let bytes = (0..variants.len())
+                .map(|index| {
+                    let index = index as u8;
+                    quote::quote! { #index }
+                })
+                .collect::<Vec<_>>();
+            (bytes, TokenStream2::new())
+        }
+    };
+
+    Ok(quote::quote! {
+        #checks
+
+        impl ::wasm_dbms_api::prelude::Encode for #ident {
+            const SIZE: ::wasm_dbms_api::prelude::DataSize = ::wasm_dbms_api::prelude::DataSize::Fixed(1);
+            const ALIGNMENT: ::wasm_dbms_api::prelude::PageOffset = 1;
+
+            fn size(&self) -> ::wasm_dbms_api::prelude::MSize {
+                1
+            }
+
+            fn encode(&'_ self) -> std::borrow::Cow<'_, [u8]> {
+                let discriminant = match self {
+                    #(Self::#variants => #bytes,)*
+                };
+                std::borrow::Cow::Owned(vec![discriminant])
+            }
+
+            fn decode(data: std::borrow::Cow<[u8]>) -> ::wasm_dbms_api::prelude::MemoryResult<Self> {
+                let Some(&discriminant) = data.first() else {
+                    return Err(::wasm_dbms_api::prelude::MemoryError::DecodeError(
+                        ::wasm_dbms_api::prelude::DecodeError::TooShort,
+                    ));
+                };
+                #(
+                    if discriminant == #bytes {
+                        return Ok(Self::#variants);
+                    }
+                )*
+                Err(::wasm_dbms_api::prelude::MemoryError::DecodeError(
+                    ::wasm_dbms_api::prelude::DecodeError::InvalidDiscriminant(discriminant),
+                ))
+            }
+        }
+    })
+}
+
 /// Generate implementation of `SIZE` const value.
 fn impl_size_const(struct_data: &DataStruct) -> TokenStream2 {
     let tuple_expansion = size_tuple_expansion(struct_data);
@@ -101,13 +247,19 @@ fn size_tuple_expansion(struct_data: &DataStruct) -> TokenStream2 {
 }
 
 /// Generate implementation of `size` method.
-fn impl_size(struct_data: &DataStruct) -> TokenStream2 {
+fn impl_size(struct_data: &DataStruct, compressed: &[Ident]) -> TokenStream2 {
     let items = struct_data.fields.iter().map(|field| {
         let field_name = &field.ident;
         let field_ty = &field.ty;
 
-        quote::quote! {
-            <#field_ty as ::wasm_dbms_api::prelude::Encode>::size(&self.#field_name)
+        if is_compressed(field, compressed) {
+            quote::quote! {
+                ::wasm_dbms_api::prelude::compressed_size(&self.#field_name)
+            }
+        } else {
+            quote::quote! {
+                <#field_ty as ::wasm_dbms_api::prelude::Encode>::size(&self.#field_name)
+            }
         }
     });
 
@@ -119,14 +271,20 @@ fn impl_size(struct_data: &DataStruct) -> TokenStream2 {
 }
 
 /// Generate implementation of `encode` method.
-fn impl_encode(struct_data: &DataStruct) -> TokenStream2 {
+fn impl_encode(struct_data: &DataStruct, compressed: &[Ident]) -> TokenStream2 {
     // make token for each field for encoding
     let encodings = struct_data.fields.iter().map(|field| {
         let field_ty = &field.ty;
         let field_name = &field.ident;
 
-        quote::quote! {
-            encoded.extend_from_slice(&<#field_ty as ::wasm_dbms_api::prelude::Encode>::encode(&self.#field_name));
+        if is_compressed(field, compressed) {
+            quote::quote! {
+                encoded.extend_from_slice(&::wasm_dbms_api::prelude::encode_compressed(&self.#field_name));
+            }
+        } else {
+            quote::quote! {
+                encoded.extend_from_slice(&<#field_ty as ::wasm_dbms_api::prelude::Encode>::encode(&self.#field_name));
+            }
         }
     });
 
@@ -140,14 +298,25 @@ fn impl_encode(struct_data: &DataStruct) -> TokenStream2 {
 }
 
 /// Generate implementation of `decode` method.
-fn impl_decode(struct_data: &DataStruct) -> TokenStream2 {
+fn impl_decode(
+    struct_data: &DataStruct,
+    compressed: &[Ident],
+    computed: &[(Ident, TokenStream2)],
+) -> TokenStream2 {
     let decodings = struct_data.fields.iter().map(|field| {
         let field_name = &field.ident;
         let field_ty = &field.ty;
 
-        quote::quote! {
-            let #field_name = <#field_ty as ::wasm_dbms_api::prelude::Encode>::decode(std::borrow::Cow::Borrowed(&data[offset..]))?;
-            offset += #field_name.size() as usize;
+        if is_compressed(field, compressed) {
+            quote::quote! {
+                let (#field_name, size) = ::wasm_dbms_api::prelude::decode_compressed::<#field_ty>(std::borrow::Cow::Borrowed(&data[offset..]))?;
+                offset += size as usize;
+            }
+        } else {
+            quote::quote! {
+                let #field_name = <#field_ty as ::wasm_dbms_api::prelude::Encode>::decode(std::borrow::Cow::Borrowed(&data[offset..]))?;
+                offset += #field_name.size() as usize;
+            }
         }
     });
 
@@ -156,15 +325,27 @@ fn impl_decode(struct_data: &DataStruct) -> TokenStream2 {
         .iter()
         .map(|field| &field.ident)
         .collect::<Vec<_>>();
+    let computed_names = computed.iter().map(|(name, _)| name).collect::<Vec<_>>();
+    let computed_values = computed.iter().map(|(_, value)| value);
 
     quote::quote! {
         fn decode(data: std::borrow::Cow<[u8]>) -> ::wasm_dbms_api::prelude::MemoryResult<Self> {
             let mut offset = 0;
             #(#decodings)*
+            #(let #computed_names = #computed_values;)*
 
             Ok(Self {
-                #(#field_names),*
+                #(#field_names,)*
+                #(#computed_names,)*
             })
         }
     }
 }
+
+/// Returns whether the field is listed among the compressed fields.
+fn is_compressed(field: &syn::Field, compressed: &[Ident]) -> bool {
+    field
+        .ident
+        .as_ref()
+        .is_some_and(|ident| compressed.contains(ident))
+}
@@ -11,10 +11,11 @@
 //!
 //! ## Provided Derive Macros
 //!
-//! - `Encode`: Automatically implements the `Encode` trait for structs.
+//! - `Encode`: Automatically implements the `Encode` trait for structs and unit enums.
 //! - `Table`: Automatically implements the `TableSchema` trait and associated types.
 //! - `DatabaseSchema`: Generates `DatabaseSchema<M>` trait dispatch and `register_tables`.
 //! - `CustomDataType`: Bridge user-defined types into the `Value` system.
+//! - `DbmsEnum`: Store fieldless enums as custom data types.
 
 #![doc(html_playground_url = "https://play.rust-lang.org")]
 #![doc(
@@ -29,6 +30,7 @@ use syn::{DeriveInput, parse_macro_input};
 
 mod custom_data_type;
 mod database_schema;
+mod dbms_enum;
 mod encode;
 mod table;
 mod utils;
@@ -89,9 +91,19 @@ mod utils;
 /// # Requirements
 ///
 /// - Each field type must implement `Encode`.
-/// - Only works on `struct`s; enums and unions are not supported.
+/// - Works on `struct`s and on enums whose variants are all unit; unions are
+///   not supported.
 /// - All field identifiers must be valid Rust identifiers (no tuple structs).
 ///
+/// # Unit enums
+///
+/// An enum whose variants are all unit (no data) is encoded as a single byte
+/// holding the discriminant of the variant, so `SIZE` is `DataSize::Fixed(1)`.
+/// Explicit discriminants are honoured and must fit in a `u8`; decoding a
+/// byte which is not the discriminant of any variant fails with
+/// `DecodeError::InvalidDiscriminant`. Use `#[unit_enum]` to store such an
+/// enum in a `Table` column.
+///
 /// # Notes
 ///
 /// - It is intended for internal use within the `wasm-dbms` DBMS memory
@@ -103,7 +115,7 @@ mod utils;
 ///
 /// - The struct has unnamed fields (tuple struct)
 /// - A field type does not implement `Encode`
-/// - The macro is applied to a non-struct item.
+/// - The macro is applied to a union, or to an enum with data-carrying variants.
 ///
 /// # Example
 ///
@@ -124,7 +136,7 @@ mod utils;
 #[proc_macro_derive(Encode)]
 pub fn derive_encode(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    self::encode::encode(input, None)
+    self::encode::encode(input, None, &[], &[])
         .expect("Failed to derive `Encode`")
         .into()
 }
@@ -195,8 +207,12 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
 ///                     unique: false,
 ///                     foreign_key: Some(ForeignKeyDef {
 ///                         local_column: "user_id",
+///                         local_columns: &["user_id"],
 ///                         foreign_table: "users",
 ///                         foreign_column: "id",
+///                         foreign_columns: &["id"],
+///                         on_delete: None,
+///                         deferred: false,
 ///                     }),
 ///                 },
 ///             ]
@@ -206,8 +222,8 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
 ///             "posts"
 ///         }
 ///
-///         fn primary_key() -> &'static str {
-///             "id"
+///         fn primary_keys() -> &'static [&'static str] {
+///             &["id"]
 ///         }
 ///
 ///         fn to_values(self) -> Vec<(ColumnDef, Value)> {
@@ -240,36 +256,64 @@ pub fn derive_encode(input: TokenStream) -> TokenStream {
 /// The `Table` derive macro supports the following attributes:
 ///
 /// - `#[alignment = N]`: (optional) Specifies the alignment for the table records. Use only if you know what you are doing.
+/// - `#[auto_timestamp(on = "insert" | "update")]`: Marks a `DateTime` field stamped by the engine with the current time (UTC): `on = "insert"` when the row is inserted, `on = "update"` when it is inserted and on every update. The field is left out of the `InsertRequest` and `UpdateRequest`.
 /// - `#[autoincrement]`: Marks a field as auto-incrementing. The macro will generate code to automatically fill in values for this field during inserts. Auto-increment fields must be non-nullable and cannot be marked as `#[unique]`.
+/// - `#[auto_increment]`: Alias of `#[autoincrement]`.
 /// - `#[candid]`: Marks the table as compatible with Candid serialization.
+/// - `#[check = "<expr>"]`: Field-level check constraint. The string is a `bool` expression over the whole record, available as `self` (e.g. `#[check = "self.end_date >= self.start_date"]`), evaluated after sanitization on every insert and update. A failing check returns `QueryError::CheckConstraintViolation`. The macro also generates `fn check_constraints(&self) -> DbmsResult<()>` on the struct.
+/// - `#[check(<filter>)]`: Struct-level check constraint, a filter which every row must match (e.g. `#[check(ge("end_date", column("start_date")))]`). Filters are `eq`, `ne`, `gt`, `lt`, `ge`, `le` (against a value, or against another column with `column("...")`), `between`, `like`, `is_null`, `not_null`, `and`, `or` and `not`; a `NULL` column never matches a comparison. An optional `name = "..."` argument names the check, which otherwise is `{table}_check_{n}`. Checks are listed by `TableSchema::checks` and evaluated on the sanitized row on every insert and update; a failing one returns `QueryError::CheckConstraintViolation` with the check name as `field`.
+/// - `#[compress]`: Stores the field LZ4-compressed. Only valid on `Text` and `Blob` fields; values that do not shrink are stored as they are, and values written before the attribute was added keep decoding.
+/// - `#[computed = "<expr>"]`: Field-level virtual column, which is not stored but evaluated when a record is read (e.g. `#[computed = "format!(\"{} {}\", self.first_name, self.last_name)"]`). The expression reads the stored fields as `self.<field>` (foreign key fields excluded) and its result is converted into the field type with `From`. The field is part of `${StructName}Record`, filled by `from_values` when every field it reads is loaded, but not of `Encode`, the `InsertRequest`, the `UpdateRequest` nor `TableSchema::columns`; it is listed by `TableSchema::computed_columns` instead. Selecting a computed column loads the fields it reads, and filtering on it is rejected.
+/// - `#[cross_validate = "<expr>"]`: Struct-level invariant over the whole record, available as `self` (e.g. `#[cross_validate = "self.min_qty <= self.max_qty"]`). Repeatable; every invariant is evaluated on each insert and update, after the per-column validators and the `#[check]` constraints, and the failing ones are reported together in a single `DbmsError::Validation`. The macro also generates `fn cross_validate(&self) -> DbmsResult<()>` on the struct.
 /// - `#[custom_type = "TypeName"]`: Specifies a custom data type for the field.
-/// - `#[default = <expr>]`: Field-level default value used by the migration planner when adding a non-nullable column. The expression must convert into the column's `Value` variant via `From`/`Into` (e.g. `#[default = 0]` on a `Uint32` column).
-/// - `#[foreign_key(entity = "EntityName", table = "table_name", column = "column_name")]`: Defines a foreign key relationship.
+/// - `#[deleted_at]`: Marks the `Nullable<Timestamp>` field holding the deletion time of a `#[soft_delete]` table.
+/// - `#[decimal(precision = P, scale = S)]`: Field-level precision and scale of a `Decimal` column, as in SQL `DECIMAL(P, S)`. Inserts and updates are rejected with `DbmsError::Validation` when the value has more than `S` fractional digits or more than `P - S` integer digits. Adding `round` (`#[decimal(precision = 18, scale = 4, round)]`) rounds the extra fractional digits with `RoundToScaleSanitizer` instead. The stored layout is unchanged.
+/// - `#[default = <lit>]`: Field-level default value, used when an insert omits the field (the field becomes `Option<T>` in the `InsertRequest`) and by the migration planner when adding a non-nullable column. The literal must convert into the column's `Value` variant via `From`/`Into` (e.g. `#[default = 0]` on a `Uint32` column).
+/// - `#[default(<expr>)]`: Like `#[default = <lit>]`, but takes an arbitrary expression evaluated on every insert (e.g. `#[default(Uint64::from(ic_cdk::api::time()))]`). It is not exposed to the migration planner.
+/// - `#[foreign_key(entity = "EntityName", table = "table_name", column = "column_name")]`: Defines a foreign key relationship. An optional `on_delete = "Restrict" | "Cascade" | "SetNull"` declares the action applied to the referencing rows when a referenced row is deleted, overriding the `DeleteBehavior` of the delete; `"SetNull"` requires a `Nullable<T>` field. The optional `deferred` flag skips the existence check of the referenced row on insert and update. To reference a composite primary key, list its columns with `columns = "a,b"` instead of `column`; the local columns default to the same names, or are set with `local_columns = "x,y"`, starting with the annotated field.
 /// - `#[index]`: Marks a field to be indexed for faster queries.
 /// - `#[migrate]`: Struct-level attribute that suppresses the macro's default `impl Migrate for T {}` so the user can provide a hand-written impl with custom `default_value` / `transform_column` overrides.
-/// - `#[primary_key]`: Marks a field as the primary key of the table.
+/// - `#[on_conflict = "update"]`: Struct-level attribute turning an insert whose primary key already exists into an update of that row: `Database::insert` and `Database::insert_returning` apply the fields set on the `InsertRequest` as a patch, while omitted `#[default]` and auto-increment fields keep their stored value. The generated `TableSchema::on_conflict_update` builds the patch. `insert_many`, `insert_with_behavior` and `upsert` are not affected.
+/// - `#[primary_key]`: Marks a field as the primary key of the table. Marking several fields declares a composite primary key: only the combination of their values must be unique.
+/// - `#[primary_key(auto_increment)]`: Marks a field as the primary key and makes it auto-incrementing, equivalent to `#[primary_key]` plus `#[autoincrement]`.
+/// - `#[rename = "column_name"]`: Field-level override of the column name, which otherwise is the field name. The Rust field keeps its name in the generated types, while `ColumnDef::name`, filters, `to_values` and `from_values` use the column name.
 /// - `#[renamed_from("old1", "old2", ...)]`: Field-level list of previous column names. The migration planner uses these to detect rename ops when matching a stored column against the compiled column.
-/// - `#[sanitizer(SanitizerType)]`: Specifies a sanitize for the field.
+/// - `#[sanitizer(SanitizerType)]`: Specifies a sanitize for the field. A comma-separated list (`#[sanitizer(TrimSanitizer, LowerCaseSanitizer)]`) applies each sanitizer in order, each receiving the output of the previous one.
+/// - `#[soft_delete]`: Struct-level attribute making deletes logical: `Database::delete` sets the `#[deleted_at]` field to the current time instead of removing the rows, and queries skip the rows where it is set unless built with `QueryBuilder::include_deleted`. `Database::force_delete` still removes rows physically.
 /// - `#[table = "table_name"]`: Specifies the name of the table in the database.
+/// - `#[unit_enum]`: Marks a field whose type is an enum with unit variants only, deriving `Encode`. The column is a `Uint8` holding the discriminant of the variant; filter it with the discriminant, e.g. `Filter::eq("status", Value::from(Status::Active as u8))`.
 /// - `#[unique]`: Marks a field to have a unique constraint.
-/// - `#[validate(ValidatorType)]`: Specifies a validator for the field.
+/// - `#[unique(group = "name")]`: Adds the field to a composite unique constraint; the combination of values of all fields sharing the same group must be unique.
+/// - `#[validate(ValidatorType)]`: Specifies a validator for the field. A comma-separated list (`#[validate(MinStrlenValidator(3), EmailValidator)]`) runs each validator in order and fails with the first error.
 ///
 #[proc_macro_derive(
     Table,
     attributes(
         alignment,
+        auto_increment,
+        auto_timestamp,
         autoincrement,
         candid,
+        check,
+        compress,
+        computed,
+        cross_validate,
         custom_type,
+        decimal,
         default,
+        deleted_at,
         foreign_key,
         index,
         migrate,
+        on_conflict,
         primary_key,
+        rename,
         renamed_from,
         sanitizer,
+        soft_delete,
         table,
         unique,
+        unit_enum,
         validate
     )
 )]
@@ -366,3 +410,59 @@ pub fn derive_database_schema(input: TokenStream) -> TokenStream {
         .expect("failed to derive `DatabaseSchema`")
         .into()
 }
+
+/// Derives everything needed to store a fieldless enum in a table column.
+///
+/// The macro generates:
+///
+/// - [`Encode`], storing the variant as a one-byte index in declaration order.
+///   Decoding an unknown index fails with `DecodeError::InvalidDiscriminant`.
+/// - [`Display`](std::fmt::Display), printing the variant name.
+/// - `DataType` and [`CustomDataType`], so the enum is stored as a
+///   `Value::Custom`. `CustomDataType::VARIANTS` lists the variant names,
+///   which gives `#[custom_type]` fields of the enum the `DataTypeKind::Enum`
+///   kind and makes inserts and updates reject unknown indexes.
+/// - `impl From<T> for Value` and `impl TryFrom<Value> for T`.
+///
+/// The stored index ignores explicit discriminants, so they may have gaps,
+/// and ordering by an enum column follows the order in which the variants
+/// are declared. An enum can have at most 256 variants.
+///
+/// # Optional attribute
+///
+/// - `#[type_tag = "..."]`: The type tag of the custom data type. Defaults to
+///   the name of the enum.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// #[derive(
+///     Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+///     Serialize, Deserialize, DbmsEnum,
+/// )]
+/// #[type_tag = "post_status"]
+/// enum PostStatus {
+///     #[default]
+///     Draft,
+///     Published,
+///     Archived,
+/// }
+///
+/// #[derive(Debug, Table, Clone, PartialEq, Eq)]
+/// #[table = "posts"]
+/// pub struct Post {
+///     #[primary_key]
+///     pub id: Uint32,
+///     #[custom_type]
+///     pub status: PostStatus,
+/// }
+///
+/// let filter = Filter::eq("status", PostStatus::Published.into());
+/// ```
+#[proc_macro_derive(DbmsEnum, attributes(type_tag))]
+pub fn derive_dbms_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    dbms_enum::dbms_enum(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
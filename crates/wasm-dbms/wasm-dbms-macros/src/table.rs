@@ -22,7 +22,21 @@ pub fn table(input: DeriveInput) -> syn::Result<TokenStream2> {
     let insert_impl = self::insert::generate_insert_request(&input.ident, &metadata);
     let update_impl = self::update::generate_update_request(&input.ident, &metadata);
     let foreign_fetcher_impl = self::foreign_fetcher::generate_foreign_fetcher(&metadata);
-    let encode_impl = crate::encode::encode(input, metadata.alignment)?;
+    let compressed = metadata
+        .fields
+        .iter()
+        .filter(|field| field.compress)
+        .map(|field| field.name.clone())
+        .collect::<Vec<_>>();
+    let computed = metadata
+        .computed
+        .iter()
+        .map(|field| {
+            let value = field.evaluate(|dependency| quote::quote! { #dependency.clone() });
+            (field.name.clone(), value)
+        })
+        .collect::<Vec<_>>();
+    let encode_impl = crate::encode::encode(input, metadata.alignment, &compressed, &computed)?;
 
     Ok(quote::quote! {
         #table_schema_tokens
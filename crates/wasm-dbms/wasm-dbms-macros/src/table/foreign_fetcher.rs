@@ -9,6 +9,7 @@ pub fn generate_foreign_fetcher(metadata: &TableMetadata) -> TokenStream2 {
 
     let fetch_impl = impl_fetch(metadata);
     let fetch_batch_impl = impl_fetch_batch(metadata);
+    let columns_impl = impl_columns(metadata);
 
     quote::quote! {
         #[derive(Default)]
@@ -17,25 +18,52 @@ pub fn generate_foreign_fetcher(metadata: &TableMetadata) -> TokenStream2 {
         impl ::wasm_dbms_api::prelude::ForeignFetcher for #foreign_fetcher {
             #fetch_impl
             #fetch_batch_impl
+            #columns_impl
         }
     }
 }
 
 fn impl_fetch(metadata: &TableMetadata) -> TokenStream2 {
-    // match every table we foreign fetch from
+    // match every foreign key we foreign fetch from
     let mut match_arms = vec![];
     for foreign in &metadata.foreign_keys {
         let table_name = &foreign.referenced_table.to_string();
+        let local_column = metadata.column_name(&foreign.field);
         let entity_to_query = &foreign.entity;
-        let pk_call = quote::quote! { #entity_to_query::primary_key() };
+        let arity = foreign.referenced_fields.len();
+
+        // equality on each referenced column, joined with `AND`
+        let mut filter = None;
+        for (index, column) in foreign.referenced_fields.iter().enumerate() {
+            let eq = quote::quote! {
+                ::wasm_dbms_api::prelude::Filter::eq(#column, key[#index].clone())
+            };
+            filter = Some(match filter {
+                Some(filter) => quote::quote! { #filter.and(#eq) },
+                None => eq,
+            });
+        }
+        let broken_key = if arity == 1 {
+            quote::quote! { key[0].clone() }
+        } else {
+            quote::quote! { ::wasm_dbms_api::prelude::Value::Array(key.to_vec()) }
+        };
 
         match_arms.push(quote::quote! {
-            #table_name => {
+            (#table_name, #local_column) => {
+                if key.len() != #arity {
+                    return Err(::wasm_dbms_api::prelude::DbmsError::Query(::wasm_dbms_api::prelude::QueryError::InvalidQuery(format!(
+                        "ForeignFetcher: foreign key '{local_column}' references {} columns of '{table}', got {} values",
+                        #arity,
+                        key.len()
+                    ))));
+                }
                 let mut results = database.select::<#entity_to_query>(
                     ::wasm_dbms_api::prelude::Query::builder()
                         .all()
                         .limit(1)
-                        .and_where(::wasm_dbms_api::prelude::Filter::Eq(#pk_call.to_string(), pk_value.clone()))
+                        .include_deleted()
+                        .and_where(#filter)
                         .build(),
                 )?;
                 let record = match results.pop() {
@@ -43,7 +71,7 @@ fn impl_fetch(metadata: &TableMetadata) -> TokenStream2 {
                     None => {
                         return Err(::wasm_dbms_api::prelude::DbmsError::Query(::wasm_dbms_api::prelude::QueryError::BrokenForeignKeyReference {
                             table: #table_name.to_string(),
-                            key: pk_value,
+                            key: #broken_key,
                         }));
                     }
                 };
@@ -67,12 +95,11 @@ fn impl_fetch(metadata: &TableMetadata) -> TokenStream2 {
             database: &impl ::wasm_dbms_api::prelude::Database,
             table: &str,
             local_column: &'static str,
-            pk_value: ::wasm_dbms_api::prelude::Value,
+            key: &[::wasm_dbms_api::prelude::Value],
         ) -> wasm_dbms_api::prelude::DbmsResult<::wasm_dbms_api::prelude::TableColumns> {
-            use ::wasm_dbms_api::prelude::TableSchema as _;
             use ::wasm_dbms_api::prelude::TableRecord as _;
 
-            match table {
+            match (table, local_column) {
                 #(#match_arms)*
                 _ => Err(wasm_dbms_api::prelude::DbmsError::Query(wasm_dbms_api::prelude::QueryError::InvalidQuery(format!(
                     "ForeignFetcher: unknown table '{table}' for {table_name} foreign fetcher",
@@ -85,7 +112,12 @@ fn impl_fetch(metadata: &TableMetadata) -> TokenStream2 {
 
 fn impl_fetch_batch(metadata: &TableMetadata) -> TokenStream2 {
     let mut match_arms = vec![];
-    for foreign in &metadata.foreign_keys {
+    // composite foreign keys are fetched one key at a time with `fetch`
+    for foreign in metadata
+        .foreign_keys
+        .iter()
+        .filter(|foreign| foreign.referenced_fields.len() == 1)
+    {
         let table_name = &foreign.referenced_table.to_string();
         let entity_to_query = &foreign.entity;
         let pk_call = quote::quote! { #entity_to_query::primary_key() };
@@ -96,6 +128,7 @@ fn impl_fetch_batch(metadata: &TableMetadata) -> TokenStream2 {
                 let results = database.select::<#entity_to_query>(
                     ::wasm_dbms_api::prelude::Query::builder()
                         .all()
+                        .include_deleted()
                         .and_where(::wasm_dbms_api::prelude::Filter::In(
                             pk_field.clone(),
                             pk_values.to_vec(),
@@ -121,8 +154,13 @@ fn impl_fetch_batch(metadata: &TableMetadata) -> TokenStream2 {
     }
 
     let table_name = &metadata.name.to_string();
+    // a table whose foreign keys are all composite has no arm
+    let allow_unused = match_arms
+        .is_empty()
+        .then(|| quote::quote! { #[allow(unused_variables, unused_imports)] });
 
     quote::quote! {
+        #allow_unused
         fn fetch_batch(
             &self,
             database: &impl ::wasm_dbms_api::prelude::Database,
@@ -144,3 +182,37 @@ fn impl_fetch_batch(metadata: &TableMetadata) -> TokenStream2 {
         }
     }
 }
+
+fn impl_columns(metadata: &TableMetadata) -> TokenStream2 {
+    let mut match_arms = vec![];
+    let mut seen = std::collections::HashSet::new();
+    for foreign in &metadata.foreign_keys {
+        let table_name = foreign.referenced_table.to_string();
+        if !seen.insert(table_name.clone()) {
+            continue;
+        }
+        let entity = &foreign.entity;
+        match_arms.push(quote::quote! {
+            #table_name => Ok(#entity::columns()),
+        });
+    }
+
+    let table_name = &metadata.name.to_string();
+
+    quote::quote! {
+        fn columns(
+            &self,
+            table: &str,
+        ) -> wasm_dbms_api::prelude::DbmsResult<&'static [::wasm_dbms_api::prelude::ColumnDef]> {
+            use ::wasm_dbms_api::prelude::TableSchema as _;
+
+            match table {
+                #(#match_arms)*
+                _ => Err(wasm_dbms_api::prelude::DbmsError::Query(wasm_dbms_api::prelude::QueryError::InvalidQuery(format!(
+                    "ForeignFetcher: unknown table '{table}' for {table_name} foreign fetcher",
+                    table_name = #table_name
+                )))),
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use proc_macro2::TokenStream as TokenStream2;
 use syn::Ident;
 
-use crate::table::metadata::TableMetadata;
+use crate::table::metadata::{Field, TableMetadata};
+use crate::table::table_schema::default_value;
 
 pub fn generate_insert_request(struct_name: &Ident, metadata: &TableMetadata) -> TokenStream2 {
     let insert_request_struct = generate_insert_request_struct(metadata);
@@ -33,16 +34,25 @@ pub fn generate_insert_request(struct_name: &Ident, metadata: &TableMetadata) ->
 ///     pub user_id: Uint32,
 /// }
 /// ```
+///
+/// Fields carrying `#[default = <lit>]` or `#[default(<expr>)]` become
+/// `Option<T>`; `None` is replaced by the default when the record is inserted.
+/// `#[auto_timestamp]` fields are left out, as the engine stamps them.
 fn generate_insert_request_struct(metadata: &TableMetadata) -> TokenStream2 {
     let mut fields = vec![];
 
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let name = &field.name;
         let value_ty = &field.ty;
         if field.auto_increment {
             fields.push(quote::quote! {
                 pub #name: ::wasm_dbms_api::prelude::Autoincrement<#value_ty>,
             });
+        } else if field.default.is_some() {
+            fields.push(quote::quote! {
+                #[doc = "Set to `None` to insert the `#[default]` of the column."]
+                pub #name: ::core::option::Option<#value_ty>,
+            });
         } else {
             fields.push(quote::quote! {
                 pub #name: #value_ty,
@@ -77,6 +87,7 @@ fn impl_insert_record(struct_name: &Ident, metadata: &TableMetadata) -> TokenStr
     let from_values_impl = impl_from_values(metadata);
     let into_values_impl = impl_into_values(metadata);
     let into_record_impl = impl_into_record(metadata);
+    let record_from_values_impl = impl_record_from_values(metadata);
 
     quote::quote! {
         impl ::wasm_dbms_api::prelude::InsertRecord for #insert_request_ident {
@@ -86,6 +97,7 @@ fn impl_insert_record(struct_name: &Ident, metadata: &TableMetadata) -> TokenStr
             #from_values_impl
             #into_values_impl
             #into_record_impl
+            #record_from_values_impl
         }
     }
 }
@@ -150,7 +162,7 @@ fn impl_insert_record(struct_name: &Ident, metadata: &TableMetadata) -> TokenStr
 /// ```
 fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     let mut declare_lets = vec![];
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let name = &field.name;
         let ty = &field.ty;
 
@@ -161,74 +173,20 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     }
 
     let mut match_arms = vec![];
-    for field in &metadata.fields {
-        let field_name = &field.name;
-        let field_name_str = field.name.to_string();
-
-        if field.custom_type {
-            let custom_ident = field
-                .custom_type_ident
-                .as_ref()
-                .expect("custom_type field must have custom_type_ident");
-            if field.nullable {
-                match_arms.push(quote::quote! {
-                    #field_name_str => {
-                        if let ::wasm_dbms_api::prelude::Value::Custom(cv) = __col_value {
-                            if let Ok(decoded) = <#custom_ident as ::wasm_dbms_api::prelude::Encode>::decode(
-                                std::borrow::Cow::Borrowed(&cv.encoded)
-                            ) {
-                                #field_name = Some(::wasm_dbms_api::prelude::Nullable::Value(decoded));
-                            }
-                        } else if let ::wasm_dbms_api::prelude::Value::Null = __col_value {
-                            #field_name = Some(::wasm_dbms_api::prelude::Nullable::Null);
-                        }
-                    }
-                });
-            } else {
-                match_arms.push(quote::quote! {
-                    #field_name_str => {
-                        if let ::wasm_dbms_api::prelude::Value::Custom(cv) = __col_value {
-                            if let Ok(decoded) = <#custom_ident as ::wasm_dbms_api::prelude::Encode>::decode(
-                                std::borrow::Cow::Borrowed(&cv.encoded)
-                            ) {
-                                #field_name = Some(decoded);
-                            }
-                        }
-                    }
-                });
+    for field in metadata.request_fields() {
+        let column_name = &field.column;
+        let decode = decode_column_value(field);
+        match_arms.push(quote::quote! {
+            #column_name => {
+                #decode
             }
-        } else {
-            let value_type = field
-                .value_type
-                .as_ref()
-                .expect("built-in field must have value_type");
-
-            if field.nullable {
-                match_arms.push(quote::quote! {
-                    #field_name_str => {
-                        if let #value_type(__inner_value) = __col_value {
-                            #field_name = Some(::wasm_dbms_api::prelude::Nullable::Value(__inner_value.clone()));
-                        } else if let ::wasm_dbms_api::prelude::Value::Null = __col_value {
-                            #field_name = Some(::wasm_dbms_api::prelude::Nullable::Null);
-                        }
-                    }
-                });
-            } else {
-                match_arms.push(quote::quote! {
-                    #field_name_str => {
-                        if let #value_type(__inner_value) = __col_value {
-                            #field_name = Some(__inner_value.clone());
-                        }
-                    }
-                });
-            }
-        }
+        });
     }
 
     let mut struct_fields = vec![];
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let name = &field.name;
-        let name_str = name.to_string();
+        let column_name = &field.column;
 
         if field.auto_increment {
             // autoincrement: wrap in Autoincrement::Value if present, Autoincrement::Auto if absent
@@ -238,6 +196,11 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
                     None => ::wasm_dbms_api::prelude::Autoincrement::Auto,
                 },
             });
+        } else if field.default.is_some() {
+            // defaulted: keep the option, `None` is resolved in `into_values`
+            struct_fields.push(quote::quote! {
+                #name,
+            });
         } else if field.nullable {
             struct_fields.push(quote::quote! {
                 #name: #name.unwrap_or(::wasm_dbms_api::prelude::Nullable::Null),
@@ -245,7 +208,7 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
         } else {
             struct_fields.push(quote::quote! {
                 #name: #name.ok_or(::wasm_dbms_api::prelude::DbmsError::Query(::wasm_dbms_api::prelude::QueryError::MissingNonNullableField(
-                    #name_str.to_string(),
+                    #column_name.to_string(),
                 )))?,
             })
         }
@@ -270,6 +233,63 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     }
 }
 
+/// Generates the statement decoding `__col_value` into the `Option<T>`
+/// variable named after `field`.
+///
+/// The value is left untouched if `__col_value` does not match the column
+/// type.
+fn decode_column_value(field: &Field) -> TokenStream2 {
+    let field_name = &field.name;
+
+    if field.custom_type {
+        let custom_ident = field
+            .custom_type_ident
+            .as_ref()
+            .expect("custom_type field must have custom_type_ident");
+        if field.nullable {
+            quote::quote! {
+                if let ::wasm_dbms_api::prelude::Value::Custom(cv) = __col_value {
+                    if let Ok(decoded) = <#custom_ident as ::wasm_dbms_api::prelude::Encode>::decode(
+                        std::borrow::Cow::Borrowed(&cv.encoded)
+                    ) {
+                        #field_name = Some(::wasm_dbms_api::prelude::Nullable::Value(decoded));
+                    }
+                } else if let ::wasm_dbms_api::prelude::Value::Null = __col_value {
+                    #field_name = Some(::wasm_dbms_api::prelude::Nullable::Null);
+                }
+            }
+        } else {
+            quote::quote! {
+                if let ::wasm_dbms_api::prelude::Value::Custom(cv) = __col_value {
+                    if let Ok(decoded) = <#custom_ident as ::wasm_dbms_api::prelude::Encode>::decode(
+                        std::borrow::Cow::Borrowed(&cv.encoded)
+                    ) {
+                        #field_name = Some(decoded);
+                    }
+                }
+            }
+        }
+    } else {
+        let bind_inner_value = field.bind_inner_value();
+
+        if field.nullable {
+            quote::quote! {
+                if #bind_inner_value {
+                    #field_name = Some(::wasm_dbms_api::prelude::Nullable::Value(__inner_value.clone()));
+                } else if let ::wasm_dbms_api::prelude::Value::Null = __col_value {
+                    #field_name = Some(::wasm_dbms_api::prelude::Nullable::Null);
+                }
+            }
+        } else {
+            quote::quote! {
+                if #bind_inner_value {
+                    #field_name = Some(__inner_value.clone());
+                }
+            }
+        }
+    }
+}
+
 /// Expected to generate for:
 ///
 /// ```rust,ignore
@@ -294,15 +314,31 @@ fn impl_into_values(metadata: &TableMetadata) -> TokenStream2 {
     let mut push_stmts = vec![];
     for (index, field) in metadata.fields.iter().enumerate() {
         let field_name = &field.name;
+        if field.auto_timestamp.is_some() {
+            continue;
+        }
+        let v_value = field.to_value(quote::quote! { v });
         if field.auto_increment {
             push_stmts.push(quote::quote! {
                 if let ::wasm_dbms_api::prelude::Autoincrement::Value(v) = self.#field_name {
                     values.push((Self::Schema::columns()[#index], v.into()));
                 }
             });
+        } else if let Some(default) = default_value(field) {
+            // defaulted: evaluate the default expression when the value is omitted
+            push_stmts.push(quote::quote! {
+                values.push((
+                    Self::Schema::columns()[#index],
+                    match self.#field_name {
+                        Some(v) => #v_value,
+                        None => #default,
+                    },
+                ));
+            });
         } else {
+            let value = field.to_value(quote::quote! { self.#field_name });
             push_stmts.push(quote::quote! {
-                values.push((Self::Schema::columns()[#index], self.#field_name.into()));
+                values.push((Self::Schema::columns()[#index], #value));
             });
         }
     }
@@ -343,29 +379,133 @@ fn impl_into_record(metadata: &TableMetadata) -> TokenStream2 {
     let mut fields = vec![];
     for field in &metadata.fields {
         let name = &field.name;
-        if field.auto_increment {
+        if let Some(default) = default_value(field) {
+            // defaulted: decode the default value back into the field type
+            let name_str = name.to_string();
+            let decode = decode_column_value(field);
+            fields.push(quote::quote! {
+                let #name = match self.#name {
+                    Some(v) => v,
+                    None => {
+                        let mut #name = None;
+                        let __col_value = &#default;
+                        #decode
+                        #name.unwrap_or_else(|| panic!(
+                            "default value of field '{}' does not match the column type", #name_str
+                        ))
+                    }
+                };
+            });
+        } else if field.auto_increment {
             // unwrap Autoincrement::Value -> T; panic on Auto since values must be resolved by now
             let name_str = name.to_string();
             fields.push(quote::quote! {
-                #name: match self.#name {
+                let #name = match self.#name {
                     ::wasm_dbms_api::prelude::Autoincrement::Value(v) => v,
                     ::wasm_dbms_api::prelude::Autoincrement::Auto => panic!(
                         "autoincrement field '{}' was not resolved before into_record()", #name_str
                     ),
-                },
+                };
+            });
+        } else if field.auto_timestamp.is_some() {
+            // stamped by the engine, see `record_from_values`
+            fields.push(quote::quote! {
+                let #name = ::core::default::Default::default();
             });
         } else {
             fields.push(quote::quote! {
-                #name: self.#name,
+                let #name = self.#name;
             });
         }
     }
+    let names = metadata.fields.iter().map(|field| &field.name);
+    let computed_names = metadata
+        .computed
+        .iter()
+        .map(|computed| &computed.name)
+        .collect::<Vec<_>>();
+    let computed_values = metadata
+        .computed
+        .iter()
+        .map(|computed| computed.evaluate(|dependency| quote::quote! { #dependency.clone() }));
 
     quote::quote! {
         fn into_record(self) -> Self::Schema {
+            #(#fields)*
+            #(let #computed_names = #computed_values;)*
+
             Self::Schema {
-                #(#fields)*
+                #(#names,)*
+                #(#computed_names,)*
+            }
+        }
+    }
+}
+
+/// Generates the `record_from_values` override for tables with
+/// `#[auto_timestamp]` fields, reading the stamped values back into the
+/// record; empty otherwise, so the trait default applies. Timestamps missing
+/// from `values` keep the default set by `into_record`.
+///
+/// Expected to generate for a `created_at` auto timestamp:
+///
+/// ```rust,ignore
+/// fn record_from_values(values: &[(ColumnDef, Value)]) -> DbmsResult<Self::Schema> {
+///     let mut record = Self::from_values(values)?.into_record();
+///     for (column, __col_value) in values {
+///         match column.name {
+///             "created_at" => {
+///                 if let Value::DateTime(__inner_value) = __col_value {
+///                     record.created_at = __inner_value.clone();
+///                 }
+///             }
+///             _ => {}
+///         }
+///     }
+///     Ok(record)
+/// }
+/// ```
+fn impl_record_from_values(metadata: &TableMetadata) -> TokenStream2 {
+    let match_arms: Vec<_> = metadata
+        .fields
+        .iter()
+        .filter(|field| field.auto_timestamp.is_some())
+        .map(|field| {
+            let name = &field.name;
+            let column_name = &field.column;
+            let bind_inner_value = field.bind_inner_value();
+            quote::quote! {
+                #column_name => {
+                    if #bind_inner_value {
+                        record.#name = __inner_value.clone();
+                    }
+                }
             }
+        })
+        .collect();
+    if match_arms.is_empty() {
+        return TokenStream2::new();
+    }
+    // computed fields may read the stamped values
+    let computed_names = metadata.computed.iter().map(|computed| &computed.name);
+    let computed_values = metadata.computed.iter().map(|computed| {
+        computed.evaluate(|dependency| quote::quote! { record.#dependency.clone() })
+    });
+
+    quote::quote! {
+        #[allow(clippy::copy_clone)]
+        fn record_from_values(values: &[(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)]) -> ::wasm_dbms_api::prelude::DbmsResult<Self::Schema> {
+            let mut record = Self::from_values(values)?.into_record();
+
+            for (column, __col_value) in values {
+                match column.name {
+                    #(#match_arms)*
+                    _ => {}
+                }
+            }
+            #(record.#computed_names = #computed_values;)*
+
+            Ok(record)
         }
     }
 }
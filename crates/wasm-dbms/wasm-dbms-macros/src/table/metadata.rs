@@ -1,12 +1,20 @@
 use std::collections::HashMap;
 
-use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro2::{Delimiter, Group, Span, TokenStream as TokenStream2, TokenTree};
 use quote::ToTokens as _;
-use syn::{DataStruct, Ident};
+use syn::punctuated::Punctuated;
+use syn::{DataStruct, Ident, Token};
 
 const MIN_ALIGNMENT: u16 = 8;
 
 const ATTRIBUTE_ALIGNMENT: &str = "alignment";
+const ATTRIBUTE_CHECK: &str = "check";
+const ATTRIBUTE_CROSS_VALIDATE: &str = "cross_validate";
+const ATTRIBUTE_DECIMAL: &str = "decimal";
+/// Largest precision of a `Decimal`, which holds up to 28 significant digits.
+const MAX_DECIMAL_PRECISION: u32 = 28;
+const ATTRIBUTE_COMPRESS: &str = "compress";
+const ATTRIBUTE_COMPUTED: &str = "computed";
 const ATTRIBUTE_TABLE: &str = "table";
 const ATTRIBUTE_INDEX: &str = "index";
 const ATTRIBUTE_UNIQUE: &str = "unique";
@@ -15,9 +23,19 @@ const ATTRIBUTE_FOREIGN_KEY: &str = "foreign_key";
 const ATTRIBUTE_FOREIGN_KEY_ENTITY: &str = "entity";
 const ATTRIBUTE_FOREIGN_KEY_TABLE: &str = "table";
 const ATTRIBUTE_FOREIGN_KEY_COLUMN: &str = "column";
+const ATTRIBUTE_FOREIGN_KEY_COLUMNS: &str = "columns";
+const ATTRIBUTE_FOREIGN_KEY_LOCAL_COLUMNS: &str = "local_columns";
+const ATTRIBUTE_FOREIGN_KEY_ON_DELETE: &str = "on_delete";
+const ATTRIBUTE_FOREIGN_KEY_DEFERRED: &str = "deferred";
 const ATTRIBUTE_DEFAULT: &str = "default";
+const ATTRIBUTE_RENAME: &str = "rename";
 const ATTRIBUTE_RENAMED_FROM: &str = "renamed_from";
 const ATTRIBUTE_MIGRATE: &str = "migrate";
+const ATTRIBUTE_ON_CONFLICT: &str = "on_conflict";
+const ATTRIBUTE_SOFT_DELETE: &str = "soft_delete";
+const ATTRIBUTE_DELETED_AT: &str = "deleted_at";
+const ATTRIBUTE_AUTO_TIMESTAMP: &str = "auto_timestamp";
+const ATTRIBUTE_UNIT_ENUM: &str = "unit_enum";
 
 /// Representation of a foreign key in a table
 pub struct ForeignKey {
@@ -31,12 +49,39 @@ pub struct ForeignKey {
     pub referenced_table: Ident,
     /// Name of the referenced field in the referenced table
     pub referenced_field: Ident,
+    /// Names of all the referenced columns, starting with `referenced_field`:
+    /// several with `columns = "a,b"`
+    pub referenced_fields: Vec<String>,
+    /// Names of the local columns declared with `local_columns = "a,b"`, if
+    /// any
+    pub local_columns: Option<Vec<String>>,
+    /// `DeleteBehavior` variant declared with `on_delete`, if any
+    pub on_delete: Option<Ident>,
+    /// Whether the referenced row is not checked on writes (`deferred`)
+    pub deferred: bool,
+}
+
+impl ForeignKey {
+    /// Returns the names of the local columns of the foreign key, the first one
+    /// being `field_column`, the column of the annotated field.
+    ///
+    /// Unless set with `local_columns`, a composite foreign key reads the local
+    /// columns named after the referenced ones.
+    pub fn local_columns(&self, field_column: &str) -> Vec<String> {
+        match &self.local_columns {
+            Some(columns) => columns.clone(),
+            None if self.referenced_fields.len() == 1 => vec![field_column.to_string()],
+            None => self.referenced_fields.clone(),
+        }
+    }
 }
 
 /// Field metadata
 pub struct Field {
     /// Name of the field
     pub name: Ident,
+    /// Name of the column; the field name unless set with `#[rename = "..."]`
+    pub column: String,
     /// Type of the field
     pub ty: syn::Path,
     /// Data type kind of the field; e.g. `DataTypeKind::Int32` or `DataTypeKind::Custom("tag")`
@@ -58,42 +103,204 @@ pub struct Field {
     /// For custom types: the inner type ident (with Nullable stripped).
     /// Used in codegen for CustomDataType::TYPE_TAG and Encode::decode lookups.
     pub custom_type_ident: Option<syn::Ident>,
-    /// Sanitize struct to use for this field
-    pub sanitize: Option<Sanitizer>,
-    /// Validate struct to use for this field
-    pub validate: Option<Validator>,
-    /// Value type of the field; e.g. `Value::Int32`. `None` for custom types.
+    /// Sanitizers to apply to this field, in order
+    pub sanitize: Vec<Sanitizer>,
+    /// Validators to run on this field, in order
+    pub validate: Vec<Validator>,
+    /// Value type of the field; e.g. `Value::Int32`. `None` for custom, array
+    /// and unit enum types.
     pub value_type: Option<syn::Path>,
+    /// For `ArrayOf<T>` fields: the element type ident (with Nullable stripped).
+    pub array_element: Option<syn::Ident>,
+    /// For `#[unit_enum]` fields: the enum type ident (with Nullable stripped).
+    pub unit_enum: Option<syn::Ident>,
+    /// Whether the field is a Rust `char`, stored as a `Char` column.
+    pub native_char: bool,
     /// Default value literal, if `#[default = ...]` is set on the field.
     ///
     /// The expression is taken verbatim and wrapped in a closure at codegen
     /// time so the resulting [`ColumnDef::default`] is a `fn() -> Value`.
     pub default: Option<syn::Expr>,
+    /// Whether the default was declared as `#[default(<expr>)]`.
+    ///
+    /// Such defaults are evaluated on every insert (e.g. the current time),
+    /// so they are kept out of [`ColumnDef::default`] and the schema snapshot.
+    pub dynamic_default: bool,
     /// Previous names this field was known by, declared via
     /// `#[renamed_from("old1", "old2", ...)]`.
     pub renamed_from: Vec<String>,
+    /// Check constraints declared via `#[check = "<expr>"]`, in order.
+    pub checks: Vec<Check>,
+    /// Whether the field is stored LZ4-compressed (i.e. `#[compress]`).
+    pub compress: bool,
+    /// Whether the field marks rows as deleted (i.e. `#[deleted_at]`).
+    pub deleted_at: bool,
+    /// Event stamping the field with the current time, if
+    /// `#[auto_timestamp(on = "...")]` is set on the field.
+    pub auto_timestamp: Option<AutoTimestamp>,
+}
+
+/// Event of an `#[auto_timestamp(on = "...")]` field.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AutoTimestamp {
+    /// `on = "insert"`
+    Insert,
+    /// `on = "update"`
+    Update,
+}
+
+impl AutoTimestamp {
+    /// Returns the path of the matching `wasm_dbms_api` variant.
+    pub fn api_variant(self) -> TokenStream2 {
+        match self {
+            Self::Insert => quote::quote! { ::wasm_dbms_api::prelude::AutoTimestamp::Insert },
+            Self::Update => quote::quote! { ::wasm_dbms_api::prelude::AutoTimestamp::Update },
+        }
+    }
+}
+
+impl Field {
+    /// Returns the `if let` condition binding `__inner_value` to the inner
+    /// value of the field held by `__col_value`, for built-in, array and unit
+    /// enum types.
+    pub fn bind_inner_value(&self) -> TokenStream2 {
+        if let Some(unit_enum) = &self.unit_enum {
+            return quote::quote! {
+                let Some(__inner_value) = &::wasm_dbms_api::prelude::unit_enum_from_value::<#unit_enum>(__col_value)
+            };
+        }
+        if let Some(elem) = &self.array_element {
+            return quote::quote! {
+                let Some(__inner_value) = &<::wasm_dbms_api::prelude::ArrayOf<::wasm_dbms_api::prelude::#elem>>::from_value(__col_value)
+            };
+        }
+        let value_type = self
+            .value_type
+            .as_ref()
+            .expect("built-in field must have value_type");
+        if self.native_char {
+            return quote::quote! {
+                let #value_type(::wasm_dbms_api::prelude::Char(__inner_value)) = __col_value
+            };
+        }
+        quote::quote! { let #value_type(__inner_value) = __col_value }
+    }
+
+    /// Returns the expression converting `value`, an owned value of the field
+    /// type, into a `Value`.
+    ///
+    /// Unit enums are converted by discriminant; any other type through its
+    /// `Into<Value>` implementation.
+    pub fn to_value(&self, value: TokenStream2) -> TokenStream2 {
+        if let Some(unit_enum) = &self.unit_enum {
+            return quote::quote! {
+                ::wasm_dbms_api::prelude::unit_enum_to_value::<#unit_enum>(&#value)
+            };
+        }
+        quote::quote! { #value.into() }
+    }
+
+    /// Returns the expression wrapping `inner`, the inner value of the field,
+    /// into a `Value`, for built-in, array and unit enum types.
+    pub fn wrap_inner_value(&self, inner: TokenStream2) -> TokenStream2 {
+        if let Some(unit_enum) = &self.unit_enum {
+            return quote::quote! {
+                ::wasm_dbms_api::prelude::unit_enum_to_value::<#unit_enum>(&#inner)
+            };
+        }
+        if self.array_element.is_some() {
+            return quote::quote! { ::wasm_dbms_api::prelude::Value::from(#inner) };
+        }
+        let value_type = self
+            .value_type
+            .as_ref()
+            .expect("built-in field must have value_type");
+        if self.native_char {
+            return quote::quote! { #value_type(::wasm_dbms_api::prelude::Char(#inner)) };
+        }
+        quote::quote! { #value_type(#inner) }
+    }
+}
+
+/// A `#[check = "<expr>"]` constraint on a field.
+#[derive(Clone)]
+pub struct Check {
+    /// Boolean expression evaluated against the record, as `self`.
+    pub expr: syn::Expr,
+    /// Source of the expression, reported when the check fails.
+    pub source: String,
+}
+
+/// A `#[computed = "<expr>"]` field, evaluated from the stored fields
+/// instead of being stored.
+pub struct ComputedField {
+    /// Name of the field, which is also the name of the computed column.
+    pub name: Ident,
+    /// Type of the field.
+    pub ty: syn::Type,
+    /// Expression, with every `self.<field>` replaced by the binding returned
+    /// by [`computed_binding`].
+    pub expr: TokenStream2,
+    /// Stored fields read by the expression, in order of first use.
+    pub depends_on: Vec<Ident>,
+}
+
+impl ComputedField {
+    /// Builds the expression evaluating the field, where `bind` yields the
+    /// owned value of each stored field it reads.
+    pub fn evaluate(&self, bind: impl Fn(&Ident) -> TokenStream2) -> TokenStream2 {
+        let ty = &self.ty;
+        let expr = &self.expr;
+        let bindings = self.depends_on.iter().map(|field| {
+            let binding = computed_binding(field);
+            let value = bind(field);
+            quote::quote! { let #binding = #value; }
+        });
+
+        quote::quote! {
+            {
+                #(#bindings)*
+                <#ty as ::core::convert::From<_>>::from(#expr)
+            }
+        }
+    }
+}
+
+/// Returns the local binding standing for `self.<field>` in a computed
+/// expression.
+pub fn computed_binding(field: &Ident) -> Ident {
+    Ident::new(&format!("__computed_{field}"), Span::call_site())
+}
+
+/// A struct-level `#[check(...)]` constraint, a filter over the row.
+pub struct TableCheck {
+    /// Name reported when the check fails; `{table}_check_{n}` if not given.
+    pub name: String,
+    /// Filter expression (e.g. `ge("end_date", column("start_date"))`).
+    pub expr: syn::Expr,
+    /// Source of the expression, exposed by `TableSchema::checks`.
+    pub source: String,
 }
 
 /// Validator metadata
 #[derive(Clone)]
 pub struct Validator {
-    pub path: syn::Path,
-    pub args: Vec<syn::Expr>,
+    /// Expression building the validator, e.g. `MaxStrlenValidator(34)` or
+    /// `IbanValidator::new("DE")`
+    pub expr: syn::Expr,
 }
 
 /// Map of field identifiers to their validators
-type Validates = HashMap<Ident, Validator>;
+type Validates = HashMap<Ident, Vec<Validator>>;
 
 /// Sanitizer metadata
 #[derive(Clone)]
 pub enum Sanitizer {
     /// A sanitizer for a unit struct
     Unit { name: syn::Path },
-    /// A sanitizer represented by a tuple struct with arguments
-    Tuple {
-        name: syn::Path,
-        args: Vec<syn::Expr>,
-    },
+    /// A sanitizer built by an arbitrary expression, e.g.
+    /// `RoundToScaleSanitizer(2)` or `MySanitizer::new("x")`
+    Expr { expr: syn::Expr },
     /// A sanitizer represented by a struct with named arguments
     NamedArgs {
         name: syn::Path,
@@ -102,7 +309,7 @@ pub enum Sanitizer {
 }
 
 /// Map of field identifiers to their sanitizers
-type Sanitizers = HashMap<Ident, Sanitizer>;
+type Sanitizers = HashMap<Ident, Vec<Sanitizer>>;
 
 /// Represents a resolved index definition, built from `#[index]` field attributes.
 ///
@@ -114,6 +321,15 @@ pub struct Index {
     pub columns: Vec<Ident>,
 }
 
+/// Represents a composite unique constraint, built from fields sharing the same
+/// `#[unique(group = "name")]` attribute.
+pub struct UniqueGroup {
+    /// Name of the group.
+    pub name: String,
+    /// Column names that make up the constraint, in field declaration order.
+    pub columns: Vec<Ident>,
+}
+
 /// Raw per-field index annotation: either standalone or grouped.
 enum FieldIndex {
     /// Bare `#[index]` -- standalone single-column index.
@@ -126,12 +342,15 @@ enum FieldIndex {
 pub struct TableMetadata {
     /// Name of the table
     pub name: Ident,
-    /// Name of the primary key field
-    pub primary_key: Ident,
+    /// Names of the primary key fields, in declaration order; more than one
+    /// for a composite primary key
+    pub primary_keys: Vec<Ident>,
     /// List of foreign keys
     pub foreign_keys: Vec<ForeignKey>,
     /// List of indexes
     pub indexes: Vec<Index>,
+    /// List of composite unique constraints
+    pub unique_groups: Vec<UniqueGroup>,
     /// Name of the record type
     pub record: Ident,
     /// Name of the insert type
@@ -150,9 +369,37 @@ pub struct TableMetadata {
     /// Set when the struct carries `#[migrate]`, suppressing the default
     /// `impl Migrate for T {}` emission so the user can provide their own.
     pub user_migrate_impl: bool,
+    /// Column of the `#[deleted_at]` field; set only for `#[soft_delete]` tables.
+    pub soft_delete_column: Option<String>,
+    /// Set when the struct carries `#[on_conflict = "update"]`, turning
+    /// inserts of an existing primary key into updates.
+    pub on_conflict_update: bool,
+    /// Struct-level `#[cross_validate]` invariants, in declaration order.
+    pub cross_validations: Vec<Check>,
+    /// Struct-level `#[check(...)]` constraints, in declaration order.
+    pub table_checks: Vec<TableCheck>,
+    /// `#[computed]` fields, which are not part of `fields`.
+    pub computed: Vec<ComputedField>,
 }
 
 impl TableMetadata {
+    /// Returns the name of the column backing the field named `field`.
+    pub fn column_name(&self, field: &Ident) -> String {
+        self.fields
+            .iter()
+            .find(|f| f.name == *field)
+            .map(|f| f.column.clone())
+            .unwrap_or_else(|| field.to_string())
+    }
+
+    /// Returns the fields exposed by the insert and update requests, i.e. all
+    /// fields but the `#[auto_timestamp]` ones stamped by the engine.
+    pub fn request_fields(&self) -> impl Iterator<Item = &Field> {
+        self.fields
+            .iter()
+            .filter(|field| field.auto_timestamp.is_none())
+    }
+
     /// Get the identifier for the foreign fetcher, or default to `NoForeignFetcher` if none is set
     pub fn foreign_fetcher_ident(&self) -> TokenStream2 {
         match self.foreign_fetcher.as_ref() {
@@ -167,7 +414,7 @@ impl TableMetadata {
 /// # Panics
 ///
 /// - If the struct does not have a `table` attribute
-/// - If the struct does not have a field marked as primary key or if multiple primary keys are found
+/// - If the struct does not have a field marked as primary key
 pub fn collect_table_metadata(
     struct_name: &Ident,
     data: &DataStruct,
@@ -175,12 +422,14 @@ pub fn collect_table_metadata(
 ) -> syn::Result<TableMetadata> {
     let alignment = get_alignment(attrs)?;
     let table_name = get_table_name(attrs)?;
-    let primary_key = get_primary_key_field(data)?;
+    let primary_keys = get_primary_key_fields(data)?;
     let unique_fields = get_unique_fields(data);
-    let indexes = collect_indexes(data, &primary_key, &unique_fields)?;
+    let indexes = collect_indexes(data, &primary_keys, &unique_fields)?;
+    let unique_groups = collect_unique_groups(data)?;
     let foreign_keys = collect_foreign_keys(data)?;
-    let validates = collect_validates(data)?;
-    let sanitizes = collect_sanitizes(data)?;
+    let mut validates = collect_validates(data)?;
+    let mut sanitizes = collect_sanitizes(data)?;
+    collect_decimals(data, &mut sanitizes, &mut validates)?;
     let record_ident = Ident::new(&format!("{struct_name}Record"), struct_name.span());
     let insert_ident = Ident::new(&format!("{struct_name}InsertRequest"), struct_name.span());
     let update_ident = Ident::new(&format!("{struct_name}UpdateRequest"), struct_name.span());
@@ -192,15 +441,22 @@ pub fn collect_table_metadata(
     } else {
         None
     };
-    let fields = get_fields(data, &primary_key, &foreign_keys, &sanitizes, &validates)?;
+    let fields = get_fields(data, &primary_keys, &foreign_keys, &sanitizes, &validates)?;
+    validate_foreign_keys(&foreign_keys, &fields)?;
+    let computed = collect_computed_fields(data, &fields)?;
     let candid = attrs.iter().any(|a| a.path().is_ident("candid"));
     let user_migrate_impl = attrs.iter().any(|a| a.path().is_ident(ATTRIBUTE_MIGRATE));
+    let soft_delete_column = get_soft_delete_column(attrs, &fields)?;
+    let on_conflict_update = get_on_conflict_update(attrs)?;
+    let cross_validations = parse_cross_validations(attrs)?;
+    let table_checks = parse_table_checks(&table_name, attrs)?;
 
     Ok(TableMetadata {
         name: table_name,
-        primary_key,
+        primary_keys,
         foreign_keys,
         indexes,
+        unique_groups,
         record: record_ident,
         insert: insert_ident,
         update: update_ident,
@@ -209,9 +465,67 @@ pub fn collect_table_metadata(
         alignment,
         candid,
         user_migrate_impl,
+        soft_delete_column,
+        on_conflict_update,
+        cross_validations,
+        table_checks,
+        computed,
     })
 }
 
+/// Resolve the struct-level `#[on_conflict = "update"]` attribute; `"update"`
+/// is the only supported action.
+fn get_on_conflict_update(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let Some(attr) = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ATTRIBUTE_ON_CONFLICT))
+    else {
+        return Ok(false);
+    };
+    let value = &attr.meta.require_name_value()?.value;
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) if lit.value() == "update" => Ok(true),
+        _ => Err(syn::Error::new_spanned(
+            value,
+            "expected `#[on_conflict = \"update\"]`",
+        )),
+    }
+}
+
+/// Resolve the column of the `#[deleted_at]` field of a `#[soft_delete]` table.
+///
+/// A `#[soft_delete]` table needs exactly one `#[deleted_at]` field, and
+/// `#[deleted_at]` is only allowed on `#[soft_delete]` tables.
+fn get_soft_delete_column(
+    attrs: &[syn::Attribute],
+    fields: &[Field],
+) -> syn::Result<Option<String>> {
+    let soft_delete = attrs
+        .iter()
+        .find(|attr| attr.path().is_ident(ATTRIBUTE_SOFT_DELETE));
+    let mut deleted_at = fields.iter().filter(|field| field.deleted_at);
+
+    match (soft_delete, deleted_at.next(), deleted_at.next()) {
+        (Some(_), Some(field), None) => Ok(Some(field.column.clone())),
+        (Some(attr), None, _) => Err(syn::Error::new_spanned(
+            attr,
+            "`#[soft_delete]` requires a `#[deleted_at]` field",
+        )),
+        (_, Some(_), Some(field)) => Err(syn::Error::new_spanned(
+            &field.name,
+            "only one field can be marked as `#[deleted_at]`",
+        )),
+        (None, Some(field), None) => Err(syn::Error::new_spanned(
+            &field.name,
+            "`#[deleted_at]` requires `#[soft_delete]` on the struct",
+        )),
+        (None, None, _) => Ok(None),
+    }
+}
+
 /// Extract the alignment from the `alignment` attribute
 fn get_alignment(attrs: &[syn::Attribute]) -> syn::Result<Option<u16>> {
     for attr in attrs {
@@ -286,16 +600,16 @@ fn get_table_name(attrs: &[syn::Attribute]) -> syn::Result<Ident> {
 /// with columns ordered by field declaration order.
 fn collect_indexes(
     data: &DataStruct,
-    primary_key: &Ident,
+    primary_keys: &[Ident],
     unique: &[Ident],
 ) -> syn::Result<Vec<Index>> {
-    // PK is always an index.
+    // PK is always an index, spanning every column of a composite key.
     let mut indexes = vec![Index {
-        columns: vec![primary_key.clone()],
+        columns: primary_keys.to_vec(),
     }];
     // Unique fields also always have an index, but we skip them if they are the primary key since it's redundant.
     for unique in unique {
-        if unique != primary_key {
+        if primary_keys != std::slice::from_ref(unique) {
             indexes.push(Index {
                 columns: vec![(*unique).clone()],
             });
@@ -314,7 +628,9 @@ fn collect_indexes(
 
                 // Skip redundant `#[index]` on the primary key — it already has an implicit index.
                 // skip also redundant `#[index]` on unique fields since they also have implicit indexes.
-                if &field_name == primary_key && !unique.contains(&field_name) {
+                if primary_keys == std::slice::from_ref(&field_name)
+                    && !unique.contains(&field_name)
+                {
                     continue;
                 }
 
@@ -375,20 +691,18 @@ fn parse_index_attr(attr: &syn::Attribute) -> syn::Result<FieldIndex> {
     }
 }
 
-/// Find the primary key field in the struct
-fn get_primary_key_field(data: &DataStruct) -> syn::Result<Ident> {
-    let mut primary_key = None;
+/// Find the primary key fields in the struct, in declaration order.
+///
+/// More than one `#[primary_key]` field declares a composite primary key.
+fn get_primary_key_fields(data: &DataStruct) -> syn::Result<Vec<Ident>> {
+    let mut primary_keys = Vec::new();
 
     for field in &data.fields {
         for attr in &field.attrs {
             if attr.path().is_ident(ATTRIBUTE_PRIMARY_KEY) {
-                if primary_key.is_some() {
-                    return Err(syn::Error::new_spanned(
-                        field,
-                        "multiple primary keys found",
-                    ));
-                }
-                primary_key = Some(field.ident.clone().ok_or(syn::Error::new(
+                // validate the attribute arguments, if any
+                primary_key_auto_increment(attr)?;
+                primary_keys.push(field.ident.clone().ok_or(syn::Error::new(
                     proc_macro2::Span::call_site(),
                     "primary_key should be a named field",
                 ))?);
@@ -396,32 +710,78 @@ fn get_primary_key_field(data: &DataStruct) -> syn::Result<Ident> {
         }
     }
 
-    if let Some(pk) = primary_key {
-        Ok(pk)
-    } else {
+    if primary_keys.is_empty() {
         Err(syn::Error::new(
             proc_macro2::Span::call_site(),
             "no primary key found",
         ))
+    } else {
+        Ok(primary_keys)
     }
 }
 
+/// Collect fields marked with a bare `#[unique]` attribute.
+///
+/// Grouped `#[unique(group = "...")]` fields are handled by [`collect_unique_groups`].
 fn get_unique_fields(data: &DataStruct) -> Vec<Ident> {
     let mut unique_fields = Vec::new();
 
+    for field in &data.fields {
+        if unique(field) {
+            let field_name = field
+                .ident
+                .clone()
+                .expect("unique can only be used on named fields");
+            unique_fields.push(field_name);
+        }
+    }
+
+    unique_fields
+}
+
+/// Collect composite unique constraints from `#[unique(group = "...")]` attributes.
+///
+/// Fields sharing the same `group` name are merged into one constraint, with columns
+/// ordered by field declaration order. Groups are sorted by name for determinism.
+fn collect_unique_groups(data: &DataStruct) -> syn::Result<Vec<UniqueGroup>> {
+    let mut grouped: HashMap<String, Vec<Ident>> = HashMap::new();
+
     for field in &data.fields {
         for attr in &field.attrs {
-            if attr.path().is_ident(ATTRIBUTE_UNIQUE) {
-                let field_name = field
-                    .ident
-                    .clone()
-                    .expect("unique can only be used on named fields");
-                unique_fields.push(field_name);
+            if !attr.path().is_ident(ATTRIBUTE_UNIQUE) || matches!(attr.meta, syn::Meta::Path(_)) {
+                continue;
             }
+            let field_name = field.ident.clone().ok_or_else(|| {
+                syn::Error::new_spanned(field, "`#[unique]` can only be used on named fields")
+            })?;
+
+            let mut group: Option<String> = None;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("group") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    group = Some(lit.value());
+                    return Ok(());
+                }
+                Err(syn::Error::new_spanned(
+                    &meta.path,
+                    "unknown unique attribute; expected `group`",
+                ))
+            })?;
+            let group = group.ok_or_else(|| {
+                syn::Error::new_spanned(attr, "`#[unique(...)]` requires `group = \"name\"`")
+            })?;
+
+            grouped.entry(group).or_default().push(field_name);
         }
     }
 
-    unique_fields
+    let mut groups: Vec<UniqueGroup> = grouped
+        .into_iter()
+        .map(|(name, columns)| UniqueGroup { name, columns })
+        .collect();
+    groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(groups)
 }
 
 /// Collect foreign keys from the struct fields
@@ -433,6 +793,10 @@ fn collect_foreign_keys(data: &DataStruct) -> syn::Result<Vec<ForeignKey>> {
                 let mut referenced_entity = None;
                 let mut referenced_table = None;
                 let mut referenced_field = None;
+                let mut referenced_fields = None;
+                let mut local_columns = None;
+                let mut on_delete = None;
+                let mut deferred = false;
 
                 attr.parse_nested_meta(|meta| {
                     if meta.path.is_ident(ATTRIBUTE_FOREIGN_KEY_ENTITY) {
@@ -450,6 +814,37 @@ fn collect_foreign_keys(data: &DataStruct) -> syn::Result<Vec<ForeignKey>> {
                         referenced_field = Some(Ident::new(&lit.value(), lit.span()));
                         return Ok(());
                     }
+                    if meta.path.is_ident(ATTRIBUTE_FOREIGN_KEY_COLUMNS) {
+                        let lit: syn::LitStr = meta.value()?.parse()?;
+                        referenced_fields = Some((parse_column_list(&lit)?, lit));
+                        return Ok(());
+                    }
+                    if meta.path.is_ident(ATTRIBUTE_FOREIGN_KEY_LOCAL_COLUMNS) {
+                        let lit: syn::LitStr = meta.value()?.parse()?;
+                        local_columns = Some(parse_column_list(&lit)?);
+                        return Ok(());
+                    }
+                    if meta.path.is_ident(ATTRIBUTE_FOREIGN_KEY_ON_DELETE) {
+                        let lit: syn::LitStr = meta.value()?.parse()?;
+                        if !matches!(lit.value().as_str(), "Restrict" | "Cascade" | "SetNull") {
+                            return Err(syn::Error::new_spanned(
+                                &lit,
+                                "`on_delete` must be one of \"Restrict\", \"Cascade\" or \"SetNull\"",
+                            ));
+                        }
+                        if lit.value() == "SetNull" && !nullable(field) {
+                            return Err(syn::Error::new_spanned(
+                                &lit,
+                                "`on_delete = \"SetNull\"` requires a `Nullable<T>` field",
+                            ));
+                        }
+                        on_delete = Some(Ident::new(&lit.value(), lit.span()));
+                        return Ok(());
+                    }
+                    if meta.path.is_ident(ATTRIBUTE_FOREIGN_KEY_DEFERRED) {
+                        deferred = true;
+                        return Ok(());
+                    }
                     Ok(())
                 })?;
 
@@ -458,6 +853,25 @@ fn collect_foreign_keys(data: &DataStruct) -> syn::Result<Vec<ForeignKey>> {
                     "missing `entity` in foreign_key attribute",
                 ))?;
                 let record = Ident::new(&format!("{}Record", entity), entity.span());
+                let (referenced_field, referenced_fields) = match (
+                    referenced_field,
+                    referenced_fields,
+                ) {
+                    (Some(_), Some((_, lit))) => {
+                        return Err(syn::Error::new_spanned(
+                            lit,
+                            "`column` and `columns` cannot be combined in foreign_key attribute",
+                        ));
+                    }
+                    (Some(field), None) => (field.clone(), vec![field.to_string()]),
+                    (None, Some((fields, lit))) => (Ident::new(&fields[0], lit.span()), fields),
+                    (None, None) => {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "missing `column` in foreign_key attribute",
+                        ));
+                    }
+                };
 
                 let fk = ForeignKey {
                     entity,
@@ -469,11 +883,12 @@ fn collect_foreign_keys(data: &DataStruct) -> syn::Result<Vec<ForeignKey>> {
                         attr,
                         "missing `table` in foreign_key attribute",
                     ))?,
-                    referenced_field: referenced_field.ok_or(syn::Error::new_spanned(
-                        attr,
-                        "missing `column` in foreign_key attribute",
-                    ))?,
+                    referenced_field,
+                    referenced_fields,
+                    local_columns,
                     record_type: record,
+                    on_delete,
+                    deferred,
                 };
 
                 foreign_keys.push(fk);
@@ -484,43 +899,91 @@ fn collect_foreign_keys(data: &DataStruct) -> syn::Result<Vec<ForeignKey>> {
     Ok(foreign_keys)
 }
 
+/// Parses a comma-separated list of column names, such as the `columns` of a
+/// composite foreign key.
+fn parse_column_list(lit: &syn::LitStr) -> syn::Result<Vec<String>> {
+    let columns: Vec<String> = lit
+        .value()
+        .split(',')
+        .map(|column| column.trim().to_string())
+        .collect();
+    if columns.iter().any(String::is_empty) {
+        return Err(syn::Error::new_spanned(
+            lit,
+            "expected a comma-separated list of column names",
+        ));
+    }
+
+    Ok(columns)
+}
+
+/// Checks the local columns of each foreign key: as many as the referenced
+/// columns, stored columns of the table, starting with the annotated field.
+fn validate_foreign_keys(foreign_keys: &[ForeignKey], fields: &[Field]) -> syn::Result<()> {
+    for fk in foreign_keys {
+        let Some(field) = fields.iter().find(|field| field.name == fk.field) else {
+            continue;
+        };
+        let local_columns = fk.local_columns(&field.column);
+        if local_columns.len() != fk.referenced_fields.len() {
+            return Err(syn::Error::new_spanned(
+                &fk.field,
+                format!(
+                    "foreign key has {} local columns but references {} columns",
+                    local_columns.len(),
+                    fk.referenced_fields.len()
+                ),
+            ));
+        }
+        if local_columns[0] != field.column {
+            return Err(syn::Error::new_spanned(
+                &fk.field,
+                format!(
+                    "the first local column of the foreign key must be `{}`, the annotated field",
+                    field.column
+                ),
+            ));
+        }
+        if let Some(column) = local_columns
+            .iter()
+            .find(|column| !fields.iter().any(|field| field.column == **column))
+        {
+            return Err(syn::Error::new_spanned(
+                &fk.field,
+                format!("foreign key local column `{column}` is not a column of the table"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the `#[validate(...)]` attributes of each field.
+///
+/// Each attribute holds a comma-separated list of validators, run in order.
+/// A validator is any expression evaluating to a `Validate` implementation,
+/// such as a path (`EmailValidator`), a tuple struct (`MaxStrlenValidator(34)`)
+/// or a constructor call (`IbanValidator::new("DE")`). Repeated attributes
+/// append to the list.
 fn collect_validates(data: &DataStruct) -> syn::Result<Validates> {
-    let mut validates = HashMap::new();
+    let mut validates: Validates = HashMap::new();
 
     for field in &data.fields {
         for attr in &field.attrs {
             if attr.path().is_ident("validate") {
-                let validator = match attr.parse_args::<syn::Expr>()? {
-                    syn::Expr::Path(expr) => Validator {
-                        path: expr.path,
-                        args: Vec::new(),
-                    },
-                    syn::Expr::Call(call) => {
-                        let path = match *call.func {
-                            syn::Expr::Path(p) => p.path,
-                            other => {
-                                return Err(syn::Error::new_spanned(
-                                    other,
-                                    "validator must be a path or a call, e.g. Validator or Validator(42)",
-                                ));
-                            }
-                        };
-
-                        Validator {
-                            path,
-                            args: call.args.into_iter().collect(),
-                        }
-                    }
-                    other => {
-                        return Err(syn::Error::new_spanned(other, "invalid validator syntax"));
-                    }
-                };
-
                 let ident = field.ident.clone().ok_or_else(|| {
                     syn::Error::new_spanned(field, "validate can only be used on named fields")
                 })?;
 
-                validates.insert(ident, validator);
+                let validators = validates.entry(ident).or_default();
+                for expr in
+                    attr.parse_args_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)?
+                {
+                    if let syn::Expr::Assign(_) = expr {
+                        return Err(syn::Error::new_spanned(expr, "invalid validator syntax"));
+                    }
+                    validators.push(Validator { expr });
+                }
             }
         }
     }
@@ -528,108 +991,173 @@ fn collect_validates(data: &DataStruct) -> syn::Result<Validates> {
     Ok(validates)
 }
 
+/// Collects the `#[sanitizer(...)]` attributes of each field.
+///
+/// Each attribute holds a comma-separated list of sanitizers, applied in
+/// order. A sanitizer is a path (`TrimSanitizer`), any expression building
+/// one (`RoundToScaleSanitizer(2)`, `MySanitizer::new("x")`) or a path
+/// followed by named arguments (`ClampSanitizer, min = 0, max = 100`). Repeated attributes append to the
+/// list.
 fn collect_sanitizes(data: &DataStruct) -> syn::Result<Sanitizers> {
-    let mut sanitizers = HashMap::new();
+    let mut sanitizes: Sanitizers = HashMap::new();
 
     for field in &data.fields {
         for attr in &field.attrs {
             if attr.path().is_ident("sanitizer") {
-                let sanitizer = if let Some(sanitizer) = parse_sanitizer_meta(attr)? {
-                    sanitizer
-                } else {
-                    parse_sanitizer_expr(attr)?
-                };
-
                 let ident = field.ident.clone().ok_or_else(|| {
-                    syn::Error::new_spanned(field, "validate can only be used on named fields")
+                    syn::Error::new_spanned(field, "sanitizer can only be used on named fields")
                 })?;
 
-                sanitizers.insert(ident, sanitizer);
+                let sanitizers = sanitizes.entry(ident).or_default();
+                for expr in
+                    attr.parse_args_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)?
+                {
+                    parse_sanitizer_expr(expr, sanitizers)?;
+                }
             }
         }
     }
 
-    Ok(sanitizers)
+    Ok(sanitizes)
 }
 
-fn parse_sanitizer_meta(attr: &syn::Attribute) -> syn::Result<Option<Sanitizer>> {
-    let syn::Meta::List(meta_list) = &attr.meta else {
-        return Ok(None);
-    };
-
-    let mut path: Option<syn::Path> = None;
-    let mut args = HashMap::new();
+/// Collects the `#[decimal(precision = P, scale = S)]` attributes of each field.
+///
+/// The attribute appends a `DecimalPrecisionValidator(P, S)` to the validators
+/// of the field. With the `round` flag, a `RoundToScaleSanitizer(S)` is also
+/// appended to its sanitizers, so values with too many fractional digits are
+/// rounded instead of rejected.
+fn collect_decimals(
+    data: &DataStruct,
+    sanitizes: &mut Sanitizers,
+    validates: &mut Validates,
+) -> syn::Result<()> {
+    for field in &data.fields {
+        for attr in &field.attrs {
+            if !attr.path().is_ident(ATTRIBUTE_DECIMAL) {
+                continue;
+            }
+            let ident = field.ident.clone().ok_or_else(|| {
+                syn::Error::new_spanned(field, "decimal can only be used on named fields")
+            })?;
 
-    meta_list.parse_nested_meta(|meta| {
-        // FIRST: sanitizer path (must NOT be name = value)
-        if path.is_none() {
-            if meta.input.peek(syn::Token![=]) {
+            let mut precision: Option<syn::LitInt> = None;
+            let mut scale: Option<syn::LitInt> = None;
+            let mut round = false;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("precision") {
+                    precision = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("scale") {
+                    scale = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("round") {
+                    round = true;
+                    return Ok(());
+                }
+                Err(syn::Error::new_spanned(
+                    &meta.path,
+                    "unknown decimal attribute; expected `precision`, `scale` or `round`",
+                ))
+            })?;
+            let (Some(precision), Some(scale)) = (precision, scale) else {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`#[decimal(...)]` requires `precision = N` and `scale = N`",
+                ));
+            };
+            let precision_value: u32 = precision.base10_parse()?;
+            let scale_value: u32 = scale.base10_parse()?;
+            if precision_value == 0 || precision_value > MAX_DECIMAL_PRECISION {
+                return Err(syn::Error::new_spanned(
+                    &precision,
+                    format!("decimal precision must be between 1 and {MAX_DECIMAL_PRECISION}"),
+                ));
+            }
+            if scale_value > precision_value {
                 return Err(syn::Error::new_spanned(
-                    meta.path,
-                    "first sanitizer argument must be a path",
+                    &scale,
+                    "decimal scale must not exceed the precision",
                 ));
             }
 
-            path = Some(meta.path.clone());
-            return Ok(());
+            let precision: syn::Expr = syn::parse_quote!(#precision_value);
+            let scale: syn::Expr = syn::parse_quote!(#scale_value);
+            if round {
+                sanitizes
+                    .entry(ident.clone())
+                    .or_default()
+                    .push(Sanitizer::Expr {
+                        expr: syn::parse_quote!(
+                            ::wasm_dbms_api::prelude::RoundToScaleSanitizer(#scale)
+                        ),
+                    });
+            }
+            validates.entry(ident).or_default().push(Validator {
+                expr: syn::parse_quote!(
+                    ::wasm_dbms_api::prelude::DecimalPrecisionValidator(#precision, #scale)
+                ),
+            });
         }
-
-        // named args: min = 0
-        let ident = meta
-            .path
-            .get_ident()
-            .ok_or_else(|| syn::Error::new_spanned(&meta.path, "expected identifier"))?;
-
-        let value = meta.value()?.parse::<syn::Expr>()?;
-        args.insert(ident.clone(), value);
-
-        Ok(())
-    })?;
-
-    if let Some(path) = path
-        && !args.is_empty()
-    {
-        Ok(Some(Sanitizer::NamedArgs { name: path, args }))
-    } else {
-        Ok(None)
     }
+
+    Ok(())
 }
 
-fn parse_sanitizer_expr(attr: &syn::Attribute) -> syn::Result<Sanitizer> {
-    match attr.parse_args::<syn::Expr>()? {
-        syn::Expr::Path(expr) => Ok(Sanitizer::Unit { name: expr.path }),
+/// Parses one item of a `#[sanitizer(...)]` list, pushing a new sanitizer or
+/// attaching a named argument to the previous one.
+fn parse_sanitizer_expr(expr: syn::Expr, sanitizers: &mut Vec<Sanitizer>) -> syn::Result<()> {
+    match expr {
+        syn::Expr::Path(expr) => sanitizers.push(Sanitizer::Unit { name: expr.path }),
+
+        // named args: min = 0
+        syn::Expr::Assign(assign) => {
+            let ident = match &*assign.left {
+                syn::Expr::Path(p) => p.path.get_ident().cloned(),
+                _ => None,
+            }
+            .ok_or_else(|| syn::Error::new_spanned(&assign.left, "expected identifier"))?;
 
-        syn::Expr::Call(call) => {
-            let path = match *call.func {
-                syn::Expr::Path(p) => p.path,
-                other => {
+            let previous = sanitizers.pop().ok_or_else(|| {
+                syn::Error::new_spanned(&assign, "first sanitizer argument must be a path")
+            })?;
+            let sanitizer = match previous {
+                Sanitizer::Unit { name } => Sanitizer::NamedArgs {
+                    name,
+                    args: HashMap::from([(ident, *assign.right)]),
+                },
+                Sanitizer::NamedArgs { name, mut args } => {
+                    args.insert(ident, *assign.right);
+                    Sanitizer::NamedArgs { name, args }
+                }
+                Sanitizer::Expr { .. } => {
                     return Err(syn::Error::new_spanned(
-                        other,
-                        "sanitizer must be a path or a call",
+                        assign,
+                        "named arguments must follow a sanitizer path",
                     ));
                 }
             };
-
-            Ok(Sanitizer::Tuple {
-                name: path,
-                args: call.args.into_iter().collect(),
-            })
+            sanitizers.push(sanitizer);
         }
 
-        other => Err(syn::Error::new_spanned(other, "invalid sanitizer syntax")),
+        expr => sanitizers.push(Sanitizer::Expr { expr }),
     }
+
+    Ok(())
 }
 
 fn get_fields(
     data: &DataStruct,
-    primary_key: &Ident,
+    primary_keys: &[Ident],
     foreign_keys: &[ForeignKey],
     sanitizes: &Sanitizers,
     validates: &Validates,
 ) -> syn::Result<Vec<Field>> {
-    let mut fields = vec![];
+    let mut fields: Vec<Field> = vec![];
 
-    for field in &data.fields {
+    for field in data.fields.iter().filter(|field| !computed(field)) {
         let name = field
             .ident
             .as_ref()
@@ -637,12 +1165,12 @@ fn get_fields(
             .ok_or(syn::Error::new_spanned(field, "All fields must be named"))?;
         let field_type = &field.ty;
         let field_type_name = field_type.to_token_stream();
-        let primary_key = &name == primary_key;
+        let primary_key = primary_keys.contains(&name);
 
         let is_fk = foreign_keys.iter().any(|fk| fk.field == name);
 
-        let sanitize = sanitizes.get(&name).cloned();
-        let validate = validates.get(&name).cloned();
+        let sanitize = sanitizes.get(&name).cloned().unwrap_or_default();
+        let validate = validates.get(&name).cloned().unwrap_or_default();
 
         let nullable = nullable(field);
         // if is nullable the data type is the inner type
@@ -666,6 +1194,10 @@ fn get_fields(
 
         // Step 2: detect field attributes
         let custom_type = is_custom_type(field);
+        let is_unit_enum = field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident(ATTRIBUTE_UNIT_ENUM));
         let unique = unique(field);
         let autoincrement = autoincrement(field)?;
 
@@ -676,6 +1208,13 @@ fn get_fields(
                 "`#[custom_type]` and `#[foreign_key]` cannot be used on the same field",
             ));
         }
+        // Validate: unit enums are stored as a plain `Uint8`, not as a custom type
+        if is_unit_enum && (custom_type || is_fk) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`#[unit_enum]` cannot be combined with `#[custom_type]` or `#[foreign_key]`",
+            ));
+        }
         // Validate: #[autoincrement] cannot be combined with #[nullable], since autoincrement fields must have a value and cannot be null
         if autoincrement && nullable {
             return Err(syn::Error::new_spanned(
@@ -684,24 +1223,94 @@ fn get_fields(
             ));
         }
 
+        // Bare floats have no `DataTypeKind` variant of their own; point the
+        // user at the wrapper types instead of failing on `DataTypeKind::f32`.
+        if let Some(wrapper) = match field_type_name_str.as_str() {
+            "f32" => Some("Float32"),
+            "f64" => Some("Float64"),
+            _ => None,
+        } {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!("use `{wrapper}` instead of `{field_type_name_str}` for float columns"),
+            ));
+        }
+
+        // Rust `char` fields are stored as `Char` columns; `Nullable` requires
+        // a data type, so nullable ones must be declared as `Nullable<Char>`.
+        let native_char = field_type_name_str == "char";
+        if native_char && (nullable || custom_type || is_unit_enum) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`char` fields cannot be nullable or custom types; use `Char` instead",
+            ));
+        }
+        let field_type_name_str = if native_char {
+            "Char".to_string()
+        } else {
+            field_type_name_str
+        };
+
+        // `ArrayOf<T>` fields store a list of primitive `T` values
+        let array_element = match field_type_name_str
+            .strip_prefix("ArrayOf <")
+            .and_then(|s| s.strip_suffix('>'))
+        {
+            Some(elem) if custom_type || is_unit_enum => {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!(
+                        "`#[custom_type]` and `#[unit_enum]` cannot be used on `ArrayOf<{}>` fields",
+                        elem.trim()
+                    ),
+                ));
+            }
+            Some(elem) => Some(syn::parse_str::<syn::Ident>(elem.trim()).map_err(|_| {
+                syn::Error::new_spanned(
+                    field,
+                    "`ArrayOf` elements must be built-in primitive data types",
+                )
+            })?),
+            None => None,
+        };
+
+        let unit_enum =
+            is_unit_enum.then(|| syn::Ident::new(&field_type_name_str, Span::call_site()));
+
         // Step 3: build data_type_kind and value_type
-        let field_type_ident = syn::Ident::new(&field_type_name_str, Span::call_site());
         let (data_type_kind, value_type, custom_type_ident): (
             syn::Expr,
             Option<syn::Path>,
             Option<syn::Ident>,
-        ) = if custom_type {
+        ) = if let Some(elem) = &array_element {
+            let dtk: syn::Expr = syn::parse_quote! {
+                ::wasm_dbms_api::prelude::DataTypeKind::Array(
+                    &::wasm_dbms_api::prelude::DataTypeKind::#elem
+                )
+            };
+            (dtk, None, None)
+        } else if unit_enum.is_some() {
+            let dtk: syn::Expr = syn::parse_quote! {
+                ::wasm_dbms_api::prelude::DataTypeKind::Uint8
+            };
+            (dtk, None, None)
+        } else if custom_type {
+            let field_type_ident = syn::Ident::new(&field_type_name_str, Span::call_site());
             let custom_ident = field_type_ident.clone();
             let dtk: syn::Expr = syn::parse_quote! {
-                ::wasm_dbms_api::prelude::DataTypeKind::Custom {
-                    tag: <#custom_ident as ::wasm_dbms_api::prelude::CustomDataType>::TYPE_TAG,
-                    wire_size: ::wasm_dbms_api::prelude::WireSize::from_data_size(
-                        <#custom_ident as ::wasm_dbms_api::prelude::Encode>::SIZE,
-                    ),
+                match <#custom_ident as ::wasm_dbms_api::prelude::CustomDataType>::VARIANTS {
+                    Some(variants) => ::wasm_dbms_api::prelude::DataTypeKind::Enum(variants),
+                    None => ::wasm_dbms_api::prelude::DataTypeKind::Custom {
+                        tag: <#custom_ident as ::wasm_dbms_api::prelude::CustomDataType>::TYPE_TAG,
+                        wire_size: ::wasm_dbms_api::prelude::WireSize::from_data_size(
+                            <#custom_ident as ::wasm_dbms_api::prelude::Encode>::SIZE,
+                        ),
+                    },
                 }
             };
             (dtk, None, Some(custom_ident))
         } else {
+            let field_type_ident = syn::Ident::new(&field_type_name_str, Span::call_site());
             let dtk: syn::Path = syn::parse_quote! {
                 ::wasm_dbms_api::prelude::DataTypeKind::#field_type_ident
             };
@@ -719,11 +1328,73 @@ fn get_fields(
             )
         };
 
-        let default = parse_default(field)?;
+        let (default, dynamic_default) = match parse_default(field)? {
+            Some((expr, dynamic)) => (Some(expr), dynamic),
+            None => (None, false),
+        };
+        let column = parse_rename(field)?.unwrap_or_else(|| name.to_string());
+        if let Some(other) = fields.iter().find(|f| f.column == column) {
+            return Err(syn::Error::new_spanned(
+                field,
+                format!(
+                    "column `{column}` is already used by field `{}`",
+                    other.name
+                ),
+            ));
+        }
         let renamed_from = parse_renamed_from(field)?;
+        let checks = parse_checks(field)?;
+        let compress = compress(field);
+        let deleted_at = field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident(ATTRIBUTE_DELETED_AT));
+
+        // Validate: the deletion time is a timestamp, null while the row is live
+        if deleted_at && (custom_type || !nullable || field_type_name_str != "Timestamp") {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`#[deleted_at]` can only be used on `Nullable<Timestamp>` fields",
+            ));
+        }
+
+        let auto_timestamp = parse_auto_timestamp(field)?;
+
+        // Validate: auto timestamps are stamped by the engine, never provided
+        if auto_timestamp.is_some() {
+            if custom_type || nullable || field_type_name_str != "DateTime" {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`#[auto_timestamp]` can only be used on `DateTime` fields",
+                ));
+            }
+            if primary_key || default.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "`#[auto_timestamp]` fields cannot be primary keys or have a `#[default]`",
+                ));
+            }
+        }
+
+        // Validate: only non-nullable `Text` and `Blob` fields can be compressed
+        if compress && (nullable || !matches!(field_type_name_str.as_str(), "Text" | "Blob")) {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`#[compress]` can only be used on `Text` and `Blob` fields",
+            ));
+        }
+
+        // Validate: autoincrement fields already generate their own value
+        if autoincrement && default.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`#[autoincrement]` fields cannot have a `#[default]`",
+            ));
+        }
 
         fields.push(Field {
             name,
+            column,
             is_fk,
             ty,
             data_type_kind,
@@ -736,41 +1407,144 @@ fn get_fields(
             sanitize,
             validate,
             value_type,
+            array_element,
+            unit_enum,
+            native_char,
             default,
+            dynamic_default,
             renamed_from,
+            checks,
+            compress,
+            deleted_at,
+            auto_timestamp,
         });
     }
 
     Ok(fields)
 }
 
-/// Parses the optional `#[default = <expr>]` attribute on a field.
+/// Parses the optional `#[default = <lit>]` or `#[default(<expr>)]` attribute
+/// on a field, returning the expression and whether it is dynamic (list form).
 ///
-/// The expression is taken verbatim and used at codegen time to build a
-/// `fn() -> Value` constructor; type compatibility against the column data
-/// type is enforced by `rustc` when the generated code is compiled, since
+/// The name-value form only accepts literals, so arbitrary expressions (e.g.
+/// a call reading the current time) use the list form. Either way the
+/// expression is taken verbatim and used at codegen time to build the
+/// default `Value`; type compatibility against the column data type is
+/// enforced by `rustc` when the generated code is compiled, since
 /// `Value::from(<expr>)` is type-checked against the column's `Value`
 /// variant.
-fn parse_default(field: &syn::Field) -> syn::Result<Option<syn::Expr>> {
-    let mut found: Option<syn::Expr> = None;
+fn parse_default(field: &syn::Field) -> syn::Result<Option<(syn::Expr, bool)>> {
+    let mut found: Option<(syn::Expr, bool)> = None;
 
     for attr in &field.attrs {
         if !attr.path().is_ident(ATTRIBUTE_DEFAULT) {
             continue;
         }
-        let name_value = attr.meta.require_name_value().map_err(|_| {
+        let expr = match &attr.meta {
+            syn::Meta::NameValue(name_value) => (name_value.value.clone(), false),
+            syn::Meta::List(list) => (list.parse_args::<syn::Expr>()?, true),
+            syn::Meta::Path(_) => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "expected `#[default = <lit>]` or `#[default(<expr>)]` (e.g. `#[default = 0]`)",
+                ));
+            }
+        };
+        if found.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate `#[default]` attribute",
+            ));
+        }
+        found = Some(expr);
+    }
+
+    Ok(found)
+}
+
+/// Parses the optional `#[auto_timestamp(on = "insert" | "update")]`
+/// attribute on a field.
+fn parse_auto_timestamp(field: &syn::Field) -> syn::Result<Option<AutoTimestamp>> {
+    let mut found = None;
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident(ATTRIBUTE_AUTO_TIMESTAMP) {
+            continue;
+        }
+        if found.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate `#[auto_timestamp]` attribute",
+            ));
+        }
+        let mut on = None;
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("on") {
+                return Err(meta.error("expected `on = \"insert\"` or `on = \"update\"`"));
+            }
+            let lit: syn::LitStr = meta.value()?.parse()?;
+            on = Some(match lit.value().as_str() {
+                "insert" => AutoTimestamp::Insert,
+                "update" => AutoTimestamp::Update,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "`on` must be either \"insert\" or \"update\"",
+                    ));
+                }
+            });
+            Ok(())
+        })?;
+        found = Some(on.ok_or_else(|| {
             syn::Error::new_spanned(
                 attr,
-                "expected `#[default = <expr>]` (e.g. `#[default = 0]`)",
+                "expected `#[auto_timestamp(on = \"insert\")]` or `#[auto_timestamp(on = \"update\")]`",
             )
-        })?;
+        })?);
+    }
+
+    Ok(found)
+}
+
+/// Parses the optional `#[rename = "column"]` attribute on a field, returning
+/// the column name overriding the field name.
+fn parse_rename(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut found = None;
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident(ATTRIBUTE_RENAME) {
+            continue;
+        }
         if found.is_some() {
             return Err(syn::Error::new_spanned(
                 attr,
-                "duplicate `#[default]` attribute",
+                "duplicate `#[rename]` attribute",
             ));
         }
-        found = Some(name_value.value.clone());
+        let syn::Meta::NameValue(name_value) = &attr.meta else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected `#[rename = \"column\"]`",
+            ));
+        };
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) = &name_value.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &name_value.value,
+                "`#[rename]` expects the column name as a string literal",
+            ));
+        };
+        let column = lit.value();
+        if column.is_empty() {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "`#[rename]` column name cannot be empty",
+            ));
+        }
+        found = Some(column);
     }
 
     Ok(found)
@@ -824,6 +1598,322 @@ fn parse_renamed_from(field: &syn::Field) -> syn::Result<Vec<String>> {
     Ok(names)
 }
 
+/// Parses the `#[check = "<expr>"]` attributes on a field.
+///
+/// The string is parsed as a Rust expression, so syntax errors are reported
+/// at the attribute; whether it is a valid `bool` expression over `self` is
+/// checked by `rustc` when the generated code is compiled.
+fn parse_checks(field: &syn::Field) -> syn::Result<Vec<Check>> {
+    let mut checks = Vec::new();
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident(ATTRIBUTE_CHECK) {
+            continue;
+        }
+        let syn::Meta::NameValue(name_value) = &attr.meta else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected `#[check = \"<expr>\"]` (e.g. `#[check = \"self.end >= self.start\"]`)",
+            ));
+        };
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) = &name_value.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &name_value.value,
+                "`#[check]` expects the expression as a string literal",
+            ));
+        };
+        checks.push(Check {
+            expr: lit.parse::<syn::Expr>()?,
+            source: lit.value(),
+        });
+    }
+
+    Ok(checks)
+}
+
+/// Parses the struct-level `#[cross_validate = "<expr>"]` attributes.
+///
+/// Like `#[check]`, the string is parsed as a Rust expression, while its type
+/// is checked by `rustc` when the generated code is compiled.
+fn parse_cross_validations(attrs: &[syn::Attribute]) -> syn::Result<Vec<Check>> {
+    let mut cross_validations = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident(ATTRIBUTE_CROSS_VALIDATE) {
+            continue;
+        }
+        let syn::Meta::NameValue(name_value) = &attr.meta else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected `#[cross_validate = \"<expr>\"]` (e.g. `#[cross_validate = \"self.end >= self.start\"]`)",
+            ));
+        };
+        let syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) = &name_value.value
+        else {
+            return Err(syn::Error::new_spanned(
+                &name_value.value,
+                "`#[cross_validate]` expects the expression as a string literal",
+            ));
+        };
+        cross_validations.push(Check {
+            expr: lit.parse::<syn::Expr>()?,
+            source: lit.value(),
+        });
+    }
+
+    Ok(cross_validations)
+}
+
+/// Collects the `#[computed = "<expr>"]` fields.
+///
+/// The expression may only read the stored, non foreign key `fields` of the
+/// record, as `self.<field>`; each read is replaced by a binding holding a
+/// clone of the field, so the expression can be evaluated without building
+/// the whole struct.
+fn collect_computed_fields(data: &DataStruct, fields: &[Field]) -> syn::Result<Vec<ComputedField>> {
+    let mut computed_fields = Vec::new();
+
+    for field in data.fields.iter().filter(|field| computed(field)) {
+        let name = field
+            .ident
+            .clone()
+            .ok_or_else(|| syn::Error::new_spanned(field, "All fields must be named"))?;
+        let mut lit = None;
+        for attr in &field.attrs {
+            if attr.path().is_ident(ATTRIBUTE_COMPUTED) {
+                let syn::Meta::NameValue(syn::MetaNameValue {
+                    value:
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(value),
+                            ..
+                        }),
+                    ..
+                }) = &attr.meta
+                else {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "expected `#[computed = \"<expr>\"]` (e.g. `#[computed = \"self.price * self.quantity\"]`)",
+                    ));
+                };
+                lit = Some(value.clone());
+            } else if is_table_field_attribute(attr) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "a `#[computed]` field cannot carry other column attributes",
+                ));
+            }
+        }
+        let lit = lit.expect("computed field has a `#[computed]` attribute");
+
+        let expr = lit.parse::<syn::Expr>()?;
+        let mut depends_on = Vec::new();
+        let expr = replace_self_fields(expr.to_token_stream(), &mut depends_on)?;
+        for dependency in &depends_on {
+            match fields.iter().find(|field| field.name == *dependency) {
+                Some(field) if field.is_fk => {
+                    return Err(syn::Error::new_spanned(
+                        &lit,
+                        format!(
+                            "computed expressions cannot read the foreign key field `{dependency}`"
+                        ),
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(syn::Error::new_spanned(
+                        &lit,
+                        format!(
+                            "computed expressions can only read stored fields, `{dependency}` is not one"
+                        ),
+                    ));
+                }
+            }
+        }
+
+        computed_fields.push(ComputedField {
+            name,
+            ty: field.ty.clone(),
+            expr,
+            depends_on,
+        });
+    }
+
+    Ok(computed_fields)
+}
+
+/// Replaces every `self.<field>` in `tokens` with the binding returned by
+/// [`computed_binding`], collecting the fields read into `depends_on`.
+///
+/// Any other use of `self`, such as a method call, is an error.
+fn replace_self_fields(
+    tokens: TokenStream2,
+    depends_on: &mut Vec<Ident>,
+) -> syn::Result<TokenStream2> {
+    let mut replaced = TokenStream2::new();
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut inner = Group::new(
+                    group.delimiter(),
+                    replace_self_fields(group.stream(), depends_on)?,
+                );
+                inner.set_span(group.span());
+                replaced.extend([TokenTree::Group(inner)]);
+            }
+            TokenTree::Ident(ident) if ident == "self" => {
+                let field = match (tokens.next(), tokens.next()) {
+                    (Some(TokenTree::Punct(dot)), Some(TokenTree::Ident(field)))
+                        if dot.as_char() == '.' =>
+                    {
+                        field
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            ident.span(),
+                            "computed expressions can only read fields, as `self.<field>`",
+                        ));
+                    }
+                };
+                if matches!(tokens.peek(), Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis)
+                {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "computed expressions cannot call methods on `self`",
+                    ));
+                }
+                if !depends_on.contains(&field) {
+                    depends_on.push(field.clone());
+                }
+                replaced.extend([TokenTree::Ident(computed_binding(&field))]);
+            }
+            token => replaced.extend([token]),
+        }
+    }
+
+    Ok(replaced)
+}
+
+/// Returns `true` if the field has a `#[computed]` attribute.
+fn computed(field: &syn::Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident(ATTRIBUTE_COMPUTED))
+}
+
+/// Returns `true` if `attr` is a field attribute of the `Table` derive
+/// describing a stored column.
+fn is_table_field_attribute(attr: &syn::Attribute) -> bool {
+    [
+        ATTRIBUTE_AUTO_TIMESTAMP,
+        "autoincrement",
+        "auto_increment",
+        ATTRIBUTE_CHECK,
+        ATTRIBUTE_COMPRESS,
+        "custom_type",
+        ATTRIBUTE_DECIMAL,
+        ATTRIBUTE_DEFAULT,
+        ATTRIBUTE_DELETED_AT,
+        ATTRIBUTE_FOREIGN_KEY,
+        ATTRIBUTE_INDEX,
+        ATTRIBUTE_PRIMARY_KEY,
+        ATTRIBUTE_RENAME,
+        ATTRIBUTE_RENAMED_FROM,
+        "sanitizer",
+        ATTRIBUTE_UNIQUE,
+        ATTRIBUTE_UNIT_ENUM,
+        "validate",
+    ]
+    .iter()
+    .any(|name| attr.path().is_ident(name))
+}
+
+/// Parses the struct-level `#[check(<filter>)]` and
+/// `#[check(name = "<name>", <filter>)]` attributes.
+///
+/// The filter expression is only parsed here; it is translated into a
+/// `Filter`, and its columns resolved, when the table schema is generated.
+fn parse_table_checks(
+    table_name: &Ident,
+    attrs: &[syn::Attribute],
+) -> syn::Result<Vec<TableCheck>> {
+    let mut checks = Vec::new();
+
+    for attr in attrs {
+        if !attr.path().is_ident(ATTRIBUTE_CHECK) {
+            continue;
+        }
+        let syn::Meta::List(_) = &attr.meta else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected `#[check(<filter>)]` (e.g. `#[check(ge(\"end_date\", column(\"start_date\")))]`)",
+            ));
+        };
+        let args = attr.parse_args_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated)?;
+        let mut name = None;
+        let mut filter = None;
+        for arg in args {
+            match arg {
+                syn::Expr::Assign(assign) if is_path(&assign.left, "name") => {
+                    let syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }) = *assign.right
+                    else {
+                        return Err(syn::Error::new_spanned(
+                            assign.right,
+                            "`name` expects a string literal",
+                        ));
+                    };
+                    name = Some(lit.value());
+                }
+                expr if filter.is_none() => filter = Some(expr),
+                expr => {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "`#[check(...)]` expects a single filter expression; combine filters with `and(...)`",
+                    ));
+                }
+            }
+        }
+        let Some(expr) = filter else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`#[check(...)]` requires a filter expression",
+            ));
+        };
+        let source = expr
+            .to_token_stream()
+            .to_string()
+            .replace(" (", "(")
+            .replace("( ", "(")
+            .replace(" )", ")")
+            .replace(" ,", ",");
+
+        checks.push(TableCheck {
+            name: name.unwrap_or_else(|| format!("{table_name}_check_{}", checks.len() + 1)),
+            expr,
+            source,
+        });
+    }
+
+    Ok(checks)
+}
+
+/// Returns `true` if `expr` is the single-segment path `ident`.
+fn is_path(expr: &syn::Expr, ident: &str) -> bool {
+    matches!(expr, syn::Expr::Path(path) if path.path.is_ident(ident))
+}
+
 /// If the type of field is `Nullable<T>`, returns `true`, else `false`.
 fn nullable(field: &syn::Field) -> bool {
     let field_type = &field.ty;
@@ -839,20 +1929,52 @@ fn is_custom_type(field: &syn::Field) -> bool {
         .any(|attr| attr.path().is_ident("custom_type"))
 }
 
-/// Returns `true` if the field has a `#[unique]` attribute.
+/// Returns `true` if the field has a bare `#[unique]` attribute.
 fn unique(field: &syn::Field) -> bool {
+    field.attrs.iter().any(|attr| {
+        attr.path().is_ident(ATTRIBUTE_UNIQUE) && matches!(attr.meta, syn::Meta::Path(_))
+    })
+}
+
+/// Returns `true` if the field has a `#[compress]` attribute.
+fn compress(field: &syn::Field) -> bool {
     field
         .attrs
         .iter()
-        .any(|attr| attr.path().is_ident("unique"))
+        .any(|attr| attr.path().is_ident(ATTRIBUTE_COMPRESS))
 }
 
-/// Check whethers the field has a `#[autoincrement]` attribute; only valid for integer primary keys
+/// Returns whether a `#[primary_key(...)]` attribute carries the `auto_increment` flag.
+///
+/// A bare `#[primary_key]` returns `false`; any other argument is rejected.
+fn primary_key_auto_increment(attr: &syn::Attribute) -> syn::Result<bool> {
+    if matches!(attr.meta, syn::Meta::Path(_)) {
+        return Ok(false);
+    }
+
+    let mut auto_increment = false;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("auto_increment") {
+            auto_increment = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported `primary_key` argument, expected `auto_increment`"))
+        }
+    })?;
+
+    Ok(auto_increment)
+}
+
+/// Check whethers the field has a `#[autoincrement]` (or `#[auto_increment]`) or `#[primary_key(auto_increment)]` attribute; only valid for integer primary keys
 fn autoincrement(field: &syn::Field) -> syn::Result<bool> {
-    let autoincrement = field
-        .attrs
-        .iter()
-        .any(|attr| attr.path().is_ident("autoincrement"));
+    let mut autoincrement = false;
+    for attr in &field.attrs {
+        if attr.path().is_ident("autoincrement") || attr.path().is_ident("auto_increment") {
+            autoincrement = true;
+        } else if attr.path().is_ident(ATTRIBUTE_PRIMARY_KEY) {
+            autoincrement |= primary_key_auto_increment(attr)?;
+        }
+    }
 
     if !autoincrement {
         return Ok(false);
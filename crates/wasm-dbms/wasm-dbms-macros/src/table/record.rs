@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream as TokenStream2;
 use syn::Ident;
 
-use crate::table::metadata::TableMetadata;
+use crate::table::metadata::{TableMetadata, computed_binding};
 
 /// Generate the `Record` implementation for `struct_name` using the provided `data` and `metadata`.
 pub fn generate_record(struct_name: &Ident, metadata: &TableMetadata) -> TokenStream2 {
@@ -28,16 +28,11 @@ fn struct_def(metadata: &TableMetadata) -> TokenStream2 {
             .iter()
             .find(|fk| fk.field == field.name);
 
+        // a nullable fk uses the same type: the relation stays `None` when the
+        // column is null
         let ty = if let Some(fk) = fk {
             let entity_record = &fk.record_type;
-            // if nullable, wrap in Nullable
-            if field.nullable {
-                quote::quote! {
-                    ::wasm_dbms_api::prelude::Nullable<Box<#entity_record>>
-                }
-            } else {
-                quote::quote! { #entity_record }
-            }
+            quote::quote! { #entity_record }
         } else {
             let value_ty = &field.ty;
             quote::quote! { #value_ty }
@@ -54,6 +49,14 @@ fn struct_def(metadata: &TableMetadata) -> TokenStream2 {
         }
     }
 
+    for computed in &metadata.computed {
+        let name = &computed.name;
+        let ty = &computed.ty;
+        fields.push(quote::quote! {
+            pub #name: Option<#ty>,
+        });
+    }
+
     let record_ident = &metadata.record;
 
     let derives = if metadata.candid {
@@ -122,11 +125,34 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
         });
     }
 
+    // computed fields are evaluated once every field they read is loaded
+    let mut computed_lets = vec![];
+    for computed in &metadata.computed {
+        let name = &computed.name;
+        let ty = &computed.ty;
+        let expr = &computed.expr;
+        let value = quote::quote! {
+            <#ty as ::core::convert::From<_>>::from(#expr)
+        };
+        let dependencies = &computed.depends_on;
+        let bindings = dependencies.iter().map(computed_binding);
+        computed_lets.push(quote::quote! {
+            let #name: Option<#ty> = match (#(#dependencies.clone(),)*) {
+                (#(Some(#bindings),)*) => Some(#value),
+                #[allow(unreachable_patterns)]
+                _ => None,
+            };
+        });
+        struct_fields.push(quote::quote! {
+            #name,
+        });
+    }
+
     // make match for each column (except fk)
     let mut field_matches = vec![];
     for field in metadata.fields.iter().filter(|f| !f.is_fk) {
         let field_ident = &field.name;
-        let field_name = field.name.to_string();
+        let field_name = &field.column;
 
         if field.custom_type {
             let custom_ident = field
@@ -161,16 +187,13 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
                 });
             }
         } else {
-            let value_type = field
-                .value_type
-                .as_ref()
-                .expect("built-in field must have value_type");
+            let bind_inner_value = field.bind_inner_value();
 
             // if is nullable, behaviour is different
             if field.nullable {
                 field_matches.push(quote::quote! {
                     #field_name => {
-                        if let #value_type(__inner_value) = __col_value {
+                        if #bind_inner_value {
                             #field_ident = Some(::wasm_dbms_api::prelude::Nullable::Value(__inner_value.clone()));
                         } else if let ::wasm_dbms_api::prelude::Value::Null = __col_value {
                             #field_ident = Some(::wasm_dbms_api::prelude::Nullable::Null);
@@ -180,7 +203,7 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
             } else if field.is_fk {
                 field_matches.push(quote::quote! {
                     #field_name => {
-                        if let #value_type(__inner_value) = __col_value {
+                        if #bind_inner_value {
                             #field_ident = Some(Box::new(__inner_value.clone()));
                         }
                     }
@@ -188,7 +211,7 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
             } else {
                 field_matches.push(quote::quote! {
                     #field_name => {
-                        if let #value_type(__inner_value) = __col_value {
+                        if #bind_inner_value {
                             #field_ident = Some(__inner_value.clone());
                         }
                     }
@@ -201,7 +224,7 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     let mut fk_matches = vec![];
     for fk in &metadata.foreign_keys {
         let table_name = fk.referenced_table.to_string();
-        let local_column = fk.field.to_string();
+        let local_column = metadata.column_name(&fk.field);
         let fk_entity_record = &fk.record_type;
         // make path for fk_entity_record::from_values
         let fk_from_record_path = quote::quote! {
@@ -252,6 +275,8 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
 
             #(#fk_matches)*
 
+            #(#computed_lets)*
+
             Self {
                 #(#struct_fields)*
             }
@@ -271,7 +296,9 @@ fn impl_to_values(metadata: &TableMetadata) -> TokenStream2 {
                 .custom_type_ident
                 .as_ref()
                 .expect("custom_type field must have custom_type_ident");
-            if field.nullable {
+            if field.is_fk {
+                continue;
+            } else if field.nullable {
                 field_match.push(quote::quote! {
                     match #self_field_name {
                         Some(::wasm_dbms_api::prelude::Nullable::Value(value)) => {
@@ -282,8 +309,6 @@ fn impl_to_values(metadata: &TableMetadata) -> TokenStream2 {
                         Some(::wasm_dbms_api::prelude::Nullable::Null) | None => ::wasm_dbms_api::prelude::Value::Null,
                     }
                 });
-            } else if field.is_fk {
-                continue;
             } else {
                 field_match.push(quote::quote! {
                     match #self_field_name {
@@ -295,26 +320,22 @@ fn impl_to_values(metadata: &TableMetadata) -> TokenStream2 {
                 });
             }
         } else {
-            let value_type = field
-                .value_type
-                .as_ref()
-                .expect("built-in field must have value_type");
+            let wrap_value_clone = field.wrap_inner_value(quote::quote! { value.clone() });
 
-            // handle nullable
-            if field.nullable {
+            if field.is_fk {
+                // do not push fk fields
+                continue;
+            } else if field.nullable {
                 field_match.push(quote::quote! {
                     match #self_field_name {
-                        Some(::wasm_dbms_api::prelude::Nullable::Value(value)) => #value_type(value.clone()),
+                        Some(::wasm_dbms_api::prelude::Nullable::Value(value)) => #wrap_value_clone,
                         Some(::wasm_dbms_api::prelude::Nullable::Null) | None => ::wasm_dbms_api::prelude::Value::Null,
                     }
                 });
-            } else if field.is_fk {
-                // do not push fk fields
-                continue;
             } else {
                 field_match.push(quote::quote! {
                     match #self_field_name {
-                        Some(value) => #value_type(value.clone()),
+                        Some(value) => #wrap_value_clone,
                         None => ::wasm_dbms_api::prelude::Value::Null,
                     }
                 });
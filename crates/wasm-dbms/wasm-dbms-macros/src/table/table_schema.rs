@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream as TokenStream2;
 use syn::Ident;
 
-use crate::table::metadata::{Field, Index, Sanitizer, TableMetadata};
+use crate::table::metadata::{Field, Index, Sanitizer, TableCheck, TableMetadata, UniqueGroup};
 
 /// Generate the table schema implementation for `struct_name` using the provided `data` and `metadata`.
 pub fn generate_table_schema(
@@ -11,19 +11,29 @@ pub fn generate_table_schema(
     let record_ident = metadata.record.clone();
     let insert_ident = metadata.insert.clone();
     let update_ident = metadata.update.clone();
-    let primary_key = metadata.primary_key.clone();
     let foreign_fetcher_ident = metadata.foreign_fetcher_ident();
     let table_name = metadata.name.to_string();
-    let primary_key_str = primary_key.to_string();
+    let primary_keys = metadata
+        .primary_keys
+        .iter()
+        .map(|pk| metadata.column_name(pk));
     let columns_def = column_def(metadata)?;
-    let indexes_def = indexes_def(&metadata.indexes);
+    let indexes_def = indexes_def(&metadata.indexes, metadata);
+    let unique_constraints_def = unique_constraints_def(&metadata.unique_groups, metadata);
     let values = to_values(&metadata.fields);
     let sanitizers = sanitizers(&metadata.fields);
     let validators = validators(&metadata.fields);
     let migrate_impl = migrate_impl(struct_name, metadata);
+    let (check_record_fn, check_constraints_impl) = checks(struct_name, metadata);
+    let soft_delete_column_fn = soft_delete_column(metadata);
+    let auto_timestamps_fn = auto_timestamps(metadata);
+    let on_conflict_update_fn = on_conflict_update(metadata);
+    let table_checks_fn = table_checks(&metadata.table_checks, metadata)?;
+    let computed_columns_fn = computed_columns(metadata);
 
     Ok(quote::quote! {
         #migrate_impl
+        #check_constraints_impl
 
         impl ::wasm_dbms_api::prelude::TableSchema for #struct_name {
             type Record = #record_ident;
@@ -35,8 +45,8 @@ pub fn generate_table_schema(
                 #table_name
             }
 
-            fn primary_key() -> &'static str {
-                #primary_key_str
+            fn primary_keys() -> &'static [&'static str] {
+                &[#(#primary_keys),*]
             }
 
             fn columns() -> &'static [::wasm_dbms_api::prelude::ColumnDef] {
@@ -47,29 +57,474 @@ pub fn generate_table_schema(
                 #indexes_def
             }
 
+            fn unique_constraints() -> &'static [::wasm_dbms_api::prelude::UniqueConstraintDef] {
+                #unique_constraints_def
+            }
+
             fn to_values(self) -> Vec<(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)> {
                 #values
             }
 
-            /// Returns the [`::wasm_dbms_api::prelude::Sanitize`] implementation for the given column name, if any.
-            fn sanitizer(column_name: &'static str) -> Option<Box<dyn ::wasm_dbms_api::prelude::Sanitize>> {
+            /// Returns the [`::wasm_dbms_api::prelude::Sanitize`] implementations for the given column name, in order.
+            fn sanitizer(column_name: &'static str) -> Vec<Box<dyn ::wasm_dbms_api::prelude::Sanitize>> {
                 #sanitizers
             }
 
-            /// Returns the [`::wasm_dbms_api::prelude::Validate`] implementation for the given column name, if any.
-            fn validator(column_name: &'static str) -> Option<Box<dyn ::wasm_dbms_api::prelude::Validate>> {
+            /// Returns the [`::wasm_dbms_api::prelude::Validate`] implementations for the given column name, in order.
+            fn validator(column_name: &'static str) -> Vec<Box<dyn ::wasm_dbms_api::prelude::Validate>> {
                 #validators
             }
+
+            #check_record_fn
+            #soft_delete_column_fn
+            #auto_timestamps_fn
+            #on_conflict_update_fn
+            #table_checks_fn
+            #computed_columns_fn
+        }
+    })
+}
+
+/// Generate the `TableSchema::soft_delete_column` override for a
+/// `#[soft_delete]` table; empty otherwise, so the trait default applies.
+fn soft_delete_column(metadata: &TableMetadata) -> TokenStream2 {
+    let Some(column) = &metadata.soft_delete_column else {
+        return TokenStream2::new();
+    };
+
+    quote::quote! {
+        fn soft_delete_column() -> Option<&'static str> {
+            Some(#column)
+        }
+    }
+}
+
+/// Generate the `TableSchema::on_conflict_update` override for a table with
+/// `#[on_conflict = "update"]`; empty otherwise, so the trait default applies.
+///
+/// Expected to generate for a table with `#[primary_key] id: Uint32`,
+/// `name: Text` and `#[default = 0] score: Uint32`:
+///
+/// ```rust,ignore
+/// fn on_conflict_update(record: &Self::Insert) -> Option<Self::Update> {
+///     let mut where_clause: Option<Filter> = None;
+///     let v = Some(record.id.clone())?;
+///     let filter = Filter::eq("id", v.into());
+///     where_clause = Some(match where_clause {
+///         Some(where_clause) => where_clause.and(filter),
+///         None => filter,
+///     });
+///     Some(Self::Update {
+///         id: None,
+///         name: Some(record.name.clone()),
+///         score: record.score.clone(),
+///         where_clause,
+///     })
+/// }
+/// ```
+fn on_conflict_update(metadata: &TableMetadata) -> TokenStream2 {
+    if !metadata.on_conflict_update {
+        return TokenStream2::new();
+    }
+
+    let mut pk_filters = vec![];
+    let mut patch_fields = vec![];
+    for field in metadata.request_fields() {
+        let name = &field.name;
+        // the value set on the record, `None` when left to the engine
+        let given = if field.auto_increment {
+            quote::quote! {
+                match &record.#name {
+                    ::wasm_dbms_api::prelude::Autoincrement::Value(v) => Some(v.clone()),
+                    ::wasm_dbms_api::prelude::Autoincrement::Auto => None,
+                }
+            }
+        } else if field.default.is_some() {
+            quote::quote! { record.#name.clone() }
+        } else {
+            quote::quote! { Some(record.#name.clone()) }
+        };
+
+        if field.primary_key {
+            let column = &field.column;
+            let value = field.to_value(quote::quote! { v });
+            pk_filters.push(quote::quote! {
+                let v = (#given)?;
+                let filter = ::wasm_dbms_api::prelude::Filter::eq(#column, #value);
+                where_clause = Some(match where_clause {
+                    Some(where_clause) => where_clause.and(filter),
+                    None => filter,
+                });
+            });
+            patch_fields.push(quote::quote! { #name: None, });
+        } else {
+            patch_fields.push(quote::quote! { #name: #given, });
+        }
+    }
+
+    quote::quote! {
+        fn on_conflict_update(record: &Self::Insert) -> Option<Self::Update> {
+            let mut where_clause: Option<::wasm_dbms_api::prelude::Filter> = None;
+            #(#pk_filters)*
+            Some(Self::Update {
+                #(#patch_fields)*
+                where_clause,
+            })
+        }
+    }
+}
+
+/// Generate the `TableSchema::auto_timestamps` override for a table with
+/// `#[auto_timestamp]` fields; empty otherwise, so the trait default applies.
+fn auto_timestamps(metadata: &TableMetadata) -> TokenStream2 {
+    let entries: Vec<_> = metadata
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let column = &field.column;
+            let on = field.auto_timestamp?.api_variant();
+            Some(quote::quote! { (#column, #on) })
+        })
+        .collect();
+    if entries.is_empty() {
+        return TokenStream2::new();
+    }
+
+    quote::quote! {
+        fn auto_timestamps() -> &'static [(&'static str, ::wasm_dbms_api::prelude::AutoTimestamp)] {
+            &[#(#entries),*]
+        }
+    }
+}
+
+/// Generate the `TableSchema::computed_columns` override for a table with
+/// `#[computed]` fields; empty otherwise, so the trait default applies.
+fn computed_columns(metadata: &TableMetadata) -> TokenStream2 {
+    if metadata.computed.is_empty() {
+        return TokenStream2::new();
+    }
+
+    let columns = metadata.computed.iter().map(|computed| {
+        let name = computed.name.to_string();
+        let depends_on = computed
+            .depends_on
+            .iter()
+            .map(|dependency| metadata.column_name(dependency));
+        quote::quote! {
+            ::wasm_dbms_api::prelude::ComputedColumnDef {
+                name: #name,
+                depends_on: &[#(#depends_on),*],
+            }
+        }
+    });
+
+    quote::quote! {
+        fn computed_columns() -> &'static [::wasm_dbms_api::prelude::ComputedColumnDef] {
+            &[#(#columns),*]
+        }
+    }
+}
+
+/// Generate the `TableSchema::checks` override for the struct-level
+/// `#[check(...)]` constraints; empty if there are none, so the trait default
+/// applies.
+fn table_checks(checks: &[TableCheck], metadata: &TableMetadata) -> syn::Result<TokenStream2> {
+    if checks.is_empty() {
+        return Ok(TokenStream2::new());
+    }
+
+    let checks = checks
+        .iter()
+        .map(|check| {
+            let name = &check.name;
+            let source = &check.source;
+            let filter = check_filter(&check.expr, metadata)?;
+            Ok(quote::quote! {
+                ::wasm_dbms_api::prelude::CheckDef {
+                    name: #name,
+                    expression: #source,
+                    filter: (|| #filter) as fn() -> ::wasm_dbms_api::prelude::Filter,
+                }
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote::quote! {
+        fn checks() -> &'static [::wasm_dbms_api::prelude::CheckDef] {
+            &[#(#checks),*]
         }
     })
 }
 
+/// Translate a `#[check(...)]` expression into the `Filter` it builds.
+///
+/// Supported are `eq`, `ne`, `gt`, `lt`, `ge` and `le` on a column and either
+/// a value or another `column("...")`, `between`, `like`, `is_null`,
+/// `not_null`, `and` and `or` on two or more filters, and `not`. Values are
+/// converted into the type of the column they are compared with.
+fn check_filter(expr: &syn::Expr, metadata: &TableMetadata) -> syn::Result<TokenStream2> {
+    let syn::Expr::Call(call) = expr else {
+        return Err(syn::Error::new_spanned(
+            expr,
+            "expected a filter call (e.g. `ge(\"end_date\", column(\"start_date\"))`)",
+        ));
+    };
+    let syn::Expr::Path(func) = call.func.as_ref() else {
+        return Err(syn::Error::new_spanned(
+            &call.func,
+            "expected a filter name",
+        ));
+    };
+    let Some(func) = func.path.get_ident() else {
+        return Err(syn::Error::new_spanned(
+            &call.func,
+            "expected a filter name",
+        ));
+    };
+    let args: Vec<&syn::Expr> = call.args.iter().collect();
+    let arity = |count: usize| {
+        if args.len() == count {
+            Ok(())
+        } else {
+            Err(syn::Error::new_spanned(
+                call,
+                format!("`{func}` expects {count} argument(s)"),
+            ))
+        }
+    };
+
+    match func.to_string().as_str() {
+        op @ ("eq" | "ne" | "gt" | "lt" | "ge" | "le") => {
+            arity(2)?;
+            let (column, field) = check_column(args[0], metadata)?;
+            if let Some(other) = column_reference(args[1]) {
+                let (other, _) = check_column(other, metadata)?;
+                let cmp = Ident::new(&capitalize(op), func.span());
+                return Ok(quote::quote! {
+                    ::wasm_dbms_api::prelude::Filter::compare_columns(
+                        #column,
+                        ::wasm_dbms_api::prelude::ColumnCmp::#cmp,
+                        #other,
+                    )
+                });
+            }
+            let value = value_expr(field, args[1]);
+            Ok(quote::quote! { ::wasm_dbms_api::prelude::Filter::#func(#column, #value) })
+        }
+        "between" => {
+            arity(3)?;
+            let (column, field) = check_column(args[0], metadata)?;
+            let low = value_expr(field, args[1]);
+            let high = value_expr(field, args[2]);
+            Ok(quote::quote! { ::wasm_dbms_api::prelude::Filter::between(#column, #low, #high) })
+        }
+        "like" => {
+            arity(2)?;
+            let (column, _) = check_column(args[0], metadata)?;
+            let pattern = args[1];
+            Ok(quote::quote! { ::wasm_dbms_api::prelude::Filter::like(#column, #pattern) })
+        }
+        "is_null" | "not_null" => {
+            arity(1)?;
+            let (column, _) = check_column(args[0], metadata)?;
+            Ok(quote::quote! { ::wasm_dbms_api::prelude::Filter::#func(#column) })
+        }
+        "and" | "or" => {
+            if args.len() < 2 {
+                return Err(syn::Error::new_spanned(
+                    call,
+                    format!("`{func}` expects at least 2 filters"),
+                ));
+            }
+            let mut filters = args
+                .iter()
+                .map(|arg| check_filter(arg, metadata))
+                .collect::<syn::Result<Vec<_>>>()?
+                .into_iter();
+            let first = filters.next().expect("at least two filters");
+            Ok(filters.fold(first, |acc, filter| quote::quote! { #acc.#func(#filter) }))
+        }
+        "not" => {
+            arity(1)?;
+            let filter = check_filter(args[0], metadata)?;
+            Ok(quote::quote! { #filter.not() })
+        }
+        "column" => Err(syn::Error::new_spanned(
+            call,
+            "`column(...)` is only allowed as the right operand of a comparison",
+        )),
+        _ => Err(syn::Error::new_spanned(
+            func,
+            "unsupported filter, expected one of `eq`, `ne`, `gt`, `lt`, `ge`, `le`, `between`, `like`, `is_null`, `not_null`, `and`, `or`, `not`",
+        )),
+    }
+}
+
+/// Resolve the column named by the string literal `expr` in a check.
+fn check_column<'a>(
+    expr: &syn::Expr,
+    metadata: &'a TableMetadata,
+) -> syn::Result<(String, &'a Field)> {
+    let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(lit),
+        ..
+    }) = expr
+    else {
+        return Err(syn::Error::new_spanned(
+            expr,
+            "expected the column name as a string literal",
+        ));
+    };
+    let column = lit.value();
+    let field = metadata
+        .fields
+        .iter()
+        .find(|field| field.column == column)
+        .ok_or_else(|| {
+            syn::Error::new_spanned(lit, format!("unknown column `{column}` in `#[check]`"))
+        })?;
+
+    Ok((column, field))
+}
+
+/// Returns the argument of a `column("...")` operand.
+fn column_reference(expr: &syn::Expr) -> Option<&syn::Expr> {
+    let syn::Expr::Call(call) = expr else {
+        return None;
+    };
+    let syn::Expr::Path(func) = call.func.as_ref() else {
+        return None;
+    };
+    if !func.path.is_ident("column") || call.args.len() != 1 {
+        return None;
+    }
+
+    call.args.first()
+}
+
+/// Capitalizes the first letter of `s` (e.g. `ge` -> `Ge`).
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// Generate the `#[check]` and `#[cross_validate]` support for `struct_name`:
+/// the `TableSchema::check_record` override, an inherent
+/// `check_constraints(&self)` evaluating every check in declaration order and
+/// an inherent `cross_validate(&self)` evaluating every invariant.
+///
+/// Both are empty when the table declares neither, so the trait default
+/// applies.
+fn checks(struct_name: &Ident, metadata: &TableMetadata) -> (TokenStream2, TokenStream2) {
+    let checks: Vec<_> = metadata
+        .fields
+        .iter()
+        .flat_map(|field| {
+            let field_name = &field.column;
+            field.checks.iter().map(move |check| {
+                let expr = &check.expr;
+                let source = &check.source;
+                quote::quote! {
+                    if !(#expr) {
+                        return Err(::wasm_dbms_api::prelude::DbmsError::Query(
+                            ::wasm_dbms_api::prelude::QueryError::CheckConstraintViolation {
+                                field: #field_name.to_string(),
+                                message: #source.to_string(),
+                            },
+                        ));
+                    }
+                }
+            })
+        })
+        .collect();
+    let cross_validations: Vec<_> = metadata
+        .cross_validations
+        .iter()
+        .map(|check| {
+            let expr = &check.expr;
+            let source = &check.source;
+            quote::quote! {
+                if !(#expr) {
+                    failed.push(#source);
+                }
+            }
+        })
+        .collect();
+    if checks.is_empty() && cross_validations.is_empty() {
+        return (TokenStream2::new(), TokenStream2::new());
+    }
+
+    let mut record_checks = Vec::new();
+    let mut methods = Vec::new();
+    if !checks.is_empty() {
+        record_checks.push(quote::quote! { record.check_constraints()?; });
+        methods.push(quote::quote! {
+            /// Evaluates the `#[check]` constraints of the table against this record.
+            ///
+            /// Fails with [`::wasm_dbms_api::prelude::QueryError::CheckConstraintViolation`]
+            /// for the first check that does not hold.
+            pub fn check_constraints(&self) -> ::wasm_dbms_api::prelude::DbmsResult<()> {
+                #(#checks)*
+                Ok(())
+            }
+        });
+    }
+    if !cross_validations.is_empty() {
+        record_checks.push(quote::quote! { record.cross_validate()?; });
+        methods.push(quote::quote! {
+            /// Evaluates the `#[cross_validate]` invariants of the table against this record.
+            ///
+            /// Fails with [`::wasm_dbms_api::prelude::DbmsError::Validation`] listing
+            /// every invariant that does not hold.
+            pub fn cross_validate(&self) -> ::wasm_dbms_api::prelude::DbmsResult<()> {
+                let mut failed: Vec<&str> = Vec::new();
+                #(#cross_validations)*
+                if failed.is_empty() {
+                    return Ok(());
+                }
+                Err(::wasm_dbms_api::prelude::DbmsError::Validation(format!(
+                    "Invariants of `{}` violated: {}",
+                    stringify!(#struct_name),
+                    failed.join("; ")
+                )))
+            }
+        });
+    }
+
+    let insert_ident = &metadata.insert;
+    let check_record_fn = quote::quote! {
+        fn check_record(
+            record_values: &[(::wasm_dbms_api::prelude::ColumnDef, ::wasm_dbms_api::prelude::Value)],
+        ) -> ::wasm_dbms_api::prelude::DbmsResult<()> {
+            use ::wasm_dbms_api::prelude::InsertRecord as _;
+
+            let record = #insert_ident::record_from_values(record_values)?;
+            #(#record_checks)*
+            Ok(())
+        }
+    };
+    let check_constraints_impl = quote::quote! {
+        impl #struct_name {
+            #(#methods)*
+        }
+    };
+
+    (check_record_fn, check_constraints_impl)
+}
+
 /// Generate the static `&[IndexDef]` slice for the `indexes()` method.
-fn indexes_def(indexes: &[Index]) -> TokenStream2 {
+fn indexes_def(indexes: &[Index], metadata: &TableMetadata) -> TokenStream2 {
     let entries: Vec<_> = indexes
         .iter()
         .map(|index| {
-            let col_strs: Vec<_> = index.columns.iter().map(|c| c.to_string()).collect();
+            let col_strs: Vec<_> = index
+                .columns
+                .iter()
+                .map(|c| metadata.column_name(c))
+                .collect();
             quote::quote! {
                 ::wasm_dbms_api::prelude::IndexDef(&[#(#col_strs),*])
             }
@@ -81,20 +536,49 @@ fn indexes_def(indexes: &[Index]) -> TokenStream2 {
     }
 }
 
+/// Generate the static `&[UniqueConstraintDef]` slice for the `unique_constraints()` method.
+fn unique_constraints_def(groups: &[UniqueGroup], metadata: &TableMetadata) -> TokenStream2 {
+    let entries: Vec<_> = groups
+        .iter()
+        .map(|group| {
+            let name = &group.name;
+            let col_strs: Vec<_> = group
+                .columns
+                .iter()
+                .map(|c| metadata.column_name(c))
+                .collect();
+            quote::quote! {
+                ::wasm_dbms_api::prelude::UniqueConstraintDef {
+                    name: #name,
+                    columns: &[#(#col_strs),*],
+                }
+            }
+        })
+        .collect();
+
+    quote::quote! {
+        &[#(#entries),*]
+    }
+}
+
 fn column_def(metadata: &TableMetadata) -> syn::Result<TokenStream2> {
     let mut columns = vec![];
 
     for field in &metadata.fields {
         let primary_key = quote_bool(field.primary_key);
-        let name = &field.name.to_string();
+        let name = &field.column;
         let foreign_key_def = foreign_key_def(field, metadata)?;
         let data_type_kind = &field.data_type_kind;
         let nullable = quote_bool(field.nullable);
         // set unique to true if either the field is marked as unique or it's a primary key (since primary keys are implicitly unique)
-        let unique = quote_bool(field.unique || field.primary_key);
+        // a single-column primary key is unique on its own; the columns of a
+        // composite key are only unique together
+        let unique =
+            quote_bool(field.unique || (field.primary_key && metadata.primary_keys.len() == 1));
         let auto_increment = quote_bool(field.auto_increment);
         let default = default_expr(field);
         let renamed_from = renamed_from_expr(field);
+        let compress = quote_bool(field.compress);
 
         columns.push(quote::quote! {
             ::wasm_dbms_api::prelude::ColumnDef {
@@ -107,6 +591,7 @@ fn column_def(metadata: &TableMetadata) -> syn::Result<TokenStream2> {
                 primary_key: #primary_key,
                 default: #default,
                 renamed_from: #renamed_from,
+                compress: #compress,
             }
         })
     }
@@ -121,47 +606,77 @@ fn column_def(metadata: &TableMetadata) -> syn::Result<TokenStream2> {
 
 /// Build the `default` field expression for a generated `ColumnDef` literal.
 ///
-/// Returns either `None` (no `#[default]` attribute) or
-/// `Some((|| ::wasm_dbms_api::prelude::Value::from(<inner>::from(<expr>))) as fn() -> Value)`
-/// for built-in types (so an unsuffixed integer literal coerces into the
-/// column's specific `Value` variant rather than defaulting to `i32`), and
-/// `Some((|| Value::from(<expr>)) as fn() -> Value)` for custom types where
-/// `From<CustomType> for Value` is provided by the `#[derive(CustomDataType)]`
-/// macro.
+/// Returns either `None` (no `#[default = ...]` attribute, or a dynamic
+/// `#[default(...)]` one) or `Some((|| <value>) as fn() -> Value)`, where
+/// `<value>` is built by [`default_value`].
+///
+/// Dynamic defaults are left out so the schema snapshot, which evaluates the
+/// constructor, stays stable across calls.
 ///
 /// The fn-pointer cast keeps [`ColumnDef`](::wasm_dbms_api::prelude::ColumnDef)
 /// `Copy`.
 fn default_expr(field: &Field) -> TokenStream2 {
-    let Some(expr) = field.default.as_ref() else {
+    let Some(body) = default_value(field).filter(|_| !field.dynamic_default) else {
         return quote::quote! { ::core::option::Option::None };
     };
 
-    let body = if field.custom_type {
-        quote::quote! {
+    quote::quote! {
+        ::core::option::Option::Some(
+            (|| #body) as fn() -> ::wasm_dbms_api::prelude::Value
+        )
+    }
+}
+
+/// Build the expression evaluating a field's `#[default]` into a `Value`.
+///
+/// Returns `None` if the field has no default. Built-in types go through
+/// `Value::from(<inner>::from(<expr>))`, so an unsuffixed integer literal
+/// coerces into the column's specific `Value` variant rather than defaulting
+/// to `i32`; custom types use `Value::from(<expr>)`, where
+/// `From<CustomType> for Value` is provided by the `#[derive(CustomDataType)]`
+/// macro; array types build an `ArrayOf` from the expression (e.g. a `Vec`)
+/// and unit enum types take the discriminant of the variant.
+pub fn default_value(field: &Field) -> Option<TokenStream2> {
+    field.default.as_ref().map(|expr| value_expr(field, expr))
+}
+
+/// Build the expression converting `expr` into a `Value` of the column of
+/// `field`, as described in [`default_value`].
+fn value_expr(field: &Field, expr: &syn::Expr) -> TokenStream2 {
+    if field.custom_type {
+        return quote::quote! {
             ::wasm_dbms_api::prelude::Value::from(#expr)
-        }
-    } else {
-        // value_type is set for non-custom fields (e.g. `Value::Uint32`); the
-        // last segment names the inner type (e.g. `Uint32`).
-        let value_type = field
-            .value_type
-            .as_ref()
-            .expect("non-custom field must carry a value_type");
-        let inner_ident = &value_type
-            .segments
-            .last()
-            .expect("value_type path must have at least one segment")
-            .ident;
-        quote::quote! {
+        };
+    }
+
+    if let Some(unit_enum) = &field.unit_enum {
+        return quote::quote! {
+            ::wasm_dbms_api::prelude::unit_enum_to_value::<#unit_enum>(&#expr)
+        };
+    }
+
+    if let Some(elem) = &field.array_element {
+        return quote::quote! {
             ::wasm_dbms_api::prelude::Value::from(
-                <::wasm_dbms_api::prelude::#inner_ident as ::core::convert::From<_>>::from(#expr)
+                <::wasm_dbms_api::prelude::ArrayOf<::wasm_dbms_api::prelude::#elem> as ::core::convert::From<_>>::from(#expr)
             )
-        }
-    };
+        };
+    }
 
+    // value_type is set for built-in scalar fields (e.g. `Value::Uint32`);
+    // the last segment names the inner type (e.g. `Uint32`).
+    let value_type = field
+        .value_type
+        .as_ref()
+        .expect("non-custom field must carry a value_type");
+    let inner_ident = &value_type
+        .segments
+        .last()
+        .expect("value_type path must have at least one segment")
+        .ident;
     quote::quote! {
-        ::core::option::Option::Some(
-            (|| #body) as fn() -> ::wasm_dbms_api::prelude::Value
+        ::wasm_dbms_api::prelude::Value::from(
+            <::wasm_dbms_api::prelude::#inner_ident as ::core::convert::From<_>>::from(#expr)
         )
     }
 }
@@ -197,15 +712,28 @@ fn foreign_key_def(field: &Field, metadata: &TableMetadata) -> syn::Result<Token
         return Ok(quote::quote! { None });
     };
 
-    let local_column = foreign_key_for_field.field.to_string();
+    let local_column = &field.column;
     let foreign_table = foreign_key_for_field.referenced_table.to_string();
+    let local_columns = foreign_key_for_field.local_columns(local_column);
     let foreign_column = foreign_key_for_field.referenced_field.to_string();
+    let foreign_columns = &foreign_key_for_field.referenced_fields;
+    let on_delete = match &foreign_key_for_field.on_delete {
+        Some(behavior) => {
+            quote::quote! { Some(::wasm_dbms_api::prelude::DeleteBehavior::#behavior) }
+        }
+        None => quote::quote! { None },
+    };
+    let deferred = quote_bool(foreign_key_for_field.deferred);
 
     Ok(quote::quote! {
         Some(::wasm_dbms_api::prelude::ForeignKeyDef {
             local_column: #local_column,
+            local_columns: &[#(#local_columns),*],
             foreign_table: #foreign_table,
             foreign_column: #foreign_column,
+            foreign_columns: &[#(#foreign_columns),*],
+            on_delete: #on_delete,
+            deferred: #deferred,
         })
     })
 }
@@ -251,10 +779,8 @@ fn to_values(fields: &[Field]) -> TokenStream2 {
             }
         } else {
             // Built-in type handling
-            let value_type = field
-                .value_type
-                .as_ref()
-                .expect("built-in field must have value_type");
+            let wrap_inner = field.wrap_inner_value(quote::quote! { inner });
+            let wrap_self_field = field.wrap_inner_value(quote::quote! { #self_field });
 
             // For nullable we need to match whether it's Null.
             // If it's null we return `Value::Null`, otherwise we wrap the inner value.
@@ -262,12 +788,12 @@ fn to_values(fields: &[Field]) -> TokenStream2 {
                 columns.push(quote::quote! {
                     (Self::columns()[#index], match #self_field {
                         ::wasm_dbms_api::prelude::Nullable::Null => ::wasm_dbms_api::prelude::Value::Null,
-                        ::wasm_dbms_api::prelude::Nullable::Value(inner) => #value_type(inner),
+                        ::wasm_dbms_api::prelude::Nullable::Value(inner) => #wrap_inner,
                     })
                 });
             } else {
                 columns.push(quote::quote! {
-                    (Self::columns()[#index], #value_type(#self_field))
+                    (Self::columns()[#index], #wrap_self_field)
                 });
             }
         }
@@ -279,28 +805,24 @@ fn to_values(fields: &[Field]) -> TokenStream2 {
 }
 
 /// Generate the match arms for the validators function.
+///
+/// Each field maps to its validators, boxed in declaration order.
 fn validators(fields: &[Field]) -> TokenStream2 {
     let mut arms = vec![];
 
     for field in fields {
-        if let Some(validator) = &field.validate {
-            let field_name = field.name.to_string();
-            let validator_struct = &validator.path;
-            let args = &validator.args;
-            if args.is_empty() {
-                arms.push(quote::quote! {
-                    #field_name => Some(Box::new(#validator_struct)),
-                });
-            } else {
-                arms.push(quote::quote! {
-                    #field_name => Some(Box::new(#validator_struct(#(#args),*))),
-                });
-            }
+        if field.validate.is_empty() {
+            continue;
         }
+        let field_name = &field.column;
+        let validators = field.validate.iter().map(|validator| &validator.expr);
+        arms.push(quote::quote! {
+            #field_name => vec![#(Box::new(#validators)),*],
+        });
     }
 
     arms.push(quote::quote! {
-        _ => None,
+        _ => Vec::new(),
     });
 
     quote::quote! {
@@ -311,39 +833,35 @@ fn validators(fields: &[Field]) -> TokenStream2 {
 }
 
 /// Generate the match arms for the sanitizers function.
+///
+/// Each field maps to its sanitizers, boxed in declaration order.
 fn sanitizers(fields: &[Field]) -> TokenStream2 {
     let mut arms = vec![];
 
     for field in fields {
-        if let Some(sanitizer) = &field.sanitize {
-            let field_name = field.name.to_string();
-            match sanitizer {
-                Sanitizer::Unit { name } => {
-                    arms.push(quote::quote! {
-                        #field_name => Some(Box::new(#name)),
-                    });
-                }
-                Sanitizer::Tuple { name, args } => {
-                    arms.push(quote::quote! {
-                        #field_name => Some(Box::new(#name(#(#args),*))),
-                    });
-                }
-                Sanitizer::NamedArgs { name, args } => {
-                    let fields = args.iter().map(|(ident, expr)| {
-                        quote::quote! {
-                            #ident: #expr
-                        }
-                    });
-                    arms.push(quote::quote! {
-                        #field_name => Some(Box::new(#name { #(#fields),* })),
-                    });
-                }
-            }
+        if field.sanitize.is_empty() {
+            continue;
         }
+        let field_name = &field.column;
+        let sanitizers = field.sanitize.iter().map(|sanitizer| match sanitizer {
+            Sanitizer::Unit { name } => quote::quote! { #name },
+            Sanitizer::Expr { expr } => quote::quote! { #expr },
+            Sanitizer::NamedArgs { name, args } => {
+                let fields = args.iter().map(|(ident, expr)| {
+                    quote::quote! {
+                        #ident: #expr
+                    }
+                });
+                quote::quote! { #name { #(#fields),* } }
+            }
+        });
+        arms.push(quote::quote! {
+            #field_name => vec![#(Box::new(#sanitizers)),*],
+        });
     }
 
     arms.push(quote::quote! {
-        _ => None,
+        _ => Vec::new(),
     });
 
     quote::quote! {
@@ -36,7 +36,7 @@ pub fn generate_update_request(struct_name: &Ident, metadata: &TableMetadata) ->
 fn generate_update_request_struct(metadata: &TableMetadata) -> TokenStream2 {
     let mut fields = vec![];
 
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let name = &field.name;
         let value_ty = &field.ty;
         fields.push(quote::quote! {
@@ -143,7 +143,7 @@ fn impl_update_record(struct_name: &Ident, metadata: &TableMetadata) -> TokenStr
 /// ```
 fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     let mut field_initializers = vec![];
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let field_name = &field.name;
         let field_type = &field.ty;
         field_initializers.push(quote::quote! {
@@ -152,9 +152,9 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     }
 
     let mut match_arms = vec![];
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let field_name = &field.name;
-        let field_name_str = field.name.to_string();
+        let field_name_str = &field.column;
 
         if field.custom_type {
             let custom_ident = field
@@ -189,15 +189,12 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
                 })
             }
         } else {
-            let value_type = field
-                .value_type
-                .as_ref()
-                .expect("built-in field must have value_type");
+            let bind_inner_value = field.bind_inner_value();
 
             if field.nullable {
                 match_arms.push(quote::quote! {
                     #field_name_str => {
-                        if let #value_type(__inner_value) = __col_value {
+                        if #bind_inner_value {
                             #field_name = Some(::wasm_dbms_api::prelude::Nullable::Value(__inner_value.clone()));
                         } else if let ::wasm_dbms_api::prelude::Value::Null = __col_value {
                             #field_name = Some(::wasm_dbms_api::prelude::Nullable::Null);
@@ -207,7 +204,7 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
             } else {
                 match_arms.push(quote::quote! {
                     #field_name_str => {
-                        if let #value_type(__inner_value) = __col_value {
+                        if #bind_inner_value {
                             #field_name = Some(__inner_value.clone());
                         }
                     }
@@ -217,7 +214,7 @@ fn impl_from_values(metadata: &TableMetadata) -> TokenStream2 {
     }
 
     let mut constructor_fields = vec![];
-    for field in &metadata.fields {
+    for field in metadata.request_fields() {
         let field_name = &field.name;
         constructor_fields.push(quote::quote! {
             #field_name,
@@ -277,10 +274,14 @@ fn impl_update_values(metadata: &TableMetadata) -> TokenStream2 {
     let mut update_values_push = vec![];
 
     for (index, field) in metadata.fields.iter().enumerate() {
+        if field.auto_timestamp.is_some() {
+            continue;
+        }
         let field_name = &field.name;
+        let value = field.to_value(quote::quote! { value.clone() });
         update_values_push.push(quote::quote! {
             if let Some(value) = &self.#field_name {
-                updates.push((Self::Schema::columns()[#index], value.clone().into()));
+                updates.push((Self::Schema::columns()[#index], #value));
             }
         });
     }
@@ -70,4 +70,9 @@ pub trait AccessControl: Default {
 
     /// Returns every identity in the ACL together with its perms.
     fn identities(&self) -> Vec<(Self::Id, IdentityPerms)>;
+
+    /// Returns every identity in the ACL, regardless of its perms.
+    fn list_all(&self) -> Vec<Self::Id> {
+        self.identities().into_iter().map(|(id, _)| id).collect()
+    }
 }
@@ -48,13 +48,15 @@
 //!   [`TableRegistryPage`].
 //! - [`TableRegistry`] — record-level storage, free-segment tracking,
 //!   and read iterators ([`TableReader`], [`RawTableReader`],
-//!   [`NextRecord`], [`RecordAddress`], [`RawRecordBytes`]).
+//!   [`NextRecord`], [`RecordRef`], [`RecordAddress`], [`RawRecordBytes`]).
 //! - [`IndexLedger`] / [`IndexTreeWalker`] — secondary index storage
 //!   and traversal.
 //! - [`table_registry::AutoincrementLedger`] — per-column
 //!   autoincrement counters.
 //! - [`UnclaimedPages`] — free page pool ([`UNCLAIMED_PAGES_CAPACITY`]
 //!   entries per ledger page).
+//! - [`OverflowBlob`] — chains of overflow pages storing blobs too large
+//!   to be stored inline in a record.
 //! - [`align_up`] / [`WASM_PAGE_SIZE`] — alignment helpers.
 //!
 //! ## Memory layout
@@ -101,6 +103,7 @@ extern crate self as wasm_dbms_memory;
 mod acl;
 mod memory_access;
 mod memory_manager;
+mod overflow_blob;
 mod provider;
 mod schema_registry;
 pub mod table_registry;
@@ -109,11 +112,12 @@ mod unclaimed_pages;
 pub use self::acl::{AccessControl, AccessControlList, NoAccessControl};
 pub use self::memory_access::MemoryAccess;
 pub use self::memory_manager::{MemoryManager, RESERVED_PAGES, align_up};
+pub use self::overflow_blob::OverflowBlob;
 pub use self::provider::{HeapMemoryProvider, MemoryProvider, WASM_PAGE_SIZE};
 pub use self::schema_registry::{SchemaRegistry, TableRegistryPage};
 pub use self::table_registry::{
-    IndexLedger, IndexTreeWalker, NextRecord, RawRecordBytes, RawTableReader, RecordAddress,
-    TableReader, TableRegistry,
+    Compaction, IndexLedger, IndexTreeWalker, NextRecord, RawRecordBytes, RawTableReader,
+    RecordAddress, RecordRef, TableCursor, TableReader, TableRegistry,
 };
 pub use self::unclaimed_pages::{UNCLAIMED_PAGES_CAPACITY, UnclaimedPages};
 
@@ -122,11 +126,12 @@ pub mod prelude {
     pub use super::acl::{AccessControl, AccessControlList, NoAccessControl};
     pub use super::memory_access::MemoryAccess;
     pub use super::memory_manager::{MemoryManager, RESERVED_PAGES, align_up};
+    pub use super::overflow_blob::OverflowBlob;
     pub use super::provider::{HeapMemoryProvider, MemoryProvider, WASM_PAGE_SIZE};
     pub use super::schema_registry::{SchemaRegistry, TableRegistryPage};
     pub use super::table_registry::{
-        AutoincrementLedger, IndexLedger, IndexTreeWalker, NextRecord, RawRecordBytes,
-        RawTableReader, RecordAddress, TableReader, TableRegistry,
+        AutoincrementLedger, Compaction, IndexLedger, IndexTreeWalker, NextRecord, RawRecordBytes,
+        RawTableReader, RecordAddress, RecordRef, TableCursor, TableReader, TableRegistry,
     };
     pub use super::unclaimed_pages::{UNCLAIMED_PAGES_CAPACITY, UnclaimedPages};
 }
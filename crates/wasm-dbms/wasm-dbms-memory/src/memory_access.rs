@@ -60,6 +60,21 @@ pub trait MemoryAccess {
         self.write_at(UNCLAIMED_PAGES_PAGE, 0, &ledger)
     }
 
+    /// Copies the whole content of `src_page` to `dst_page`, byte for byte.
+    ///
+    /// Much cheaper than decoding and re-encoding the records of a page
+    /// when moving or snapshotting it. Both pages must be allocated; the
+    /// copy goes through [`MemoryAccess::write_at_raw`], so a journaled
+    /// writer can roll it back.
+    fn copy_page(&mut self, src_page: Page, dst_page: Page) -> MemoryResult<()> {
+        if src_page == dst_page {
+            return Ok(());
+        }
+        let mut buf = vec![0u8; self.page_size() as usize];
+        self.read_at_raw(src_page, 0, &mut buf)?;
+        self.write_at_raw(dst_page, 0, &buf)
+    }
+
     /// Reads a typed value from the specified page and offset.
     fn read_at<D>(&mut self, page: Page, offset: PageOffset) -> MemoryResult<D>
     where
@@ -598,6 +598,37 @@ mod tests {
         assert!(matches!(result, Err(MemoryError::SegmentationFault { .. })));
     }
 
+    #[test]
+    fn test_should_copy_page() {
+        let mut mm = make_mm();
+        let src = mm.claim_page().expect("claim src");
+        let dst = mm.claim_page().expect("claim dst");
+        mm.write_at_raw(src, 0, &[1, 2, 3, 4]).expect("write 0");
+        mm.write_at_raw(src, 65_000, &[5, 6]).expect("write 65k");
+        mm.write_at_raw(dst, 100, &[9, 9]).expect("write dst");
+
+        mm.copy_page(src, dst).expect("copy_page");
+
+        let mut src_buf = vec![0u8; HeapMemoryProvider::PAGE_SIZE as usize];
+        let mut dst_buf = vec![0u8; HeapMemoryProvider::PAGE_SIZE as usize];
+        mm.read_at_raw(src, 0, &mut src_buf).expect("read src");
+        mm.read_at_raw(dst, 0, &mut dst_buf).expect("read dst");
+        assert_eq!(src_buf, dst_buf);
+        assert_eq!(&dst_buf[..4], &[1, 2, 3, 4]);
+        assert_eq!(&dst_buf[100..102], &[0, 0]);
+    }
+
+    #[test]
+    fn test_copy_page_rejects_unallocated_pages() {
+        let mut mm = make_mm();
+        let page = mm.claim_page().expect("claim");
+
+        let result = mm.copy_page(99, page);
+        assert!(matches!(result, Err(MemoryError::SegmentationFault { .. })));
+        let result = mm.copy_page(page, 99);
+        assert!(matches!(result, Err(MemoryError::SegmentationFault { .. })));
+    }
+
     #[test]
     fn test_should_check_unallocated_page() {
         let mm = make_mm();
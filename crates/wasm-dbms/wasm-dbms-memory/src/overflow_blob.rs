@@ -0,0 +1,287 @@
+// Rust guideline compliant 2026-04-28
+// X-WHERE-CLAUSE, M-CANONICAL-DOCS
+
+//! Overflow storage for blobs too large to be stored inline in a record.
+//!
+//! The bytes of an [`OverflowBlob`] are stored in a chain of dedicated
+//! pages, claimed through [`MemoryAccess::claim_page`]. Each page starts
+//! with the number of the next page of the chain, `0` on the last one, and
+//! is followed by the payload:
+//!
+//! ```text
+//! +0:  next page (u32, little endian)
+//! +4:  payload   (page size - 4 bytes)
+//! ```
+//!
+//! The record keeps only a reference to the chain, made of the total length
+//! of the blob and of its first page (see [`OverflowBlob::to_bytes`]).
+
+use wasm_dbms_api::prelude::{MemoryError, MemoryResult, Page, PageOffset};
+
+use crate::MemoryAccess;
+
+/// Bytes of the page header, holding the next page of the chain.
+const PAGE_HEADER_SIZE: u64 = 4;
+
+/// Marker opening the encoded reference of an [`OverflowBlob`].
+const MAGIC: [u8; 4] = [0xFF, 0xB1, 0x0B, 0xFF];
+
+/// A blob stored in a chain of overflow pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowBlob {
+    /// Total length of the blob in bytes.
+    pub len: u64,
+    /// First page of the chain.
+    pub first_page: Page,
+}
+
+impl OverflowBlob {
+    /// Size of the encoded reference returned by [`OverflowBlob::to_bytes`].
+    pub const ENCODED_SIZE: usize = MAGIC.len() + 8 + 4;
+
+    /// Encodes the reference to the blob stored in the record.
+    ///
+    /// Layout: the 4-byte marker, the length (`u64`) and the first page
+    /// (`u32`), both little endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_SIZE);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&self.len.to_le_bytes());
+        bytes.extend_from_slice(&self.first_page.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a reference encoded by [`OverflowBlob::to_bytes`], or returns
+    /// `None` if `bytes` is not one.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::ENCODED_SIZE || bytes[..MAGIC.len()] != MAGIC {
+            return None;
+        }
+        let len = u64::from_le_bytes(bytes[4..12].try_into().ok()?);
+        let first_page = Page::from_le_bytes(bytes[12..16].try_into().ok()?);
+
+        Some(Self { len, first_page })
+    }
+
+    /// Stores `bytes` in a new chain of overflow pages.
+    pub fn write(bytes: &[u8], mm: &mut impl MemoryAccess) -> MemoryResult<Self> {
+        let mut blob = Self {
+            len: 0,
+            first_page: mm.claim_page()?,
+        };
+        blob.write_at(0, bytes, mm)?;
+
+        Ok(blob)
+    }
+
+    /// Writes `bytes` at `offset`, growing the blob and its chain when they
+    /// extend past its end.
+    ///
+    /// Fails with [`MemoryError::SegmentationFault`] if `offset` is past the
+    /// end of the blob.
+    pub fn write_at(
+        &mut self,
+        offset: u64,
+        bytes: &[u8],
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<()> {
+        if offset > self.len {
+            return Err(self.out_of_bounds(offset, bytes.len() as u64, mm));
+        }
+        let payload = payload_size(mm);
+        let new_len = self.len.max(offset + bytes.len() as u64);
+        let mut pages = self.pages(mm)?;
+        let needed = new_len.div_ceil(payload).max(1) as usize;
+        while pages.len() < needed {
+            let page = mm.claim_page()?;
+            let last = *pages.last().expect("a chain has at least one page");
+            mm.write_at_raw(last, 0, &page.to_le_bytes())?;
+            pages.push(page);
+        }
+
+        let mut written = 0;
+        while written < bytes.len() {
+            let position = offset + written as u64;
+            let page = pages[(position / payload) as usize];
+            let page_offset = position % payload;
+            let chunk = ((payload - page_offset) as usize).min(bytes.len() - written);
+            mm.write_at_raw(
+                page,
+                (PAGE_HEADER_SIZE + page_offset) as PageOffset,
+                &bytes[written..written + chunk],
+            )?;
+            written += chunk;
+        }
+        self.len = new_len;
+
+        Ok(())
+    }
+
+    /// Reads up to `len` bytes starting at `offset`.
+    ///
+    /// Reading past the end of the blob returns fewer bytes; an `offset`
+    /// past the end fails with [`MemoryError::SegmentationFault`].
+    pub fn read(&self, offset: u64, len: u64, mm: &mut impl MemoryAccess) -> MemoryResult<Vec<u8>> {
+        if offset > self.len {
+            return Err(self.out_of_bounds(offset, len, mm));
+        }
+        let payload = payload_size(mm);
+        let pages = self.pages(mm)?;
+        let len = len.min(self.len - offset) as usize;
+        let mut bytes = vec![0; len];
+
+        let mut read = 0;
+        while read < len {
+            let position = offset + read as u64;
+            let page = pages[(position / payload) as usize];
+            let page_offset = position % payload;
+            let chunk = ((payload - page_offset) as usize).min(len - read);
+            mm.read_at_raw(
+                page,
+                (PAGE_HEADER_SIZE + page_offset) as PageOffset,
+                &mut bytes[read..read + chunk],
+            )?;
+            read += chunk;
+        }
+
+        Ok(bytes)
+    }
+
+    /// Returns the pages of the chain to the unclaimed-pages ledger.
+    pub fn release(self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        for page in self.pages(mm)? {
+            mm.unclaim_page(page)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the pages of the chain, in order.
+    pub fn pages(&self, mm: &mut impl MemoryAccess) -> MemoryResult<Vec<Page>> {
+        let count = self.len.div_ceil(payload_size(mm)).max(1) as usize;
+        let mut pages = Vec::with_capacity(count);
+        let mut page = self.first_page;
+        loop {
+            pages.push(page);
+            let mut next = [0; PAGE_HEADER_SIZE as usize];
+            mm.read_at_raw(page, 0, &mut next)?;
+            page = Page::from_le_bytes(next);
+            // stop on a corrupted chain looping back
+            if page == 0 || pages.len() > count {
+                break;
+            }
+        }
+
+        Ok(pages)
+    }
+
+    fn out_of_bounds(&self, offset: u64, len: u64, mm: &impl MemoryAccess) -> MemoryError {
+        MemoryError::SegmentationFault {
+            page: self.first_page,
+            offset: offset.min(PageOffset::MAX as u64) as PageOffset,
+            data_size: len,
+            page_size: mm.page_size(),
+        }
+    }
+}
+
+/// Bytes of payload stored in each page of a chain.
+fn payload_size(mm: &impl MemoryAccess) -> u64 {
+    mm.page_size() - PAGE_HEADER_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{HeapMemoryProvider, MemoryManager};
+
+    fn mm() -> MemoryManager<HeapMemoryProvider> {
+        MemoryManager::init(HeapMemoryProvider::default())
+    }
+
+    fn bytes(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_should_encode_and_decode_reference() {
+        let blob = OverflowBlob {
+            len: 1 << 40,
+            first_page: 42,
+        };
+        let encoded = blob.to_bytes();
+        assert_eq!(encoded.len(), OverflowBlob::ENCODED_SIZE);
+        assert_eq!(OverflowBlob::from_bytes(&encoded), Some(blob));
+
+        assert_eq!(OverflowBlob::from_bytes(&encoded[1..]), None);
+        let mut other = encoded.clone();
+        other[0] = 0;
+        assert_eq!(OverflowBlob::from_bytes(&other), None);
+    }
+
+    #[test]
+    fn test_should_write_and_read_blob_spanning_pages() {
+        let mut mm = mm();
+        let data = bytes(200_000);
+        let blob = OverflowBlob::write(&data, &mut mm).unwrap();
+        assert_eq!(blob.len, 200_000);
+        assert_eq!(blob.pages(&mut mm).unwrap().len(), 4);
+
+        assert_eq!(blob.read(0, blob.len, &mut mm).unwrap(), data);
+        assert_eq!(
+            blob.read(65_530, 20, &mut mm).unwrap(),
+            data[65_530..65_550]
+        );
+        // reads are clamped to the end of the blob
+        assert_eq!(blob.read(199_990, 100, &mut mm).unwrap(), data[199_990..]);
+        assert!(blob.read(200_000, 10, &mut mm).unwrap().is_empty());
+        assert!(matches!(
+            blob.read(200_001, 10, &mut mm),
+            Err(MemoryError::SegmentationFault { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_overwrite_and_append_chunks() {
+        let mut mm = mm();
+        let mut blob = OverflowBlob::write(&bytes(10), &mut mm).unwrap();
+        assert_eq!(blob.pages(&mut mm).unwrap().len(), 1);
+
+        let mut expected = bytes(10);
+        for chunk in 0..5 {
+            let data = vec![chunk as u8 + 1; 40_000];
+            blob.write_at(blob.len, &data, &mut mm).unwrap();
+            expected.extend_from_slice(&data);
+        }
+        blob.write_at(5, &[0xAA; 70_000], &mut mm).unwrap();
+        expected[5..70_005].fill(0xAA);
+
+        assert_eq!(blob.len, expected.len() as u64);
+        assert_eq!(blob.pages(&mut mm).unwrap().len(), 4);
+        assert_eq!(blob.read(0, blob.len, &mut mm).unwrap(), expected);
+
+        assert!(matches!(
+            blob.write_at(blob.len + 1, &[1], &mut mm),
+            Err(MemoryError::SegmentationFault { .. })
+        ));
+    }
+
+    #[test]
+    fn test_should_release_pages_for_reuse() {
+        let mut mm = mm();
+        let blob = OverflowBlob::write(&bytes(150_000), &mut mm).unwrap();
+        let pages = blob.pages(&mut mm).unwrap();
+        let pages_count = mm.pages_count();
+        blob.release(&mut mm).unwrap();
+
+        let other = OverflowBlob::write(&bytes(150_000), &mut mm).unwrap();
+        let mut reused = other.pages(&mut mm).unwrap();
+        reused.sort_unstable();
+        let mut pages = pages;
+        pages.sort_unstable();
+        assert_eq!(reused, pages);
+        assert_eq!(mm.pages_count(), pages_count);
+        assert_eq!(other.read(0, other.len, &mut mm).unwrap(), bytes(150_000));
+    }
+}
@@ -4,8 +4,8 @@ use std::collections::HashMap;
 
 use wasm_dbms_api::memory::MemoryError;
 use wasm_dbms_api::prelude::{
-    DEFAULT_ALIGNMENT, DataSize, Encode, MSize, MemoryResult, Page, PageOffset, TableFingerprint,
-    TableSchema, TableSchemaSnapshot, fingerprint_for_name,
+    DEFAULT_ALIGNMENT, DataSize, Encode, MSize, MemoryResult, Page, PageOffset, RetentionPolicy,
+    TableFingerprint, TableSchema, TableSchemaSnapshot, TableStats, fingerprint_for_name,
 };
 use xxhash_rust::xxh3::Xxh3;
 
@@ -34,6 +34,9 @@ pub struct TableRegistryPage {
 pub struct SchemaRegistry {
     schema_hash: u64,
     tables: HashMap<TableFingerprint, TableRegistryPage>,
+    /// Retention policies, kept on the heap only: they are not persisted and
+    /// must be registered again after every load.
+    retention_policies: Vec<RetentionPolicy>,
 }
 
 impl SchemaRegistry {
@@ -247,6 +250,24 @@ impl SchemaRegistry {
         Ok(None)
     }
 
+    /// Drops the table `TS`, the typed counterpart of [`Self::unregister_table`].
+    ///
+    /// Every page owned by the table is zeroed and pushed to the
+    /// unclaimed-pages ledger, so [`MemoryAccess::claim_page`] reuses it
+    /// before growing the memory, and the table entry is removed from the
+    /// persisted registry. Dropping a table which is not registered is a
+    /// no-op.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::unregister_table`].
+    pub fn drop_table<TS>(&mut self, mm: &mut impl MemoryAccess) -> MemoryResult<()>
+    where
+        TS: TableSchema,
+    {
+        self.unregister_table(TS::table_name(), mm).map(|_| ())
+    }
+
     /// Returns the persisted [`TableSchemaSnapshot`] for every registered table.
     ///
     /// The order is unspecified. Callers that need a stable order (e.g. for
@@ -269,6 +290,58 @@ impl SchemaRegistry {
             .collect()
     }
 
+    /// Returns the [`TableStats`] of every registered table, sorted by table name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`MemoryError`] encountered while loading a table
+    /// registry or walking its records.
+    pub fn table_stats(&self, mm: &mut impl MemoryAccess) -> MemoryResult<Vec<TableStats>> {
+        let mut stats = self
+            .tables
+            .values()
+            .map(|pages| Self::stats_of(pages, mm))
+            .collect::<MemoryResult<Vec<_>>>()?;
+        stats.sort_by(|a, b| a.table.cmp(&b.table));
+        Ok(stats)
+    }
+
+    /// Returns the [`TableStats`] of the table with the given fingerprint, or
+    /// `None` if it is not registered.
+    pub fn table_stats_of(
+        &self,
+        table: TableFingerprint,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<Option<TableStats>> {
+        self.tables
+            .get(&table)
+            .map(|pages| Self::stats_of(pages, mm))
+            .transpose()
+    }
+
+    /// Returns the [`TableStats`] of the table stored at `pages`.
+    fn stats_of(pages: &TableRegistryPage, mm: &mut impl MemoryAccess) -> MemoryResult<TableStats> {
+        let snapshot = SchemaSnapshotLedger::load(pages.schema_snapshot_page, mm)?
+            .get()
+            .clone();
+        TableRegistry::load(*pages, mm)?.stats(snapshot.name, snapshot.alignment as PageOffset, mm)
+    }
+
+    /// Sets the retention policy of `policy.table`, replacing the previous
+    /// one, if any.
+    ///
+    /// Policies are not persisted by [`Self::save`].
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.retention_policies
+            .retain(|registered| registered.table != policy.table);
+        self.retention_policies.push(policy);
+    }
+
+    /// Returns the registered retention policies.
+    pub fn retention_policies(&self) -> &[RetentionPolicy] {
+        &self.retention_policies
+    }
+
     /// Recomputes the cached schema hash from the currently registered tables.
     pub fn refresh_schema_hash(&mut self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
         self.schema_hash = compute_hash(self.stored_snapshots(mm)?);
@@ -369,6 +442,7 @@ impl Encode for SchemaRegistry {
         Ok(Self {
             schema_hash,
             tables,
+            retention_policies: Vec::new(),
         })
     }
 
@@ -655,8 +729,8 @@ mod tests {
             &[]
         }
 
-        fn primary_key() -> &'static str {
-            ""
+        fn primary_keys() -> &'static [&'static str] {
+            &[""]
         }
 
         fn indexes() -> &'static [wasm_dbms_api::prelude::IndexDef] {
@@ -667,16 +741,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -777,8 +847,8 @@ mod tests {
             &[]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [wasm_dbms_api::prelude::IndexDef] {
@@ -789,16 +859,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -817,6 +883,35 @@ mod tests {
         assert_eq!(decoded.tables.len(), 0);
     }
 
+    #[test]
+    fn test_set_retention_policy_replaces_policy_of_table() {
+        let mut mm = make_mm();
+        let mut registry = SchemaRegistry::load(&mut mm).expect("failed to load registry");
+        registry.set_retention_policy(RetentionPolicy {
+            table: "users",
+            column: "created_at",
+            max_age_nanos: 10,
+        });
+        registry.set_retention_policy(RetentionPolicy {
+            table: "users",
+            column: "updated_at",
+            max_age_nanos: 20,
+        });
+        assert_eq!(
+            registry.retention_policies(),
+            &[RetentionPolicy {
+                table: "users",
+                column: "updated_at",
+                max_age_nanos: 20,
+            }]
+        );
+
+        // policies are not persisted
+        registry.save(&mut mm).expect("failed to save registry");
+        let reloaded = SchemaRegistry::load(&mut mm).expect("failed to reload registry");
+        assert!(reloaded.retention_policies().is_empty());
+    }
+
     #[test]
     fn test_load_fresh_memory_returns_empty_registry() {
         let mut mm = make_mm();
@@ -1026,11 +1121,12 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             }]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -1041,16 +1137,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -1275,4 +1367,36 @@ mod tests {
             .clone();
         assert_eq!(snapshot.name, "users");
     }
+
+    #[test]
+    fn test_drop_table_zeroes_and_reuses_pages() {
+        let mut mm = make_mm();
+        let mut registry = SchemaRegistry::default();
+        let pages = registry.register_table::<User>(&mut mm).expect("register");
+        registry
+            .register_table::<AnotherTable>(&mut mm)
+            .expect("register");
+        let last_page_before_drop = mm.last_page().expect("at least one page");
+
+        registry.drop_table::<User>(&mut mm).expect("drop");
+
+        // the entry is gone from the persisted registry, the other table is kept
+        let reloaded = SchemaRegistry::load(&mut mm).expect("reload");
+        assert!(reloaded.table_registry_page::<User>().is_none());
+        assert!(reloaded.table_registry_page::<AnotherTable>().is_some());
+
+        // the released pages are zeroed
+        let mut buf = vec![0xffu8; mm.page_size() as usize];
+        mm.read_at_raw(pages.schema_snapshot_page, 0, &mut buf)
+            .expect("read page");
+        assert!(buf.iter().all(|byte| *byte == 0));
+
+        // and reused before growing the memory
+        let page = mm.claim_page().expect("claim");
+        assert!(page <= last_page_before_drop);
+        assert_eq!(mm.last_page(), Some(last_page_before_drop));
+
+        // dropping an unregistered table is a no-op
+        registry.drop_table::<User>(&mut mm).expect("drop again");
+    }
 }
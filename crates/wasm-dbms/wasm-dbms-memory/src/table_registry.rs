@@ -11,7 +11,10 @@ mod schema_snapshot_ledger;
 mod table_reader;
 mod write_at;
 
-use wasm_dbms_api::prelude::{Encode, MSize, MemoryResult, PageOffset, Value};
+use wasm_dbms_api::prelude::{
+    CompactionReport, DecodeError, Encode, MSize, MemoryError, MemoryResult, Page, PageOffset,
+    TableSchema, TableStats, Value,
+};
 
 pub use self::autoincrement_ledger::AutoincrementLedger;
 use self::free_segments_ledger::FreeSegmentsLedger;
@@ -21,7 +24,7 @@ use self::raw_record::RawRecord;
 pub use self::raw_table_reader::{RawRecordBytes, RawTableReader};
 pub use self::record_address::RecordAddress;
 pub use self::schema_snapshot_ledger::SchemaSnapshotLedger;
-pub use self::table_reader::{NextRecord, TableReader};
+pub use self::table_reader::{NextRecord, RecordRef, TableCursor, TableReader};
 use self::write_at::WriteAt;
 use crate::{MemoryAccess, TableRegistryPage, align_up};
 
@@ -41,8 +44,21 @@ pub struct TableRegistry {
     auto_increment_ledger: Option<AutoincrementLedger>,
 }
 
+/// Outcome of [`TableRegistry::compact`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compaction {
+    /// Page counters of the call.
+    pub report: CompactionReport,
+    /// Old and new address of every moved record, in move order.
+    pub moves: Vec<(RecordAddress, RecordAddress)>,
+}
+
 impl TableRegistry {
     /// Loads the table registry from memory.
+    ///
+    /// The stored schema is not checked against any table definition, so the
+    /// registry can be read under an older schema, e.g. while migrating.
+    /// Use [`TableRegistry::load_checked`] to access the rows of a table `T`.
     pub fn load(table_pages: TableRegistryPage, mm: &mut impl MemoryAccess) -> MemoryResult<Self> {
         Ok(Self {
             schema_snapshot_ledger: SchemaSnapshotLedger::load(
@@ -60,6 +76,32 @@ impl TableRegistry {
         })
     }
 
+    /// Loads the table registry of `T` from memory, checking that the stored
+    /// schema version matches the one of `T`.
+    ///
+    /// Returns [`DecodeError::VersionMismatch`] if the columns of `T` differ
+    /// from the stored ones, meaning the rows can't be decoded as `T` until
+    /// the table is migrated.
+    pub fn load_checked<T>(
+        table_pages: TableRegistryPage,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<Self>
+    where
+        T: TableSchema,
+    {
+        let registry = Self::load(table_pages, mm)?;
+        let stored = registry.schema_snapshot_ledger.get().schema_version();
+        let current = T::schema_snapshot().schema_version();
+        if stored != current {
+            return Err(MemoryError::DecodeError(DecodeError::VersionMismatch {
+                stored,
+                current,
+            }));
+        }
+
+        Ok(registry)
+    }
+
     /// Inserts a new record into the table registry.
     ///
     /// Returns the address where the record was inserted, which can be used to read it back or to update/delete it.
@@ -105,6 +147,20 @@ impl TableRegistry {
         TableReader::new(&self.page_ledger, mm)
     }
 
+    /// Creates a [`TableReader`] resuming from a [`TableCursor`] obtained with
+    /// [`TableReader::into_cursor`].
+    pub fn resume_read<'a, E, MA>(
+        &'a self,
+        cursor: TableCursor,
+        mm: &'a mut MA,
+    ) -> TableReader<'a, E, MA>
+    where
+        E: Encode,
+        MA: MemoryAccess,
+    {
+        TableReader::resume(&self.page_ledger, mm, cursor)
+    }
+
     /// Reads a single record at the given address.
     pub fn read_at<E, MA>(&self, address: RecordAddress, mm: &mut MA) -> MemoryResult<E>
     where
@@ -173,6 +229,39 @@ impl TableRegistry {
         RawTableReader::new(&self.page_ledger, alignment, mm)
     }
 
+    /// Computes the [`TableStats`] of the table named `table`.
+    ///
+    /// Records are counted by walking their headers, without decoding them.
+    /// `alignment` must match the on-disk layout, as for [`Self::iter_raw`].
+    pub fn stats(
+        &self,
+        table: String,
+        alignment: PageOffset,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<TableStats> {
+        let pages = self.page_ledger.pages();
+        let allocated_bytes = pages.len() as u64 * mm.page_size();
+        let free_bytes: u64 = pages.iter().map(|page| page.free).sum();
+        let fragmented_bytes = self.free_segments_ledger.free_bytes(mm)?;
+
+        let mut rows = 0;
+        let mut reader = self.iter_raw(alignment, mm);
+        while reader.try_next()?.is_some() {
+            rows += 1;
+        }
+
+        Ok(TableStats {
+            table,
+            rows,
+            pages: pages.len() as u64,
+            used_bytes: allocated_bytes
+                .saturating_sub(free_bytes)
+                .saturating_sub(fragmented_bytes),
+            free_bytes,
+            fragmented_bytes,
+        })
+    }
+
     /// Insert pre-encoded record bytes under the given alignment.
     ///
     /// Used by the migration apply pipeline. `bytes` is the body of the
@@ -208,7 +297,24 @@ impl TableRegistry {
             self.page_ledger.commit_raw(page, total, alignment, mm)?;
             (page, offset)
         };
-        let mut full = Vec::with_capacity(total as usize);
+        Self::write_raw_at(page, offset, bytes, alignment, mm)?;
+
+        Ok(RecordAddress { page, offset })
+    }
+
+    /// Writes the length header, the record body `bytes` and the zeroed
+    /// padding at `page` and `offset`, space already being reserved.
+    fn write_raw_at(
+        page: Page,
+        offset: PageOffset,
+        bytes: &[u8],
+        alignment: PageOffset,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<()> {
+        use self::raw_record::RAW_RECORD_HEADER_SIZE;
+
+        let length = bytes.len() as MSize;
+        let mut full = Vec::with_capacity((RAW_RECORD_HEADER_SIZE + length) as usize);
         full.extend_from_slice(&length.to_le_bytes());
         full.extend_from_slice(bytes);
         mm.write_at_raw(page, offset, &full)?;
@@ -218,8 +324,7 @@ impl TableRegistry {
             let padding_offset = offset + RAW_RECORD_HEADER_SIZE + length;
             mm.zero_raw(page, padding_offset, padding)?;
         }
-
-        Ok(RecordAddress { page, offset })
+        Ok(())
     }
 
     /// Read raw record bytes at the given address (header stripped).
@@ -299,6 +404,188 @@ impl TableRegistry {
         Ok(())
     }
 
+    /// Removes every record of the table and returns how many were removed.
+    ///
+    /// Records are counted by walking their headers, then the record pages
+    /// are released and the free-segments and index ledgers emptied, without
+    /// touching the records one by one. `alignment` must match the on-disk
+    /// layout, as for [`Self::iter_raw`]. The autoincrement counters are
+    /// kept.
+    ///
+    /// NOTE: like the other CRUD operations, this function does NOT check
+    /// whether other tables reference the removed records.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`wasm_dbms_api::prelude::MemoryError`] surfaced
+    /// while walking the records or releasing pages.
+    pub fn truncate(
+        &mut self,
+        alignment: PageOffset,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<u64> {
+        let mut rows = 0;
+        let mut reader = self.iter_raw(alignment, mm);
+        while reader.try_next()?.is_some() {
+            rows += 1;
+        }
+
+        self.page_ledger.clear(mm)?;
+        self.free_segments_ledger.clear(mm)?;
+        self.index_ledger.clear(mm)?;
+
+        Ok(rows)
+    }
+
+    /// Rewrites the records of the table contiguously and returns how many
+    /// record pages were released.
+    ///
+    /// Every live record is read, then the record pages are released and the
+    /// free-segments ledger emptied, and the records are inserted again one
+    /// after the other, leaving no free segment behind. Record addresses
+    /// change, so the caller must rebuild the indexes. `alignment` must match
+    /// the on-disk layout, as for [`Self::iter_raw`].
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`wasm_dbms_api::prelude::MemoryError`] surfaced
+    /// while walking the records, releasing pages or writing the records.
+    pub fn defragment(
+        &mut self,
+        alignment: PageOffset,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<u64> {
+        let mut records = Vec::new();
+        let mut reader = self.iter_raw(alignment, mm);
+        while let Some(record) = reader.try_next()? {
+            records.push(record.bytes);
+        }
+
+        let pages_before = self.page_ledger.pages().len();
+        self.page_ledger.clear(mm)?;
+        self.free_segments_ledger.clear(mm)?;
+        for bytes in records {
+            self.insert_raw(&bytes, alignment, mm)?;
+        }
+
+        Ok(pages_before.saturating_sub(self.page_ledger.pages().len()) as u64)
+    }
+
+    /// Moves the records of the last record pages into free space on the
+    /// other pages, releasing every page it empties, at most `max_pages` of
+    /// them.
+    ///
+    /// Pages are emptied from the last one backwards. Each record goes to the
+    /// first free segment, or else the first page end, with room for it on
+    /// another page; no page is claimed. The call stops at the first record
+    /// that fits nowhere, leaving its page in place, so the table stays
+    /// consistent after every call and compaction resumes on the next one.
+    /// The released pages go to the unclaimed-pages ledger, where any table
+    /// can reuse them.
+    ///
+    /// Record addresses change, so the caller must repoint the indexes to the
+    /// returned [`Compaction::moves`]. `alignment` must match the on-disk
+    /// layout, as for [`Self::iter_raw`]. Unlike [`Self::defragment`], the
+    /// records are placed first-fit, so pages may be left for the full rewrite
+    /// when record sizes vary widely.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`wasm_dbms_api::prelude::MemoryError`] surfaced
+    /// while reading, moving the records or releasing pages.
+    pub fn compact(
+        &mut self,
+        alignment: PageOffset,
+        max_pages: u64,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<Compaction> {
+        let page_size = mm.page_size();
+        let pages_before = self.page_ledger.pages().len() as u64;
+        let mut moves = Vec::new();
+        let mut released = 0;
+        let mut complete = false;
+
+        while released < max_pages {
+            let pages = self.page_ledger.pages();
+            let Some(last) = pages.last().filter(|_| pages.len() > 1) else {
+                complete = true;
+                break;
+            };
+            let page = last.page;
+
+            let mut records = Vec::new();
+            let mut reader = RawTableReader::from_page(&self.page_ledger, page, alignment, mm);
+            while let Some(record) = reader.try_next()? {
+                records.push(record);
+            }
+            for record in records {
+                match self.move_raw(&record, alignment, mm)? {
+                    Some(address) => moves.push((record.address, address)),
+                    None => {
+                        complete = true;
+                        break;
+                    }
+                }
+            }
+            if complete {
+                break;
+            }
+
+            self.free_segments_ledger.forget_page(page, mm)?;
+            self.page_ledger.release_page(page, mm)?;
+            released += 1;
+        }
+
+        let pages_after = self.page_ledger.pages().len() as u64;
+        Ok(Compaction {
+            report: CompactionReport {
+                pages_before,
+                pages_after,
+                bytes_reclaimed: released * page_size,
+                complete: complete || pages_after <= 1,
+            },
+            moves,
+        })
+    }
+
+    /// Moves `record` to free space on a page other than its own, returning
+    /// its new address, or `None` if no such space fits it.
+    fn move_raw(
+        &mut self,
+        record: &RawRecordBytes,
+        alignment: PageOffset,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<Option<RecordAddress>> {
+        use self::raw_record::RAW_RECORD_HEADER_SIZE;
+
+        let from = record.address.page;
+        let length = record.bytes.len() as MSize;
+        let physical_size = align_up_msize(RAW_RECORD_HEADER_SIZE + length, alignment);
+        let (page, offset) = if let Some(segment) = self
+            .free_segments_ledger
+            .find_reusable_segment_outside(from, physical_size, mm)?
+        {
+            let page = segment.segment.page;
+            let offset = segment.segment.offset;
+            self.free_segments_ledger
+                .commit_reused_space_raw(physical_size, segment, mm)?;
+            (page, offset)
+        } else if let Some((page, offset)) =
+            self.page_ledger
+                .find_room_raw(physical_size as u64, alignment, from, mm.page_size())
+        {
+            let total = (RAW_RECORD_HEADER_SIZE + length) as u64;
+            self.page_ledger.commit_raw(page, total, alignment, mm)?;
+            (page, offset)
+        } else {
+            return Ok(None);
+        };
+
+        Self::write_raw_at(page, offset, &record.bytes, alignment, mm)?;
+        self.delete_raw(record.address, length, alignment, mm)?;
+        Ok(Some(RecordAddress { page, offset }))
+    }
+
     /// Returns how many pages dropping this table would release.
     pub fn releasable_pages_count(
         &self,
@@ -348,6 +635,57 @@ impl TableRegistry {
         }
     }
 
+    /// Get next value for an autoincrement column of the given type, without incrementing it.
+    pub fn peek_autoincrement(&self, column_name: &str) -> MemoryResult<Option<Value>> {
+        self.auto_increment_ledger
+            .as_ref()
+            .map(|ledger| ledger.peek(column_name))
+            .transpose()
+    }
+
+    /// Make sure the next value generated for an autoincrement column is greater than `value`.
+    ///
+    /// No-op if the table has no autoincrement ledger.
+    pub fn bump_autoincrement(
+        &mut self,
+        column_name: &str,
+        value: &Value,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<()> {
+        if let Some(ledger) = &mut self.auto_increment_ledger {
+            ledger.bump(column_name, value, mm)?;
+        }
+        Ok(())
+    }
+
+    /// Reserve a value for an autoincrement column without persisting the counter: `value` when
+    /// given, making sure later generated values are greater, or the next generated value
+    /// otherwise.
+    ///
+    /// The reservations are only persisted by [`Self::save_autoincrement`]. Returns `None` if
+    /// the table has no autoincrement ledger.
+    pub fn reserve_autoincrement(
+        &mut self,
+        column_name: &str,
+        value: Option<&Value>,
+    ) -> MemoryResult<Option<Value>> {
+        self.auto_increment_ledger
+            .as_mut()
+            .map(|ledger| ledger.reserve(column_name, value))
+            .transpose()
+    }
+
+    /// Persist the autoincrement counters, along with the values reserved by
+    /// [`Self::reserve_autoincrement`].
+    ///
+    /// No-op if the table has no autoincrement ledger.
+    pub fn save_autoincrement(&self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        match &self.auto_increment_ledger {
+            Some(ledger) => ledger.save(mm),
+            None => Ok(()),
+        }
+    }
+
     /// Update a [`RawRecord`] in place at the given page and offset.
     ///
     /// The [`RecordAddress`] of the record is returned, which is the same as the old one.
@@ -736,6 +1074,248 @@ mod tests {
         assert!(buffer.iter().all(|&b| b == 0));
     }
 
+    #[test]
+    fn test_should_compute_table_stats() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let mut registry = registry(&mut mm);
+
+        let stats = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(stats.table, "users");
+        assert_eq!(stats.rows, 0);
+        assert_eq!(stats.pages, 0);
+        assert_eq!(stats.used_bytes, 0);
+
+        let mut addresses = Vec::new();
+        for id in 0..3 {
+            let user = User {
+                id,
+                name: "Test".to_string(),
+                email: "new_user@example.com".to_string(),
+                age: 25,
+            };
+            let address = registry
+                .insert(user.clone(), &mut mm)
+                .expect("failed to insert");
+            addresses.push((user, address));
+        }
+        let (user, address) = addresses.remove(1);
+        registry
+            .delete(user, address, &mut mm)
+            .expect("failed to delete");
+
+        let stats = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(stats.rows, 2);
+        assert_eq!(stats.pages, 1);
+        // each record takes 64 bytes once padded
+        assert_eq!(stats.used_bytes, 128);
+        assert_eq!(stats.fragmented_bytes, 64);
+        assert_eq!(stats.free_bytes, mm.page_size() - 192);
+    }
+
+    #[test]
+    fn test_should_truncate_table() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let mut registry = registry(&mut mm);
+
+        let user = |id| User {
+            id,
+            name: "Test".to_string(),
+            email: "new_user@example.com".to_string(),
+            age: 25,
+        };
+        let mut addresses = Vec::new();
+        for id in 0..100 {
+            let address = registry
+                .insert(user(id), &mut mm)
+                .expect("failed to insert");
+            addresses.push(address);
+        }
+        registry
+            .delete(user(1), addresses[1], &mut mm)
+            .expect("failed to delete");
+
+        let removed = registry
+            .truncate(User::ALIGNMENT, &mut mm)
+            .expect("failed to truncate");
+        assert_eq!(removed, 99);
+
+        let stats = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(stats.rows, 0);
+        assert_eq!(stats.pages, 0);
+        assert_eq!(stats.fragmented_bytes, 0);
+
+        // the released pages are reused by the next inserts
+        let pages_count = mm.pages_count();
+        let address = registry
+            .insert(user(200), &mut mm)
+            .expect("failed to insert");
+        assert_eq!(mm.pages_count(), pages_count);
+        let read: User = registry.read_at(address, &mut mm).expect("failed to read");
+        assert_eq!(read, user(200));
+    }
+
+    #[test]
+    fn test_should_defragment_table() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let mut registry = registry(&mut mm);
+
+        let user = |id| User {
+            id,
+            name: "Test".to_string(),
+            email: "new_user@example.com".to_string(),
+            age: 25,
+        };
+        let mut addresses = Vec::new();
+        for id in 0..2_000 {
+            let address = registry
+                .insert(user(id), &mut mm)
+                .expect("failed to insert");
+            addresses.push(address);
+        }
+        for (id, address) in addresses.into_iter().enumerate() {
+            if id % 10 != 0 {
+                registry
+                    .delete(user(id as u32), address, &mut mm)
+                    .expect("failed to delete");
+            }
+        }
+        let before = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(before.pages, 2);
+
+        let released = registry
+            .defragment(User::ALIGNMENT, &mut mm)
+            .expect("failed to defragment");
+        assert_eq!(released, 1);
+
+        let stats = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(stats.rows, 200);
+        assert_eq!(stats.pages, 1);
+        assert_eq!(stats.fragmented_bytes, 0);
+        assert_eq!(stats.used_bytes, 200 * 64);
+
+        let mut reader = registry.read::<User, _>(&mut mm);
+        let mut ids = Vec::new();
+        while let Some(record) = reader.try_next().expect("failed to read") {
+            ids.push(record.record.id);
+        }
+        assert_eq!(ids, (0..2_000).step_by(10).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn test_should_compact_table_in_bounded_steps() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let mut registry = registry(&mut mm);
+
+        let user = |id| User {
+            id,
+            name: "Test".to_string(),
+            email: "new_user@example.com".to_string(),
+            age: 25,
+        };
+        let mut addresses = Vec::new();
+        for id in 0..5_000 {
+            let address = registry
+                .insert(user(id), &mut mm)
+                .expect("failed to insert");
+            addresses.push(address);
+        }
+        for (id, address) in addresses.into_iter().enumerate() {
+            if id % 10 != 0 {
+                registry
+                    .delete(user(id as u32), address, &mut mm)
+                    .expect("failed to delete");
+            }
+        }
+        let last_page = registry.page_ledger.pages().last().expect("no pages").page;
+
+        let step = registry
+            .compact(User::ALIGNMENT, 1, &mut mm)
+            .expect("failed to compact");
+        assert_eq!(step.report.pages_before, 5);
+        assert_eq!(step.report.pages_after, 4);
+        assert_eq!(step.report.bytes_reclaimed, mm.page_size());
+        assert!(!step.report.complete);
+        assert!(
+            step.moves
+                .iter()
+                .all(|(from, to)| from.page == last_page && to.page != last_page)
+        );
+        for (_, to) in &step.moves {
+            let moved: User = registry.read_at(*to, &mut mm).expect("failed to read");
+            assert_eq!(moved.id % 10, 0);
+        }
+
+        let step = registry
+            .compact(User::ALIGNMENT, u64::MAX, &mut mm)
+            .expect("failed to compact");
+        assert_eq!(step.report.pages_before, 4);
+        assert_eq!(step.report.pages_after, 1);
+        assert!(step.report.complete);
+
+        let stats = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(stats.rows, 500);
+        assert_eq!(stats.pages, 1);
+
+        let mut reader = registry.read::<User, _>(&mut mm);
+        let mut ids = Vec::new();
+        while let Some(record) = reader.try_next().expect("failed to read") {
+            ids.push(record.record.id);
+        }
+        ids.sort_unstable();
+        assert_eq!(ids, (0..5_000).step_by(10).collect::<Vec<u32>>());
+
+        // the released pages are handed to the next page claim
+        assert_eq!(
+            mm.claim_page().expect("failed to claim page"),
+            last_page - 3
+        );
+    }
+
+    #[test]
+    fn test_should_not_compact_full_pages() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let mut registry = registry(&mut mm);
+
+        for id in 0..2_000 {
+            registry
+                .insert(
+                    User {
+                        id,
+                        name: "Test".to_string(),
+                        email: "new_user@example.com".to_string(),
+                        age: 25,
+                    },
+                    &mut mm,
+                )
+                .expect("failed to insert");
+        }
+
+        let step = registry
+            .compact(User::ALIGNMENT, u64::MAX, &mut mm)
+            .expect("failed to compact");
+        assert_eq!(step.report.pages_before, 2);
+        assert_eq!(step.report.pages_after, 2);
+        assert_eq!(step.report.bytes_reclaimed, 0);
+        assert!(step.report.complete);
+
+        let stats = registry
+            .stats("users".to_string(), User::ALIGNMENT, &mut mm)
+            .expect("failed to compute stats");
+        assert_eq!(stats.rows, 2_000);
+    }
+
     #[test]
     fn test_read_at_returns_record_at_address() {
         let mut mm = MemoryManager::init(HeapMemoryProvider::default());
@@ -1251,11 +1831,12 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             }]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -1266,16 +1847,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -1340,6 +1917,54 @@ mod tests {
         assert_eq!(value, Value::Uint32(6u32.into()));
     }
 
+    #[test]
+    fn test_load_checked_accepts_matching_schema() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+
+        use crate::SchemaRegistry;
+        let mut schema = SchemaRegistry::load(&mut mm).expect("failed to load schema");
+        let pages = schema
+            .register_table::<AutoincUser>(&mut mm)
+            .expect("failed to register table");
+
+        assert!(TableRegistry::load_checked::<AutoincUser>(pages, &mut mm).is_ok());
+    }
+
+    #[test]
+    fn test_load_checked_rejects_schema_version_mismatch() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+
+        use crate::SchemaRegistry;
+        let mut schema = SchemaRegistry::load(&mut mm).expect("failed to load schema");
+        let pages = schema
+            .register_table::<AutoincUser>(&mut mm)
+            .expect("failed to register table");
+
+        // store the snapshot of a previous schema, with an extra column
+        let mut stored = AutoincUser::schema_snapshot();
+        let mut extra = stored.columns[0].clone();
+        extra.name = "nickname".to_string();
+        stored.columns.push(extra);
+        let stored_version = stored.schema_version();
+        let mut registry = TableRegistry::load(pages, &mut mm).expect("failed to load");
+        registry
+            .schema_snapshot_ledger_mut()
+            .write(pages.schema_snapshot_page, stored, &mut mm)
+            .expect("failed to write snapshot");
+
+        // the unchecked load still reads the table under the stored schema
+        assert!(TableRegistry::load(pages, &mut mm).is_ok());
+        let result = TableRegistry::load_checked::<AutoincUser>(pages, &mut mm);
+        match result {
+            Err(MemoryError::DecodeError(DecodeError::VersionMismatch { stored, current })) => {
+                assert_eq!(stored, stored_version);
+                assert_eq!(current, AutoincUser::schema_snapshot().schema_version());
+            }
+            Err(other) => panic!("expected VersionMismatch, got {other:?}"),
+            Ok(_) => panic!("expected VersionMismatch, got Ok"),
+        }
+    }
+
     #[test]
     fn test_next_autoincrement_overflow_returns_error() {
         let mut mm = MemoryManager::init(HeapMemoryProvider::default());
@@ -1494,11 +2119,12 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             }]
         }
 
-        fn primary_key() -> &'static str {
-            "val"
+        fn primary_keys() -> &'static [&'static str] {
+            &["val"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -1509,16 +2135,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
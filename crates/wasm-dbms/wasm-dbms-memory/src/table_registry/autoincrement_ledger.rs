@@ -56,6 +56,53 @@ impl AutoincrementLedger {
         Ok(value)
     }
 
+    /// Returns the [`Value`] the next call to [`Self::next`] would return for the given column,
+    /// without advancing the counter.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemoryError::AutoincrementOverflow`] if the column has reached its maximum value.
+    pub fn peek(&self, column: &str) -> MemoryResult<Value> {
+        self.registry.clone().next(column)
+    }
+
+    /// Raises the counter of the given column to `value` if it is greater than the current one,
+    /// persisting the registry only when it changes.
+    pub fn bump(
+        &mut self,
+        column: &str,
+        value: &Value,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<()> {
+        if self.registry.bump(column, value) {
+            mm.write_at(self.page, 0, &self.registry)?;
+        }
+        Ok(())
+    }
+
+    /// Reserves a value for the given column without persisting the registry: `value` when
+    /// given, raising the counter to it, or the next value of the counter otherwise.
+    ///
+    /// The reservations are lost unless [`Self::save`] is called afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MemoryError::AutoincrementOverflow`] if the column has reached its maximum value.
+    pub fn reserve(&mut self, column: &str, value: Option<&Value>) -> MemoryResult<Value> {
+        match value {
+            Some(value) => {
+                self.registry.bump(column, value);
+                Ok(value.clone())
+            }
+            None => self.registry.next(column),
+        }
+    }
+
+    /// Persists the registry to memory, along with the values reserved by [`Self::reserve`].
+    pub fn save(&self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        mm.write_at(self.page, 0, &self.registry)
+    }
+
     /// Returns the zero [`Value`] based on the column [`DataTypeKind`].
     fn zero(data_type: DataTypeKind) -> Value {
         match data_type {
@@ -194,11 +241,12 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             }]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -209,16 +257,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -327,6 +371,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 ColumnDef {
                     name: "seq",
@@ -338,12 +383,13 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
             ]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -354,16 +400,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -471,11 +513,12 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             }]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -486,16 +529,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
@@ -596,6 +635,72 @@ mod tests {
         assert_eq!(value, Value::Uint32(6u32.into()));
     }
 
+    #[test]
+    fn test_peek_does_not_advance_counter() {
+        let mut mm = make_mm();
+        let page = mm.claim_page().expect("failed to allocate page");
+
+        let mut ledger =
+            AutoincrementLedger::init::<SingleAutoincTable>(page, &mut mm).expect("failed to init");
+
+        assert_eq!(
+            ledger.peek("id").expect("peek failed"),
+            Value::Uint32(1u32.into())
+        );
+        assert_eq!(
+            ledger.peek("id").expect("peek failed"),
+            Value::Uint32(1u32.into())
+        );
+        let value = ledger.next("id", &mut mm).expect("next failed");
+        assert_eq!(value, Value::Uint32(1u32.into()));
+    }
+
+    #[test]
+    fn test_bump_persists_to_memory() {
+        let mut mm = make_mm();
+        let page = mm.claim_page().expect("failed to allocate page");
+
+        let mut ledger =
+            AutoincrementLedger::init::<SingleAutoincTable>(page, &mut mm).expect("failed to init");
+        ledger
+            .bump("id", &Value::Uint32(41u32.into()), &mut mm)
+            .expect("bump failed");
+
+        let mut reloaded = AutoincrementLedger::load(page, &mut mm).expect("failed to load ledger");
+        let value = reloaded
+            .next("id", &mut mm)
+            .expect("next after reload failed");
+        assert_eq!(value, Value::Uint32(42u32.into()));
+    }
+
+    #[test]
+    fn test_reserve_persists_only_on_save() {
+        let mut mm = make_mm();
+        let page = mm.claim_page().expect("failed to allocate page");
+
+        let mut ledger =
+            AutoincrementLedger::init::<SingleAutoincTable>(page, &mut mm).expect("failed to init");
+        let value = ledger.reserve("id", None).expect("reserve failed");
+        assert_eq!(value, Value::Uint32(1u32.into()));
+        let value = ledger
+            .reserve("id", Some(&Value::Uint32(41u32.into())))
+            .expect("reserve failed");
+        assert_eq!(value, Value::Uint32(41u32.into()));
+
+        let reloaded = AutoincrementLedger::load(page, &mut mm).expect("failed to load ledger");
+        assert_eq!(
+            reloaded.peek("id").expect("peek failed"),
+            Value::Uint32(1u32.into())
+        );
+
+        ledger.save(&mut mm).expect("save failed");
+        let reloaded = AutoincrementLedger::load(page, &mut mm).expect("failed to load ledger");
+        assert_eq!(
+            reloaded.peek("id").expect("peek failed"),
+            Value::Uint32(42u32.into())
+        );
+    }
+
     #[test]
     fn test_next_overflow_returns_error() {
         let mut mm = make_mm();
@@ -751,11 +856,12 @@ mod tests {
                 foreign_key: None,
                 default: None,
                 renamed_from: &[],
+                compress: false,
             }]
         }
 
-        fn primary_key() -> &'static str {
-            "counter"
+        fn primary_keys() -> &'static [&'static str] {
+            &["counter"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -766,16 +872,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(
-            _column_name: &'static str,
-        ) -> Option<Box<dyn wasm_dbms_api::prelude::Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn wasm_dbms_api::prelude::Validate>> {
+            Vec::new()
         }
     }
 
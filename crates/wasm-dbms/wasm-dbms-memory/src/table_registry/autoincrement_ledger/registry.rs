@@ -27,6 +27,29 @@ impl AutoincrementRegistry {
         Ok(next_value)
     }
 
+    /// Raises the current value of the given column to `value`, if `value` is greater.
+    ///
+    /// Called when a record is inserted with an explicit value for an autoincrement
+    /// column, so that later generated values never collide with it.
+    /// Returns whether the registry has been changed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column` has not been initialized in the registry.
+    pub fn bump(&mut self, column: &str, value: &Value) -> bool {
+        let current_value = self.0.entry(column.to_string()).or_insert_with(|| {
+            panic!("column '{column}' does not have an autoincrement value in the registry")
+        });
+        // values of a different type than the counter are left to the type checks of the insert
+        if std::mem::discriminant(current_value) != std::mem::discriminant(value)
+            || value <= current_value
+        {
+            return false;
+        }
+        *current_value = value.clone();
+        true
+    }
+
     /// Initializes the autoincrement value for the given column in the registry.
     ///
     /// Zero value should be provided, as the registry does not know the type of the column,
@@ -221,6 +244,29 @@ mod tests {
         assert_eq!(third, Value::Uint32(3u32.into()));
     }
 
+    #[test]
+    fn test_bump_raises_counter_above_explicit_value() {
+        let mut registry = AutoincrementRegistry::default();
+        registry.init("id", Value::Uint32(0u32.into()));
+
+        assert!(registry.bump("id", &Value::Uint32(10u32.into())));
+        assert_eq!(registry.next("id").unwrap(), Value::Uint32(11u32.into()));
+
+        // lower or equal values leave the counter untouched
+        assert!(!registry.bump("id", &Value::Uint32(5u32.into())));
+        assert!(!registry.bump("id", &Value::Uint32(11u32.into())));
+        assert_eq!(registry.next("id").unwrap(), Value::Uint32(12u32.into()));
+    }
+
+    #[test]
+    fn test_bump_ignores_mismatched_type() {
+        let mut registry = AutoincrementRegistry::default();
+        registry.init("id", Value::Uint32(0u32.into()));
+
+        assert!(!registry.bump("id", &Value::Uint64(100u64.into())));
+        assert_eq!(registry.next("id").unwrap(), Value::Uint32(1u32.into()));
+    }
+
     #[test]
     #[should_panic(expected = "does not have an autoincrement value")]
     fn test_next_panics_on_uninitialized_column() {
@@ -97,6 +97,15 @@ impl FreeSegmentsLedger {
         table.insert_free_segment(page, offset, physical_size, mm)
     }
 
+    /// Returns the total size in bytes of the free segments tracked by the ledger.
+    pub fn free_bytes(&self, mm: &mut impl MemoryAccess) -> MemoryResult<u64> {
+        let mut free_bytes = 0;
+        for table in self.tables(mm) {
+            free_bytes += table?.free_bytes();
+        }
+        Ok(free_bytes)
+    }
+
     /// Finds a reusable free segment that can accommodate the size of the given record.
     ///
     /// - If a suitable free segment is found, it is returned as [`Some<FreeSegmentTicket>`].
@@ -220,6 +229,37 @@ impl FreeSegmentsLedger {
         table.remove(segment.segment, physical_size, mm)
     }
 
+    /// Finds a free segment of at least `physical_size` bytes located on a
+    /// page other than `excluded`.
+    ///
+    /// Used by `TableRegistry::compact` to move records off `excluded`.
+    pub fn find_reusable_segment_outside(
+        &self,
+        excluded: Page,
+        physical_size: MSize,
+        mm: &mut impl MemoryAccess,
+    ) -> MemoryResult<Option<FreeSegmentTicket>> {
+        for table in self.tables(mm) {
+            let table = table?;
+            if let Some(segment) = table.find(|r| r.page != excluded && r.size >= physical_size) {
+                return Ok(Some(FreeSegmentTicket {
+                    segment,
+                    table: table.page(),
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Forgets the free segments located on `page`, once the page has been
+    /// released by the table.
+    pub fn forget_page(&mut self, page: Page, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        for &table_page in self.tables.pages() {
+            FreeSegmentsTable::load(table_page, mm)?.remove_page(page, mm)?;
+        }
+        Ok(())
+    }
+
     /// Releases every page owned by this ledger (chained free-segments
     /// table pages plus the ledger page itself) back to the unclaimed-pages
     /// ledger.
@@ -244,6 +284,23 @@ impl FreeSegmentsLedger {
         self.tables.pages().len() + 1
     }
 
+    /// Forgets every free segment, releasing the pages of the
+    /// [`FreeSegmentsTable`]s but keeping the ledger page itself.
+    ///
+    /// Used by `Database::truncate`, once the table's record pages have been
+    /// released. The ledger is left empty and valid.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`wasm_dbms_api::prelude::MemoryError`] surfaced by
+    /// [`MemoryAccess::unclaim_page`].
+    pub fn clear(&mut self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        for page in std::mem::take(&mut self.tables).pages() {
+            mm.unclaim_page(*page)?;
+        }
+        self.commit(mm)
+    }
+
     /// Writes the current state of the free segments table back to memory.
     fn commit(&self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
         mm.write_at(self.free_segments_page, 0, &self.tables)
@@ -351,6 +408,37 @@ mod tests {
         assert_eq!(reusable_space, None);
     }
 
+    #[test]
+    fn test_should_find_and_forget_segments_by_page() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let page = mm.claim_page().expect("Failed to allocate page");
+
+        let mut ledger =
+            FreeSegmentsLedger::load(page, &mut mm).expect("Failed to load DeletedRecordsLedger");
+        ledger
+            .insert_free_segment_raw(4, 0, 64, &mut mm)
+            .expect("Failed to insert free segment");
+        ledger
+            .insert_free_segment_raw(5, 128, 32, &mut mm)
+            .expect("Failed to insert free segment");
+
+        let outside = |ledger: &FreeSegmentsLedger, excluded, size, mm: &mut MemoryManager<_>| {
+            ledger
+                .find_reusable_segment_outside(excluded, size, mm)
+                .expect("Failed to find free segment")
+                .map(|ticket| ticket.segment.page)
+        };
+        assert_eq!(outside(&ledger, 5, 32, &mut mm), Some(4));
+        assert_eq!(outside(&ledger, 4, 32, &mut mm), Some(5));
+        assert_eq!(outside(&ledger, 4, 64, &mut mm), None);
+
+        ledger
+            .forget_page(4, &mut mm)
+            .expect("Failed to forget page");
+        assert_eq!(outside(&ledger, 5, 32, &mut mm), None);
+        assert_eq!(ledger.free_bytes(&mut mm).expect("Failed to sum"), 32);
+    }
+
     #[test]
     fn test_should_commit_reused_space_without_creating_a_new_record() {
         let mut mm = MemoryManager::init(HeapMemoryProvider::default());
@@ -82,6 +82,15 @@ impl FreeSegmentsTable {
         self.commit(mm)
     }
 
+    /// Returns the total size in bytes of the free segments in the table.
+    pub fn free_bytes(&self) -> u64 {
+        self.records
+            .0
+            .iter()
+            .map(|segment| segment.size as u64)
+            .sum()
+    }
+
     /// Finds a free segment that matches the given predicate.
     pub fn find<F>(&self, predicate: F) -> Option<FreeSegment>
     where
@@ -125,6 +134,16 @@ impl FreeSegmentsTable {
         Ok(())
     }
 
+    /// Removes every free segment located on `page`.
+    pub fn remove_page(&mut self, page: Page, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        let before = self.records.0.len();
+        self.records.0.retain(|segment| segment.page != page);
+        if self.records.0.len() == before {
+            return Ok(());
+        }
+        self.commit(mm)
+    }
+
     /// Commits the current state of the table back to memory.
     fn commit(&self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
         mm.write_at(self.page, 0, &self.records)
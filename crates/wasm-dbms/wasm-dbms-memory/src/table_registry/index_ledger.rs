@@ -188,6 +188,23 @@ impl IndexLedger {
         Ok(count)
     }
 
+    /// Empties every index, releasing the pages of its B-tree and replacing
+    /// it with a new empty tree.
+    ///
+    /// Used by `Database::truncate`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`MemoryError`] surfaced while walking trees,
+    /// releasing or claiming pages.
+    pub fn clear(&mut self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        for root in self.tables.0.values_mut() {
+            self::index_tree::release_index_tree_pages(*root, mm)?;
+            *root = IndexTree::<wasm_dbms_api::prelude::Uint32>::init(mm)?.root_page();
+        }
+        mm.write_at(self.ledger_page, 0, &self.tables)
+    }
+
     fn lookup_root_page(&self, columns: &[&str]) -> MemoryResult<Page> {
         let key = columns.iter().map(ToString::to_string).collect::<Vec<_>>();
         self.tables
@@ -64,10 +64,7 @@ impl PageLedger {
         // otherwise allocate a new one
         let new_page = mm.claim_page()?;
         // add to ledger
-        self.pages.pages.push(PageRecord {
-            page: new_page,
-            free: page_size, // NOTE: we commit later, so full free space
-        });
+        self.track_page(new_page, page_size); // NOTE: we commit later, so full free space
 
         Ok((new_page, 0))
     }
@@ -134,10 +131,7 @@ impl PageLedger {
         }
 
         let new_page = mm.claim_page()?;
-        self.pages.pages.push(PageRecord {
-            page: new_page,
-            free: page_size,
-        });
+        self.track_page(new_page, page_size);
 
         Ok((new_page, 0))
     }
@@ -176,7 +170,49 @@ impl PageLedger {
         Err(wasm_dbms_api::prelude::MemoryError::OutOfBounds)
     }
 
-    /// Returns the list of pages in the ledger.
+    /// Finds room for a record of `physical_size` bytes (header included,
+    /// padded to alignment) at the end of a page other than `excluded`.
+    ///
+    /// Unlike [`Self::get_page_and_offset_raw`], it never claims a new page.
+    /// Used by `TableRegistry::compact` to move records off `excluded`.
+    pub fn find_room_raw(
+        &self,
+        physical_size: u64,
+        alignment: PageOffset,
+        excluded: Page,
+        page_size: u64,
+    ) -> Option<(Page, PageOffset)> {
+        self.pages
+            .pages
+            .iter()
+            .filter(|page_record| page_record.page != excluded)
+            .find_map(|page_record| {
+                let taken = page_size.saturating_sub(page_record.free);
+                let aligned_offset = align_up_u64(taken, alignment as u64);
+                (aligned_offset + physical_size <= page_size)
+                    .then_some((page_record.page, aligned_offset as PageOffset))
+            })
+    }
+
+    /// Removes `page` from the ledger and hands it back to the
+    /// unclaimed-pages ledger.
+    ///
+    /// The page must not hold any live record. Used by
+    /// `TableRegistry::compact` once it has moved every record off the page.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`wasm_dbms_api::prelude::MemoryError`] surfaced by
+    /// [`MemoryAccess::unclaim_page`].
+    pub fn release_page(&mut self, page: Page, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        self.pages
+            .pages
+            .retain(|page_record| page_record.page != page);
+        mm.unclaim_page(page)?;
+        self.write(mm)
+    }
+
+    /// Returns the list of pages in the ledger, sorted by page number.
     pub fn pages(&self) -> &[PageRecord] {
         &self.pages.pages
     }
@@ -204,6 +240,35 @@ impl PageLedger {
         mm.unclaim_page(self.ledger_page)
     }
 
+    /// Releases every record page tracked by this ledger back to the
+    /// unclaimed-pages ledger, keeping the ledger page itself.
+    ///
+    /// Used by `Database::truncate`. The ledger is left empty and valid.
+    ///
+    /// # Errors
+    ///
+    /// Propagates any [`wasm_dbms_api::prelude::MemoryError`] surfaced by
+    /// [`MemoryAccess::unclaim_page`].
+    pub fn clear(&mut self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
+        for record in std::mem::take(&mut self.pages.pages) {
+            mm.unclaim_page(record.page)?;
+        }
+        self.write(mm)
+    }
+
+    /// Adds a newly claimed `page` with `free` bytes to the ledger.
+    ///
+    /// The table readers walk the pages by increasing page number, and a page
+    /// reused from the unclaimed-pages ledger may be lower than the others,
+    /// so the page is inserted in order rather than appended.
+    fn track_page(&mut self, page: Page, free: u64) {
+        let position = self
+            .pages
+            .pages
+            .partition_point(|page_record| page_record.page < page);
+        self.pages.pages.insert(position, PageRecord { page, free });
+    }
+
     /// Write the page ledger to memory.
     fn write(&self, mm: &mut impl MemoryAccess) -> MemoryResult<()> {
         mm.write_at(self.ledger_page, 0, &self.pages)
@@ -343,6 +408,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_should_keep_reused_pages_in_page_order() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let ledger_page = mm.claim_page().expect("failed to allocate ledger page");
+        let low_page = mm.claim_page().expect("failed to allocate page");
+        let mut page_ledger =
+            PageLedger::load(ledger_page, &mut mm).expect("failed to load page ledger");
+
+        // a full page record forces a new page on every allocation
+        let page_size = HeapMemoryProvider::PAGE_SIZE;
+        let (high_page, _) = page_ledger
+            .get_page_and_offset_raw(page_size, 8, &mut mm)
+            .expect("failed to get page");
+        page_ledger
+            .commit_raw(high_page, page_size, 8, &mut mm)
+            .expect("failed to commit");
+        mm.unclaim_page(low_page).expect("failed to unclaim page");
+        let (reused, _) = page_ledger
+            .get_page_and_offset_raw(page_size, 8, &mut mm)
+            .expect("failed to get page");
+        assert_eq!(reused, low_page);
+
+        let pages: Vec<Page> = page_ledger.pages().iter().map(|p| p.page).collect();
+        assert_eq!(pages, vec![low_page, high_page]);
+    }
+
+    #[test]
+    fn test_should_find_room_and_release_page() {
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let ledger_page = mm.claim_page().expect("failed to allocate ledger page");
+        let mut page_ledger =
+            PageLedger::load(ledger_page, &mut mm).expect("failed to load page ledger");
+        let page_size = HeapMemoryProvider::PAGE_SIZE;
+        let (first, _) = page_ledger
+            .get_page_and_offset_raw(page_size - 64, 8, &mut mm)
+            .expect("failed to get page");
+        page_ledger
+            .commit_raw(first, page_size - 64, 8, &mut mm)
+            .expect("failed to commit");
+        let (second, _) = page_ledger
+            .get_page_and_offset_raw(128, 8, &mut mm)
+            .expect("failed to get page");
+        page_ledger
+            .commit_raw(second, 30, 8, &mut mm)
+            .expect("failed to commit");
+
+        assert_eq!(
+            page_ledger.find_room_raw(64, 8, second, page_size),
+            Some((first, (page_size - 64) as PageOffset))
+        );
+        assert_eq!(page_ledger.find_room_raw(72, 8, second, page_size), None);
+        assert_eq!(
+            page_ledger.find_room_raw(72, 8, first, page_size),
+            Some((second, 32))
+        );
+
+        page_ledger
+            .release_page(second, &mut mm)
+            .expect("failed to release page");
+        let reloaded = PageLedger::load(ledger_page, &mut mm).expect("failed to load page ledger");
+        assert_eq!(reloaded.pages().len(), 1);
+        assert_eq!(reloaded.pages()[0].page, first);
+        assert_eq!(mm.claim_page().expect("failed to claim page"), second);
+    }
+
     #[test]
     fn test_should_account_for_padding_on_commit() {
         let mut mm = MemoryManager::init(HeapMemoryProvider::default());
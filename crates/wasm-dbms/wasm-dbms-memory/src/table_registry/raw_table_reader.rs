@@ -65,6 +65,20 @@ where
         }
     }
 
+    /// Build a reader starting at the beginning of `page`, which must be
+    /// tracked by `page_ledger`. Used by `TableRegistry::compact` to read the
+    /// records of the page it empties.
+    pub(crate) fn from_page(
+        page_ledger: &'a PageLedger,
+        page: Page,
+        alignment: PageOffset,
+        mm: &'a mut MA,
+    ) -> Self {
+        let mut reader = Self::new(page_ledger, alignment, mm);
+        reader.cursor = Some(Cursor { page, offset: 0 });
+        reader
+    }
+
     /// Pop the next live record's bytes, or `Ok(None)` at end of table.
     pub fn try_next(&mut self) -> MemoryResult<Option<RawRecordBytes>> {
         loop {
@@ -203,6 +203,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 ColumnDef {
                     name: "name",
@@ -214,12 +215,13 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
             ]
         }
 
-        fn primary_key() -> &'static str {
-            "id"
+        fn primary_keys() -> &'static [&'static str] {
+            &["id"]
         }
 
         fn indexes() -> &'static [IndexDef] {
@@ -230,12 +232,12 @@ mod tests {
             vec![]
         }
 
-        fn sanitizer(_column_name: &'static str) -> Option<Box<dyn Sanitize>> {
-            None
+        fn sanitizer(_column_name: &'static str) -> Vec<Box<dyn Sanitize>> {
+            Vec::new()
         }
 
-        fn validator(_column_name: &'static str) -> Option<Box<dyn Validate>> {
-            None
+        fn validator(_column_name: &'static str) -> Vec<Box<dyn Validate>> {
+            Vec::new()
         }
     }
 
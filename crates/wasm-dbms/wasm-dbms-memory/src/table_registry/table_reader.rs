@@ -1,5 +1,6 @@
 // Rust guideline compliant 2026-02-28
 
+use std::borrow::Cow;
 use std::marker::PhantomData;
 
 use wasm_dbms_api::prelude::{
@@ -38,6 +39,48 @@ where
     pub offset: PageOffset,
 }
 
+/// A record read by [`TableReader::try_next_ref`], borrowing its encoded
+/// bytes from the page buffer of the reader.
+///
+/// Nothing is decoded or copied until [`RecordRef::decode`] is called, so a
+/// scan pays for the records it keeps only.
+pub struct RecordRef<'r, E>
+where
+    E: Encode,
+{
+    /// The encoded record, without its length header.
+    data: &'r [u8],
+    pub page: Page,
+    pub offset: PageOffset,
+    phantom: PhantomData<E>,
+}
+
+impl<E> RecordRef<'_, E>
+where
+    E: Encode,
+{
+    /// Returns the encoded bytes of the record, without its length header.
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Decodes the record.
+    pub fn decode(&self) -> MemoryResult<E> {
+        E::decode(Cow::Borrowed(self.data))
+    }
+}
+
+/// Saved position of a [`TableReader`], used to resume reading later.
+///
+/// Unlike the reader, the cursor holds no borrow of the memory, so the scan can
+/// be suspended while memory is used for something else.
+#[derive(Debug, Clone)]
+pub struct TableCursor {
+    /// Content of the page at `position`.
+    buffer: Vec<u8>,
+    position: Option<Position>,
+}
+
 /// A reader for the table registry that allows reading records from memory.
 ///
 /// The table reader provides methods to read records from the table registry one by one,
@@ -47,8 +90,10 @@ where
     E: Encode,
     MA: MemoryAccess,
 {
-    /// Buffer used to read records from memory.
+    /// Content of the page being read.
     buffer: Vec<u8>,
+    /// Whether [`Self::buffer`] must be read again before use.
+    buffer_stale: bool,
     /// Reference to the memory access implementor.
     mm: &'a mut MA,
     page_ledger: &'a PageLedger,
@@ -74,6 +119,7 @@ where
         let page_size = mm.page_size() as usize;
         Self {
             buffer: vec![0u8; page_size],
+            buffer_stale: true,
             mm,
             page_ledger,
             phantom: PhantomData,
@@ -82,8 +128,50 @@ where
         }
     }
 
+    /// Creates a table reader resuming from the given [`TableCursor`].
+    ///
+    /// The page at the cursor is read again before the next record, since it
+    /// may have been written to while the scan was suspended.
+    pub fn resume(page_ledger: &'a PageLedger, mm: &'a mut MA, cursor: TableCursor) -> Self {
+        let page_size = mm.page_size() as usize;
+        Self {
+            buffer: cursor.buffer,
+            buffer_stale: true,
+            mm,
+            page_ledger,
+            phantom: PhantomData,
+            position: cursor.position,
+            page_size,
+        }
+    }
+
+    /// Consumes the reader, returning the [`TableCursor`] to resume it from.
+    pub fn into_cursor(self) -> TableCursor {
+        TableCursor {
+            buffer: self.buffer,
+            position: self.position,
+        }
+    }
+
     /// Reads the next record from the table registry.
     pub fn try_next(&mut self) -> MemoryResult<Option<NextRecord<E>>> {
+        let Some(record) = self.try_next_ref()? else {
+            return Ok(None);
+        };
+
+        Ok(Some(NextRecord {
+            record: record.decode()?,
+            page: record.page,
+            offset: record.offset,
+        }))
+    }
+
+    /// Reads the next record from the table registry without decoding it.
+    ///
+    /// The returned [`RecordRef`] borrows the record from the page buffer of
+    /// the reader, which is filled once per page: skipping a record costs
+    /// neither a memory read nor an allocation.
+    pub fn try_next_ref(&mut self) -> MemoryResult<Option<RecordRef<'_, E>>> {
         let Some(Position { page, offset }) = self.position else {
             return Ok(None);
         };
@@ -95,16 +183,16 @@ where
             return Ok(None);
         };
 
-        // read raw record
-        let record: RawRecord<E> = self.mm.read_at(next_record.page, next_record.offset)?;
-
         // update position
         self.position = next_record.new_position;
 
-        Ok(Some(NextRecord {
-            record: record.data,
+        let start = next_record.offset as usize + RAW_RECORD_HEADER_SIZE as usize;
+        let end = next_record.offset as usize + next_record.length as usize;
+        Ok(Some(RecordRef {
+            data: &self.buffer[start..end],
             page: next_record.page,
             offset: next_record.offset,
+            phantom: PhantomData,
         }))
     }
 
@@ -119,8 +207,9 @@ where
     ) -> MemoryResult<Option<FoundRecord>> {
         loop {
             // if offset is zero, read page; otherwise, just reuse buffer
-            if offset == 0 {
+            if offset == 0 || self.buffer_stale {
                 self.mm.read_at_raw(page, 0, &mut self.buffer)?;
+                self.buffer_stale = false;
             }
 
             // find next record in buffer; if found, return it
@@ -215,9 +304,15 @@ where
 #[cfg(test)]
 mod tests {
 
+    use std::cell::Cell;
+    use std::rc::Rc;
+
     use super::*;
     use crate::table_registry::test_utils::User;
-    use crate::{HeapMemoryProvider, MemoryManager, TableRegistry, TableRegistryPage};
+    use crate::{
+        HeapMemoryProvider, MemoryManager, MemoryProvider, RecordAddress, TableRegistry,
+        TableRegistryPage,
+    };
 
     #[test]
     fn test_should_read_all_records() {
@@ -239,6 +334,89 @@ mod tests {
         assert_eq!(id, COUNT);
     }
 
+    #[test]
+    fn test_should_resume_from_cursor() {
+        const COUNT: u32 = 4_000;
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let table_registry = mock_table_registry(COUNT, &mut mm);
+        let mut cursor = mocked(&table_registry, &mut mm).into_cursor();
+
+        let mut id = 0;
+        loop {
+            let mut reader: TableReader<'_, User, _> =
+                TableReader::resume(&table_registry.page_ledger, &mut mm, cursor);
+            let Some(NextRecord { record: user, .. }) =
+                reader.try_next().expect("failed to read user")
+            else {
+                break;
+            };
+            assert_eq!(user.id, id);
+            id += 1;
+            cursor = reader.into_cursor();
+        }
+        assert_eq!(id, COUNT);
+    }
+
+    #[test]
+    fn test_should_read_records_by_ref() {
+        const COUNT: u32 = 4_000;
+        let mut mm = MemoryManager::init(HeapMemoryProvider::default());
+        let table_registry = mock_table_registry(COUNT, &mut mm);
+        let mut reader = mocked(&table_registry, &mut mm);
+
+        let mut id = 0;
+        while let Some(record) = reader.try_next_ref().expect("failed to read user") {
+            let user = record.decode().expect("failed to decode user");
+            assert_eq!(user.id, id);
+            assert_eq!(record.data().len(), user.size() as usize);
+            id += 1;
+        }
+        assert_eq!(id, COUNT);
+    }
+
+    #[test]
+    fn test_scan_by_ref_reads_each_page_once() {
+        const COUNT: u32 = 4_000;
+        let provider = CountingMemoryProvider::default();
+        let read_bytes = provider.read_bytes.clone();
+        let mut mm = MemoryManager::init(provider);
+        let table_registry = mock_table_registry(COUNT, &mut mm);
+        let pages = table_registry.page_ledger.pages().len() as u64;
+
+        let mut addresses = Vec::new();
+        let mut reader = mocked(&table_registry, &mut mm);
+        while let Some(record) = reader.try_next_ref().expect("failed to read user") {
+            addresses.push(RecordAddress::new(record.page, record.offset));
+        }
+        assert_eq!(addresses.len(), COUNT as usize);
+
+        // reading each record on its own copies the rest of its page
+        let before = read_bytes.get();
+        for address in &addresses {
+            let _: User = table_registry
+                .read_at(*address, &mut mm)
+                .expect("failed to read user");
+        }
+        let per_record = read_bytes.get() - before;
+
+        let before = read_bytes.get();
+        let mut reader = mocked(&table_registry, &mut mm);
+        let mut matching = 0;
+        while let Some(record) = reader.try_next_ref().expect("failed to read user") {
+            if record.decode().expect("failed to decode user").id % 100 == 0 {
+                matching += 1;
+            }
+        }
+        let scan = read_bytes.get() - before;
+
+        assert_eq!(matching, COUNT / 100);
+        assert_eq!(scan, pages * CountingMemoryProvider::PAGE_SIZE);
+        assert!(
+            scan * 100 < per_record,
+            "scan read {scan} bytes, per-record reads {per_record} bytes"
+        );
+    }
+
     #[test]
     fn test_should_find_next_page() {
         let mut mm = MemoryManager::init(HeapMemoryProvider::default());
@@ -325,10 +503,10 @@ mod tests {
         ));
     }
 
-    fn mock_table_registry(
-        entries: u32,
-        mm: &mut MemoryManager<HeapMemoryProvider>,
-    ) -> TableRegistry {
+    fn mock_table_registry<P>(entries: u32, mm: &mut MemoryManager<P>) -> TableRegistry
+    where
+        P: MemoryProvider,
+    {
         let schema_snapshot_page = mm.claim_page().expect("failed to get page");
         let page_ledger_page = mm.claim_page().expect("failed to get page");
         let free_segments_page = mm.claim_page().expect("failed to get page");
@@ -360,10 +538,46 @@ mod tests {
         registry
     }
 
-    fn mocked<'a>(
+    fn mocked<'a, P>(
         table_registry: &'a TableRegistry,
-        mm: &'a mut MemoryManager<HeapMemoryProvider>,
-    ) -> TableReader<'a, User, MemoryManager<HeapMemoryProvider>> {
+        mm: &'a mut MemoryManager<P>,
+    ) -> TableReader<'a, User, MemoryManager<P>>
+    where
+        P: MemoryProvider,
+    {
         TableReader::new(&table_registry.page_ledger, mm)
     }
+
+    /// Heap memory counting the bytes read from it.
+    #[derive(Default)]
+    struct CountingMemoryProvider {
+        inner: HeapMemoryProvider,
+        read_bytes: Rc<Cell<u64>>,
+    }
+
+    impl MemoryProvider for CountingMemoryProvider {
+        const PAGE_SIZE: u64 = HeapMemoryProvider::PAGE_SIZE;
+
+        fn size(&self) -> u64 {
+            self.inner.size()
+        }
+
+        fn pages(&self) -> u64 {
+            self.inner.pages()
+        }
+
+        fn grow(&mut self, new_pages: u64) -> MemoryResult<u64> {
+            self.inner.grow(new_pages)
+        }
+
+        fn read(&mut self, offset: u64, buf: &mut [u8]) -> MemoryResult<()> {
+            self.read_bytes
+                .set(self.read_bytes.get() + buf.len() as u64);
+            self.inner.read(offset, buf)
+        }
+
+        fn write(&mut self, offset: u64, buf: &[u8]) -> MemoryResult<()> {
+            self.inner.write(offset, buf)
+        }
+    }
 }
@@ -8,9 +8,11 @@
 //! through a single shared reference.
 
 use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 
 use wasm_dbms_api::prelude::{
-    DbmsResult, IdentityPerms, PermGrant, PermRevoke, TableFingerprint, TablePerms, TransactionId,
+    DbmsError, DbmsResult, IdentityPerms, PermGrant, PermRevoke, RetentionPolicy, SavepointId,
+    TableError, TableFingerprint, TablePerms, TableStats, TransactionError, TransactionId,
 };
 use wasm_dbms_memory::prelude::{
     AccessControl, AccessControlList, MemoryManager, MemoryProvider, SchemaRegistry,
@@ -18,7 +20,7 @@ use wasm_dbms_memory::prelude::{
 };
 
 use crate::transaction::journal::Journal;
-use crate::transaction::session::TransactionSession;
+use crate::transaction::session::{TransactionInfo, TransactionSession};
 
 /// Owns all mutable DBMS state behind interior-mutable wrappers.
 ///
@@ -61,10 +63,26 @@ where
     /// schema attached to a database session on this context.
     pub(crate) drift: Cell<Option<(u64, bool)>>,
 
+    /// Tables whose stored schema version has been checked against their
+    /// compiled definition, so later accesses skip the check. Cleared along
+    /// with the drift flag.
+    pub(crate) checked_schema_versions: RefCell<HashSet<TableFingerprint>>,
+
     /// Set while a migration apply pass is mutating stable memory so the
     /// per-CRUD drift gate does not block the engine's own internal reads
     /// (e.g. tightening validation that scans existing rows).
     pub(crate) migrating: Cell<bool>,
+    /// Source of the current time, in nanoseconds since the Unix epoch, used
+    /// to stamp soft-deleted rows.
+    pub(crate) clock: Cell<fn() -> u64>,
+    /// Maximum size of a blob in bytes; `None` means unlimited.
+    pub(crate) max_blob_size: Cell<Option<u64>>,
+    /// Maximum size of a chunk read or written by `blob_read_chunk` and
+    /// `blob_write_chunk`; `None` means unlimited.
+    pub(crate) max_blob_chunk_size: Cell<Option<u64>>,
+    /// Maximum number of rows processed by a single `delete_chunked` or
+    /// `update_chunked` call; `None` means unlimited.
+    pub(crate) max_chunk_rows: Cell<Option<u64>>,
 }
 
 impl<M> DbmsContext<M>
@@ -85,7 +103,12 @@ where
             transaction_session: RefCell::new(TransactionSession::default()),
             journal: RefCell::new(None),
             drift: Cell::new(None),
+            checked_schema_versions: RefCell::new(HashSet::new()),
             migrating: Cell::new(false),
+            clock: Cell::new(unix_epoch),
+            max_blob_size: Cell::new(None),
+            max_blob_chunk_size: Cell::new(None),
+            max_chunk_rows: Cell::new(None),
         }
     }
 }
@@ -108,10 +131,42 @@ where
             transaction_session: RefCell::new(TransactionSession::default()),
             journal: RefCell::new(None),
             drift: Cell::new(None),
+            checked_schema_versions: RefCell::new(HashSet::new()),
             migrating: Cell::new(false),
+            clock: Cell::new(unix_epoch),
+            max_blob_size: Cell::new(None),
+            max_blob_chunk_size: Cell::new(None),
+            max_chunk_rows: Cell::new(None),
         }
     }
 
+    /// Sets the source of the current time, in nanoseconds since the Unix
+    /// epoch, used to stamp the rows deleted from `#[soft_delete]` tables.
+    ///
+    /// The default clock always returns `0`, as the engine has no portable
+    /// time source of its own.
+    pub fn set_clock(&self, clock: fn() -> u64) {
+        self.clock.set(clock);
+    }
+
+    /// Returns the current time according to the clock set with
+    /// [`Self::set_clock`].
+    pub(crate) fn now(&self) -> u64 {
+        (self.clock.get())()
+    }
+
+    /// Registers a retention policy, applied by
+    /// [`Database::vacuum`](wasm_dbms_api::prelude::Database::vacuum).
+    ///
+    /// A table has at most one policy: registering another one replaces it.
+    /// Policies are not persisted, so they must be registered again each
+    /// time the context is created.
+    pub fn set_retention_policy(&self, policy: RetentionPolicy) {
+        self.schema_registry
+            .borrow_mut()
+            .set_retention_policy(policy);
+    }
+
     /// Registers a table schema, persisting it in stable memory.
     pub fn register_table<T: wasm_dbms_api::prelude::TableSchema>(
         &self,
@@ -129,6 +184,27 @@ where
             .is_some()
     }
 
+    /// Returns the storage statistics of every registered table, sorted by
+    /// table name.
+    ///
+    /// Statistics reflect committed data only: writes buffered in open
+    /// transactions are not accounted for.
+    pub fn table_stats(&self) -> DbmsResult<Vec<TableStats>> {
+        let sr = self.schema_registry.borrow();
+        let mut mm = self.mm.borrow_mut();
+        sr.table_stats(&mut *mm).map_err(Into::into)
+    }
+
+    /// Returns the storage statistics of the table with the given fingerprint.
+    ///
+    /// Fails with [`TableError::TableNotFound`] if the table is not registered.
+    pub fn table_stats_of(&self, table: TableFingerprint) -> DbmsResult<TableStats> {
+        let sr = self.schema_registry.borrow();
+        let mut mm = self.mm.borrow_mut();
+        sr.table_stats_of(table, &mut *mm)?
+            .ok_or(DbmsError::Table(TableError::TableNotFound))
+    }
+
     /// Returns whether `id` is granted `required` on `table`.
     pub fn granted(&self, id: &A::Id, table: TableFingerprint, required: TablePerms) -> bool {
         self.acl.borrow().granted(id, table, required)
@@ -183,18 +259,105 @@ where
         self.acl.borrow().identities()
     }
 
+    /// Returns every identity in the ACL.
+    pub fn acl_list_all(&self) -> Vec<A::Id> {
+        self.acl.borrow().list_all()
+    }
+
     /// Begins a new transaction for the given owner identity.
     pub fn begin_transaction(&self, owner: Vec<u8>) -> TransactionId {
         let mut ts = self.transaction_session.borrow_mut();
         ts.begin_transaction(owner)
     }
 
+    /// Begins a new transaction for the given owner identity, created at
+    /// `now` and expiring `ttl` nanoseconds later (never, if `None`).
+    ///
+    /// Fails with [`TransactionError::TooManyOpenTransactions`] when the owner
+    /// already holds the number of open transactions set with
+    /// [`Self::set_max_open_transactions`].
+    pub fn begin_transaction_with_ttl(
+        &self,
+        owner: Vec<u8>,
+        now: u64,
+        ttl: Option<u64>,
+    ) -> Result<TransactionId, TransactionError> {
+        let mut ts = self.transaction_session.borrow_mut();
+        ts.begin_transaction_with_ttl(owner, now, ttl)
+    }
+
+    /// Sets the maximum number of transactions an owner may keep open through
+    /// [`Self::begin_transaction_with_ttl`]; `None` removes the limit.
+    pub fn set_max_open_transactions(&self, limit: Option<u32>) {
+        let mut ts = self.transaction_session.borrow_mut();
+        ts.set_max_open_transactions(limit);
+    }
+
+    /// Sets the maximum size of a blob in bytes, enforced on inserts, updates
+    /// and chunk writes; `None` removes the limit.
+    pub fn set_max_blob_size(&self, limit: Option<u64>) {
+        self.max_blob_size.set(limit);
+    }
+
+    /// Sets the maximum size of a chunk read or written by
+    /// `blob_read_chunk` and `blob_write_chunk`; `None` removes the limit.
+    pub fn set_max_blob_chunk_size(&self, limit: Option<u64>) {
+        self.max_blob_chunk_size.set(limit);
+    }
+
+    /// Sets the maximum number of rows processed by a single
+    /// `delete_chunked` or `update_chunked` call, which also applies to the
+    /// calls without `max_rows`; `None` removes the limit.
+    pub fn set_max_chunk_rows(&self, limit: Option<u64>) {
+        self.max_chunk_rows.set(limit);
+    }
+
     /// Returns whether the given transaction is owned by the given identity.
     pub fn has_transaction(&self, tx_id: &TransactionId, caller: &[u8]) -> bool {
         let ts = self.transaction_session.borrow();
         ts.has_transaction(tx_id, caller)
     }
 
+    /// Returns whether the given transaction, owned by the given identity,
+    /// was dropped by [`Self::expire_transactions`].
+    pub fn has_expired_transaction(&self, tx_id: &TransactionId, caller: &[u8]) -> bool {
+        let ts = self.transaction_session.borrow();
+        ts.has_expired_transaction(tx_id, caller)
+    }
+
+    /// Drops every transaction past its TTL at `now` and returns their IDs.
+    pub fn expire_transactions(&self, now: u64) -> Vec<TransactionId> {
+        let mut ts = self.transaction_session.borrow_mut();
+        ts.expire_transactions(now)
+    }
+
+    /// Returns the metadata of every active transaction, sorted by ID.
+    pub fn list_active_transactions(&self) -> Vec<TransactionInfo> {
+        let ts = self.transaction_session.borrow();
+        ts.list_active_transactions()
+    }
+
+    /// Creates a named savepoint in the given transaction.
+    pub fn create_savepoint(
+        &self,
+        tx_id: &TransactionId,
+        name: impl Into<String>,
+    ) -> DbmsResult<SavepointId> {
+        let mut ts = self.transaction_session.borrow_mut();
+        ts.create_savepoint(tx_id, name)
+    }
+
+    /// Rolls the given transaction back to `savepoint`, keeping the
+    /// transaction open.
+    pub fn rollback_to_savepoint(
+        &self,
+        tx_id: &TransactionId,
+        savepoint: SavepointId,
+    ) -> DbmsResult<()> {
+        let mut ts = self.transaction_session.borrow_mut();
+        ts.rollback_to_savepoint(tx_id, savepoint)
+    }
+
     /// Returns the cached drift flag for `compiled_hash`, if present.
     pub(crate) fn cached_drift_for(&self, compiled_hash: u64) -> Option<bool> {
         self.drift
@@ -207,9 +370,25 @@ where
         self.drift.set(Some((compiled_hash, value)));
     }
 
-    /// Clears the cached drift flag, forcing the next call to recompute.
+    /// Clears the cached drift flag and schema version checks, forcing the
+    /// next call to recompute them.
     pub(crate) fn clear_drift(&self) {
         self.drift.set(None);
+        self.checked_schema_versions.borrow_mut().clear();
+    }
+
+    /// Returns whether the stored schema version of the table `fingerprint`
+    /// has already been checked.
+    pub(crate) fn is_schema_version_checked(&self, fingerprint: TableFingerprint) -> bool {
+        self.checked_schema_versions.borrow().contains(&fingerprint)
+    }
+
+    /// Records that the stored schema version of the table `fingerprint`
+    /// matches its compiled definition.
+    pub(crate) fn set_schema_version_checked(&self, fingerprint: TableFingerprint) {
+        self.checked_schema_versions
+            .borrow_mut()
+            .insert(fingerprint);
     }
 
     /// Returns `true` while a migration apply pass is mutating stable memory.
@@ -237,6 +416,11 @@ where
     }
 }
 
+/// Default clock of a [`DbmsContext`].
+fn unix_epoch() -> u64 {
+    0
+}
+
 #[cfg(test)]
 mod tests {
     use wasm_dbms_memory::prelude::HeapMemoryProvider;
@@ -1,29 +1,39 @@
 //! Core DBMS database struct providing CRUD and transaction operations.
 
 mod aggregate;
+mod blob;
 mod filter_analyzer;
 mod index_reader;
 mod migration;
+mod prepared_filter;
+mod select_iter;
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 
 use wasm_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, ColumnDef, DataTypeKind, Database, DbmsError, DbmsResult,
-    DeleteBehavior, Filter, ForeignFetcher, ForeignKeyDef, InsertRecord, JoinColumnDef,
-    MigrationError, MigrationOp, MigrationPolicy, OrderDirection, Query, QueryError, TableColumns,
-    TableError, TableRecord, TableSchema, TransactionError, TransactionId, UpdateRecord, Value,
-    ValuesSource,
+    AggregateFunction, AggregatedRow, AutoTimestamp, BlobChunk, ColumnDef, CompactionReport,
+    ContinuationToken, DataTypeSnapshot, Database, DateTime, DbmsError, DbmsResult, DeleteBehavior,
+    Filter, ForeignFetcher, ForeignKeyDef, InsertConflictBehavior, InsertRecord, JoinColumnDef,
+    MigrationError, MigrationOp, MigrationPolicy, OrderDirection, PageOffset, Query, QueryError,
+    TableColumns, TableError, TableRecord, TableSchema, Timestamp, TransactionError, TransactionId,
+    UpdateRecord, UpsertResult, VacuumReport, Value, ValuesSource, apply_json_patch,
 };
 use wasm_dbms_memory::RecordAddress;
 use wasm_dbms_memory::prelude::{
-    AccessControl, AccessControlList, MemoryAccess, MemoryProvider, NextRecord, TableRegistry,
+    AccessControl, AccessControlList, MemoryAccess, MemoryProvider, TableRegistry,
 };
 
+use self::blob::{hide_overflow_blobs, release_blobs, spill_blobs};
 use self::filter_analyzer::{IndexPlan, analyze_filter};
 use self::index_reader::{IndexReader, IndexSearchResult};
+use self::prepared_filter::{PreparedFilter, validate_in_lists};
+pub use self::select_iter::SelectIter;
 use crate::context::DbmsContext;
 use crate::database::migration::snapshots;
+use crate::integrity::check_batch_conflicts;
+use crate::primary_key::{row_key, row_key_components, row_key_filter, table_row_key};
 use crate::schema::DatabaseSchema;
 use crate::transaction::journal::{Journal, JournaledWriter};
 use crate::transaction::{DatabaseOverlay, Transaction, TransactionOp};
@@ -31,6 +41,20 @@ use crate::transaction::{DatabaseOverlay, Transaction, TransactionOp};
 /// Default capacity for SELECT queries.
 const DEFAULT_SELECT_CAPACITY: usize = 128;
 
+/// Maximum number of nested `force_delete` calls a cascade may reach.
+const MAX_CASCADE_DEPTH: usize = 64;
+
+/// State of the cascade delete in progress.
+#[derive(Default)]
+struct CascadeState {
+    /// Number of nested `force_delete` calls.
+    depth: usize,
+    /// `(table, primary key)` of the rows already claimed for deletion, so
+    /// that a cycle of foreign keys neither recurses forever nor deletes and
+    /// counts a row twice.
+    visited: HashSet<(&'static str, Value)>,
+}
+
 fn prime_drift_cache<M, A>(ctx: &DbmsContext<M, A>, schema: &dyn DatabaseSchema<M, A>)
 where
     M: MemoryProvider,
@@ -57,6 +81,11 @@ where
     schema: Box<dyn DatabaseSchema<M, A> + 'ctx>,
     /// Active transaction ID, if any.
     transaction: Option<TransactionId>,
+    /// Time frozen while a transaction commits, so that every operation
+    /// replayed by the commit stamps the same time.
+    commit_time: Option<u64>,
+    /// Cascade delete in progress.
+    cascade: RefCell<CascadeState>,
 }
 
 impl<'ctx, M, A> WasmDbmsDatabase<'ctx, M, A>
@@ -72,6 +101,8 @@ where
             ctx,
             schema,
             transaction: None,
+            commit_time: None,
+            cascade: RefCell::default(),
         }
     }
 
@@ -87,6 +118,8 @@ where
             ctx,
             schema,
             transaction: Some(transaction_id),
+            commit_time: None,
+            cascade: RefCell::default(),
         }
     }
 
@@ -204,6 +237,31 @@ where
         Ok(self.ctx.acl_identities())
     }
 
+    // --- Statistics -------------------------------------------------------
+
+    /// Returns the storage statistics of the table `T`.
+    ///
+    /// Statistics reflect committed data only: writes buffered in the open
+    /// transaction are not accounted for.
+    pub fn table_stats<T>(&self) -> DbmsResult<wasm_dbms_api::prelude::TableStats>
+    where
+        T: TableSchema,
+    {
+        self.ctx.table_stats_of(T::fingerprint())
+    }
+
+    /// Rewrites the records of the table named `table` contiguously, as
+    /// [`Database::defragment`] does for a typed table.
+    pub fn defragment_table(&self, table: &str) -> DbmsResult<u64> {
+        self.schema.defragment(self, table)
+    }
+
+    /// Releases at most `max_pages` record pages of the table named `table`,
+    /// as [`Database::compact`] does for a typed table.
+    pub fn compact_table(&self, table: &str, max_pages: u64) -> DbmsResult<CompactionReport> {
+        self.schema.compact(self, table, max_pages)
+    }
+
     /// Returns the cached drift flag, computing and caching it on first call.
     ///
     /// `O(tables × snapshot bytes)` on the first invocation; `O(1)` thereafter.
@@ -242,12 +300,15 @@ where
     ///
     /// When a journal is already active (e.g., inside [`Database::commit`]),
     /// this method delegates to the outer journal and does not manage its own.
+    /// Several [`Database`] writes run inside `f` are therefore applied, or
+    /// rolled back, together. Writes of a transactional instance are staged in
+    /// the transaction and are not journaled.
     ///
     /// # Panics
     ///
     /// Panics if the rollback itself fails, because a failed rollback leaves
     /// memory in an irrecoverably corrupt state (M-PANIC-ON-BUG).
-    fn atomic<F, R>(&self, f: F) -> DbmsResult<R>
+    pub fn atomic<F, R>(&self, f: F) -> DbmsResult<R>
     where
         F: FnOnce(&WasmDbmsDatabase<'ctx, M, A>) -> DbmsResult<R>,
     {
@@ -273,66 +334,192 @@ where
         }
     }
 
-    /// Checks whether any foreign key references exist for the given record.
+    /// Applies the `ON DELETE` action of every foreign key referencing the
+    /// given record: the action declared on the foreign key, or `behaviour`
+    /// for the foreign keys declaring none.
+    ///
+    /// Returns the number of referencing rows deleted or set to null.
     ///
-    /// Returns `true` if at least one referencing row exists in any table.
-    fn has_foreign_key_references<T>(
+    /// Foreign keys restricting the delete are skipped: callers check them
+    /// upfront with [`Self::has_referencing_rows`], before writing anything.
+    fn apply_on_delete<T>(
         &self,
+        behaviour: DeleteBehavior,
         record_values: &[(ColumnDef, Value)],
-    ) -> DbmsResult<bool>
+    ) -> DbmsResult<u64>
     where
         T: TableSchema,
     {
-        let pk = Self::extract_pk::<T>(record_values)?;
-
-        for (table, columns) in self.schema.referenced_tables(T::table_name()) {
-            for column in columns.iter() {
-                let filter = Filter::eq(column, pk.clone());
-                let query = Query::builder().field(column).filter(Some(filter)).build();
-                let rows = self.schema.select(self, table, query)?;
-                if !rows.is_empty() {
-                    return Ok(true);
+        let mut count = 0;
+        for (table, fk) in self.schema.referencing_foreign_keys(T::table_name()) {
+            let filter = Self::referencing_filter(&fk, record_values)?;
+            match fk.on_delete.unwrap_or(behaviour) {
+                DeleteBehavior::Cascade => {
+                    count += self.schema.force_delete(
+                        self,
+                        table,
+                        DeleteBehavior::Cascade,
+                        Some(filter),
+                    )?;
                 }
+                DeleteBehavior::SetNull => {
+                    count += self.set_null_foreign_key::<T>(table, fk, filter)?;
+                }
+                DeleteBehavior::Restrict => {}
             }
         }
-        Ok(false)
+        Ok(count)
     }
 
-    /// Deletes foreign key related records recursively for cascade deletes.
-    fn delete_foreign_keys_cascade<T>(
+    /// Returns the table and foreign key column of the first row, deleted
+    /// ones included, referencing the record of `T` with `record_values`
+    /// through a foreign key restricting its delete: one declaring
+    /// `Restrict`, or declaring no action when `behaviour` is `Restrict`.
+    ///
+    /// Read-only: used to reject a delete before any row is written.
+    fn has_referencing_rows<T>(
         &self,
         record_values: &[(ColumnDef, Value)],
-    ) -> DbmsResult<u64>
+        behaviour: DeleteBehavior,
+    ) -> DbmsResult<Option<(&'static str, &'static str)>>
     where
         T: TableSchema,
     {
-        let pk = Self::extract_pk::<T>(record_values)?;
+        for (table, fk) in self.schema.referencing_foreign_keys(T::table_name()) {
+            if fk.on_delete.unwrap_or(behaviour) != DeleteBehavior::Restrict {
+                continue;
+            }
+            let filter = Self::referencing_filter(&fk, record_values)?;
+            if self.is_referenced(table, fk.local_column, filter)? {
+                return Ok(Some((table, fk.local_column)));
+            }
+        }
+        Ok(None)
+    }
 
-        let mut count = 0;
+    /// Returns the filter matching the rows holding `fk` which reference the
+    /// record of `T` with `record_values`.
+    fn referencing_filter(
+        fk: &ForeignKeyDef,
+        record_values: &[(ColumnDef, Value)],
+    ) -> DbmsResult<Filter> {
+        fk.referencing_filter(record_values).ok_or_else(|| {
+            DbmsError::Query(QueryError::UnknownColumn(fk.foreign_columns.join(", ")))
+        })
+    }
+
+    /// Returns whether a row of `table`, deleted ones included, matches the
+    /// `filter` on the foreign key `column`.
+    fn is_referenced(
+        &self,
+        table: &'static str,
+        column: &'static str,
+        filter: Filter,
+    ) -> DbmsResult<bool> {
+        let query = Query::builder()
+            .field(column)
+            .include_deleted()
+            .filter(Some(filter))
+            .limit(1)
+            .build();
+        Ok(!self.schema.select(self, table, query)?.is_empty())
+    }
+
+    /// Fails with [`QueryError::ForeignKeyConstraintViolation`] if a row of
+    /// another table references any row of `T`.
+    fn ensure_not_referenced<T>(&self) -> DbmsResult<()>
+    where
+        T: TableSchema,
+    {
         for (table, columns) in self.schema.referenced_tables(T::table_name()) {
+            // rows referencing their own table are truncated with it
+            if table == T::table_name() {
+                continue;
+            }
             for column in columns.iter() {
-                let filter = Filter::eq(column, pk.clone());
-                let res = self
-                    .schema
-                    .delete(self, table, DeleteBehavior::Cascade, Some(filter))?;
-                count += res;
+                let query = Query::builder()
+                    .field(column)
+                    .include_deleted()
+                    .filter(Some(Filter::not_null(column)))
+                    .limit(1)
+                    .build();
+                if !self.schema.select(self, table, query)?.is_empty() {
+                    return Err(DbmsError::Query(
+                        QueryError::ForeignKeyConstraintViolation {
+                            referencing_table: table.to_string(),
+                            field: column.to_string(),
+                        },
+                    ));
+                }
             }
         }
-        Ok(count)
+        Ok(())
     }
 
-    /// Extracts the primary key value from a record's column-value pairs.
-    fn extract_pk<T>(record_values: &[(ColumnDef, Value)]) -> DbmsResult<Value>
+    /// Sets to null the foreign key `fk` of the rows of `table` matching
+    /// `filter`, which reference a deleted record of `T`.
+    ///
+    /// Every local column of the foreign key is set to null. Fails with
+    /// [`QueryError::ConstraintViolation`] if such a row exists and any of
+    /// those columns is not nullable.
+    fn set_null_foreign_key<T>(
+        &self,
+        table: &'static str,
+        fk: ForeignKeyDef,
+        filter: Filter,
+    ) -> DbmsResult<u64>
     where
         T: TableSchema,
     {
-        record_values
+        let snapshots = self.schema.compiled_snapshots_dyn();
+        let table_columns = snapshots
             .iter()
-            .find(|(col_def, _)| col_def.primary_key)
-            .ok_or(DbmsError::Query(QueryError::UnknownColumn(
-                T::primary_key().to_string(),
-            )))
-            .map(|(_, v)| v.clone())
+            .find(|snapshot| snapshot.name == table)
+            .map(|snapshot| snapshot.columns.as_slice())
+            .unwrap_or_default();
+        let non_nullable = fk.local_columns.iter().find(|column| {
+            !table_columns
+                .iter()
+                .find(|c| c.name == **column)
+                .is_some_and(|c| c.nullable)
+        });
+        if let Some(column) = non_nullable {
+            if self.is_referenced(table, column, filter)? {
+                return Err(DbmsError::Query(QueryError::ConstraintViolation(format!(
+                    "cannot set non-nullable foreign key '{table}.{column}' to null"
+                ))));
+            }
+            return Ok(0);
+        }
+
+        let null_patch = fk
+            .local_columns
+            .iter()
+            .zip(fk.foreign_columns)
+            .map(|(local, foreign)| {
+                let data_type = T::columns()
+                    .iter()
+                    .find(|col_def| col_def.name == *foreign)
+                    .map(|col_def| col_def.data_type)
+                    .ok_or_else(|| {
+                        DbmsError::Query(QueryError::UnknownColumn(foreign.to_string()))
+                    })?;
+                let col_def = ColumnDef {
+                    name: local,
+                    data_type,
+                    auto_increment: false,
+                    nullable: true,
+                    primary_key: false,
+                    unique: false,
+                    foreign_key: Some(fk),
+                    default: None,
+                    renamed_from: &[],
+                    compress: false,
+                };
+                Ok((col_def, Value::Null))
+            })
+            .collect::<DbmsResult<Vec<_>>>()?;
+        self.schema.update(self, table, &null_patch, Some(filter))
     }
 
     /// Retrieves the current overlay from the active transaction.
@@ -344,9 +531,9 @@ where
     fn record_matches_filter(
         &self,
         record_values: &[(ColumnDef, Value)],
-        filter: &Filter,
+        filter: &PreparedFilter,
     ) -> DbmsResult<bool> {
-        filter.matches(record_values).map_err(DbmsError::from)
+        filter.matches(record_values)
     }
 
     /// Removes duplicate records based on the values of the given columns.
@@ -385,7 +572,14 @@ where
         if query.all_selected() {
             return;
         }
-        let selected_columns = query.columns::<T>();
+        let mut selected_columns = query.columns::<T>();
+        // computed columns are evaluated from the columns they read
+        let dependencies: Vec<String> = T::computed_columns()
+            .iter()
+            .filter(|computed| selected_columns.iter().any(|col| col == computed.name))
+            .flat_map(|computed| computed.depends_on.iter().map(|col| col.to_string()))
+            .collect();
+        selected_columns.extend(dependencies);
         results
             .iter_mut()
             .flat_map(|record| record.iter_mut())
@@ -412,12 +606,19 @@ where
 
         for relation in &query.eager_relations {
             let fk_columns = Self::collect_fk_values::<T>(results, relation)?;
+            self.load_composite_relations::<T>(results, relation, query.eager_columns(relation))?;
 
             for (local_column, pk_values) in &fk_columns {
                 let batch_map = fetcher.fetch_batch(self, relation, pk_values)?;
 
                 Self::verify_fk_batch(&batch_map, pk_values, relation)?;
-                Self::attach_foreign_data(results, &batch_map, relation, local_column);
+                Self::attach_foreign_data(
+                    results,
+                    &batch_map,
+                    relation,
+                    local_column,
+                    query.eager_columns(relation),
+                );
             }
         }
 
@@ -425,6 +626,10 @@ where
     }
 
     /// Collects distinct FK values across all records for a given relation.
+    ///
+    /// Values keep the order in which they first appear in `results`, so a
+    /// broken reference is reported for the first record holding it. Null
+    /// values of nullable foreign keys are skipped, leaving the relation unset.
     fn collect_fk_values<T>(
         results: &[TableColumns],
         relation: &str,
@@ -432,7 +637,7 @@ where
     where
         T: TableSchema,
     {
-        let mut fk_columns: Vec<(&'static str, HashSet<Value>)> = vec![];
+        let mut fk_columns: Vec<(&'static str, Vec<Value>, HashSet<Value>)> = vec![];
 
         for record_columns in results {
             let Some(cols) = Self::this_columns(record_columns) else {
@@ -449,14 +654,25 @@ where
                 }
 
                 found_fk = true;
-                match fk_columns.iter_mut().find(|(lc, _)| *lc == fk.local_column) {
-                    Some((_, values)) => {
-                        values.insert(value.clone());
+                // composite keys are loaded by `load_composite_relations`
+                if value.is_null() || fk.is_composite() {
+                    continue;
+                }
+                match fk_columns
+                    .iter_mut()
+                    .find(|(lc, _, _)| *lc == fk.local_column)
+                {
+                    Some((_, values, seen)) => {
+                        if seen.insert(value.clone()) {
+                            values.push(value.clone());
+                        }
                     }
                     None => {
-                        let mut set = HashSet::new();
-                        set.insert(value.clone());
-                        fk_columns.push((fk.local_column, set));
+                        fk_columns.push((
+                            fk.local_column,
+                            vec![value.clone()],
+                            HashSet::from([value.clone()]),
+                        ));
                     }
                 }
             }
@@ -471,10 +687,71 @@ where
 
         Ok(fk_columns
             .into_iter()
-            .map(|(col, set)| (col, set.into_iter().collect()))
+            .map(|(col, values, _)| (col, values))
             .collect())
     }
 
+    /// Loads the relation of `T` to `relation` through its composite foreign
+    /// keys, fetching each distinct key once, keeping only the selected
+    /// `columns` when set.
+    ///
+    /// Records with a null foreign key column leave the relation unset.
+    fn load_composite_relations<T>(
+        &self,
+        results: &mut [TableColumns],
+        relation: &str,
+        columns: Option<&[String]>,
+    ) -> DbmsResult<()>
+    where
+        T: TableSchema,
+    {
+        let fetcher = T::foreign_fetcher();
+        for fk in T::columns()
+            .iter()
+            .filter_map(|col_def| col_def.foreign_key.as_ref())
+            .filter(|fk| fk.is_composite() && fk.foreign_table == relation)
+        {
+            let mut fetched: std::collections::HashMap<Vec<Value>, Vec<(ColumnDef, Value)>> =
+                std::collections::HashMap::new();
+            for record_columns in results.iter_mut() {
+                let Some(key) =
+                    Self::this_columns(record_columns).and_then(|cols| fk.local_values(cols))
+                else {
+                    continue;
+                };
+                let foreign_values = match fetched.get(&key) {
+                    Some(values) => values.clone(),
+                    None => {
+                        let values = fetcher
+                            .fetch(self, relation, fk.local_column, &key)?
+                            .pop()
+                            .map(|(_, values)| values)
+                            .unwrap_or_default();
+                        fetched.insert(key, values.clone());
+                        values
+                    }
+                };
+                let foreign_values = match columns {
+                    Some(columns) => foreign_values
+                        .into_iter()
+                        .filter(|(col_def, _)| columns.iter().any(|c| c == col_def.name))
+                        .collect(),
+                    None => foreign_values,
+                };
+
+                record_columns.push((
+                    ValuesSource::Foreign {
+                        table: relation.to_string(),
+                        column: fk.local_column.to_string(),
+                    },
+                    foreign_values,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verifies all FK values were found in the batch result.
     fn verify_fk_batch(
         batch_map: &std::collections::HashMap<Value, Vec<(ColumnDef, Value)>>,
@@ -490,12 +767,14 @@ where
         Ok(())
     }
 
-    /// Attaches batch-fetched foreign data to each record.
+    /// Attaches batch-fetched foreign data to each record, keeping only the
+    /// selected `columns` when set.
     fn attach_foreign_data(
         results: &mut [TableColumns],
         batch_map: &std::collections::HashMap<Value, Vec<(ColumnDef, Value)>>,
         relation: &str,
         local_column: &str,
+        columns: Option<&[String]>,
     ) {
         for record_columns in results.iter_mut() {
             let fk_value = Self::this_columns(record_columns).and_then(|cols| {
@@ -510,13 +789,21 @@ where
             let Some(foreign_values) = batch_map.get(&fk_val) else {
                 continue;
             };
+            let foreign_values = match columns {
+                Some(columns) => foreign_values
+                    .iter()
+                    .filter(|(col_def, _)| columns.iter().any(|c| c == col_def.name))
+                    .cloned()
+                    .collect(),
+                None => foreign_values.clone(),
+            };
 
             record_columns.push((
                 ValuesSource::Foreign {
                     table: relation.to_string(),
                     column: local_column.to_string(),
                 },
-                foreign_values.clone(),
+                foreign_values,
             ));
         }
     }
@@ -531,7 +818,7 @@ where
             .map(|(_, cols)| cols)
     }
 
-    /// Retrieves existing rows matching a filter, returning `(primary_key, full_row)` pairs.
+    /// Retrieves existing rows matching a filter, returning `(row_key, full_row)` pairs.
     #[expect(
         clippy::type_complexity,
         reason = "complex return type is necessary for returning both PK and full row data"
@@ -543,19 +830,13 @@ where
     where
         T: TableSchema,
     {
-        let pk = T::primary_key();
-        let query = Query::builder().filter(filter).build();
+        let query = Query::builder().include_deleted().filter(filter).build();
         let records = self.select::<T>(query)?;
         let rows = records
             .into_iter()
             .map(|record| {
                 let values = record.to_values();
-                let pk_value = values
-                    .iter()
-                    .find(|(col_def, _)| col_def.name == pk)
-                    .expect("primary key not found")
-                    .1
-                    .clone();
+                let pk_value = row_key(&values).expect("primary key not found");
                 (pk_value, values)
             })
             .collect();
@@ -574,24 +855,46 @@ where
             .ok_or(DbmsError::Table(TableError::TableNotFound))?;
 
         let mut mm = self.ctx.mm.borrow_mut();
-        TableRegistry::load(registry_pages, &mut *mm).map_err(DbmsError::from)
+        // the schema version only changes through migrations, which clear the
+        // cache, so each table is checked once
+        if self.ctx.is_schema_version_checked(T::fingerprint()) {
+            return TableRegistry::load(registry_pages, &mut *mm).map_err(DbmsError::from);
+        }
+        let registry = TableRegistry::load_checked::<T>(registry_pages, &mut *mm)?;
+        if !self.ctx.is_migrating() {
+            self.ctx.set_schema_version_checked(T::fingerprint());
+        }
+        Ok(registry)
     }
 
     /// Sorts query results by a column.
+    ///
+    /// `column` is either a local column name or a `relation.column` reference to an
+    /// eagerly loaded relation (see [`validate_order_by_relations`]).
     fn sort_query_results(
         &self,
         results: &mut [TableColumns],
         column: &str,
         direction: OrderDirection,
     ) {
+        let (relation, column) = match column.split_once('.') {
+            Some((relation, column)) => (Some(relation), column),
+            None => (None, column),
+        };
+
         results.sort_by(|a, b| {
             fn get_value<'a>(
                 values: &'a [(ValuesSource, Vec<(ColumnDef, Value)>)],
+                relation: Option<&str>,
                 column: &str,
             ) -> Option<&'a Value> {
                 values
                     .iter()
-                    .find(|(source, _)| *source == ValuesSource::This)
+                    .find(|(source, _)| match (source, relation) {
+                        (ValuesSource::This, None) => true,
+                        (ValuesSource::Foreign { table, .. }, Some(relation)) => table == relation,
+                        _ => false,
+                    })
                     .and_then(|(_, cols)| {
                         cols.iter()
                             .find(|(col_def, _)| col_def.name == column)
@@ -599,8 +902,8 @@ where
                     })
             }
 
-            let a_value = get_value(a, column);
-            let b_value = get_value(b, column);
+            let a_value = get_value(a, relation, column);
+            let b_value = get_value(b, relation, column);
 
             sort_values_with_direction(a_value, b_value, direction)
         });
@@ -660,6 +963,11 @@ where
         let Some(analyzed) = analyze_filter(filter, T::indexes()) else {
             return Ok(None);
         };
+        let remaining_filter = analyzed
+            .remaining_filter
+            .as_ref()
+            .map(PreparedFilter::new)
+            .transpose()?;
 
         let mut mm = self.ctx.mm.borrow_mut();
         let reader = IndexReader::new(
@@ -667,28 +975,32 @@ where
             table_overlay.index_overlay(T::table_name()),
         );
         let search_result = self.execute_index_plan(&reader, &analyzed.plan, &mut *mm)?;
+        let overlay = table_overlay.table_overlay(T::table_name());
 
         let mut indexed_rows = Vec::new();
-        let pk_name = T::primary_key();
 
         for address in &search_result.addresses {
             let record: T = table_registry
                 .read_at(*address, &mut *mm)
                 .map_err(DbmsError::from)?;
             let values = record.to_values();
-            let Some(pk) = values
-                .iter()
-                .find(|(column, _)| column.name == pk_name)
-                .map(|(_, value)| value)
-            else {
+            let Some(pk) = row_key(&values) else {
                 continue;
             };
 
-            if search_result.removed_pks.contains(pk) || search_result.overlay_pks.contains(pk) {
+            if search_result.removed_pks.contains(&pk) || search_result.overlay_pks.contains(&pk) {
                 continue;
             }
+            // updates of non-indexed columns are only in the table overlay
+            let values = match overlay {
+                Some(overlay) => match overlay.patch_row(values) {
+                    Some(values) => values,
+                    None => continue,
+                },
+                None => values,
+            };
 
-            if let Some(remaining_filter) = &analyzed.remaining_filter
+            if let Some(remaining_filter) = &remaining_filter
                 && !self.record_matches_filter(&values, remaining_filter)?
             {
                 continue;
@@ -697,22 +1009,18 @@ where
             indexed_rows.push(values);
         }
 
-        if let Some(overlay) = table_overlay.table_overlay(T::table_name()) {
+        if let Some(overlay) = overlay {
             let mut pending_overlay_pks = search_result.overlay_pks.clone();
 
             for row in overlay.iter_inserted() {
-                let Some(pk) = row
-                    .iter()
-                    .find(|(column, _)| column.name == pk_name)
-                    .map(|(_, value)| value)
-                else {
+                let Some(pk) = row_key(&row) else {
                     continue;
                 };
 
-                if !pending_overlay_pks.remove(pk) {
+                if !pending_overlay_pks.remove(&pk) {
                     continue;
                 }
-                if let Some(remaining_filter) = &analyzed.remaining_filter
+                if let Some(remaining_filter) = &remaining_filter
                     && !self.record_matches_filter(&row, remaining_filter)?
                 {
                     continue;
@@ -723,11 +1031,11 @@ where
 
             if !pending_overlay_pks.is_empty() {
                 let pk_reader = IndexReader::new(table_registry.index_ledger(), None);
-                let pk_columns = [T::primary_key()];
+                let pk_columns = T::primary_keys();
 
                 for pk in pending_overlay_pks {
-                    let pk_key = [pk];
-                    let pk_lookup = pk_reader.search_eq(&pk_columns, &pk_key, &mut *mm)?;
+                    let pk_key = row_key_components(pk_columns, &pk);
+                    let pk_lookup = pk_reader.search_eq(pk_columns, &pk_key, &mut *mm)?;
                     for address in pk_lookup.addresses {
                         let record: T = table_registry
                             .read_at(address, &mut *mm)
@@ -737,7 +1045,7 @@ where
                             continue;
                         };
 
-                        if let Some(remaining_filter) = &analyzed.remaining_filter
+                        if let Some(remaining_filter) = &remaining_filter
                             && !self.record_matches_filter(&patched_values, remaining_filter)?
                         {
                             continue;
@@ -759,6 +1067,21 @@ where
         T: TableSchema,
     {
         reject_aggregate_clauses(&query)?;
+        let mut query = query;
+        if let Some(filter) = &query.filter {
+            validate_in_lists(filter)?;
+            reject_computed_filter::<T>(filter)?;
+        }
+        validate_order_by_relations::<T>(&mut query)?;
+        validate_eager_columns::<T>(&query)?;
+        // A filter on related columns can only be evaluated once the eager
+        // relations are loaded, so the scan collects every row.
+        let relation_filter = if validate_filter_relations::<T>(&mut query)? {
+            query.filter.take()
+        } else {
+            None
+        };
+        let filter = query.filter.as_ref().map(PreparedFilter::new).transpose()?;
         let table_registry = self.load_table_registry::<T>()?;
         let mut table_overlay = if self.transaction.is_some() {
             self.overlay()?
@@ -767,26 +1090,45 @@ where
         };
 
         let mut results = Vec::with_capacity(query.limit.unwrap_or(DEFAULT_SELECT_CAPACITY));
-        // When ORDER BY or DISTINCT is present, LIMIT and OFFSET must be applied after
-        // sorting and deduplication to comply with standard SQL semantics
-        // (WHERE -> DISTINCT -> ORDER BY -> OFFSET -> LIMIT).
-        let has_order_by = !query.order_by.is_empty();
-        let has_distinct = !query.distinct_by.is_empty();
-        let defer_pagination = has_order_by || has_distinct;
-        let mut count = 0;
+        // Without ORDER BY or DISTINCT, the first OFFSET + LIMIT rows in primary
+        // key order are the whole result.
+        let scan_limit = query
+            .limit
+            .filter(|_| {
+                query.order_by.is_empty()
+                    && query.distinct_by.is_empty()
+                    && relation_filter.is_none()
+            })
+            .map(|limit| limit + query.offset.unwrap_or_default());
 
         if let Some(indexed_rows) =
             self.try_index_select::<T>(&query, &table_registry, &table_overlay)?
         {
-            for values in indexed_rows {
-                if !defer_pagination {
-                    count += 1;
-                    if query.offset.is_some_and(|offset| count <= offset) {
-                        continue;
-                    }
+            results.extend(
+                indexed_rows
+                    .into_iter()
+                    .map(|values| vec![(ValuesSource::This, values)]),
+            );
+        } else if self.transaction.is_none() {
+            // walk the primary key index, so rows come in primary key order
+            // and the scan can stop at `scan_limit`
+            let mut mm = self.ctx.mm.borrow_mut();
+            let mut walker = table_registry.index_ledger().range_scan(
+                T::primary_keys(),
+                &Vec::<Value>::new(),
+                None,
+                &mut *mm,
+            )?;
+            while let Some(address) = walker.next(&mut *mm)? {
+                let record: T = table_registry.read_at(address, &mut *mm)?;
+                let values = record.to_values();
+                if let Some(filter) = &filter
+                    && !self.record_matches_filter(&values, filter)?
+                {
+                    continue;
                 }
                 results.push(vec![(ValuesSource::This, values)]);
-                if !defer_pagination && query.limit.is_some_and(|limit| results.len() >= limit) {
+                if scan_limit.is_some_and(|limit| results.len() >= limit) {
                     break;
                 }
             }
@@ -796,194 +1138,449 @@ where
             let mut table_reader = table_overlay.reader(table_reader);
 
             while let Some(values) = table_reader.try_next()? {
-                if let Some(filter) = &query.filter
+                if let Some(filter) = &filter
                     && !self.record_matches_filter(&values, filter)?
                 {
                     continue;
                 }
-                if !defer_pagination {
-                    count += 1;
-                    if query.offset.is_some_and(|offset| count <= offset) {
-                        continue;
-                    }
-                }
                 results.push(vec![(ValuesSource::This, values)]);
-                if !defer_pagination && query.limit.is_some_and(|limit| results.len() >= limit) {
-                    break;
-                }
             }
         }
 
-        self.apply_distinct(&mut results, &query.distinct_by);
-        self.batch_load_eager_relations::<T>(&mut results, &query)?;
+        // Index lookups and the transaction overlay return rows in their own
+        // order: sort them by primary key, so results without ORDER BY are
+        // stable across writes and ORDER BY ties keep primary key order.
+        sort_by_primary_key::<T>(&mut results);
+
+        if let Some(filter) = &relation_filter {
+            self.batch_load_eager_relations::<T>(&mut results, &query)?;
+            let mut matching = Vec::with_capacity(results.len());
+            for record in results {
+                if filter.matches_record(&record)? {
+                    matching.push(record);
+                }
+            }
+            results = matching;
+            self.apply_distinct(&mut results, &query.distinct_by);
+        } else {
+            self.apply_distinct(&mut results, &query.distinct_by);
+            self.batch_load_eager_relations::<T>(&mut results, &query)?;
+        }
         self.apply_column_selection::<T>(&mut results, &query);
 
         for (column, direction) in query.order_by.into_iter().rev() {
             self.sort_query_results(&mut results, &column, direction);
         }
 
-        // Apply OFFSET and LIMIT after sorting/deduplication when deferred
-        if defer_pagination {
-            let offset = query.offset.unwrap_or_default();
-            if offset > 0 {
-                if offset >= results.len() {
-                    results.clear();
-                } else {
-                    results.drain(..offset);
-                }
+        // OFFSET and LIMIT apply last, to comply with standard SQL semantics
+        // (WHERE -> DISTINCT -> ORDER BY -> OFFSET -> LIMIT).
+        let offset = query.offset.unwrap_or_default();
+        if offset > 0 {
+            if offset >= results.len() {
+                results.clear();
+            } else {
+                results.drain(..offset);
             }
-            if let Some(limit) = query.limit {
-                results.truncate(limit);
+        }
+        if let Some(limit) = query.limit {
+            results.truncate(limit);
+        }
+
+        for record in &mut results {
+            for (_, values) in record.iter_mut() {
+                hide_overflow_blobs(values);
             }
         }
 
         Ok(results)
     }
 
-    /// Executes a join query.
-    fn select_join_inner(
-        &self,
-        table: &str,
-        query: Query,
-    ) -> DbmsResult<Vec<Vec<(JoinColumnDef, Value)>>> {
-        reject_aggregate_clauses(&query)?;
-        self.schema.select_join(self, table, query)
-    }
-
-    /// Updates primary key references in tables referencing the updated table.
-    fn update_pk_referencing_updated_table<T>(
+    /// Returns the next chunk of a chunked delete or update of the rows of
+    /// `T` matching `filter`, resuming after `token`: the filter matching
+    /// the rows of the chunk and the token of the chunk after it, or `None`
+    /// once no matching row is left.
+    ///
+    /// The chunk holds at most `max_rows` rows in primary key order, capped
+    /// by [`DbmsContext::set_max_chunk_rows`]; without either limit, it holds
+    /// every remaining row.
+    ///
+    /// Fails with [`QueryError::InvalidQuery`] if `max_rows` is zero or
+    /// `token` is malformed.
+    #[doc(hidden)]
+    pub fn next_chunk<T>(
         &self,
-        old_pk: Value,
-        new_pk: Value,
-        data_type: DataTypeKind,
-        pk_name: &'static str,
-    ) -> DbmsResult<u64>
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<&ContinuationToken>,
+    ) -> DbmsResult<Option<(Option<Filter>, Option<ContinuationToken>)>>
     where
         T: TableSchema,
     {
-        let mut count = 0;
-        for (ref_table, ref_col) in self
-            .schema
-            .referenced_tables(T::table_name())
-            .into_iter()
-            .flat_map(|(ref_table, ref_cols)| {
-                ref_cols
-                    .into_iter()
-                    .map(move |ref_col| (ref_table, ref_col))
-            })
-        {
-            let ref_patch_value = (
-                ColumnDef {
-                    name: ref_col,
-                    data_type,
-                    auto_increment: false,
-                    nullable: false,
-                    primary_key: false,
-                    unique: false,
-                    foreign_key: Some(ForeignKeyDef {
-                        foreign_table: T::table_name(),
-                        foreign_column: pk_name,
-                        local_column: ref_col,
-                    }),
-                    default: None,
-                    renamed_from: &[],
-                },
-                new_pk.clone(),
-            );
-            let filter = Filter::eq(ref_col, old_pk.clone());
-
-            count += self
-                .schema
-                .update(self, ref_table, &[ref_patch_value], Some(filter))?;
+        if max_rows == Some(0) {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(
+                "max_rows must be greater than zero".to_string(),
+            )));
         }
+        let max_rows = match (max_rows, self.ctx.max_chunk_rows.get()) {
+            (Some(rows), Some(limit)) => Some(rows.min(limit)),
+            (rows, limit) => rows.or(limit),
+        };
+        let Some(max_rows) = max_rows else {
+            return Ok(Some((ContinuationToken::resume::<T>(filter, token)?, None)));
+        };
 
-        Ok(count)
+        let query = ContinuationToken::chunk_query::<T>(filter.clone(), token, max_rows)?;
+        let keys = self.select::<T>(query)?;
+        let Some(end) = ContinuationToken::chunk_end::<T>(&keys) else {
+            return Ok(None);
+        };
+        let filter = end.chunk_filter::<T>(filter, token)?;
+        let next = (keys.len() as u64 >= max_rows).then_some(end);
+
+        Ok(Some((Some(filter), next)))
     }
 
-    /// Sanitizes values using the table schema's sanitizers.
-    fn sanitize_values<T>(
-        &self,
-        values: Vec<(ColumnDef, Value)>,
-    ) -> DbmsResult<Vec<(ColumnDef, Value)>>
+    /// Like [`Self::select_columns`], but hides the soft-deleted rows of `T`
+    /// unless the query opts into them with [`Query::include_deleted`].
+    #[doc(hidden)]
+    pub fn select_live_columns<T>(&self, query: Query) -> DbmsResult<Vec<TableColumns>>
     where
         T: TableSchema,
     {
-        let mut sanitized_values = Vec::with_capacity(values.len());
-        for (col_def, value) in values.into_iter() {
-            let value = match T::sanitizer(col_def.name) {
-                Some(sanitizer) => sanitizer.sanitize(value)?,
-                None => value,
-            };
-            sanitized_values.push((col_def, value));
-        }
-        Ok(sanitized_values)
+        self.select_columns::<T>(hide_deleted_rows::<T>(query))
     }
 
-    /// Collects all records matching a filter from the table registry.
-    #[allow(clippy::type_complexity)]
-    fn collect_matching_records<T>(
-        &self,
-        table_registry: &TableRegistry,
-        filter: &Option<Filter>,
-    ) -> DbmsResult<Vec<(NextRecord<T>, Vec<(ColumnDef, Value)>)>>
+    /// Executes a SELECT query on `T`, returning an iterator over its records.
+    ///
+    /// Unlike [`Database::select`], records are produced one at a time, so
+    /// callers can process them as they come and stop early without reading
+    /// the rest of the table. See [`SelectIter`] for when the scan is lazy.
+    pub fn select_iter<T>(&self, query: Query) -> DbmsResult<SelectIter<'_, 'ctx, T, M, A>>
     where
         T: TableSchema,
     {
-        let mut mm = self.ctx.mm.borrow_mut();
+        self.ensure_no_drift()?;
+        if !query.joins.is_empty() {
+            return Err(DbmsError::Query(QueryError::JoinInsideTypedSelect));
+        }
+        let mut query = hide_deleted_rows::<T>(query);
+        reject_aggregate_clauses(&query)?;
+        if let Some(filter) = &query.filter {
+            reject_computed_filter::<T>(filter)?;
+        }
+        validate_eager_columns::<T>(&query)?;
+        let filters_relations = validate_filter_relations::<T>(&mut query)?;
+        let filter = query.filter.as_ref().map(PreparedFilter::new).transpose()?;
+
+        // ORDER BY, DISTINCT and filters on related columns need the whole
+        // result set, while an index lookup already narrows it down to the
+        // matching rows. Inside a transaction the overlay rows must be merged
+        // into primary key order, which the index walk cannot do lazily.
+        let needs_full_result = self.transaction.is_some()
+            || !query.order_by.is_empty()
+            || !query.distinct_by.is_empty()
+            || filters_relations
+            || query
+                .filter
+                .as_ref()
+                .is_some_and(|filter| analyze_filter(filter, T::indexes()).is_some());
+        if needs_full_result {
+            let results = self.select_columns::<T>(query.clone())?;
+            return Ok(SelectIter::materialized(self, query, results));
+        }
 
-        // `collect_matching_records` is only used by the non-transactional update/delete paths.
-        // Transactional mutations first resolve rows via `existing_rows_for_filter`, which reads
-        // through `select()` and therefore includes the overlay. Using `overlay = None` here is
-        // intentional because the atomic write path is operating on committed storage only.
-        if let Some(filter) = filter
-            && let Some(analyzed) = analyze_filter(filter, T::indexes())
-        {
-            let reader = IndexReader::new(table_registry.index_ledger(), None);
-            let search_result = self.execute_index_plan(&reader, &analyzed.plan, &mut *mm)?;
+        let table_registry = self.load_table_registry::<T>()?;
+        SelectIter::scan(self, query, filter, table_registry)
+    }
 
-            let mut records = Vec::new();
-            for address in search_result.addresses {
-                let record: T = table_registry
-                    .read_at(address, &mut *mm)
-                    .map_err(DbmsError::from)?;
-                let record_values = record.clone().to_values();
-                if let Some(remaining_filter) = &analyzed.remaining_filter
-                    && !self.record_matches_filter(&record_values, remaining_filter)?
-                {
-                    continue;
-                }
-                records.push((
-                    NextRecord {
-                        record,
-                        page: address.page,
-                        offset: address.offset,
-                    },
-                    record_values,
-                ));
-            }
+    /// Counts the rows of `T` matching `filter`, honouring the transaction
+    /// overlay, without building records or loading relations.
+    fn count_rows<T>(&self, filter: Option<Filter>) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        let table_registry = self.load_table_registry::<T>()?;
+        let mut table_overlay = if self.transaction.is_some() {
+            self.overlay()?
+        } else {
+            DatabaseOverlay::default()
+        };
 
-            return Ok(records);
+        let prepared_filter = filter.as_ref().map(PreparedFilter::new).transpose()?;
+        let mut query = Query::default();
+        query.filter = filter;
+        if let Some(indexed_rows) =
+            self.try_index_select::<T>(&query, &table_registry, &table_overlay)?
+        {
+            return Ok(indexed_rows.len() as u64);
         }
 
-        let mut table_reader = table_registry.read::<T, _>(&mut *mm);
-        let mut records = vec![];
+        let mut mm = self.ctx.mm.borrow_mut();
+        let table_reader = table_registry.read::<T, _>(&mut *mm);
+        let mut table_reader = table_overlay.reader(table_reader);
+
+        let mut count = 0;
         while let Some(values) = table_reader.try_next()? {
-            let record_values = values.record.clone().to_values();
-            if let Some(filter) = filter
-                && !self.record_matches_filter(&record_values, filter)?
+            if let Some(filter) = &prepared_filter
+                && !self.record_matches_filter(&values, filter)?
             {
                 continue;
             }
-            records.push((values, record_values));
+            count += 1;
         }
-        Ok(records)
+
+        Ok(count)
     }
 
-    /// For each indexed column for the table, inserts the index for the given record address.
-    fn insert_index<T>(
-        &self,
-        table_registry: &mut TableRegistry,
-        record_address: RecordAddress,
+    /// Returns whether any row of `T` matches `filter`, honouring the
+    /// transaction overlay and stopping at the first match.
+    fn exists_row<T>(&self, filter: Filter) -> DbmsResult<bool>
+    where
+        T: TableSchema,
+    {
+        let table_registry = self.load_table_registry::<T>()?;
+        let mut table_overlay = if self.transaction.is_some() {
+            self.overlay()?
+        } else {
+            DatabaseOverlay::default()
+        };
+
+        let prepared_filter = PreparedFilter::new(&filter)?;
+        let mut query = Query::default();
+        query.filter = Some(filter);
+        if let Some(indexed_rows) =
+            self.try_index_select::<T>(&query, &table_registry, &table_overlay)?
+        {
+            return Ok(!indexed_rows.is_empty());
+        }
+
+        let mut mm = self.ctx.mm.borrow_mut();
+        let table_reader = table_registry.read::<T, _>(&mut *mm);
+        let mut table_reader = table_overlay.reader(table_reader);
+
+        while let Some(values) = table_reader.try_next()? {
+            if self.record_matches_filter(&values, &prepared_filter)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Executes a join query.
+    fn select_join_inner(
+        &self,
+        table: &str,
+        query: Query,
+    ) -> DbmsResult<Vec<Vec<(JoinColumnDef, Value)>>> {
+        reject_aggregate_clauses(&query)?;
+        if let Some(filter) = &query.filter {
+            validate_in_lists(filter)?;
+        }
+        self.schema.select_join(self, table, query)
+    }
+
+    /// Rewrites the foreign keys referencing the record of `T` whose
+    /// referenced columns changed from `old_values` to `new_values`, returning
+    /// the number of referencing rows updated.
+    fn update_keys_referencing_updated_record<T>(
+        &self,
+        old_values: &[(ColumnDef, Value)],
+        new_values: &[(ColumnDef, Value)],
+    ) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        let mut count = 0;
+        for (ref_table, fk) in self.schema.referencing_foreign_keys(T::table_name()) {
+            let Some(patch) = Self::referencing_key_patch(&fk, old_values, new_values) else {
+                continue;
+            };
+            let filter = Self::referencing_filter(&fk, old_values)?;
+            count += self.schema.update(self, ref_table, &patch, Some(filter))?;
+        }
+
+        Ok(count)
+    }
+
+    /// Returns the patch writing into the local columns of `fk` the values
+    /// of its foreign columns in `new_values`, or `None` if none of them
+    /// changed from `old_values`.
+    fn referencing_key_patch(
+        fk: &ForeignKeyDef,
+        old_values: &[(ColumnDef, Value)],
+        new_values: &[(ColumnDef, Value)],
+    ) -> Option<Vec<(ColumnDef, Value)>> {
+        let value_of = |values: &[(ColumnDef, Value)], column: &str| {
+            values
+                .iter()
+                .find(|(col_def, _)| col_def.name == column)
+                .cloned()
+        };
+        let changed = fk
+            .foreign_columns
+            .iter()
+            .any(|column| value_of(old_values, column) != value_of(new_values, column));
+        if !changed {
+            return None;
+        }
+
+        fk.local_columns
+            .iter()
+            .zip(fk.foreign_columns)
+            .map(|(local, foreign)| {
+                let (foreign_def, value) = value_of(new_values, foreign)?;
+                let col_def = ColumnDef {
+                    name: local,
+                    data_type: foreign_def.data_type,
+                    auto_increment: false,
+                    nullable: false,
+                    primary_key: false,
+                    unique: false,
+                    foreign_key: Some(ForeignKeyDef {
+                        on_delete: None,
+                        deferred: false,
+                        ..*fk
+                    }),
+                    default: None,
+                    renamed_from: &[],
+                    compress: false,
+                };
+                Some((col_def, value))
+            })
+            .collect()
+    }
+
+    /// Counts the rows, deleted ones included, whose foreign keys a cascade
+    /// from `old_values` to `new_values` of a record of `T` would rewrite.
+    fn count_key_references<T>(
+        &self,
+        old_values: &[(ColumnDef, Value)],
+        new_values: &[(ColumnDef, Value)],
+    ) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        let mut count = 0;
+        for (ref_table, fk) in self.schema.referencing_foreign_keys(T::table_name()) {
+            if Self::referencing_key_patch(&fk, old_values, new_values).is_none() {
+                continue;
+            }
+            let query = Query::builder()
+                .field(fk.local_column)
+                .include_deleted()
+                .filter(Some(Self::referencing_filter(&fk, old_values)?))
+                .build();
+            count += self.schema.select(self, ref_table, query)?.len() as u64;
+        }
+
+        Ok(count)
+    }
+
+    /// Sanitizes values using the table schema's sanitizers, in declaration order.
+    fn sanitize_values<T>(
+        &self,
+        values: Vec<(ColumnDef, Value)>,
+    ) -> DbmsResult<Vec<(ColumnDef, Value)>>
+    where
+        T: TableSchema,
+    {
+        let mut sanitized_values = Vec::with_capacity(values.len());
+        for (col_def, mut value) in values.into_iter() {
+            for sanitizer in T::sanitizer(col_def.name) {
+                value = sanitizer.sanitize(value)?;
+            }
+            sanitized_values.push((col_def, value));
+        }
+        Ok(sanitized_values)
+    }
+
+    /// Collects the addresses and values of all records matching a filter
+    /// from the table registry.
+    ///
+    /// Records are decoded from the page buffer of the table reader and
+    /// converted to values once; rejected records are dropped right away.
+    #[allow(clippy::type_complexity)]
+    fn collect_matching_records<T>(
+        &self,
+        table_registry: &TableRegistry,
+        filter: &Option<Filter>,
+    ) -> DbmsResult<Vec<(RecordAddress, Vec<(ColumnDef, Value)>)>>
+    where
+        T: TableSchema,
+    {
+        let prepared_filter = filter.as_ref().map(PreparedFilter::new).transpose()?;
+        let mut mm = self.ctx.mm.borrow_mut();
+
+        // `collect_matching_records` is only used by the non-transactional update/delete paths.
+        // Transactional mutations first resolve rows via `existing_rows_for_filter`, which reads
+        // through `select()` and therefore includes the overlay. Using `overlay = None` here is
+        // intentional because the atomic write path is operating on committed storage only.
+        if let Some(filter) = filter
+            && let Some(analyzed) = analyze_filter(filter, T::indexes())
+        {
+            let remaining_filter = analyzed
+                .remaining_filter
+                .as_ref()
+                .map(PreparedFilter::new)
+                .transpose()?;
+            let reader = IndexReader::new(table_registry.index_ledger(), None);
+            let search_result = self.execute_index_plan(&reader, &analyzed.plan, &mut *mm)?;
+
+            let mut records = Vec::new();
+            for address in search_result.addresses {
+                let record: T = table_registry
+                    .read_at(address, &mut *mm)
+                    .map_err(DbmsError::from)?;
+                let record_values = record.to_values();
+                if let Some(remaining_filter) = &remaining_filter
+                    && !self.record_matches_filter(&record_values, remaining_filter)?
+                {
+                    continue;
+                }
+                records.push((address, record_values));
+            }
+
+            return Ok(records);
+        }
+
+        let mut table_reader = table_registry.read::<T, _>(&mut *mm);
+        let mut records = vec![];
+        while let Some(record) = table_reader.try_next_ref()? {
+            let address = RecordAddress::new(record.page, record.offset);
+            let record_values = record.decode()?.to_values();
+            if let Some(filter) = &prepared_filter
+                && !self.record_matches_filter(&record_values, filter)?
+            {
+                continue;
+            }
+            records.push((address, record_values));
+        }
+        Ok(records)
+    }
+
+    /// Returns the stored record of `T` with primary key `pk`, with the large
+    /// blobs as their overflow reference.
+    ///
+    /// Fails with [`QueryError::RecordNotFound`] if there is no such record.
+    fn stored_row<T>(
+        &self,
+        table_registry: &TableRegistry,
+        pk: &Value,
+    ) -> DbmsResult<(RecordAddress, Vec<(ColumnDef, Value)>)>
+    where
+        T: TableSchema,
+    {
+        self.collect_matching_records::<T>(table_registry, &Some(row_key_filter::<T>(pk)))?
+            .pop()
+            .ok_or(DbmsError::Query(QueryError::RecordNotFound))
+    }
+
+    /// For each indexed column for the table, inserts the index for the given record address.
+    fn insert_index<T>(
+        &self,
+        table_registry: &mut TableRegistry,
+        record_address: RecordAddress,
         values: &[(ColumnDef, Value)],
         mm: &mut impl wasm_dbms_memory::MemoryAccess,
     ) -> DbmsResult<()>
@@ -1054,32 +1651,403 @@ where
         Ok(())
     }
 
-    /// Fills in auto-increment values for columns that are missing from the input.
-    fn fill_auto_increment_values<T>(
+    /// Inserts `record` into table `T`, returning the values written after
+    /// auto-increment and sanitization.
+    ///
+    /// For an `#[on_conflict = "update"]` table, a record whose primary key
+    /// already exists updates that row instead, and its values are returned.
+    #[doc(hidden)]
+    pub fn insert_values<T>(&self, record: T::Insert) -> DbmsResult<Vec<(ColumnDef, Value)>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+    {
+        self.ensure_no_drift()?;
+        if let Some(patch) = T::on_conflict_update(&record)
+            && let Some(pk_filter) = patch.where_clause()
+            && self.exists_row::<T>(pk_filter)?
+        {
+            let (_, mut rows) = self.update_values::<T>(patch)?;
+            return Ok(rows.pop().unwrap_or_default());
+        }
+        self.insert_new_values::<T>(record)
+    }
+
+    /// Inserts `record` into table `T`, failing on a primary key conflict
+    /// whatever the table's `on_conflict` action.
+    fn insert_new_values<T>(&self, record: T::Insert) -> DbmsResult<Vec<(ColumnDef, Value)>>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+    {
+        let mut table_registry = self.load_table_registry::<T>()?;
+        let mut record_values = record.clone().into_values();
+        self.stamp_auto_timestamps::<T>(&mut record_values, AutoTimestamp::Insert);
+        let record_values = reserve_auto_increment_values::<T>(&mut table_registry, record_values)?;
+        let sanitized_values = self.sanitize_values::<T>(record_values)?;
+        self.schema
+            .validate_insert(self, T::table_name(), &sanitized_values)?;
+        if self.transaction.is_some() {
+            table_registry.save_autoincrement(&mut *self.ctx.mm.borrow_mut())?;
+            self.with_transaction_mut(|tx| tx.insert::<T>(sanitized_values.clone()))?;
+        } else {
+            self.atomic(|db| {
+                let mut mm = db.ctx.mm.borrow_mut();
+                // update journal with the insert operation before mutating memory
+                let mut journal_ref = db.ctx.journal.borrow_mut();
+                let journal = journal_ref
+                    .as_mut()
+                    .expect("journal must be active inside atomic");
+                let mut writer = JournaledWriter::new(&mut *mm, journal);
+                table_registry.save_autoincrement(&mut writer)?;
+                // move the large blobs to overflow pages before encoding the record
+                let mut stored_values = sanitized_values.clone();
+                spill_blobs(
+                    &mut stored_values,
+                    None,
+                    db.ctx.max_blob_size.get(),
+                    &mut writer,
+                )?;
+                let record = T::Insert::record_from_values(&stored_values)?;
+                // insert the record in the table registry, and eventually update the indexes
+                let record_address = table_registry
+                    .insert(record, &mut writer)
+                    .map_err(DbmsError::from)?;
+                self.insert_index::<T>(
+                    &mut table_registry,
+                    record_address,
+                    &stored_values,
+                    &mut writer,
+                )?;
+                Ok(())
+            })?;
+        }
+
+        Ok(sanitized_values)
+    }
+
+    /// Updates the rows of table `T` matching the patch's `where_clause`.
+    ///
+    /// Returns the number of updated rows, including rows of other tables
+    /// updated by a primary key cascade, and the values of the updated rows of
+    /// `T`: as written outside a transaction, as seen through the overlay
+    /// inside one.
+    #[doc(hidden)]
+    #[expect(
+        clippy::type_complexity,
+        reason = "returns both the affected row count and the updated rows"
+    )]
+    pub fn update_values<T>(
         &self,
-        table_registry: &mut TableRegistry,
-        mut values: Vec<(ColumnDef, Value)>,
-    ) -> DbmsResult<Vec<(ColumnDef, Value)>>
+        patch: T::Update,
+    ) -> DbmsResult<(u64, Vec<Vec<(ColumnDef, Value)>>)>
     where
         T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
     {
-        let mut mm = self.ctx.mm.borrow_mut();
-        // iter over auto-increment columns, for each of them check if the value is provided, if not get the next auto-increment value.
-        for auto_increment_column in T::columns().iter().filter(|col| col.auto_increment) {
-            if values
-                .iter()
-                .any(|(col_def, _)| col_def.name == auto_increment_column.name)
+        self.update_patch_values::<T>(patch.update_values(), patch.where_clause())
+    }
+
+    /// Updates the rows of `T` from the raw column values of a schema
+    /// dispatch.
+    ///
+    /// The values are read through [`UpdateRecord::from_values`], while the
+    /// [`Value::JsonPatch`] values it can't carry are kept as they are.
+    #[doc(hidden)]
+    #[expect(
+        clippy::type_complexity,
+        reason = "returns both the affected row count and the updated rows"
+    )]
+    pub fn update_from_values<T>(
+        &self,
+        patch_values: &[(ColumnDef, Value)],
+        filter: Option<Filter>,
+    ) -> DbmsResult<(u64, Vec<Vec<(ColumnDef, Value)>>)>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        let patch = T::Update::from_values(patch_values, filter);
+        let mut values = patch.update_values();
+        for (col_def, value) in patch_values {
+            if !matches!(value, Value::JsonPatch(_))
+                || values
+                    .iter()
+                    .any(|(patched, _)| patched.name == col_def.name)
             {
                 continue;
             }
-            let next_value = table_registry
-                .next_autoincrement(auto_increment_column.name, &mut *mm)?
-                .ok_or(DbmsError::Table(TableError::SchemaMismatch))?;
-            values.push((*auto_increment_column, next_value));
+            if let Some(column) = T::columns()
+                .iter()
+                .find(|column| column.name == col_def.name)
+            {
+                values.push((*column, value.clone()));
+            }
+        }
+
+        self.update_patch_values::<T>(values, patch.where_clause())
+    }
+
+    /// Writes `patch_values` into the rows of `T` matching `filter`.
+    ///
+    /// A [`Value::JsonPatch`] is applied to the stored document of each row.
+    #[expect(
+        clippy::type_complexity,
+        reason = "returns both the affected row count and the updated rows"
+    )]
+    fn update_patch_values<T>(
+        &self,
+        mut patch_values: Vec<(ColumnDef, Value)>,
+        filter: Option<Filter>,
+    ) -> DbmsResult<(u64, Vec<Vec<(ColumnDef, Value)>>)>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        self.ensure_no_drift()?;
+        self.stamp_auto_timestamps::<T>(&mut patch_values, AutoTimestamp::Update);
+        if self.transaction.is_some() {
+            let rows = self.existing_rows_for_filter::<T>(filter.clone())?;
+            let updated_rows = rows
+                .iter()
+                .map(|(_, row)| {
+                    let mut row = row.clone();
+                    apply_patch(&mut row, &patch_values)?;
+                    self.sanitize_values::<T>(row)
+                })
+                .collect::<DbmsResult<Vec<_>>>()?;
+            // count what the commit replay will change: the matching rows,
+            // plus the rows its key cascade will rewrite
+            let mut count = rows.len() as u64;
+            for ((_, row), updated_row) in rows.iter().zip(&updated_rows) {
+                count += self.count_key_references::<T>(row, updated_row)?;
+            }
+            self.with_transaction_mut(|tx| tx.update::<T>(patch_values, filter, rows))?;
+
+            return Ok((count, updated_rows));
+        }
+
+        let patch = patch_values;
+
+        self.atomic(|db| {
+            let mut count = 0;
+            let mut updated_rows = Vec::new();
+
+            let mut table_registry = db.load_table_registry::<T>()?;
+            let records = db.collect_matching_records::<T>(&table_registry, &filter)?;
+
+            for (old_address, record_values) in records {
+                let current_pk_value = row_key(&record_values).expect("primary key not found");
+
+                let previous_record = values_to_schema_entity::<T>(record_values.clone())?;
+                let old_values_for_index = record_values.clone();
+                let mut record_values = record_values;
+                apply_patch(&mut record_values, &patch)?;
+                let record_values = db.sanitize_values::<T>(record_values)?;
+                db.schema
+                    .validate_update(db, T::table_name(), &record_values, current_pk_value)?;
+                {
+                    let mut mm = db.ctx.mm.borrow_mut();
+                    // update journal with the update operation before mutating memory
+                    let mut journal_ref = db.ctx.journal.borrow_mut();
+                    let journal = journal_ref
+                        .as_mut()
+                        .expect("journal must be active inside atomic");
+                    let mut writer = JournaledWriter::new(&mut *mm, journal);
+                    // move the replaced large blobs to overflow pages
+                    let mut stored_values = record_values.clone();
+                    spill_blobs(
+                        &mut stored_values,
+                        Some(&old_values_for_index),
+                        db.ctx.max_blob_size.get(),
+                        &mut writer,
+                    )?;
+                    let updated_record = values_to_schema_entity::<T>(stored_values.clone())?;
+                    // update table registry
+                    let new_address = table_registry
+                        .update(updated_record, previous_record, old_address, &mut writer)
+                        .map_err(DbmsError::from)?;
+                    // update indexes if needed
+                    self.update_index::<T>(
+                        &mut table_registry,
+                        old_address,
+                        new_address,
+                        &old_values_for_index,
+                        &stored_values,
+                        &mut writer,
+                    )?;
+                }
+                count += 1;
+                count += db.update_keys_referencing_updated_record::<T>(
+                    &old_values_for_index,
+                    &record_values,
+                )?;
+                let mut record_values = record_values;
+                hide_overflow_blobs(&mut record_values);
+                updated_rows.push(record_values);
+            }
+
+            Ok((count, updated_rows))
+        })
+    }
+
+    /// Records an upsert of `values`, whose row key is `pk`, in the active
+    /// transaction.
+    ///
+    /// The record is sanitized and validated now, as an insert or as an update
+    /// of the row visible through the overlay, so errors surface before the
+    /// commit.
+    fn upsert_in_transaction<T>(
+        &self,
+        mut values: Vec<(ColumnDef, Value)>,
+        pk: Value,
+        pk_filter: Filter,
+    ) -> DbmsResult<UpsertResult>
+    where
+        T: TableSchema,
+    {
+        let existing = self.existing_rows_for_filter::<T>(Some(pk_filter))?.pop();
+        let event = match existing {
+            Some(_) => AutoTimestamp::Update,
+            None => AutoTimestamp::Insert,
+        };
+        self.stamp_auto_timestamps::<T>(&mut values, event);
+        let mut table_registry = None;
+        if existing.is_none() {
+            let registry = table_registry.insert(self.load_table_registry::<T>()?);
+            values = reserve_auto_increment_values::<T>(registry, values)?;
+        }
+        let values = self.sanitize_values::<T>(values)?;
+
+        let result = if existing.is_some() {
+            self.schema
+                .validate_update(self, T::table_name(), &values, pk)?;
+            UpsertResult::Updated
+        } else {
+            self.schema
+                .validate_insert(self, T::table_name(), &values)?;
+            UpsertResult::Inserted
+        };
+        if let Some(table_registry) = table_registry {
+            table_registry.save_autoincrement(&mut *self.ctx.mm.borrow_mut())?;
+        }
+        self.with_transaction_mut(|tx| tx.upsert::<T>(values, existing))?;
+
+        Ok(result)
+    }
+
+    /// Returns the current time, frozen for the duration of a commit.
+    fn now(&self) -> u64 {
+        self.commit_time.unwrap_or_else(|| self.ctx.now())
+    }
+
+    /// Sets the `#[auto_timestamp]` columns of `T` stamped on `event` to the
+    /// current time in `values`.
+    ///
+    /// On [`AutoTimestamp::Insert`] every auto timestamp is set, as the
+    /// `on = "update"` ones need a value for the new row as well.
+    fn stamp_auto_timestamps<T>(&self, values: &mut Vec<(ColumnDef, Value)>, event: AutoTimestamp)
+    where
+        T: TableSchema,
+    {
+        let stamped_columns = T::auto_timestamps()
+            .iter()
+            .filter(|(_, on)| event == AutoTimestamp::Insert || *on == event);
+        let mut now = None;
+        for (column, _) in stamped_columns {
+            let Some(col_def) = T::columns().iter().find(|col| col.name == *column) else {
+                continue;
+            };
+            let now = *now.get_or_insert_with(|| DateTime::from(Timestamp(self.now())));
+            values.retain(|(col, _)| col.name != *column);
+            values.push((*col_def, Value::DateTime(now)));
         }
+    }
+}
 
-        Ok(values)
+/// Fills in the auto-increment values missing from `values`, reserving them
+/// on `table_registry`.
+///
+/// Explicitly provided values are kept, but raise the column counter so that
+/// later generated values never collide with them. The counters are only
+/// persisted by [`TableRegistry::save_autoincrement`], once the insert is
+/// validated, so a rejected insert does not burn values.
+fn reserve_auto_increment_values<T>(
+    table_registry: &mut TableRegistry,
+    mut values: Vec<(ColumnDef, Value)>,
+) -> DbmsResult<Vec<(ColumnDef, Value)>>
+where
+    T: TableSchema,
+{
+    for auto_increment_column in T::columns().iter().filter(|col| col.auto_increment) {
+        if let Some((_, value)) = values
+            .iter()
+            .find(|(col_def, _)| col_def.name == auto_increment_column.name)
+        {
+            table_registry.reserve_autoincrement(auto_increment_column.name, Some(value))?;
+            continue;
+        }
+        let next_value = table_registry
+            .reserve_autoincrement(auto_increment_column.name, None)?
+            .ok_or(DbmsError::Table(TableError::SchemaMismatch))?;
+        values.push((*auto_increment_column, next_value));
     }
+
+    Ok(values)
+}
+
+/// Fills in the auto-increment values missing from `values` with the ones an
+/// insert would generate, without advancing the counters.
+fn peek_auto_increment_values<T>(
+    table_registry: &TableRegistry,
+    mut values: Vec<(ColumnDef, Value)>,
+) -> DbmsResult<Vec<(ColumnDef, Value)>>
+where
+    T: TableSchema,
+{
+    for auto_increment_column in T::columns().iter().filter(|col| col.auto_increment) {
+        if values
+            .iter()
+            .any(|(col_def, _)| col_def.name == auto_increment_column.name)
+        {
+            continue;
+        }
+        let next_value = table_registry
+            .peek_autoincrement(auto_increment_column.name)?
+            .ok_or(DbmsError::Table(TableError::SchemaMismatch))?;
+        values.push((*auto_increment_column, next_value));
+    }
+
+    Ok(values)
+}
+
+/// ANDs the soft-delete predicate of `T` into `filter`, so that only rows
+/// not logically deleted match. Returns `filter` unchanged for tables
+/// without a `#[deleted_at]` column.
+fn live_rows_filter<T>(filter: Option<Filter>) -> Option<Filter>
+where
+    T: TableSchema,
+{
+    let Some(column) = T::soft_delete_column() else {
+        return filter;
+    };
+    let live = Filter::is_null(column);
+    Some(match filter {
+        Some(filter) => filter.and(live),
+        None => live,
+    })
+}
+
+/// Applies [`live_rows_filter`] to `query`, unless it opted into
+/// soft-deleted rows via [`Query::include_deleted`].
+fn hide_deleted_rows<T>(mut query: Query) -> Query
+where
+    T: TableSchema,
+{
+    if !query.include_deleted {
+        query.filter = live_rows_filter::<T>(query.filter.take());
+    }
+    query
 }
 
 /// Rejects queries that carry `GROUP BY` or `HAVING` on a non-aggregate path.
@@ -1096,6 +2064,167 @@ fn reject_aggregate_clauses(query: &Query) -> DbmsResult<()> {
     Ok(())
 }
 
+/// Rejects filters on the computed columns of `T`, which are not stored.
+fn reject_computed_filter<T>(filter: &Filter) -> DbmsResult<()>
+where
+    T: TableSchema,
+{
+    let mut filter = filter.clone();
+    if let Some(column) = filter.fields_mut().into_iter().find(|column| {
+        T::computed_columns()
+            .iter()
+            .any(|computed| computed.name == column.as_str())
+    }) {
+        return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "cannot filter on computed column `{column}`"
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Validates `relation.column` keys in `ORDER BY`.
+///
+/// Keys qualified with the queried table's own name are rewritten to the bare
+/// column name. Any other relation must be eagerly loaded with
+/// [`QueryBuilder::with`](wasm_dbms_api::prelude::QueryBuilder::with), since
+/// sorting reads the value from the loaded foreign columns.
+fn validate_order_by_relations<T>(query: &mut Query) -> DbmsResult<()>
+where
+    T: TableSchema,
+{
+    for (key, _) in &mut query.order_by {
+        let Some((relation, column)) = key.split_once('.') else {
+            continue;
+        };
+        if relation == T::table_name() {
+            *key = column.to_string();
+            continue;
+        }
+        if !query.eager_relations.iter().any(|r| r == relation) {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                "Cannot order by '{key}': relation '{relation}' must be eagerly loaded with `.with(\"{relation}\")`"
+            ))));
+        }
+        if let Some((_, columns)) = query.eager_columns.iter().find(|(r, _)| r == relation)
+            && !columns.iter().any(|c| c == column)
+        {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                "Cannot order by '{key}': column '{column}' is not selected for relation '{relation}'"
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Validates the `relation.column` fields of the query filter, returning
+/// whether the filter references any related column.
+///
+/// Fields qualified with the queried table's own name are rewritten to the
+/// bare column name. Any other relation must be eagerly loaded with
+/// [`QueryBuilder::with`](wasm_dbms_api::prelude::QueryBuilder::with) and the
+/// column selected for it, since the filter reads the value from the loaded
+/// foreign columns.
+fn validate_filter_relations<T>(query: &mut Query) -> DbmsResult<bool>
+where
+    T: TableSchema,
+{
+    let Some(filter) = &mut query.filter else {
+        return Ok(false);
+    };
+
+    let mut filters_relations = false;
+    for field in filter.fields_mut() {
+        let Some((relation, column)) = field.split_once('.') else {
+            continue;
+        };
+        if relation == T::table_name() {
+            *field = column.to_string();
+            continue;
+        }
+        if !query.eager_relations.iter().any(|r| r == relation) {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                "Cannot filter by '{field}': relation '{relation}' must be eagerly loaded with `.with(\"{relation}\")`"
+            ))));
+        }
+        if !T::foreign_fetcher()
+            .columns(relation)?
+            .iter()
+            .any(|col| col.name == column)
+        {
+            return Err(DbmsError::Query(QueryError::UnknownColumn(field.clone())));
+        }
+        if let Some((_, columns)) = query.eager_columns.iter().find(|(r, _)| r == relation)
+            && !columns.iter().any(|c| c == column)
+        {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                "Cannot filter by '{field}': column '{column}' is not selected for relation '{relation}'"
+            ))));
+        }
+        filters_relations = true;
+    }
+    Ok(filters_relations)
+}
+
+/// Validates the columns selected for the eager relations with
+/// [`QueryBuilder::with_fields`](wasm_dbms_api::prelude::QueryBuilder::with_fields).
+///
+/// Every selected column must exist on the foreign table, so a typo fails the
+/// query instead of leaving the field `None` in every loaded record.
+fn validate_eager_columns<T>(query: &Query) -> DbmsResult<()>
+where
+    T: TableSchema,
+{
+    if query.eager_columns.is_empty() {
+        return Ok(());
+    }
+
+    let fetcher = T::foreign_fetcher();
+    for (relation, columns) in &query.eager_columns {
+        let foreign_columns = fetcher.columns(relation)?;
+        if let Some(unknown) = columns.iter().find(|column| {
+            !foreign_columns
+                .iter()
+                .any(|col| col.name == column.as_str())
+        }) {
+            return Err(DbmsError::Query(QueryError::UnknownColumn(format!(
+                "{relation}.{unknown}"
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Sorts the rows of `T` by primary key, ascending.
+///
+/// The columns of a composite primary key are compared in declaration order.
+fn sort_by_primary_key<T>(results: &mut [TableColumns])
+where
+    T: TableSchema,
+{
+    fn pk_value<'a>(record: &'a TableColumns, column: &str) -> Option<&'a Value> {
+        record
+            .iter()
+            .find(|(source, _)| matches!(source, ValuesSource::This))
+            .and_then(|(_, values)| values.iter().find(|(col_def, _)| col_def.name == column))
+            .map(|(_, value)| value)
+    }
+
+    results.sort_by(|a, b| {
+        T::primary_keys()
+            .iter()
+            .map(|column| {
+                sort_values_with_direction(
+                    pk_value(a, column),
+                    pk_value(b, column),
+                    OrderDirection::Ascending,
+                )
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
 /// Provides ordering for two optional values by direction.
 pub fn sort_values_with_direction(
     a: Option<&Value>,
@@ -1118,8 +2247,45 @@ fn values_to_schema_entity<T>(values: Vec<(ColumnDef, Value)>) -> DbmsResult<T>
 where
     T: TableSchema,
 {
-    let record = T::Insert::from_values(&values)?.into_record();
-    Ok(record)
+    T::Insert::record_from_values(&values)
+}
+
+/// Overwrites the values of `record_values` with those of the matching columns
+/// of `patch`.
+fn apply_patch(
+    record_values: &mut [(ColumnDef, Value)],
+    patch: &[(ColumnDef, Value)],
+) -> DbmsResult<()> {
+    for (patch_col_def, patch_value) in patch {
+        if let Some((_, record_value)) = record_values
+            .iter_mut()
+            .find(|(record_col_def, _)| record_col_def.name == patch_col_def.name)
+        {
+            *record_value = patched_value(patch_col_def.name, record_value, patch_value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the value written by `patch_value` over the `current` value of
+/// `column`.
+///
+/// A [`Value::JsonPatch`] is applied to the current document, which must be a
+/// [`Value::Json`]; any other value replaces the current one.
+pub(crate) fn patched_value(
+    column: &str,
+    current: &Value,
+    patch_value: &Value,
+) -> DbmsResult<Value> {
+    match (patch_value, current) {
+        (Value::JsonPatch(ops), Value::Json(json)) => Ok(Value::Json(apply_json_patch(json, ops)?)),
+        (Value::JsonPatch(_), current) => Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "JSON patch can't be applied to the {} value of column '{column}'",
+            current.type_name()
+        )))),
+        (patch_value, _) => Ok(patch_value.clone()),
+    }
 }
 
 /// Builds the index key for the given columns by extracting values from the record.
@@ -1151,10 +2317,37 @@ where
         if !query.joins.is_empty() {
             return Err(DbmsError::Query(QueryError::JoinInsideTypedSelect));
         }
-        let results = self.select_columns::<T>(query)?;
+        let results = self.select_live_columns::<T>(query)?;
         Ok(results.into_iter().map(T::Record::from_values).collect())
     }
 
+    fn select_one<T>(&self, mut query: Query) -> DbmsResult<Option<T::Record>>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        if !query.joins.is_empty() {
+            return Err(DbmsError::Query(QueryError::JoinInsideTypedSelect));
+        }
+        // `select_columns` stops scanning the primary key index once `limit`
+        // rows are collected, unless there is an ORDER BY or DISTINCT.
+        query.limit = Some(1);
+        let results = self.select_live_columns::<T>(query)?;
+        Ok(results.into_iter().next().map(T::Record::from_values))
+    }
+
+    fn exists<T>(&self, filter: Filter) -> DbmsResult<bool>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        let filter = match T::soft_delete_column() {
+            Some(column) => filter.and(Filter::is_null(column)),
+            None => filter,
+        };
+        self.exists_row::<T>(filter)
+    }
+
     fn select_raw(&self, table: &str, query: Query) -> DbmsResult<Vec<Vec<(ColumnDef, Value)>>> {
         self.ensure_no_drift()?;
         self.schema.select(self, table, query)
@@ -1177,151 +2370,266 @@ where
     where
         T: TableSchema,
     {
-        self.ensure_no_drift()?;
-        aggregate::run_aggregate::<T, _, _>(self, query, aggregates)
+        self.ensure_no_drift()?;
+        let query = hide_deleted_rows::<T>(query);
+        aggregate::run_aggregate::<T, _, _>(self, query, aggregates)
+    }
+
+    fn count<T>(&self, filter: Option<Filter>) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        self.count_rows::<T>(live_rows_filter::<T>(filter))
+    }
+
+    fn insert<T>(&self, record: T::Insert) -> DbmsResult<()>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+    {
+        self.insert_values::<T>(record).map(|_| ())
+    }
+
+    fn insert_returning<T>(&self, record: T::Insert) -> DbmsResult<T::Record>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+    {
+        let values = self.insert_values::<T>(record)?;
+        Ok(T::Record::from_values(vec![(ValuesSource::This, values)]))
     }
 
-    fn insert<T>(&self, record: T::Insert) -> DbmsResult<()>
+    fn insert_many<T>(&self, records: Vec<T::Insert>) -> DbmsResult<u64>
     where
         T: TableSchema,
         T::Insert: InsertRecord<Schema = T>,
     {
         self.ensure_no_drift()?;
         let mut table_registry = self.load_table_registry::<T>()?;
-        let record_values = record.clone().into_values();
-        let record_values =
-            self.fill_auto_increment_values::<T>(&mut table_registry, record_values)?;
-        let sanitized_values = self.sanitize_values::<T>(record_values)?;
-        self.schema
-            .validate_insert(self, T::table_name(), &sanitized_values)?;
+        let mut rows = Vec::with_capacity(records.len());
+        for record in records {
+            let mut record_values = record.into_values();
+            self.stamp_auto_timestamps::<T>(&mut record_values, AutoTimestamp::Insert);
+            let record_values =
+                reserve_auto_increment_values::<T>(&mut table_registry, record_values)?;
+            rows.push(self.sanitize_values::<T>(record_values)?);
+        }
+        check_batch_conflicts::<T>(&rows)?;
+        for values in &rows {
+            self.schema.validate_insert(self, T::table_name(), values)?;
+        }
+
+        let inserted = rows.len() as u64;
         if self.transaction.is_some() {
-            self.with_transaction_mut(|tx| tx.insert::<T>(sanitized_values))?;
+            table_registry.save_autoincrement(&mut *self.ctx.mm.borrow_mut())?;
+            self.with_transaction_mut(|tx| tx.insert_many::<T>(rows))?;
         } else {
             self.atomic(|db| {
-                let record = T::Insert::from_values(&sanitized_values)?;
                 let mut mm = db.ctx.mm.borrow_mut();
-                // update journal with the insert operation before mutating memory
                 let mut journal_ref = db.ctx.journal.borrow_mut();
                 let journal = journal_ref
                     .as_mut()
                     .expect("journal must be active inside atomic");
                 let mut writer = JournaledWriter::new(&mut *mm, journal);
-                // insert the record in the table registry, and eventually update the indexes
-                let record_address = table_registry
-                    .insert(record.into_record(), &mut writer)
-                    .map_err(DbmsError::from)?;
-                self.insert_index::<T>(
-                    &mut table_registry,
-                    record_address,
-                    &sanitized_values,
-                    &mut writer,
-                )?;
+                table_registry.save_autoincrement(&mut writer)?;
+                for values in &rows {
+                    let mut values = values.clone();
+                    spill_blobs(&mut values, None, db.ctx.max_blob_size.get(), &mut writer)?;
+                    let record = T::Insert::record_from_values(&values)?;
+                    let record_address = table_registry
+                        .insert(record, &mut writer)
+                        .map_err(DbmsError::from)?;
+                    self.insert_index::<T>(
+                        &mut table_registry,
+                        record_address,
+                        &values,
+                        &mut writer,
+                    )?;
+                }
                 Ok(())
             })?;
         }
 
-        Ok(())
+        Ok(inserted)
     }
 
-    fn update<T>(&self, patch: T::Update) -> DbmsResult<u64>
+    fn insert_with_behavior<T>(
+        &self,
+        record: T::Insert,
+        behavior: InsertConflictBehavior,
+    ) -> DbmsResult<()>
     where
         T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
         T::Update: UpdateRecord<Schema = T>,
     {
         self.ensure_no_drift()?;
-        let filter = patch.where_clause().clone();
-        if self.transaction.is_some() {
-            let rows = self.existing_rows_for_filter::<T>(filter.clone())?;
-            let count = rows.len() as u64;
-            self.with_transaction_mut(|tx| tx.update::<T>(patch, filter, rows))?;
-
-            return Ok(count);
+        // an explicit behavior overrides the table's `on_conflict` action
+        match behavior {
+            InsertConflictBehavior::Error => self.insert_new_values::<T>(record).map(|_| ()),
+            InsertConflictBehavior::Ignore => match self.insert_new_values::<T>(record) {
+                Err(DbmsError::Query(
+                    QueryError::PrimaryKeyConflict | QueryError::UniqueConstraintViolation { .. },
+                )) => Ok(()),
+                result => result.map(|_| ()),
+            },
+            InsertConflictBehavior::Replace => self.upsert::<T>(record).map(|_| ()),
         }
+    }
 
-        let patch = patch.update_values();
+    fn upsert<T>(&self, record: T::Insert) -> DbmsResult<UpsertResult>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        self.ensure_no_drift()?;
+        let values = record.clone().into_values();
+        // an omitted (auto-increment) primary key can't collide with an existing row
+        let Ok(pk) = table_row_key::<T>(&values) else {
+            return self.insert::<T>(record).map(|()| UpsertResult::Inserted);
+        };
+        let pk_filter = row_key_filter::<T>(&pk);
+        if self.transaction.is_some() {
+            return self.upsert_in_transaction::<T>(values, pk, pk_filter);
+        }
+        if !self.exists_row::<T>(pk_filter.clone())? {
+            return self.insert::<T>(record).map(|()| UpsertResult::Inserted);
+        }
 
-        let pk_in_patch = patch.iter().find_map(|(col_def, value)| {
-            if col_def.primary_key {
-                Some((col_def, value))
-            } else {
-                None
-            }
-        });
+        // overwrite through the update path, so sanitizers, validators and
+        // integrity checks run exactly as for a regular update
+        let patch_values: Vec<_> = values
+            .into_iter()
+            .filter(|(col_def, _)| !col_def.primary_key)
+            .collect();
+        let patch = T::Update::from_values(&patch_values, Some(pk_filter));
+        self.update::<T>(patch).map(|_| UpsertResult::Updated)
+    }
 
-        self.atomic(|db| {
-            let mut count = 0;
+    fn update<T>(&self, patch: T::Update) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        self.update_values::<T>(patch).map(|(count, _)| count)
+    }
 
-            let mut table_registry = db.load_table_registry::<T>()?;
-            let records = db.collect_matching_records::<T>(&table_registry, &filter)?;
+    fn update_chunked<T>(
+        &self,
+        patch: T::Update,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+    ) -> DbmsResult<(u64, Option<ContinuationToken>)>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        let Some((filter, next)) =
+            self.next_chunk::<T>(patch.where_clause(), max_rows, token.as_ref())?
+        else {
+            return Ok((0, None));
+        };
+        let (count, _) = self.update_patch_values::<T>(patch.update_values(), filter)?;
 
-            for (record, record_values) in records {
-                let current_pk_value = record_values
-                    .iter()
-                    .find(|(col_def, _)| col_def.primary_key)
-                    .expect("primary key not found")
-                    .1
-                    .clone();
+        Ok((count, next))
+    }
 
-                let previous_record = values_to_schema_entity::<T>(record_values.clone())?;
-                let old_values_for_index = record_values.clone();
-                let mut record_values = record_values;
+    fn update_returning<T>(&self, patch: T::Update) -> DbmsResult<Vec<T::Record>>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        let (_, rows) = self.update_values::<T>(patch)?;
+        Ok(rows
+            .into_iter()
+            .map(|values| T::Record::from_values(vec![(ValuesSource::This, values)]))
+            .collect())
+    }
 
-                for (patch_col_def, patch_value) in &patch {
-                    if let Some((_, record_value)) = record_values
-                        .iter_mut()
-                        .find(|(record_col_def, _)| record_col_def.name == patch_col_def.name)
-                    {
-                        *record_value = patch_value.clone();
-                    }
-                }
-                let record_values = db.sanitize_values::<T>(record_values)?;
-                db.schema.validate_update(
-                    db,
-                    T::table_name(),
-                    &record_values,
-                    current_pk_value.clone(),
-                )?;
-                let updated_record = values_to_schema_entity::<T>(record_values.clone())?;
-                {
-                    let mut mm = db.ctx.mm.borrow_mut();
-                    // update journal with the update operation before mutating memory
-                    let mut journal_ref = db.ctx.journal.borrow_mut();
-                    let journal = journal_ref
-                        .as_mut()
-                        .expect("journal must be active inside atomic");
-                    let mut writer = JournaledWriter::new(&mut *mm, journal);
-                    // update table registry
-                    let old_address = RecordAddress::new(record.page, record.offset);
-                    let new_address = table_registry
-                        .update(updated_record, previous_record, old_address, &mut writer)
-                        .map_err(DbmsError::from)?;
-                    // update indexes if needed
-                    self.update_index::<T>(
-                        &mut table_registry,
-                        old_address,
-                        new_address,
-                        &old_values_for_index,
-                        &record_values,
-                        &mut writer,
-                    )?;
-                }
-                count += 1;
+    fn validate_insert_only<T>(&self, record: T::Insert) -> DbmsResult<()>
+    where
+        T: TableSchema,
+        T::Insert: InsertRecord<Schema = T>,
+    {
+        self.ensure_no_drift()?;
+        let table_registry = self.load_table_registry::<T>()?;
+        let mut record_values = record.into_values();
+        self.stamp_auto_timestamps::<T>(&mut record_values, AutoTimestamp::Insert);
+        let record_values = peek_auto_increment_values::<T>(&table_registry, record_values)?;
+        let sanitized_values = self.sanitize_values::<T>(record_values)?;
+        self.schema
+            .validate_insert(self, T::table_name(), &sanitized_values)
+    }
 
-                if let Some((pk_column, new_pk_value)) = pk_in_patch {
-                    count += db.update_pk_referencing_updated_table::<T>(
-                        current_pk_value,
-                        new_pk_value.clone(),
-                        pk_column.data_type,
-                        pk_column.name,
-                    )?;
-                }
-            }
+    fn validate_update_only<T>(&self, patch: T::Update) -> DbmsResult<()>
+    where
+        T: TableSchema,
+        T::Update: UpdateRecord<Schema = T>,
+    {
+        self.ensure_no_drift()?;
+        let mut patch_values = patch.update_values();
+        self.stamp_auto_timestamps::<T>(&mut patch_values, AutoTimestamp::Update);
+        // rows are read through the overlay when a transaction is active,
+        // keeping the foreign key columns the records leave out
+        let query = Query::builder()
+            .include_deleted()
+            .filter(patch.where_clause())
+            .build();
+        for record in self.select_columns::<T>(query)? {
+            let Some(mut row) = Self::this_columns(&record).cloned() else {
+                continue;
+            };
+            let pk = row_key(&row).expect("primary key not found");
+            apply_patch(&mut row, &patch_values)?;
+            let row = self.sanitize_values::<T>(row)?;
+            self.schema
+                .validate_update(self, T::table_name(), &row, pk)?;
+        }
 
-            Ok(count)
-        })
+        Ok(())
     }
 
     fn delete<T>(&self, behaviour: DeleteBehavior, filter: Option<Filter>) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        let Some(column) = T::soft_delete_column() else {
+            return self.force_delete::<T>(behaviour, filter);
+        };
+        let col_def = T::columns()
+            .iter()
+            .find(|col_def| col_def.name == column)
+            .ok_or(DbmsError::Query(QueryError::UnknownColumn(
+                column.to_string(),
+            )))?;
+
+        // rows already deleted keep their original deletion timestamp
+        let deleted_at = Value::Timestamp(Timestamp(self.now()));
+        let patch =
+            T::Update::from_values(&[(*col_def, deleted_at)], live_rows_filter::<T>(filter));
+        self.update::<T>(patch)
+    }
+
+    fn delete_chunked<T>(
+        &self,
+        behaviour: DeleteBehavior,
+        filter: Option<Filter>,
+        max_rows: Option<u64>,
+        token: Option<ContinuationToken>,
+    ) -> DbmsResult<(u64, Option<ContinuationToken>)>
+    where
+        T: TableSchema,
+    {
+        let Some((filter, next)) = self.next_chunk::<T>(filter, max_rows, token.as_ref())? else {
+            return Ok((0, None));
+        };
+
+        Ok((self.delete::<T>(behaviour, filter)?, next))
+    }
+
+    fn force_delete<T>(&self, behaviour: DeleteBehavior, filter: Option<Filter>) -> DbmsResult<u64>
     where
         T: TableSchema,
     {
@@ -1335,26 +2643,48 @@ where
             return Ok(count);
         }
 
-        self.atomic(|db| {
+        let depth = self.cascade.borrow().depth + 1;
+        if depth > MAX_CASCADE_DEPTH {
+            return Err(DbmsError::Query(QueryError::CascadeTooDeep {
+                table: T::table_name().to_string(),
+                depth: MAX_CASCADE_DEPTH,
+            }));
+        }
+        self.cascade.borrow_mut().depth = depth;
+        let result = self.atomic(|db| {
             let mut table_registry = db.load_table_registry::<T>()?;
-            let records = db.collect_matching_records::<T>(&table_registry, &filter)?;
+            let matching = db.collect_matching_records::<T>(&table_registry, &filter)?;
+            // claim the rows first: rows claimed by an outer call are deleted there
+            let mut records = Vec::with_capacity(matching.len());
+            let mut cascade = db.cascade.borrow_mut();
+            for (address, record_values) in matching {
+                let key = table_row_key::<T>(&record_values)?;
+                if cascade.visited.insert((T::table_name(), key)) {
+                    records.push((address, record_values));
+                }
+            }
+            drop(cascade);
+            // reject the delete before writing anything
+            for (_, record_values) in &records {
+                if let Some((table, column)) =
+                    db.has_referencing_rows::<T>(record_values, behaviour)?
+                {
+                    return Err(DbmsError::Query(
+                        QueryError::ForeignKeyConstraintViolation {
+                            referencing_table: table.to_string(),
+                            field: column.to_string(),
+                        },
+                    ));
+                }
+            }
             let mut count = records.len() as u64;
-            for (record, record_values) in records {
-                match behaviour {
-                    DeleteBehavior::Cascade => {
-                        count += db.delete_foreign_keys_cascade::<T>(&record_values)?;
-                    }
-                    DeleteBehavior::Restrict => {
-                        if db.has_foreign_key_references::<T>(&record_values)? {
-                            return Err(DbmsError::Query(
-                                QueryError::ForeignKeyConstraintViolation {
-                                    referencing_table: T::table_name().to_string(),
-                                    field: T::primary_key().to_string(),
-                                },
-                            ));
-                        }
-                    }
+            for (address, record_values) in records {
+                let cascaded = db.apply_on_delete::<T>(behaviour, &record_values)?;
+                if cascaded > 0 {
+                    // the cascade may have written to this table
+                    table_registry = db.load_table_registry::<T>()?;
                 }
+                count += cascaded;
                 let mut mm = db.ctx.mm.borrow_mut();
                 let mut journal_ref = db.ctx.journal.borrow_mut();
                 let journal = journal_ref
@@ -1362,17 +2692,282 @@ where
                     .expect("journal must be active inside atomic");
                 // write table and index deletions to the journal before mutating memory
                 let mut writer = JournaledWriter::new(&mut *mm, journal);
-                let address = RecordAddress::new(record.page, record.offset);
+                release_blobs(&record_values, &mut writer)?;
+                let record = values_to_schema_entity::<T>(record_values.clone())?;
                 table_registry
-                    .delete(record.record, address, &mut writer)
+                    .delete(record, address, &mut writer)
                     .map_err(DbmsError::from)?;
                 self.delete_index::<T>(&mut table_registry, address, &record_values, &mut writer)?;
             }
 
             Ok(count)
+        });
+        let mut cascade = self.cascade.borrow_mut();
+        cascade.depth -= 1;
+        if cascade.depth == 0 {
+            cascade.visited.clear();
+        }
+        result
+    }
+
+    fn truncate<T>(&self) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        if self.transaction.is_some() {
+            return self.force_delete::<T>(DeleteBehavior::Restrict, None);
+        }
+
+        self.atomic(|db| {
+            db.ensure_not_referenced::<T>()?;
+            let mut table_registry = db.load_table_registry::<T>()?;
+            let alignment = table_registry.schema_snapshot_ledger().get().alignment;
+            let mut mm = db.ctx.mm.borrow_mut();
+            let mut journal_ref = db.ctx.journal.borrow_mut();
+            let journal = journal_ref
+                .as_mut()
+                .expect("journal must be active inside atomic");
+            // write the released pages to the journal before mutating memory
+            let mut writer = JournaledWriter::new(&mut *mm, journal);
+            if blob::has_blob_columns::<T>() {
+                let mut rows = Vec::new();
+                let mut table_reader = table_registry.read::<T, _>(&mut writer);
+                while let Some(values) = table_reader.try_next()? {
+                    rows.push(values.record.to_values());
+                }
+                for values in rows {
+                    release_blobs(&values, &mut writer)?;
+                }
+            }
+            table_registry
+                .truncate(alignment as PageOffset, &mut writer)
+                .map_err(DbmsError::from)
+        })
+    }
+
+    fn blob_read_chunk<T>(
+        &self,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> DbmsResult<BlobChunk>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        blob::check_chunk_size(len, self.ctx.max_blob_chunk_size.get())?;
+        let table_registry = self.load_table_registry::<T>()?;
+        let (_, values) = self.stored_row::<T>(&table_registry, &pk)?;
+        let blob = blob::stored_blob::<T>(&values, column)?;
+        let mut mm = self.ctx.mm.borrow_mut();
+        blob::read_chunk(&blob, offset, len, &mut *mm)
+    }
+
+    fn blob_write_chunk<T>(
+        &self,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        bytes: &[u8],
+    ) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        if self.transaction.is_some() {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(
+                "blob chunks can't be written inside a transaction".to_string(),
+            )));
+        }
+        blob::check_chunk_size(bytes.len() as u64, self.ctx.max_blob_chunk_size.get())?;
+
+        self.atomic(|db| {
+            let mut table_registry = db.load_table_registry::<T>()?;
+            let (old_address, values) = db.stored_row::<T>(&table_registry, &pk)?;
+            let blob = blob::stored_blob::<T>(&values, column)?;
+            let mut mm = db.ctx.mm.borrow_mut();
+            let mut journal_ref = db.ctx.journal.borrow_mut();
+            let journal = journal_ref
+                .as_mut()
+                .expect("journal must be active inside atomic");
+            // write the blob pages to the journal before mutating memory
+            let mut writer = JournaledWriter::new(&mut *mm, journal);
+            let (new_blob, len) = blob::write_chunk(
+                &blob,
+                offset,
+                bytes,
+                db.ctx.max_blob_size.get(),
+                &mut writer,
+            )?;
+            if new_blob == blob {
+                return Ok(len);
+            }
+
+            // the inline content or the overflow reference changed
+            let mut new_values = values.clone();
+            if let Some((_, value)) = new_values
+                .iter_mut()
+                .find(|(col_def, _)| col_def.name == column)
+            {
+                *value = Value::Blob(new_blob);
+            }
+            let previous_record = values_to_schema_entity::<T>(values.clone())?;
+            let updated_record = values_to_schema_entity::<T>(new_values.clone())?;
+            let new_address = table_registry
+                .update(updated_record, previous_record, old_address, &mut writer)
+                .map_err(DbmsError::from)?;
+            db.update_index::<T>(
+                &mut table_registry,
+                old_address,
+                new_address,
+                &values,
+                &new_values,
+                &mut writer,
+            )?;
+
+            Ok(len)
+        })
+    }
+
+    fn defragment<T>(&self) -> DbmsResult<u64>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        if self.transaction.is_some() {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(
+                "tables can't be defragmented inside a transaction".to_string(),
+            )));
+        }
+
+        self.atomic(|db| {
+            let mut table_registry = db.load_table_registry::<T>()?;
+            let alignment = table_registry.schema_snapshot_ledger().get().alignment;
+            let mut mm = db.ctx.mm.borrow_mut();
+            let mut journal_ref = db.ctx.journal.borrow_mut();
+            let journal = journal_ref
+                .as_mut()
+                .expect("journal must be active inside atomic");
+            // write the rewritten pages to the journal before mutating memory
+            let mut writer = JournaledWriter::new(&mut *mm, journal);
+            let released = table_registry
+                .defragment(alignment as PageOffset, &mut writer)
+                .map_err(DbmsError::from)?;
+
+            // the records moved, so the indexes are rebuilt from scratch
+            table_registry
+                .index_ledger_mut()
+                .clear(&mut writer)
+                .map_err(DbmsError::from)?;
+            let mut rows = Vec::new();
+            let mut table_reader = table_registry.read::<T, _>(&mut writer);
+            while let Some(values) = table_reader.try_next()? {
+                let address = RecordAddress::new(values.page, values.offset);
+                rows.push((address, values.record.to_values()));
+            }
+            for (address, values) in rows {
+                db.insert_index::<T>(&mut table_registry, address, &values, &mut writer)?;
+            }
+
+            Ok(released)
+        })
+    }
+
+    fn compact<T>(&self, max_pages: u64) -> DbmsResult<CompactionReport>
+    where
+        T: TableSchema,
+    {
+        self.ensure_no_drift()?;
+        if self.transaction.is_some() {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(
+                "tables can't be compacted inside a transaction".to_string(),
+            )));
+        }
+
+        self.atomic(|db| {
+            let mut table_registry = db.load_table_registry::<T>()?;
+            let alignment = table_registry.schema_snapshot_ledger().get().alignment;
+            let mut mm = db.ctx.mm.borrow_mut();
+            let mut journal_ref = db.ctx.journal.borrow_mut();
+            let journal = journal_ref
+                .as_mut()
+                .expect("journal must be active inside atomic");
+            let mut writer = JournaledWriter::new(&mut *mm, journal);
+            let compaction = table_registry
+                .compact(alignment as PageOffset, max_pages, &mut writer)
+                .map_err(DbmsError::from)?;
+
+            // the moved records keep their values, so only the index pointers change
+            for (old_address, new_address) in compaction.moves {
+                let record: T = table_registry.read_at(new_address, &mut writer)?;
+                let values = record.to_values();
+                db.update_index::<T>(
+                    &mut table_registry,
+                    old_address,
+                    new_address,
+                    &values,
+                    &values,
+                    &mut writer,
+                )?;
+            }
+
+            Ok(compaction.report)
         })
     }
 
+    fn vacuum(&self) -> DbmsResult<VacuumReport> {
+        self.ensure_no_drift()?;
+        let policies = self
+            .ctx
+            .schema_registry
+            .borrow()
+            .retention_policies()
+            .to_vec();
+        let snapshots = self.schema.compiled_snapshots_dyn();
+        let now = self.now();
+
+        let mut report = VacuumReport::default();
+        for policy in policies {
+            let snapshot = snapshots
+                .iter()
+                .find(|snapshot| snapshot.name == policy.table)
+                .ok_or_else(|| {
+                    DbmsError::Query(QueryError::TableNotFound(policy.table.to_string()))
+                })?;
+            let column = snapshot
+                .columns
+                .iter()
+                .find(|column| column.name == policy.column)
+                .ok_or_else(|| {
+                    DbmsError::Query(QueryError::UnknownColumn(policy.column.to_string()))
+                })?;
+            let cutoff = Timestamp(now.saturating_sub(policy.max_age_nanos));
+            let cutoff = match column.data_type {
+                DataTypeSnapshot::Timestamp => Value::Timestamp(cutoff),
+                DataTypeSnapshot::Datetime => Value::DateTime(cutoff.into()),
+                DataTypeSnapshot::ZonedDatetime => Value::ZonedDateTime(cutoff.into()),
+                _ => {
+                    return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                        "retention column `{}.{}` is not a timestamp column",
+                        policy.table, policy.column
+                    ))));
+                }
+            };
+
+            report.rows_deleted += self.schema.force_delete(
+                self,
+                policy.table,
+                DeleteBehavior::Restrict,
+                Some(Filter::lt(policy.column, cutoff)),
+            )?;
+            report.tables_vacuumed += 1;
+        }
+
+        Ok(report)
+    }
+
     fn commit(&mut self) -> DbmsResult<()> {
         self.ensure_no_drift()?;
         let Some(txid) = self.transaction.take() else {
@@ -1386,6 +2981,7 @@ where
         };
 
         *self.ctx.journal.borrow_mut() = Some(Journal::new());
+        self.commit_time = Some(self.ctx.now());
 
         for op in transaction.operations {
             let result = match op {
@@ -1393,22 +2989,31 @@ where
                     .schema
                     .validate_insert(self, table, &values)
                     .and_then(|()| self.schema.insert(self, table, &values)),
+                TransactionOp::InsertMany { table, rows } => rows.iter().try_for_each(|values| {
+                    self.schema
+                        .validate_insert(self, table, values)
+                        .and_then(|()| self.schema.insert(self, table, values))
+                }),
                 TransactionOp::Delete {
                     table,
                     behaviour,
                     filter,
                 } => self
                     .schema
-                    .delete(self, table, behaviour, filter)
+                    .force_delete(self, table, behaviour, filter)
                     .map(|_| ()),
                 TransactionOp::Update {
                     table,
                     patch,
                     filter,
                 } => self.schema.update(self, table, &patch, filter).map(|_| ()),
+                TransactionOp::Upsert { table, values } => {
+                    self.schema.upsert(self, table, &values).map(|_| ())
+                }
             };
 
             if let Err(err) = result {
+                self.commit_time = None;
                 if let Some(journal) = self.ctx.journal.borrow_mut().take() {
                     journal
                         .rollback(&mut self.ctx.mm.borrow_mut())
@@ -1418,6 +3023,7 @@ where
             }
         }
 
+        self.commit_time = None;
         if let Some(journal) = self.ctx.journal.borrow_mut().take() {
             journal.commit();
         }
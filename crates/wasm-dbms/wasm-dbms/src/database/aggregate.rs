@@ -2,6 +2,7 @@
 
 //! Aggregate query execution: GROUP BY, aggregate functions, HAVING.
 
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use rust_decimal::Decimal as RustDecimal;
@@ -148,6 +149,7 @@ fn compute_aggregates(
 /// regardless of input integer width.
 ///
 /// An all-null group returns `Value::Null` (matching SQL `SUM` semantics).
+/// A sum exceeding the [`Decimal`] range fails instead of wrapping.
 fn sum_column(rows: &[TableColumns], col: &str) -> DbmsResult<Value> {
     let mut acc = RustDecimal::ZERO;
     let mut any = false;
@@ -155,7 +157,7 @@ fn sum_column(rows: &[TableColumns], col: &str) -> DbmsResult<Value> {
         if let Some(v) = this_value(row, col)
             && !v.is_null()
         {
-            acc += value_to_decimal(v).map_err(DbmsError::Query)?;
+            acc = accumulate(acc, v, col)?;
             any = true;
         }
     }
@@ -177,7 +179,7 @@ fn avg_column(rows: &[TableColumns], col: &str) -> DbmsResult<Value> {
         if let Some(v) = this_value(row, col)
             && !v.is_null()
         {
-            acc += value_to_decimal(v).map_err(DbmsError::Query)?;
+            acc = accumulate(acc, v, col)?;
             count += 1;
         }
     }
@@ -209,6 +211,17 @@ fn min_max_column(rows: &[TableColumns], col: &str, is_min: bool) -> Value {
     best.unwrap_or(Value::Null)
 }
 
+/// Adds the numeric `value` of `col` to `acc`, failing with
+/// [`QueryError::InvalidQuery`] when the result overflows.
+fn accumulate(acc: RustDecimal, value: &Value, col: &str) -> DbmsResult<RustDecimal> {
+    let value = value_to_decimal(value).map_err(DbmsError::Query)?;
+    acc.checked_add(value).ok_or_else(|| {
+        DbmsError::Query(QueryError::InvalidQuery(format!(
+            "aggregate overflow on column '{col}'"
+        )))
+    })
+}
+
 /// Coerces a numeric [`Value`] into a `RustDecimal` for sum/avg accumulation.
 ///
 /// Errors with [`QueryError::InvalidQuery`] when called on a non-numeric value;
@@ -225,6 +238,12 @@ fn value_to_decimal(value: &Value) -> Result<RustDecimal, QueryError> {
         Value::Uint32(v) => RustDecimal::from(v.0),
         Value::Uint64(v) => RustDecimal::from(v.0),
         Value::Decimal(v) => v.0,
+        Value::Float32(v) => RustDecimal::try_from(v.0).map_err(|_| {
+            QueryError::InvalidQuery(format!("cannot aggregate non-finite float: {}", v.0))
+        })?,
+        Value::Float64(v) => RustDecimal::try_from(v.0).map_err(|_| {
+            QueryError::InvalidQuery(format!("cannot aggregate non-finite float: {}", v.0))
+        })?,
         other => {
             return Err(QueryError::InvalidQuery(format!(
                 "cannot aggregate non-numeric value: {other:?}"
@@ -247,6 +266,8 @@ fn is_numeric_kind(kind: DataTypeKind) -> bool {
             | DataTypeKind::Uint32
             | DataTypeKind::Uint64
             | DataTypeKind::Decimal
+            | DataTypeKind::Float32
+            | DataTypeKind::Float64
     )
 }
 
@@ -324,11 +345,28 @@ fn validate_having_filter(
     aggregates: &[AggregateFunction],
 ) -> DbmsResult<()> {
     walk_filter(filter, &mut |f| match f {
-        Filter::Like(_, _) => Err(DbmsError::Query(QueryError::InvalidQuery(
-            "LIKE is not supported in HAVING".to_string(),
+        Filter::Like(_, _) | Filter::ILike(_, _) => Err(DbmsError::Query(
+            QueryError::InvalidQuery("LIKE / ILIKE are not supported in HAVING".to_string()),
+        )),
+        Filter::Regex(_, _) => Err(DbmsError::Query(QueryError::InvalidQuery(
+            "REGEX is not supported in HAVING".to_string(),
         ))),
-        Filter::Json(_, _) => Err(DbmsError::Query(QueryError::InvalidQuery(
-            "JSON filters are not supported in HAVING".to_string(),
+        Filter::StartsWith(_, _) | Filter::EndsWith(_, _) => {
+            Err(DbmsError::Query(QueryError::InvalidQuery(
+                "STARTS WITH / ENDS WITH are not supported in HAVING".to_string(),
+            )))
+        }
+        Filter::Json(_, _) | Filter::JsonPath(_, _, _) => Err(DbmsError::Query(
+            QueryError::InvalidQuery("JSON filters are not supported in HAVING".to_string()),
+        )),
+        Filter::AnyOf(_, _) | Filter::AllOf(_, _) => Err(DbmsError::Query(
+            QueryError::InvalidQuery("array filters are not supported in HAVING".to_string()),
+        )),
+        Filter::StrLen(_, _) | Filter::BlobLen(_, _) => Err(DbmsError::Query(
+            QueryError::InvalidQuery("length filters are not supported in HAVING".to_string()),
+        )),
+        Filter::CompareColumns(_, _, _) => Err(DbmsError::Query(QueryError::InvalidQuery(
+            "column comparisons are not supported in HAVING".to_string(),
         ))),
         _ => {
             if let Some(col) = filter_column(f)
@@ -390,9 +428,20 @@ fn filter_column(filter: &Filter) -> Option<&str> {
         | Filter::Lt(c, _)
         | Filter::Ge(c, _)
         | Filter::Le(c, _)
+        | Filter::Between(c, _, _)
         | Filter::In(c, _)
+        | Filter::AnyOf(c, _)
+        | Filter::AllOf(c, _)
         | Filter::Json(c, _)
+        | Filter::JsonPath(c, _, _)
         | Filter::Like(c, _)
+        | Filter::ILike(c, _)
+        | Filter::StartsWith(c, _)
+        | Filter::EndsWith(c, _)
+        | Filter::Regex(c, _)
+        | Filter::StrLen(c, _)
+        | Filter::BlobLen(c, _)
+        | Filter::CompareColumns(c, _, _)
         | Filter::NotNull(c)
         | Filter::IsNull(c) => Some(c),
         Filter::And(_, _) | Filter::Or(_, _) | Filter::Not(_) => None,
@@ -475,25 +524,59 @@ fn eval_filter(filter: &Filter, lookup: &HashMap<String, Value>) -> DbmsResult<b
     let res = match filter {
         Filter::Eq(c, v) => lookup.get(c).is_some_and(|x| x == v),
         Filter::Ne(c, v) => lookup.get(c).is_some_and(|x| x != v),
-        Filter::Gt(c, v) => lookup.get(c).is_some_and(|x| x > v),
-        Filter::Lt(c, v) => lookup.get(c).is_some_and(|x| x < v),
-        Filter::Ge(c, v) => lookup.get(c).is_some_and(|x| x >= v),
-        Filter::Le(c, v) => lookup.get(c).is_some_and(|x| x <= v),
+        Filter::Gt(c, v) => compare_having(lookup.get(c), v, Ordering::is_gt)?,
+        Filter::Lt(c, v) => compare_having(lookup.get(c), v, Ordering::is_lt)?,
+        Filter::Ge(c, v) => compare_having(lookup.get(c), v, Ordering::is_ge)?,
+        Filter::Le(c, v) => compare_having(lookup.get(c), v, Ordering::is_le)?,
+        Filter::Between(c, low, high) => {
+            if low.type_name() != high.type_name() {
+                return Err(DbmsError::Query(QueryError::TypeMismatch {
+                    expected: low.type_name().to_string(),
+                    found: high.type_name().to_string(),
+                }));
+            }
+            compare_having(lookup.get(c), low, Ordering::is_ge)?
+                && compare_having(lookup.get(c), high, Ordering::is_le)?
+        }
         Filter::In(c, list) => lookup.get(c).is_some_and(|x| list.iter().any(|v| v == x)),
         Filter::IsNull(c) => lookup.get(c).is_some_and(Value::is_null),
         Filter::NotNull(c) => lookup.get(c).is_some_and(|x| !x.is_null()),
         Filter::And(a, b) => eval_filter(a, lookup)? && eval_filter(b, lookup)?,
         Filter::Or(a, b) => eval_filter(a, lookup)? || eval_filter(b, lookup)?,
         Filter::Not(inner) => !eval_filter(inner, lookup)?,
-        Filter::Like(_, _) | Filter::Json(_, _) => {
+        Filter::Like(_, _)
+        | Filter::ILike(_, _)
+        | Filter::StartsWith(_, _)
+        | Filter::EndsWith(_, _)
+        | Filter::Regex(_, _)
+        | Filter::Json(_, _)
+        | Filter::JsonPath(_, _, _)
+        | Filter::AnyOf(_, _)
+        | Filter::AllOf(_, _)
+        | Filter::StrLen(_, _)
+        | Filter::BlobLen(_, _)
+        | Filter::CompareColumns(_, _, _) => {
             return Err(DbmsError::Query(QueryError::InvalidQuery(
-                "LIKE/JSON not supported in HAVING".to_string(),
+                "LIKE/REGEX/JSON/array/length/column filters not supported in HAVING".to_string(),
             )));
         }
     };
     Ok(res)
 }
 
+/// Compares a `HAVING` operand with `value` through [`Value::compare_typed`],
+/// applying `f` to the ordering. A missing column or `NULL` never matches.
+fn compare_having(
+    x: Option<&Value>,
+    value: &Value,
+    f: impl FnOnce(Ordering) -> bool,
+) -> DbmsResult<bool> {
+    match x {
+        Some(x) if !x.is_null() && !value.is_null() => Ok(f(x.compare_typed(value)?)),
+        _ => Ok(false),
+    }
+}
+
 /// Applies multi-key ORDER BY to aggregated rows. Keys are processed last to
 /// first so that earlier keys dominate later ones in the final order.
 fn apply_order_by(
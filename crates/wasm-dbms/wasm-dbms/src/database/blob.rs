@@ -0,0 +1,224 @@
+// X-WHERE-CLAUSE, M-CANONICAL-DOCS
+
+//! Out-of-line storage of large blobs.
+//!
+//! A blob longer than [`INLINE_BLOB_SIZE`] is stored in a chain of overflow
+//! pages, and the record keeps only the reference encoded by
+//! [`OverflowBlob::to_bytes`]. A blob which happens to look like a reference
+//! is stored out of line too, so a stored reference is never ambiguous.
+//!
+//! Selects return an empty placeholder in place of such blobs; their content
+//! is accessed in chunks through `blob_read_chunk` and `blob_write_chunk`.
+
+use wasm_dbms_api::prelude::{
+    Blob, BlobChunk, ColumnDef, DataTypeKind, DbmsError, DbmsResult, QueryError, TableSchema, Value,
+};
+use wasm_dbms_memory::prelude::{MemoryAccess, OverflowBlob};
+
+/// Maximum size of a blob stored inline in its record.
+pub(crate) const INLINE_BLOB_SIZE: usize = 4 * 1024;
+
+/// Returns whether table `T` has blob columns.
+pub(super) fn has_blob_columns<T>() -> bool
+where
+    T: TableSchema,
+{
+    T::columns()
+        .iter()
+        .any(|col_def| col_def.data_type == DataTypeKind::Blob)
+}
+
+/// Moves the blobs of `values` which don't fit inline to overflow pages,
+/// replacing them with their reference.
+///
+/// When `previous` holds the stored values of the updated record, its
+/// unchanged blobs are kept as they are, while the overflow pages of the
+/// replaced ones are released.
+///
+/// Fails with [`QueryError::InvalidQuery`] if a new blob exceeds
+/// `max_blob_size`.
+pub(super) fn spill_blobs(
+    values: &mut [(ColumnDef, Value)],
+    previous: Option<&[(ColumnDef, Value)]>,
+    max_blob_size: Option<u64>,
+    mm: &mut impl MemoryAccess,
+) -> DbmsResult<()> {
+    for (col_def, value) in values.iter_mut() {
+        if col_def.data_type != DataTypeKind::Blob {
+            continue;
+        }
+        let old_value = previous.and_then(|previous| {
+            previous
+                .iter()
+                .find(|(old_col_def, _)| old_col_def.name == col_def.name)
+                .map(|(_, old_value)| old_value)
+        });
+        if old_value == Some(value) {
+            continue;
+        }
+        if let Some(Value::Blob(old_blob)) = old_value
+            && let Some(overflow) = OverflowBlob::from_bytes(&old_blob.0)
+        {
+            overflow.release(mm)?;
+        }
+
+        let Value::Blob(blob) = value else {
+            continue;
+        };
+        check_blob_size(blob.len() as u64, max_blob_size)?;
+        if blob.len() > INLINE_BLOB_SIZE || OverflowBlob::from_bytes(&blob.0).is_some() {
+            let overflow = OverflowBlob::write(&blob.0, mm)?;
+            *blob = Blob(overflow.to_bytes());
+        }
+    }
+
+    Ok(())
+}
+
+/// Releases the overflow pages of the blobs of a deleted record.
+pub(super) fn release_blobs(
+    values: &[(ColumnDef, Value)],
+    mm: &mut impl MemoryAccess,
+) -> DbmsResult<()> {
+    for (_, value) in values {
+        if let Value::Blob(blob) = value
+            && let Some(overflow) = OverflowBlob::from_bytes(&blob.0)
+        {
+            overflow.release(mm)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Replaces the blobs of `values` stored out of line, or too large to be,
+/// with an empty placeholder.
+pub(super) fn hide_overflow_blobs(values: &mut [(ColumnDef, Value)]) {
+    for (_, value) in values.iter_mut() {
+        if let Value::Blob(blob) = value
+            && (blob.len() > INLINE_BLOB_SIZE || OverflowBlob::from_bytes(&blob.0).is_some())
+        {
+            *blob = Blob::default();
+        }
+    }
+}
+
+/// Reads up to `len` bytes at `offset` of the stored blob `blob`.
+pub(super) fn read_chunk(
+    blob: &Blob,
+    offset: u64,
+    len: u64,
+    mm: &mut impl MemoryAccess,
+) -> DbmsResult<BlobChunk> {
+    if let Some(overflow) = OverflowBlob::from_bytes(&blob.0) {
+        check_offset(offset, overflow.len)?;
+        return Ok(BlobChunk {
+            total_len: overflow.len,
+            bytes: overflow.read(offset, len, mm)?,
+        });
+    }
+
+    let total_len = blob.len() as u64;
+    check_offset(offset, total_len)?;
+    let end = offset.saturating_add(len).min(total_len);
+    Ok(BlobChunk {
+        total_len,
+        bytes: blob.0[offset as usize..end as usize].to_vec(),
+    })
+}
+
+/// Writes `bytes` at `offset` of the stored blob `blob`, returning the blob to
+/// store in the record and its new length.
+///
+/// An inline blob growing past [`INLINE_BLOB_SIZE`] is moved to overflow
+/// pages.
+pub(super) fn write_chunk(
+    blob: &Blob,
+    offset: u64,
+    bytes: &[u8],
+    max_blob_size: Option<u64>,
+    mm: &mut impl MemoryAccess,
+) -> DbmsResult<(Blob, u64)> {
+    if let Some(mut overflow) = OverflowBlob::from_bytes(&blob.0) {
+        check_offset(offset, overflow.len)?;
+        check_blob_size(overflow.len.max(offset + bytes.len() as u64), max_blob_size)?;
+        overflow.write_at(offset, bytes, mm)?;
+        return Ok((Blob(overflow.to_bytes()), overflow.len));
+    }
+
+    check_offset(offset, blob.len() as u64)?;
+    let offset = offset as usize;
+    let mut data = blob.0.clone();
+    let end = offset + bytes.len();
+    if end > data.len() {
+        data.resize(end, 0);
+    }
+    data[offset..end].copy_from_slice(bytes);
+    let len = data.len() as u64;
+    check_blob_size(len, max_blob_size)?;
+    if data.len() > INLINE_BLOB_SIZE || OverflowBlob::from_bytes(&data).is_some() {
+        let overflow = OverflowBlob::write(&data, mm)?;
+        return Ok((Blob(overflow.to_bytes()), len));
+    }
+
+    Ok((Blob(data), len))
+}
+
+/// Fails with [`QueryError::InvalidQuery`] if `len` exceeds `max_blob_size`.
+fn check_blob_size(len: u64, max_blob_size: Option<u64>) -> DbmsResult<()> {
+    match max_blob_size {
+        Some(max) if len > max => Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "blob of {len} bytes exceeds the maximum blob size of {max} bytes"
+        )))),
+        _ => Ok(()),
+    }
+}
+
+/// Fails with [`QueryError::InvalidQuery`] if `offset` is past the end of a
+/// blob of `len` bytes.
+fn check_offset(offset: u64, len: u64) -> DbmsResult<()> {
+    if offset > len {
+        return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "offset {offset} is past the end of the blob of {len} bytes"
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Returns the stored blob of `column` in the record `values` of table `T`.
+///
+/// Fails with [`QueryError::UnknownColumn`] if `column` is not on `T`, and
+/// with [`QueryError::InvalidQuery`] if it is not a blob column or is null.
+pub(super) fn stored_blob<T>(values: &[(ColumnDef, Value)], column: &str) -> DbmsResult<Blob>
+where
+    T: TableSchema,
+{
+    let col_def = T::columns()
+        .iter()
+        .find(|col_def| col_def.name == column)
+        .ok_or_else(|| DbmsError::Query(QueryError::UnknownColumn(column.to_string())))?;
+    if col_def.data_type != DataTypeKind::Blob {
+        return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "column `{column}` is not a blob column"
+        ))));
+    }
+
+    match values.iter().find(|(col_def, _)| col_def.name == column) {
+        Some((_, Value::Blob(blob))) => Ok(blob.clone()),
+        _ => Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "column `{column}` is null"
+        )))),
+    }
+}
+
+/// Fails with [`QueryError::InvalidQuery`] if a chunk of `len` bytes exceeds
+/// `max_chunk_size`.
+pub(super) fn check_chunk_size(len: u64, max_chunk_size: Option<u64>) -> DbmsResult<()> {
+    match max_chunk_size {
+        Some(max) if len > max => Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+            "chunk of {len} bytes exceeds the maximum chunk size of {max} bytes"
+        )))),
+        _ => Ok(()),
+    }
+}
@@ -3,6 +3,8 @@
 
 //! Filter analyzer for choosing a single-column index execution plan.
 
+use std::cmp::Ordering;
+
 use wasm_dbms_api::prelude::{Filter, IndexDef, Value};
 
 /// A single-column index execution plan.
@@ -108,6 +110,20 @@ fn analyze_inner(filter: &Filter, indexed_columns: &[&'static str]) -> Option<An
                 remaining_filter: Some(filter.clone()),
             })
         }
+        // Mismatched or `NULL` bounds fall back to a full scan so `Filter::matches`
+        // reports the error (or the empty result) consistently.
+        Filter::Between(column, low, high)
+            if !low.is_null() && low.type_name() == high.type_name() =>
+        {
+            resolve_column(column, indexed_columns).map(|column| AnalyzedFilter {
+                plan: IndexPlan::Range {
+                    column,
+                    start: Some(low.clone()),
+                    end: Some(high.clone()),
+                },
+                remaining_filter: None,
+            })
+        }
         Filter::In(column, values) => {
             resolve_column(column, indexed_columns).map(|column| AnalyzedFilter {
                 plan: IndexPlan::In {
@@ -117,6 +133,19 @@ fn analyze_inner(filter: &Filter, indexed_columns: &[&'static str]) -> Option<An
                 remaining_filter: None,
             })
         }
+        // Every value starting with the prefix lies between the prefix and its
+        // successor; the range is inclusive, so the original filter trims the
+        // successor and any non-matching value in between.
+        Filter::StartsWith(column, prefix) if !prefix.is_empty() => {
+            resolve_column(column, indexed_columns).map(|column| AnalyzedFilter {
+                plan: IndexPlan::Range {
+                    column,
+                    start: Some(Value::Text(prefix.clone().into())),
+                    end: prefix_successor(prefix).map(|end| Value::Text(end.into())),
+                },
+                remaining_filter: Some(filter.clone()),
+            })
+        }
         Filter::And(left, right) => analyze_and(left, right, indexed_columns),
         _ => None,
     }
@@ -130,34 +159,13 @@ fn analyze_and(
     let left_analysis = analyze_inner(left, indexed_columns);
     let right_analysis = analyze_inner(right, indexed_columns);
 
+    if let (Some(left_analysis), Some(right_analysis)) = (&left_analysis, &right_analysis)
+        && let Some(merged) = merge_ranges(left_analysis, right_analysis)
+    {
+        return Some(merged);
+    }
+
     match (left_analysis, right_analysis) {
-        (
-            Some(AnalyzedFilter {
-                plan:
-                    IndexPlan::Range {
-                        column: left_column,
-                        start: left_start,
-                        end: left_end,
-                    },
-                remaining_filter: left_remaining,
-            }),
-            Some(AnalyzedFilter {
-                plan:
-                    IndexPlan::Range {
-                        column: right_column,
-                        start: right_start,
-                        end: right_end,
-                    },
-                remaining_filter: right_remaining,
-            }),
-        ) if left_column == right_column => Some(AnalyzedFilter {
-            plan: IndexPlan::Range {
-                column: left_column,
-                start: left_start.or(right_start),
-                end: left_end.or(right_end),
-            },
-            remaining_filter: combine_filters(left_remaining, right_remaining),
-        }),
         (Some(left_analysis), Some(right_analysis)) => Some(AnalyzedFilter {
             plan: left_analysis.plan,
             remaining_filter: combine_filters(
@@ -177,6 +185,79 @@ fn analyze_and(
     }
 }
 
+/// Merges two range plans on the same column into a single range.
+///
+/// Both sides are inclusive, so the merged range keeps the greater start and
+/// the smaller end; strict bounds stay in the remaining filters. Returns `None`
+/// when the plans can't be merged.
+fn merge_ranges(left: &AnalyzedFilter, right: &AnalyzedFilter) -> Option<AnalyzedFilter> {
+    let (
+        IndexPlan::Range {
+            column: left_column,
+            start: left_start,
+            end: left_end,
+        },
+        IndexPlan::Range {
+            column: right_column,
+            start: right_start,
+            end: right_end,
+        },
+    ) = (&left.plan, &right.plan)
+    else {
+        return None;
+    };
+    if left_column != right_column {
+        return None;
+    }
+
+    Some(AnalyzedFilter {
+        plan: IndexPlan::Range {
+            column: left_column,
+            start: tighter_bound(left_start, right_start, Ordering::Greater)?,
+            end: tighter_bound(left_end, right_end, Ordering::Less)?,
+        },
+        remaining_filter: combine_filters(
+            left.remaining_filter.clone(),
+            right.remaining_filter.clone(),
+        ),
+    })
+}
+
+/// Picks the bound that restricts the range the most: the one ordered as
+/// `keep` relative to the other when both are set.
+///
+/// Returns `None` if both bounds are set but of different types, since
+/// [`Value`] ordering across types doesn't reflect their numeric order.
+fn tighter_bound(
+    left: &Option<Value>,
+    right: &Option<Value>,
+    keep: Ordering,
+) -> Option<Option<Value>> {
+    match (left, right) {
+        (Some(left), Some(right)) if left.type_name() != right.type_name() => None,
+        (Some(left), Some(right)) if right.cmp(left) == keep => Some(Some(right.clone())),
+        (Some(bound), _) | (None, Some(bound)) => Some(Some(bound.clone())),
+        (None, None) => Some(None),
+    }
+}
+
+/// Returns the smallest string greater than every string starting with
+/// `prefix`, or `None` if there is none (e.g. every char is [`char::MAX`]).
+fn prefix_successor(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(last) = chars.pop() {
+        let next = match last {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
 fn resolve_column(column: &str, indexed_columns: &[&'static str]) -> Option<&'static str> {
     indexed_columns
         .iter()
@@ -242,6 +323,40 @@ mod tests {
         assert_eq!(analyzed.remaining_filter, Some(filter));
     }
 
+    #[test]
+    fn test_starts_with_on_indexed_column_scans_prefix_range() {
+        let filter = Filter::starts_with("name", "al");
+        let analyzed = analyze_filter(&filter, single_index()).expect("analysis should exist");
+
+        assert_eq!(
+            analyzed.plan,
+            IndexPlan::Range {
+                column: "name",
+                start: Some(Value::Text("al".to_string().into())),
+                end: Some(Value::Text("am".to_string().into())),
+            }
+        );
+        assert_eq!(analyzed.remaining_filter, Some(filter));
+    }
+
+    #[test]
+    fn test_should_compute_prefix_successor() {
+        assert_eq!(super::prefix_successor("abc").as_deref(), Some("abd"));
+        assert_eq!(super::prefix_successor("a\u{10FFFF}").as_deref(), Some("b"));
+        assert_eq!(
+            super::prefix_successor("\u{D7FF}").as_deref(),
+            Some("\u{E000}")
+        );
+        assert_eq!(super::prefix_successor("\u{10FFFF}"), None);
+        assert_eq!(super::prefix_successor(""), None);
+    }
+
+    #[test]
+    fn test_empty_starts_with_and_ends_with_are_not_indexed() {
+        assert!(analyze_filter(&Filter::starts_with("name", ""), single_index()).is_none());
+        assert!(analyze_filter(&Filter::ends_with("name", "ce"), single_index()).is_none());
+    }
+
     #[test]
     fn test_in_on_indexed_column() {
         let filter = Filter::in_list(
@@ -309,6 +424,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_and_range_keeps_tighter_bounds() {
+        let filter = Filter::And(
+            Box::new(Filter::And(
+                Box::new(Filter::ge("name", Value::Text("a".to_string().into()))),
+                Box::new(Filter::ge("name", Value::Text("c".to_string().into()))),
+            )),
+            Box::new(Filter::And(
+                Box::new(Filter::le("name", Value::Text("x".to_string().into()))),
+                Box::new(Filter::le("name", Value::Text("m".to_string().into()))),
+            )),
+        );
+        let analyzed = analyze_filter(&filter, single_index()).expect("analysis should exist");
+
+        assert_eq!(
+            analyzed,
+            AnalyzedFilter {
+                plan: IndexPlan::Range {
+                    column: "name",
+                    start: Some(Value::Text("c".to_string().into())),
+                    end: Some(Value::Text("m".to_string().into())),
+                },
+                remaining_filter: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_and_range_with_mismatched_bound_types_keeps_right_as_filter() {
+        let right = Filter::ge("name", Value::Uint32(10.into()));
+        let filter = Filter::And(
+            Box::new(Filter::ge("name", Value::Text("a".to_string().into()))),
+            Box::new(right.clone()),
+        );
+        let analyzed = analyze_filter(&filter, single_index()).expect("analysis should exist");
+
+        assert_eq!(
+            analyzed,
+            AnalyzedFilter {
+                plan: IndexPlan::Range {
+                    column: "name",
+                    start: Some(Value::Text("a".to_string().into())),
+                    end: None,
+                },
+                remaining_filter: Some(right),
+            }
+        );
+    }
+
+    #[test]
+    fn test_between_on_indexed_column() {
+        let filter = Filter::between(
+            "name",
+            Value::Text("a".to_string().into()),
+            Value::Text("m".to_string().into()),
+        );
+        let analyzed = analyze_filter(&filter, single_index()).expect("analysis should exist");
+
+        assert_eq!(
+            analyzed,
+            AnalyzedFilter {
+                plan: IndexPlan::Range {
+                    column: "name",
+                    start: Some(Value::Text("a".to_string().into())),
+                    end: Some(Value::Text("m".to_string().into())),
+                },
+                remaining_filter: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_between_with_mismatched_bounds_returns_none() {
+        let filter = Filter::between(
+            "name",
+            Value::Text("a".to_string().into()),
+            Value::Uint32(10.into()),
+        );
+        assert!(analyze_filter(&filter, single_index()).is_none());
+    }
+
     #[test]
     fn test_or_returns_none() {
         let filter = Filter::Or(
@@ -69,8 +69,13 @@ where
     db.ctx.set_migrating(false);
     {
         let mut mm = db.ctx.mm.borrow_mut();
-        let refreshed = wasm_dbms_memory::SchemaRegistry::load(&mut *mm)?;
-        *db.ctx.schema_registry.borrow_mut() = refreshed;
+        let mut refreshed = wasm_dbms_memory::SchemaRegistry::load(&mut *mm)?;
+        let mut sr = db.ctx.schema_registry.borrow_mut();
+        // retention policies live on the heap only
+        for policy in sr.retention_policies() {
+            refreshed.set_retention_policy(*policy);
+        }
+        *sr = refreshed;
     }
     result?;
 
@@ -226,7 +231,9 @@ where
     M: MemoryProvider,
     A: AccessControl,
 {
-    let rows = db.schema.select(db, table, Query::builder().build())?;
+    let rows = db
+        .schema
+        .select(db, table, Query::builder().include_deleted().build())?;
     for row in rows {
         let Some((_, value)) = row.iter().find(|(c, _)| c.name == column) else {
             continue;
@@ -254,6 +261,7 @@ where
         db,
         &fk.table,
         Query::builder()
+            .include_deleted()
             .filter(Some(Filter::eq(&fk.column, value.clone())))
             .build(),
     )?;
@@ -817,7 +825,9 @@ where
     M: MemoryProvider,
     A: AccessControl,
 {
-    let rows = db.schema.select(db, table, Query::builder().build())?;
+    let rows = db
+        .schema
+        .select(db, table, Query::builder().include_deleted().build())?;
     for row in rows {
         let value_is_null = row
             .iter()
@@ -844,7 +854,9 @@ where
     M: MemoryProvider,
     A: AccessControl,
 {
-    let rows = db.schema.select(db, table, Query::builder().build())?;
+    let rows = db
+        .schema
+        .select(db, table, Query::builder().include_deleted().build())?;
     let mut seen = std::collections::HashSet::new();
     for row in rows {
         if let Some((_, value)) = row.iter().find(|(col_def, _)| col_def.name == column)
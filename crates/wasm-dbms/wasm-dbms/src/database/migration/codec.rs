@@ -10,12 +10,15 @@
 //! snapshot and re-encode them under a target snapshot. Custom-typed columns
 //! produce [`CustomValue`] with an empty `display`; consumers
 //! (`Migrate::transform_column`, the symmetric encode) only depend on
-//! `type_tag` + `encoded` and never read `display`.
+//! `type_tag` + `encoded` and never read `display`. Enum columns are
+//! snapshotted as `Uint8`, so their values decode to the `Uint8`
+//! discriminant.
 
 use wasm_dbms_api::prelude::{
-    Blob, Boolean, CustomValue, DataTypeSnapshot, Date, DateTime, Decimal, DecodeError, Encode,
-    Int8, Int16, Int32, Int64, Json, MemoryError, MemoryResult, TableSchemaSnapshot, Text, Uint8,
-    Uint16, Uint32, Uint64, Uuid, Value, WireSize,
+    Blob, Boolean, Char, CustomValue, DataTypeSnapshot, Date, DateTime, Decimal, DecodeError,
+    Encode, Float32, Float64, Int8, Int16, Int32, Int64, Json, MemoryError, MemoryResult,
+    TableSchemaSnapshot, Text, Timestamp, Uint8, Uint16, Uint32, Uint64, Uuid, Value, WireSize,
+    ZonedDateTime, decode_compressed,
 };
 
 /// Decode raw record bytes under the given stored snapshot into a
@@ -122,6 +125,7 @@ fn decode_non_nullable(dt: &DataTypeSnapshot, bytes: &[u8]) -> MemoryResult<(Val
         DataTypeSnapshot::Uint32 => {
             decode_fixed::<Uint32>(bytes, 4).map(|(v, n)| (Value::Uint32(v), n))
         }
+        DataTypeSnapshot::Char => decode_fixed::<Char>(bytes, 4).map(|(v, n)| (Value::Char(v), n)),
         DataTypeSnapshot::Date => decode_fixed::<Date>(bytes, 4).map(|(v, n)| (Value::Date(v), n)),
         DataTypeSnapshot::Int64 => {
             decode_fixed::<Int64>(bytes, 8).map(|(v, n)| (Value::Int64(v), n))
@@ -129,22 +133,47 @@ fn decode_non_nullable(dt: &DataTypeSnapshot, bytes: &[u8]) -> MemoryResult<(Val
         DataTypeSnapshot::Uint64 => {
             decode_fixed::<Uint64>(bytes, 8).map(|(v, n)| (Value::Uint64(v), n))
         }
+        DataTypeSnapshot::Timestamp => {
+            decode_fixed::<Timestamp>(bytes, 8).map(|(v, n)| (Value::Timestamp(v), n))
+        }
+        DataTypeSnapshot::Float32 => {
+            decode_fixed::<Float32>(bytes, 4).map(|(v, n)| (Value::Float32(v), n))
+        }
+        DataTypeSnapshot::Float64 => {
+            decode_fixed::<Float64>(bytes, 8).map(|(v, n)| (Value::Float64(v), n))
+        }
         DataTypeSnapshot::Datetime => {
             decode_fixed::<DateTime>(bytes, 13).map(|(v, n)| (Value::DateTime(v), n))
         }
+        DataTypeSnapshot::ZonedDatetime => {
+            decode_fixed::<ZonedDateTime>(bytes, 10).map(|(v, n)| (Value::ZonedDateTime(v), n))
+        }
         DataTypeSnapshot::Decimal => {
             decode_fixed::<Decimal>(bytes, 16).map(|(v, n)| (Value::Decimal(v), n))
         }
         DataTypeSnapshot::Uuid => decode_fixed::<Uuid>(bytes, 16).map(|(v, n)| (Value::Uuid(v), n)),
-        DataTypeSnapshot::Text => {
-            decode_length_prefixed::<Text>(bytes).map(|(v, n)| (Value::Text(v), n))
-        }
-        DataTypeSnapshot::Blob => {
-            decode_length_prefixed::<Blob>(bytes).map(|(v, n)| (Value::Blob(v), n))
-        }
+        // `#[compress]` columns: plain values decode as they are
+        DataTypeSnapshot::Text => decode_compressed::<Text>(std::borrow::Cow::Borrowed(bytes))
+            .map(|(v, n)| (Value::Text(v), n as usize)),
+        DataTypeSnapshot::Blob => decode_compressed::<Blob>(std::borrow::Cow::Borrowed(bytes))
+            .map(|(v, n)| (Value::Blob(v), n as usize)),
         DataTypeSnapshot::Json => {
             decode_length_prefixed::<Json>(bytes).map(|(v, n)| (Value::Json(v), n))
         }
+        DataTypeSnapshot::Array(elem) => {
+            if bytes.len() < 2 {
+                return Err(MemoryError::DecodeError(DecodeError::TooShort));
+            }
+            let count = u16::from_le_bytes([bytes[0], bytes[1]]) as usize;
+            let mut consumed = 2;
+            let mut values = Vec::with_capacity(count);
+            for _ in 0..count {
+                let (value, n) = decode_non_nullable(elem, &bytes[consumed..])?;
+                values.push(value);
+                consumed += n;
+            }
+            Ok((Value::Array(values), consumed))
+        }
         DataTypeSnapshot::Custom(meta) => {
             let (slice, consumed) = match meta.wire_size {
                 WireSize::Fixed(n) => {
@@ -173,15 +202,6 @@ fn decode_non_nullable(dt: &DataTypeSnapshot, bytes: &[u8]) -> MemoryResult<(Val
             });
             Ok((value, consumed))
         }
-        // `Float32`/`Float64` exist in `DataTypeSnapshot` but no compile-time
-        // `Value::FloatXX` variants, so a compiled schema cannot produce them.
-        // If a stored snapshot somehow carries one, fail loud rather than
-        // silently misdecoding bytes.
-        DataTypeSnapshot::Float32 | DataTypeSnapshot::Float64 => {
-            Err(MemoryError::DecodeError(DecodeError::IdentityDecodeError(
-                format!("Float types are not yet wired into the snapshot codec: {dt:?}"),
-            )))
-        }
     }
 }
 
@@ -242,28 +262,40 @@ fn encode_non_nullable(
     match (dt, value) {
         (DataTypeSnapshot::Int8, Value::Int8(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Uint8, Value::Uint8(v)) => out.extend_from_slice(&v.encode()),
+        // enum values, such as the default of an added enum column
+        (DataTypeSnapshot::Uint8, Value::Custom(cv)) if cv.encoded.len() == 1 => {
+            out.extend_from_slice(&cv.encoded);
+        }
         (DataTypeSnapshot::Boolean, Value::Boolean(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Int16, Value::Int16(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Uint16, Value::Uint16(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Int32, Value::Int32(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Uint32, Value::Uint32(v)) => out.extend_from_slice(&v.encode()),
+        (DataTypeSnapshot::Char, Value::Char(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Date, Value::Date(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Int64, Value::Int64(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Uint64, Value::Uint64(v)) => out.extend_from_slice(&v.encode()),
+        (DataTypeSnapshot::Timestamp, Value::Timestamp(v)) => out.extend_from_slice(&v.encode()),
+        (DataTypeSnapshot::Float32, Value::Float32(v)) => out.extend_from_slice(&v.encode()),
+        (DataTypeSnapshot::Float64, Value::Float64(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Datetime, Value::DateTime(v)) => out.extend_from_slice(&v.encode()),
+        (DataTypeSnapshot::ZonedDatetime, Value::ZonedDateTime(v)) => {
+            out.extend_from_slice(&v.encode())
+        }
         (DataTypeSnapshot::Decimal, Value::Decimal(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Uuid, Value::Uuid(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Text, Value::Text(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Blob, Value::Blob(v)) => out.extend_from_slice(&v.encode()),
         (DataTypeSnapshot::Json, Value::Json(v)) => out.extend_from_slice(&v.encode()),
+        (DataTypeSnapshot::Array(elem), Value::Array(values)) => {
+            out.extend_from_slice(&(values.len() as u16).to_le_bytes());
+            for value in values {
+                encode_non_nullable(elem, value, out)?;
+            }
+        }
         (DataTypeSnapshot::Custom(_), Value::Custom(cv)) => {
             out.extend_from_slice(&cv.encoded);
         }
-        (DataTypeSnapshot::Float32 | DataTypeSnapshot::Float64, _) => {
-            return Err(MemoryError::DecodeError(DecodeError::IdentityDecodeError(
-                format!("Float types are not yet wired into the snapshot codec: {dt:?}"),
-            )));
-        }
         (dt, value) => {
             return Err(MemoryError::DecodeError(DecodeError::IdentityDecodeError(
                 format!("Value variant does not match data type: {dt:?} vs {value:?}"),
@@ -276,8 +308,8 @@ fn encode_non_nullable(
 #[cfg(test)]
 mod tests {
     use wasm_dbms_api::prelude::{
-        ColumnSnapshot, CustomDataTypeSnapshot, DataTypeSnapshot, Int64, TableSchemaSnapshot, Text,
-        Uint32, Value, WireSize,
+        ArrayOf, ColumnSnapshot, CustomDataTypeSnapshot, DataTypeSnapshot, Int64,
+        TableSchemaSnapshot, Text, Uint32, Value, WireSize, encode_compressed,
     };
 
     use super::*;
@@ -321,6 +353,26 @@ mod tests {
         assert_eq!(decoded, values);
     }
 
+    #[test]
+    fn test_decode_compressed_text() {
+        let snap = snap_with(vec![
+            col("id", DataTypeSnapshot::Uint32, false),
+            col("body", DataTypeSnapshot::Text, false),
+        ]);
+        let body = Text("lorem ipsum ".repeat(50));
+        let mut bytes = Uint32(7).encode().into_owned();
+        bytes.extend_from_slice(&encode_compressed(&body));
+
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                ("id".into(), Value::Uint32(Uint32(7))),
+                ("body".into(), Value::Text(body)),
+            ]
+        );
+    }
+
     #[test]
     fn test_round_trip_nullable_null_and_value() {
         let snap = snap_with(vec![
@@ -336,6 +388,125 @@ mod tests {
         assert_eq!(decoded, values);
     }
 
+    #[test]
+    fn test_round_trip_float32_float64() {
+        let snap = snap_with(vec![
+            col("a", DataTypeSnapshot::Float32, false),
+            col("b", DataTypeSnapshot::Float64, true),
+        ]);
+        let values = vec![
+            ("a".into(), Value::Float32(Float32(-1.5))),
+            ("b".into(), Value::Float64(Float64(f64::NAN))),
+        ];
+        let bytes = encode_record_by_snapshot(&values, &snap).unwrap();
+        assert_eq!(bytes.len(), 4 + 1 + 8);
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_round_trip_char() {
+        let snap = snap_with(vec![
+            col("grade", DataTypeSnapshot::Char, false),
+            col("symbol", DataTypeSnapshot::Char, true),
+        ]);
+        let values = vec![
+            ("grade".into(), Value::Char(Char('A'))),
+            ("symbol".into(), Value::Char(Char('€'))),
+        ];
+        let bytes = encode_record_by_snapshot(&values, &snap).unwrap();
+        assert_eq!(bytes.len(), 4 + 1 + 4);
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_encode_enum_value_as_uint8_discriminant() {
+        let snap = snap_with(vec![col("status", DataTypeSnapshot::Uint8, false)]);
+        let values = vec![(
+            "status".into(),
+            Value::Custom(CustomValue {
+                type_tag: "status".into(),
+                encoded: vec![2],
+                display: "Archived".into(),
+            }),
+        )];
+        let bytes = encode_record_by_snapshot(&values, &snap).unwrap();
+        assert_eq!(bytes, vec![2]);
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(decoded, vec![("status".into(), Value::Uint8(Uint8(2)))]);
+    }
+
+    #[test]
+    fn test_round_trip_timestamp() {
+        let snap = snap_with(vec![
+            col("created_at", DataTypeSnapshot::Timestamp, false),
+            col("deleted_at", DataTypeSnapshot::Timestamp, true),
+        ]);
+        let values = vec![
+            (
+                "created_at".into(),
+                Value::Timestamp(Timestamp(1_774_794_600_000_000_000)),
+            ),
+            ("deleted_at".into(), Value::Null),
+        ];
+        let bytes = encode_record_by_snapshot(&values, &snap).unwrap();
+        assert_eq!(bytes.len(), 8 + 1);
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_round_trip_zoned_datetime() {
+        let snap = snap_with(vec![
+            col("starts_at", DataTypeSnapshot::ZonedDatetime, false),
+            col("ends_at", DataTypeSnapshot::ZonedDatetime, true),
+        ]);
+        let values = vec![
+            (
+                "starts_at".into(),
+                Value::ZonedDateTime(ZonedDateTime::new(1_774_794_600_000_000_000, 120)),
+            ),
+            (
+                "ends_at".into(),
+                Value::ZonedDateTime(ZonedDateTime::new(-1, -300)),
+            ),
+        ];
+        let bytes = encode_record_by_snapshot(&values, &snap).unwrap();
+        assert_eq!(bytes.len(), 10 + 1 + 10);
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_round_trip_array() {
+        let snap = snap_with(vec![
+            col(
+                "ids",
+                DataTypeSnapshot::Array(Box::new(DataTypeSnapshot::Uint32)),
+                false,
+            ),
+            col(
+                "tags",
+                DataTypeSnapshot::Array(Box::new(DataTypeSnapshot::Text)),
+                true,
+            ),
+        ]);
+        let ids = ArrayOf(vec![Uint32(1), Uint32(2)]);
+        let tags = ArrayOf(vec![Text("a".into()), Text("bc".into())]);
+        let values = vec![
+            ("ids".into(), Value::from(ids.clone())),
+            ("tags".into(), Value::from(tags.clone())),
+        ];
+        let bytes = encode_record_by_snapshot(&values, &snap).unwrap();
+        let mut expected = ids.encode().into_owned();
+        expected.push(1);
+        expected.extend_from_slice(&tags.encode());
+        assert_eq!(bytes, expected);
+        let decoded = decode_record_by_snapshot(&bytes, &snap).unwrap();
+        assert_eq!(decoded, values);
+    }
+
     #[test]
     fn test_round_trip_custom_fixed() {
         let snap = snap_with(vec![col(
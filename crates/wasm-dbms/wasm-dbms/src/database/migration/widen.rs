@@ -14,8 +14,8 @@
 //! Anything else returns [`MigrationError::WideningIncompatible`].
 
 use wasm_dbms_api::prelude::{
-    DataTypeSnapshot, DbmsError, DbmsResult, Int16, Int32, Int64, MigrationError, Uint16, Uint32,
-    Uint64, Value,
+    DataTypeSnapshot, DbmsError, DbmsResult, Float64, Int16, Int32, Int64, MigrationError, Uint16,
+    Uint32, Uint64, Value,
 };
 
 /// Apply a single-column widening conversion. `Value::Null` is preserved
@@ -51,6 +51,7 @@ pub(crate) fn widen_value(
         (D::Uint16, D::Int32, Value::Uint16(v)) => Value::Int32(Int32(v.0 as i32)),
         (D::Uint16, D::Int64, Value::Uint16(v)) => Value::Int64(Int64(v.0 as i64)),
         (D::Uint32, D::Int64, Value::Uint32(v)) => Value::Int64(Int64(v.0 as i64)),
+        (D::Float32, D::Float64, Value::Float32(v)) => Value::Float64(Float64(v.0 as f64)),
         (old, new, _) => {
             return Err(DbmsError::Migration(MigrationError::WideningIncompatible {
                 table: table.to_string(),
@@ -108,6 +109,19 @@ mod tests {
         assert_eq!(v, Value::Int32(Int32(40_000)));
     }
 
+    #[test]
+    fn test_widen_float32_to_float64() {
+        let v = widen_value(
+            "t",
+            "c",
+            &DataTypeSnapshot::Float32,
+            &DataTypeSnapshot::Float64,
+            Value::Float32(wasm_dbms_api::prelude::Float32(2.5)),
+        )
+        .unwrap();
+        assert_eq!(v, Value::Float64(Float64(2.5)));
+    }
+
     #[test]
     fn test_widen_null_preserved() {
         let v = widen_value(
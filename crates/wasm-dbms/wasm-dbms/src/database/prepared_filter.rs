@@ -0,0 +1,330 @@
+//! Query filters prepared once per query before matching rows.
+
+use std::collections::HashSet;
+
+use wasm_dbms_api::prelude::{ColumnDef, DbmsError, DbmsResult, Filter, QueryError, Uint64, Value};
+use wasm_dbms_memory::prelude::OverflowBlob;
+
+/// Length above which the list of a [`Filter::In`] is matched through a
+/// [`HashSet`] rather than scanned.
+pub const IN_SET_THRESHOLD: usize = 16;
+
+/// A [`Filter`] prepared for matching the rows of a query.
+///
+/// The lists of the `IN` filters longer than [`IN_SET_THRESHOLD`] are collected
+/// into a [`HashSet`] once, so checking a row no longer scans them, and the
+/// `BLOBLEN` filters read the length of blobs stored out of line from their
+/// reference. Subtrees without either are kept as they are and matched by
+/// [`Filter::matches`].
+///
+/// Only the reference of a blob stored out of line is read with its row, so
+/// comparing its content fails with [`QueryError::InvalidQuery`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreparedFilter {
+    /// A subtree matched as is.
+    Filter(Filter),
+    /// A long `IN` list.
+    InSet(String, HashSet<Value>),
+    /// A `BLOBLEN` filter.
+    BlobLen(String, Filter),
+    And(Box<PreparedFilter>, Box<PreparedFilter>),
+    Or(Box<PreparedFilter>, Box<PreparedFilter>),
+    Not(Box<PreparedFilter>),
+}
+
+impl PreparedFilter {
+    /// Prepares `filter`, failing with [`QueryError::InvalidQuery`] if it
+    /// holds an `IN` filter with an empty list.
+    pub fn new(filter: &Filter) -> DbmsResult<Self> {
+        validate_in_lists(filter)?;
+        Ok(Self::prepare(filter).unwrap_or_else(|| Self::Filter(filter.clone())))
+    }
+
+    /// Returns whether the record matches the filter.
+    pub fn matches(&self, values: &[(ColumnDef, Value)]) -> DbmsResult<bool> {
+        let res = match self {
+            Self::Filter(filter) => {
+                reject_overflow_content(values, |column| compares_content(filter, column))?;
+                filter.matches(values)?
+            }
+            Self::InSet(field, set) => {
+                reject_overflow_content(values, |column| column == field)?;
+                values
+                    .iter()
+                    .find(|(col, _)| col.name == field)
+                    .is_some_and(|(_, val)| set.contains(val))
+            }
+            Self::BlobLen(field, inner) => {
+                let len = match values.iter().find(|(col, _)| col.name == field) {
+                    Some((_, Value::Blob(blob))) => OverflowBlob::from_bytes(&blob.0)
+                        .map_or(blob.len() as u64, |overflow| overflow.len),
+                    None | Some((_, Value::Null)) => return Ok(false),
+                    Some(_) => {
+                        return Err(DbmsError::Query(QueryError::InvalidQuery(
+                            "BLOBLEN operator can only be applied to Blob values".to_string(),
+                        )));
+                    }
+                };
+                inner.matches_value(field, &Value::Uint64(Uint64(len)))?
+            }
+            Self::And(left, right) => left.matches(values)? && right.matches(values)?,
+            Self::Or(left, right) => left.matches(values)? || right.matches(values)?,
+            Self::Not(inner) => !inner.matches(values)?,
+        };
+
+        Ok(res)
+    }
+
+    /// Prepares `filter`, or returns `None` when it holds no long `IN` list
+    /// nor `BLOBLEN` filter.
+    fn prepare(filter: &Filter) -> Option<Self> {
+        match filter {
+            Filter::In(field, list) if list.len() > IN_SET_THRESHOLD => {
+                Some(Self::InSet(field.clone(), list.iter().cloned().collect()))
+            }
+            Filter::BlobLen(field, inner) => {
+                Some(Self::BlobLen(field.clone(), inner.as_ref().clone()))
+            }
+            Filter::And(left, right) => {
+                Self::prepare_pair(left, right).map(|(left, right)| Self::And(left, right))
+            }
+            Filter::Or(left, right) => {
+                Self::prepare_pair(left, right).map(|(left, right)| Self::Or(left, right))
+            }
+            Filter::Not(inner) => Self::prepare(inner).map(|inner| Self::Not(Box::new(inner))),
+            _ => None,
+        }
+    }
+
+    /// Prepares both operands of an `AND` / `OR`, or returns `None` when
+    /// neither needs preparing.
+    fn prepare_pair(left: &Filter, right: &Filter) -> Option<(Box<Self>, Box<Self>)> {
+        match (Self::prepare(left), Self::prepare(right)) {
+            (None, None) => None,
+            (prepared_left, prepared_right) => Some((
+                Box::new(prepared_left.unwrap_or_else(|| Self::Filter(left.clone()))),
+                Box::new(prepared_right.unwrap_or_else(|| Self::Filter(right.clone()))),
+            )),
+        }
+    }
+}
+
+/// Fails with [`QueryError::InvalidQuery`] if a blob of `values` stored out
+/// of line is in a column whose content is compared, as told by `compared`.
+fn reject_overflow_content(
+    values: &[(ColumnDef, Value)],
+    compared: impl Fn(&str) -> bool,
+) -> DbmsResult<()> {
+    for (col_def, value) in values {
+        if let Value::Blob(blob) = value
+            && OverflowBlob::from_bytes(&blob.0).is_some()
+            && compared(col_def.name)
+        {
+            return Err(DbmsError::Query(QueryError::InvalidQuery(format!(
+                "cannot compare the content of blob '{}' stored out of line; filter it by length or nullness",
+                col_def.name
+            ))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns whether `filter` compares the content of `column`, rather than
+/// only its length or nullness.
+fn compares_content(filter: &Filter, column: &str) -> bool {
+    match filter {
+        Filter::Eq(field, _)
+        | Filter::Ne(field, _)
+        | Filter::Gt(field, _)
+        | Filter::Lt(field, _)
+        | Filter::Ge(field, _)
+        | Filter::Le(field, _)
+        | Filter::In(field, _)
+        | Filter::AnyOf(field, _)
+        | Filter::AllOf(field, _)
+        | Filter::Json(field, _)
+        | Filter::JsonPath(field, _, _)
+        | Filter::Between(field, _, _)
+        | Filter::Like(field, _)
+        | Filter::ILike(field, _)
+        | Filter::StartsWith(field, _)
+        | Filter::EndsWith(field, _)
+        | Filter::Regex(field, _)
+        | Filter::StrLen(field, _) => field == column,
+        Filter::CompareColumns(left, _, right) => left == column || right == column,
+        Filter::BlobLen(_, _) | Filter::NotNull(_) | Filter::IsNull(_) => false,
+        Filter::And(left, right) | Filter::Or(left, right) => {
+            compares_content(left, column) || compares_content(right, column)
+        }
+        Filter::Not(inner) => compares_content(inner, column),
+    }
+}
+
+/// Fails with [`QueryError::InvalidQuery`] if `filter` holds an `IN` filter
+/// with an empty list, which could never match.
+pub fn validate_in_lists(filter: &Filter) -> DbmsResult<()> {
+    match filter {
+        Filter::In(field, list) if list.is_empty() => Err(DbmsError::Query(
+            QueryError::InvalidQuery(format!("IN list on '{field}' must not be empty")),
+        )),
+        Filter::And(left, right) | Filter::Or(left, right) => {
+            validate_in_lists(left)?;
+            validate_in_lists(right)
+        }
+        Filter::Not(inner) => validate_in_lists(inner),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use wasm_dbms_api::prelude::{Blob, DataTypeKind, Int32, Text};
+
+    use super::*;
+
+    fn row(id: i32, name: &str) -> Vec<(ColumnDef, Value)> {
+        let column = |name, data_type| ColumnDef {
+            name,
+            data_type,
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        vec![
+            (column("id", DataTypeKind::Int32), Value::Int32(Int32(id))),
+            (
+                column("name", DataTypeKind::Text),
+                Value::Text(Text(name.to_string())),
+            ),
+        ]
+    }
+
+    fn ids(range: std::ops::Range<i32>) -> Vec<Value> {
+        range.map(|id| Value::Int32(Int32(id))).collect()
+    }
+
+    #[test]
+    fn test_should_keep_short_in_list_as_filter() {
+        let filter = Filter::in_list("id", ids(0..IN_SET_THRESHOLD as i32));
+        let prepared = PreparedFilter::new(&filter).unwrap();
+        assert_eq!(prepared, PreparedFilter::Filter(filter));
+    }
+
+    #[test]
+    fn test_should_collect_long_in_list_into_set() {
+        let filter = Filter::in_list("id", ids(0..5_000));
+        let prepared = PreparedFilter::new(&filter).unwrap();
+        let PreparedFilter::InSet(field, set) = &prepared else {
+            panic!("expected a set, got {prepared:?}");
+        };
+        assert_eq!(field, "id");
+        assert_eq!(set.len(), 5_000);
+
+        for id in [0, 2_500, 4_999] {
+            assert!(prepared.matches(&row(id, "a")).unwrap());
+        }
+        for id in [-1, 5_000, 10_000] {
+            assert!(!prepared.matches(&row(id, "a")).unwrap());
+        }
+        // missing column
+        let filter = Filter::in_list("age", ids(0..5_000));
+        let prepared = PreparedFilter::new(&filter).unwrap();
+        assert!(!prepared.matches(&row(1, "a")).unwrap());
+    }
+
+    #[test]
+    fn test_should_prepare_long_in_list_inside_logical_filters() {
+        let filter = Filter::in_list("id", ids(0..1_000))
+            .and(Filter::eq("name", Value::Text(Text("a".to_string()))))
+            .or(Filter::in_list("id", ids(5_000..6_000)).not());
+        let prepared = PreparedFilter::new(&filter).unwrap();
+        let PreparedFilter::Or(left, right) = &prepared else {
+            panic!("expected OR, got {prepared:?}");
+        };
+        assert!(matches!(
+            left.as_ref(),
+            PreparedFilter::And(set, rest)
+                if matches!(set.as_ref(), PreparedFilter::InSet(_, _))
+                    && matches!(rest.as_ref(), PreparedFilter::Filter(_))
+        ));
+        assert!(matches!(right.as_ref(), PreparedFilter::Not(_)));
+
+        for (id, name) in [(10, "a"), (10, "b"), (5_500, "a"), (7_000, "b")] {
+            let values = row(id, name);
+            assert_eq!(
+                prepared.matches(&values).unwrap(),
+                filter.matches(&values).unwrap(),
+                "mismatch for {id} {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_should_match_blob_len_of_blob_stored_out_of_line() {
+        let reference = OverflowBlob {
+            len: 10_000,
+            first_page: 3,
+        };
+        let mut values = row(1, "a");
+        values.push((
+            ColumnDef {
+                name: "data",
+                data_type: DataTypeKind::Blob,
+                ..values[0].0
+            },
+            Value::Blob(Blob(reference.to_bytes())),
+        ));
+
+        let filter = Filter::blob_len("data", Filter::eq("data", Value::Uint64(Uint64(10_000))));
+        assert!(
+            PreparedFilter::new(&filter)
+                .unwrap()
+                .matches(&values)
+                .unwrap()
+        );
+        let filter = Filter::blob_len("data", Filter::lt("data", Value::Uint64(Uint64(100))))
+            .or(Filter::eq("id", Value::Int32(Int32(2))));
+        assert!(
+            !PreparedFilter::new(&filter)
+                .unwrap()
+                .matches(&values)
+                .unwrap()
+        );
+
+        let filter = Filter::eq("name", Value::Text(Text("a".to_string())))
+            .and(Filter::eq("data", Value::Blob(Blob(vec![1]))).not());
+        let err = PreparedFilter::new(&filter)
+            .unwrap()
+            .matches(&values)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            DbmsError::Query(QueryError::InvalidQuery(message)) if message.contains("'data'")
+        ));
+        let filter = Filter::not_null("data");
+        assert!(
+            PreparedFilter::new(&filter)
+                .unwrap()
+                .matches(&values)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_reject_empty_in_list() {
+        let filter = Filter::eq("name", Value::Text(Text("a".to_string())))
+            .and(Filter::in_list("id", vec![]).not());
+        let err = PreparedFilter::new(&filter).unwrap_err();
+        assert!(matches!(
+            err,
+            DbmsError::Query(QueryError::InvalidQuery(message)) if message.contains("'id'")
+        ));
+    }
+}
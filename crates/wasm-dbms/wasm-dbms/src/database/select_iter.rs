@@ -0,0 +1,213 @@
+// X-WHERE-CLAUSE, M-CANONICAL-DOCS
+
+//! Lazy SELECT execution yielding one record at a time.
+
+use std::marker::PhantomData;
+
+use wasm_dbms_api::prelude::{
+    ColumnDef, DbmsResult, Query, TableColumns, TableRecord, TableSchema, Value, ValuesSource,
+};
+use wasm_dbms_memory::prelude::{AccessControl, IndexTreeWalker, MemoryProvider, TableRegistry};
+
+use crate::database::WasmDbmsDatabase;
+use crate::database::blob::hide_overflow_blobs;
+use crate::database::prepared_filter::PreparedFilter;
+
+/// Iterator over the records of a SELECT query, returned by
+/// [`WasmDbmsDatabase::select_iter`].
+///
+/// Outside a transaction, when the query has no `ORDER BY` or `DISTINCT` and
+/// its filter cannot be served by an index, the table is scanned lazily along
+/// its primary key index: each call to [`Iterator::next`] reads only as far as
+/// the next matching record, and stops once `limit` records have been yielded.
+/// Otherwise the query is executed up front, since those clauses and the
+/// transaction overlay need the whole result set. Either way records come in
+/// the same order as with [`Database::select`](wasm_dbms_api::prelude::Database::select).
+///
+/// Memory is only borrowed while a record is read, so the database can be
+/// used while iterating. Rows written to the same table during iteration
+/// may or may not be visited.
+///
+/// After an error is yielded the iterator is exhausted.
+pub struct SelectIter<'db, 'ctx, T, M, A>
+where
+    T: TableSchema,
+    M: MemoryProvider,
+    A: AccessControl,
+{
+    db: &'db WasmDbmsDatabase<'ctx, M, A>,
+    query: Query,
+    source: Source,
+    /// Matching rows skipped so far to honour the offset.
+    skipped: usize,
+    /// Records yielded so far.
+    yielded: usize,
+    _marker: PhantomData<T>,
+}
+
+/// Where [`SelectIter`] takes its rows from.
+enum Source {
+    /// Results already computed by the regular select path.
+    Materialized(std::vec::IntoIter<TableColumns>),
+    /// Lazy scan of the table in primary key order.
+    Scan(Box<ScanState>),
+}
+
+/// State of a lazy table scan, resumed on every [`SelectIter::next`] call.
+struct ScanState {
+    table_registry: TableRegistry,
+    /// The query filter, prepared once for the whole scan.
+    filter: Option<PreparedFilter>,
+    /// Walker over the primary key index.
+    walker: IndexTreeWalker<Vec<Value>>,
+}
+
+impl<'db, 'ctx, T, M, A> SelectIter<'db, 'ctx, T, M, A>
+where
+    T: TableSchema,
+    M: MemoryProvider,
+    A: AccessControl,
+{
+    /// Creates an iterator that lazily scans `T` along its primary key index.
+    pub(super) fn scan(
+        db: &'db WasmDbmsDatabase<'ctx, M, A>,
+        query: Query,
+        filter: Option<PreparedFilter>,
+        table_registry: TableRegistry,
+    ) -> DbmsResult<Self> {
+        let walker = {
+            let mut mm = db.ctx.mm.borrow_mut();
+            table_registry.index_ledger().range_scan(
+                T::primary_keys(),
+                &Vec::<Value>::new(),
+                None,
+                &mut *mm,
+            )?
+        };
+
+        Ok(Self {
+            db,
+            query,
+            source: Source::Scan(Box::new(ScanState {
+                table_registry,
+                filter,
+                walker,
+            })),
+            skipped: 0,
+            yielded: 0,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Creates an iterator over results that have already been computed.
+    pub(super) fn materialized(
+        db: &'db WasmDbmsDatabase<'ctx, M, A>,
+        query: Query,
+        results: Vec<TableColumns>,
+    ) -> Self {
+        Self {
+            db,
+            query,
+            source: Source::Materialized(results.into_iter()),
+            skipped: 0,
+            yielded: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Reads the next row of the scan matching the query filter and offset.
+    fn next_scanned_row(&mut self) -> DbmsResult<Option<Vec<(ColumnDef, Value)>>> {
+        let Source::Scan(scan) = &mut self.source else {
+            return Ok(None);
+        };
+        let ScanState {
+            table_registry,
+            filter,
+            walker,
+        } = scan.as_mut();
+
+        loop {
+            let values = {
+                let mut mm = self.db.ctx.mm.borrow_mut();
+                let Some(address) = walker.next(&mut *mm)? else {
+                    return Ok(None);
+                };
+                let record: T = table_registry.read_at(address, &mut *mm)?;
+                record.to_values()
+            };
+
+            if let Some(filter) = filter
+                && !self.db.record_matches_filter(&values, filter)?
+            {
+                continue;
+            }
+            if self
+                .query
+                .offset
+                .is_some_and(|offset| self.skipped < offset)
+            {
+                self.skipped += 1;
+                continue;
+            }
+
+            return Ok(Some(values));
+        }
+    }
+
+    /// Builds the next record of the scan, loading its eager relations and
+    /// applying the column selection.
+    fn next_scanned_record(&mut self) -> DbmsResult<Option<TableColumns>> {
+        let Some(mut values) = self.next_scanned_row()? else {
+            return Ok(None);
+        };
+        hide_overflow_blobs(&mut values);
+
+        let mut results = vec![vec![(ValuesSource::This, values)]];
+        self.db
+            .batch_load_eager_relations::<T>(&mut results, &self.query)?;
+        self.db
+            .apply_column_selection::<T>(&mut results, &self.query);
+
+        Ok(results.pop())
+    }
+
+    /// Stops the iteration.
+    fn finish(&mut self) {
+        self.source = Source::Materialized(Vec::new().into_iter());
+    }
+}
+
+impl<T, M, A> Iterator for SelectIter<'_, '_, T, M, A>
+where
+    T: TableSchema,
+    M: MemoryProvider,
+    A: AccessControl,
+{
+    type Item = DbmsResult<T::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.query.limit.is_some_and(|limit| self.yielded >= limit) {
+            return None;
+        }
+
+        let next = match &mut self.source {
+            Source::Materialized(results) => Ok(results.next()),
+            Source::Scan(_) => self.next_scanned_record(),
+        };
+
+        match next {
+            Ok(Some(record)) => {
+                self.yielded += 1;
+                Some(Ok(T::Record::from_values(record)))
+            }
+            Ok(None) => {
+                self.finish();
+                None
+            }
+            Err(err) => {
+                self.finish();
+                Some(Err(err))
+            }
+        }
+    }
+}
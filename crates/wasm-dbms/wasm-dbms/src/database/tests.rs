@@ -1,13 +1,21 @@
+use std::cell::Cell;
 use std::cmp::Ordering;
+use std::str::FromStr as _;
 
 use wasm_dbms_api::prelude::{
-    Database as _, DeleteBehavior, Filter, InsertRecord as _, Nullable, OrderDirection, Query,
-    TableSchema as _, Text, Uint32, UpdateRecord as _, Value,
+    ArrayOf, AutoTimestamp, Blob, Char, ClampSanitizer, ColumnCmp, ComputedColumnDef, DataTypeKind,
+    Database as _, DateTime, DbmsError, DbmsResult, Decimal, DecodeError, DeleteBehavior,
+    EmailValidator, Encode as _, Filter, Float32, Float64, InsertConflictBehavior,
+    InsertRecord as _, Int32, Json, JsonPatchOp, LowerCaseSanitizer, MaxStrlenValidator,
+    MemoryError, MemoryResult, MinStrlenValidator, Nullable, OrderDirection, Query, QueryError,
+    RetentionPolicy, Sanitize, SanitizeError, TableSchema as _, Text, Timestamp, TransactionError,
+    TrimSanitizer, Uint32, Uint64, UpdateRecord as _, UpperCaseSanitizer, UpsertResult,
+    VacuumReport, Validate, ValidationError, Value, ZonedDateTime, unit_enum_to_value,
 };
-use wasm_dbms_macros::{DatabaseSchema, Table};
-use wasm_dbms_memory::prelude::HeapMemoryProvider;
+use wasm_dbms_macros::{DatabaseSchema, DbmsEnum, Encode, Table};
+use wasm_dbms_memory::prelude::{HeapMemoryProvider, MemoryProvider};
 
-use super::sort_values_with_direction;
+use super::{MAX_CASCADE_DEPTH, sort_values_with_direction};
 use crate::prelude::{DbmsContext, WasmDbmsDatabase};
 use crate::schema::DatabaseSchema as _;
 
@@ -176,6 +184,254 @@ fn test_select_with_order_by_descending() {
     assert_eq!(rows[2].name, Some(Text("alice".to_string())));
 }
 
+#[test]
+fn test_select_with_order_by_eager_foreign_column() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "charlie");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "bob");
+    insert_post(&db, 1, "c2", 1);
+    insert_post(&db, 2, "a1", 2);
+    insert_post(&db, 3, "b1", 3);
+    insert_post(&db, 4, "c1", 1);
+
+    let rows = db
+        .select::<Post>(
+            Query::builder()
+                .all()
+                .with("users")
+                .order_by_asc("users.name")
+                .order_by_asc("title")
+                .build(),
+        )
+        .unwrap();
+    let titles: Vec<_> = rows
+        .iter()
+        .map(|row| row.title.clone().unwrap().0)
+        .collect();
+    assert_eq!(titles, ["a1", "b1", "c1", "c2"]);
+
+    let rows = db
+        .select::<Post>(
+            Query::builder()
+                .all()
+                .with("users")
+                .order_by_desc("users.name")
+                .order_by_desc("posts.title")
+                .build(),
+        )
+        .unwrap();
+    let titles: Vec<_> = rows
+        .iter()
+        .map(|row| row.title.clone().unwrap().0)
+        .collect();
+    assert_eq!(titles, ["c2", "c1", "b1", "a1"]);
+}
+
+#[test]
+fn test_select_with_order_by_foreign_column_requires_eager_relation() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_post(&db, 1, "hello", 1);
+
+    let result = db.select::<Post>(Query::builder().all().order_by_asc("users.name").build());
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidQuery(ref msg))) if msg.contains("users")
+    ));
+}
+
+#[test]
+fn test_select_eager_relation_with_fields() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 1, "hello", 2);
+    insert_post(&db, 2, "world", 1);
+
+    let rows = db
+        .select::<Post>(
+            Query::builder()
+                .all()
+                .with_fields("users", &["name"])
+                .order_by_asc("users.name")
+                .build(),
+        )
+        .unwrap();
+    let authors: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            let user = row.user_id.as_ref().expect("eager user");
+            assert!(user.id.is_none());
+            user.name.clone().unwrap().0
+        })
+        .collect();
+    assert_eq!(authors, ["alice", "bob"]);
+
+    let rows: Vec<_> = db
+        .select_iter::<Post>(Query::builder().all().with_fields("users", &["id"]).build())
+        .unwrap()
+        .collect::<DbmsResult<_>>()
+        .unwrap();
+    let user = rows[0].user_id.as_ref().expect("eager user");
+    assert_eq!(user.id, Some(Uint32(2)));
+    assert!(user.name.is_none());
+}
+
+#[test]
+fn test_select_eager_relation_with_unknown_field_fails() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+    // fails even when no row is loaded
+    let result = db.select::<Post>(
+        Query::builder()
+            .all()
+            .with_fields("users", &["name", "email"])
+            .build(),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::UnknownColumn(ref column))) if column == "users.email"
+    ));
+
+    let result = db.select_iter::<Post>(Query::builder().with_fields("users", &["email"]).build());
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::UnknownColumn(_)))
+    ));
+}
+
+#[test]
+fn test_select_with_order_by_foreign_column_requires_selected_field() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_post(&db, 1, "hello", 1);
+
+    let result = db.select::<Post>(
+        Query::builder()
+            .all()
+            .with_fields("users", &["id"])
+            .order_by_asc("users.name")
+            .build(),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidQuery(ref msg))) if msg.contains("not selected")
+    ));
+}
+
+#[test]
+fn test_select_with_filter_on_eager_foreign_column() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 1, "a1", 1);
+    insert_post(&db, 2, "b1", 2);
+    insert_post(&db, 3, "a2", 1);
+    insert_post(&db, 4, "b2", 2);
+    insert_post(&db, 5, "a3", 1);
+
+    let rows = db
+        .select::<Post>(
+            Query::builder()
+                .all()
+                .with("users")
+                .and_where(Filter::eq(
+                    "users.name",
+                    Value::Text(Text("alice".to_string())),
+                ))
+                .and_where(Filter::ne(
+                    "posts.title",
+                    Value::Text(Text("a2".to_string())),
+                ))
+                .build(),
+        )
+        .unwrap();
+    let titles: Vec<_> = rows
+        .iter()
+        .map(|row| row.title.clone().unwrap().0)
+        .collect();
+    assert_eq!(titles, ["a1", "a3"]);
+
+    // offset and limit apply to the filtered rows
+    let rows = db
+        .select::<Post>(
+            Query::builder()
+                .all()
+                .with("users")
+                .and_where(Filter::eq(
+                    "users.name",
+                    Value::Text(Text("alice".to_string())),
+                ))
+                .offset(1)
+                .limit(1)
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].title, Some(Text("a2".to_string())));
+
+    let rows: Vec<_> = db
+        .select_iter::<Post>(
+            Query::builder()
+                .all()
+                .with_fields("users", &["name"])
+                .and_where(Filter::starts_with("users.name", "b"))
+                .limit(1)
+                .build(),
+        )
+        .unwrap()
+        .collect::<DbmsResult<_>>()
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].title, Some(Text("b1".to_string())));
+}
+
+#[test]
+fn test_select_with_filter_on_foreign_column_requires_eager_relation() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_post(&db, 1, "hello", 1);
+
+    let filter = Filter::eq("users.name", Value::Text(Text("alice".to_string())));
+    let result = db.select::<Post>(Query::builder().all().and_where(filter.clone()).build());
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidQuery(ref msg))) if msg.contains("eagerly loaded")
+    ));
+
+    let result = db.select::<Post>(
+        Query::builder()
+            .all()
+            .with_fields("users", &["id"])
+            .and_where(filter)
+            .build(),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidQuery(ref msg))) if msg.contains("not selected")
+    ));
+
+    let result = db.select::<Post>(
+        Query::builder()
+            .all()
+            .with("users")
+            .and_where(Filter::is_null("users.email"))
+            .build(),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::UnknownColumn(ref column))) if column == "users.email"
+    ));
+}
+
 // -- select with offset and limit --
 
 #[test]
@@ -262,6 +518,65 @@ fn test_select_with_order_by_and_offset_and_limit() {
     assert_eq!(rows[1].name, Some(Text("charlie".to_string())));
 }
 
+// -- select_page --
+
+#[test]
+fn test_select_page_walks_table_with_cursor() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in [4, 1, 5, 3, 2] {
+        insert_user(&db, id, &format!("user{id}"));
+    }
+
+    let mut ids = Vec::new();
+    let mut query = Query::builder().all().limit(2).build();
+    loop {
+        let (rows, cursor) = db.select_page::<User>(query).unwrap();
+        ids.extend(rows.iter().map(|row| row.id.unwrap().0));
+        let Some(cursor) = cursor else {
+            break;
+        };
+        query = Query::builder()
+            .all()
+            .after(cursor.column, cursor.value)
+            .limit(2)
+            .build();
+    }
+
+    assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn test_select_page_on_descending_column() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "charlie");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "bob");
+
+    let query = Query::builder()
+        .all()
+        .order_by_desc("name")
+        .limit(2)
+        .build();
+    let (rows, cursor) = db.select_page::<User>(query).unwrap();
+    assert_eq!(rows.len(), 2);
+    let cursor = cursor.expect("a full page must return a cursor");
+    assert_eq!(cursor.column, "name");
+    assert_eq!(cursor.value, Value::Text(Text("bob".to_string())));
+
+    let query = Query::builder()
+        .all()
+        .order_by_desc("name")
+        .after(cursor.column, cursor.value)
+        .limit(2)
+        .build();
+    let (rows, cursor) = db.select_page::<User>(query).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, Some(Text("alice".to_string())));
+    assert!(cursor.is_none());
+}
+
 // -- select with filter --
 
 #[test]
@@ -283,6 +598,35 @@ fn test_select_with_filter() {
     assert_eq!(rows[0].name, Some(Text("alice".to_string())));
 }
 
+#[test]
+fn test_select_with_regex_filter() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_user(&db, 3, "alfred");
+
+    let rows = db
+        .select::<User>(
+            Query::builder()
+                .and_where(Filter::regex("name", "^al(ice|fred)$"))
+                .build(),
+        )
+        .unwrap();
+    let ids: Vec<_> = rows.iter().map(|r| r.id.unwrap().0).collect();
+    assert_eq!(ids, [1, 3]);
+
+    let result = db.select::<User>(
+        Query::builder()
+            .and_where(Filter::regex("name", "(al"))
+            .build(),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidRegex(_)))
+    ));
+}
+
 // -- select with column selection --
 
 #[test]
@@ -361,76 +705,434 @@ fn test_update_request_default_with_struct_update() {
     assert_eq!(rows[0].name, Some(Text("alicia".to_string())));
 }
 
-// -- delete operations --
-
 #[test]
-fn test_delete_with_filter() {
+fn test_update_chunked_resumes_from_token() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_user(&db, 2, "bob");
+    for id in [4, 1, 5, 3, 2] {
+        insert_user(&db, id, &format!("user{id}"));
+    }
 
-    let count = db
-        .delete::<User>(
-            DeleteBehavior::Restrict,
-            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
-        )
+    let patch = || UserUpdateRequest {
+        name: Some(Text("renamed".to_string())),
+        where_clause: Some(Filter::ne("id", Value::Uint32(Uint32(3)))),
+        ..Default::default()
+    };
+    let (count, token) = db.update_chunked::<User>(patch(), Some(2), None).unwrap();
+    assert_eq!(count, 2);
+    let token = token.expect("a full chunk must return a token");
+    assert_eq!(token.key, vec![Value::Uint32(Uint32(2))]);
+
+    let (count, token) = db
+        .update_chunked::<User>(patch(), Some(2), Some(token))
         .unwrap();
-    assert_eq!(count, 1);
+    assert_eq!(count, 2);
+    let (count, token) = db.update_chunked::<User>(patch(), Some(2), token).unwrap();
+    assert_eq!(count, 0);
+    assert!(token.is_none());
 
-    let rows = db.select::<User>(Query::builder().build()).unwrap();
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0].id, Some(Uint32(2)));
+    let rows = db
+        .select::<User>(
+            Query::builder()
+                .and_where(Filter::eq("name", Value::Text(Text("renamed".to_string()))))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 4);
 }
 
 #[test]
-fn test_delete_restrict_with_fk_reference_fails() {
+fn test_update_chunked_without_max_rows_updates_every_row() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_post(&db, 10, "post1", 1);
+    for id in 1..=3 {
+        insert_user(&db, id, &format!("user{id}"));
+    }
 
-    let result = db.delete::<User>(DeleteBehavior::Restrict, None);
-    assert!(result.is_err());
+    let patch = UserUpdateRequest {
+        name: Some(Text("renamed".to_string())),
+        ..Default::default()
+    };
+    let (count, token) = db.update_chunked::<User>(patch, None, None).unwrap();
+    assert_eq!(count, 3);
+    assert!(token.is_none());
 }
 
+// -- delete operations --
+
 #[test]
-fn test_delete_cascade_removes_referencing_records() {
+fn test_delete_chunked_walks_table_with_token() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_post(&db, 10, "post1", 1);
+    for id in [4, 1, 5, 3, 2] {
+        insert_user(&db, id, &format!("user{id}"));
+    }
 
-    let count = db.delete::<User>(DeleteBehavior::Cascade, None).unwrap();
-    // 1 user + 1 cascaded post
-    assert_eq!(count, 2);
+    let filter = Some(Filter::ne("id", Value::Uint32(Uint32(4))));
+    let mut counts = Vec::new();
+    let mut token = None;
+    loop {
+        let (count, next) = db
+            .delete_chunked::<User>(DeleteBehavior::Restrict, filter.clone(), Some(2), token)
+            .unwrap();
+        counts.push(count);
+        token = next;
+        if token.is_none() {
+            break;
+        }
+    }
 
-    let users = db.select::<User>(Query::builder().build()).unwrap();
-    assert!(users.is_empty());
-    let posts = db.select::<Post>(Query::builder().build()).unwrap();
-    assert!(posts.is_empty());
+    assert_eq!(counts, vec![2, 2, 0]);
+    let rows = db.select::<User>(Query::builder().build()).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, Some(Uint32(4)));
 }
 
-// -- commit without transaction --
-
 #[test]
-fn test_commit_without_transaction_returns_error() {
+fn test_delete_chunked_short_chunk_returns_no_token() {
     let ctx = setup();
-    let mut db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    let result = db.commit();
-    assert!(result.is_err());
-}
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=3 {
+        insert_user(&db, id, &format!("user{id}"));
+    }
 
-// -- transaction commit with update --
+    let (count, token) = db
+        .delete_chunked::<User>(DeleteBehavior::Restrict, None, Some(10), None)
+        .unwrap();
+    assert_eq!(count, 3);
+    assert!(token.is_none());
+}
 
 #[test]
-fn test_transaction_update_and_commit() {
+fn test_chunked_delete_is_capped_by_max_chunk_rows() {
     let ctx = setup();
+    ctx.set_max_chunk_rows(Some(2));
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
+    for id in 1..=5 {
+        insert_user(&db, id, &format!("user{id}"));
+    }
 
-    let owner = vec![1, 2, 3];
-    let tx_id = ctx.begin_transaction(owner);
+    // without max_rows, the rows below the threshold are deleted at once
+    let filter = Some(Filter::eq("id", Value::Uint32(Uint32(1))));
+    let (count, token) = db
+        .delete_chunked::<User>(DeleteBehavior::Restrict, filter, None, None)
+        .unwrap();
+    assert_eq!((count, token), (1, None));
+
+    // above it, both a missing and a larger max_rows stop at the threshold
+    let (count, token) = db
+        .delete_chunked::<User>(DeleteBehavior::Restrict, None, None, None)
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(token.as_ref().unwrap().key, vec![Value::Uint32(Uint32(3))]);
+    let (count, token) = db
+        .delete_chunked::<User>(DeleteBehavior::Restrict, None, Some(100), token)
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(token.as_ref().unwrap().key, vec![Value::Uint32(Uint32(5))]);
+    assert_eq!(db.count::<User>(None).unwrap(), 0);
+}
+
+#[test]
+fn test_chunked_delete_rejects_zero_max_rows() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    assert!(matches!(
+        db.delete_chunked::<User>(DeleteBehavior::Restrict, None, Some(0), None),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+    assert_eq!(db.count::<User>(None).unwrap(), 1);
+}
+
+#[test]
+fn test_delete_with_filter() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+
+    let count = db
+        .delete::<User>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+    assert_eq!(count, 1);
+
+    let rows = db.select::<User>(Query::builder().build()).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, Some(Uint32(2)));
+}
+
+#[test]
+fn test_delete_restrict_with_fk_reference_fails() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_post(&db, 10, "post1", 1);
+
+    let result = db.delete::<User>(DeleteBehavior::Restrict, None);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_delete_restrict_returns_error_and_keeps_earlier_rows() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 10, "post1", 2);
+
+    // alice is deleted before bob fails the restrict check: the error is
+    // returned and alice's deletion rolled back
+    let result = db.delete::<User>(DeleteBehavior::Restrict, None);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(
+            QueryError::ForeignKeyConstraintViolation { .. }
+        ))
+    ));
+    let users = db.select::<User>(Query::builder().build()).unwrap();
+    assert_eq!(users.len(), 2);
+}
+
+#[test]
+fn test_delete_cascade_removes_referencing_records() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_post(&db, 10, "post1", 1);
+
+    let count = db.delete::<User>(DeleteBehavior::Cascade, None).unwrap();
+    // 1 user + 1 cascaded post
+    assert_eq!(count, 2);
+
+    let users = db.select::<User>(Query::builder().build()).unwrap();
+    assert!(users.is_empty());
+    let posts = db.select::<Post>(Query::builder().build()).unwrap();
+    assert!(posts.is_empty());
+}
+
+#[test]
+fn test_delete_set_null_rejects_non_nullable_foreign_key() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 10, "post1", 1);
+
+    let result = db.delete::<User>(
+        DeleteBehavior::SetNull,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::ConstraintViolation(_)))
+    ));
+    assert_eq!(db.select::<User>(Query::default()).unwrap().len(), 2);
+
+    // users nobody references can still be deleted
+    let count = db
+        .delete::<User>(
+            DeleteBehavior::SetNull,
+            Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+        )
+        .unwrap();
+    assert_eq!(count, 1);
+}
+
+// -- truncate --
+
+#[test]
+fn test_truncate_removes_every_row() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=3 {
+        insert_user(&db, id, "alice");
+    }
+
+    assert_eq!(db.truncate::<User>().unwrap(), 3);
+    assert!(db.select::<User>(Query::default()).unwrap().is_empty());
+    let by_pk = Query::builder()
+        .filter(Some(Filter::eq("id", Value::Uint32(Uint32(1)))))
+        .build();
+    assert!(db.select::<User>(by_pk.clone()).unwrap().is_empty());
+
+    // the primary key index is emptied too
+    insert_user(&db, 1, "bob");
+    let rows = db.select::<User>(by_pk).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, Some(Text("bob".to_string())));
+}
+
+#[test]
+fn test_truncate_referenced_table_fails() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_post(&db, 10, "post1", 1);
+
+    let result = db.truncate::<User>();
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(
+            QueryError::ForeignKeyConstraintViolation { .. }
+        ))
+    ));
+    assert_eq!(db.count::<User>(None).unwrap(), 1);
+
+    assert_eq!(db.truncate::<Post>().unwrap(), 1);
+    assert_eq!(db.truncate::<User>().unwrap(), 1);
+}
+
+#[test]
+fn test_truncate_in_transaction_is_applied_at_commit() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    assert_eq!(db.truncate::<User>().unwrap(), 2);
+    assert!(db.select::<User>(Query::default()).unwrap().is_empty());
+    let oneshot = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    assert_eq!(oneshot.count::<User>(None).unwrap(), 2);
+
+    db.commit().unwrap();
+    assert_eq!(oneshot.count::<User>(None).unwrap(), 0);
+}
+
+// -- defragment --
+
+#[test]
+fn test_defragment_compacts_rows_and_rebuilds_indexes() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=20 {
+        insert_user(&db, id, "alice");
+    }
+    db.delete::<User>(
+        DeleteBehavior::Restrict,
+        Some(Filter::lt("id", Value::Uint32(Uint32(16)))),
+    )
+    .unwrap();
+    assert!(db.table_stats::<User>().unwrap().fragmented_bytes > 0);
+
+    assert_eq!(db.defragment::<User>().unwrap(), 0);
+    let stats = db.table_stats::<User>().unwrap();
+    assert_eq!(stats.rows, 5);
+    assert_eq!(stats.fragmented_bytes, 0);
+
+    // the primary key index points to the new addresses
+    for id in 16..=20 {
+        let by_pk = Query::builder()
+            .filter(Some(Filter::eq("id", Value::Uint32(Uint32(id)))))
+            .build();
+        let rows = db.select::<User>(by_pk).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, Some(Uint32(id)));
+    }
+    insert_user(&db, 1, "bob");
+    assert_eq!(db.count::<User>(None).unwrap(), 6);
+}
+
+#[test]
+fn test_defragment_in_transaction_fails() {
+    let ctx = setup();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+
+    let result = db.defragment::<User>();
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+}
+
+#[test]
+fn test_compact_releases_pages_in_bounded_steps() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let name = "a".repeat(200);
+    for id in 0..900 {
+        insert_user(&db, id, &name);
+    }
+    db.delete::<User>(
+        DeleteBehavior::Restrict,
+        Some(Filter::ge("id", Value::Uint32(Uint32(300)))),
+    )
+    .unwrap();
+    let pages = db.table_stats::<User>().unwrap().pages;
+    assert!(pages > 2);
+
+    let report = db.compact::<User>(1).unwrap();
+    assert_eq!(report.pages_before, pages);
+    assert_eq!(report.pages_after, pages - 1);
+    assert!(!report.complete);
+
+    let report = db.compact::<User>(u64::MAX).unwrap();
+    assert!(report.complete);
+    assert_eq!(report.pages_after, db.table_stats::<User>().unwrap().pages);
+    assert!(report.pages_after < pages - 1);
+    assert_eq!(
+        report.bytes_reclaimed,
+        (report.pages_before - report.pages_after) * HeapMemoryProvider::PAGE_SIZE
+    );
+    assert_eq!(
+        db.compact::<User>(u64::MAX).unwrap().pages_after,
+        report.pages_after
+    );
+
+    // the primary key index points to the new addresses
+    for id in (0..300).step_by(7) {
+        let by_pk = Query::builder()
+            .filter(Some(Filter::eq("id", Value::Uint32(Uint32(id)))))
+            .build();
+        let rows = db.select::<User>(by_pk).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, Some(Uint32(id)));
+    }
+    assert_eq!(db.count::<User>(None).unwrap(), 300);
+    insert_user(&db, 900, "bob");
+    assert_eq!(db.count::<User>(None).unwrap(), 301);
+}
+
+#[test]
+fn test_compact_in_transaction_fails() {
+    let ctx = setup();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+
+    let result = db.compact::<User>(1);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+}
+
+// -- commit without transaction --
+
+#[test]
+fn test_commit_without_transaction_returns_error() {
+    let ctx = setup();
+    let mut db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let result = db.commit();
+    assert!(result.is_err());
+}
+
+// -- transaction commit with update --
+
+#[test]
+fn test_transaction_update_and_commit() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner);
     let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
 
     let patch = UserUpdateRequest::from_values(
@@ -549,63 +1251,199 @@ fn test_transaction_pk_update_then_delete() {
     assert_eq!(rows.len(), 0);
 }
 
-// -- select_raw --
+// -- transaction update counts --
 
 #[test]
-fn test_select_raw() {
+fn test_transaction_update_count_matches_commit() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "bob");
 
-    let rows = db.select_raw("users", Query::builder().build()).unwrap();
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0][0].1, Value::Uint32(Uint32(1)));
-}
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
 
-// -- select with distinct --
+    let patch = UserUpdateRequest::from_values(
+        &[(User::columns()[1], Value::Text(Text("alicia".to_string())))],
+        Some(Filter::eq("name", Value::Text(Text("alice".to_string())))),
+    );
+    let count = db.update::<User>(patch).unwrap();
+    assert_eq!(count, 2);
+    db.commit().unwrap();
 
-#[test]
-fn test_select_distinct_by_single_column() {
-    let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_user(&db, 2, "bob");
-    insert_user(&db, 3, "alice");
-    insert_user(&db, 4, "charlie");
-    insert_user(&db, 5, "bob");
-
-    let rows = db
-        .select::<User>(
-            Query::builder()
-                .all()
-                .distinct(&["name"])
-                .order_by_asc("name")
-                .build(),
-        )
+    let renamed = db
+        .count::<User>(Some(Filter::eq(
+            "name",
+            Value::Text(Text("alicia".to_string())),
+        )))
         .unwrap();
-
-    assert_eq!(rows.len(), 3);
-    assert_eq!(rows[0].name, Some(Text("alice".to_string())));
-    assert_eq!(rows[1].name, Some(Text("bob".to_string())));
-    assert_eq!(rows[2].name, Some(Text("charlie".to_string())));
+    assert_eq!(renamed, count);
 }
 
 #[test]
-fn test_select_distinct_keeps_first_encountered() {
+fn test_transaction_pk_update_count_includes_fk_cascade() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
-    insert_user(&db, 2, "alice");
-    insert_user(&db, 3, "alice");
-
-    let rows = db
-        .select::<User>(Query::builder().all().distinct(&["name"]).build())
-        .unwrap();
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 10, "first", 1);
+    insert_post(&db, 20, "second", 1);
+    insert_post(&db, 30, "third", 2);
+
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+
+    let patch = UserUpdateRequest::from_values(
+        &[(User::columns()[0], Value::Uint32(Uint32(100)))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    // the user row plus its two posts
+    let count = db.update::<User>(patch).unwrap();
+    assert_eq!(count, 3);
+    db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let users = db
+        .count::<User>(Some(Filter::eq("id", Value::Uint32(Uint32(100)))))
+        .unwrap();
+    let posts = db
+        .count::<Post>(Some(Filter::eq("user_id", Value::Uint32(Uint32(100)))))
+        .unwrap();
+    assert_eq!(users + posts, count);
+}
+
+#[test]
+fn test_transaction_update_count_includes_overlay_rows() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "alice");
+
+    let patch = UserUpdateRequest::from_values(
+        &[(User::columns()[1], Value::Text(Text("alicia".to_string())))],
+        Some(Filter::eq("name", Value::Text(Text("alice".to_string())))),
+    );
+    let count = db.update::<User>(patch).unwrap();
+    assert_eq!(count, 3);
+    db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let renamed = db
+        .count::<User>(Some(Filter::eq(
+            "name",
+            Value::Text(Text("alicia".to_string())),
+        )))
+        .unwrap();
+    assert_eq!(renamed, count);
+}
+
+// -- select_raw --
+
+#[test]
+fn test_select_raw() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    let rows = db.select_raw("users", Query::builder().build()).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0][0].1, Value::Uint32(Uint32(1)));
+}
+
+// -- select with distinct --
+
+#[test]
+fn test_select_distinct_by_single_column() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_user(&db, 3, "alice");
+    insert_user(&db, 4, "charlie");
+    insert_user(&db, 5, "bob");
+
+    let rows = db
+        .select::<User>(
+            Query::builder()
+                .all()
+                .distinct(&["name"])
+                .order_by_asc("name")
+                .build(),
+        )
+        .unwrap();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].name, Some(Text("alice".to_string())));
+    assert_eq!(rows[1].name, Some(Text("bob".to_string())));
+    assert_eq!(rows[2].name, Some(Text("charlie".to_string())));
+}
+
+#[test]
+fn test_select_distinct_keeps_first_in_primary_key_order() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "alice");
+
+    let rows = db
+        .select::<User>(Query::builder().all().distinct(&["name"]).build())
+        .unwrap();
 
     assert_eq!(rows.len(), 1);
     assert_eq!(rows[0].id, Some(Uint32(1)));
 }
 
+#[test]
+fn test_select_distinct_keeps_first_in_primary_key_order_not_first_inserted() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 3, "alice");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 1, "bob");
+
+    let rows = db
+        .select::<User>(Query::builder().all().distinct(&["name"]).build())
+        .unwrap();
+
+    let ids: Vec<_> = rows.into_iter().map(|row| row.id.unwrap().0).collect();
+    assert_eq!(ids, [1, 2]);
+}
+
+#[test]
+fn test_select_distinct_keeps_first_in_primary_key_order_whatever_the_order_by() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "bob");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "bob");
+    insert_user(&db, 4, "alice");
+
+    let by_id_asc = Query::builder()
+        .all()
+        .distinct(&["name"])
+        .order_by_asc("id");
+    let by_id_desc = Query::builder()
+        .all()
+        .distinct(&["name"])
+        .order_by_desc("id");
+    for query in [by_id_asc.build(), by_id_desc.build()] {
+        let rows = db.select::<User>(query).unwrap();
+        let mut ids: Vec<_> = rows.into_iter().map(|row| row.id.unwrap()).collect();
+        ids.sort();
+        assert_eq!(ids, vec![Uint32(1), Uint32(2)]);
+    }
+}
+
 #[test]
 fn test_select_distinct_by_multiple_columns() {
     let ctx = setup();
@@ -783,6 +1621,220 @@ fn test_select_distinct_via_select_raw() {
     assert_eq!(rows.len(), 2);
 }
 
+#[test]
+fn test_select_distinct_selected_fields_with_order_by_and_offset() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_user(&db, 3, "charlie");
+    insert_post(&db, 10, "a", 1);
+    insert_post(&db, 11, "b", 3);
+    insert_post(&db, 12, "c", 1);
+    insert_post(&db, 13, "d", 2);
+    insert_post(&db, 14, "e", 3);
+    insert_post(&db, 15, "f", 2);
+
+    // 3 distinct authors -> 3, 2, 1 descending; offset 1, limit 10 -> 2, 1
+    let rows = db
+        .select::<Post>(
+            Query::builder()
+                .field("user_id")
+                .with("users")
+                .distinct_selected()
+                .order_by_desc("user_id")
+                .offset(1)
+                .limit(10)
+                .build(),
+        )
+        .unwrap();
+
+    let authors: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            let user = row.user_id.as_ref().expect("eager user");
+            (user.id.unwrap().0, user.name.clone().unwrap().0)
+        })
+        .collect();
+    assert_eq!(authors, [(2, "bob".to_string()), (1, "alice".to_string())]);
+    assert!(rows.iter().all(|row| row.title.is_none()));
+}
+
+#[test]
+fn test_select_distinct_selected_limit_counts_distinct_rows() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "alice");
+    insert_user(&db, 3, "alice");
+    insert_user(&db, 4, "bob");
+    insert_user(&db, 5, "charlie");
+
+    let rows = db
+        .select::<User>(
+            Query::builder()
+                .field("name")
+                .limit(2)
+                .distinct_selected()
+                .build(),
+        )
+        .unwrap();
+
+    let names: Vec<_> = rows.iter().map(|row| row.name.clone().unwrap().0).collect();
+    assert_eq!(names, ["alice", "bob"]);
+}
+
+// -- select_iter --
+
+#[test]
+fn test_select_iter_applies_filter_offset_and_limit() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for (id, name) in [
+        (1, "alice"),
+        (2, "bob"),
+        (3, "carol"),
+        (4, "dave"),
+        (5, "erin"),
+    ] {
+        insert_user(&db, id, name);
+    }
+
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::ne("name", Value::Text(Text("bob".to_string()))))
+        .offset(1)
+        .limit(2)
+        .build();
+    let records = db
+        .select_iter::<User>(query.clone())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let ids: Vec<_> = records.iter().map(|r| r.id.unwrap().0).collect();
+    assert_eq!(ids, [3, 4]);
+    assert_eq!(records, db.select::<User>(query).unwrap());
+}
+
+#[test]
+fn test_select_iter_can_stop_early_and_write_while_iterating() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=10 {
+        insert_user(&db, id, "user");
+    }
+
+    let mut iter = db.select_iter::<User>(Query::builder().build()).unwrap();
+    for post_id in 100..103 {
+        let user = iter.next().unwrap().unwrap();
+        insert_post(&db, post_id, "hello", user.id.unwrap().0);
+    }
+    drop(iter);
+
+    let posts = db.select::<Post>(Query::builder().build()).unwrap();
+    assert_eq!(posts.len(), 3);
+}
+
+#[test]
+fn test_select_iter_yields_primary_key_order_after_deletes() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let expected = insert_users_reusing_freed_space(&db);
+
+    let ids = db
+        .select_iter::<User>(Query::builder().build())
+        .unwrap()
+        .map(|record| record.map(|r| r.id.unwrap().0))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(ids, expected);
+}
+
+#[test]
+fn test_select_iter_sees_transaction_overlay() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_user(&db, 3, "carol");
+    db.delete::<User>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    )
+    .unwrap();
+    let patch = UserUpdateRequest::from_values(
+        &[(User::columns()[1], Value::Text(Text("bobby".to_string())))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    );
+    db.update::<User>(patch).unwrap();
+
+    let names = db
+        .select_iter::<User>(Query::builder().build())
+        .unwrap()
+        .map(|record| record.map(|r| r.name.unwrap().0))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(names, ["bobby", "carol"]);
+}
+
+#[test]
+fn test_select_iter_with_order_by_and_eager_relation() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 10, "b", 1);
+    insert_post(&db, 11, "c", 2);
+    insert_post(&db, 12, "a", 2);
+
+    let records = db
+        .select_iter::<Post>(
+            Query::builder()
+                .all()
+                .with("users")
+                .order_by_asc("title")
+                .limit(2)
+                .build(),
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let rows: Vec<_> = records
+        .iter()
+        .map(|r| {
+            (
+                r.title.clone().unwrap().0,
+                r.user_id.as_ref().unwrap().name.clone().unwrap().0,
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("a".to_string(), "bob".to_string()),
+            ("b".to_string(), "alice".to_string())
+        ]
+    );
+}
+
+#[test]
+fn test_select_iter_rejects_join() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+    let query = Query::builder()
+        .all()
+        .inner_join("posts", "id", "user_id")
+        .build();
+    assert!(matches!(
+        db.select_iter::<User>(query),
+        Err(DbmsError::Query(QueryError::JoinInsideTypedSelect))
+    ));
+}
+
 // -- select with join returns error on typed select --
 
 #[test]
@@ -894,33 +1946,368 @@ fn test_select_eq_on_indexed_column() {
     );
 }
 
-#[test]
-fn test_select_range_on_indexed_column() {
-    let ctx = setup_name_indexed();
-    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
-    insert_name_indexed_user(&db, 1, "alice", 20);
-    insert_name_indexed_user(&db, 2, "bob", 25);
-    insert_name_indexed_user(&db, 3, "charlie", 30);
+/// Heap memory provider counting the reads issued against it.
+#[derive(Debug, Default)]
+struct CountingMemoryProvider {
+    inner: HeapMemoryProvider,
+    reads: std::rc::Rc<std::cell::Cell<u64>>,
+}
 
-    let rows = db
-        .select::<NameIndexedUser>(
-            Query::builder()
-                .all()
-                .and_where(Filter::gt("name", Value::Text(Text("alice".to_string()))))
-                .build(),
-        )
-        .unwrap();
+impl MemoryProvider for CountingMemoryProvider {
+    const PAGE_SIZE: u64 = HeapMemoryProvider::PAGE_SIZE;
 
-    assert_eq!(rows.len(), 2);
-    assert!(
-        rows.iter()
-            .all(|row| row.name != Some(Text("alice".to_string())))
-    );
-}
+    fn size(&self) -> u64 {
+        self.inner.size()
+    }
 
-#[test]
-fn test_select_in_on_indexed_column() {
-    let ctx = setup_name_indexed();
+    fn pages(&self) -> u64 {
+        self.inner.pages()
+    }
+
+    fn grow(&mut self, new_pages: u64) -> MemoryResult<u64> {
+        self.inner.grow(new_pages)
+    }
+
+    fn read(&mut self, offset: u64, buf: &mut [u8]) -> MemoryResult<()> {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.read(offset, buf)
+    }
+
+    fn write(&mut self, offset: u64, buf: &[u8]) -> MemoryResult<()> {
+        self.inner.write(offset, buf)
+    }
+}
+
+#[test]
+fn test_indexed_lookup_reads_far_less_than_scan() {
+    const ROWS: u32 = 1_000;
+
+    let provider = CountingMemoryProvider::default();
+    let reads = provider.reads.clone();
+    let ctx = DbmsContext::new(provider);
+    NameIndexedTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    for id in 0..ROWS {
+        let insert = NameIndexedUserInsertRequest {
+            id: Uint32(id),
+            name: Text(format!("user-{id}")),
+            age: Uint32(id),
+        };
+        db.insert::<NameIndexedUser>(insert).unwrap();
+    }
+
+    let count_reads = |filter: Filter| {
+        let before = reads.get();
+        let rows = db
+            .select::<NameIndexedUser>(Query::builder().all().and_where(filter).build())
+            .unwrap();
+        (rows, reads.get() - before)
+    };
+
+    // `name` is indexed, `age` is not: both filters select the same row
+    let (indexed_rows, indexed_reads) = count_reads(Filter::eq(
+        "name",
+        Value::Text(Text("user-742".to_string())),
+    ));
+    let (scanned_rows, scanned_reads) = count_reads(Filter::eq("age", Value::Uint32(Uint32(742))));
+    assert_eq!(indexed_rows, scanned_rows);
+    assert_eq!(indexed_rows.len(), 1);
+    assert!(
+        indexed_reads * 20 < scanned_reads,
+        "indexed lookup read {indexed_reads} times, scan {scanned_reads} times"
+    );
+
+    let (in_rows, in_reads) = count_reads(Filter::in_list(
+        "name",
+        vec![
+            Value::Text(Text("user-1".to_string())),
+            Value::Text(Text("user-999".to_string())),
+        ],
+    ));
+    assert_eq!(in_rows.len(), 2);
+    assert!(
+        in_reads * 20 < scanned_reads,
+        "indexed IN lookup read {in_reads} times, scan {scanned_reads} times"
+    );
+}
+
+/// Inserts 100 users with even ids, deletes 20 scattered ones and inserts 10
+/// odd ids into the freed space. Returns the expected ids, ascending.
+fn insert_users_reusing_freed_space(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>) -> Vec<u32> {
+    for id in (0..200).step_by(2) {
+        insert_user(db, id, &format!("user-{id}"));
+    }
+    for id in (0..200).step_by(10) {
+        db.delete::<User>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(Uint32(id)))),
+        )
+        .unwrap();
+    }
+    for id in (1..200).step_by(20) {
+        insert_user(db, id, &format!("user-{id}"));
+    }
+
+    let mut expected: Vec<u32> = (0..200)
+        .step_by(2)
+        .filter(|id| id % 10 != 0)
+        .chain((1..200).step_by(20))
+        .collect();
+    expected.sort_unstable();
+    expected
+}
+
+fn page_through_users(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>) -> Vec<u32> {
+    const PAGE_SIZE: usize = 7;
+
+    let mut ids = Vec::new();
+    for page in 0.. {
+        let users = db
+            .select::<User>(
+                Query::builder()
+                    .all()
+                    .limit(PAGE_SIZE)
+                    .offset(page * PAGE_SIZE)
+                    .build(),
+            )
+            .unwrap();
+        if users.is_empty() {
+            break;
+        }
+        ids.extend(users.into_iter().map(|user| user.id.unwrap().0));
+    }
+    ids
+}
+
+#[test]
+fn test_select_pages_in_primary_key_order_after_deletes() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let expected = insert_users_reusing_freed_space(&db);
+    assert_eq!(expected.len(), 90);
+
+    assert_eq!(page_through_users(&db), expected);
+}
+
+#[test]
+fn test_select_pages_in_primary_key_order_in_transaction() {
+    let ctx = setup();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let tx_db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    let expected = insert_users_reusing_freed_space(&tx_db);
+
+    assert_eq!(page_through_users(&tx_db), expected);
+}
+
+#[test]
+fn test_select_one_stops_scanning_at_first_match() {
+    const ROWS: u32 = 200;
+
+    let provider = CountingMemoryProvider::default();
+    let reads = provider.reads.clone();
+    let ctx = DbmsContext::new(provider);
+    NameIndexedTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    for id in 0..ROWS {
+        let insert = NameIndexedUserInsertRequest {
+            id: Uint32(id),
+            name: Text(format!("user-{id}")),
+            age: Uint32(id),
+        };
+        db.insert::<NameIndexedUser>(insert).unwrap();
+    }
+
+    // `age` is not indexed, so both calls scan the table
+    let filter = Filter::ge("age", Value::Uint32(Uint32(0)));
+    let before = reads.get();
+    let all = db
+        .select::<NameIndexedUser>(Query::builder().and_where(filter.clone()).build())
+        .unwrap();
+    let select_reads = reads.get() - before;
+    assert_eq!(all.len(), ROWS as usize);
+
+    let before = reads.get();
+    let first = db
+        .select_one::<NameIndexedUser>(Query::builder().and_where(filter).build())
+        .unwrap();
+    let select_one_reads = reads.get() - before;
+    assert_eq!(first.and_then(|user| user.id), Some(Uint32(0)));
+    assert!(
+        select_one_reads * 20 < select_reads,
+        "select_one read {select_one_reads} times, select {select_reads} times"
+    );
+}
+
+#[test]
+fn test_select_with_large_in_list() {
+    const ROWS: u32 = 2_000;
+
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    for id in 0..ROWS {
+        insert_name_indexed_user(&db, id, &format!("user-{id}"), id);
+    }
+
+    // `age` is not indexed: every row is checked against a list of 3000
+    // values, two thirds of which match no row
+    let ages: Vec<Value> = (0..3 * ROWS / 2)
+        .filter(|age| age % 2 == 0)
+        .map(|age| Value::Uint32(Uint32(age)))
+        .collect();
+    let filter = Filter::in_list("age", ages);
+    let rows = db
+        .select::<NameIndexedUser>(Query::builder().all().and_where(filter.clone()).build())
+        .unwrap();
+    assert_eq!(rows.len(), (ROWS / 2) as usize);
+    assert!(rows.iter().all(|row| row.age.unwrap().0 % 2 == 0));
+    assert_eq!(
+        db.count::<NameIndexedUser>(Some(filter.clone())).unwrap(),
+        u64::from(ROWS / 2)
+    );
+
+    // inside a logical filter
+    let filter = filter.and(Filter::lt("age", Value::Uint32(Uint32(100))));
+    let rows = db
+        .select::<NameIndexedUser>(Query::builder().all().and_where(filter.clone()).build())
+        .unwrap();
+    assert_eq!(rows.len(), 50);
+    assert_eq!(
+        db.delete::<NameIndexedUser>(DeleteBehavior::Restrict, Some(filter))
+            .unwrap(),
+        50
+    );
+    assert_eq!(
+        db.count::<NameIndexedUser>(None).unwrap(),
+        u64::from(ROWS - 50)
+    );
+}
+
+#[test]
+fn test_empty_in_list_is_rejected() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 30);
+
+    let is_empty_in_error = |err: DbmsError| {
+        matches!(
+            err,
+            DbmsError::Query(QueryError::InvalidQuery(message)) if message.contains("must not be empty")
+        )
+    };
+
+    // on a non-indexed and on an indexed column
+    for column in ["age", "name"] {
+        let filter = Filter::eq("id", Value::Uint32(Uint32(1))).or(Filter::in_list(column, vec![]));
+        let err = db
+            .select::<NameIndexedUser>(Query::builder().all().and_where(filter.clone()).build())
+            .unwrap_err();
+        assert!(is_empty_in_error(err));
+        let err = db
+            .count::<NameIndexedUser>(Some(filter.clone()))
+            .unwrap_err();
+        assert!(is_empty_in_error(err));
+        let err = db.exists::<NameIndexedUser>(filter.clone()).unwrap_err();
+        assert!(is_empty_in_error(err));
+        let err = db
+            .delete::<NameIndexedUser>(DeleteBehavior::Restrict, Some(filter))
+            .unwrap_err();
+        assert!(is_empty_in_error(err));
+    }
+    assert_eq!(db.count::<NameIndexedUser>(None).unwrap(), 1);
+}
+
+#[test]
+fn test_select_range_on_indexed_column() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+    insert_name_indexed_user(&db, 3, "charlie", 30);
+
+    let rows = db
+        .select::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::gt("name", Value::Text(Text("alice".to_string()))))
+                .build(),
+        )
+        .unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert!(
+        rows.iter()
+            .all(|row| row.name != Some(Text("alice".to_string())))
+    );
+}
+
+#[test]
+fn test_select_starts_with_and_ends_with_on_indexed_column() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "al", 20);
+    insert_name_indexed_user(&db, 2, "alice", 25);
+    insert_name_indexed_user(&db, 3, "alfred", 30);
+    insert_name_indexed_user(&db, 4, "am", 35);
+    insert_name_indexed_user(&db, 5, "bob", 40);
+
+    let names = |filter: Filter| -> Vec<String> {
+        db.select::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(filter)
+                .order_by_asc("name")
+                .build(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|row| row.name.unwrap().0)
+        .collect()
+    };
+
+    assert_eq!(
+        names(Filter::starts_with("name", "al")),
+        vec!["al".to_string(), "alfred".to_string(), "alice".to_string()]
+    );
+    assert_eq!(
+        names(Filter::ends_with("name", "ed")),
+        vec!["alfred".to_string()]
+    );
+    assert!(names(Filter::starts_with("name", "al%")).is_empty());
+}
+
+#[test]
+fn test_select_overlapping_ranges_on_indexed_column() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+    insert_name_indexed_user(&db, 3, "charlie", 30);
+    insert_name_indexed_user(&db, 4, "dave", 35);
+
+    let text = |s: &str| Value::Text(Text(s.to_string()));
+    let filter = Filter::ge("name", text("alice"))
+        .and(Filter::ge("name", text("bob")))
+        .and(Filter::le("name", text("dave")))
+        .and(Filter::le("name", text("charlie")));
+    let names: Vec<_> = db
+        .select::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(filter)
+                .order_by_asc("name")
+                .build(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|row| row.name.unwrap().0)
+        .collect();
+
+    assert_eq!(names, vec!["bob".to_string(), "charlie".to_string()]);
+}
+
+#[test]
+fn test_select_in_on_indexed_column() {
+    let ctx = setup_name_indexed();
     let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
     insert_name_indexed_user(&db, 1, "alice", 20);
     insert_name_indexed_user(&db, 2, "bob", 25);
@@ -950,6 +2337,59 @@ fn test_select_in_on_indexed_column() {
     }));
 }
 
+#[test]
+fn test_select_between_on_indexed_and_scanned_columns() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+    insert_name_indexed_user(&db, 3, "charlie", 30);
+    insert_name_indexed_user(&db, 4, "dave", 35);
+
+    let rows = db
+        .select::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::between(
+                    "name",
+                    Value::Text(Text("bob".to_string())),
+                    Value::Text(Text("charlie".to_string())),
+                ))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+
+    let rows = db
+        .select::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::between(
+                    "age",
+                    Value::Uint32(Uint32(25)),
+                    Value::Uint32(Uint32(35)),
+                ))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+
+    let result = db.select::<NameIndexedUser>(
+        Query::builder()
+            .all()
+            .and_where(Filter::between(
+                "age",
+                Value::Uint32(Uint32(25)),
+                Value::Text(Text("35".to_string())),
+            ))
+            .build(),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::TypeMismatch { .. }))
+    ));
+}
+
 #[test]
 fn test_select_on_non_indexed_column_falls_back_to_scan() {
     let ctx = setup_name_indexed();
@@ -1026,63 +2466,320 @@ fn test_select_eq_on_indexed_column_after_delete_and_reinsert_in_transaction() {
 }
 
 #[test]
-fn test_update_on_indexed_column_filter() {
+fn test_count_on_indexed_and_scanned_columns() {
     let ctx = setup_name_indexed();
     let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
     insert_name_indexed_user(&db, 1, "alice", 20);
     insert_name_indexed_user(&db, 2, "bob", 25);
     insert_name_indexed_user(&db, 3, "alice", 30);
 
-    let patch = NameIndexedUserUpdateRequest::from_values(
-        &[(NameIndexedUser::columns()[2], Value::Uint32(Uint32(99)))],
-        Some(Filter::eq("name", Value::Text(Text("alice".to_string())))),
+    assert_eq!(db.count::<NameIndexedUser>(None).unwrap(), 3);
+    assert_eq!(
+        db.count::<NameIndexedUser>(Some(Filter::eq(
+            "name",
+            Value::Text(Text("alice".to_string()))
+        )))
+        .unwrap(),
+        2
+    );
+    assert_eq!(
+        db.count::<NameIndexedUser>(Some(Filter::ge("age", Value::Uint32(Uint32(25)))))
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        db.count::<NameIndexedUser>(Some(Filter::eq(
+            "name",
+            Value::Text(Text("zoe".to_string()))
+        )))
+        .unwrap(),
+        0
     );
-    let count = db.update::<NameIndexedUser>(patch).unwrap();
-    assert_eq!(count, 2);
-
-    let rows = db
-        .select::<NameIndexedUser>(
-            Query::builder()
-                .all()
-                .and_where(Filter::eq("name", Value::Text(Text("alice".to_string()))))
-                .build(),
-        )
-        .unwrap();
-
-    assert!(rows.iter().all(|row| row.age == Some(Uint32(99))));
 }
 
 #[test]
-fn test_delete_on_indexed_column_filter() {
+fn test_count_reflects_transaction_overlay() {
     let ctx = setup_name_indexed();
     let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
     insert_name_indexed_user(&db, 1, "alice", 20);
     insert_name_indexed_user(&db, 2, "bob", 25);
-    insert_name_indexed_user(&db, 3, "alice", 30);
 
-    let count = db
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let tx_db = WasmDbmsDatabase::from_transaction(&ctx, NameIndexedTestSchema, tx_id);
+    insert_name_indexed_user(&tx_db, 3, "alice", 35);
+    tx_db
         .delete::<NameIndexedUser>(
             DeleteBehavior::Restrict,
-            Some(Filter::eq("name", Value::Text(Text("alice".to_string())))),
+            Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
         )
         .unwrap();
-    assert_eq!(count, 2);
 
-    let rows = db
-        .select::<NameIndexedUser>(Query::builder().all().build())
-        .unwrap();
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0].name, Some(Text("bob".to_string())));
+    assert_eq!(tx_db.count::<NameIndexedUser>(None).unwrap(), 2);
+    assert_eq!(
+        tx_db
+            .count::<NameIndexedUser>(Some(Filter::eq(
+                "name",
+                Value::Text(Text("alice".to_string()))
+            )))
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        tx_db
+            .count::<NameIndexedUser>(Some(Filter::lt("age", Value::Uint32(Uint32(30)))))
+            .unwrap(),
+        1
+    );
+
+    // changes are not visible outside the transaction
+    assert_eq!(db.count::<NameIndexedUser>(None).unwrap(), 2);
+    assert_eq!(
+        db.count::<NameIndexedUser>(Some(Filter::eq(
+            "name",
+            Value::Text(Text("alice".to_string()))
+        )))
+        .unwrap(),
+        1
+    );
 }
 
 #[test]
-fn test_insert_index_populates_single_column_index() {
-    use wasm_dbms_memory::RecordAddress;
+fn test_count_reflects_updates_in_transaction_overlay() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
 
-    let ctx = setup_indexed();
-    let db = WasmDbmsDatabase::oneshot(&ctx, IndexedTestSchema);
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let tx_db = WasmDbmsDatabase::from_transaction(&ctx, NameIndexedTestSchema, tx_id);
+    let patch = NameIndexedUserUpdateRequest::from_values(
+        &[(
+            NameIndexedUser::columns()[1],
+            Value::Text(Text("alice".to_string())),
+        )],
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    );
+    assert_eq!(tx_db.update::<NameIndexedUser>(patch).unwrap(), 1);
 
-    // Load the table registry and manually call insert_index.
+    // indexed path
+    assert_eq!(
+        tx_db
+            .count::<NameIndexedUser>(Some(Filter::eq(
+                "name",
+                Value::Text(Text("alice".to_string()))
+            )))
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        tx_db
+            .count::<NameIndexedUser>(Some(Filter::eq(
+                "name",
+                Value::Text(Text("bob".to_string()))
+            )))
+            .unwrap(),
+        0
+    );
+    // scan path
+    assert_eq!(
+        tx_db
+            .count::<NameIndexedUser>(Some(Filter::ne(
+                "name",
+                Value::Text(Text("alice".to_string()))
+            )))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        db.count::<NameIndexedUser>(Some(Filter::eq(
+            "name",
+            Value::Text(Text("bob".to_string()))
+        )))
+        .unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_select_one_returns_first_match_or_none() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+    insert_name_indexed_user(&db, 3, "alice", 30);
+
+    let user = db
+        .select_one::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::ge("age", Value::Uint32(Uint32(25))))
+                .build(),
+        )
+        .unwrap()
+        .expect("should find a user");
+    assert_eq!(user.id, Some(Uint32(2)));
+
+    let oldest = db
+        .select_one::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::eq("name", Value::Text(Text("alice".to_string()))))
+                .order_by_desc("age")
+                .build(),
+        )
+        .unwrap()
+        .expect("should find alice");
+    assert_eq!(oldest.id, Some(Uint32(3)));
+
+    let none = db
+        .select_one::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::gt("age", Value::Uint32(Uint32(99))))
+                .build(),
+        )
+        .unwrap();
+    assert!(none.is_none());
+}
+
+#[test]
+fn test_exists_on_indexed_and_scanned_columns() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+
+    assert!(
+        db.exists::<NameIndexedUser>(Filter::eq("id", Value::Uint32(Uint32(2))))
+            .unwrap()
+    );
+    assert!(
+        db.exists::<NameIndexedUser>(Filter::eq("name", Value::Text(Text("alice".to_string()))))
+            .unwrap()
+    );
+    assert!(
+        db.exists::<NameIndexedUser>(Filter::lt("age", Value::Uint32(Uint32(25))))
+            .unwrap()
+    );
+    assert!(
+        !db.exists::<NameIndexedUser>(Filter::gt("age", Value::Uint32(Uint32(25))))
+            .unwrap()
+    );
+    assert!(
+        !NameIndexedTestSchema
+            .exists(
+                &db,
+                NameIndexedUser::table_name(),
+                Filter::eq("name", Value::Text(Text("zoe".to_string())))
+            )
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_exists_reflects_transaction_overlay() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let tx_db = WasmDbmsDatabase::from_transaction(&ctx, NameIndexedTestSchema, tx_id);
+    insert_name_indexed_user(&tx_db, 2, "bob", 25);
+    tx_db
+        .delete::<NameIndexedUser>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+
+    let bob = Filter::eq("name", Value::Text(Text("bob".to_string())));
+    let old = Filter::lt("age", Value::Uint32(Uint32(21)));
+    assert!(tx_db.exists::<NameIndexedUser>(bob.clone()).unwrap());
+    assert!(!tx_db.exists::<NameIndexedUser>(old.clone()).unwrap());
+    assert!(!db.exists::<NameIndexedUser>(bob).unwrap());
+    assert!(db.exists::<NameIndexedUser>(old).unwrap());
+}
+
+#[test]
+fn test_exists_reflects_updates_in_transaction_overlay() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let tx_db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    let patch = UserUpdateRequest::from_values(
+        &[(User::columns()[1], Value::Text(Text("alicia".to_string())))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    tx_db.update::<User>(patch).unwrap();
+
+    let alice = Filter::eq("name", Value::Text(Text("alice".to_string())));
+    let alicia = Filter::eq("name", Value::Text(Text("alicia".to_string())));
+    assert!(!tx_db.exists::<User>(alice.clone()).unwrap());
+    assert!(tx_db.exists::<User>(alicia.clone()).unwrap());
+    assert!(db.exists::<User>(alice).unwrap());
+    assert!(!db.exists::<User>(alicia).unwrap());
+}
+
+#[test]
+fn test_update_on_indexed_column_filter() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+    insert_name_indexed_user(&db, 3, "alice", 30);
+
+    let patch = NameIndexedUserUpdateRequest::from_values(
+        &[(NameIndexedUser::columns()[2], Value::Uint32(Uint32(99)))],
+        Some(Filter::eq("name", Value::Text(Text("alice".to_string())))),
+    );
+    let count = db.update::<NameIndexedUser>(patch).unwrap();
+    assert_eq!(count, 2);
+
+    let rows = db
+        .select::<NameIndexedUser>(
+            Query::builder()
+                .all()
+                .and_where(Filter::eq("name", Value::Text(Text("alice".to_string()))))
+                .build(),
+        )
+        .unwrap();
+
+    assert!(rows.iter().all(|row| row.age == Some(Uint32(99))));
+}
+
+#[test]
+fn test_delete_on_indexed_column_filter() {
+    let ctx = setup_name_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NameIndexedTestSchema);
+    insert_name_indexed_user(&db, 1, "alice", 20);
+    insert_name_indexed_user(&db, 2, "bob", 25);
+    insert_name_indexed_user(&db, 3, "alice", 30);
+
+    let count = db
+        .delete::<NameIndexedUser>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("name", Value::Text(Text("alice".to_string())))),
+        )
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let rows = db
+        .select::<NameIndexedUser>(Query::builder().all().build())
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].name, Some(Text("bob".to_string())));
+}
+
+#[test]
+fn test_insert_index_populates_single_column_index() {
+    use wasm_dbms_memory::RecordAddress;
+
+    let ctx = setup_indexed();
+    let db = WasmDbmsDatabase::oneshot(&ctx, IndexedTestSchema);
+
+    // Load the table registry and manually call insert_index.
     let mut table_registry = db.load_table_registry::<IndexedUser>().unwrap();
     let record_address = RecordAddress::new(100, 0);
     let values = vec![
@@ -1733,305 +3430,264 @@ fn test_contract_should_have_unique_code() {
         .expect("index on id column");
 }
 
-// -- unique constraint tests --
-
-#[test]
-fn test_insert_contract_with_unique_code_succeeds() {
-    let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
-    insert_contract(&db, 2, "CONTRACT-002", 1);
+// -- float columns --
 
-    let rows = db.select::<Contract>(Query::builder().build()).unwrap();
-    assert_eq!(rows.len(), 2);
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "readings"]
+pub struct Reading {
+    #[primary_key]
+    pub id: Uint32,
+    #[index]
+    pub temperature: Float32,
+    pub pressure: Nullable<Float64>,
 }
 
-#[test]
-fn test_insert_contract_with_duplicate_code_fails() {
-    let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
+#[derive(DatabaseSchema)]
+#[tables(Reading = "readings")]
+pub struct ReadingTestSchema;
 
-    let insert = ContractInsertRequest::from_values(&[
-        (Contract::columns()[0], Value::Uint32(Uint32(2))),
-        (
-            Contract::columns()[1],
-            Value::Text(Text("CONTRACT-001".to_string())),
-        ),
-        // columns()[2] is `order` (autoincrement) — omitted
-        (Contract::columns()[3], Value::Uint32(Uint32(1))),
-    ])
+fn insert_reading(
+    db: &WasmDbmsDatabase<'_, HeapMemoryProvider>,
+    id: u32,
+    temperature: f32,
+    pressure: Option<f64>,
+) {
+    db.insert::<Reading>(ReadingInsertRequest {
+        id: Uint32(id),
+        temperature: Float32(temperature),
+        pressure: pressure.map_or(Nullable::Null, |p| Nullable::Value(Float64(p))),
+    })
     .unwrap();
-    let result = db.insert::<Contract>(insert);
-    assert!(matches!(
-        result,
-        Err(wasm_dbms_api::prelude::DbmsError::Query(
-            wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { .. }
-        ))
-    ));
 }
 
 #[test]
-fn test_update_contract_code_to_unique_value_succeeds() {
-    let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
-
-    let patch = ContractUpdateRequest::from_values(
-        &[(
-            Contract::columns()[1],
-            Value::Text(Text("CONTRACT-999".to_string())),
-        )],
-        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+fn test_float_columns_roundtrip_filter_and_order() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ReadingTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, ReadingTestSchema);
+    assert_eq!(
+        Reading::columns()[1].data_type,
+        wasm_dbms_api::prelude::DataTypeKind::Float32
     );
-    db.update::<Contract>(patch).unwrap();
+    assert_eq!(
+        Reading::columns()[2].data_type,
+        wasm_dbms_api::prelude::DataTypeKind::Float64
+    );
+
+    insert_reading(&db, 1, 21.5, Some(1013.25));
+    insert_reading(&db, 2, -3.75, None);
+    insert_reading(&db, 3, f32::NAN, Some(998.0));
+    insert_reading(&db, 4, 0.0, Some(1001.5));
 
     let rows = db
-        .select::<Contract>(
+        .select::<Reading>(
             Query::builder()
-                .and_where(Filter::Eq("id".to_string(), Value::Uint32(Uint32(1))))
+                .all()
+                .and_where(Filter::ge("temperature", Value::Float32(Float32(0.0))))
+                .order_by_asc("temperature")
                 .build(),
         )
         .unwrap();
-    assert_eq!(rows[0].code, Some(Text("CONTRACT-999".to_string())));
+    // NaN sorts after every other value under `total_cmp`.
+    let ids: Vec<u32> = rows.iter().map(|r| r.id.unwrap().0).collect();
+    assert_eq!(ids, vec![4, 1, 3]);
+    assert_eq!(rows[1].pressure, Some(Nullable::Value(Float64(1013.25))));
+
+    let rows = db
+        .select::<Reading>(
+            Query::builder()
+                .all()
+                .and_where(Filter::eq("temperature", Value::Float32(Float32(f32::NAN))))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, Some(Uint32(3)));
+
+    let sum = db
+        .aggregate::<Reading>(
+            Query::builder()
+                .and_where(Filter::not_null("pressure"))
+                .and_where(Filter::ne("id", Value::Uint32(Uint32(3))))
+                .build(),
+            &[wasm_dbms_api::prelude::AggregateFunction::Sum(
+                "pressure".into(),
+            )],
+        )
+        .unwrap();
+    assert_eq!(
+        sum[0].values[0],
+        wasm_dbms_api::prelude::AggregatedValue::Sum(Value::Decimal(Decimal(
+            rust_decimal::Decimal::new(201475, 2)
+        )))
+    );
+}
+
+// -- insert_many tests --
+
+fn user_insert(id: u32, name: &str) -> UserInsertRequest {
+    UserInsertRequest::from_values(&[
+        (User::columns()[0], Value::Uint32(Uint32(id))),
+        (User::columns()[1], Value::Text(Text(name.to_string()))),
+    ])
+    .unwrap()
+}
+
+fn contract_insert(id: u32, code: &str, user_id: u32) -> ContractInsertRequest {
+    ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(id))),
+        (Contract::columns()[1], Value::Text(Text(code.to_string()))),
+        (Contract::columns()[3], Value::Uint32(Uint32(user_id))),
+    ])
+    .unwrap()
 }
 
 #[test]
-fn test_update_contract_keeping_same_code_succeeds() {
+fn test_insert_many_inserts_all_records() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_user(&db, 2, "bob");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
+    let records = (1..=50).map(|id| user_insert(id, "user")).collect();
 
-    // Change user_id but keep the same unique code
-    let patch = ContractUpdateRequest::from_values(
-        &[(Contract::columns()[2], Value::Uint32(Uint32(2)))],
-        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
-    );
-    db.update::<Contract>(patch).unwrap();
+    let inserted = db.insert_many::<User>(records).unwrap();
+    assert_eq!(inserted, 50);
+    assert_eq!(db.count::<User>(None).unwrap(), 50);
 }
 
 #[test]
-fn test_update_contract_code_to_existing_value_fails() {
+fn test_insert_many_fills_auto_increment_values() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
-    insert_contract(&db, 2, "CONTRACT-002", 1);
+    let records = vec![contract_insert(1, "C-1", 1), contract_insert(2, "C-2", 1)];
 
-    let patch = ContractUpdateRequest::from_values(
-        &[(
-            Contract::columns()[1],
-            Value::Text(Text("CONTRACT-001".to_string())),
-        )],
-        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
-    );
-    let result = db.update::<Contract>(patch);
-    assert!(matches!(
-        result,
-        Err(wasm_dbms_api::prelude::DbmsError::Query(
-            wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { .. }
-        ))
-    ));
+    db.insert_many::<Contract>(records).unwrap();
+
+    let rows = db
+        .select::<Contract>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_ne!(rows[0].order, rows[1].order);
 }
 
 #[test]
-fn test_unique_constraint_with_transaction_commit() {
+fn test_insert_many_rejects_duplicate_pk_within_batch() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
-
-    // Insert a second contract within a transaction
-    let owner = vec![1, 2, 3];
-    let tx_id = ctx.begin_transaction(owner);
-    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
-    insert_contract(&db, 2, "CONTRACT-002", 1);
-    db.commit().unwrap();
+    let records = vec![
+        user_insert(1, "alice"),
+        user_insert(2, "bob"),
+        user_insert(1, "carol"),
+    ];
 
-    // After commit, inserting a duplicate should fail
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    let insert = ContractInsertRequest::from_values(&[
-        (Contract::columns()[0], Value::Uint32(Uint32(3))),
-        (
-            Contract::columns()[1],
-            Value::Text(Text("CONTRACT-002".to_string())),
-        ),
-        // columns()[2] is `order` (autoincrement) — omitted
-        (Contract::columns()[3], Value::Uint32(Uint32(1))),
-    ])
-    .unwrap();
+    let result = db.insert_many::<User>(records);
     assert!(matches!(
-        db.insert::<Contract>(insert),
-        Err(wasm_dbms_api::prelude::DbmsError::Query(
-            wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { .. }
-        ))
+        result,
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
     ));
+    assert_eq!(db.count::<User>(None).unwrap(), 0);
 }
 
 #[test]
-fn test_unique_constraint_after_delete_allows_reuse() {
+fn test_insert_many_rejects_duplicate_unique_within_batch() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
-    insert_contract(&db, 1, "CONTRACT-001", 1);
-
-    // Delete the contract
-    db.delete::<Contract>(
-        DeleteBehavior::Restrict,
-        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
-    )
-    .unwrap();
+    let records = vec![contract_insert(1, "C-1", 1), contract_insert(2, "C-1", 1)];
 
-    // Now inserting a new contract with the same code should succeed
-    insert_contract(&db, 2, "CONTRACT-001", 1);
+    let result = db.insert_many::<Contract>(records);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::UniqueConstraintViolation { ref column, .. }))
+            if column == "code"
+    ));
+    assert_eq!(db.count::<Contract>(None).unwrap(), 0);
 }
 
-// -- autoincrement tests --
-
 #[test]
-fn test_autoincrement_auto_generates_sequential_values() {
+fn test_insert_many_conflict_with_existing_inserts_nothing() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-
-    insert_contract(&db, 1, "C-001", 1);
-    insert_contract(&db, 2, "C-002", 1);
-    insert_contract(&db, 3, "C-003", 1);
-
-    let rows = db
-        .select::<Contract>(Query::builder().order_by_asc("id").build())
-        .unwrap();
+    insert_user(&db, 2, "bob");
+    let records = vec![user_insert(1, "alice"), user_insert(2, "bob")];
 
-    assert_eq!(rows.len(), 3);
-    assert_eq!(rows[0].order, Some(Uint32(1)));
-    assert_eq!(rows[1].order, Some(Uint32(2)));
-    assert_eq!(rows[2].order, Some(Uint32(3)));
+    let result = db.insert_many::<User>(records);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+    assert_eq!(db.count::<User>(None).unwrap(), 1);
 }
 
 #[test]
-fn test_autoincrement_explicit_value_overrides_auto() {
-    use wasm_dbms_api::prelude::Autoincrement;
-
+fn test_insert_many_in_transaction_and_commit() {
     let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    let records = (1..=3).map(|id| user_insert(id, "user")).collect();
 
-    // Insert with an explicit autoincrement value
-    let insert = ContractInsertRequest {
-        id: Uint32(1),
-        code: Text("C-001".to_string()),
-        order: Autoincrement::Value(Uint32(42)),
-        user_id: Uint32(1),
-    };
-    db.insert::<Contract>(insert).unwrap();
+    assert_eq!(db.insert_many::<User>(records).unwrap(), 3);
+    assert_eq!(db.count::<User>(None).unwrap(), 3);
 
-    let rows = db.select::<Contract>(Query::builder().build()).unwrap();
+    let oneshot = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    assert_eq!(oneshot.count::<User>(None).unwrap(), 0);
 
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0].order, Some(Uint32(42)));
+    db.commit().unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    assert_eq!(db.count::<User>(None).unwrap(), 3);
 }
 
 #[test]
-fn test_autoincrement_does_not_recycle_after_delete() {
+fn test_insert_many_via_schema_dispatch() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
-
-    insert_contract(&db, 1, "C-001", 1); // order = 1
-    insert_contract(&db, 2, "C-002", 1); // order = 2
-
-    // Delete the first contract
-    db.delete::<Contract>(
-        DeleteBehavior::Restrict,
-        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
-    )
-    .unwrap();
-
-    // Next insert should get order = 3, not 1
-    insert_contract(&db, 3, "C-003", 1);
+    let rows: Vec<_> = (1..=2)
+        .map(|id| {
+            vec![
+                (User::columns()[0], Value::Uint32(Uint32(id))),
+                (User::columns()[1], Value::Text(Text("user".to_string()))),
+            ]
+        })
+        .collect();
 
-    let rows = db
-        .select::<Contract>(
-            Query::builder()
-                .and_where(Filter::eq("id", Value::Uint32(Uint32(3))))
-                .build(),
-        )
+    let inserted = TestSchema
+        .insert_many(&db, User::table_name(), &rows)
         .unwrap();
-
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0].order, Some(Uint32(3)));
+    assert_eq!(inserted, 2);
 }
 
+// -- insert conflict behavior tests --
+
 #[test]
-fn test_autoincrement_with_transaction_commit() {
+fn test_insert_with_behavior_error_returns_conflict() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
 
-    // Insert outside transaction
-    insert_contract(&db, 1, "C-001", 1); // order = 1
-
-    // Insert inside transaction
-    let owner = vec![1, 2, 3];
-    let tx_id = ctx.begin_transaction(owner);
-    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
-    insert_contract(&db, 2, "C-002", 1); // order = 2
-    db.commit().unwrap();
-
-    // After commit, next auto value should be 3
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_contract(&db, 3, "C-003", 1);
-
-    let rows = db
-        .select::<Contract>(Query::builder().order_by_asc("id").build())
-        .unwrap();
-
-    assert_eq!(rows.len(), 3);
-    assert_eq!(rows[0].order, Some(Uint32(1)));
-    assert_eq!(rows[1].order, Some(Uint32(2)));
-    assert_eq!(rows[2].order, Some(Uint32(3)));
+    let result =
+        db.insert_with_behavior::<User>(user_insert(1, "bob"), InsertConflictBehavior::Error);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
 }
 
 #[test]
-fn test_autoincrement_with_transaction_rollback_does_not_revert_counter() {
+fn test_insert_with_behavior_ignore_skips_conflicts() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "C-001", 1);
 
-    insert_contract(&db, 1, "C-001", 1); // order = 1
-
-    // Insert inside transaction then rollback
-    let owner = vec![1, 2, 3];
-    let tx_id = ctx.begin_transaction(owner.clone());
-    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
-    insert_contract(&db, 2, "C-002", 1); // order = 2 (consumed)
-    db.rollback().unwrap();
-
-    // After rollback, counter should still have advanced (order = 2 is consumed)
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_contract(&db, 3, "C-003", 1);
-
-    let rows = db
-        .select::<Contract>(Query::builder().order_by_asc("id").build())
+    // primary key conflict
+    db.insert_with_behavior::<User>(user_insert(1, "bob"), InsertConflictBehavior::Ignore)
         .unwrap();
+    let users = db.select::<User>(Query::builder().build()).unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].name, Some(Text("alice".to_string())));
 
-    assert_eq!(rows.len(), 2); // only C-001 and C-003
-    assert_eq!(rows[0].order, Some(Uint32(1)));
-    assert_eq!(rows[1].order, Some(Uint32(3))); // 2 was consumed by the rolled-back tx
-}
-
-#[test]
-fn test_autoincrement_from_values_with_auto_variant() {
-    use wasm_dbms_api::prelude::Autoincrement;
-
-    // from_values without the autoincrement column should produce Auto
-    let insert = ContractInsertRequest::from_values(&[
-        (Contract::columns()[0], Value::Uint32(Uint32(1))),
+    // unique constraint conflict
+    let contract = ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(2))),
         (
             Contract::columns()[1],
             Value::Text(Text("C-001".to_string())),
@@ -2039,1073 +3695,5626 @@ fn test_autoincrement_from_values_with_auto_variant() {
         (Contract::columns()[3], Value::Uint32(Uint32(1))),
     ])
     .unwrap();
+    db.insert_with_behavior::<Contract>(contract, InsertConflictBehavior::Ignore)
+        .unwrap();
+    assert_eq!(db.count::<Contract>(None).unwrap(), 1);
 
-    assert_eq!(insert.order, Autoincrement::Auto);
+    // non-conflicting records are inserted, other errors are not swallowed
+    db.insert_with_behavior::<User>(user_insert(2, "bob"), InsertConflictBehavior::Ignore)
+        .unwrap();
+    assert_eq!(db.count::<User>(None).unwrap(), 2);
+    let broken_post = PostInsertRequest::from_values(&[
+        (Post::columns()[0], Value::Uint32(Uint32(1))),
+        (Post::columns()[1], Value::Text(Text("orphan".to_string()))),
+        (Post::columns()[2], Value::Uint32(Uint32(99))),
+    ])
+    .unwrap();
+    assert!(matches!(
+        db.insert_with_behavior::<Post>(broken_post, InsertConflictBehavior::Ignore),
+        Err(DbmsError::Query(QueryError::ForeignKeyViolation { .. }))
+    ));
 }
 
 #[test]
-fn test_autoincrement_from_values_with_value_variant() {
-    use wasm_dbms_api::prelude::Autoincrement;
+fn test_eager_fk_batch_reports_first_missing_reference() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=3 {
+        insert_user(&db, id, &format!("user-{id}"));
+    }
+    for (id, user_id) in [(1, 3), (2, 1), (3, 2), (4, 3)] {
+        insert_post(&db, id, &format!("post-{id}"), user_id);
+    }
 
-    // from_values with the autoincrement column should produce Value
-    let insert = ContractInsertRequest::from_values(&[
-        (Contract::columns()[0], Value::Uint32(Uint32(1))),
-        (
-            Contract::columns()[1],
-            Value::Text(Text("C-001".to_string())),
-        ),
-        (Contract::columns()[2], Value::Uint32(Uint32(99))),
-        (Contract::columns()[3], Value::Uint32(Uint32(1))),
-    ])
-    .unwrap();
+    let results = db
+        .select_columns::<Post>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+    let fk_columns =
+        WasmDbmsDatabase::<HeapMemoryProvider>::collect_fk_values::<Post>(&results, "users")
+            .unwrap();
+    assert_eq!(
+        fk_columns,
+        vec![(
+            "user_id",
+            vec![
+                Value::Uint32(Uint32(3)),
+                Value::Uint32(Uint32(1)),
+                Value::Uint32(Uint32(2)),
+            ]
+        )]
+    );
 
-    assert_eq!(insert.order, Autoincrement::Value(Uint32(99)));
+    // only user 1 resolves: the first dangling key in result order is reported
+    let batch = std::collections::HashMap::from([(Value::Uint32(Uint32(1)), vec![])]);
+    let err =
+        WasmDbmsDatabase::<HeapMemoryProvider>::verify_fk_batch(&batch, &fk_columns[0].1, "users")
+            .unwrap_err();
+    assert!(matches!(
+        err,
+        DbmsError::Query(QueryError::BrokenForeignKeyReference { table, key })
+            if table == "users" && key == Value::Uint32(Uint32(3))
+    ));
 }
 
 #[test]
-fn test_autoincrement_into_values_skips_auto() {
-    use wasm_dbms_api::prelude::{Autoincrement, InsertRecord as _};
+fn test_foreign_fetcher_fetch_batch_maps_found_keys() {
+    use wasm_dbms_api::prelude::ForeignFetcher as _;
 
-    let insert = ContractInsertRequest {
-        id: Uint32(1),
-        code: Text("C-001".to_string()),
-        order: Autoincrement::Auto,
-        user_id: Uint32(1),
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=3 {
+        insert_user(&db, id, &format!("user-{id}"));
+    }
+
+    let batch = Post::foreign_fetcher()
+        .fetch_batch(
+            &db,
+            "users",
+            &[
+                Value::Uint32(Uint32(3)),
+                Value::Uint32(Uint32(1)),
+                Value::Uint32(Uint32(99)),
+            ],
+        )
+        .unwrap();
+    // missing keys are left out, so the caller can report them
+    assert_eq!(batch.len(), 2);
+    let name = |id: u32| {
+        batch[&Value::Uint32(Uint32(id))]
+            .iter()
+            .find(|(col, _)| col.name == "name")
+            .map(|(_, value)| value.clone())
     };
-    let values = insert.into_values();
+    assert_eq!(name(1), Some(Value::Text(Text("user-1".to_string()))));
+    assert_eq!(name(3), Some(Value::Text(Text("user-3".to_string()))));
 
-    // Should have 3 values (id, code, user_id) — order is skipped
-    assert_eq!(values.len(), 3);
-    assert!(values.iter().all(|(col, _)| col.name != "order"));
+    assert!(matches!(
+        Post::foreign_fetcher().fetch_batch(&db, "contracts", &[]),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
 }
 
 #[test]
-fn test_autoincrement_into_values_includes_explicit_value() {
-    use wasm_dbms_api::prelude::{Autoincrement, InsertRecord as _};
+fn test_insert_with_behavior_replace_overwrites_or_inserts() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_post(&db, 1, "hello", 1);
 
-    let insert = ContractInsertRequest {
-        id: Uint32(1),
-        code: Text("C-001".to_string()),
-        order: Autoincrement::Value(Uint32(42)),
-        user_id: Uint32(1),
-    };
-    let values = insert.into_values();
+    db.insert_with_behavior::<User>(user_insert(1, "alicia"), InsertConflictBehavior::Replace)
+        .unwrap();
+    db.insert_with_behavior::<User>(user_insert(3, "carol"), InsertConflictBehavior::Replace)
+        .unwrap();
 
-    // Should have 4 values (id, code, order, user_id)
-    assert_eq!(values.len(), 4);
-    let order_val = values.iter().find(|(col, _)| col.name == "order");
-    assert!(order_val.is_some());
-    assert_eq!(order_val.unwrap().1, Value::Uint32(Uint32(42)));
+    let users: Vec<_> = db
+        .select::<User>(Query::builder().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|user| (user.id.unwrap(), user.name.unwrap().0))
+        .collect();
+    assert_eq!(
+        users,
+        vec![
+            (Uint32(1), "alicia".to_string()),
+            (Uint32(2), "bob".to_string()),
+            (Uint32(3), "carol".to_string()),
+        ]
+    );
+    // rows referencing the replaced one are untouched
+    assert_eq!(db.count::<Post>(None).unwrap(), 1);
+
+    // the replacement is checked like an update
+    let post = PostInsertRequest::from_values(&[
+        (Post::columns()[0], Value::Uint32(Uint32(1))),
+        (Post::columns()[1], Value::Text(Text("moved".to_string()))),
+        (Post::columns()[2], Value::Uint32(Uint32(99))),
+    ])
+    .unwrap();
+    assert!(matches!(
+        db.insert_with_behavior::<Post>(post, InsertConflictBehavior::Replace),
+        Err(DbmsError::Query(QueryError::ForeignKeyViolation { .. }))
+    ));
 }
 
 #[test]
-fn test_autoincrement_select_filter_on_autoincrement_column() {
+fn test_insert_with_behavior_replace_inside_transaction() {
     let ctx = setup();
     let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
     insert_user(&db, 1, "alice");
 
-    insert_contract(&db, 1, "C-001", 1); // order = 1
-    insert_contract(&db, 2, "C-002", 1); // order = 2
-    insert_contract(&db, 3, "C-003", 1); // order = 3
-
-    let rows = db
-        .select::<Contract>(
-            Query::builder()
-                .and_where(Filter::eq("order", Value::Uint32(Uint32(2))))
-                .build(),
-        )
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    tx_db
+        .insert_with_behavior::<User>(user_insert(1, "alicia"), InsertConflictBehavior::Replace)
         .unwrap();
 
-    assert_eq!(rows.len(), 1);
-    assert_eq!(rows[0].id, Some(Uint32(2)));
-    assert_eq!(rows[0].order, Some(Uint32(2)));
-}
+    let name_of = |db: &WasmDbmsDatabase<'_, HeapMemoryProvider>| {
+        db.select_one::<User>(Query::default())
+            .unwrap()
+            .and_then(|user| user.name)
+            .unwrap()
+            .0
+    };
+    assert_eq!(name_of(&tx_db), "alicia");
+    assert_eq!(name_of(&db), "alice");
 
-// -- aggregate tests --
+    tx_db.commit().unwrap();
+    assert_eq!(name_of(&db), "alicia");
+}
 
-mod aggregate_tests {
-    use rust_decimal::Decimal as RustDecimal;
-    use wasm_dbms_api::prelude::{
-        AggregateFunction, AggregatedValue, Database as _, Decimal, Filter, OrderDirection, Query,
-        Text, Uint32, Uint64, Value,
-    };
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "profiles"]
+pub struct Profile {
+    #[primary_key]
+    pub id: Uint32,
+    #[sanitizer(TrimSanitizer)]
+    #[validate(MaxStrlenValidator(8))]
+    pub nickname: Text,
+}
 
-    use super::{Post, TestSchema, User, insert_post, insert_user, setup};
-    use crate::prelude::WasmDbmsDatabase;
+#[derive(DatabaseSchema)]
+#[tables(Profile = "profiles")]
+pub struct ProfileTestSchema;
 
-    fn seed(db: &WasmDbmsDatabase<'_, wasm_dbms_memory::prelude::HeapMemoryProvider>) {
-        insert_user(db, 1, "alice");
-        insert_user(db, 2, "bob");
-        insert_user(db, 3, "carol");
-        // Posts: alice has 3, bob has 1, carol has 0.
-        insert_post(db, 10, "p1", 1);
-        insert_post(db, 20, "p2", 1);
-        insert_post(db, 30, "p3", 1);
-        insert_post(db, 40, "p4", 2);
-    }
+#[test]
+fn test_insert_with_behavior_replace_runs_sanitizers_and_validators() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ProfileTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, ProfileTestSchema);
+    let profile = |nickname: &str| ProfileInsertRequest {
+        id: Uint32(1),
+        nickname: Text(nickname.to_string()),
+    };
+    db.insert::<Profile>(profile("neo")).unwrap();
 
-    fn dec(s: &str) -> Value {
-        Value::Decimal(Decimal(RustDecimal::from_str_exact(s).unwrap()))
-    }
+    db.insert_with_behavior::<Profile>(profile("  trinity  "), InsertConflictBehavior::Replace)
+        .unwrap();
+    let row = db.select_one::<Profile>(Query::default()).unwrap().unwrap();
+    assert_eq!(row.nickname, Some(Text("trinity".to_string())));
 
-    #[test]
-    fn aggregate_count_all_no_group_by() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    let result = db.insert_with_behavior::<Profile>(
+        profile("morpheus the captain"),
+        InsertConflictBehavior::Replace,
+    );
+    assert!(matches!(result, Err(DbmsError::Validation(_))));
+    let row = db.select_one::<Profile>(Query::default()).unwrap().unwrap();
+    assert_eq!(row.nickname, Some(Text("trinity".to_string())));
+}
 
-        let result = db
-            .aggregate::<Post>(Query::default(), &[AggregateFunction::Count(None)])
-            .unwrap();
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "subscribers"]
+pub struct Subscriber {
+    #[primary_key]
+    pub id: Uint32,
+    #[sanitizer(TrimSanitizer, LowerCaseSanitizer)]
+    #[validate(MinStrlenValidator(6), MaxStrlenValidator(254), EmailValidator)]
+    pub email: Text,
+    #[sanitizer(ClampSanitizer, min = 0, max = 100)]
+    pub score: Int32,
+}
 
-        assert_eq!(result.len(), 1);
-        assert!(result[0].group_keys.is_empty());
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(4)]);
-    }
+#[derive(DatabaseSchema)]
+#[tables(Subscriber = "subscribers")]
+pub struct SubscriberTestSchema;
 
-    #[test]
-    fn aggregate_count_column_skips_nulls() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_insert_runs_chained_sanitizers_and_validators() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    SubscriberTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, SubscriberTestSchema);
+    let subscriber = |id: u32, email: &str| SubscriberInsertRequest {
+        id: Uint32(id),
+        email: Text(email.to_string()),
+        score: Int32(150),
+    };
 
-        // user_id is non-null on every Post, so COUNT(user_id) == COUNT(*).
-        let result = db
-            .aggregate::<Post>(
-                Query::default(),
-                &[AggregateFunction::Count(Some("user_id".into()))],
-            )
-            .unwrap();
+    db.insert::<Subscriber>(subscriber(1, "  Alice@Example.COM  "))
+        .unwrap();
+    let row = db
+        .select_one::<Subscriber>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.email, Some(Text("alice@example.com".to_string())));
+    assert_eq!(row.score, Some(Int32(100)));
 
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(4)]);
-    }
+    // the first failing validator reports the error
+    let Err(DbmsError::Validation(message)) = db.insert::<Subscriber>(subscriber(2, "a@b")) else {
+        panic!("expected validation error");
+    };
+    let Err(ValidationError(expected)) =
+        MinStrlenValidator(6).validate(&Value::Text(Text("a@b".to_string())))
+    else {
+        panic!("expected validation error");
+    };
+    assert_eq!(message, expected);
 
-    #[test]
-    fn aggregate_sum_avg_min_max_no_group_by() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    let result = db.insert::<Subscriber>(subscriber(3, "not-an-email"));
+    assert!(matches!(result, Err(DbmsError::Validation(_))));
+}
 
-        let aggs = vec![
-            AggregateFunction::Sum("user_id".into()),
-            AggregateFunction::Avg("user_id".into()),
-            AggregateFunction::Min("user_id".into()),
-            AggregateFunction::Max("user_id".into()),
-        ];
+/// Validator defined outside of the api crate, accepting the IBANs of a country.
+struct IbanValidator {
+    country: &'static str,
+}
 
-        let result = db.aggregate::<Post>(Query::default(), &aggs).unwrap();
-        assert_eq!(result.len(), 1);
-        let v = &result[0].values;
-        // sum(1+1+1+2) = 5, avg = 5/4 = 1.25
-        assert_eq!(v[0], AggregatedValue::Sum(dec("5")));
-        assert_eq!(v[1], AggregatedValue::Avg(dec("1.25")));
-        assert_eq!(v[2], AggregatedValue::Min(Value::Uint32(Uint32(1))));
-        assert_eq!(v[3], AggregatedValue::Max(Value::Uint32(Uint32(2))));
+impl IbanValidator {
+    fn new(country: &'static str) -> Self {
+        Self { country }
     }
+}
 
-    #[test]
-    fn aggregate_group_by_single_column() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+impl Validate for IbanValidator {
+    fn validate(&self, value: &Value) -> Result<(), ValidationError> {
+        match value {
+            Value::Text(Text(iban))
+                if iban.starts_with(self.country)
+                    && iban.chars().all(|c| c.is_ascii_alphanumeric()) =>
+            {
+                Ok(())
+            }
+            _ => Err(ValidationError(format!(
+                "value is not a valid {} IBAN",
+                self.country
+            ))),
+        }
+    }
+}
 
-        let query = Query::builder().group_by(&["user_id"]).build();
-        let aggs = vec![AggregateFunction::Count(None)];
+/// Sanitizer defined outside of the api crate, removing a character.
+struct RemoveCharSanitizer {
+    removed: char,
+}
 
-        let mut result = db.aggregate::<Post>(query, &aggs).unwrap();
-        // Sort by group key for deterministic assertion.
-        result.sort_by(|a, b| a.group_keys[0].cmp(&b.group_keys[0]));
+impl RemoveCharSanitizer {
+    fn new(removed: char) -> Self {
+        Self { removed }
+    }
+}
 
-        assert_eq!(result.len(), 2);
-        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(1))]);
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
-        assert_eq!(result[1].group_keys, vec![Value::Uint32(Uint32(2))]);
-        assert_eq!(result[1].values, vec![AggregatedValue::Count(1)]);
+impl Sanitize for RemoveCharSanitizer {
+    fn sanitize(&self, value: Value) -> Result<Value, SanitizeError> {
+        match value {
+            Value::Text(Text(text)) => Ok(Value::Text(Text(text.replace(self.removed, "")))),
+            other => Ok(other),
+        }
     }
+}
 
-    #[test]
-    fn aggregate_having_filters_groups() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "bank_accounts"]
+pub struct BankAccount {
+    #[primary_key]
+    pub id: Uint32,
+    #[sanitizer(RemoveCharSanitizer::new(' '), UpperCaseSanitizer)]
+    #[validate(MaxStrlenValidator(34), IbanValidator::new("DE"))]
+    pub iban: Text,
+}
 
-        // GROUP BY user_id, HAVING agg0 > 1 — keep only alice's group.
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .having(Filter::gt("agg0", Value::Uint64(Uint64(1))))
-            .build();
-        let aggs = vec![AggregateFunction::Count(None)];
+#[derive(DatabaseSchema)]
+#[tables(BankAccount = "bank_accounts")]
+pub struct BankAccountTestSchema;
 
-        let result = db.aggregate::<Post>(query, &aggs).unwrap();
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(1))]);
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
-    }
+#[test]
+fn test_insert_runs_custom_sanitizers_and_validators_in_order() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    BankAccountTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BankAccountTestSchema);
+    let account = |id: u32, iban: &str| BankAccountInsertRequest {
+        id: Uint32(id),
+        iban: Text(iban.to_string()),
+    };
 
-    #[test]
-    fn aggregate_having_on_group_key_column() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    assert_eq!(BankAccount::validator("iban").len(), 2);
+    assert_eq!(BankAccount::sanitizer("iban").len(), 2);
+    assert!(BankAccount::validator("id").is_empty());
 
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .having(Filter::eq("user_id", Value::Uint32(Uint32(2))))
-            .build();
-        let aggs = vec![AggregateFunction::Count(None)];
+    db.insert::<BankAccount>(account(1, "de89 3704 0044 0532 0130 00"))
+        .unwrap();
+    let row = db
+        .select_one::<BankAccount>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.iban, Some(Text("DE89370400440532013000".to_string())));
 
-        let result = db.aggregate::<Post>(query, &aggs).unwrap();
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(2))]);
-    }
+    // the built-in validator runs first
+    let too_long = "DE".repeat(20);
+    let Err(DbmsError::Validation(message)) = db.insert::<BankAccount>(account(2, &too_long))
+    else {
+        panic!("expected validation error");
+    };
+    let Err(ValidationError(expected)) =
+        MaxStrlenValidator(34).validate(&Value::Text(Text(too_long)))
+    else {
+        panic!("expected validation error");
+    };
+    assert_eq!(message, expected);
 
-    #[test]
-    fn aggregate_order_by_aggregate_output_then_limit() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    let Err(DbmsError::Validation(message)) =
+        db.insert::<BankAccount>(account(3, "FR76 3000 6000 0112 3456 7890 189"))
+    else {
+        panic!("expected validation error");
+    };
+    assert_eq!(message, "value is not a valid DE IBAN");
+    assert_eq!(db.count::<BankAccount>(None).unwrap(), 1);
+}
 
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .order_by_desc("agg0")
-            .limit(1)
-            .build();
-        let aggs = vec![AggregateFunction::Count(None)];
+// -- dry-run validation --
 
-        let result = db.aggregate::<Post>(query, &aggs).unwrap();
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(1))]);
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
-    }
+#[test]
+fn test_validate_insert_only_runs_checks_without_writing() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    BankAccountTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BankAccountTestSchema);
+    let account = |id: u32, iban: &str| BankAccountInsertRequest {
+        id: Uint32(id),
+        iban: Text(iban.to_string()),
+    };
 
-    #[test]
-    fn aggregate_offset_skips_rows() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    db.validate_insert_only::<BankAccount>(account(1, "de89 3704 0044 0532 0130 00"))
+        .unwrap();
+    assert_eq!(db.count::<BankAccount>(None).unwrap(), 0);
 
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .order_by_asc("user_id")
-            .offset(1)
-            .build();
-        let aggs = vec![AggregateFunction::Count(None)];
+    let result = db.validate_insert_only::<BankAccount>(account(1, "FR76 3000 6000"));
+    assert!(matches!(result, Err(DbmsError::Validation(_))));
 
-        let result = db.aggregate::<Post>(query, &aggs).unwrap();
-        assert_eq!(result.len(), 1);
-        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(2))]);
-    }
+    db.insert::<BankAccount>(account(1, "DE89370400440532013000"))
+        .unwrap();
+    let result = db.validate_insert_only::<BankAccount>(account(1, "DE89370400440532013000"));
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+    assert_eq!(db.count::<BankAccount>(None).unwrap(), 1);
+}
 
-    #[test]
-    fn aggregate_with_where_pre_filters_rows() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_validate_insert_only_does_not_advance_autoincrement() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    let contract = |id: u32, code: &str| {
+        ContractInsertRequest::from_values(&[
+            (Contract::columns()[0], Value::Uint32(Uint32(id))),
+            (Contract::columns()[1], Value::Text(Text(code.to_string()))),
+            (Contract::columns()[3], Value::Uint32(Uint32(1))),
+        ])
+        .unwrap()
+    };
 
-        let query = Query::builder()
-            .and_where(Filter::eq("user_id", Value::Uint32(Uint32(1))))
-            .build();
-        let aggs = vec![AggregateFunction::Count(None)];
+    db.validate_insert_only::<Contract>(contract(1, "C-1"))
+        .unwrap();
+    db.validate_insert_only::<Contract>(contract(1, "C-1"))
+        .unwrap();
+    db.insert::<Contract>(contract(1, "C-1")).unwrap();
 
-        let result = db.aggregate::<Post>(query, &aggs).unwrap();
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
-    }
+    let rows = db.select::<Contract>(Query::default()).unwrap();
+    assert_eq!(rows[0].order, Some(Uint32(1)));
+}
 
-    #[test]
-    fn aggregate_empty_table_no_group_by() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        // No inserts.
+#[test]
+fn test_validate_update_only_runs_checks_without_writing() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "C-1", 1);
+    insert_contract(&db, 2, "C-2", 1);
+    let patch = |column: usize, value: Value| {
+        ContractUpdateRequest::from_values(
+            &[(Contract::columns()[column], value)],
+            Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+        )
+    };
 
-        let aggs = vec![
-            AggregateFunction::Count(None),
-            AggregateFunction::Sum("user_id".into()),
-            AggregateFunction::Avg("user_id".into()),
-            AggregateFunction::Min("user_id".into()),
-            AggregateFunction::Max("user_id".into()),
-        ];
+    db.validate_update_only::<Contract>(patch(1, Value::Text(Text("C-3".to_string()))))
+        .unwrap();
+    let result =
+        db.validate_update_only::<Contract>(patch(1, Value::Text(Text("C-1".to_string()))));
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(
+            QueryError::UniqueConstraintViolation { .. }
+        ))
+    ));
+    let result = db.validate_update_only::<Contract>(patch(3, Value::Uint32(Uint32(42))));
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::ForeignKeyViolation { .. }))
+    ));
 
-        let result = db.aggregate::<Post>(Query::default(), &aggs).unwrap();
-        assert_eq!(result.len(), 1);
-        let v = &result[0].values;
-        assert_eq!(v[0], AggregatedValue::Count(0));
-        assert_eq!(v[1], AggregatedValue::Sum(Value::Null));
-        assert_eq!(v[2], AggregatedValue::Avg(Value::Null));
-        assert_eq!(v[3], AggregatedValue::Min(Value::Null));
-        assert_eq!(v[4], AggregatedValue::Max(Value::Null));
-    }
+    let rows = db
+        .select::<Contract>(
+            Query::builder()
+                .filter(Some(Filter::eq("id", Value::Uint32(Uint32(2)))))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows[0].code, Some(Text("C-2".to_string())));
+}
 
-    #[test]
-    fn aggregate_empty_table_group_by_returns_no_rows() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+#[test]
+fn test_validate_insert_only_sees_transaction_overlay() {
+    let ctx = setup();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_user(&db, 1, "alice");
+    let post = PostInsertRequest::from_values(&[
+        (Post::columns()[0], Value::Uint32(Uint32(1))),
+        (Post::columns()[1], Value::Text(Text("hello".to_string()))),
+        (Post::columns()[2], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
 
-        let query = Query::builder().group_by(&["user_id"]).build();
-        let aggs = vec![AggregateFunction::Count(None)];
+    db.validate_insert_only::<Post>(post.clone()).unwrap();
+    let user = UserInsertRequest::from_values(&[
+        (User::columns()[0], Value::Uint32(Uint32(1))),
+        (User::columns()[1], Value::Text(Text("bob".to_string()))),
+    ])
+    .unwrap();
+    assert!(matches!(
+        db.validate_insert_only::<User>(user),
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
 
-        let result = db.aggregate::<Post>(query, &aggs).unwrap();
-        assert!(result.is_empty());
+    // the user only exists in the transaction
+    let oneshot = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    assert!(matches!(
+        oneshot.validate_insert_only::<Post>(post),
+        Err(DbmsError::Query(QueryError::ForeignKeyViolation { .. }))
+    ));
+}
+
+fn subscriber_insert(id: u32, email: &str, score: i32) -> SubscriberInsertRequest {
+    SubscriberInsertRequest {
+        id: Uint32(id),
+        email: Text(email.to_string()),
+        score: Int32(score),
     }
+}
 
-    #[test]
-    fn aggregate_sum_on_non_numeric_column_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_upsert_reports_insert_or_update_and_sanitizes() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    SubscriberTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, SubscriberTestSchema);
 
-        let aggs = vec![AggregateFunction::Sum("name".into())];
-        let err = db
-            .aggregate::<User>(Query::default(), &aggs)
-            .expect_err("SUM on Text must be rejected");
-        let msg = err.to_string();
-        assert!(msg.contains("numeric"), "unexpected error: {msg}");
-    }
+    let result = db
+        .upsert::<Subscriber>(subscriber_insert(1, " Alice@Example.com ", 150))
+        .unwrap();
+    assert_eq!(result, UpsertResult::Inserted);
+    let result = db
+        .upsert::<Subscriber>(subscriber_insert(1, " ALICE@new.example.com", -5))
+        .unwrap();
+    assert_eq!(result, UpsertResult::Updated);
 
-    #[test]
-    fn aggregate_avg_on_non_numeric_column_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    let rows = db.select::<Subscriber>(Query::default()).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(
+        rows[0].email,
+        Some(Text("alice@new.example.com".to_string()))
+    );
+    assert_eq!(rows[0].score, Some(Int32(0)));
 
-        let aggs = vec![AggregateFunction::Avg("name".into())];
-        let err = db
-            .aggregate::<User>(Query::default(), &aggs)
-            .expect_err("AVG on Text must be rejected");
-        assert!(err.to_string().contains("numeric"));
+    // validators reject the record on both paths
+    for id in [1, 2] {
+        assert!(matches!(
+            db.upsert::<Subscriber>(subscriber_insert(id, "nope", 1)),
+            Err(DbmsError::Validation(_))
+        ));
     }
+    assert_eq!(db.count::<Subscriber>(None).unwrap(), 1);
+}
 
-    #[test]
-    fn aggregate_unknown_column_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_upsert_in_transaction_is_resolved_at_commit() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    SubscriberTestSchema::register_tables(&ctx).unwrap();
 
-        let aggs = vec![AggregateFunction::Sum("not_a_column".into())];
-        let err = db
-            .aggregate::<User>(Query::default(), &aggs)
-            .expect_err("unknown column must be rejected");
-        assert!(err.to_string().contains("not_a_column"));
-    }
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, SubscriberTestSchema, tx_id);
+    let result = tx_db
+        .upsert::<Subscriber>(subscriber_insert(1, "first@example.com", 10))
+        .unwrap();
+    assert_eq!(result, UpsertResult::Inserted);
+    let result = tx_db
+        .upsert::<Subscriber>(subscriber_insert(1, "second@example.com", 20))
+        .unwrap();
+    assert_eq!(result, UpsertResult::Updated);
+    let visible = tx_db.select::<Subscriber>(Query::default()).unwrap();
+    assert_eq!(visible.len(), 1);
+    assert_eq!(
+        visible[0].email,
+        Some(Text("second@example.com".to_string()))
+    );
 
-    #[test]
-    fn aggregate_group_by_unknown_column_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    // a row with the same key committed meanwhile is overwritten, not a conflict
+    let db = WasmDbmsDatabase::oneshot(&ctx, SubscriberTestSchema);
+    db.insert::<Subscriber>(subscriber_insert(1, "other@example.com", 30))
+        .unwrap();
+    tx_db.commit().unwrap();
 
-        let query = Query::builder().group_by(&["nope"]).build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("unknown group_by column must be rejected");
-        assert!(err.to_string().contains("nope"));
-    }
+    let rows = db.select::<Subscriber>(Query::default()).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].email, Some(Text("second@example.com".to_string())));
+    assert_eq!(rows[0].score, Some(Int32(20)));
+}
 
-    #[test]
-    fn aggregate_having_unknown_aggregate_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_expired_transaction_is_dropped_and_reported() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let tx_id = ctx
+        .begin_transaction_with_ttl(vec![1, 2, 3], 1_000, Some(60))
+        .unwrap();
+    let tx_db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_user(&tx_db, 1, "alice");
 
-        // Only one aggregate (agg0); reference agg1.
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .having(Filter::gt("agg1", Value::Uint64(Uint64(0))))
-            .build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("HAVING on unknown agg must be rejected");
-        assert!(err.to_string().contains("agg1"));
-    }
+    assert!(ctx.expire_transactions(1_059).is_empty());
+    assert_eq!(ctx.expire_transactions(1_060), vec![tx_id]);
+    assert!(!ctx.has_transaction(&tx_id, &[1, 2, 3]));
+    assert!(ctx.has_expired_transaction(&tx_id, &[1, 2, 3]));
 
-    #[test]
-    fn aggregate_having_unknown_column_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+    let insert = UserInsertRequest::from_values(&[
+        (User::columns()[0], Value::Uint32(Uint32(2))),
+        (User::columns()[1], Value::Text(Text("bob".to_string()))),
+    ])
+    .unwrap();
+    assert!(matches!(
+        tx_db.insert::<User>(insert),
+        Err(DbmsError::Transaction(TransactionError::TransactionExpired(id))) if id == tx_id
+    ));
 
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .having(Filter::eq("foo", Value::Uint32(Uint32(0))))
-            .build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("HAVING on unknown column must be rejected");
-        assert!(err.to_string().contains("foo"));
-    }
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    assert!(matches!(
+        tx_db.commit(),
+        Err(DbmsError::Transaction(
+            TransactionError::TransactionExpired(_)
+        ))
+    ));
+    // the overlay was discarded: nothing reached the table
+    assert_eq!(db.count::<User>(None).unwrap(), 0);
+}
 
-    #[test]
-    fn aggregate_order_by_unknown_aggregate_errors() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_insert_returning_returns_stored_values() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "c-1", 1);
 
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .order_by_asc("agg7")
-            .build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("ORDER BY on unknown agg must be rejected");
-        assert!(err.to_string().contains("agg7"));
-    }
+    let insert = ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(2))),
+        (Contract::columns()[1], Value::Text(Text("c-2".to_string()))),
+        (Contract::columns()[3], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
+    let contract = db.insert_returning::<Contract>(insert).unwrap();
+    assert_eq!(contract.id, Some(Uint32(2)));
+    assert_eq!(contract.code, Some(Text("c-2".to_string())));
+    // the auto-increment value is returned
+    assert_eq!(
+        contract.order,
+        db.select_one::<Contract>(
+            Query::builder()
+                .and_where(Filter::eq("id", Value::Uint32(Uint32(2))))
+                .build()
+        )
+        .unwrap()
+        .unwrap()
+        .order
+    );
+    assert_ne!(contract.order, None);
+}
 
-    #[test]
-    fn aggregate_having_like_rejected() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        seed(&db);
+#[test]
+fn test_insert_returning_returns_sanitized_values() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    SubscriberTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, SubscriberTestSchema);
 
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .having(Filter::like("user_id", "%"))
-            .build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("LIKE in HAVING must be rejected");
-        assert!(err.to_string().contains("LIKE"));
-    }
+    let subscriber = db
+        .insert_returning::<Subscriber>(subscriber_insert(1, " Alice@Example.com ", 150))
+        .unwrap();
+    assert_eq!(
+        subscriber.email,
+        Some(Text("alice@example.com".to_string()))
+    );
+    assert_eq!(subscriber.score, Some(Int32(100)));
 
-    #[test]
-    fn aggregate_avg_decimal_division_precision() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        // Insert posts so SUM(user_id) / 3 = 7/3 = 2.333...
-        insert_user(&db, 1, "a");
-        insert_user(&db, 2, "b");
-        insert_user(&db, 4, "c");
-        insert_post(&db, 1, "p", 1);
-        insert_post(&db, 2, "p", 2);
-        insert_post(&db, 3, "p", 4);
+    assert!(matches!(
+        db.insert_returning::<Subscriber>(subscriber_insert(2, "nope", 1)),
+        Err(DbmsError::Validation(_))
+    ));
+}
 
-        let aggs = vec![AggregateFunction::Avg("user_id".into())];
-        let result = db.aggregate::<Post>(Query::default(), &aggs).unwrap();
-        let AggregatedValue::Avg(Value::Decimal(d)) = &result[0].values[0] else {
-            panic!("expected Decimal avg");
-        };
-        // 7 / 3 truncated to RustDecimal precision should round-trip via string.
-        assert!(d.0.to_string().starts_with("2.33"));
-    }
+#[test]
+fn test_update_returning_returns_updated_rows() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    SubscriberTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, SubscriberTestSchema);
+    db.insert::<Subscriber>(subscriber_insert(1, "a@example.com", 10))
+        .unwrap();
+    db.insert::<Subscriber>(subscriber_insert(2, "b@example.com", 20))
+        .unwrap();
+    db.insert::<Subscriber>(subscriber_insert(3, "c@example.com", 30))
+        .unwrap();
 
-    #[test]
-    fn aggregate_join_query_rejected() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let patch = SubscriberUpdateRequest {
+        score: Some(Int32(500)),
+        where_clause: Some(Filter::lt("id", Value::Uint32(Uint32(3)))),
+        ..Default::default()
+    };
+    let mut updated = db.update_returning::<Subscriber>(patch).unwrap();
+    updated.sort_by_key(|subscriber| subscriber.id);
+    let updated: Vec<_> = updated
+        .into_iter()
+        .map(|subscriber| (subscriber.id.unwrap(), subscriber.score.unwrap()))
+        .collect();
+    // the sanitized score is returned
+    assert_eq!(
+        updated,
+        vec![(Uint32(1), Int32(100)), (Uint32(2), Int32(100))]
+    );
 
-        let query = Query::builder()
-            .inner_join("users", "user_id", "id")
-            .build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("joins must be rejected");
-        assert!(err.to_string().contains("join"));
-    }
+    let patch = SubscriberUpdateRequest {
+        score: Some(Int32(1)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(99)))),
+        ..Default::default()
+    };
+    assert!(db.update_returning::<Subscriber>(patch).unwrap().is_empty());
+}
 
-    #[test]
-    fn aggregate_eager_relations_rejected() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+#[test]
+fn test_returning_in_transaction_returns_overlay_view() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    SubscriberTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, SubscriberTestSchema);
+    db.insert::<Subscriber>(subscriber_insert(1, "a@example.com", 10))
+        .unwrap();
 
-        let query = Query::builder().with("users").build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("eager relations must be rejected");
-        assert!(err.to_string().contains("eager"));
-    }
-
-    #[test]
-    fn aggregate_orders_by_descending() {
-        let _ = OrderDirection::Descending; // silence unused import warning if any
-    }
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, SubscriberTestSchema, tx_id);
+    let inserted = tx_db
+        .insert_returning::<Subscriber>(subscriber_insert(2, " B@Example.com", 20))
+        .unwrap();
+    assert_eq!(inserted.email, Some(Text("b@example.com".to_string())));
 
-    // -- extra coverage: multi-col group_by, NULLs, Decimal AVG, transactions,
-    //    combined order, JSON-in-HAVING rejection --
+    let patch = SubscriberUpdateRequest {
+        score: Some(Int32(420)),
+        where_clause: None,
+        ..Default::default()
+    };
+    let mut updated = tx_db.update_returning::<Subscriber>(patch).unwrap();
+    updated.sort_by_key(|subscriber| subscriber.id);
+    assert_eq!(updated.len(), 2);
+    // the returned rows are sanitized, as they will be written at commit
+    assert!(updated.iter().all(|row| row.score == Some(Int32(100))));
+    assert_eq!(updated[1].email, Some(Text("b@example.com".to_string())));
+
+    // nothing is visible outside the transaction until commit
+    assert_eq!(db.count::<Subscriber>(None).unwrap(), 1);
+    tx_db.commit().unwrap();
+    assert_eq!(
+        updated,
+        db.select::<Subscriber>(Query::builder().all().order_by_asc("id").build())
+            .unwrap()
+    );
+}
 
-    use wasm_dbms_api::prelude::{InsertRecord as _, JsonFilter, Nullable, TableSchema as _};
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "events"]
+pub struct Event {
+    #[primary_key]
+    pub id: Uint32,
+    pub at: Timestamp,
+    pub ended_at: Nullable<Timestamp>,
+}
 
-    use super::{Sale, SaleInsertRequest};
+#[derive(DatabaseSchema)]
+#[tables(Event = "events")]
+pub struct EventTestSchema;
 
-    fn insert_sale(
-        db: &WasmDbmsDatabase<'_, wasm_dbms_memory::prelude::HeapMemoryProvider>,
-        id: u32,
-        category: &str,
-        price: u32,
-        bonus: Option<u32>,
-    ) {
-        let bonus: Nullable<Uint32> = bonus.map(Uint32).into();
-        let req = SaleInsertRequest::from_values(&[
-            (Sale::columns()[0], Value::Uint32(Uint32(id))),
-            (Sale::columns()[1], Value::Text(Text(category.to_string()))),
-            (Sale::columns()[2], Value::Uint32(Uint32(price))),
-            (Sale::columns()[3], bonus.into()),
-        ])
+#[test]
+fn test_timestamp_column_filters_and_sorts() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    EventTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, EventTestSchema);
+    for (id, at) in [(1, 3_000), (2, 1_000), (3, 2_000)] {
+        db.insert::<Event>(EventInsertRequest {
+            id: Uint32(id),
+            at: Timestamp(at),
+            ended_at: Nullable::Null,
+        })
         .unwrap();
-        db.insert::<Sale>(req).unwrap();
     }
 
-    #[test]
-    fn aggregate_multi_column_group_by() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        insert_sale(&db, 1, "books", 10, None);
-        insert_sale(&db, 2, "books", 20, None);
-        insert_sale(&db, 3, "toys", 5, None);
-        insert_sale(&db, 4, "toys", 5, None);
-        insert_sale(&db, 5, "books", 10, None);
-
-        let query = Query::builder()
-            .group_by(&["category", "price"])
-            .order_by_asc("category")
-            .order_by_asc("price")
-            .build();
-        let result = db
-            .aggregate::<Sale>(query, &[AggregateFunction::Count(None)])
-            .unwrap();
-
-        // Groups: (books, 10) x2, (books, 20) x1, (toys, 5) x2
-        assert_eq!(result.len(), 3);
-        assert_eq!(
-            result[0].group_keys,
-            vec![Value::Text(Text("books".into())), Value::Uint32(Uint32(10))]
-        );
-        assert_eq!(result[0].values, vec![AggregatedValue::Count(2)]);
-        assert_eq!(result[1].values, vec![AggregatedValue::Count(1)]);
-        assert_eq!(result[2].values, vec![AggregatedValue::Count(2)]);
-    }
+    let rows = db
+        .select::<Event>(
+            Query::builder()
+                .and_where(Filter::ge("at", Value::Timestamp(Timestamp(2_000))))
+                .order_by_asc("at")
+                .build(),
+        )
+        .unwrap();
+    let ids: Vec<_> = rows.into_iter().map(|row| row.id.unwrap()).collect();
+    assert_eq!(ids, vec![Uint32(3), Uint32(1)]);
 
-    #[test]
-    fn aggregate_count_column_with_actual_nulls() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        insert_sale(&db, 1, "a", 1, Some(10));
-        insert_sale(&db, 2, "a", 1, None);
-        insert_sale(&db, 3, "a", 1, Some(20));
+    let event = db.select_one::<Event>(Query::default()).unwrap().unwrap();
+    assert_eq!(event.ended_at, Some(Nullable::Null));
+}
 
-        let result = db
-            .aggregate::<Sale>(
-                Query::default(),
-                &[
-                    AggregateFunction::Count(None),
-                    AggregateFunction::Count(Some("bonus".into())),
-                ],
-            )
-            .unwrap();
-        let v = &result[0].values;
-        assert_eq!(v[0], AggregatedValue::Count(3));
-        assert_eq!(v[1], AggregatedValue::Count(2));
-    }
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "grades"]
+pub struct Grade {
+    #[primary_key]
+    pub id: Uint32,
+    pub letter: char,
+    #[default = '$']
+    pub currency: Char,
+    pub note: Nullable<Char>,
+}
 
-    #[test]
-    fn aggregate_sum_avg_skips_nulls_decimal_output() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        // Use the nullable `bonus` column so SUM/AVG must skip nulls.
-        insert_sale(&db, 1, "x", 1, Some(10));
-        insert_sale(&db, 2, "x", 1, None);
-        insert_sale(&db, 3, "x", 1, Some(20));
+#[derive(DatabaseSchema)]
+#[tables(Grade = "grades")]
+pub struct GradeTestSchema;
 
-        let result = db
-            .aggregate::<Sale>(
-                Query::default(),
-                &[
-                    AggregateFunction::Sum("bonus".into()),
-                    AggregateFunction::Avg("bonus".into()),
-                ],
-            )
-            .unwrap();
-        assert_eq!(result[0].values[0], AggregatedValue::Sum(dec("30")));
-        // avg = 30 / 2 = 15
-        assert_eq!(result[0].values[1], AggregatedValue::Avg(dec("15")));
+#[test]
+fn test_char_column_maps_rust_char_and_filters() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    GradeTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GradeTestSchema);
+    assert_eq!(Grade::columns()[1].data_type, DataTypeKind::Char);
+
+    for (id, letter) in [(1, 'B'), (2, 'A'), (3, 'C')] {
+        db.insert::<Grade>(GradeInsertRequest {
+            id: Uint32(id),
+            letter,
+            currency: Some(Char('€')),
+            note: Nullable::Null,
+        })
+        .unwrap();
     }
+    db.insert::<Grade>(
+        GradeInsertRequest::from_values(&[
+            (Grade::columns()[0], Value::Uint32(Uint32(4))),
+            (Grade::columns()[1], Value::from('A')),
+            (Grade::columns()[3], Value::Char(Char('+'))),
+        ])
+        .unwrap(),
+    )
+    .unwrap();
 
-    #[test]
-    fn aggregate_inside_transaction_sees_writes() {
-        let ctx = setup();
-        let tx_id = ctx.begin_transaction(b"tester".to_vec());
-        let db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
-
-        // Inside the transaction, insert rows then aggregate.
-        insert_user(&db, 1, "a");
-        insert_user(&db, 2, "b");
-        insert_post(&db, 10, "p", 1);
-        insert_post(&db, 11, "p", 1);
-        insert_post(&db, 12, "p", 2);
+    let rows = db
+        .select::<Grade>(
+            Query::builder()
+                .and_where(Filter::le("letter", Value::from('B')))
+                .order_by_asc("letter")
+                .order_by_asc("id")
+                .build(),
+        )
+        .unwrap();
+    let ids: Vec<_> = rows.iter().map(|row| row.id.unwrap()).collect();
+    assert_eq!(ids, vec![Uint32(2), Uint32(4), Uint32(1)]);
+    assert_eq!(rows[0].letter, Some('A'));
+    assert_eq!(rows[0].currency, Some(Char('€')));
+    assert_eq!(rows[1].currency, Some(Char('$')));
+    assert_eq!(rows[1].note, Some(Nullable::Value(Char('+'))));
+
+    db.update::<Grade>(GradeUpdateRequest::from_values(
+        &[(Grade::columns()[1], Value::from('D'))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(3)))),
+    ))
+    .unwrap();
+    let grade = db
+        .select_one::<Grade>(
+            Query::builder()
+                .and_where(Filter::eq("id", Value::Uint32(Uint32(3))))
+                .build(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(grade.letter, Some('D'));
+}
 
-        let result = db
-            .aggregate::<Post>(
-                Query::builder().group_by(&["user_id"]).build(),
-                &[AggregateFunction::Count(None)],
-            )
-            .unwrap();
-        // 2 distinct user_ids in the overlay
-        assert_eq!(result.len(), 2);
-    }
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "appointments"]
+pub struct Appointment {
+    #[primary_key]
+    pub id: Uint32,
+    pub starts_at: ZonedDateTime,
+    pub ends_at: Nullable<ZonedDateTime>,
+}
 
-    #[test]
-    fn aggregate_order_by_combines_group_key_and_aggregate() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-        insert_user(&db, 1, "a");
-        insert_user(&db, 2, "b");
-        insert_user(&db, 3, "c");
-        insert_post(&db, 10, "p", 1);
-        insert_post(&db, 20, "p", 2);
-        insert_post(&db, 21, "p", 2);
-        insert_post(&db, 30, "p", 3);
+#[derive(DatabaseSchema)]
+#[tables(Appointment = "appointments")]
+pub struct AppointmentTestSchema;
+
+fn local_time(hour: u8, timezone_offset_minutes: i16) -> ZonedDateTime {
+    ZonedDateTime::from(DateTime {
+        year: 2024,
+        month: 6,
+        day: 15,
+        hour,
+        timezone_offset_minutes,
+        ..Default::default()
+    })
+}
 
-        // Order by COUNT desc then user_id asc.
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .order_by_desc("agg0")
-            .order_by_asc("user_id")
-            .build();
-        let result = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .unwrap();
+#[test]
+fn test_zoned_datetime_column_keeps_offset_and_sorts_by_instant() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    AppointmentTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, AppointmentTestSchema);
+    assert_eq!(
+        Appointment::columns()[1].data_type,
+        DataTypeKind::ZonedDateTime
+    );
 
-        assert_eq!(result.len(), 3);
-        // user 2 has 2 posts (highest); users 1 and 3 each have 1, tie broken by user_id asc.
-        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(2))]);
-        assert_eq!(result[1].group_keys, vec![Value::Uint32(Uint32(1))]);
-        assert_eq!(result[2].group_keys, vec![Value::Uint32(Uint32(3))]);
+    // 12:00 in Rome and 06:00 in New York are the same instant
+    for (id, starts_at) in [
+        (1, local_time(12, 120)),
+        (2, local_time(9, 60)),
+        (3, local_time(6, -240)),
+        (4, local_time(11, 0)),
+    ] {
+        db.insert::<Appointment>(AppointmentInsertRequest {
+            id: Uint32(id),
+            starts_at,
+            ends_at: Nullable::Value(starts_at.with_offset(0)),
+        })
+        .unwrap();
     }
 
-    #[test]
-    fn aggregate_having_json_filter_rejected() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-
-        let json_filter = JsonFilter::extract_eq("$.foo", Value::Text(Text("bar".into())));
-        let query = Query::builder()
-            .group_by(&["user_id"])
-            .having(Filter::json("user_id", json_filter))
-            .build();
-        let err = db
-            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
-            .expect_err("JSON in HAVING must be rejected");
-        assert!(err.to_string().contains("JSON"));
-    }
+    let rows = db
+        .select::<Appointment>(
+            Query::builder()
+                .and_where(Filter::ge(
+                    "starts_at",
+                    Value::ZonedDateTime(local_time(10, 60)),
+                ))
+                .order_by_desc("starts_at")
+                .build(),
+        )
+        .unwrap();
+    let ids: Vec<_> = rows.iter().map(|row| row.id.unwrap()).collect();
+    assert_eq!(ids, vec![Uint32(4), Uint32(1), Uint32(3)]);
+    let new_york = rows[2].starts_at.unwrap();
+    assert_eq!(new_york.offset_minutes, -240);
+    assert_eq!(new_york.to_local().hour, 6);
+    assert_eq!(
+        rows[2].ends_at,
+        Some(Nullable::Value(new_york.with_offset(0)))
+    );
 }
 
-#[test]
-fn select_with_group_by_is_rejected() {
-    let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
-    insert_user(&db, 1, "alice");
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    DbmsEnum,
+)]
+#[type_tag = "story_status"]
+pub enum StoryStatus {
+    #[default]
+    Draft,
+    Published,
+    Archived,
+}
 
-    let query = Query::builder().group_by(&["id"]).build();
-    let err = db
-        .select::<User>(query)
-        .expect_err("GROUP BY must be rejected on select");
-    assert!(err.to_string().contains("GROUP BY"));
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "stories"]
+pub struct Story {
+    #[primary_key]
+    pub id: Uint32,
+    #[custom_type]
+    pub status: StoryStatus,
+    #[custom_type]
+    pub previous_status: Nullable<StoryStatus>,
 }
 
+#[derive(DatabaseSchema)]
+#[tables(Story = "stories")]
+pub struct StoryTestSchema;
+
 #[test]
-fn select_with_having_is_rejected() {
-    use wasm_dbms_api::prelude::Filter as F;
+fn test_dbms_enum_column_filters_and_sorts_by_declaration_order() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    StoryTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, StoryTestSchema);
 
-    let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for (id, status, previous_status) in [
+        (
+            1,
+            StoryStatus::Archived,
+            Nullable::Value(StoryStatus::Published),
+        ),
+        (2, StoryStatus::Draft, Nullable::Null),
+        (
+            3,
+            StoryStatus::Published,
+            Nullable::Value(StoryStatus::Draft),
+        ),
+        (4, StoryStatus::Published, Nullable::Null),
+    ] {
+        db.insert::<Story>(StoryInsertRequest {
+            id: Uint32(id),
+            status,
+            previous_status,
+        })
+        .unwrap();
+    }
 
-    let query = Query::builder()
-        .having(F::gt("agg0", Value::Uint32(Uint32(0))))
-        .build();
-    let err = db
-        .select::<User>(query)
-        .expect_err("HAVING must be rejected on select");
-    assert!(err.to_string().contains("GROUP BY"));
+    let published = db
+        .select::<Story>(
+            Query::builder()
+                .and_where(Filter::eq("status", StoryStatus::Published.into()))
+                .build(),
+        )
+        .unwrap();
+    let mut ids: Vec<_> = published.iter().map(|row| row.id.unwrap()).collect();
+    ids.sort();
+    assert_eq!(ids, vec![Uint32(3), Uint32(4)]);
+    assert_eq!(published[0].status, Some(StoryStatus::Published));
+
+    let rows = db
+        .select::<Story>(
+            Query::builder()
+                .order_by_desc("status")
+                .order_by_asc("id")
+                .build(),
+        )
+        .unwrap();
+    let ids: Vec<_> = rows.iter().map(|row| row.id.unwrap()).collect();
+    assert_eq!(ids, vec![Uint32(1), Uint32(3), Uint32(4), Uint32(2)]);
+    assert_eq!(
+        rows[0].previous_status,
+        Some(Nullable::Value(StoryStatus::Published))
+    );
+
+    let value = Value::from(StoryStatus::Archived);
+    assert_eq!(StoryStatus::Archived.to_string(), "Archived");
+    assert_eq!(
+        value.as_custom_type::<StoryStatus>(),
+        Some(StoryStatus::Archived)
+    );
 }
 
-mod migration_e2e {
-    use std::borrow::Cow;
+#[test]
+fn test_dbms_enum_rejects_unknown_discriminant() {
+    assert_eq!(StoryStatus::Archived.encode().as_ref(), &[2]);
+    assert!(matches!(
+        StoryStatus::decode(std::borrow::Cow::Borrowed(&[3])),
+        Err(MemoryError::DecodeError(DecodeError::InvalidDiscriminant(
+            3
+        )))
+    ));
+}
 
-    use wasm_dbms_api::prelude::{
-        ColumnSnapshot, DataTypeSnapshot, Database as _, DbmsError, Encode as _, IndexSnapshot,
-        InsertRecord as _, MigrationError, MigrationOp, MigrationPolicy, Query, TableSchema as _,
-        Text, Uint32, Value,
-    };
-    use wasm_dbms_macros::{DatabaseSchema, Table};
-    use wasm_dbms_memory::MemoryAccess;
-    use wasm_dbms_memory::prelude::HeapMemoryProvider;
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    serde::Serialize,
+    serde::Deserialize,
+    DbmsEnum,
+)]
+pub enum StoryPriority {
+    #[default]
+    Low = 10,
+    High = 20,
+}
 
-    use crate::database::migration::snapshots;
-    use crate::prelude::{DbmsContext, WasmDbmsDatabase};
-    use crate::schema::DatabaseSchema;
+#[test]
+fn test_dbms_enum_stores_the_declaration_index() {
+    assert_eq!(StoryPriority::Low.encode().as_ref(), &[0]);
+    assert_eq!(StoryPriority::High.encode().as_ref(), &[1]);
+    assert_eq!(
+        StoryPriority::decode(std::borrow::Cow::Borrowed(&[1])).unwrap(),
+        StoryPriority::High
+    );
+    assert!(matches!(
+        StoryPriority::decode(std::borrow::Cow::Borrowed(&[20])),
+        Err(MemoryError::DecodeError(DecodeError::InvalidDiscriminant(
+            20
+        )))
+    ));
+}
 
-    #[derive(Debug, Table, Clone, PartialEq, Eq)]
-    #[table = "users"]
-    pub struct User {
-        #[primary_key]
-        pub id: Uint32,
-        pub name: Text,
-    }
+#[test]
+fn test_dbms_enum_column_lists_its_variants() {
+    let variants: &[&str] = &["Draft", "Published", "Archived"];
+    let status = Story::columns()[1];
+    assert_eq!(status.data_type, DataTypeKind::Enum(variants));
+    assert_eq!(status.allowed_values(), Some(variants));
+    assert_eq!(Story::columns()[0].allowed_values(), None);
 
-    #[derive(DatabaseSchema)]
-    #[tables(User = "users")]
-    pub struct UserSchema;
+    assert_eq!(
+        StoryStatus::try_from(Value::from(StoryStatus::Published)).unwrap(),
+        StoryStatus::Published
+    );
+    assert!(matches!(
+        StoryStatus::try_from(Value::Uint8(1u8.into())),
+        Err(QueryError::TypeMismatch { expected, found })
+            if expected == "Custom(story_status)" && found == "Uint8"
+    ));
+}
 
-    #[derive(Debug, Table, Clone, PartialEq, Eq)]
-    #[table = "users"]
-    #[migrate]
-    pub struct UserV2 {
-        #[primary_key]
-        pub id: Uint32,
-        pub name: Text,
-        pub email: Text,
-    }
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "attachments"]
+pub struct Attachment {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    pub data: Blob,
+}
 
-    impl wasm_dbms_api::prelude::Migrate for UserV2 {
-        fn default_value(column: &str) -> Option<Value> {
-            match column {
-                "email" => Some(Value::Text(Text("dynamic@example.com".to_string()))),
-                _ => None,
-            }
-        }
-    }
+#[derive(DatabaseSchema)]
+#[tables(Attachment = "attachments")]
+pub struct AttachmentTestSchema;
 
-    #[derive(DatabaseSchema)]
-    #[tables(UserV2 = "users")]
-    pub struct UserSchemaV2;
+fn attachment_bytes(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
 
-    fn setup() -> DbmsContext<HeapMemoryProvider> {
-        let ctx = DbmsContext::new(HeapMemoryProvider::default());
-        UserSchema::register_tables(&ctx).unwrap();
-        ctx
+#[test]
+fn test_large_blob_is_stored_out_of_line() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    AttachmentTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, AttachmentTestSchema);
+    let data = attachment_bytes(150_000);
+    for (id, data) in [(1, data.clone()), (2, vec![1, 2, 3])] {
+        db.insert::<Attachment>(AttachmentInsertRequest {
+            id: Uint32(id),
+            name: Text(format!("file-{id}")),
+            data: Blob(data),
+        })
+        .unwrap();
     }
 
-    fn tamper_snapshot_to_force_drift(ctx: &DbmsContext<HeapMemoryProvider>) {
-        let snapshot_page = {
-            let sr = ctx.schema_registry.borrow();
-            sr.table_registry_page::<User>()
-                .unwrap()
-                .schema_snapshot_page
-        };
-        let mut tampered = User::schema_snapshot();
-        tampered.indexes.push(IndexSnapshot {
-            columns: vec!["name".to_string()],
-            unique: false,
-        });
-        ctx.mm
-            .borrow_mut()
-            .write_at(snapshot_page, 0, &tampered)
-            .unwrap();
-        let mut schema_registry = ctx.schema_registry.borrow_mut();
-        let mut mm = ctx.mm.borrow_mut();
-        schema_registry.refresh_schema_hash(&mut *mm).unwrap();
-        schema_registry.save(&mut *mm).unwrap();
-        ctx.clear_drift();
-    }
+    // selects return a placeholder for the large blob only
+    let rows = db
+        .select::<Attachment>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+    assert_eq!(rows[0].data, Some(Blob::default()));
+    assert_eq!(rows[1].data, Some(Blob(vec![1, 2, 3])));
 
-    #[test]
-    fn test_has_drift_returns_false_for_matching_schemas() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        assert!(!db.has_drift().unwrap());
-    }
+    let chunk = db
+        .blob_read_chunk::<Attachment>(Uint32(1).into(), "data", 0, 200_000)
+        .unwrap();
+    assert_eq!(chunk.total_len, 150_000);
+    assert_eq!(chunk.bytes, data);
+    let chunk = db
+        .blob_read_chunk::<Attachment>(Uint32(2).into(), "data", 1, 10)
+        .unwrap();
+    assert_eq!(chunk.bytes, vec![2, 3]);
+    assert!(chunk.is_last(1));
 
-    #[test]
-    fn test_database_creation_eagerly_caches_drift_flag() {
-        let ctx = setup();
-        let compiled_hash = snapshots::compute_hash(<UserSchema as DatabaseSchema<
-            HeapMemoryProvider,
-        >>::compiled_snapshots());
-        assert_eq!(ctx.cached_drift_for(compiled_hash), None);
-        let _db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        assert_eq!(ctx.cached_drift_for(compiled_hash), Some(false));
-    }
+    // updating other columns keeps the blob
+    db.update::<Attachment>(AttachmentUpdateRequest {
+        name: Some(Text("renamed".to_string())),
+        where_clause: Some(Filter::eq("id", Uint32(1).into())),
+        ..Default::default()
+    })
+    .unwrap();
+    let chunk = db
+        .blob_read_chunk::<Attachment>(Uint32(1).into(), "data", 149_990, 100)
+        .unwrap();
+    assert_eq!(chunk.bytes, data[149_990..]);
 
-    #[test]
-    fn test_has_drift_returns_true_after_persisted_snapshot_diverges() {
-        let ctx = setup();
-        tamper_snapshot_to_force_drift(&ctx);
-        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        assert!(db.has_drift().unwrap());
-    }
+    // deleting the row releases its overflow pages for reuse
+    let pages_count = ctx.mm.borrow().pages_count();
+    db.delete::<Attachment>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Uint32(1).into())),
+    )
+    .unwrap();
+    db.insert::<Attachment>(AttachmentInsertRequest {
+        id: Uint32(3),
+        name: Text("file-3".to_string()),
+        data: Blob(data.clone()),
+    })
+    .unwrap();
+    assert_eq!(ctx.mm.borrow().pages_count(), pages_count);
 
-    #[test]
-    fn test_pending_migrations_is_empty_when_schemas_match() {
-        let ctx = setup();
-        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        let ops = db.pending_migrations().unwrap();
-        assert!(ops.is_empty());
+    assert!(matches!(
+        db.blob_read_chunk::<Attachment>(Uint32(1).into(), "data", 0, 10),
+        Err(DbmsError::Query(QueryError::RecordNotFound))
+    ));
+    assert!(matches!(
+        db.blob_read_chunk::<Attachment>(Uint32(3).into(), "name", 0, 10),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+    assert!(matches!(
+        db.blob_read_chunk::<Attachment>(Uint32(3).into(), "missing", 0, 10),
+        Err(DbmsError::Query(QueryError::UnknownColumn(_)))
+    ));
+}
+
+#[test]
+fn test_filter_on_blob_stored_out_of_line() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    AttachmentTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, AttachmentTestSchema);
+    for (id, data) in [(1, attachment_bytes(150_000)), (2, vec![1, 2, 3])] {
+        db.insert::<Attachment>(AttachmentInsertRequest {
+            id: Uint32(id),
+            name: Text(format!("file-{id}")),
+            data: Blob(data),
+        })
+        .unwrap();
     }
+    let ids = |filter: Filter| {
+        db.select::<Attachment>(Query::builder().filter(Some(filter)).build())
+            .unwrap()
+            .into_iter()
+            .map(|row| row.id.unwrap().0)
+            .collect::<Vec<_>>()
+    };
 
-    #[test]
-    fn test_pending_migrations_produces_drop_index_for_extra_persisted_index() {
-        let ctx = setup();
-        tamper_snapshot_to_force_drift(&ctx);
-        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        let ops = db.pending_migrations().unwrap();
-        assert_eq!(ops.len(), 1);
+    // lengths come from the overflow reference
+    let len = |filter: fn(&str, Value) -> Filter, len: u64| {
+        Filter::blob_len("data", filter("data", Value::Uint64(Uint64(len))))
+    };
+    assert_eq!(ids(len(Filter::eq, 150_000)), [1]);
+    assert_eq!(ids(len(Filter::gt, 4096)), [1]);
+    assert_eq!(ids(len(Filter::lt, 16)), [2]);
+    assert_eq!(
+        db.count::<Attachment>(Some(len(Filter::eq, 16))).unwrap(),
+        0
+    );
+    assert_eq!(ids(Filter::not_null("data")), [1, 2]);
+
+    // the content of a blob stored out of line is never compared
+    for filter in [
+        Filter::eq("data", Value::Blob(Blob(attachment_bytes(150_000)))),
+        Filter::in_list("data", vec![Value::Blob(Blob(vec![1, 2, 3]))]),
+    ] {
         assert!(matches!(
-            &ops[0],
-            MigrationOp::DropIndex { table, index }
-                if table == "users" && index.columns == vec!["name".to_string()]
+            db.select::<Attachment>(Query::builder().filter(Some(filter)).build()),
+            Err(DbmsError::Query(QueryError::InvalidQuery(message))) if message.contains("'data'")
         ));
     }
+}
 
-    #[test]
-    fn test_migrate_clears_drift_and_persists_compiled_snapshot() {
-        let ctx = setup();
-        tamper_snapshot_to_force_drift(&ctx);
-        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+#[test]
+fn test_text_larger_than_max_size_is_rejected() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    AttachmentTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, AttachmentTestSchema);
+    let too_large = Text("a".repeat(Text::MAX_BYTES + 1));
 
-        assert!(db.has_drift().unwrap());
-        db.migrate(MigrationPolicy::default()).unwrap();
-        assert!(!db.has_drift().unwrap());
+    let result = db.insert::<Attachment>(AttachmentInsertRequest {
+        id: Uint32(1),
+        name: too_large.clone(),
+        data: Blob::default(),
+    });
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::ValueTooLarge { column, max, actual }))
+            if column == "name"
+                && max == Text::MAX_BYTES as u64
+                && actual == Text::MAX_BYTES as u64 + 1
+    ));
+    assert_eq!(db.count::<Attachment>(None).unwrap(), 0);
 
-        let stored = ctx
-            .schema_registry
-            .borrow()
-            .stored_snapshots(&mut *ctx.mm.borrow_mut())
-            .unwrap();
-        let users = stored.iter().find(|s| s.name == "users").unwrap();
-        assert!(
-            !users
-                .indexes
-                .iter()
-                .any(|i| i.columns == vec!["name".to_string()]),
-            "extra index should be removed by the migration"
-        );
-    }
+    db.insert::<Attachment>(AttachmentInsertRequest {
+        id: Uint32(1),
+        name: Text("file".to_string()),
+        data: Blob::default(),
+    })
+    .unwrap();
+    let patch = AttachmentUpdateRequest::from_values(
+        &[(Attachment::columns()[1], Value::Text(too_large))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    assert!(matches!(
+        db.update::<Attachment>(patch),
+        Err(DbmsError::Query(QueryError::ValueTooLarge { .. }))
+    ));
+    let row = db
+        .select_one::<Attachment>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.name, Some(Text("file".to_string())));
+}
 
-    #[test]
-    fn test_select_returns_schema_drift_error_while_drift_is_active() {
-        let ctx = setup();
-        tamper_snapshot_to_force_drift(&ctx);
-        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+#[test]
+fn test_blob_is_written_in_chunks() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    AttachmentTestSchema::register_tables(&ctx).unwrap();
+    ctx.set_max_blob_size(Some(100_000));
+    ctx.set_max_blob_chunk_size(Some(30_000));
+    let db = WasmDbmsDatabase::oneshot(&ctx, AttachmentTestSchema);
+    db.insert::<Attachment>(AttachmentInsertRequest {
+        id: Uint32(1),
+        name: Text("upload".to_string()),
+        data: Blob::default(),
+    })
+    .unwrap();
 
-        let result = db.select::<User>(Query::builder().build());
-        assert!(matches!(
-            result,
-            Err(DbmsError::Migration(MigrationError::SchemaDrift))
-        ));
+    let data = attachment_bytes(90_000);
+    for (i, chunk) in data.chunks(30_000).enumerate() {
+        let len = db
+            .blob_write_chunk::<Attachment>(Uint32(1).into(), "data", i as u64 * 30_000, chunk)
+            .unwrap();
+        assert_eq!(len, (i as u64 + 1) * 30_000);
     }
-
-    #[test]
-    fn test_insert_returns_schema_drift_error_while_drift_is_active() {
-        let ctx = setup();
-        tamper_snapshot_to_force_drift(&ctx);
-        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-
-        let insert = UserInsertRequest::from_values(&[
-            (User::columns()[0], Value::Uint32(Uint32(1))),
-            (User::columns()[1], Value::Text(Text("alice".to_string()))),
-        ])
+    // overwrite a range spanning two chunks
+    db.blob_write_chunk::<Attachment>(Uint32(1).into(), "data", 29_990, &[0xAA; 20])
         .unwrap();
-        let result = db.insert::<User>(insert);
-        assert!(matches!(
-            result,
-            Err(DbmsError::Migration(MigrationError::SchemaDrift))
-        ));
+    let mut expected = data;
+    expected[29_990..30_010].fill(0xAA);
+
+    let mut read = Vec::new();
+    while read.len() < expected.len() {
+        let chunk = db
+            .blob_read_chunk::<Attachment>(Uint32(1).into(), "data", read.len() as u64, 30_000)
+            .unwrap();
+        assert_eq!(chunk.total_len, 90_000);
+        read.extend(chunk.bytes);
     }
+    assert_eq!(read, expected);
 
-    #[test]
+    // limits
+    assert!(matches!(
+        db.blob_read_chunk::<Attachment>(Uint32(1).into(), "data", 0, 30_001),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+    assert!(matches!(
+        db.blob_write_chunk::<Attachment>(Uint32(1).into(), "data", 90_000, &[0; 10_001]),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+    assert!(matches!(
+        db.blob_write_chunk::<Attachment>(Uint32(1).into(), "data", 90_001, &[0]),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+    assert!(matches!(
+        db.insert::<Attachment>(AttachmentInsertRequest {
+            id: Uint32(2),
+            name: Text("too-large".to_string()),
+            data: Blob(vec![0; 100_001]),
+        }),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+
+    let tx = ctx.begin_transaction(vec![1]);
+    let db = WasmDbmsDatabase::from_transaction(&ctx, AttachmentTestSchema, tx);
+    assert!(matches!(
+        db.blob_write_chunk::<Attachment>(Uint32(1).into(), "data", 0, &[0]),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "folders"]
+pub struct Folder {
+    #[primary_key]
+    pub id: Uint32,
+    #[foreign_key(entity = "Folder", table = "folders", column = "id")]
+    pub parent_id: Nullable<Uint32>,
+    pub name: Text,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Folder = "folders")]
+pub struct FolderTestSchema;
+
+fn folder(id: u32, parent_id: Option<u32>) -> FolderInsertRequest {
+    FolderInsertRequest {
+        id: Uint32(id),
+        parent_id: parent_id.map_or(Nullable::Null, |parent| Nullable::Value(Uint32(parent))),
+        name: Text(format!("folder-{id}")),
+    }
+}
+
+#[test]
+fn test_nullable_foreign_key_is_optional_relation() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    FolderTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, FolderTestSchema);
+    db.insert::<Folder>(folder(1, None)).unwrap();
+    db.insert::<Folder>(folder(2, Some(1))).unwrap();
+    db.insert::<Folder>(folder(3, None)).unwrap();
+
+    // a non-null reference must still point at an existing row
+    assert!(matches!(
+        db.insert::<Folder>(folder(4, Some(99))),
+        Err(DbmsError::Query(QueryError::ForeignKeyViolation { .. }))
+    ));
+
+    let folders = db
+        .select::<Folder>(
+            Query::builder()
+                .all()
+                .with("folders")
+                .order_by_asc("id")
+                .build(),
+        )
+        .unwrap();
+    let parents: Vec<_> = folders
+        .iter()
+        .map(|folder| folder.parent_id.as_ref().map(|parent| parent.id.unwrap()))
+        .collect();
+    assert_eq!(parents, vec![None, Some(Uint32(1)), None]);
+
+    // Null references do not block a restricted delete
+    db.delete::<Folder>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(3)))),
+    )
+    .unwrap();
+    assert!(matches!(
+        db.delete::<Folder>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1))))
+        ),
+        Err(DbmsError::Query(
+            QueryError::ForeignKeyConstraintViolation { .. }
+        ))
+    ));
+
+    // and are left alone by a cascading one
+    db.insert::<Folder>(folder(5, None)).unwrap();
+    let deleted = db
+        .delete::<Folder>(
+            DeleteBehavior::Cascade,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+    assert_eq!(deleted, 2);
+    let remaining: Vec<_> = db
+        .select::<Folder>(Query::default())
+        .unwrap()
+        .into_iter()
+        .map(|folder| folder.id.unwrap())
+        .collect();
+    assert_eq!(remaining, vec![Uint32(5)]);
+}
+
+#[test]
+fn test_cascade_delete_counts_each_row_once() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    FolderTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, FolderTestSchema);
+    db.insert::<Folder>(folder(1, None)).unwrap();
+    db.insert::<Folder>(folder(2, Some(1))).unwrap();
+    db.insert::<Folder>(folder(3, Some(2))).unwrap();
+    db.insert::<Folder>(folder(4, Some(1))).unwrap();
+
+    // every row is matched by the filter and reached by the cascade
+    let deleted = db.delete::<Folder>(DeleteBehavior::Cascade, None).unwrap();
+    assert_eq!(deleted, 4);
+    assert!(db.select::<Folder>(Query::default()).unwrap().is_empty());
+}
+
+#[test]
+fn test_cascade_delete_too_deep_is_rolled_back() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    FolderTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, FolderTestSchema);
+    db.insert::<Folder>(folder(0, None)).unwrap();
+    let depth = MAX_CASCADE_DEPTH as u32 + 1;
+    for id in 1..=depth {
+        db.insert::<Folder>(folder(id, Some(id - 1))).unwrap();
+    }
+
+    let result = db.delete::<Folder>(
+        DeleteBehavior::Cascade,
+        Some(Filter::eq("id", Value::Uint32(Uint32(0)))),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::CascadeTooDeep { table, depth }))
+            if table == "folders" && depth == MAX_CASCADE_DEPTH
+    ));
+    assert_eq!(
+        db.select::<Folder>(Query::default()).unwrap().len(),
+        depth as usize + 1
+    );
+
+    // the state of the failed cascade is not carried over
+    let deleted = db
+        .delete::<Folder>(
+            DeleteBehavior::Cascade,
+            Some(Filter::eq("id", Value::Uint32(Uint32(depth)))),
+        )
+        .unwrap();
+    assert_eq!(deleted, 1);
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "imported_files"]
+pub struct ImportedFile {
+    #[primary_key]
+    pub id: Uint32,
+    #[foreign_key(entity = "Folder", table = "folders", column = "id", deferred)]
+    pub folder_id: Uint32,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Folder = "folders", ImportedFile = "imported_files")]
+pub struct ImportTestSchema;
+
+#[test]
+fn test_deferred_foreign_key_is_not_checked_on_write() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ImportTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, ImportTestSchema);
+    assert!(ImportedFile::columns()[1].foreign_key.unwrap().deferred);
+
+    // the child is loaded before its parent
+    db.insert::<ImportedFile>(ImportedFileInsertRequest {
+        id: Uint32(1),
+        folder_id: Uint32(7),
+    })
+    .unwrap();
+    db.update::<ImportedFile>(ImportedFileUpdateRequest {
+        folder_id: Some(Uint32(8)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    })
+    .unwrap();
+    db.insert::<Folder>(folder(8, None)).unwrap();
+
+    let files = db
+        .select::<ImportedFile>(Query::builder().all().with("folders").build())
+        .unwrap();
+    assert_eq!(files[0].folder_id.as_ref().unwrap().id, Some(Uint32(8)));
+}
+
+#[test]
+fn test_delete_set_null_clears_referencing_foreign_keys() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    FolderTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, FolderTestSchema);
+    db.insert::<Folder>(folder(1, None)).unwrap();
+    db.insert::<Folder>(folder(2, Some(1))).unwrap();
+    db.insert::<Folder>(folder(3, Some(1))).unwrap();
+    db.insert::<Folder>(folder(4, Some(2))).unwrap();
+
+    let count = db
+        .delete::<Folder>(
+            DeleteBehavior::SetNull,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+    // 1 deleted folder + 2 nulled children
+    assert_eq!(count, 3);
+
+    let folders: Vec<_> = db
+        .select::<Folder>(
+            Query::builder()
+                .all()
+                .with("folders")
+                .order_by_asc("id")
+                .build(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|folder| {
+            (
+                folder.id.unwrap(),
+                folder.parent_id.map(|parent| parent.id.unwrap()),
+            )
+        })
+        .collect();
+    assert_eq!(
+        folders,
+        vec![
+            (Uint32(2), None),
+            (Uint32(3), None),
+            (Uint32(4), Some(Uint32(2))),
+        ]
+    );
+}
+
+#[test]
+fn test_delete_set_null_in_transaction_is_applied_at_commit() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    FolderTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, FolderTestSchema);
+    db.insert::<Folder>(folder(1, None)).unwrap();
+    db.insert::<Folder>(folder(2, Some(1))).unwrap();
+
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, FolderTestSchema, tx_id);
+    tx_db
+        .delete::<Folder>(
+            DeleteBehavior::SetNull,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+    tx_db.commit().unwrap();
+
+    let folders = db
+        .select::<Folder>(Query::builder().all().with("folders").build())
+        .unwrap();
+    assert_eq!(folders.len(), 1);
+    assert_eq!(folders[0].id, Some(Uint32(2)));
+    assert!(folders[0].parent_id.is_none());
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "teams"]
+pub struct Team {
+    #[primary_key]
+    pub id: Uint32,
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "members"]
+pub struct Member {
+    #[primary_key]
+    pub id: Uint32,
+    #[foreign_key(entity = "Team", table = "teams", column = "id", on_delete = "SetNull")]
+    pub team_id: Nullable<Uint32>,
+    #[foreign_key(
+        entity = "Team",
+        table = "teams",
+        column = "id",
+        on_delete = "Restrict"
+    )]
+    pub home_team_id: Nullable<Uint32>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Team = "teams", Member = "members")]
+pub struct TeamTestSchema;
+
+fn setup_teams() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    TeamTestSchema::register_tables(&ctx).unwrap();
+    {
+        let db = WasmDbmsDatabase::oneshot(&ctx, TeamTestSchema);
+        for id in 1..=2 {
+            db.insert::<Team>(TeamInsertRequest { id: Uint32(id) })
+                .unwrap();
+        }
+        for (id, team_id, home_team_id) in [(10, Some(1), None), (11, Some(1), Some(2))] {
+            db.insert::<Member>(MemberInsertRequest {
+                id: Uint32(id),
+                team_id: team_id.map_or(Nullable::Null, |id| Nullable::Value(Uint32(id))),
+                home_team_id: home_team_id.map_or(Nullable::Null, |id| Nullable::Value(Uint32(id))),
+            })
+            .unwrap();
+        }
+    }
+    ctx
+}
+
+fn member_teams(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>) -> Vec<(Uint32, Nullable<Uint32>)> {
+    db.select_raw("members", Query::builder().all().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let id = match &row[0].1 {
+                Value::Uint32(id) => *id,
+                other => panic!("unexpected id {other:?}"),
+            };
+            let team_id = match &row[1].1 {
+                Value::Uint32(id) => Nullable::Value(*id),
+                Value::Null => Nullable::Null,
+                other => panic!("unexpected team_id {other:?}"),
+            };
+            (id, team_id)
+        })
+        .collect()
+}
+
+#[test]
+fn test_declared_on_delete_set_null_overrides_delete_behavior() {
+    let ctx = setup_teams();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TeamTestSchema);
+    assert_eq!(
+        Member::columns()[1].foreign_key.unwrap().on_delete,
+        Some(DeleteBehavior::SetNull)
+    );
+
+    // the members are not deleted although the delete cascades
+    let count = db
+        .delete::<Team>(
+            DeleteBehavior::Cascade,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(
+        member_teams(&db),
+        vec![(Uint32(10), Nullable::Null), (Uint32(11), Nullable::Null)]
+    );
+}
+
+#[test]
+fn test_declared_on_delete_restrict_overrides_delete_behavior() {
+    let ctx = setup_teams();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TeamTestSchema);
+
+    let result = db.delete::<Team>(
+        DeleteBehavior::Cascade,
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    );
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::ForeignKeyConstraintViolation {
+            referencing_table,
+            field,
+        })) if referencing_table == "members" && field == "home_team_id"
+    ));
+    assert_eq!(db.select::<Team>(Query::default()).unwrap().len(), 2);
+    assert_eq!(db.select::<Member>(Query::default()).unwrap().len(), 2);
+}
+
+#[test]
+fn test_has_referencing_rows_reports_restricting_foreign_key() {
+    let ctx = setup_teams();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TeamTestSchema);
+
+    // team 1 is only referenced through the `SetNull` foreign key
+    assert_eq!(
+        db.has_referencing_rows::<Team>(
+            &[(Team::columns()[0], Value::Uint32(Uint32(1)))],
+            DeleteBehavior::Restrict
+        )
+        .unwrap(),
+        None
+    );
+    assert_eq!(
+        db.has_referencing_rows::<Team>(
+            &[(Team::columns()[0], Value::Uint32(Uint32(2)))],
+            DeleteBehavior::Cascade
+        )
+        .unwrap(),
+        Some(("members", "home_team_id"))
+    );
+}
+
+#[test]
+fn test_restricted_delete_leaves_other_rows_untouched() {
+    let ctx = setup_teams();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TeamTestSchema);
+
+    // team 1 would null the members' team, but team 2 is restricted
+    let result = db.delete::<Team>(DeleteBehavior::Cascade, None);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::ForeignKeyConstraintViolation {
+            referencing_table,
+            field,
+        })) if referencing_table == "members" && field == "home_team_id"
+    ));
+    assert_eq!(db.select::<Team>(Query::default()).unwrap().len(), 2);
+    assert_eq!(
+        member_teams(&db),
+        vec![
+            (Uint32(10), Nullable::Value(Uint32(1))),
+            (Uint32(11), Nullable::Value(Uint32(1)))
+        ]
+    );
+}
+
+#[test]
+fn test_declared_on_delete_set_null_in_transaction_is_applied_at_commit() {
+    let ctx = setup_teams();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, TeamTestSchema, tx_id);
+    tx_db
+        .delete::<Team>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        )
+        .unwrap();
+    tx_db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, TeamTestSchema);
+    assert_eq!(
+        member_teams(&db),
+        vec![(Uint32(10), Nullable::Null), (Uint32(11), Nullable::Null)]
+    );
+}
+
+#[test]
+fn test_insert_with_behavior_via_schema_dispatch() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    let values = vec![
+        (User::columns()[0], Value::Uint32(Uint32(1))),
+        (User::columns()[1], Value::Text(Text("alicia".to_string()))),
+    ];
+
+    TestSchema
+        .insert_with_behavior(
+            &db,
+            User::table_name(),
+            &values,
+            InsertConflictBehavior::Replace,
+        )
+        .unwrap();
+    let user = db.select_one::<User>(Query::default()).unwrap().unwrap();
+    assert_eq!(user.name, Some(Text("alicia".to_string())));
+}
+
+// -- unique constraint tests --
+
+#[test]
+fn test_insert_contract_with_unique_code_succeeds() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+    insert_contract(&db, 2, "CONTRACT-002", 1);
+
+    let rows = db.select::<Contract>(Query::builder().build()).unwrap();
+    assert_eq!(rows.len(), 2);
+}
+
+#[test]
+fn test_insert_contract_with_duplicate_code_fails() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+
+    let insert = ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(2))),
+        (
+            Contract::columns()[1],
+            Value::Text(Text("CONTRACT-001".to_string())),
+        ),
+        // columns()[2] is `order` (autoincrement) — omitted
+        (Contract::columns()[3], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
+    let result = db.insert::<Contract>(insert);
+    assert!(matches!(
+        result,
+        Err(wasm_dbms_api::prelude::DbmsError::Query(
+            wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_update_contract_code_to_unique_value_succeeds() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+
+    let patch = ContractUpdateRequest::from_values(
+        &[(
+            Contract::columns()[1],
+            Value::Text(Text("CONTRACT-999".to_string())),
+        )],
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    db.update::<Contract>(patch).unwrap();
+
+    let rows = db
+        .select::<Contract>(
+            Query::builder()
+                .and_where(Filter::Eq("id".to_string(), Value::Uint32(Uint32(1))))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows[0].code, Some(Text("CONTRACT-999".to_string())));
+}
+
+#[test]
+fn test_update_contract_keeping_same_code_succeeds() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_user(&db, 2, "bob");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+
+    // Change user_id but keep the same unique code
+    let patch = ContractUpdateRequest::from_values(
+        &[(Contract::columns()[2], Value::Uint32(Uint32(2)))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    db.update::<Contract>(patch).unwrap();
+}
+
+#[test]
+fn test_update_contract_code_to_existing_value_fails() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+    insert_contract(&db, 2, "CONTRACT-002", 1);
+
+    let patch = ContractUpdateRequest::from_values(
+        &[(
+            Contract::columns()[1],
+            Value::Text(Text("CONTRACT-001".to_string())),
+        )],
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    );
+    let result = db.update::<Contract>(patch);
+    assert!(matches!(
+        result,
+        Err(wasm_dbms_api::prelude::DbmsError::Query(
+            wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_unique_constraint_with_transaction_commit() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+
+    // Insert a second contract within a transaction
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_contract(&db, 2, "CONTRACT-002", 1);
+    db.commit().unwrap();
+
+    // After commit, inserting a duplicate should fail
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    let insert = ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(3))),
+        (
+            Contract::columns()[1],
+            Value::Text(Text("CONTRACT-002".to_string())),
+        ),
+        // columns()[2] is `order` (autoincrement) — omitted
+        (Contract::columns()[3], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
+    assert!(matches!(
+        db.insert::<Contract>(insert),
+        Err(wasm_dbms_api::prelude::DbmsError::Query(
+            wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { .. }
+        ))
+    ));
+}
+
+#[test]
+fn test_unique_constraint_after_delete_allows_reuse() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+    insert_contract(&db, 1, "CONTRACT-001", 1);
+
+    // Delete the contract
+    db.delete::<Contract>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    )
+    .unwrap();
+
+    // Now inserting a new contract with the same code should succeed
+    insert_contract(&db, 2, "CONTRACT-001", 1);
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "user_roles"]
+pub struct UserRole {
+    #[primary_key]
+    pub user_id: Uint32,
+    #[primary_key]
+    pub role_id: Uint32,
+    #[index]
+    pub granted_by: Text,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(UserRole = "user_roles")]
+pub struct UserRoleTestSchema;
+
+fn user_role(user_id: u32, role_id: u32, granted_by: &str) -> UserRoleInsertRequest {
+    UserRoleInsertRequest {
+        user_id: Uint32(user_id),
+        role_id: Uint32(role_id),
+        granted_by: Text(granted_by.to_string()),
+    }
+}
+
+fn user_role_filter(user_id: u32, role_id: u32) -> Filter {
+    Filter::eq("user_id", Value::Uint32(Uint32(user_id)))
+        .and(Filter::eq("role_id", Value::Uint32(Uint32(role_id))))
+}
+
+#[test]
+fn test_composite_primary_key_schema() {
+    assert_eq!(UserRole::primary_keys(), &["user_id", "role_id"]);
+    assert_eq!(UserRole::indexes()[0].columns(), &["user_id", "role_id"]);
+    assert!(UserRole::columns().iter().all(|c| !c.unique));
+}
+
+#[test]
+fn test_composite_primary_key_conflicts_only_on_full_key() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    UserRoleTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, UserRoleTestSchema);
+
+    // sharing part of the key is fine
+    db.insert::<UserRole>(user_role(1, 1, "root")).unwrap();
+    db.insert::<UserRole>(user_role(1, 2, "root")).unwrap();
+    db.insert::<UserRole>(user_role(2, 1, "root")).unwrap();
+    assert!(matches!(
+        db.insert::<UserRole>(user_role(1, 2, "alice")),
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+    assert!(matches!(
+        db.insert_many::<UserRole>(vec![user_role(3, 1, "root"), user_role(3, 1, "bob")]),
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+
+    // moving a row onto another key conflicts, onto a free one does not
+    let patch = UserRoleUpdateRequest::from_values(
+        &[(UserRole::columns()[1], Value::Uint32(Uint32(1)))],
+        Some(user_role_filter(1, 2)),
+    );
+    assert!(matches!(
+        db.update::<UserRole>(patch),
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+    let patch = UserRoleUpdateRequest::from_values(
+        &[(UserRole::columns()[1], Value::Uint32(Uint32(3)))],
+        Some(user_role_filter(1, 2)),
+    );
+    assert_eq!(db.update::<UserRole>(patch).unwrap(), 1);
+    // updating a non-key column keeps the key
+    let patch = UserRoleUpdateRequest::from_values(
+        &[(
+            UserRole::columns()[2],
+            Value::Text(Text("carol".to_string())),
+        )],
+        Some(user_role_filter(1, 3)),
+    );
+    assert_eq!(db.update::<UserRole>(patch).unwrap(), 1);
+
+    assert!(db.exists::<UserRole>(user_role_filter(1, 3)).unwrap());
+    assert!(!db.exists::<UserRole>(user_role_filter(1, 2)).unwrap());
+    assert_eq!(
+        db.delete::<UserRole>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("user_id", Value::Uint32(Uint32(1))))
+        )
+        .unwrap(),
+        2
+    );
+    assert_eq!(db.count::<UserRole>(None).unwrap(), 1);
+}
+
+#[test]
+fn test_composite_primary_key_in_transaction() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    UserRoleTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, UserRoleTestSchema);
+    db.insert::<UserRole>(user_role(1, 1, "root")).unwrap();
+    db.insert::<UserRole>(user_role(1, 2, "root")).unwrap();
+
+    let tx_id = ctx.begin_transaction(vec![1]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, UserRoleTestSchema, tx_id);
+    tx_db.insert::<UserRole>(user_role(2, 1, "root")).unwrap();
+    assert!(matches!(
+        tx_db.insert::<UserRole>(user_role(2, 1, "root")),
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+
+    // re-point a committed row through the secondary index
+    let patch = UserRoleUpdateRequest::from_values(
+        &[(
+            UserRole::columns()[2],
+            Value::Text(Text("alice".to_string())),
+        )],
+        Some(user_role_filter(1, 2)),
+    );
+    assert_eq!(tx_db.update::<UserRole>(patch).unwrap(), 1);
+    let rows = tx_db
+        .select::<UserRole>(
+            Query::builder()
+                .all()
+                .and_where(Filter::eq(
+                    "granted_by",
+                    Value::Text(Text("alice".to_string())),
+                ))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].user_id, Some(Uint32(1)));
+    assert_eq!(rows[0].role_id, Some(Uint32(2)));
+
+    tx_db
+        .delete::<UserRole>(DeleteBehavior::Restrict, Some(user_role_filter(1, 1)))
+        .unwrap();
+    assert!(!tx_db.exists::<UserRole>(user_role_filter(1, 1)).unwrap());
+    assert!(tx_db.exists::<UserRole>(user_role_filter(1, 2)).unwrap());
+    tx_db.commit().unwrap();
+
+    let keys: Vec<_> = db
+        .select::<UserRole>(Query::builder().all().order_by_asc("user_id").build())
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.user_id.unwrap().0, row.role_id.unwrap().0))
+        .collect();
+    assert_eq!(keys, vec![(1, 2), (2, 1)]);
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "grants"]
+pub struct Grant {
+    #[primary_key]
+    pub id: Uint32,
+    #[foreign_key(entity = "UserRole", table = "user_roles", columns = "user_id,role_id")]
+    pub user_id: Uint32,
+    pub role_id: Uint32,
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "delegations"]
+pub struct Delegation {
+    #[primary_key]
+    pub id: Uint32,
+    #[foreign_key(
+        entity = "UserRole",
+        table = "user_roles",
+        columns = "user_id,role_id",
+        on_delete = "SetNull"
+    )]
+    pub user_id: Nullable<Uint32>,
+    pub role_id: Nullable<Uint32>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(UserRole = "user_roles", Grant = "grants", Delegation = "delegations")]
+pub struct GrantTestSchema;
+
+fn grant(id: u32, user_id: u32, role_id: u32) -> GrantInsertRequest {
+    GrantInsertRequest {
+        id: Uint32(id),
+        user_id: Uint32(user_id),
+        role_id: Uint32(role_id),
+    }
+}
+
+fn setup_grants() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    GrantTestSchema::register_tables(&ctx).unwrap();
+    {
+        let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+        db.insert::<UserRole>(user_role(1, 1, "root")).unwrap();
+        db.insert::<UserRole>(user_role(1, 2, "alice")).unwrap();
+        db.insert::<Grant>(grant(10, 1, 1)).unwrap();
+        db.insert::<Grant>(grant(11, 1, 2)).unwrap();
+    }
+    ctx
+}
+
+#[test]
+fn test_composite_foreign_key_schema() {
+    let fk = Grant::columns()[1].foreign_key.unwrap();
+    assert!(fk.is_composite());
+    assert_eq!(fk.local_column, "user_id");
+    assert_eq!(fk.local_columns, &["user_id", "role_id"]);
+    assert_eq!(fk.foreign_columns, &["user_id", "role_id"]);
+    assert!(Grant::columns()[2].foreign_key.is_none());
+}
+
+#[test]
+fn test_composite_foreign_key_must_reference_existing_row() {
+    let ctx = setup_grants();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+
+    // user 1 and role 3 exist separately, not together
+    let err = db.insert::<Grant>(grant(12, 1, 3)).unwrap_err();
+    assert!(matches!(
+        err,
+        DbmsError::Query(QueryError::ForeignKeyViolation { table, column, value })
+            if table == "user_roles"
+                && column == "user_id, role_id"
+                && value == Value::Array(vec![Value::Uint32(Uint32(1)), Value::Uint32(Uint32(3))])
+    ));
+    assert_eq!(db.count::<Grant>(None).unwrap(), 2);
+}
+
+#[test]
+fn test_composite_foreign_key_loads_relation() {
+    let ctx = setup_grants();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+
+    let grants = db
+        .select::<Grant>(
+            Query::builder()
+                .all()
+                .with("user_roles")
+                .order_by_asc("id")
+                .build(),
+        )
+        .unwrap();
+    let granted_by: Vec<_> = grants
+        .iter()
+        .map(|grant| grant.user_id.as_ref().unwrap().granted_by.clone().unwrap())
+        .collect();
+    assert_eq!(
+        granted_by,
+        vec![Text("root".to_string()), Text("alice".to_string())]
+    );
+}
+
+#[test]
+fn test_delete_referenced_composite_key() {
+    let ctx = setup_grants();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+
+    assert!(matches!(
+        db.delete::<UserRole>(DeleteBehavior::Restrict, Some(user_role_filter(1, 2))),
+        Err(DbmsError::Query(
+            QueryError::ForeignKeyConstraintViolation { .. }
+        ))
+    ));
+    // only the grant referencing the whole key is cascaded
+    assert_eq!(
+        db.delete::<UserRole>(DeleteBehavior::Cascade, Some(user_role_filter(1, 2)))
+            .unwrap(),
+        2
+    );
+    let ids: Vec<_> = db
+        .select::<Grant>(Query::default())
+        .unwrap()
+        .into_iter()
+        .map(|grant| grant.id.unwrap())
+        .collect();
+    assert_eq!(ids, vec![Uint32(10)]);
+}
+
+fn delegation(id: u32, user_id: u32, role_id: u32) -> DelegationInsertRequest {
+    DelegationInsertRequest {
+        id: Uint32(id),
+        user_id: Nullable::Value(Uint32(user_id)),
+        role_id: Nullable::Value(Uint32(role_id)),
+    }
+}
+
+/// Returns the `(id, user_id, role_id)` values of the rows of `table`.
+fn composite_key_rows(
+    db: &WasmDbmsDatabase<'_, HeapMemoryProvider>,
+    table: &str,
+) -> Vec<Vec<Value>> {
+    db.select_raw(table, Query::builder().all().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|row| row.into_iter().map(|(_, value)| value).collect())
+        .collect()
+}
+
+fn key_row(id: u32, user_id: u32, role_id: u32) -> Vec<Value> {
+    vec![
+        Value::Uint32(Uint32(id)),
+        Value::Uint32(Uint32(user_id)),
+        Value::Uint32(Uint32(role_id)),
+    ]
+}
+
+#[test]
+fn test_update_composite_primary_key_cascades_to_referencing_rows() {
+    let ctx = setup_grants();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+    db.insert::<Delegation>(delegation(20, 1, 2)).unwrap();
+
+    let patch = UserRoleUpdateRequest::from_values(
+        &[(UserRole::columns()[1], Value::Uint32(Uint32(3)))],
+        Some(user_role_filter(1, 2)),
+    );
+    // the role, its grant and its delegation
+    assert_eq!(db.update::<UserRole>(patch).unwrap(), 3);
+
+    assert_eq!(
+        composite_key_rows(&db, "grants"),
+        vec![key_row(10, 1, 1), key_row(11, 1, 3)]
+    );
+    assert_eq!(
+        composite_key_rows(&db, "delegations"),
+        vec![key_row(20, 1, 3)]
+    );
+}
+
+#[test]
+fn test_update_composite_primary_key_in_transaction_cascades_at_commit() {
+    let ctx = setup_grants();
+    let tx_id = ctx.begin_transaction(vec![1]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, GrantTestSchema, tx_id);
+
+    let patch = UserRoleUpdateRequest::from_values(
+        &[(UserRole::columns()[1], Value::Uint32(Uint32(3)))],
+        Some(user_role_filter(1, 2)),
+    );
+    assert_eq!(tx_db.update::<UserRole>(patch).unwrap(), 2);
+    tx_db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+    assert_eq!(
+        composite_key_rows(&db, "grants"),
+        vec![key_row(10, 1, 1), key_row(11, 1, 3)]
+    );
+}
+
+#[test]
+fn test_delete_set_null_clears_every_column_of_composite_foreign_key() {
+    let ctx = setup_grants();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+    db.insert::<Delegation>(delegation(20, 1, 2)).unwrap();
+    db.insert::<Delegation>(delegation(21, 1, 1)).unwrap();
+
+    // the role, its cascaded grant and its delegation set to null
+    assert_eq!(
+        db.delete::<UserRole>(DeleteBehavior::Cascade, Some(user_role_filter(1, 2)))
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        composite_key_rows(&db, "delegations"),
+        vec![
+            vec![Value::Uint32(Uint32(20)), Value::Null, Value::Null],
+            key_row(21, 1, 1),
+        ]
+    );
+}
+
+#[test]
+fn test_delete_set_null_rejects_non_nullable_composite_foreign_key() {
+    let ctx = setup_grants();
+    let db = WasmDbmsDatabase::oneshot(&ctx, GrantTestSchema);
+
+    assert!(matches!(
+        db.delete::<UserRole>(DeleteBehavior::SetNull, Some(user_role_filter(1, 2))),
+        Err(DbmsError::Query(QueryError::ConstraintViolation(_)))
+    ));
+    assert_eq!(
+        composite_key_rows(&db, "grants"),
+        vec![key_row(10, 1, 1), key_row(11, 1, 2)]
+    );
+}
+
+static NEXT_SERIAL: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(100);
+
+fn next_serial() -> u32 {
+    NEXT_SERIAL.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "articles"]
+pub struct Article {
+    #[primary_key]
+    pub id: Uint32,
+    pub title: Text,
+    #[default = "draft"]
+    pub status: Text,
+    #[default(next_serial())]
+    pub serial: Uint32,
+    #[default = "anonymous"]
+    pub author: Nullable<Text>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Article = "articles")]
+pub struct ArticleTestSchema;
+
+#[test]
+fn test_insert_fills_omitted_default_fields() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ArticleTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, ArticleTestSchema);
+
+    for id in [1, 2] {
+        db.insert::<Article>(ArticleInsertRequest {
+            id: Uint32(id),
+            title: Text("hello".to_string()),
+            status: None,
+            serial: None,
+            author: None,
+        })
+        .unwrap();
+    }
+    db.insert::<Article>(ArticleInsertRequest {
+        id: Uint32(3),
+        title: Text("hello".to_string()),
+        status: Some(Text("published".to_string())),
+        serial: Some(Uint32(7)),
+        author: Some(Nullable::Null),
+    })
+    .unwrap();
+
+    let rows = db
+        .select::<Article>(Query::builder().all().order_by_asc("id").build())
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].status, Some(Text("draft".to_string())));
+    assert_eq!(
+        rows[0].author,
+        Some(Nullable::Value(Text("anonymous".to_string())))
+    );
+    // the default expression is evaluated for every insert
+    let first = rows[0].serial.unwrap();
+    let second = rows[1].serial.unwrap();
+    assert!(second.0 > first.0);
+    assert_eq!(rows[2].status, Some(Text("published".to_string())));
+    assert_eq!(rows[2].serial, Some(Uint32(7)));
+    assert_eq!(rows[2].author, Some(Nullable::Null));
+}
+
+#[test]
+fn test_defaulted_insert_request_conversions() {
+    let request = ArticleInsertRequest::from_values(&[
+        (Article::columns()[0], Value::Uint32(Uint32(1))),
+        (Article::columns()[1], Value::Text(Text("hi".to_string()))),
+    ])
+    .unwrap();
+    assert!(request.status.is_none());
+    assert!(request.serial.is_none());
+    assert!(request.author.is_none());
+
+    let values = request.clone().into_values();
+    assert_eq!(values.len(), 5);
+    assert_eq!(values[2].1, Value::Text(Text("draft".to_string())));
+    // dynamic defaults stay out of the column definition and the schema snapshot
+    assert!(Article::columns()[2].default.is_some());
+    assert!(Article::columns()[3].default.is_none());
+
+    let record = request.into_record();
+    assert_eq!(record.status, Text("draft".to_string()));
+    assert_eq!(
+        record.author,
+        Nullable::Value(Text("anonymous".to_string()))
+    );
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "badges"]
+pub struct Badge {
+    #[primary_key]
+    pub id: Uint32,
+    #[unique]
+    pub tag: Nullable<Text>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Badge = "badges")]
+pub struct BadgeTestSchema;
+
+#[test]
+fn test_nullable_unique_column_allows_multiple_nulls() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    BadgeTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BadgeTestSchema);
+    let badge = |id: u32, tag: Option<&str>| BadgeInsertRequest {
+        id: Uint32(id),
+        tag: match tag {
+            Some(tag) => Nullable::Value(Text(tag.to_string())),
+            None => Nullable::Null,
+        },
+    };
+
+    db.insert::<Badge>(badge(1, None)).unwrap();
+    db.insert::<Badge>(badge(2, None)).unwrap();
+    db.insert::<Badge>(badge(3, Some("gold"))).unwrap();
+    assert!(matches!(
+        db.insert::<Badge>(badge(4, Some("gold"))),
+        Err(DbmsError::Query(QueryError::UniqueConstraintViolation { ref table, ref column }))
+            if table == "badges" && column == "tag"
+    ));
+
+    // updating a row to NULL never collides either
+    let patch = BadgeUpdateRequest::from_values(
+        &[(Badge::columns()[1], Value::Null)],
+        Some(Filter::eq("id", Value::Uint32(Uint32(3)))),
+    );
+    assert_eq!(db.update::<Badge>(patch).unwrap(), 1);
+
+    // two NULLs inserted by the same transaction are fine, two equal values are not
+    let tx_id = ctx.begin_transaction(vec![1]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, BadgeTestSchema, tx_id);
+    tx_db.insert::<Badge>(badge(5, None)).unwrap();
+    tx_db.insert::<Badge>(badge(6, Some("silver"))).unwrap();
+    assert!(tx_db.insert::<Badge>(badge(7, Some("silver"))).is_err());
+    tx_db.commit().unwrap();
+
+    assert_eq!(db.count::<Badge>(None).unwrap(), 5);
+    assert_eq!(db.count::<Badge>(Some(Filter::is_null("tag"))).unwrap(), 4);
+}
+
+// -- autoincrement tests --
+
+#[test]
+fn test_autoincrement_auto_generates_sequential_values() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    insert_contract(&db, 1, "C-001", 1);
+    insert_contract(&db, 2, "C-002", 1);
+    insert_contract(&db, 3, "C-003", 1);
+
+    let rows = db
+        .select::<Contract>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].order, Some(Uint32(1)));
+    assert_eq!(rows[1].order, Some(Uint32(2)));
+    assert_eq!(rows[2].order, Some(Uint32(3)));
+}
+
+#[test]
+fn test_autoincrement_explicit_value_overrides_auto() {
+    use wasm_dbms_api::prelude::Autoincrement;
+
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    // Insert with an explicit autoincrement value
+    let insert = ContractInsertRequest {
+        id: Uint32(1),
+        code: Text("C-001".to_string()),
+        order: Autoincrement::Value(Uint32(42)),
+        user_id: Uint32(1),
+    };
+    db.insert::<Contract>(insert).unwrap();
+
+    let rows = db.select::<Contract>(Query::builder().build()).unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].order, Some(Uint32(42)));
+}
+
+#[test]
+fn test_autoincrement_does_not_recycle_after_delete() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    insert_contract(&db, 1, "C-001", 1); // order = 1
+    insert_contract(&db, 2, "C-002", 1); // order = 2
+
+    // Delete the first contract
+    db.delete::<Contract>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    )
+    .unwrap();
+
+    // Next insert should get order = 3, not 1
+    insert_contract(&db, 3, "C-003", 1);
+
+    let rows = db
+        .select::<Contract>(
+            Query::builder()
+                .and_where(Filter::eq("id", Value::Uint32(Uint32(3))))
+                .build(),
+        )
+        .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].order, Some(Uint32(3)));
+}
+
+#[test]
+fn test_autoincrement_with_transaction_commit() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    // Insert outside transaction
+    insert_contract(&db, 1, "C-001", 1); // order = 1
+
+    // Insert inside transaction
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_contract(&db, 2, "C-002", 1); // order = 2
+    db.commit().unwrap();
+
+    // After commit, next auto value should be 3
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_contract(&db, 3, "C-003", 1);
+
+    let rows = db
+        .select::<Contract>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].order, Some(Uint32(1)));
+    assert_eq!(rows[1].order, Some(Uint32(2)));
+    assert_eq!(rows[2].order, Some(Uint32(3)));
+}
+
+#[test]
+fn test_autoincrement_with_transaction_rollback_does_not_revert_counter() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    insert_contract(&db, 1, "C-001", 1); // order = 1
+
+    // Insert inside transaction then rollback
+    let owner = vec![1, 2, 3];
+    let tx_id = ctx.begin_transaction(owner.clone());
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+    insert_contract(&db, 2, "C-002", 1); // order = 2 (consumed)
+    db.rollback().unwrap();
+
+    // After rollback, counter should still have advanced (order = 2 is consumed)
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_contract(&db, 3, "C-003", 1);
+
+    let rows = db
+        .select::<Contract>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+
+    assert_eq!(rows.len(), 2); // only C-001 and C-003
+    assert_eq!(rows[0].order, Some(Uint32(1)));
+    assert_eq!(rows[1].order, Some(Uint32(3))); // 2 was consumed by the rolled-back tx
+}
+
+#[test]
+fn test_autoincrement_explicit_value_bumps_counter() {
+    use wasm_dbms_api::prelude::Autoincrement;
+
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    insert_contract(&db, 1, "C-001", 1); // order = 1
+    db.insert::<Contract>(ContractInsertRequest {
+        id: Uint32(2),
+        code: Text("C-002".to_string()),
+        order: Autoincrement::Value(Uint32(10)),
+        user_id: Uint32(1),
+    })
+    .unwrap();
+    // generated values continue above the explicit one
+    insert_contract(&db, 3, "C-003", 1);
+    // a lower explicit value does not move the counter back
+    db.insert::<Contract>(ContractInsertRequest {
+        id: Uint32(4),
+        code: Text("C-004".to_string()),
+        order: Autoincrement::Value(Uint32(5)),
+        user_id: Uint32(1),
+    })
+    .unwrap();
+    insert_contract(&db, 5, "C-005", 1);
+
+    let orders: Vec<_> = db
+        .select::<Contract>(Query::builder().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|row| row.order.unwrap())
+        .collect();
+    assert_eq!(
+        orders,
+        vec![Uint32(1), Uint32(10), Uint32(11), Uint32(5), Uint32(12)]
+    );
+}
+
+#[test]
+fn test_autoincrement_rejected_insert_does_not_advance_counter() {
+    use wasm_dbms_api::prelude::Autoincrement;
+
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    // both inserts reference a missing user
+    let explicit = ContractInsertRequest {
+        id: Uint32(1),
+        code: Text("C-001".to_string()),
+        order: Autoincrement::Value(Uint32(10)),
+        user_id: Uint32(99),
+    };
+    assert!(db.insert::<Contract>(explicit).is_err());
+    let generated = ContractInsertRequest {
+        id: Uint32(1),
+        code: Text("C-001".to_string()),
+        order: Autoincrement::Auto,
+        user_id: Uint32(99),
+    };
+    assert!(db.insert::<Contract>(generated).is_err());
+
+    // an insert rolled back by `atomic` restores the counter
+    let res = db.atomic(|db| {
+        insert_contract(db, 2, "C-002", 1);
+        Err::<(), _>(DbmsError::Query(QueryError::InvalidQuery(
+            "abort".to_string(),
+        )))
+    });
+    assert!(res.is_err());
+
+    insert_contract(&db, 3, "C-003", 1);
+    let rows = db.select::<Contract>(Query::builder().build()).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].order, Some(Uint32(1)));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "tickets"]
+pub struct Ticket {
+    #[primary_key(auto_increment)]
+    pub id: Uint64,
+    pub subject: Text,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Ticket = "tickets")]
+pub struct TicketTestSchema;
+
+#[test]
+fn test_primary_key_auto_increment_allocates_ids_at_insert_time() {
+    use wasm_dbms_api::prelude::Autoincrement;
+
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    TicketTestSchema::register_tables(&ctx).unwrap();
+    assert!(Ticket::columns()[0].auto_increment);
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, TicketTestSchema);
+    db.insert::<Ticket>(TicketInsertRequest {
+        id: Autoincrement::Auto,
+        subject: Text("first".to_string()),
+    })
+    .unwrap();
+    db.insert::<Ticket>(TicketInsertRequest {
+        id: Autoincrement::Value(Uint64(100)),
+        subject: Text("explicit".to_string()),
+    })
+    .unwrap();
+
+    // ids are reserved by the insert itself, so two concurrent transactions
+    // never receive the same value, whatever their commit order
+    let tx_a = ctx.begin_transaction(vec![1]);
+    let tx_b = ctx.begin_transaction(vec![2]);
+    let mut db_a = WasmDbmsDatabase::from_transaction(&ctx, TicketTestSchema, tx_a);
+    let mut db_b = WasmDbmsDatabase::from_transaction(&ctx, TicketTestSchema, tx_b);
+    db_a.insert::<Ticket>(TicketInsertRequest {
+        id: Autoincrement::Auto,
+        subject: Text("tx a".to_string()),
+    })
+    .unwrap();
+    db_b.insert::<Ticket>(TicketInsertRequest {
+        id: Autoincrement::Auto,
+        subject: Text("tx b".to_string()),
+    })
+    .unwrap();
+    db_b.commit().unwrap();
+    db_a.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, TicketTestSchema);
+    let rows: Vec<_> = db
+        .select::<Ticket>(Query::builder().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|row| (row.id.unwrap(), row.subject.unwrap().0))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            (Uint64(1), "first".to_string()),
+            (Uint64(100), "explicit".to_string()),
+            (Uint64(101), "tx a".to_string()),
+            (Uint64(102), "tx b".to_string()),
+        ]
+    );
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "receipts"]
+pub struct Receipt {
+    #[primary_key]
+    #[auto_increment]
+    pub id: Uint32,
+    pub total: Uint64,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Receipt = "receipts")]
+pub struct ReceiptTestSchema;
+
+#[test]
+fn test_auto_increment_attribute_generates_ids_from_none() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ReceiptTestSchema::register_tables(&ctx).unwrap();
+    assert!(Receipt::columns()[0].auto_increment);
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, ReceiptTestSchema);
+    for (id, total) in [(None, 10), (Some(Uint32(7)), 20), (None, 30)] {
+        db.insert::<Receipt>(ReceiptInsertRequest {
+            id: id.into(),
+            total: Uint64(total),
+        })
+        .unwrap();
+    }
+
+    let ids: Vec<_> = db
+        .select::<Receipt>(Query::builder().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|row| row.id.unwrap())
+        .collect();
+    assert_eq!(ids, vec![Uint32(1), Uint32(7), Uint32(8)]);
+}
+
+#[test]
+fn test_autoincrement_from_values_with_auto_variant() {
+    use wasm_dbms_api::prelude::Autoincrement;
+
+    // from_values without the autoincrement column should produce Auto
+    let insert = ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(1))),
+        (
+            Contract::columns()[1],
+            Value::Text(Text("C-001".to_string())),
+        ),
+        (Contract::columns()[3], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
+
+    assert_eq!(insert.order, Autoincrement::Auto);
+}
+
+#[test]
+fn test_autoincrement_from_values_with_value_variant() {
+    use wasm_dbms_api::prelude::Autoincrement;
+
+    // from_values with the autoincrement column should produce Value
+    let insert = ContractInsertRequest::from_values(&[
+        (Contract::columns()[0], Value::Uint32(Uint32(1))),
+        (
+            Contract::columns()[1],
+            Value::Text(Text("C-001".to_string())),
+        ),
+        (Contract::columns()[2], Value::Uint32(Uint32(99))),
+        (Contract::columns()[3], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
+
+    assert_eq!(insert.order, Autoincrement::Value(Uint32(99)));
+}
+
+#[test]
+fn test_autoincrement_into_values_skips_auto() {
+    use wasm_dbms_api::prelude::{Autoincrement, InsertRecord as _};
+
+    let insert = ContractInsertRequest {
+        id: Uint32(1),
+        code: Text("C-001".to_string()),
+        order: Autoincrement::Auto,
+        user_id: Uint32(1),
+    };
+    let values = insert.into_values();
+
+    // Should have 3 values (id, code, user_id) — order is skipped
+    assert_eq!(values.len(), 3);
+    assert!(values.iter().all(|(col, _)| col.name != "order"));
+}
+
+#[test]
+fn test_autoincrement_into_values_includes_explicit_value() {
+    use wasm_dbms_api::prelude::{Autoincrement, InsertRecord as _};
+
+    let insert = ContractInsertRequest {
+        id: Uint32(1),
+        code: Text("C-001".to_string()),
+        order: Autoincrement::Value(Uint32(42)),
+        user_id: Uint32(1),
+    };
+    let values = insert.into_values();
+
+    // Should have 4 values (id, code, order, user_id)
+    assert_eq!(values.len(), 4);
+    let order_val = values.iter().find(|(col, _)| col.name == "order");
+    assert!(order_val.is_some());
+    assert_eq!(order_val.unwrap().1, Value::Uint32(Uint32(42)));
+}
+
+#[test]
+fn test_autoincrement_select_filter_on_autoincrement_column() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    insert_contract(&db, 1, "C-001", 1); // order = 1
+    insert_contract(&db, 2, "C-002", 1); // order = 2
+    insert_contract(&db, 3, "C-003", 1); // order = 3
+
+    let rows = db
+        .select::<Contract>(
+            Query::builder()
+                .and_where(Filter::eq("order", Value::Uint32(Uint32(2))))
+                .build(),
+        )
+        .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, Some(Uint32(2)));
+    assert_eq!(rows[0].order, Some(Uint32(2)));
+}
+
+// -- aggregate tests --
+
+mod aggregate_tests {
+    use rust_decimal::Decimal as RustDecimal;
+    use wasm_dbms_api::prelude::{
+        AggregateFunction, AggregatedValue, Database as _, DbmsError, Decimal, Filter,
+        OrderDirection, Query, QueryError, Text, Uint32, Uint64, Value,
+    };
+    use wasm_dbms_macros::{DatabaseSchema, Table};
+    use wasm_dbms_memory::prelude::HeapMemoryProvider;
+
+    use super::{Post, TestSchema, User, insert_post, insert_user, setup};
+    use crate::prelude::{DbmsContext, WasmDbmsDatabase};
+
+    fn seed(db: &WasmDbmsDatabase<'_, wasm_dbms_memory::prelude::HeapMemoryProvider>) {
+        insert_user(db, 1, "alice");
+        insert_user(db, 2, "bob");
+        insert_user(db, 3, "carol");
+        // Posts: alice has 3, bob has 1, carol has 0.
+        insert_post(db, 10, "p1", 1);
+        insert_post(db, 20, "p2", 1);
+        insert_post(db, 30, "p3", 1);
+        insert_post(db, 40, "p4", 2);
+    }
+
+    fn dec(s: &str) -> Value {
+        Value::Decimal(Decimal(RustDecimal::from_str_exact(s).unwrap()))
+    }
+
+    #[test]
+    fn aggregate_count_all_no_group_by() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let result = db
+            .aggregate::<Post>(Query::default(), &[AggregateFunction::Count(None)])
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert!(result[0].group_keys.is_empty());
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(4)]);
+    }
+
+    #[test]
+    fn aggregate_count_column_skips_nulls() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        // user_id is non-null on every Post, so COUNT(user_id) == COUNT(*).
+        let result = db
+            .aggregate::<Post>(
+                Query::default(),
+                &[AggregateFunction::Count(Some("user_id".into()))],
+            )
+            .unwrap();
+
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(4)]);
+    }
+
+    #[test]
+    fn aggregate_sum_avg_min_max_no_group_by() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let aggs = vec![
+            AggregateFunction::Sum("user_id".into()),
+            AggregateFunction::Avg("user_id".into()),
+            AggregateFunction::Min("user_id".into()),
+            AggregateFunction::Max("user_id".into()),
+        ];
+
+        let result = db.aggregate::<Post>(Query::default(), &aggs).unwrap();
+        assert_eq!(result.len(), 1);
+        let v = &result[0].values;
+        // sum(1+1+1+2) = 5, avg = 5/4 = 1.25
+        assert_eq!(v[0], AggregatedValue::Sum(dec("5")));
+        assert_eq!(v[1], AggregatedValue::Avg(dec("1.25")));
+        assert_eq!(v[2], AggregatedValue::Min(Value::Uint32(Uint32(1))));
+        assert_eq!(v[3], AggregatedValue::Max(Value::Uint32(Uint32(2))));
+    }
+
+    #[test]
+    fn aggregate_group_by_single_column() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder().group_by(&["user_id"]).build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let mut result = db.aggregate::<Post>(query, &aggs).unwrap();
+        // Sort by group key for deterministic assertion.
+        result.sort_by(|a, b| a.group_keys[0].cmp(&b.group_keys[0]));
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(1))]);
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
+        assert_eq!(result[1].group_keys, vec![Value::Uint32(Uint32(2))]);
+        assert_eq!(result[1].values, vec![AggregatedValue::Count(1)]);
+    }
+
+    #[test]
+    fn aggregate_having_filters_groups() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        // GROUP BY user_id, HAVING agg0 > 1 — keep only alice's group.
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .having(Filter::gt("agg0", Value::Uint64(Uint64(1))))
+            .build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let result = db.aggregate::<Post>(query, &aggs).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(1))]);
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
+    }
+
+    #[test]
+    fn aggregate_having_on_group_key_column() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .having(Filter::eq("user_id", Value::Uint32(Uint32(2))))
+            .build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let result = db.aggregate::<Post>(query, &aggs).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(2))]);
+    }
+
+    #[test]
+    fn aggregate_order_by_aggregate_output_then_limit() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .order_by_desc("agg0")
+            .limit(1)
+            .build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let result = db.aggregate::<Post>(query, &aggs).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(1))]);
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
+    }
+
+    #[test]
+    fn aggregate_offset_skips_rows() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .order_by_asc("user_id")
+            .offset(1)
+            .build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let result = db.aggregate::<Post>(query, &aggs).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(2))]);
+    }
+
+    #[test]
+    fn aggregate_with_where_pre_filters_rows() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .and_where(Filter::eq("user_id", Value::Uint32(Uint32(1))))
+            .build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let result = db.aggregate::<Post>(query, &aggs).unwrap();
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(3)]);
+    }
+
+    #[test]
+    fn aggregate_empty_table_no_group_by() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        // No inserts.
+
+        let aggs = vec![
+            AggregateFunction::Count(None),
+            AggregateFunction::Sum("user_id".into()),
+            AggregateFunction::Avg("user_id".into()),
+            AggregateFunction::Min("user_id".into()),
+            AggregateFunction::Max("user_id".into()),
+        ];
+
+        let result = db.aggregate::<Post>(Query::default(), &aggs).unwrap();
+        assert_eq!(result.len(), 1);
+        let v = &result[0].values;
+        assert_eq!(v[0], AggregatedValue::Count(0));
+        assert_eq!(v[1], AggregatedValue::Sum(Value::Null));
+        assert_eq!(v[2], AggregatedValue::Avg(Value::Null));
+        assert_eq!(v[3], AggregatedValue::Min(Value::Null));
+        assert_eq!(v[4], AggregatedValue::Max(Value::Null));
+    }
+
+    #[test]
+    fn aggregate_empty_table_group_by_returns_no_rows() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+        let query = Query::builder().group_by(&["user_id"]).build();
+        let aggs = vec![AggregateFunction::Count(None)];
+
+        let result = db.aggregate::<Post>(query, &aggs).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn aggregate_sum_on_non_numeric_column_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let aggs = vec![AggregateFunction::Sum("name".into())];
+        let err = db
+            .aggregate::<User>(Query::default(), &aggs)
+            .expect_err("SUM on Text must be rejected");
+        let msg = err.to_string();
+        assert!(msg.contains("numeric"), "unexpected error: {msg}");
+    }
+
+    #[test]
+    fn aggregate_avg_on_non_numeric_column_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let aggs = vec![AggregateFunction::Avg("name".into())];
+        let err = db
+            .aggregate::<User>(Query::default(), &aggs)
+            .expect_err("AVG on Text must be rejected");
+        assert!(err.to_string().contains("numeric"));
+    }
+
+    #[test]
+    fn aggregate_unknown_column_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let aggs = vec![AggregateFunction::Sum("not_a_column".into())];
+        let err = db
+            .aggregate::<User>(Query::default(), &aggs)
+            .expect_err("unknown column must be rejected");
+        assert!(err.to_string().contains("not_a_column"));
+    }
+
+    #[test]
+    fn group_count_counts_rows_per_group() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .order_by_desc("agg0")
+            .build();
+        let result = db.group_count::<Post>(query).unwrap();
+
+        assert_eq!(
+            result,
+            vec![(Value::Uint32(Uint32(1)), 3), (Value::Uint32(Uint32(2)), 1)]
+        );
+    }
+
+    #[test]
+    fn group_count_requires_single_group_by_column() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let err = db
+            .group_count::<Post>(Query::default())
+            .expect_err("group_count without group_by must be rejected");
+        assert!(matches!(err, DbmsError::Query(QueryError::InvalidQuery(_))));
+
+        let query = Query::builder().group_by(&["user_id", "title"]).build();
+        assert!(db.group_count::<Post>(query).is_err());
+    }
+
+    #[test]
+    fn aggregate_group_by_unknown_column_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+        let query = Query::builder().group_by(&["nope"]).build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("unknown group_by column must be rejected");
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn aggregate_group_by_relation_column_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        // only the columns of the aggregated table can be grouped by
+        let query = Query::builder().group_by(&["user_id.name"]).build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("group_by on a related table column must be rejected");
+        assert!(matches!(
+            err,
+            DbmsError::Query(QueryError::UnknownColumn(column)) if column == "user_id.name"
+        ));
+    }
+
+    #[test]
+    fn aggregate_having_unknown_aggregate_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        // Only one aggregate (agg0); reference agg1.
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .having(Filter::gt("agg1", Value::Uint64(Uint64(0))))
+            .build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("HAVING on unknown agg must be rejected");
+        assert!(err.to_string().contains("agg1"));
+    }
+
+    #[test]
+    fn aggregate_having_unknown_column_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .having(Filter::eq("foo", Value::Uint32(Uint32(0))))
+            .build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("HAVING on unknown column must be rejected");
+        assert!(err.to_string().contains("foo"));
+    }
+
+    #[test]
+    fn aggregate_order_by_unknown_aggregate_errors() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .order_by_asc("agg7")
+            .build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("ORDER BY on unknown agg must be rejected");
+        assert!(err.to_string().contains("agg7"));
+    }
+
+    #[test]
+    fn aggregate_having_like_rejected() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        seed(&db);
+
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .having(Filter::like("user_id", "%"))
+            .build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("LIKE in HAVING must be rejected");
+        assert!(err.to_string().contains("LIKE"));
+    }
+
+    #[test]
+    fn aggregate_avg_decimal_division_precision() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        // Insert posts so SUM(user_id) / 3 = 7/3 = 2.333...
+        insert_user(&db, 1, "a");
+        insert_user(&db, 2, "b");
+        insert_user(&db, 4, "c");
+        insert_post(&db, 1, "p", 1);
+        insert_post(&db, 2, "p", 2);
+        insert_post(&db, 3, "p", 4);
+
+        let aggs = vec![AggregateFunction::Avg("user_id".into())];
+        let result = db.aggregate::<Post>(Query::default(), &aggs).unwrap();
+        let AggregatedValue::Avg(Value::Decimal(d)) = &result[0].values[0] else {
+            panic!("expected Decimal avg");
+        };
+        // 7 / 3 truncated to RustDecimal precision should round-trip via string.
+        assert!(d.0.to_string().starts_with("2.33"));
+    }
+
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[table = "balances"]
+    pub struct Balance {
+        #[primary_key]
+        pub id: Uint32,
+        pub amount: Decimal,
+    }
+
+    #[derive(DatabaseSchema)]
+    #[tables(Balance = "balances")]
+    pub struct BalanceTestSchema;
+
+    #[test]
+    fn aggregate_sum_overflow_errors() {
+        let ctx = DbmsContext::new(HeapMemoryProvider::default());
+        BalanceTestSchema::register_tables(&ctx).unwrap();
+        let db = WasmDbmsDatabase::oneshot(&ctx, BalanceTestSchema);
+        for id in 1..=2 {
+            db.insert::<Balance>(BalanceInsertRequest {
+                id: Uint32(id),
+                amount: Decimal(RustDecimal::MAX),
+            })
+            .unwrap();
+        }
+
+        for agg in [
+            AggregateFunction::Sum("amount".into()),
+            AggregateFunction::Avg("amount".into()),
+        ] {
+            let err = db
+                .aggregate::<Balance>(Query::default(), &[agg])
+                .expect_err("overflowing aggregate must fail");
+            assert!(err.to_string().contains("overflow"));
+        }
+
+        let result = db
+            .aggregate::<Balance>(Query::default(), &[AggregateFunction::Max("amount".into())])
+            .unwrap();
+        assert_eq!(
+            result[0].values,
+            vec![AggregatedValue::Max(Value::Decimal(Decimal(
+                RustDecimal::MAX
+            )))]
+        );
+    }
+
+    #[test]
+    fn aggregate_join_query_rejected() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+        let query = Query::builder()
+            .inner_join("users", "user_id", "id")
+            .build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("joins must be rejected");
+        assert!(err.to_string().contains("join"));
+    }
+
+    #[test]
+    fn aggregate_eager_relations_rejected() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+        let query = Query::builder().with("users").build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("eager relations must be rejected");
+        assert!(err.to_string().contains("eager"));
+    }
+
+    #[test]
+    fn aggregate_orders_by_descending() {
+        let _ = OrderDirection::Descending; // silence unused import warning if any
+    }
+
+    // -- extra coverage: multi-col group_by, NULLs, Decimal AVG, transactions,
+    //    combined order, JSON-in-HAVING rejection --
+
+    use wasm_dbms_api::prelude::{InsertRecord as _, JsonFilter, Nullable, TableSchema as _};
+
+    use super::{Sale, SaleInsertRequest};
+
+    fn insert_sale(
+        db: &WasmDbmsDatabase<'_, wasm_dbms_memory::prelude::HeapMemoryProvider>,
+        id: u32,
+        category: &str,
+        price: u32,
+        bonus: Option<u32>,
+    ) {
+        let bonus: Nullable<Uint32> = bonus.map(Uint32).into();
+        let req = SaleInsertRequest::from_values(&[
+            (Sale::columns()[0], Value::Uint32(Uint32(id))),
+            (Sale::columns()[1], Value::Text(Text(category.to_string()))),
+            (Sale::columns()[2], Value::Uint32(Uint32(price))),
+            (Sale::columns()[3], bonus.into()),
+        ])
+        .unwrap();
+        db.insert::<Sale>(req).unwrap();
+    }
+
+    #[test]
+    fn aggregate_multi_column_group_by() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        insert_sale(&db, 1, "books", 10, None);
+        insert_sale(&db, 2, "books", 20, None);
+        insert_sale(&db, 3, "toys", 5, None);
+        insert_sale(&db, 4, "toys", 5, None);
+        insert_sale(&db, 5, "books", 10, None);
+
+        let query = Query::builder()
+            .group_by(&["category", "price"])
+            .order_by_asc("category")
+            .order_by_asc("price")
+            .build();
+        let result = db
+            .aggregate::<Sale>(query, &[AggregateFunction::Count(None)])
+            .unwrap();
+
+        // Groups: (books, 10) x2, (books, 20) x1, (toys, 5) x2
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[0].group_keys,
+            vec![Value::Text(Text("books".into())), Value::Uint32(Uint32(10))]
+        );
+        assert_eq!(result[0].values, vec![AggregatedValue::Count(2)]);
+        assert_eq!(result[1].values, vec![AggregatedValue::Count(1)]);
+        assert_eq!(result[2].values, vec![AggregatedValue::Count(2)]);
+    }
+
+    #[test]
+    fn aggregate_count_column_with_actual_nulls() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        insert_sale(&db, 1, "a", 1, Some(10));
+        insert_sale(&db, 2, "a", 1, None);
+        insert_sale(&db, 3, "a", 1, Some(20));
+
+        let result = db
+            .aggregate::<Sale>(
+                Query::default(),
+                &[
+                    AggregateFunction::Count(None),
+                    AggregateFunction::Count(Some("bonus".into())),
+                ],
+            )
+            .unwrap();
+        let v = &result[0].values;
+        assert_eq!(v[0], AggregatedValue::Count(3));
+        assert_eq!(v[1], AggregatedValue::Count(2));
+    }
+
+    #[test]
+    fn aggregate_sum_avg_skips_nulls_decimal_output() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        // Use the nullable `bonus` column so SUM/AVG must skip nulls.
+        insert_sale(&db, 1, "x", 1, Some(10));
+        insert_sale(&db, 2, "x", 1, None);
+        insert_sale(&db, 3, "x", 1, Some(20));
+
+        let result = db
+            .aggregate::<Sale>(
+                Query::default(),
+                &[
+                    AggregateFunction::Sum("bonus".into()),
+                    AggregateFunction::Avg("bonus".into()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(result[0].values[0], AggregatedValue::Sum(dec("30")));
+        // avg = 30 / 2 = 15
+        assert_eq!(result[0].values[1], AggregatedValue::Avg(dec("15")));
+    }
+
+    #[test]
+    fn aggregate_inside_transaction_sees_writes() {
+        let ctx = setup();
+        let tx_id = ctx.begin_transaction(b"tester".to_vec());
+        let db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+
+        // Inside the transaction, insert rows then aggregate.
+        insert_user(&db, 1, "a");
+        insert_user(&db, 2, "b");
+        insert_post(&db, 10, "p", 1);
+        insert_post(&db, 11, "p", 1);
+        insert_post(&db, 12, "p", 2);
+
+        let result = db
+            .aggregate::<Post>(
+                Query::builder().group_by(&["user_id"]).build(),
+                &[AggregateFunction::Count(None)],
+            )
+            .unwrap();
+        // 2 distinct user_ids in the overlay
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn aggregate_order_by_combines_group_key_and_aggregate() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        insert_user(&db, 1, "a");
+        insert_user(&db, 2, "b");
+        insert_user(&db, 3, "c");
+        insert_post(&db, 10, "p", 1);
+        insert_post(&db, 20, "p", 2);
+        insert_post(&db, 21, "p", 2);
+        insert_post(&db, 30, "p", 3);
+
+        // Order by COUNT desc then user_id asc.
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .order_by_desc("agg0")
+            .order_by_asc("user_id")
+            .build();
+        let result = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .unwrap();
+
+        assert_eq!(result.len(), 3);
+        // user 2 has 2 posts (highest); users 1 and 3 each have 1, tie broken by user_id asc.
+        assert_eq!(result[0].group_keys, vec![Value::Uint32(Uint32(2))]);
+        assert_eq!(result[1].group_keys, vec![Value::Uint32(Uint32(1))]);
+        assert_eq!(result[2].group_keys, vec![Value::Uint32(Uint32(3))]);
+    }
+
+    #[test]
+    fn aggregate_having_json_filter_rejected() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+        let json_filter = JsonFilter::extract_eq("$.foo", Value::Text(Text("bar".into())));
+        let query = Query::builder()
+            .group_by(&["user_id"])
+            .having(Filter::json("user_id", json_filter))
+            .build();
+        let err = db
+            .aggregate::<Post>(query, &[AggregateFunction::Count(None)])
+            .expect_err("JSON in HAVING must be rejected");
+        assert!(err.to_string().contains("JSON"));
+    }
+}
+
+#[test]
+fn select_with_group_by_is_rejected() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    insert_user(&db, 1, "alice");
+
+    let query = Query::builder().group_by(&["id"]).build();
+    let err = db
+        .select::<User>(query)
+        .expect_err("GROUP BY must be rejected on select");
+    assert!(err.to_string().contains("GROUP BY"));
+}
+
+#[test]
+fn select_with_having_is_rejected() {
+    use wasm_dbms_api::prelude::Filter as F;
+
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+    let query = Query::builder()
+        .having(F::gt("agg0", Value::Uint32(Uint32(0))))
+        .build();
+    let err = db
+        .select::<User>(query)
+        .expect_err("HAVING must be rejected on select");
+    assert!(err.to_string().contains("GROUP BY"));
+}
+
+mod migration_e2e {
+    use std::borrow::Cow;
+
+    use wasm_dbms_api::prelude::{
+        ColumnSnapshot, DataTypeSnapshot, Database as _, DbmsError, Encode as _, IndexSnapshot,
+        InsertRecord as _, MigrationError, MigrationOp, MigrationPolicy, Nullable, Query,
+        TableSchema as _, Text, Uint32, Value,
+    };
+    use wasm_dbms_macros::{DatabaseSchema, Table};
+    use wasm_dbms_memory::MemoryAccess;
+    use wasm_dbms_memory::prelude::HeapMemoryProvider;
+
+    use crate::database::migration::snapshots;
+    use crate::prelude::{DbmsContext, WasmDbmsDatabase};
+    use crate::schema::DatabaseSchema;
+
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[table = "users"]
+    pub struct User {
+        #[primary_key]
+        pub id: Uint32,
+        pub name: Text,
+    }
+
+    #[derive(DatabaseSchema)]
+    #[tables(User = "users")]
+    pub struct UserSchema;
+
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[table = "users"]
+    #[migrate]
+    pub struct UserV2 {
+        #[primary_key]
+        pub id: Uint32,
+        pub name: Text,
+        pub email: Text,
+    }
+
+    impl wasm_dbms_api::prelude::Migrate for UserV2 {
+        fn default_value(column: &str) -> Option<Value> {
+            match column {
+                "email" => Some(Value::Text(Text("dynamic@example.com".to_string()))),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(DatabaseSchema)]
+    #[tables(UserV2 = "users")]
+    pub struct UserSchemaV2;
+
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[table = "users"]
+    pub struct UserV3 {
+        #[primary_key]
+        pub id: Uint32,
+        pub name: Text,
+        pub nickname: Nullable<Text>,
+    }
+
+    #[derive(DatabaseSchema)]
+    #[tables(UserV3 = "users")]
+    pub struct UserSchemaV3;
+
+    fn setup() -> DbmsContext<HeapMemoryProvider> {
+        let ctx = DbmsContext::new(HeapMemoryProvider::default());
+        UserSchema::register_tables(&ctx).unwrap();
+        ctx
+    }
+
+    fn tamper_snapshot_to_force_drift(ctx: &DbmsContext<HeapMemoryProvider>) {
+        let snapshot_page = {
+            let sr = ctx.schema_registry.borrow();
+            sr.table_registry_page::<User>()
+                .unwrap()
+                .schema_snapshot_page
+        };
+        let mut tampered = User::schema_snapshot();
+        tampered.indexes.push(IndexSnapshot {
+            columns: vec!["name".to_string()],
+            unique: false,
+        });
+        ctx.mm
+            .borrow_mut()
+            .write_at(snapshot_page, 0, &tampered)
+            .unwrap();
+        let mut schema_registry = ctx.schema_registry.borrow_mut();
+        let mut mm = ctx.mm.borrow_mut();
+        schema_registry.refresh_schema_hash(&mut *mm).unwrap();
+        schema_registry.save(&mut *mm).unwrap();
+        ctx.clear_drift();
+    }
+
+    #[test]
+    fn test_has_drift_returns_false_for_matching_schemas() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        assert!(!db.has_drift().unwrap());
+    }
+
+    #[test]
+    fn test_database_creation_eagerly_caches_drift_flag() {
+        let ctx = setup();
+        let compiled_hash = snapshots::compute_hash(<UserSchema as DatabaseSchema<
+            HeapMemoryProvider,
+        >>::compiled_snapshots());
+        assert_eq!(ctx.cached_drift_for(compiled_hash), None);
+        let _db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        assert_eq!(ctx.cached_drift_for(compiled_hash), Some(false));
+    }
+
+    #[test]
+    fn test_schema_version_is_checked_once_per_table() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        assert!(!ctx.is_schema_version_checked(User::fingerprint()));
+
+        db.select::<User>(Query::builder().build()).unwrap();
+        assert!(ctx.is_schema_version_checked(User::fingerprint()));
+
+        ctx.clear_drift();
+        assert!(!ctx.is_schema_version_checked(User::fingerprint()));
+    }
+
+    #[test]
+    fn test_has_drift_returns_true_after_persisted_snapshot_diverges() {
+        let ctx = setup();
+        tamper_snapshot_to_force_drift(&ctx);
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        assert!(db.has_drift().unwrap());
+    }
+
+    #[test]
+    fn test_pending_migrations_is_empty_when_schemas_match() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        let ops = db.pending_migrations().unwrap();
+        assert!(ops.is_empty());
+    }
+
+    #[test]
+    fn test_pending_migrations_produces_drop_index_for_extra_persisted_index() {
+        let ctx = setup();
+        tamper_snapshot_to_force_drift(&ctx);
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        let ops = db.pending_migrations().unwrap();
+        assert_eq!(ops.len(), 1);
+        assert!(matches!(
+            &ops[0],
+            MigrationOp::DropIndex { table, index }
+                if table == "users" && index.columns == vec!["name".to_string()]
+        ));
+    }
+
+    #[test]
+    fn test_migrate_clears_drift_and_persists_compiled_snapshot() {
+        let ctx = setup();
+        tamper_snapshot_to_force_drift(&ctx);
+        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+
+        assert!(db.has_drift().unwrap());
+        db.migrate(MigrationPolicy::default()).unwrap();
+        assert!(!db.has_drift().unwrap());
+
+        let stored = ctx
+            .schema_registry
+            .borrow()
+            .stored_snapshots(&mut *ctx.mm.borrow_mut())
+            .unwrap();
+        let users = stored.iter().find(|s| s.name == "users").unwrap();
+        assert!(
+            !users
+                .indexes
+                .iter()
+                .any(|i| i.columns == vec!["name".to_string()]),
+            "extra index should be removed by the migration"
+        );
+    }
+
+    #[test]
+    fn test_select_returns_schema_drift_error_while_drift_is_active() {
+        let ctx = setup();
+        tamper_snapshot_to_force_drift(&ctx);
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+
+        let result = db.select::<User>(Query::builder().build());
+        assert!(matches!(
+            result,
+            Err(DbmsError::Migration(MigrationError::SchemaDrift))
+        ));
+    }
+
+    #[test]
+    fn test_insert_returns_schema_drift_error_while_drift_is_active() {
+        let ctx = setup();
+        tamper_snapshot_to_force_drift(&ctx);
+        let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+
+        let insert = UserInsertRequest::from_values(&[
+            (User::columns()[0], Value::Uint32(Uint32(1))),
+            (User::columns()[1], Value::Text(Text("alice".to_string()))),
+        ])
+        .unwrap();
+        let result = db.insert::<User>(insert);
+        assert!(matches!(
+            result,
+            Err(DbmsError::Migration(MigrationError::SchemaDrift))
+        ));
+    }
+
+    #[test]
     fn test_acl_operations_bypass_drift_gate() {
         let ctx = setup();
-        tamper_snapshot_to_force_drift(&ctx);
-        ctx.acl_grant(vec![1, 2, 3], wasm_dbms_api::prelude::PermGrant::Admin)
+        tamper_snapshot_to_force_drift(&ctx);
+        ctx.acl_grant(vec![1, 2, 3], wasm_dbms_api::prelude::PermGrant::Admin)
+            .unwrap();
+        assert!(ctx.granted_admin(&vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_crud_resumes_after_successful_migration() {
+        let ctx = setup();
+
+        {
+            let pre_db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+            let insert = UserInsertRequest::from_values(&[
+                (User::columns()[0], Value::Uint32(Uint32(1))),
+                (User::columns()[1], Value::Text(Text("alice".to_string()))),
+            ])
+            .unwrap();
+            pre_db.insert::<User>(insert).unwrap();
+        }
+
+        tamper_snapshot_to_force_drift(&ctx);
+
+        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        let blocked = db.select::<User>(Query::builder().build());
+        assert!(matches!(
+            blocked,
+            Err(DbmsError::Migration(MigrationError::SchemaDrift))
+        ));
+
+        db.migrate(MigrationPolicy::default()).unwrap();
+
+        let rows = db.select::<User>(Query::builder().build()).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, Some(Uint32(1)));
+        assert_eq!(rows[0].name, Some(Text("alice".to_string())));
+    }
+
+    #[test]
+    fn test_migrate_rejects_destructive_drop_table_under_default_policy() {
+        let ctx = setup();
+        let stale_snapshot = wasm_dbms_api::prelude::TableSchemaSnapshot {
+            version: wasm_dbms_api::prelude::TableSchemaSnapshot::latest_version(),
+            name: "stale_table".to_string(),
+            primary_key: "id".to_string(),
+            alignment: 8,
+            columns: vec![ColumnSnapshot {
+                name: "id".to_string(),
+                data_type: DataTypeSnapshot::Uint32,
+                nullable: false,
+                auto_increment: false,
+                unique: true,
+                primary_key: true,
+                foreign_key: None,
+                default: None,
+            }],
+            indexes: vec![],
+        };
+        ctx.schema_registry
+            .borrow_mut()
+            .register_table_from_snapshot(&stale_snapshot, &mut *ctx.mm.borrow_mut())
+            .unwrap();
+        ctx.clear_drift();
+
+        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        assert!(db.has_drift().unwrap());
+
+        let result = db.migrate(MigrationPolicy::default());
+        assert!(matches!(
+            result,
+            Err(DbmsError::Migration(MigrationError::DestructiveOpDenied { ref op })) if op.contains("DropTable")
+        ));
+
+        let after = db.select::<User>(Query::builder().build());
+        assert!(matches!(
+            after,
+            Err(DbmsError::Migration(MigrationError::SchemaDrift))
+        ));
+    }
+
+    #[test]
+    fn test_migrate_with_destructive_policy_drops_stale_table() {
+        let ctx = setup();
+        let stale_snapshot = wasm_dbms_api::prelude::TableSchemaSnapshot {
+            version: wasm_dbms_api::prelude::TableSchemaSnapshot::latest_version(),
+            name: "stale_table".to_string(),
+            primary_key: "id".to_string(),
+            alignment: 8,
+            columns: vec![ColumnSnapshot {
+                name: "id".to_string(),
+                data_type: DataTypeSnapshot::Uint32,
+                nullable: false,
+                auto_increment: false,
+                unique: true,
+                primary_key: true,
+                foreign_key: None,
+                default: None,
+            }],
+            indexes: vec![],
+        };
+        ctx.schema_registry
+            .borrow_mut()
+            .register_table_from_snapshot(&stale_snapshot, &mut *ctx.mm.borrow_mut())
+            .unwrap();
+        ctx.clear_drift();
+
+        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+        db.migrate(MigrationPolicy {
+            allow_destructive: true,
+        })
+        .unwrap();
+
+        let stored = ctx
+            .schema_registry
+            .borrow()
+            .stored_snapshots(&mut *ctx.mm.borrow_mut())
+            .unwrap();
+        assert!(!stored.iter().any(|s| s.name == "stale_table"));
+        assert!(stored.iter().any(|s| s.name == "users"));
+    }
+
+    #[test]
+    fn test_migrate_accepts_dynamic_default_for_non_nullable_added_column() {
+        let ctx = setup();
+        {
+            let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+            let insert = UserInsertRequest::from_values(&[
+                (User::columns()[0], Value::Uint32(Uint32(1))),
+                (User::columns()[1], Value::Text(Text("alice".to_string()))),
+            ])
+            .unwrap();
+            db.insert::<User>(insert).unwrap();
+        }
+
+        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchemaV2);
+        assert!(db.has_drift().unwrap());
+        db.migrate(MigrationPolicy::default()).unwrap();
+
+        let snapshot = {
+            let mut mm = ctx.mm.borrow_mut();
+            ctx.schema_registry
+                .borrow()
+                .stored_snapshots(&mut *mm)
+                .unwrap()
+                .into_iter()
+                .find(|snapshot| snapshot.name == "users")
+                .unwrap()
+        };
+        let pages = ctx
+            .schema_registry
+            .borrow()
+            .table_registry_page_by_name("users")
+            .unwrap();
+        let raw_body = {
+            let mut mm = ctx.mm.borrow_mut();
+            let registry = wasm_dbms_memory::TableRegistry::load(pages, &mut *mm).unwrap();
+            let mut reader = registry.iter_raw(snapshot.alignment as u16, &mut *mm);
+            let raw = reader.try_next().unwrap().expect("migrated row");
+            raw.bytes
+        };
+        let decoded_snapshot =
+            crate::database::migration::codec::decode_record_by_snapshot(&raw_body, &snapshot)
+                .unwrap();
+        assert_eq!(decoded_snapshot.len(), 3);
+        let decoded_row = UserV2::decode(Cow::Borrowed(&raw_body)).unwrap();
+        assert_eq!(decoded_row.email, Text("dynamic@example.com".to_string()));
+        {
+            let mut mm = ctx.mm.borrow_mut();
+            let registry = wasm_dbms_memory::TableRegistry::load(pages, &mut *mm).unwrap();
+            let mut reader = registry.read::<UserV2, _>(&mut *mm);
+            let first = reader.try_next().unwrap().expect("typed migrated row");
+            assert_eq!(first.record.email, Text("dynamic@example.com".to_string()));
+            let second = reader.try_next();
+            assert!(
+                second.is_ok(),
+                "typed reader should not error after migrated row"
+            );
+            assert!(
+                second.unwrap().is_none(),
+                "typed reader should stop after one row"
+            );
+        }
+        let rows = db.select_raw("users", Query::builder().build()).unwrap();
+        assert_eq!(rows.len(), 1);
+        let email = rows[0]
+            .iter()
+            .find(|(column, _)| column.name == "email")
+            .map(|(_, value)| value)
+            .expect("email column should be present after migration");
+        assert_eq!(email, &Value::Text(Text("dynamic@example.com".to_string())));
+    }
+
+    #[test]
+    fn test_migrate_adds_nullable_column_and_backfills_null() {
+        let ctx = setup();
+        {
+            let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
+            for (id, name) in [(1, "alice"), (2, "bob")] {
+                let insert = UserInsertRequest::from_values(&[
+                    (User::columns()[0], Value::Uint32(Uint32(id))),
+                    (User::columns()[1], Value::Text(Text(name.to_string()))),
+                ])
+                .unwrap();
+                db.insert::<User>(insert).unwrap();
+            }
+        }
+
+        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchemaV3);
+        let pending = db.pending_migrations().unwrap();
+        assert!(matches!(
+            pending.as_slice(),
+            [MigrationOp::AddColumn { table, column }]
+                if table == "users" && column.name == "nickname" && column.nullable
+        ));
+        db.migrate(MigrationPolicy::default()).unwrap();
+        assert!(!db.has_drift().unwrap());
+
+        let rows = db.select::<UserV3>(Query::builder().build()).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(
+            rows.iter()
+                .all(|row| row.nickname == Some(Nullable::Null) && row.name.is_some())
+        );
+
+        let insert = UserV3InsertRequest {
+            id: Uint32(3),
+            name: Text("carol".to_string()),
+            nickname: Nullable::Value(Text("caz".to_string())),
+        };
+        db.insert::<UserV3>(insert).unwrap();
+        let carol = db
+            .select_one::<UserV3>(
+                Query::builder()
+                    .and_where(wasm_dbms_api::prelude::Filter::eq(
+                        "id",
+                        Value::Uint32(Uint32(3)),
+                    ))
+                    .build(),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            carol.nickname,
+            Some(Nullable::Value(Text("caz".to_string())))
+        );
+    }
+}
+
+#[test]
+fn select_join_with_group_by_is_rejected() {
+    use wasm_dbms_api::prelude::Database as _;
+
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+    let query = Query::builder()
+        .inner_join("posts", "id", "user_id")
+        .group_by(&["id"])
+        .build();
+    let err = db
+        .select_join("users", query)
+        .expect_err("GROUP BY must be rejected on select_join");
+    assert!(err.to_string().contains("GROUP BY"));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "bookings"]
+pub struct Booking {
+    #[primary_key]
+    pub id: Uint32,
+    #[sanitizer(ClampSanitizer, min = 0, max = 100)]
+    pub start_day: Int32,
+    #[check = "self.end_day >= self.start_day"]
+    pub end_day: Int32,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Booking = "bookings")]
+pub struct BookingTestSchema;
+
+fn booking(id: u32, start_day: i32, end_day: i32) -> BookingInsertRequest {
+    BookingInsertRequest {
+        id: Uint32(id),
+        start_day: Int32(start_day),
+        end_day: Int32(end_day),
+    }
+}
+
+fn is_end_day_check_violation(result: DbmsResult<impl std::fmt::Debug>) -> bool {
+    matches!(
+        result,
+        Err(DbmsError::Query(QueryError::CheckConstraintViolation { field, message }))
+            if field == "end_day" && message == "self.end_day >= self.start_day"
+    )
+}
+
+#[test]
+fn test_check_constraint_is_evaluated_on_insert_after_sanitization() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    BookingTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BookingTestSchema);
+
+    db.insert::<Booking>(booking(1, 5, 10)).unwrap();
+    assert!(is_end_day_check_violation(
+        db.insert::<Booking>(booking(2, 10, 5))
+    ));
+    // start_day is clamped to 100 before the check runs
+    db.insert::<Booking>(booking(3, 150, 120)).unwrap();
+
+    let ids: Vec<_> = db
+        .select::<Booking>(Query::builder().all().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|booking| booking.id.unwrap())
+        .collect();
+    assert_eq!(ids, vec![Uint32(1), Uint32(3)]);
+}
+
+#[test]
+fn test_check_constraint_is_evaluated_on_update() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    BookingTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BookingTestSchema);
+    db.insert::<Booking>(booking(1, 5, 10)).unwrap();
+
+    let patch = BookingUpdateRequest {
+        end_day: Some(Int32(1)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert!(is_end_day_check_violation(db.update::<Booking>(patch)));
+    let row = db.select_one::<Booking>(Query::default()).unwrap().unwrap();
+    assert_eq!(row.end_day, Some(Int32(10)));
+
+    let patch = BookingUpdateRequest {
+        end_day: Some(Int32(5)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert_eq!(db.update::<Booking>(patch).unwrap(), 1);
+}
+
+#[test]
+fn test_check_constraints_on_record() {
+    let valid = Booking {
+        id: Uint32(1),
+        start_day: Int32(1),
+        end_day: Int32(1),
+    };
+    assert!(valid.check_constraints().is_ok());
+
+    let invalid = Booking {
+        end_day: Int32(0),
+        ..valid
+    };
+    assert!(is_end_day_check_violation(invalid.check_constraints()));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "stock_levels"]
+#[cross_validate = "self.min_qty <= self.max_qty"]
+#[cross_validate = "self.reorder_qty <= self.max_qty"]
+pub struct StockLevel {
+    #[primary_key]
+    pub id: Uint32,
+    pub min_qty: Uint32,
+    pub max_qty: Uint32,
+    pub reorder_qty: Uint32,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(StockLevel = "stock_levels")]
+pub struct StockLevelTestSchema;
+
+fn stock_level(id: u32, min_qty: u32, max_qty: u32, reorder_qty: u32) -> StockLevelInsertRequest {
+    StockLevelInsertRequest {
+        id: Uint32(id),
+        min_qty: Uint32(min_qty),
+        max_qty: Uint32(max_qty),
+        reorder_qty: Uint32(reorder_qty),
+    }
+}
+
+fn violated_invariants(result: DbmsResult<impl std::fmt::Debug>) -> Vec<&'static str> {
+    let Err(DbmsError::Validation(message)) = result else {
+        panic!("expected a validation error, got {result:?}");
+    };
+    [
+        "self.min_qty <= self.max_qty",
+        "self.reorder_qty <= self.max_qty",
+    ]
+    .into_iter()
+    .filter(|invariant| message.contains(invariant))
+    .collect()
+}
+
+#[test]
+fn test_cross_validations_are_evaluated_on_insert() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    StockLevelTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, StockLevelTestSchema);
+
+    db.insert::<StockLevel>(stock_level(1, 1, 10, 5)).unwrap();
+    assert_eq!(
+        violated_invariants(db.insert::<StockLevel>(stock_level(2, 20, 10, 5))),
+        vec!["self.min_qty <= self.max_qty"]
+    );
+    assert_eq!(
+        violated_invariants(db.insert::<StockLevel>(stock_level(3, 20, 10, 50))),
+        vec![
+            "self.min_qty <= self.max_qty",
+            "self.reorder_qty <= self.max_qty"
+        ]
+    );
+
+    let rows = db.select::<StockLevel>(Query::default()).unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
+#[test]
+fn test_cross_validations_are_evaluated_on_update() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    StockLevelTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, StockLevelTestSchema);
+    db.insert::<StockLevel>(stock_level(1, 1, 10, 5)).unwrap();
+
+    let patch = StockLevelUpdateRequest {
+        max_qty: Some(Uint32(0)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert_eq!(
+        violated_invariants(db.update::<StockLevel>(patch)),
+        vec![
+            "self.min_qty <= self.max_qty",
+            "self.reorder_qty <= self.max_qty"
+        ]
+    );
+    let row = db
+        .select_one::<StockLevel>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.max_qty, Some(Uint32(10)));
+
+    let patch = StockLevelUpdateRequest {
+        max_qty: Some(Uint32(5)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert_eq!(db.update::<StockLevel>(patch).unwrap(), 1);
+}
+
+#[test]
+fn test_cross_validate_on_record() {
+    let valid = StockLevel {
+        id: Uint32(1),
+        min_qty: Uint32(1),
+        max_qty: Uint32(10),
+        reorder_qty: Uint32(10),
+    };
+    assert!(valid.cross_validate().is_ok());
+
+    let invalid = StockLevel {
+        reorder_qty: Uint32(11),
+        ..valid
+    };
+    assert_eq!(
+        violated_invariants(invalid.cross_validate()),
+        vec!["self.reorder_qty <= self.max_qty"]
+    );
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "promotions"]
+#[check(name = "valid_period", ge("end_day", column("start_day")))]
+#[check(and(le("discount", column("price")), le("discount", 50)))]
+pub struct Promotion {
+    #[primary_key]
+    pub id: Uint32,
+    pub start_day: Int32,
+    pub end_day: Int32,
+    pub price: Uint32,
+    pub discount: Uint32,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Promotion = "promotions")]
+pub struct PromotionTestSchema;
+
+fn promotion(
+    id: u32,
+    start_day: i32,
+    end_day: i32,
+    price: u32,
+    discount: u32,
+) -> PromotionInsertRequest {
+    PromotionInsertRequest {
+        id: Uint32(id),
+        start_day: Int32(start_day),
+        end_day: Int32(end_day),
+        price: Uint32(price),
+        discount: Uint32(discount),
+    }
+}
+
+fn violated_check(result: DbmsResult<impl std::fmt::Debug>) -> String {
+    match result {
+        Err(DbmsError::Query(QueryError::CheckConstraintViolation { field, .. })) => field,
+        other => panic!("expected a check constraint violation, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_table_checks_are_listed_on_schema() {
+    let checks = Promotion::checks();
+    assert_eq!(checks.len(), 2);
+    assert_eq!(checks[0].name, "valid_period");
+    assert_eq!(
+        checks[0].expression,
+        "ge(\"end_day\", column(\"start_day\"))"
+    );
+    assert_eq!(
+        (checks[0].filter)(),
+        Filter::compare_columns("end_day", ColumnCmp::Ge, "start_day")
+    );
+    assert_eq!(checks[1].name, "promotions_check_2");
+    assert!(Booking::checks().is_empty());
+}
+
+#[test]
+fn test_table_checks_are_evaluated_on_insert() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PromotionTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PromotionTestSchema);
+
+    db.insert::<Promotion>(promotion(1, 5, 10, 100, 20))
+        .unwrap();
+    assert_eq!(
+        violated_check(db.insert::<Promotion>(promotion(2, 10, 5, 100, 20))),
+        "valid_period"
+    );
+    assert_eq!(
+        violated_check(db.insert::<Promotion>(promotion(3, 5, 10, 10, 20))),
+        "promotions_check_2"
+    );
+    assert_eq!(
+        violated_check(db.insert::<Promotion>(promotion(4, 5, 10, 100, 60))),
+        "promotions_check_2"
+    );
+
+    let rows = db.select::<Promotion>(Query::default()).unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
+#[test]
+fn test_table_checks_are_evaluated_on_update() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PromotionTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PromotionTestSchema);
+    db.insert::<Promotion>(promotion(1, 5, 10, 100, 20))
+        .unwrap();
+
+    let patch = PromotionUpdateRequest {
+        price: Some(Uint32(10)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert_eq!(
+        violated_check(db.update::<Promotion>(patch)),
+        "promotions_check_2"
+    );
+    let row = db
+        .select_one::<Promotion>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.price, Some(Uint32(100)));
+
+    let patch = PromotionUpdateRequest {
+        price: Some(Uint32(20)),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert_eq!(db.update::<Promotion>(patch).unwrap(), 1);
+}
+
+#[test]
+fn test_table_checks_are_evaluated_in_transaction() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PromotionTestSchema::register_tables(&ctx).unwrap();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, PromotionTestSchema, tx_id);
+
+    db.insert::<Promotion>(promotion(1, 5, 10, 100, 20))
+        .unwrap();
+    assert_eq!(
+        violated_check(db.insert::<Promotion>(promotion(2, 10, 5, 100, 20))),
+        "valid_period"
+    );
+    db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, PromotionTestSchema);
+    let rows = db.select::<Promotion>(Query::default()).unwrap();
+    assert_eq!(rows.len(), 1);
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "people"]
+pub struct Person {
+    #[primary_key]
+    pub id: Uint32,
+    pub first_name: Text,
+    pub last_name: Text,
+    #[computed = "format!(\"{} {}\", self.first_name, self.last_name)"]
+    pub full_name: Text,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Person = "people")]
+pub struct PersonTestSchema;
+
+fn person(id: u32, first_name: &str, last_name: &str) -> PersonInsertRequest {
+    PersonInsertRequest {
+        id: Uint32(id),
+        first_name: first_name.into(),
+        last_name: last_name.into(),
+    }
+}
+
+#[test]
+fn test_computed_column_is_not_stored() {
+    assert!(Person::columns().iter().all(|col| col.name != "full_name"));
+    assert_eq!(
+        Person::computed_columns(),
+        &[ComputedColumnDef {
+            name: "full_name",
+            depends_on: &["first_name", "last_name"],
+        }]
+    );
+
+    let record = person(1, "Ada", "Lovelace").into_record();
+    assert_eq!(record.full_name, Text::from("Ada Lovelace"));
+    let decoded = Person::decode(record.encode()).unwrap();
+    assert_eq!(decoded, record);
+}
+
+#[test]
+fn test_computed_column_is_returned_by_select() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PersonTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PersonTestSchema);
+
+    let inserted = db
+        .insert_returning::<Person>(person(1, "Ada", "Lovelace"))
+        .unwrap();
+    assert_eq!(inserted.full_name, Some(Text::from("Ada Lovelace")));
+
+    let row = db.select_one::<Person>(Query::default()).unwrap().unwrap();
+    assert_eq!(row.full_name, Some(Text::from("Ada Lovelace")));
+
+    let query = Query::builder().field("full_name").build();
+    let row = db.select_one::<Person>(query).unwrap().unwrap();
+    assert_eq!(row.full_name, Some(Text::from("Ada Lovelace")));
+    assert_eq!(row.id, None);
+
+    let query = Query::builder().field("id").build();
+    let row = db.select_one::<Person>(query).unwrap().unwrap();
+    assert_eq!(row.id, Some(Uint32(1)));
+    assert_eq!(row.full_name, None);
+}
+
+#[test]
+fn test_should_not_filter_on_computed_column() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PersonTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PersonTestSchema);
+    db.insert::<Person>(person(1, "Ada", "Lovelace")).unwrap();
+
+    let query = Query::builder()
+        .filter(Some(Filter::eq(
+            "full_name",
+            Value::Text("Ada Lovelace".into()),
+        )))
+        .build();
+    assert!(matches!(
+        db.select::<Person>(query),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "prices"]
+pub struct Price {
+    #[primary_key]
+    pub id: Uint32,
+    #[decimal(precision = 6, scale = 2)]
+    pub amount: Decimal,
+    #[decimal(precision = 4, scale = 3, round)]
+    pub rate: Nullable<Decimal>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Price = "prices")]
+pub struct PriceTestSchema;
+
+fn dec(s: &str) -> Decimal {
+    Decimal(rust_decimal::Decimal::from_str_exact(s).unwrap())
+}
+
+fn price(id: u32, amount: &str, rate: Option<&str>) -> PriceInsertRequest {
+    PriceInsertRequest {
+        id: Uint32(id),
+        amount: dec(amount),
+        rate: rate.map_or(Nullable::Null, |rate| Nullable::Value(dec(rate))),
+    }
+}
+
+#[test]
+fn test_decimal_precision_and_scale_are_enforced() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PriceTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PriceTestSchema);
+
+    db.insert::<Price>(price(1, "9999.99", None)).unwrap();
+    db.insert::<Price>(price(2, "-12.50", Some("1.5"))).unwrap();
+    for (id, amount) in [(3, "12.345"), (4, "10000")] {
+        assert!(matches!(
+            db.insert::<Price>(price(id, amount, None)),
+            Err(DbmsError::Validation(_))
+        ));
+    }
+    // `round` rounds the extra fractional digits, but the integer digits are still checked
+    db.insert::<Price>(price(5, "1", Some("2.71828"))).unwrap();
+    assert!(matches!(
+        db.insert::<Price>(price(6, "1", Some("12.5"))),
+        Err(DbmsError::Validation(_))
+    ));
+
+    let rate = db
+        .select_one::<Price>(
+            Query::builder()
+                .and_where(Filter::eq("id", Value::Uint32(Uint32(5))))
+                .build(),
+        )
+        .unwrap()
+        .unwrap()
+        .rate;
+    assert_eq!(rate, Some(Nullable::Value(dec("2.718"))));
+
+    let patch = PriceUpdateRequest {
+        amount: Some(dec("0.001")),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert!(matches!(
+        db.update::<Price>(patch),
+        Err(DbmsError::Validation(_))
+    ));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "documents"]
+pub struct Document {
+    #[primary_key]
+    pub id: Uint32,
+    #[compress]
+    pub body: Text,
+    #[compress]
+    pub attachment: Blob,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Document = "documents")]
+pub struct DocumentTestSchema;
+
+#[test]
+fn test_compressed_columns_round_trip() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    DocumentTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, DocumentTestSchema);
+    assert!(Document::columns()[1].compress);
+    assert!(Document::columns()[2].compress);
+    assert!(!Document::columns()[0].compress);
+
+    let body = Text("all work and no play makes jack a dull boy. ".repeat(200));
+    let attachment = Blob(vec![7; 4096]);
+    let document = Document {
+        id: Uint32(1),
+        body: body.clone(),
+        attachment: attachment.clone(),
+    };
+    assert!(document.size() < 4 + body.size() + attachment.size());
+
+    db.insert::<Document>(DocumentInsertRequest {
+        id: Uint32(1),
+        body: body.clone(),
+        attachment: Blob(vec![1, 2, 3]),
+    })
+    .unwrap();
+    let row = db
+        .select_one::<Document>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.body, Some(body));
+    assert_eq!(row.attachment, Some(Blob(vec![1, 2, 3])));
+
+    let patch = DocumentUpdateRequest {
+        attachment: Some(attachment.clone()),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    };
+    assert_eq!(db.update::<Document>(patch).unwrap(), 1);
+    let row = db
+        .select_one::<Document>(Query::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(row.attachment, Some(attachment));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "accounts"]
+pub struct Account {
+    #[primary_key]
+    #[rename = "account_id"]
+    pub id: Uint32,
+    #[index]
+    #[rename = "display_name"]
+    pub name: Text,
+    #[foreign_key(entity = "User", table = "users", column = "id")]
+    #[rename = "owner_id"]
+    pub owner: Uint32,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(User = "users", Account = "accounts")]
+pub struct AccountTestSchema;
+
+#[test]
+fn test_renamed_columns_round_trip() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    AccountTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, AccountTestSchema);
+    let names: Vec<_> = Account::columns().iter().map(|col| col.name).collect();
+    assert_eq!(names, vec!["account_id", "display_name", "owner_id"]);
+    assert_eq!(Account::primary_key(), "account_id");
+
+    insert_user(&db, 1, "alice");
+    let insert = AccountInsertRequest::from_values(&[
+        (Account::columns()[0], Value::Uint32(Uint32(10))),
+        (Account::columns()[1], Value::Text(Text("main".to_string()))),
+        (Account::columns()[2], Value::Uint32(Uint32(1))),
+    ])
+    .unwrap();
+    db.insert::<Account>(insert).unwrap();
+
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::eq(
+            "display_name",
+            Value::Text(Text("main".to_string())),
+        ))
+        .build();
+    let row = db.select_one::<Account>(query).unwrap().unwrap();
+    assert_eq!(row.id, Some(Uint32(10)));
+    assert_eq!(row.name, Some(Text("main".to_string())));
+
+    let patch = AccountUpdateRequest::from_values(
+        &[(
+            Account::columns()[1],
+            Value::Text(Text("savings".to_string())),
+        )],
+        Some(Filter::eq("account_id", Value::Uint32(Uint32(10)))),
+    );
+    assert_eq!(db.update::<Account>(patch).unwrap(), 1);
+    let query = Query::builder()
+        .all()
+        .and_where(Filter::eq("owner_id", Value::Uint32(Uint32(1))))
+        .build();
+    let row = db.select_one::<Account>(query).unwrap().unwrap();
+    assert_eq!(row.name, Some(Text("savings".to_string())));
+}
+
+#[test]
+fn test_table_stats_reports_every_table() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=3 {
+        insert_user(&db, id, "alice");
+    }
+    db.delete::<User>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    )
+    .unwrap();
+
+    let stats = ctx.table_stats().unwrap();
+    let tables: Vec<_> = stats.iter().map(|stats| stats.table.as_str()).collect();
+    assert_eq!(tables, vec!["contracts", "posts", "sales", "users"]);
+
+    let users = &stats[3];
+    assert_eq!(users.rows, 2);
+    assert_eq!(users.pages, 1);
+    assert!(users.used_bytes > 0);
+    assert!(users.fragmented_bytes > 0);
+
+    let posts = &stats[1];
+    assert_eq!(posts.rows, 0);
+    assert_eq!(posts.used_bytes, 0);
+}
+
+#[test]
+fn test_table_stats_of_single_table() {
+    let ctx = setup();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+    for id in 1..=4 {
+        insert_user(&db, id, "alice");
+    }
+
+    let users = db.table_stats::<User>().unwrap();
+    assert_eq!(users.table, "users");
+    assert_eq!(users.rows, 4);
+    assert_eq!(users.average_row_size(), users.used_bytes / 4);
+    assert_eq!(
+        users.allocated_bytes(),
+        users.pages * HeapMemoryProvider::PAGE_SIZE
+    );
+    assert_eq!(db.table_stats::<Post>().unwrap().average_row_size(), 0);
+
+    let err = db.table_stats::<Booking>().unwrap_err();
+    assert!(matches!(
+        err,
+        DbmsError::Table(wasm_dbms_api::prelude::TableError::TableNotFound)
+    ));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "notes"]
+#[soft_delete]
+pub struct Note {
+    #[primary_key]
+    pub id: Uint32,
+    pub title: Text,
+    #[deleted_at]
+    pub deleted_at: Nullable<Timestamp>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Note = "notes")]
+pub struct NoteTestSchema;
+
+fn setup_notes() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ctx.set_clock(|| 1_700_000_000_000_000_000);
+    NoteTestSchema::register_tables(&ctx).unwrap();
+    {
+        let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+        for id in 1..=3 {
+            db.insert::<Note>(NoteInsertRequest {
+                id: Uint32(id),
+                title: Text(format!("note {id}")),
+                deleted_at: Nullable::Null,
+            })
             .unwrap();
-        assert!(ctx.granted_admin(&vec![1, 2, 3]));
+        }
     }
+    ctx
+}
 
-    #[test]
-    fn test_crud_resumes_after_successful_migration() {
-        let ctx = setup();
+#[test]
+fn test_soft_delete_marks_rows_instead_of_removing_them() {
+    let ctx = setup_notes();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+    assert_eq!(Note::soft_delete_column(), Some("deleted_at"));
 
-        {
-            let pre_db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-            let insert = UserInsertRequest::from_values(&[
-                (User::columns()[0], Value::Uint32(Uint32(1))),
-                (User::columns()[1], Value::Text(Text("alice".to_string()))),
-            ])
+    let deleted = db
+        .delete::<Note>(
+            DeleteBehavior::Restrict,
+            Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+        )
+        .unwrap();
+    assert_eq!(deleted, 1);
+
+    let ids: Vec<_> = db
+        .select::<Note>(Query::builder().order_by_asc("id").build())
+        .unwrap()
+        .into_iter()
+        .map(|note| note.id.unwrap())
+        .collect();
+    assert_eq!(ids, vec![Uint32(1), Uint32(3)]);
+    assert_eq!(db.count::<Note>(None).unwrap(), 2);
+    assert!(
+        !db.exists::<Note>(Filter::eq("id", Value::Uint32(Uint32(2))))
+            .unwrap()
+    );
+
+    let deleted = db
+        .select_one::<Note>(
+            Query::builder()
+                .include_deleted()
+                .and_where(Filter::eq("id", Value::Uint32(Uint32(2))))
+                .build(),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        deleted.deleted_at,
+        Some(Nullable::Value(Timestamp(1_700_000_000_000_000_000)))
+    );
+}
+
+#[test]
+fn test_soft_deleted_rows_are_hidden_from_raw_join_and_iter_selects() {
+    let ctx = setup_notes();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+    db.delete::<Note>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    )
+    .unwrap();
+    let live = || Query::builder().all().order_by_asc("id").build();
+    let all = || {
+        Query::builder()
+            .all()
+            .include_deleted()
+            .order_by_asc("id")
+            .build()
+    };
+
+    assert_eq!(db.select_raw("notes", live()).unwrap().len(), 2);
+    assert_eq!(db.select_raw("notes", all()).unwrap().len(), 3);
+    assert_eq!(db.select_join("notes", live()).unwrap().len(), 2);
+    assert_eq!(db.select_join("notes", all()).unwrap().len(), 3);
+
+    let iter_ids = |query: Query| {
+        db.select_iter::<Note>(query)
+            .unwrap()
+            .map(|note| note.map(|note| note.id.unwrap().0))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    assert_eq!(iter_ids(Query::builder().build()), [1, 3]);
+    assert_eq!(
+        iter_ids(Query::builder().include_deleted().build()),
+        [1, 2, 3]
+    );
+}
+
+#[test]
+fn test_soft_delete_skips_rows_already_deleted() {
+    let ctx = setup_notes();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+    let filter = Filter::eq("id", Value::Uint32(Uint32(1)));
+    assert_eq!(
+        db.delete::<Note>(DeleteBehavior::Restrict, Some(filter.clone()))
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        db.delete::<Note>(DeleteBehavior::Restrict, Some(filter))
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        db.delete::<Note>(DeleteBehavior::Restrict, None).unwrap(),
+        2
+    );
+    assert_eq!(db.count::<Note>(None).unwrap(), 0);
+}
+
+#[test]
+fn test_force_delete_removes_soft_deleted_rows() {
+    let ctx = setup_notes();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+    db.delete::<Note>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    )
+    .unwrap();
+
+    let deleted = db
+        .force_delete::<Note>(
+            DeleteBehavior::Restrict,
+            Some(Filter::lt("id", Value::Uint32(Uint32(3)))),
+        )
+        .unwrap();
+    assert_eq!(deleted, 2);
+
+    let rows = db
+        .select::<Note>(Query::builder().include_deleted().build())
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].id, Some(Uint32(3)));
+}
+
+#[test]
+fn test_soft_deleted_row_keeps_its_primary_key() {
+    let ctx = setup_notes();
+    let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+    db.delete::<Note>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    )
+    .unwrap();
+
+    let result = db.insert::<Note>(NoteInsertRequest {
+        id: Uint32(1),
+        title: Text("again".to_string()),
+        deleted_at: Nullable::Null,
+    });
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+}
+
+#[test]
+fn test_soft_delete_inside_transaction() {
+    let ctx = setup_notes();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, NoteTestSchema, tx_id);
+    db.delete::<Note>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(3)))),
+    )
+    .unwrap();
+    db.force_delete::<Note>(
+        DeleteBehavior::Restrict,
+        Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+    )
+    .unwrap();
+    assert_eq!(db.count::<Note>(None).unwrap(), 1);
+    db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, NoteTestSchema);
+    assert_eq!(db.count::<Note>(None).unwrap(), 1);
+    let rows = db
+        .select::<Note>(
+            Query::builder()
+                .include_deleted()
+                .order_by_asc("id")
+                .build(),
+        )
+        .unwrap();
+    let ids: Vec<_> = rows.iter().map(|note| note.id.unwrap()).collect();
+    assert_eq!(ids, vec![Uint32(1), Uint32(3)]);
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "bookmarks"]
+pub struct Bookmark {
+    #[primary_key]
+    pub id: Uint32,
+    pub tags: ArrayOf<Text>,
+    pub ratings: Nullable<ArrayOf<Uint32>>,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Bookmark = "bookmarks")]
+pub struct BookmarkTestSchema;
+
+fn tags(tags: &[&str]) -> ArrayOf<Text> {
+    ArrayOf(tags.iter().map(|tag| Text(tag.to_string())).collect())
+}
+
+fn setup_bookmarks() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    BookmarkTestSchema::register_tables(&ctx).unwrap();
+    {
+        let db = WasmDbmsDatabase::oneshot(&ctx, BookmarkTestSchema);
+        for (id, bookmark_tags, ratings) in [
+            (
+                1,
+                tags(&["rust", "wasm"]),
+                Nullable::Value(ArrayOf(vec![Uint32(5)])),
+            ),
+            (2, tags(&["rust", "ic", "wasm"]), Nullable::Null),
+            (3, tags(&[]), Nullable::Value(ArrayOf::default())),
+        ] {
+            db.insert::<Bookmark>(BookmarkInsertRequest {
+                id: Uint32(id),
+                tags: bookmark_tags,
+                ratings,
+            })
             .unwrap();
-            pre_db.insert::<User>(insert).unwrap();
         }
+    }
+    ctx
+}
+
+fn bookmark_ids(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>, filter: Filter) -> Vec<Uint32> {
+    db.select::<Bookmark>(
+        Query::builder()
+            .and_where(filter)
+            .order_by_asc("id")
+            .build(),
+    )
+    .unwrap()
+    .into_iter()
+    .map(|bookmark| bookmark.id.unwrap())
+    .collect()
+}
+
+#[test]
+fn test_array_columns_round_trip() {
+    let ctx = setup_bookmarks();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BookmarkTestSchema);
+    assert_eq!(
+        Bookmark::columns()[1].data_type,
+        DataTypeKind::Array(&DataTypeKind::Text)
+    );
+
+    let rows = db
+        .select::<Bookmark>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].tags, Some(tags(&["rust", "wasm"])));
+    assert_eq!(
+        rows[0].ratings,
+        Some(Nullable::Value(ArrayOf(vec![Uint32(5)])))
+    );
+    assert_eq!(rows[1].ratings, Some(Nullable::Null));
+    assert_eq!(rows[2].tags, Some(ArrayOf::default()));
+}
+
+#[test]
+fn test_should_filter_array_columns_with_any_of_and_all_of() {
+    let ctx = setup_bookmarks();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BookmarkTestSchema);
+
+    assert_eq!(
+        bookmark_ids(&db, Filter::any_of("tags", Value::Text("ic".into()))),
+        vec![Uint32(2)]
+    );
+    assert_eq!(
+        bookmark_ids(&db, Filter::any_of("tags", Value::Text("rust".into()))),
+        vec![Uint32(1), Uint32(2)]
+    );
+    assert_eq!(
+        bookmark_ids(
+            &db,
+            Filter::all_of(
+                "tags",
+                vec![Value::Text("wasm".into()), Value::Text("ic".into())]
+            )
+        ),
+        vec![Uint32(2)]
+    );
+    assert_eq!(
+        bookmark_ids(&db, Filter::any_of("ratings", Value::Uint32(Uint32(5)))),
+        vec![Uint32(1)]
+    );
+}
+
+#[test]
+fn test_should_update_array_column() {
+    let ctx = setup_bookmarks();
+    let db = WasmDbmsDatabase::oneshot(&ctx, BookmarkTestSchema);
+    let updated = db
+        .update::<Bookmark>(BookmarkUpdateRequest::from_values(
+            &[(
+                Bookmark::columns()[1],
+                Value::from(tags(&["rust", "ic", "wasm", "dbms"])),
+            )],
+            Some(Filter::eq("id", Value::Uint32(Uint32(3)))),
+        ))
+        .unwrap();
+    assert_eq!(updated, 1);
+
+    assert_eq!(
+        bookmark_ids(&db, Filter::any_of("tags", Value::Text("dbms".into()))),
+        vec![Uint32(3)]
+    );
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "issues"]
+pub struct Issue {
+    #[primary_key]
+    pub id: Uint32,
+    pub title: Text,
+    #[auto_timestamp(on = "insert")]
+    pub created_at: DateTime,
+    #[auto_timestamp(on = "update")]
+    pub updated_at: DateTime,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Issue = "issues")]
+pub struct IssueTestSchema;
+
+thread_local! {
+    static ISSUE_CLOCK: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Sets the time returned by the clock of [`setup_issues`], in seconds.
+fn set_issue_clock(seconds: u64) {
+    ISSUE_CLOCK.with(|clock| clock.set(seconds * 1_000_000_000));
+}
+
+fn setup_issues() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ctx.set_clock(|| ISSUE_CLOCK.with(Cell::get));
+    IssueTestSchema::register_tables(&ctx).unwrap();
+    ctx
+}
+
+fn issue_insert(id: u32) -> IssueInsertRequest {
+    IssueInsertRequest {
+        id: Uint32(id),
+        title: Text(format!("issue {id}")),
+    }
+}
+
+fn select_issue(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>, id: u32) -> IssueRecord {
+    db.select_one::<Issue>(
+        Query::builder()
+            .and_where(Filter::eq("id", Value::Uint32(Uint32(id))))
+            .build(),
+    )
+    .unwrap()
+    .unwrap()
+}
+
+#[test]
+fn test_auto_timestamps_are_stamped_on_insert_and_update() {
+    assert_eq!(
+        Issue::auto_timestamps(),
+        &[
+            ("created_at", AutoTimestamp::Insert),
+            ("updated_at", AutoTimestamp::Update)
+        ]
+    );
+    let ctx = setup_issues();
+    let db = WasmDbmsDatabase::oneshot(&ctx, IssueTestSchema);
+    let inserted_at = DateTime::from(Timestamp(1_000_000_000_000));
+
+    set_issue_clock(1_000);
+    let returned = db.insert_returning::<Issue>(issue_insert(1)).unwrap();
+    assert_eq!(returned.created_at, Some(inserted_at));
+    assert_eq!(returned.updated_at, Some(inserted_at));
+
+    set_issue_clock(2_000);
+    db.update::<Issue>(IssueUpdateRequest {
+        title: Some(Text("renamed".to_string())),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let issue = select_issue(&db, 1);
+    assert_eq!(issue.title, Some(Text("renamed".to_string())));
+    assert_eq!(issue.created_at, Some(inserted_at));
+    assert_eq!(
+        issue.updated_at,
+        Some(DateTime::from(Timestamp(2_000_000_000_000)))
+    );
+}
+
+#[test]
+fn test_auto_timestamps_are_not_writable_through_patches() {
+    let ctx = setup_issues();
+    let db = WasmDbmsDatabase::oneshot(&ctx, IssueTestSchema);
+    set_issue_clock(1_000);
+    db.insert::<Issue>(issue_insert(1)).unwrap();
+
+    set_issue_clock(3_000);
+    let patch = IssueUpdateRequest::from_values(
+        &[(
+            Issue::columns()[2],
+            Value::DateTime(DateTime::from(Timestamp(0))),
+        )],
+        Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+    );
+    assert!(patch.update_values().is_empty());
+    db.update::<Issue>(patch).unwrap();
+
+    let issue = select_issue(&db, 1);
+    assert_eq!(
+        issue.created_at,
+        Some(DateTime::from(Timestamp(1_000_000_000_000)))
+    );
+    assert_eq!(
+        issue.updated_at,
+        Some(DateTime::from(Timestamp(3_000_000_000_000)))
+    );
+}
+
+#[test]
+fn test_auto_timestamps_are_taken_at_commit_time() {
+    let ctx = setup_issues();
+    set_issue_clock(1_000);
+    WasmDbmsDatabase::oneshot(&ctx, IssueTestSchema)
+        .insert::<Issue>(issue_insert(1))
+        .unwrap();
+
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, IssueTestSchema, tx_id);
+    set_issue_clock(2_000);
+    db.insert::<Issue>(issue_insert(2)).unwrap();
+    db.update::<Issue>(IssueUpdateRequest {
+        title: Some(Text("renamed".to_string())),
+        where_clause: Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ..Default::default()
+    })
+    .unwrap();
+
+    set_issue_clock(5_000);
+    db.commit().unwrap();
+
+    let committed_at = Some(DateTime::from(Timestamp(5_000_000_000_000)));
+    let db = WasmDbmsDatabase::oneshot(&ctx, IssueTestSchema);
+    let updated = select_issue(&db, 1);
+    assert_eq!(
+        updated.created_at,
+        Some(DateTime::from(Timestamp(1_000_000_000_000)))
+    );
+    assert_eq!(updated.updated_at, committed_at);
+    let inserted = select_issue(&db, 2);
+    assert_eq!(inserted.created_at, committed_at);
+    assert_eq!(inserted.updated_at, committed_at);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode)]
+pub enum TaskStatus {
+    Todo,
+    Doing,
+    Done = 10,
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "tasks"]
+pub struct Task {
+    #[primary_key]
+    pub id: Uint32,
+    #[unit_enum]
+    #[default(TaskStatus::Todo)]
+    pub status: TaskStatus,
+    #[unit_enum]
+    pub blocked_on: TaskStatus,
+}
+
+#[derive(DatabaseSchema)]
+#[tables(Task = "tasks")]
+pub struct TaskTestSchema;
+
+#[test]
+fn test_unit_enum_columns_round_trip() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    TaskTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TaskTestSchema);
+    assert_eq!(Task::columns()[1].data_type, DataTypeKind::Uint8);
+
+    db.insert::<Task>(TaskInsertRequest {
+        id: Uint32(1),
+        status: Some(TaskStatus::Done),
+        blocked_on: TaskStatus::Doing,
+    })
+    .unwrap();
+    db.insert::<Task>(TaskInsertRequest {
+        id: Uint32(2),
+        status: None,
+        blocked_on: TaskStatus::Done,
+    })
+    .unwrap();
+
+    let rows = db
+        .select::<Task>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+    assert_eq!(rows[0].status, Some(TaskStatus::Done));
+    assert_eq!(rows[0].blocked_on, Some(TaskStatus::Doing));
+    assert_eq!(rows[1].status, Some(TaskStatus::Todo));
+    assert_eq!(rows[1].blocked_on, Some(TaskStatus::Done));
+
+    let done = db
+        .select::<Task>(
+            Query::builder()
+                .and_where(Filter::eq("status", Value::from(TaskStatus::Done as u8)))
+                .build(),
+        )
+        .unwrap();
+    assert_eq!(done.len(), 1);
+    assert_eq!(done[0].id, Some(Uint32(1)));
+}
+
+#[test]
+fn test_should_update_unit_enum_column() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    TaskTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, TaskTestSchema);
+    db.insert::<Task>(TaskInsertRequest {
+        id: Uint32(1),
+        status: None,
+        blocked_on: TaskStatus::Done,
+    })
+    .unwrap();
+
+    let updated = db
+        .update::<Task>(TaskUpdateRequest::from_values(
+            &[(Task::columns()[1], unit_enum_to_value(&TaskStatus::Doing))],
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ))
+        .unwrap();
+    assert_eq!(updated, 1);
+
+    let task = db
+        .select_one::<Task>(Query::builder().build())
+        .unwrap()
+        .unwrap();
+    assert_eq!(task.status, Some(TaskStatus::Doing));
+}
+
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "preferences"]
+pub struct Preference {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    pub data: Json,
+}
 
-        tamper_snapshot_to_force_drift(&ctx);
+#[derive(DatabaseSchema)]
+#[tables(Preference = "preferences")]
+pub struct PreferenceTestSchema;
 
-        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        let blocked = db.select::<User>(Query::builder().build());
-        assert!(matches!(
-            blocked,
-            Err(DbmsError::Migration(MigrationError::SchemaDrift))
-        ));
+fn setup_preferences() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    PreferenceTestSchema::register_tables(&ctx).unwrap();
+    {
+        let db = WasmDbmsDatabase::oneshot(&ctx, PreferenceTestSchema);
+        for (id, name) in [(1, "alice"), (2, "bob")] {
+            db.insert::<Preference>(PreferenceInsertRequest {
+                id: Uint32(id),
+                name: Text(name.to_string()),
+                data: Json::from_str(r#"{"tags": ["a"], "address": {"city": "Rome"}}"#).unwrap(),
+            })
+            .unwrap();
+        }
+    }
+    ctx
+}
 
-        db.migrate(MigrationPolicy::default()).unwrap();
+fn preference_data(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>, id: u32) -> Json {
+    db.select_one::<Preference>(
+        Query::builder()
+            .and_where(Filter::eq("id", Value::Uint32(Uint32(id))))
+            .build(),
+    )
+    .unwrap()
+    .unwrap()
+    .data
+    .unwrap()
+}
 
-        let rows = db.select::<User>(Query::builder().build()).unwrap();
-        assert_eq!(rows.len(), 1);
-        assert_eq!(rows[0].id, Some(Uint32(1)));
-        assert_eq!(rows[0].name, Some(Text("alice".to_string())));
-    }
+fn json_patch_update(
+    db: &WasmDbmsDatabase<'_, HeapMemoryProvider>,
+    id: u32,
+    ops: Vec<JsonPatchOp>,
+) -> DbmsResult<u64> {
+    PreferenceTestSchema.update(
+        db,
+        Preference::table_name(),
+        &[(Preference::columns()[2], Value::JsonPatch(ops))],
+        Some(Filter::eq("id", Value::Uint32(Uint32(id)))),
+    )
+}
 
-    #[test]
-    fn test_migrate_rejects_destructive_drop_table_under_default_policy() {
-        let ctx = setup();
-        let stale_snapshot = wasm_dbms_api::prelude::TableSchemaSnapshot {
-            version: wasm_dbms_api::prelude::TableSchemaSnapshot::latest_version(),
-            name: "stale_table".to_string(),
-            primary_key: "id".to_string(),
-            alignment: 8,
-            columns: vec![ColumnSnapshot {
-                name: "id".to_string(),
-                data_type: DataTypeSnapshot::Uint32,
-                nullable: false,
-                auto_increment: false,
-                unique: true,
-                primary_key: true,
-                foreign_key: None,
-                default: None,
-            }],
-            indexes: vec![],
-        };
-        ctx.schema_registry
-            .borrow_mut()
-            .register_table_from_snapshot(&stale_snapshot, &mut *ctx.mm.borrow_mut())
-            .unwrap();
-        ctx.clear_drift();
+#[test]
+fn test_update_applies_json_patch_to_matching_rows() {
+    let ctx = setup_preferences();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PreferenceTestSchema);
 
-        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        assert!(db.has_drift().unwrap());
+    let updated = json_patch_update(
+        &db,
+        1,
+        vec![
+            JsonPatchOp::set("address.city", "Paris"),
+            JsonPatchOp::remove("address.zip"),
+            JsonPatchOp::append("tags", "b"),
+        ],
+    )
+    .unwrap();
+    assert_eq!(updated, 1);
 
-        let result = db.migrate(MigrationPolicy::default());
-        assert!(matches!(
-            result,
-            Err(DbmsError::Migration(MigrationError::DestructiveOpDenied { ref op })) if op.contains("DropTable")
-        ));
+    assert_eq!(
+        preference_data(&db, 1),
+        Json::from_str(r#"{"tags": ["a", "b"], "address": {"city": "Paris"}}"#).unwrap()
+    );
+    assert_eq!(
+        preference_data(&db, 2),
+        Json::from_str(r#"{"tags": ["a"], "address": {"city": "Rome"}}"#).unwrap()
+    );
+}
 
-        let after = db.select::<User>(Query::builder().build());
-        assert!(matches!(
-            after,
-            Err(DbmsError::Migration(MigrationError::SchemaDrift))
-        ));
+#[test]
+fn test_update_rejects_invalid_json_patch() {
+    let ctx = setup_preferences();
+    let db = WasmDbmsDatabase::oneshot(&ctx, PreferenceTestSchema);
+    let original = preference_data(&db, 1);
+
+    for ops in [
+        vec![JsonPatchOp::append("address", "b")],
+        vec![JsonPatchOp::set("tags[*]", "b")],
+        vec![
+            JsonPatchOp::set("address.city", "Paris"),
+            JsonPatchOp::set("missing.key", 1i32),
+        ],
+    ] {
+        let err = json_patch_update(&db, 1, ops).unwrap_err();
+        assert!(
+            matches!(err, DbmsError::Query(QueryError::InvalidQuery(_))),
+            "unexpected error: {err:?}"
+        );
     }
+    assert_eq!(preference_data(&db, 1), original);
+
+    // only Json columns can be patched
+    let err = PreferenceTestSchema
+        .update(
+            &db,
+            Preference::table_name(),
+            &[(
+                Preference::columns()[1],
+                Value::JsonPatch(vec![JsonPatchOp::remove("name")]),
+            )],
+            None,
+        )
+        .unwrap_err();
+    assert!(matches!(err, DbmsError::Query(QueryError::InvalidQuery(_))));
+}
 
-    #[test]
-    fn test_migrate_with_destructive_policy_drops_stale_table() {
-        let ctx = setup();
-        let stale_snapshot = wasm_dbms_api::prelude::TableSchemaSnapshot {
-            version: wasm_dbms_api::prelude::TableSchemaSnapshot::latest_version(),
-            name: "stale_table".to_string(),
-            primary_key: "id".to_string(),
-            alignment: 8,
-            columns: vec![ColumnSnapshot {
-                name: "id".to_string(),
-                data_type: DataTypeSnapshot::Uint32,
-                nullable: false,
-                auto_increment: false,
-                unique: true,
-                primary_key: true,
-                foreign_key: None,
-                default: None,
-            }],
-            indexes: vec![],
-        };
-        ctx.schema_registry
-            .borrow_mut()
-            .register_table_from_snapshot(&stale_snapshot, &mut *ctx.mm.borrow_mut())
-            .unwrap();
-        ctx.clear_drift();
+#[test]
+fn test_json_patch_in_transaction_is_applied_at_commit() {
+    let ctx = setup_preferences();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut db = WasmDbmsDatabase::from_transaction(&ctx, PreferenceTestSchema, tx_id);
+
+    json_patch_update(&db, 1, vec![JsonPatchOp::append("tags", "b")]).unwrap();
+    json_patch_update(&db, 1, vec![JsonPatchOp::append("tags", "c")]).unwrap();
+    let expected =
+        Json::from_str(r#"{"tags": ["a", "b", "c"], "address": {"city": "Rome"}}"#).unwrap();
+    assert_eq!(preference_data(&db, 1), expected);
+    let oneshot = WasmDbmsDatabase::oneshot(&ctx, PreferenceTestSchema);
+    assert_eq!(
+        preference_data(&oneshot, 1),
+        Json::from_str(r#"{"tags": ["a"], "address": {"city": "Rome"}}"#).unwrap()
+    );
 
-        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-        db.migrate(MigrationPolicy {
-            allow_destructive: true,
+    db.commit().unwrap();
+    assert_eq!(preference_data(&oneshot, 1), expected);
+}
+
+#[test]
+fn test_vacuum_deletes_rows_past_retention() {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    ctx.set_clock(|| 10_000);
+    EventTestSchema::register_tables(&ctx).unwrap();
+    let db = WasmDbmsDatabase::oneshot(&ctx, EventTestSchema);
+    for (id, at) in [(1, 3_000), (2, 8_000), (3, 6_000), (4, 9_500)] {
+        db.insert::<Event>(EventInsertRequest {
+            id: Uint32(id),
+            at: Timestamp(at),
+            ended_at: Nullable::Null,
         })
         .unwrap();
-
-        let stored = ctx
-            .schema_registry
-            .borrow()
-            .stored_snapshots(&mut *ctx.mm.borrow_mut())
-            .unwrap();
-        assert!(!stored.iter().any(|s| s.name == "stale_table"));
-        assert!(stored.iter().any(|s| s.name == "users"));
     }
 
-    #[test]
-    fn test_migrate_accepts_dynamic_default_for_non_nullable_added_column() {
-        let ctx = setup();
-        {
-            let db = WasmDbmsDatabase::oneshot(&ctx, UserSchema);
-            let insert = UserInsertRequest::from_values(&[
-                (User::columns()[0], Value::Uint32(Uint32(1))),
-                (User::columns()[1], Value::Text(Text("alice".to_string()))),
-            ])
-            .unwrap();
-            db.insert::<User>(insert).unwrap();
+    // no policy, nothing to vacuum
+    assert_eq!(db.vacuum().unwrap(), VacuumReport::default());
+
+    ctx.set_retention_policy(RetentionPolicy {
+        table: "events",
+        column: "at",
+        max_age_nanos: 3_000,
+    });
+    let report = db.vacuum().unwrap();
+    assert_eq!(
+        report,
+        VacuumReport {
+            tables_vacuumed: 1,
+            rows_deleted: 2,
         }
+    );
+    let rows = db
+        .select::<Event>(Query::builder().order_by_asc("id").build())
+        .unwrap();
+    let ids: Vec<_> = rows.into_iter().map(|row| row.id.unwrap()).collect();
+    assert_eq!(ids, vec![Uint32(2), Uint32(4)]);
+
+    // a null column never expires, a non-timestamp column is rejected
+    ctx.set_retention_policy(RetentionPolicy {
+        table: "events",
+        column: "ended_at",
+        max_age_nanos: 0,
+    });
+    assert_eq!(db.vacuum().unwrap().rows_deleted, 0);
+    ctx.set_retention_policy(RetentionPolicy {
+        table: "events",
+        column: "id",
+        max_age_nanos: 0,
+    });
+    assert!(matches!(
+        db.vacuum(),
+        Err(DbmsError::Query(QueryError::InvalidQuery(_)))
+    ));
+}
 
-        let mut db = WasmDbmsDatabase::oneshot(&ctx, UserSchemaV2);
-        assert!(db.has_drift().unwrap());
-        db.migrate(MigrationPolicy::default()).unwrap();
+#[derive(Debug, Table, Clone, PartialEq, Eq)]
+#[table = "counters"]
+#[on_conflict = "update"]
+pub struct Counter {
+    #[primary_key]
+    pub id: Uint32,
+    pub name: Text,
+    #[default = 0]
+    pub hits: Uint32,
+}
 
-        let snapshot = {
-            let mut mm = ctx.mm.borrow_mut();
-            ctx.schema_registry
-                .borrow()
-                .stored_snapshots(&mut *mm)
-                .unwrap()
-                .into_iter()
-                .find(|snapshot| snapshot.name == "users")
-                .unwrap()
-        };
-        let pages = ctx
-            .schema_registry
-            .borrow()
-            .table_registry_page_by_name("users")
-            .unwrap();
-        let raw_body = {
-            let mut mm = ctx.mm.borrow_mut();
-            let registry = wasm_dbms_memory::TableRegistry::load(pages, &mut *mm).unwrap();
-            let mut reader = registry.iter_raw(snapshot.alignment as u16, &mut *mm);
-            let raw = reader.try_next().unwrap().expect("migrated row");
-            raw.bytes
-        };
-        let decoded_snapshot =
-            crate::database::migration::codec::decode_record_by_snapshot(&raw_body, &snapshot)
-                .unwrap();
-        assert_eq!(decoded_snapshot.len(), 3);
-        let decoded_row = UserV2::decode(Cow::Borrowed(&raw_body)).unwrap();
-        assert_eq!(decoded_row.email, Text("dynamic@example.com".to_string()));
-        {
-            let mut mm = ctx.mm.borrow_mut();
-            let registry = wasm_dbms_memory::TableRegistry::load(pages, &mut *mm).unwrap();
-            let mut reader = registry.read::<UserV2, _>(&mut *mm);
-            let first = reader.try_next().unwrap().expect("typed migrated row");
-            assert_eq!(first.record.email, Text("dynamic@example.com".to_string()));
-            let second = reader.try_next();
-            assert!(
-                second.is_ok(),
-                "typed reader should not error after migrated row"
-            );
-            assert!(
-                second.unwrap().is_none(),
-                "typed reader should stop after one row"
-            );
-        }
-        let rows = db.select_raw("users", Query::builder().build()).unwrap();
-        assert_eq!(rows.len(), 1);
-        let email = rows[0]
-            .iter()
-            .find(|(column, _)| column.name == "email")
-            .map(|(_, value)| value)
-            .expect("email column should be present after migration");
-        assert_eq!(email, &Value::Text(Text("dynamic@example.com".to_string())));
+#[derive(DatabaseSchema)]
+#[tables(Counter = "counters")]
+pub struct CounterTestSchema;
+
+fn setup_counters() -> DbmsContext<HeapMemoryProvider> {
+    let ctx = DbmsContext::new(HeapMemoryProvider::default());
+    CounterTestSchema::register_tables(&ctx).unwrap();
+    {
+        let db = WasmDbmsDatabase::oneshot(&ctx, CounterTestSchema);
+        db.insert::<Counter>(CounterInsertRequest {
+            id: Uint32(1),
+            name: Text("visits".to_string()),
+            hits: Some(Uint32(5)),
+        })
+        .unwrap();
     }
+    ctx
+}
+
+fn counter(db: &WasmDbmsDatabase<'_, HeapMemoryProvider>, id: u32) -> CounterRecord {
+    db.select_one::<Counter>(
+        Query::builder()
+            .and_where(Filter::eq("id", Value::Uint32(Uint32(id))))
+            .build(),
+    )
+    .unwrap()
+    .expect("counter not found")
 }
 
 #[test]
-fn select_join_with_group_by_is_rejected() {
-    use wasm_dbms_api::prelude::Database as _;
+fn test_on_conflict_update_builds_patch_from_given_fields() {
+    let patch = Counter::on_conflict_update(&CounterInsertRequest {
+        id: Uint32(1),
+        name: Text("clicks".to_string()),
+        hits: None,
+    })
+    .expect("expected a patch");
+    assert!(patch.id.is_none());
+    assert_eq!(patch.name, Some(Text("clicks".to_string())));
+    assert!(patch.hits.is_none());
+    assert_eq!(
+        patch.where_clause,
+        Some(Filter::eq("id", Value::Uint32(Uint32(1))))
+    );
+    assert!(
+        User::on_conflict_update(&UserInsertRequest {
+            id: Uint32(1),
+            name: Text("alice".to_string()),
+        })
+        .is_none()
+    );
+}
 
-    let ctx = setup();
-    let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+#[test]
+fn test_insert_with_on_conflict_update_updates_existing_row() {
+    let ctx = setup_counters();
+    let db = WasmDbmsDatabase::oneshot(&ctx, CounterTestSchema);
 
-    let query = Query::builder()
-        .inner_join("posts", "id", "user_id")
-        .group_by(&["id"])
-        .build();
-    let err = db
-        .select_join("users", query)
-        .expect_err("GROUP BY must be rejected on select_join");
-    assert!(err.to_string().contains("GROUP BY"));
+    // the omitted `hits` keeps its stored value instead of the default
+    db.insert::<Counter>(CounterInsertRequest {
+        id: Uint32(1),
+        name: Text("clicks".to_string()),
+        hits: None,
+    })
+    .unwrap();
+    let updated = counter(&db, 1);
+    assert_eq!(updated.name, Some(Text("clicks".to_string())));
+    assert_eq!(updated.hits, Some(Uint32(5)));
+
+    let returned = db
+        .insert_returning::<Counter>(CounterInsertRequest {
+            id: Uint32(1),
+            name: Text("clicks".to_string()),
+            hits: Some(Uint32(6)),
+        })
+        .unwrap();
+    assert_eq!(returned.hits, Some(Uint32(6)));
+
+    // a new primary key is inserted, with the default applied
+    db.insert::<Counter>(CounterInsertRequest {
+        id: Uint32(2),
+        name: Text("likes".to_string()),
+        hits: None,
+    })
+    .unwrap();
+    assert_eq!(counter(&db, 2).hits, Some(Uint32(0)));
+    assert_eq!(db.count::<Counter>(None).unwrap(), 2);
+}
+
+#[test]
+fn test_insert_with_behavior_overrides_on_conflict_update() {
+    let ctx = setup_counters();
+    let db = WasmDbmsDatabase::oneshot(&ctx, CounterTestSchema);
+    let record = CounterInsertRequest {
+        id: Uint32(1),
+        name: Text("clicks".to_string()),
+        hits: None,
+    };
+
+    let result = db.insert_with_behavior::<Counter>(record.clone(), InsertConflictBehavior::Error);
+    assert!(matches!(
+        result,
+        Err(DbmsError::Query(QueryError::PrimaryKeyConflict))
+    ));
+    db.insert_with_behavior::<Counter>(record, InsertConflictBehavior::Ignore)
+        .unwrap();
+    assert_eq!(counter(&db, 1).name, Some(Text("visits".to_string())));
+}
+
+#[test]
+fn test_insert_with_on_conflict_update_in_transaction() {
+    let ctx = setup_counters();
+    let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+    let mut tx_db = WasmDbmsDatabase::from_transaction(&ctx, CounterTestSchema, tx_id);
+    tx_db
+        .insert::<Counter>(CounterInsertRequest {
+            id: Uint32(1),
+            name: Text("clicks".to_string()),
+            hits: None,
+        })
+        .unwrap();
+    assert_eq!(counter(&tx_db, 1).name, Some(Text("clicks".to_string())));
+    tx_db.commit().unwrap();
+
+    let db = WasmDbmsDatabase::oneshot(&ctx, CounterTestSchema);
+    let committed = counter(&db, 1);
+    assert_eq!(committed.name, Some(Text("clicks".to_string())));
+    assert_eq!(committed.hits, Some(Uint32(5)));
 }
@@ -6,5 +6,6 @@ pub(crate) mod common;
 mod insert;
 mod update;
 
+pub(crate) use self::insert::check_batch_conflicts;
 pub use self::insert::InsertIntegrityValidator;
 pub use self::update::UpdateIntegrityValidator;
@@ -3,52 +3,183 @@
 //! Shared integrity-check functions used by both insert and update validators.
 
 use wasm_dbms_api::prelude::{
-    ColumnDef, Database, DbmsError, DbmsResult, ForeignFetcher, ForeignKeyDef, QueryError,
-    TableSchema, Value,
+    ColumnDef, Database, DbmsError, DbmsResult, Filter, ForeignFetcher, ForeignKeyDef, QueryError,
+    TableSchema, UniqueConstraintDef, Value,
 };
 
-/// Checks whether `value` passes the validator defined for `column`, if any.
+/// Checks whether `value` passes the validators defined for `column`, in
+/// declaration order, failing with the error of the first one rejecting it.
 pub fn check_column_validate<T: TableSchema>(column: &ColumnDef, value: &Value) -> DbmsResult<()> {
-    let Some(validator) = T::validator(column.name) else {
+    for validator in T::validator(column.name) {
+        validator.validate(value)?;
+    }
+
+    Ok(())
+}
+
+/// Checks whether `value` fits in the maximum size of `column`, if any.
+///
+/// Fails with [`QueryError::ValueTooLarge`] before anything is written, as an
+/// oversized value would not fit in its record page.
+pub fn check_value_size(column: &ColumnDef, value: &Value) -> DbmsResult<()> {
+    let (Some(max), Value::Text(text)) = (column.max_size(), value) else {
         return Ok(());
     };
+    let actual = text.as_str().len() as u64;
+    if actual > max {
+        return Err(DbmsError::Query(QueryError::ValueTooLarge {
+            column: column.name.to_string(),
+            max,
+            actual,
+        }));
+    }
+
+    Ok(())
+}
+
+/// Checks whether the discriminant of an enum `value` names a variant of
+/// `column`, failing with [`QueryError::InvalidEnumValue`] otherwise.
+pub fn check_enum_value(column: &ColumnDef, value: &Value) -> DbmsResult<()> {
+    let (Some(variants), Value::Custom(custom)) = (column.allowed_values(), value) else {
+        return Ok(());
+    };
+    match custom.encoded.as_slice() {
+        [discriminant] if (*discriminant as usize) < variants.len() => Ok(()),
+        encoded => Err(DbmsError::Query(QueryError::InvalidEnumValue {
+            column: column.name.to_string(),
+            discriminant: encoded.first().copied().unwrap_or_default(),
+        })),
+    }
+}
+
+/// Builds the filter matching records that share the values of a composite unique
+/// constraint with `record_values`.
+///
+/// Returns `None` when any column of the group is missing or `NULL`, since a
+/// `NULL` never collides with another value.
+pub fn unique_constraint_filter(
+    constraint: &UniqueConstraintDef,
+    record_values: &[(ColumnDef, Value)],
+) -> Option<Filter> {
+    let mut filter: Option<Filter> = None;
+    for column in constraint.columns {
+        let value = record_values
+            .iter()
+            .find(|(col, _)| col.name == *column)
+            .map(|(_, value)| value)
+            .filter(|value| !value.is_null())?;
+        let eq = Filter::eq(column, value.clone());
+        filter = Some(match filter {
+            Some(filter) => filter.and(eq),
+            None => eq,
+        });
+    }
+
+    filter
+}
 
-    validator.validate(value)
+/// Returns the values of the columns of a composite unique constraint, in
+/// declaration order.
+///
+/// Returns `None` when any column of the group is missing or `NULL`.
+pub fn unique_constraint_key(
+    constraint: &UniqueConstraintDef,
+    record_values: &[(ColumnDef, Value)],
+) -> Option<Vec<Value>> {
+    constraint
+        .columns
+        .iter()
+        .map(|column| {
+            record_values
+                .iter()
+                .find(|(col, _)| col.name == *column)
+                .map(|(_, value)| value)
+                .filter(|value| !value.is_null())
+                .cloned()
+        })
+        .collect()
+}
+
+/// Builds the [`QueryError::UniqueConstraintViolation`] error for a composite unique
+/// constraint of table `T`, reporting its columns as a comma-separated list.
+pub fn unique_constraint_violation<T: TableSchema>(constraint: &UniqueConstraintDef) -> DbmsError {
+    DbmsError::Query(QueryError::UniqueConstraintViolation {
+        table: T::table_name().to_string(),
+        column: constraint.columns.join(", "),
+    })
 }
 
 /// Checks whether all foreign keys in `record_values` reference existing records.
+///
+/// Foreign keys with a null column reference nothing and are skipped, as
+/// are `deferred` foreign keys.
 pub fn check_foreign_keys<T: TableSchema>(
     database: &impl Database,
     record_values: &[(ColumnDef, Value)],
 ) -> DbmsResult<()> {
     record_values
         .iter()
-        .filter_map(|(col, value)| col.foreign_key.as_ref().map(|fk| (fk, value)))
-        .try_for_each(|(fk, value)| check_foreign_key_existence::<T>(database, fk, value))
+        .filter_map(|(col, _)| col.foreign_key.as_ref())
+        .filter(|fk| !fk.deferred)
+        .filter_map(|fk| fk.local_values(record_values).map(|key| (fk, key)))
+        .try_for_each(|(fk, key)| check_foreign_key_existence::<T>(database, fk, &key))
 }
 
-/// Checks whether a single foreign key references an existing record.
+/// Checks whether a single foreign key, holding `key` in its local columns,
+/// references an existing record, failing with
+/// [`QueryError::ForeignKeyViolation`] otherwise.
+///
+/// The lookup goes through `database`, so rows written earlier in the same
+/// transaction are found.
 pub fn check_foreign_key_existence<T: TableSchema>(
     database: &impl Database,
     foreign_key: &ForeignKeyDef,
-    value: &Value,
+    key: &[Value],
 ) -> DbmsResult<()> {
-    let res = T::foreign_fetcher().fetch(
+    let found = match T::foreign_fetcher().fetch(
         database,
         foreign_key.foreign_table,
         foreign_key.local_column,
-        value.clone(),
-    )?;
-    if res.is_empty() {
-        Err(DbmsError::Query(
-            QueryError::ForeignKeyConstraintViolation {
-                field: foreign_key.local_column.to_string(),
-                referencing_table: foreign_key.foreign_table.to_string(),
-            },
-        ))
-    } else {
-        Ok(())
+        key,
+    ) {
+        Ok(res) => !res.is_empty(),
+        Err(DbmsError::Query(QueryError::BrokenForeignKeyReference { .. })) => false,
+        Err(err) => return Err(err),
+    };
+    if found {
+        return Ok(());
     }
+    // composite keys report all of their columns and values
+    let (column, value) = match key {
+        [value] => (foreign_key.local_column.to_string(), value.clone()),
+        key => (
+            foreign_key.local_columns.join(", "),
+            Value::Array(key.to_vec()),
+        ),
+    };
+    Err(DbmsError::Query(QueryError::ForeignKeyViolation {
+        table: foreign_key.foreign_table.to_string(),
+        column,
+        value,
+    }))
+}
+
+/// Checks whether `record_values` satisfies the table-level `#[check(...)]`
+/// constraints of `T`.
+///
+/// Fails with [`QueryError::CheckConstraintViolation`] on the first check
+/// whose filter does not match the record.
+pub fn check_table_checks<T: TableSchema>(record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
+    for check in T::checks() {
+        if !(check.filter)().matches(record_values)? {
+            return Err(DbmsError::Query(QueryError::CheckConstraintViolation {
+                field: check.name.to_string(),
+                message: check.expression.to_string(),
+            }));
+        }
+    }
+
+    Ok(())
 }
 
 /// Checks whether all non-nullable columns are present in `record_values`.
@@ -73,7 +204,8 @@ pub fn check_non_nullable_fields<T: TableSchema>(
 mod tests {
 
     use wasm_dbms_api::prelude::{
-        Database as _, InsertRecord as _, MaxStrlenValidator, TableSchema as _, Text, Uint32, Value,
+        CustomValue, DataTypeKind, Database as _, InsertRecord as _, MaxStrlenValidator,
+        TableSchema as _, Text, Uint32, Value,
     };
     use wasm_dbms_macros::{DatabaseSchema, Table};
     use wasm_dbms_memory::prelude::HeapMemoryProvider;
@@ -136,6 +268,55 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_check_value_size_rejects_text_past_max_size() {
+        let column = Post::columns()[1]; // title
+        let fits = Value::Text(Text("a".repeat(Text::MAX_BYTES)));
+        assert!(check_value_size(&column, &fits).is_ok());
+
+        let too_large = Value::Text(Text("a".repeat(Text::MAX_BYTES + 1)));
+        let result = check_value_size(&column, &too_large);
+        assert!(matches!(
+            result,
+            Err(DbmsError::Query(QueryError::ValueTooLarge { column, max, actual }))
+                if column == "title"
+                    && max == Text::MAX_BYTES as u64
+                    && actual == Text::MAX_BYTES as u64 + 1
+        ));
+    }
+
+    #[test]
+    fn test_check_enum_value_rejects_discriminant_without_variant() {
+        let column = ColumnDef {
+            name: "status",
+            data_type: DataTypeKind::Enum(&["Active", "Inactive"]),
+            auto_increment: false,
+            nullable: false,
+            primary_key: false,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        };
+        let value = |encoded: Vec<u8>| {
+            Value::Custom(CustomValue {
+                type_tag: "status".to_string(),
+                encoded,
+                display: String::new(),
+            })
+        };
+        assert!(check_enum_value(&column, &value(vec![1])).is_ok());
+        assert!(check_enum_value(&column, &Value::Null).is_ok());
+
+        let result = check_enum_value(&column, &value(vec![2]));
+        assert!(matches!(
+            result,
+            Err(DbmsError::Query(QueryError::InvalidEnumValue { column, discriminant }))
+                if column == "status" && discriminant == 2
+        ));
+    }
+
     #[test]
     fn test_check_non_nullable_fields_all_present() {
         let values = vec![
@@ -200,21 +381,37 @@ mod tests {
         let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
 
         // No users inserted; FK reference to user_id=999 should fail.
-        // The generated ForeignFetcher returns BrokenForeignKeyReference
-        // when the referenced record does not exist.
         let post_values = vec![
             (Post::columns()[0], Value::Uint32(Uint32(10))),
             (Post::columns()[1], Value::Text(Text("title".to_string()))),
             (Post::columns()[2], Value::Uint32(Uint32(999))),
         ];
         let result = check_foreign_keys::<Post>(&db, &post_values);
-        assert!(result.is_err());
         assert!(matches!(
             result.unwrap_err(),
-            DbmsError::Query(QueryError::BrokenForeignKeyReference { .. })
+            DbmsError::Query(QueryError::ForeignKeyViolation { table, column, value })
+                if table == "users" && column == "user_id" && value == Value::Uint32(Uint32(999))
         ));
     }
 
+    #[test]
+    fn test_check_foreign_keys_skips_deferred_foreign_key() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+
+        let mut user_id = Post::columns()[2];
+        user_id.foreign_key = user_id.foreign_key.map(|fk| ForeignKeyDef {
+            deferred: true,
+            ..fk
+        });
+        let post_values = vec![
+            (Post::columns()[0], Value::Uint32(Uint32(10))),
+            (Post::columns()[1], Value::Text(Text("title".to_string()))),
+            (user_id, Value::Uint32(Uint32(999))),
+        ];
+        assert!(check_foreign_keys::<Post>(&db, &post_values).is_ok());
+    }
+
     #[test]
     fn test_check_foreign_key_existence_found() {
         let ctx = setup();
@@ -231,9 +428,13 @@ mod tests {
         let fk = ForeignKeyDef {
             foreign_table: "users",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
             local_column: "user_id",
+            local_columns: &["user_id"],
         };
-        let result = check_foreign_key_existence::<Post>(&db, &fk, &Value::Uint32(Uint32(1)));
+        let result = check_foreign_key_existence::<Post>(&db, &fk, &[Value::Uint32(Uint32(1))]);
         assert!(result.is_ok());
     }
 
@@ -245,14 +446,16 @@ mod tests {
         let fk = ForeignKeyDef {
             foreign_table: "users",
             foreign_column: "id",
+            foreign_columns: &["id"],
+            on_delete: None,
+            deferred: false,
             local_column: "user_id",
+            local_columns: &["user_id"],
         };
-        let result = check_foreign_key_existence::<Post>(&db, &fk, &Value::Uint32(Uint32(999)));
-        assert!(result.is_err());
-        // The generated ForeignFetcher returns BrokenForeignKeyReference
+        let result = check_foreign_key_existence::<Post>(&db, &fk, &[Value::Uint32(Uint32(999))]);
         assert!(matches!(
             result.unwrap_err(),
-            DbmsError::Query(QueryError::BrokenForeignKeyReference { .. })
+            DbmsError::Query(QueryError::ForeignKeyViolation { .. })
         ));
     }
 }
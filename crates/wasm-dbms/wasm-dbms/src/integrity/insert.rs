@@ -3,6 +3,8 @@
 
 //! Integrity validator for insert operations.
 
+use std::collections::HashSet;
+
 use wasm_dbms_api::prelude::{
     ColumnDef, Database as _, DbmsError, DbmsResult, Filter, Query, QueryError, TableSchema, Value,
 };
@@ -10,6 +12,7 @@ use wasm_dbms_memory::prelude::{AccessControl, AccessControlList, MemoryProvider
 
 use super::common;
 use crate::database::WasmDbmsDatabase;
+use crate::primary_key::{row_key, row_key_filter, table_row_key};
 
 /// Integrity validator for insert operations.
 pub struct InsertIntegrityValidator<'a, T, M, A = AccessControlList>
@@ -46,30 +49,31 @@ where
     /// Verifies whether the given insert record is valid.
     pub fn validate(&self, record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
         for (col, value) in record_values {
+            common::check_value_size(col, value)?;
+            common::check_enum_value(col, value)?;
             common::check_column_validate::<T>(col, value)?;
         }
         self.check_primary_key_conflict(record_values)?;
         self.check_unique_constraints(record_values)?;
         common::check_foreign_keys::<T>(self.database, record_values)?;
         common::check_non_nullable_fields::<T>(record_values)?;
+        T::check_record(record_values)?;
+        common::check_table_checks::<T>(record_values)?;
 
         Ok(())
     }
 
     /// Checks for primary key conflicts.
+    ///
+    /// For a composite primary key only a record matching every column
+    /// conflicts; sharing part of the key is allowed.
     fn check_primary_key_conflict(&self, record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
-        let pk_name = T::primary_key();
-        let pk = record_values
-            .iter()
-            .find(|(col_def, _)| col_def.name == pk_name)
-            .map(|(_, value)| value.clone())
-            .ok_or(DbmsError::Query(QueryError::MissingNonNullableField(
-                pk_name.to_string(),
-            )))?;
+        let pk = table_row_key::<T>(record_values)?;
 
         let query: Query = Query::builder()
-            .field(pk_name)
-            .and_where(Filter::Eq(pk_name.to_string(), pk))
+            .field(T::primary_key())
+            .include_deleted()
+            .and_where(row_key_filter::<T>(&pk))
             .build();
 
         let res = self.database.select::<T>(query)?;
@@ -82,25 +86,89 @@ where
 
     /// Checks for unique constraint violations.
     ///
-    /// Queries each unique field individually, returning an error on the first conflict found.
+    /// Queries each unique field individually, then each composite unique group,
+    /// returning an error on the first conflict found. As in SQL, `NULL` never
+    /// conflicts, so a nullable unique column may hold any number of `NULL`s.
     fn check_unique_constraints(&self, record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
-        for (col_def, value) in record_values.iter().filter(|(col_def, _)| col_def.unique) {
+        for (col_def, value) in record_values
+            .iter()
+            .filter(|(col_def, value)| col_def.unique && !value.is_null())
+        {
             let query = Query::builder()
                 .field(T::primary_key())
+                .include_deleted()
                 .and_where(Filter::Eq(col_def.name.to_string(), value.clone()))
                 .build();
 
             if !self.database.select::<T>(query)?.is_empty() {
                 return Err(DbmsError::Query(QueryError::UniqueConstraintViolation {
-                    field: col_def.name.to_string(),
+                    table: T::table_name().to_string(),
+                    column: col_def.name.to_string(),
                 }));
             }
         }
 
+        for constraint in T::unique_constraints() {
+            let Some(filter) = common::unique_constraint_filter(constraint, record_values) else {
+                continue;
+            };
+            let query = Query::builder()
+                .field(T::primary_key())
+                .include_deleted()
+                .and_where(filter)
+                .build();
+
+            if !self.database.select::<T>(query)?.is_empty() {
+                return Err(common::unique_constraint_violation::<T>(constraint));
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Checks the records of an insert batch against each other.
+///
+/// Returns [`QueryError::PrimaryKeyConflict`] when two records share a primary
+/// key and [`QueryError::UniqueConstraintViolation`] when they share the value
+/// of a `#[unique]` column or of a composite unique group. Checks against the
+/// records already stored are left to [`InsertIntegrityValidator`].
+pub(crate) fn check_batch_conflicts<T>(rows: &[Vec<(ColumnDef, Value)>]) -> DbmsResult<()>
+where
+    T: TableSchema,
+{
+    let mut seen_pks = HashSet::with_capacity(rows.len());
+    let mut seen_unique: HashSet<(&'static str, &Value)> = HashSet::new();
+    let mut seen_groups: HashSet<(&'static str, Vec<Value>)> = HashSet::new();
+
+    for record_values in rows {
+        if let Some(pk) = row_key(record_values)
+            && !seen_pks.insert(pk)
+        {
+            return Err(DbmsError::Query(QueryError::PrimaryKeyConflict));
+        }
+        for (col_def, value) in record_values {
+            if col_def.unique && !value.is_null() && !seen_unique.insert((col_def.name, value)) {
+                return Err(DbmsError::Query(QueryError::UniqueConstraintViolation {
+                    table: T::table_name().to_string(),
+                    column: col_def.name.to_string(),
+                }));
+            }
+        }
+
+        for constraint in T::unique_constraints() {
+            let Some(key) = common::unique_constraint_key(constraint, record_values) else {
+                continue;
+            };
+            if !seen_groups.insert((constraint.name, key)) {
+                return Err(common::unique_constraint_violation::<T>(constraint));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -131,8 +199,19 @@ mod tests {
         pub user_id: Uint32,
     }
 
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[table = "memberships"]
+    pub struct Membership {
+        #[primary_key]
+        pub id: Uint32,
+        #[unique(group = "ux_membership")]
+        pub team: Text,
+        #[unique(group = "ux_membership")]
+        pub member: Text,
+    }
+
     #[derive(DatabaseSchema)]
-    #[tables(User = "users", Contract = "contracts")]
+    #[tables(User = "users", Contract = "contracts", Membership = "memberships")]
     pub struct TestSchema;
 
     fn setup() -> DbmsContext<HeapMemoryProvider> {
@@ -165,6 +244,27 @@ mod tests {
         db.insert::<Contract>(insert).unwrap();
     }
 
+    fn insert_membership(
+        db: &WasmDbmsDatabase<'_, HeapMemoryProvider>,
+        id: u32,
+        team: &str,
+        member: &str,
+    ) -> wasm_dbms_api::prelude::DbmsResult<()> {
+        let insert = MembershipInsertRequest::from_values(&[
+            (Membership::columns()[0], Value::Uint32(Uint32(id))),
+            (
+                Membership::columns()[1],
+                Value::Text(Text(team.to_string())),
+            ),
+            (
+                Membership::columns()[2],
+                Value::Text(Text(member.to_string())),
+            ),
+        ])
+        .unwrap();
+        db.insert::<Membership>(insert)
+    }
+
     #[test]
     fn test_insert_with_unique_field_succeeds() {
         let ctx = setup();
@@ -195,8 +295,8 @@ mod tests {
         assert!(matches!(
             result.unwrap_err(),
             wasm_dbms_api::prelude::DbmsError::Query(
-                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref field }
-            ) if field == "code"
+                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref column, .. }
+            ) if column == "code"
         ),);
     }
 
@@ -223,8 +323,40 @@ mod tests {
         assert!(matches!(
             result.unwrap_err(),
             wasm_dbms_api::prelude::DbmsError::Query(
-                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref field }
-            ) if field == "code"
+                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref column, .. }
+            ) if column == "code"
         ),);
     }
+
+    #[test]
+    fn test_insert_with_composite_unique_group() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        insert_membership(&db, 1, "core", "alice").unwrap();
+        // sharing a single column of the group is allowed
+        insert_membership(&db, 2, "core", "bob").unwrap();
+        insert_membership(&db, 3, "docs", "alice").unwrap();
+
+        let result = insert_membership(&db, 4, "core", "alice");
+        assert!(matches!(
+            result.unwrap_err(),
+            wasm_dbms_api::prelude::DbmsError::Query(
+                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref table, ref column }
+            ) if table == "memberships" && column == "team, member"
+        ));
+    }
+
+    #[test]
+    fn test_should_expose_unique_constraints() {
+        let constraints = Membership::unique_constraints();
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0].name, "ux_membership");
+        assert_eq!(constraints[0].columns, ["team", "member"]);
+        assert!(
+            Membership::columns()
+                .iter()
+                .all(|c| !c.unique || c.primary_key)
+        );
+        assert!(Contract::unique_constraints().is_empty());
+    }
 }
@@ -11,6 +11,7 @@ use wasm_dbms_memory::prelude::{AccessControl, AccessControlList, MemoryProvider
 
 use super::common;
 use crate::database::WasmDbmsDatabase;
+use crate::primary_key::{row_key, row_key_filter, table_row_key};
 
 /// Integrity validator for update operations.
 ///
@@ -23,7 +24,8 @@ where
     A: AccessControl,
 {
     database: &'a WasmDbmsDatabase<'a, M, A>,
-    /// The current primary key value of the record being updated.
+    /// The current row key of the record being updated; the primary key value
+    /// itself unless the primary key is composite.
     old_pk: Value,
     _marker: std::marker::PhantomData<T>,
 }
@@ -53,30 +55,28 @@ where
     /// Verifies whether the given updated record values are valid.
     pub fn validate(&self, record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
         for (col, value) in record_values {
+            common::check_value_size(col, value)?;
+            common::check_enum_value(col, value)?;
             common::check_column_validate::<T>(col, value)?;
         }
         self.check_primary_key_conflict(record_values)?;
         self.check_unique_constraints(record_values)?;
         common::check_foreign_keys::<T>(self.database, record_values)?;
         common::check_non_nullable_fields::<T>(record_values)?;
+        T::check_record(record_values)?;
+        common::check_table_checks::<T>(record_values)?;
 
         Ok(())
     }
 
     /// Checks for primary key conflicts with *other* records.
     fn check_primary_key_conflict(&self, record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
-        let pk_name = T::primary_key();
-        let new_pk = record_values
-            .iter()
-            .find(|(col_def, _)| col_def.name == pk_name)
-            .map(|(_, value)| value.clone())
-            .ok_or(DbmsError::Query(QueryError::MissingNonNullableField(
-                pk_name.to_string(),
-            )))?;
+        let new_pk = table_row_key::<T>(record_values)?;
 
         let query = Query::builder()
-            .field(pk_name)
-            .and_where(Filter::Eq(pk_name.to_string(), new_pk.clone()))
+            .field(T::primary_key())
+            .include_deleted()
+            .and_where(row_key_filter::<T>(&new_pk))
             .build();
 
         let res = self.database.select::<T>(query)?;
@@ -95,35 +95,47 @@ where
 
     /// Checks for unique constraint violations, excluding the record being updated.
     ///
-    /// For each unique field, queries for existing records with the same value.
-    /// A match is only a conflict if it belongs to a different record (different primary key).
+    /// For each unique field and each composite unique group, queries for existing
+    /// records with the same values. A match is only a conflict if it belongs to a
+    /// different record (different primary key). `NULL` values never conflict.
     fn check_unique_constraints(&self, record_values: &[(ColumnDef, Value)]) -> DbmsResult<()> {
-        let pk_name = T::primary_key();
-
-        for (col_def, value) in record_values.iter().filter(|(col_def, _)| col_def.unique) {
-            let query = Query::builder()
-                .field(pk_name)
-                .and_where(Filter::Eq(col_def.name.to_string(), value.clone()))
-                .build();
-
-            let res = self.database.select::<T>(query)?;
-            for record in &res {
-                let record_pk = record
-                    .to_values()
-                    .into_iter()
-                    .find(|(c, _)| c.name == pk_name)
-                    .map(|(_, v)| v);
-
-                if record_pk.as_ref() != Some(&self.old_pk) {
-                    return Err(DbmsError::Query(QueryError::UniqueConstraintViolation {
-                        field: col_def.name.to_string(),
-                    }));
-                }
+        for (col_def, value) in record_values
+            .iter()
+            .filter(|(col_def, value)| col_def.unique && !value.is_null())
+        {
+            if self.has_other_record(Filter::Eq(col_def.name.to_string(), value.clone()))? {
+                return Err(DbmsError::Query(QueryError::UniqueConstraintViolation {
+                    table: T::table_name().to_string(),
+                    column: col_def.name.to_string(),
+                }));
+            }
+        }
+
+        for constraint in T::unique_constraints() {
+            let Some(filter) = common::unique_constraint_filter(constraint, record_values) else {
+                continue;
+            };
+            if self.has_other_record(filter)? {
+                return Err(common::unique_constraint_violation::<T>(constraint));
             }
         }
 
         Ok(())
     }
+
+    /// Returns whether a record other than the one being updated matches `filter`.
+    fn has_other_record(&self, filter: Filter) -> DbmsResult<bool> {
+        let query = Query::builder()
+            .fields(T::primary_keys().iter().copied())
+            .include_deleted()
+            .and_where(filter)
+            .build();
+
+        let res = self.database.select::<T>(query)?;
+        Ok(res
+            .iter()
+            .any(|record| row_key(&record.to_values()).as_ref() != Some(&self.old_pk)))
+    }
 }
 
 #[cfg(test)]
@@ -157,8 +169,19 @@ mod tests {
         pub user_id: Uint32,
     }
 
+    #[derive(Debug, Table, Clone, PartialEq, Eq)]
+    #[table = "memberships"]
+    pub struct Membership {
+        #[primary_key]
+        pub id: Uint32,
+        #[unique(group = "ux_membership")]
+        pub team: Text,
+        #[unique(group = "ux_membership")]
+        pub member: Text,
+    }
+
     #[derive(DatabaseSchema)]
-    #[tables(User = "users", Contract = "contracts")]
+    #[tables(User = "users", Contract = "contracts", Membership = "memberships")]
     pub struct TestSchema;
 
     fn setup() -> DbmsContext<HeapMemoryProvider> {
@@ -191,6 +214,27 @@ mod tests {
         db.insert::<Contract>(insert).unwrap();
     }
 
+    fn insert_membership(
+        db: &WasmDbmsDatabase<'_, HeapMemoryProvider>,
+        id: u32,
+        team: &str,
+        member: &str,
+    ) -> wasm_dbms_api::prelude::DbmsResult<()> {
+        let insert = MembershipInsertRequest::from_values(&[
+            (Membership::columns()[0], Value::Uint32(Uint32(id))),
+            (
+                Membership::columns()[1],
+                Value::Text(Text(team.to_string())),
+            ),
+            (
+                Membership::columns()[2],
+                Value::Text(Text(member.to_string())),
+            ),
+        ])
+        .unwrap();
+        db.insert::<Membership>(insert)
+    }
+
     #[test]
     fn test_update_unique_field_to_new_value_succeeds() {
         let ctx = setup();
@@ -247,8 +291,41 @@ mod tests {
         assert!(matches!(
             result.unwrap_err(),
             wasm_dbms_api::prelude::DbmsError::Query(
-                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref field }
-            ) if field == "code"
+                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref column, .. }
+            ) if column == "code"
         ),);
     }
+
+    #[test]
+    fn test_update_with_composite_unique_group() {
+        let ctx = setup();
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        insert_membership(&db, 1, "core", "alice").unwrap();
+        insert_membership(&db, 2, "core", "bob").unwrap();
+
+        // Rewriting a row with its own values is not a conflict
+        let patch = MembershipUpdateRequest::from_values(
+            &[(
+                Membership::columns()[2],
+                Value::Text(Text("bob".to_string())),
+            )],
+            Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+        );
+        assert_eq!(db.update::<Membership>(patch).unwrap(), 1);
+
+        let patch = MembershipUpdateRequest::from_values(
+            &[(
+                Membership::columns()[2],
+                Value::Text(Text("alice".to_string())),
+            )],
+            Some(Filter::eq("id", Value::Uint32(Uint32(2)))),
+        );
+        let result = db.update::<Membership>(patch);
+        assert!(matches!(
+            result.unwrap_err(),
+            wasm_dbms_api::prelude::DbmsError::Query(
+                wasm_dbms_api::prelude::QueryError::UniqueConstraintViolation { ref table, ref column }
+            ) if table == "memberships" && column == "team, member"
+        ));
+    }
 }
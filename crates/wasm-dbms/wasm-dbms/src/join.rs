@@ -56,7 +56,7 @@ where
     ) -> DbmsResult<Vec<Vec<(JoinColumnDef, Value)>>> {
         let from_rows = self
             .schema
-            .select(dbms, from_table, Query::builder().all().build())?;
+            .select(dbms, from_table, scan_query(&query, None))?;
 
         let mut joined_rows: Vec<JoinedRow> = from_rows
             .into_iter()
@@ -83,6 +83,7 @@ where
                 left_col,
                 right_col,
                 keep_unmatched_right,
+                &query,
             )?;
 
             joined_rows = self.nested_loop_join(
@@ -145,6 +146,7 @@ where
         left_col: &str,
         right_col: &str,
         keep_unmatched_right: bool,
+        query: &Query,
     ) -> DbmsResult<Vec<Vec<(ColumnDef, Value)>>> {
         let unique_join_values: Vec<Value> = {
             let mut seen = HashSet::new();
@@ -158,19 +160,19 @@ where
         if unique_join_values.is_empty() || keep_unmatched_right {
             return self
                 .schema
-                .select(dbms, right_table, Query::builder().all().build());
+                .select(dbms, right_table, scan_query(query, None));
         }
 
         self.schema.select(
             dbms,
             right_table,
-            Query::builder()
-                .all()
-                .filter(Some(wasm_dbms_api::prelude::Filter::in_list(
+            scan_query(
+                query,
+                Some(wasm_dbms_api::prelude::Filter::in_list(
                     right_col,
                     unique_join_values,
-                )))
-                .build(),
+                )),
+            ),
         )
     }
 
@@ -333,6 +335,16 @@ where
     }
 }
 
+/// Builds the query scanning one table of a join, narrowed by `filter` and
+/// keeping soft-deleted rows only when the join `query` includes them.
+fn scan_query(query: &Query, filter: Option<wasm_dbms_api::prelude::Filter>) -> Query {
+    let mut builder = Query::builder().all().filter(filter);
+    if query.include_deleted {
+        builder = builder.include_deleted();
+    }
+    builder.build()
+}
+
 #[cfg(test)]
 mod tests {
 
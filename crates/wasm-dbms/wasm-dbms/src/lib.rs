@@ -201,12 +201,13 @@ mod context;
 mod database;
 pub mod integrity;
 pub mod join;
+mod primary_key;
 pub mod referenced_tables;
 pub mod schema;
 pub mod transaction;
 
 pub use self::context::DbmsContext;
-pub use self::database::WasmDbmsDatabase;
+pub use self::database::{SelectIter, WasmDbmsDatabase};
 
 /// Prelude re-exports for convenient use.
 ///
@@ -217,11 +218,11 @@ pub use self::database::WasmDbmsDatabase;
 /// ```
 pub mod prelude {
     pub use super::context::DbmsContext;
-    pub use super::database::WasmDbmsDatabase;
+    pub use super::database::{SelectIter, WasmDbmsDatabase};
     pub use super::integrity::{InsertIntegrityValidator, UpdateIntegrityValidator};
     pub use super::join::JoinEngine;
-    pub use super::referenced_tables::get_referenced_tables;
+    pub use super::referenced_tables::{get_referenced_tables, get_referencing_foreign_keys};
     pub use super::schema::DatabaseSchema;
     pub use super::transaction::DatabaseOverlay;
-    pub use super::transaction::session::TransactionSession;
+    pub use super::transaction::session::{TransactionInfo, TransactionSession};
 }
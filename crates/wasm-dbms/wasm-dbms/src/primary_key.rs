@@ -0,0 +1,196 @@
+// Rust guideline compliant 2026-10-16
+// X-WHERE-CLAUSE, M-CANONICAL-DOCS
+
+//! Row keys: a single [`Value`] identifying a record by its primary key.
+//!
+//! For a single-column primary key the row key is the column value itself.
+//! For a composite primary key it is a [`Value::Blob`] packing each component
+//! as `[len: u16 LE] + [Value::encode()]`, with the components ordered by
+//! column name so the key does not depend on the order of the row values.
+//! This lets the transaction overlay and the index overlay keep tracking
+//! records by one value regardless of the primary key shape.
+
+use std::borrow::Cow;
+
+use wasm_dbms_api::prelude::{
+    Blob, ColumnDef, DbmsError, DbmsResult, Encode as _, Filter, QueryError, TableSchema, Value,
+};
+
+/// Returns the row key of `row`, built from the columns flagged as primary key.
+///
+/// Returns `None` if `row` carries no primary key column.
+pub(crate) fn row_key(row: &[(ColumnDef, Value)]) -> Option<Value> {
+    let mut components: Vec<(&str, &Value)> = row
+        .iter()
+        .filter(|(col_def, _)| col_def.primary_key)
+        .map(|(col_def, value)| (col_def.name, value))
+        .collect();
+
+    match components.as_slice() {
+        [] => None,
+        [(_, value)] => Some((*value).clone()),
+        _ => {
+            components.sort_by_key(|(name, _)| *name);
+            let mut bytes = Vec::new();
+            for (_, value) in components {
+                let encoded = value.encode();
+                bytes.extend_from_slice(&(encoded.len() as u16).to_le_bytes());
+                bytes.extend_from_slice(&encoded);
+            }
+            Some(Value::Blob(Blob(bytes)))
+        }
+    }
+}
+
+/// Returns the row key of `row` for table `T`.
+///
+/// Fails with [`QueryError::MissingNonNullableField`] naming the first primary
+/// key column missing from `row`, so a partial composite key is rejected.
+pub(crate) fn table_row_key<T>(row: &[(ColumnDef, Value)]) -> DbmsResult<Value>
+where
+    T: TableSchema,
+{
+    if let Some(missing) = T::primary_keys()
+        .iter()
+        .find(|pk| !row.iter().any(|(col_def, _)| col_def.name == **pk))
+    {
+        return Err(DbmsError::Query(QueryError::MissingNonNullableField(
+            missing.to_string(),
+        )));
+    }
+
+    row_key(row).ok_or_else(|| {
+        DbmsError::Query(QueryError::MissingNonNullableField(
+            T::primary_key().to_string(),
+        ))
+    })
+}
+
+/// Splits a row key back into its components, ordered as `pk_columns`.
+pub(crate) fn row_key_components(pk_columns: &[&'static str], key: &Value) -> Vec<Value> {
+    if pk_columns.len() == 1 {
+        return vec![key.clone()];
+    }
+
+    let Value::Blob(Blob(bytes)) = key else {
+        panic!("composite row key must be a blob");
+    };
+
+    let mut sorted_values = Vec::with_capacity(pk_columns.len());
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let len = u16::from_le_bytes([bytes[offset], bytes[offset + 1]]) as usize;
+        offset += 2;
+        let value = Value::decode(Cow::Borrowed(&bytes[offset..offset + len]))
+            .expect("composite row key component must decode");
+        sorted_values.push(value);
+        offset += len;
+    }
+
+    let mut sorted_columns = pk_columns.to_vec();
+    sorted_columns.sort_unstable();
+    pk_columns
+        .iter()
+        .map(|column| {
+            let position = sorted_columns
+                .iter()
+                .position(|sorted| sorted == column)
+                .expect("primary key column must be present");
+            sorted_values[position].clone()
+        })
+        .collect()
+}
+
+/// Builds the filter matching the record identified by `key` in table `T`.
+pub(crate) fn row_key_filter<T>(key: &Value) -> Filter
+where
+    T: TableSchema,
+{
+    let pk_columns = T::primary_keys();
+    row_key_components(pk_columns, key)
+        .into_iter()
+        .zip(pk_columns)
+        .map(|(value, column)| Filter::eq(column, value))
+        .reduce(Filter::and)
+        .expect("table must have at least one primary key column")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use wasm_dbms_api::prelude::{DataTypeKind, Text, Uint32};
+
+    use super::*;
+
+    fn column(name: &'static str, primary_key: bool) -> ColumnDef {
+        ColumnDef {
+            name,
+            data_type: DataTypeKind::Uint32,
+            auto_increment: false,
+            nullable: false,
+            primary_key,
+            unique: false,
+            foreign_key: None,
+            default: None,
+            renamed_from: &[],
+            compress: false,
+        }
+    }
+
+    #[test]
+    fn test_single_column_row_key_is_the_value() {
+        let row = vec![
+            (column("id", true), Value::Uint32(Uint32(7))),
+            (column("name", false), Value::Text(Text("a".to_string()))),
+        ];
+        assert_eq!(row_key(&row), Some(Value::Uint32(Uint32(7))));
+        assert_eq!(
+            row_key_components(&["id"], &Value::Uint32(Uint32(7))),
+            vec![Value::Uint32(Uint32(7))]
+        );
+    }
+
+    #[test]
+    fn test_row_key_without_primary_key_is_none() {
+        let row = vec![(column("name", false), Value::Uint32(Uint32(1)))];
+        assert_eq!(row_key(&row), None);
+    }
+
+    #[test]
+    fn test_composite_row_key_ignores_value_order() {
+        let a = vec![
+            (column("user_id", true), Value::Uint32(Uint32(1))),
+            (column("role_id", true), Value::Uint32(Uint32(2))),
+        ];
+        let b = vec![
+            (column("role_id", true), Value::Uint32(Uint32(2))),
+            (column("user_id", true), Value::Uint32(Uint32(1))),
+        ];
+        assert_eq!(row_key(&a), row_key(&b));
+
+        let swapped = vec![
+            (column("user_id", true), Value::Uint32(Uint32(2))),
+            (column("role_id", true), Value::Uint32(Uint32(1))),
+        ];
+        assert_ne!(row_key(&a), row_key(&swapped));
+    }
+
+    #[test]
+    fn test_composite_row_key_round_trips_components() {
+        let row = vec![
+            (column("user_id", true), Value::Uint32(Uint32(1))),
+            (
+                column("role_id", true),
+                Value::Text(Text("admin".to_string())),
+            ),
+        ];
+        let key = row_key(&row).unwrap();
+        assert_eq!(
+            row_key_components(&["user_id", "role_id"], &key),
+            vec![
+                Value::Uint32(Uint32(1)),
+                Value::Text(Text("admin".to_string()))
+            ]
+        );
+    }
+}
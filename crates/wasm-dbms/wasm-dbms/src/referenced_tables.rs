@@ -4,7 +4,7 @@
 //!
 //! Identifies which tables reference a given target table via foreign keys.
 
-use wasm_dbms_api::prelude::ColumnDef;
+use wasm_dbms_api::prelude::{ColumnDef, ForeignKeyDef};
 
 /// Returns the list of tables that reference the target table.
 pub fn get_referenced_tables(
@@ -29,3 +29,21 @@ pub fn get_referenced_tables(
         })
         .collect()
 }
+
+/// Returns the foreign keys that reference the target table, each with the
+/// name of the table holding it.
+pub fn get_referencing_foreign_keys(
+    target: &'static str,
+    tables: &[(&'static str, &'static [ColumnDef])],
+) -> Vec<(&'static str, ForeignKeyDef)> {
+    tables
+        .iter()
+        .flat_map(|(table_name, columns)| {
+            columns
+                .iter()
+                .filter_map(|col| col.foreign_key)
+                .filter(|fk| fk.foreign_table == target)
+                .map(|fk| (*table_name, fk))
+        })
+        .collect()
+}
@@ -2,8 +2,9 @@
 // X-WHERE-CLAUSE, M-CANONICAL-DOCS
 
 use wasm_dbms_api::prelude::{
-    AggregateFunction, AggregatedRow, ColumnDef, DbmsResult, DeleteBehavior, Filter, JoinColumnDef,
-    Query, TableSchemaSnapshot, Value,
+    AggregateFunction, AggregatedRow, BlobChunk, ColumnDef, CompactionReport, DbmsResult,
+    DeleteBehavior, Filter, ForeignKeyDef, InsertConflictBehavior, JoinColumnDef, Query,
+    TableSchemaSnapshot, UpsertResult, Value,
 };
 use wasm_dbms_memory::prelude::{AccessControl, AccessControlList, MemoryProvider};
 
@@ -23,6 +24,9 @@ where
     A: AccessControl,
 {
     /// Performs a generic select for the given table name and query.
+    ///
+    /// Soft-deleted rows are hidden unless the query sets
+    /// [`Query::include_deleted`](wasm_dbms_api::prelude::Query::include_deleted).
     fn select(
         &self,
         dbms: &WasmDbmsDatabase<'_, M, A>,
@@ -50,9 +54,29 @@ where
         aggregates: &[AggregateFunction],
     ) -> DbmsResult<Vec<AggregatedRow>>;
 
+    /// Counts the rows matching `filter` for the given table name.
+    fn count(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &str,
+        filter: Option<Filter>,
+    ) -> DbmsResult<u64>;
+
+    /// Returns whether any row of the given table name matches `filter`.
+    fn exists(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &str,
+        filter: Filter,
+    ) -> DbmsResult<bool>;
+
     /// Returns tables and columns that reference the given table via foreign keys.
     fn referenced_tables(&self, table: &'static str) -> Vec<(&'static str, Vec<&'static str>)>;
 
+    /// Returns the foreign keys that reference the given table, each with the
+    /// name of the table holding it.
+    fn referencing_foreign_keys(&self, table: &'static str) -> Vec<(&'static str, ForeignKeyDef)>;
+
     /// Performs an insert for the given table name.
     fn insert(
         &self,
@@ -61,6 +85,41 @@ where
         record_values: &[(ColumnDef, Value)],
     ) -> DbmsResult<()>;
 
+    /// Performs an insert for the given table name, resolving conflicts
+    /// with existing rows according to `behavior`.
+    fn insert_with_behavior(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+        record_values: &[(ColumnDef, Value)],
+        behavior: InsertConflictBehavior,
+    ) -> DbmsResult<()>;
+
+    /// Performs an insert for the given table name, returning the row as
+    /// stored.
+    fn insert_returning(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+        record_values: &[(ColumnDef, Value)],
+    ) -> DbmsResult<Vec<(ColumnDef, Value)>>;
+
+    /// Performs an upsert for the given table name.
+    fn upsert(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+        record_values: &[(ColumnDef, Value)],
+    ) -> DbmsResult<UpsertResult>;
+
+    /// Performs a batch insert for the given table name.
+    fn insert_many(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+        rows: &[Vec<(ColumnDef, Value)>],
+    ) -> DbmsResult<u64>;
+
     /// Performs a delete for the given table name.
     fn delete(
         &self,
@@ -70,6 +129,57 @@ where
         filter: Option<Filter>,
     ) -> DbmsResult<u64>;
 
+    /// Physically deletes rows for the given table name, bypassing soft
+    /// delete.
+    fn force_delete(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+        delete_behavior: DeleteBehavior,
+        filter: Option<Filter>,
+    ) -> DbmsResult<u64>;
+
+    /// Deletes every row of the given table name at once.
+    fn truncate(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+    ) -> DbmsResult<u64>;
+
+    /// Rewrites the records of the given table name contiguously.
+    fn defragment(&self, dbms: &WasmDbmsDatabase<'_, M, A>, table_name: &str) -> DbmsResult<u64>;
+
+    /// Releases at most `max_pages` record pages of the given table name.
+    fn compact(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &str,
+        max_pages: u64,
+    ) -> DbmsResult<CompactionReport>;
+
+    /// Reads a chunk of a blob column of the given table name.
+    fn blob_read_chunk(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        len: u64,
+    ) -> DbmsResult<BlobChunk>;
+
+    /// Writes a chunk of a blob column of the given table name, returning the
+    /// new length of the blob.
+    fn blob_write_chunk(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &str,
+        pk: Value,
+        column: &str,
+        offset: u64,
+        bytes: &[u8],
+    ) -> DbmsResult<u64>;
+
     /// Performs an update for the given table name.
     fn update(
         &self,
@@ -79,6 +189,16 @@ where
         filter: Option<Filter>,
     ) -> DbmsResult<u64>;
 
+    /// Performs an update for the given table name, returning the updated
+    /// rows of that table as stored.
+    fn update_returning(
+        &self,
+        dbms: &WasmDbmsDatabase<'_, M, A>,
+        table_name: &'static str,
+        patch_values: &[(ColumnDef, Value)],
+        filter: Option<Filter>,
+    ) -> DbmsResult<Vec<Vec<(ColumnDef, Value)>>>;
+
     /// Validates an insert operation.
     fn validate_insert(
         &self,
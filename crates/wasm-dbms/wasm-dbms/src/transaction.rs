@@ -7,10 +7,12 @@ mod overlay;
 pub mod session;
 
 use wasm_dbms_api::prelude::{
-    ColumnDef, DbmsResult, DeleteBehavior, Filter, TableSchema, UpdateRecord as _, Value,
+    ColumnDef, DbmsError, DbmsResult, DeleteBehavior, Filter, SavepointId, TableSchema,
+    TransactionError, Value,
 };
 
-pub use self::overlay::{DatabaseOverlay, IndexOverlay};
+pub use self::overlay::{DatabaseOverlay, IndexOverlay, OverlayCursor};
+use crate::database::patched_value;
 
 /// A transaction represents a sequence of operations performed as a single
 /// logical unit of work.
@@ -20,6 +22,21 @@ pub struct Transaction {
     pub(crate) operations: Vec<TransactionOp>,
     /// Overlay to track uncommitted changes.
     overlay: DatabaseOverlay,
+    /// Savepoints created in this transaction, oldest first.
+    savepoints: Vec<Savepoint>,
+    /// Next savepoint ID to allocate.
+    next_savepoint_id: SavepointId,
+}
+
+/// A named point within a transaction that can be rolled back to.
+#[derive(Debug)]
+struct Savepoint {
+    id: SavepointId,
+    name: String,
+    /// Length of [`Transaction::operations`] when the savepoint was created.
+    operations_len: usize,
+    /// Overlay state when the savepoint was created.
+    overlay: DatabaseOverlay,
 }
 
 impl Transaction {
@@ -36,28 +53,80 @@ impl Transaction {
         Ok(())
     }
 
+    /// Inserts a batch of records as a single operation into the transaction.
+    pub fn insert_many<T>(&mut self, rows: Vec<Vec<(ColumnDef, Value)>>) -> DbmsResult<()>
+    where
+        T: TableSchema,
+    {
+        for values in &rows {
+            self.overlay.insert::<T>(values.clone())?;
+        }
+        self.operations.push(TransactionOp::InsertMany {
+            table: T::table_name(),
+            rows,
+        });
+        Ok(())
+    }
+
+    /// Inserts a new upsert operation into the transaction.
+    ///
+    /// `existing` is the `(primary_key, current_row)` pair of the row the
+    /// record overwrites, or `None` if the record is new. It only drives the
+    /// overlay: the commit resolves the upsert again against the stored rows.
+    pub fn upsert<T>(
+        &mut self,
+        values: Vec<(ColumnDef, Value)>,
+        existing: Option<(Value, Vec<(ColumnDef, Value)>)>,
+    ) -> DbmsResult<()>
+    where
+        T: TableSchema,
+    {
+        match existing {
+            Some((pk, current_row)) => {
+                let patch = values
+                    .iter()
+                    .filter(|(col, _)| !col.primary_key)
+                    .map(|(col, val)| (col.name, val.clone()))
+                    .collect();
+                self.overlay.update::<T>(pk, patch, &current_row);
+            }
+            None => self.overlay.insert::<T>(values.clone())?,
+        }
+
+        self.operations.push(TransactionOp::Upsert {
+            table: T::table_name(),
+            values,
+        });
+        Ok(())
+    }
+
     /// Inserts a new update operation into the transaction.
     ///
+    /// `patch_values` are the column values written by the update.
     /// `rows` is a list of `(primary_key, current_row)` pairs for each affected record.
     /// The current row is needed to track old indexed values in the overlay.
     pub fn update<T>(
         &mut self,
-        patch: T::Update,
+        patch_values: Vec<(ColumnDef, Value)>,
         filter: Option<Filter>,
         rows: Vec<(Value, Vec<(ColumnDef, Value)>)>,
     ) -> DbmsResult<()>
     where
         T: TableSchema,
     {
-        let patch_values = patch.update_values();
-        let overlay_patch: Vec<_> = patch_values
-            .iter()
-            .map(|(col, val)| (col.name, val.clone()))
-            .collect();
-
         for (pk, current_row) in rows {
-            self.overlay
-                .update::<T>(pk, overlay_patch.clone(), &current_row);
+            // JSON patches are resolved against each row
+            let overlay_patch = patch_values
+                .iter()
+                .map(|(col, val)| {
+                    let current = current_row
+                        .iter()
+                        .find(|(current_col, _)| current_col.name == col.name)
+                        .map_or(&Value::Null, |(_, current)| current);
+                    patched_value(col.name, current, val).map(|val| (col.name, val))
+                })
+                .collect::<DbmsResult<Vec<_>>>()?;
+            self.overlay.update::<T>(pk, overlay_patch, &current_row);
         }
 
         self.operations.push(TransactionOp::Update {
@@ -93,6 +162,52 @@ impl Transaction {
         Ok(())
     }
 
+    /// Creates a savepoint capturing the current state of the transaction.
+    pub fn create_savepoint(&mut self, name: impl Into<String>) -> SavepointId {
+        let id = self.next_savepoint_id;
+        self.next_savepoint_id += 1;
+
+        self.savepoints.push(Savepoint {
+            id,
+            name: name.into(),
+            operations_len: self.operations.len(),
+            overlay: self.overlay.clone(),
+        });
+
+        id
+    }
+
+    /// Rolls the transaction back to the given savepoint.
+    ///
+    /// Operations recorded after the savepoint are discarded and the overlay
+    /// is restored to its state at that time. The savepoint itself is kept,
+    /// so it can be rolled back to again; savepoints created after it are
+    /// discarded.
+    pub fn rollback_to_savepoint(&mut self, savepoint: SavepointId) -> DbmsResult<()> {
+        let position = self
+            .savepoints
+            .iter()
+            .position(|sp| sp.id == savepoint)
+            .ok_or(DbmsError::Transaction(TransactionError::SavepointNotFound(
+                savepoint,
+            )))?;
+
+        self.savepoints.truncate(position + 1);
+        let savepoint = &self.savepoints[position];
+        self.operations.truncate(savepoint.operations_len);
+        self.overlay = savepoint.overlay.clone();
+
+        Ok(())
+    }
+
+    /// Returns the name of the given savepoint, if it exists.
+    pub fn savepoint_name(&self, savepoint: SavepointId) -> Option<&str> {
+        self.savepoints
+            .iter()
+            .find(|sp| sp.id == savepoint)
+            .map(|sp| sp.name.as_str())
+    }
+
     /// Returns a reference to the overlay.
     pub fn overlay(&self) -> &DatabaseOverlay {
         &self.overlay
@@ -111,6 +226,10 @@ pub enum TransactionOp {
         table: &'static str,
         values: Vec<(ColumnDef, Value)>,
     },
+    InsertMany {
+        table: &'static str,
+        rows: Vec<Vec<(ColumnDef, Value)>>,
+    },
     Delete {
         table: &'static str,
         behaviour: DeleteBehavior,
@@ -121,6 +240,10 @@ pub enum TransactionOp {
         patch: Vec<(ColumnDef, Value)>,
         filter: Option<Filter>,
     },
+    Upsert {
+        table: &'static str,
+        values: Vec<(ColumnDef, Value)>,
+    },
 }
 
 #[cfg(test)]
@@ -169,6 +292,32 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_transaction_insert_many_records_single_operation() {
+        let mut tx = Transaction::default();
+        let rows = (1..=3)
+            .map(|id| {
+                vec![
+                    (Item::columns()[0], Value::Uint32(Uint32(id))),
+                    (Item::columns()[1], Value::Text(Text(format!("item{id}")))),
+                ]
+            })
+            .collect();
+        tx.insert_many::<Item>(rows).unwrap();
+        assert_eq!(tx.operations.len(), 1);
+        assert!(matches!(
+            &tx.operations[0],
+            TransactionOp::InsertMany { table: "items", rows } if rows.len() == 3
+        ));
+        let inserted = tx
+            .overlay()
+            .table_overlay("items")
+            .unwrap()
+            .iter_inserted()
+            .count();
+        assert_eq!(inserted, 3);
+    }
+
     #[test]
     fn test_transaction_update_records_operation() {
         let mut tx = Transaction::default();
@@ -181,7 +330,7 @@ mod tests {
             (Item::columns()[1], Value::Text(Text("foo".to_string()))),
         ];
         tx.update::<Item>(
-            patch,
+            patch.update_values(),
             Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
             vec![(Value::Uint32(Uint32(1)), current_row)],
         )
@@ -193,6 +342,35 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_transaction_upsert_records_operation() {
+        let mut tx = Transaction::default();
+        let values = vec![
+            (Item::columns()[0], Value::Uint32(Uint32(1))),
+            (Item::columns()[1], Value::Text(Text("foo".to_string()))),
+        ];
+        tx.upsert::<Item>(values.clone(), None).unwrap();
+        let updated = vec![
+            (Item::columns()[0], Value::Uint32(Uint32(1))),
+            (Item::columns()[1], Value::Text(Text("bar".to_string()))),
+        ];
+        tx.upsert::<Item>(updated, Some((Value::Uint32(Uint32(1)), values)))
+            .unwrap();
+        assert_eq!(tx.operations.len(), 2);
+        assert!(
+            tx.operations
+                .iter()
+                .all(|op| matches!(op, TransactionOp::Upsert { table: "items", .. }))
+        );
+        let inserted = tx
+            .overlay()
+            .table_overlay("items")
+            .unwrap()
+            .iter_inserted()
+            .count();
+        assert_eq!(inserted, 1);
+    }
+
     #[test]
     fn test_transaction_delete_records_operation() {
         let mut tx = Transaction::default();
@@ -270,6 +448,91 @@ mod tests {
         assert!(rows.is_empty());
     }
 
+    fn item_insert(id: u32, name: &str) -> ItemInsertRequest {
+        ItemInsertRequest::from_values(&[
+            (Item::columns()[0], Value::Uint32(Uint32(id))),
+            (Item::columns()[1], Value::Text(Text(name.to_string()))),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_savepoint_truncates_operations_and_overlay() {
+        let mut tx = Transaction::default();
+        let values = vec![
+            (Item::columns()[0], Value::Uint32(Uint32(1))),
+            (Item::columns()[1], Value::Text(Text("a".to_string()))),
+        ];
+        tx.insert::<Item>(values).unwrap();
+        let savepoint = tx.create_savepoint("after_first");
+
+        let values = vec![
+            (Item::columns()[0], Value::Uint32(Uint32(2))),
+            (Item::columns()[1], Value::Text(Text("b".to_string()))),
+        ];
+        tx.insert::<Item>(values).unwrap();
+        assert_eq!(tx.operations.len(), 2);
+
+        tx.rollback_to_savepoint(savepoint).unwrap();
+        assert_eq!(tx.operations.len(), 1);
+        let inserted: Vec<_> = tx
+            .overlay()
+            .table_overlay("items")
+            .unwrap()
+            .iter_inserted()
+            .collect();
+        assert_eq!(inserted.len(), 1);
+    }
+
+    #[test]
+    fn test_rollback_to_savepoint_keeps_earlier_operations_on_commit() {
+        let ctx = setup();
+        let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+        let mut db = WasmDbmsDatabase::from_transaction(&ctx, TestSchema, tx_id);
+
+        db.insert::<Item>(item_insert(1, "kept")).unwrap();
+        let savepoint = ctx.create_savepoint(&tx_id, "before_second").unwrap();
+        db.insert::<Item>(item_insert(2, "discarded")).unwrap();
+        db.update::<Item>(ItemUpdateRequest::from_values(
+            &[(Item::columns()[1], Value::Text(Text("renamed".to_string())))],
+            Some(Filter::eq("id", Value::Uint32(Uint32(1)))),
+        ))
+        .unwrap();
+
+        ctx.rollback_to_savepoint(&tx_id, savepoint).unwrap();
+
+        // the open transaction no longer sees the discarded changes
+        let rows = db.select::<Item>(Query::builder().build()).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].name, Some(Text("kept".to_string())));
+
+        // the transaction can keep going after the partial rollback
+        db.insert::<Item>(item_insert(3, "after")).unwrap();
+        db.commit().unwrap();
+
+        let db = WasmDbmsDatabase::oneshot(&ctx, TestSchema);
+        let rows = db
+            .select::<Item>(Query::builder().order_by_asc("id").build())
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].id, Some(Uint32(1)));
+        assert_eq!(rows[0].name, Some(Text("kept".to_string())));
+        assert_eq!(rows[1].id, Some(Uint32(3)));
+    }
+
+    #[test]
+    fn test_rollback_to_unknown_savepoint_returns_error() {
+        let ctx = setup();
+        let tx_id = ctx.begin_transaction(vec![1, 2, 3]);
+        let result = ctx.rollback_to_savepoint(&tx_id, 7);
+        assert!(matches!(
+            result,
+            Err(DbmsError::Transaction(TransactionError::SavepointNotFound(
+                7
+            )))
+        ));
+    }
+
     #[test]
     fn test_rollback_without_transaction_returns_error() {
         let ctx = setup();
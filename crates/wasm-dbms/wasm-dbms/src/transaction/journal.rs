@@ -407,6 +407,34 @@ mod tests {
         assert_ne!(next, page);
     }
 
+    #[test]
+    fn test_journal_rollback_restores_copied_page() {
+        let mut mm = make_mm();
+        let src = mm.claim_page().expect("Failed to claim page");
+        let dst = mm.claim_page().expect("Failed to claim page");
+        mm.write_at_raw(src, 0, &[1, 2, 3, 4])
+            .expect("Failed to write source");
+        mm.write_at_raw(dst, 0, &[0xDE, 0xAD, 0xBE, 0xEF])
+            .expect("Failed to write destination");
+
+        let mut journal = Journal::new();
+        {
+            let mut writer = JournaledWriter::new(&mut mm, &mut journal);
+            writer.copy_page(src, dst).expect("Failed to copy page");
+        }
+
+        let mut buf = [0u8; 4];
+        mm.read_at_raw(dst, 0, &mut buf).expect("Failed to read");
+        assert_eq!(buf, [1, 2, 3, 4]);
+
+        journal
+            .rollback(&mut mm)
+            .expect("Failed to rollback journal");
+
+        mm.read_at_raw(dst, 0, &mut buf).expect("Failed to read");
+        assert_eq!(buf, [0xDE, 0xAD, 0xBE, 0xEF]);
+    }
+
     #[test]
     fn test_journal_rollback_mixed_write_at_and_zero() {
         let mut mm = make_mm();
@@ -7,12 +7,13 @@ mod table;
 
 use std::collections::HashMap;
 
-use wasm_dbms_api::prelude::{ColumnDef, DbmsError, DbmsResult, QueryError, TableSchema, Value};
-use wasm_dbms_memory::prelude::{MemoryAccess, TableReader};
+use wasm_dbms_api::prelude::{ColumnDef, DbmsResult, TableSchema, Value};
+use wasm_dbms_memory::prelude::{MemoryAccess, TableReader, TableRegistry};
 
-pub use self::reader::DatabaseOverlayReader;
+pub use self::reader::{DatabaseOverlayReader, OverlayCursor};
 pub use self::table::IndexOverlay;
 pub(crate) use self::table::TableOverlay;
+use crate::primary_key::table_row_key;
 
 /// Manages uncommitted changes during a transaction.
 ///
@@ -41,14 +42,33 @@ impl DatabaseOverlay {
         DatabaseOverlayReader::new(table_overlay, table_reader)
     }
 
+    /// Returns a reader resuming from a cursor obtained with
+    /// [`DatabaseOverlayReader::into_cursor`].
+    pub fn resume_reader<'a, T, MA>(
+        &'a mut self,
+        table_registry: &'a TableRegistry,
+        mm: &'a mut MA,
+        cursor: OverlayCursor,
+    ) -> DatabaseOverlayReader<'a, T, MA>
+    where
+        T: TableSchema,
+        MA: MemoryAccess,
+    {
+        let table_name = T::table_name();
+        let table_overlay = self
+            .tables
+            .entry(table_name.to_string())
+            .or_insert_with(|| TableOverlay::new(T::indexes()));
+        DatabaseOverlayReader::resume(table_overlay, table_registry, mm, cursor)
+    }
+
     /// Inserts a record into the overlay for the specified table.
     pub fn insert<T>(&mut self, values: Vec<(ColumnDef, Value)>) -> DbmsResult<()>
     where
         T: TableSchema,
     {
         let table_name = T::table_name();
-        let pk = T::primary_key();
-        let pk = Self::primary_key(pk, &values)?;
+        let pk = table_row_key::<T>(&values)?;
         let overlay = self
             .tables
             .entry(table_name.to_string())
@@ -101,15 +121,4 @@ impl DatabaseOverlay {
     pub(crate) fn table_overlay(&self, table: &str) -> Option<&TableOverlay> {
         self.tables.get(table)
     }
-
-    fn primary_key(pk: &'static str, values: &[(ColumnDef, Value)]) -> DbmsResult<Value> {
-        for (col_def, value) in values {
-            if col_def.name == pk {
-                return Ok(value.clone());
-            }
-        }
-        Err(DbmsError::Query(QueryError::MissingNonNullableField(
-            pk.to_string(),
-        )))
-    }
 }
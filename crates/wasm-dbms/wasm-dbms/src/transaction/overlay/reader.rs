@@ -3,10 +3,20 @@
 //! Overlay reader that merges base table data with overlay changes.
 
 use wasm_dbms_api::prelude::{ColumnDef, DbmsResult, TableSchema, Value};
-use wasm_dbms_memory::prelude::{MemoryAccess, TableReader};
+use wasm_dbms_memory::prelude::{MemoryAccess, TableCursor, TableReader, TableRegistry};
 
 use super::table::TableOverlay;
 
+/// Saved position of a [`DatabaseOverlayReader`], used to resume reading later.
+///
+/// See [`super::DatabaseOverlay::resume_reader`].
+#[derive(Debug, Clone)]
+pub struct OverlayCursor {
+    inserted_rows: Vec<Vec<(ColumnDef, Value)>>,
+    new_rows_cursor: usize,
+    table_cursor: TableCursor,
+}
+
 /// A reader that merges base table data with overlay changes.
 pub struct DatabaseOverlayReader<'a, T, MA>
 where
@@ -41,13 +51,38 @@ where
         }
     }
 
+    /// Creates an overlay reader resuming from the given [`OverlayCursor`].
+    pub fn resume(
+        table_overlay: &'a TableOverlay,
+        table_registry: &'a TableRegistry,
+        mm: &'a mut MA,
+        cursor: OverlayCursor,
+    ) -> Self {
+        Self {
+            inserted_rows: cursor.inserted_rows,
+            new_rows_cursor: cursor.new_rows_cursor,
+            table_overlay,
+            table_reader: table_registry.resume_read(cursor.table_cursor, mm),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Consumes the reader, returning the [`OverlayCursor`] to resume it from.
+    pub fn into_cursor(self) -> OverlayCursor {
+        OverlayCursor {
+            inserted_rows: self.inserted_rows,
+            new_rows_cursor: self.new_rows_cursor,
+            table_cursor: self.table_reader.into_cursor(),
+        }
+    }
+
     /// Attempts to get the next row, applying overlay changes.
     pub fn try_next(&mut self) -> DbmsResult<Option<Vec<(ColumnDef, Value)>>> {
         loop {
-            let next_base_row = self
-                .table_reader
-                .try_next()?
-                .map(|row| row.record.to_values());
+            let next_base_row = match self.table_reader.try_next_ref()? {
+                Some(row) => Some(row.decode()?.to_values()),
+                None => None,
+            };
 
             let Some(next_row) = next_base_row.or_else(|| self.next_overlay_row()) else {
                 return Ok(None);
@@ -5,6 +5,7 @@ mod index;
 use wasm_dbms_api::prelude::{ColumnDef, IndexDef, Value};
 
 pub use self::index::IndexOverlay;
+use crate::primary_key::row_key;
 
 /// The table overlay tracks uncommitted changes for a specific table.
 #[derive(Debug, Clone)]
@@ -163,11 +164,7 @@ impl TableOverlay {
     #[allow(clippy::manual_try_fold)]
     pub fn patch_row(&self, row: Vec<(ColumnDef, Value)>) -> Option<Vec<(ColumnDef, Value)>> {
         // get primary key value
-        let mut current_pk = row
-            .iter()
-            .find(|(col_def, _)| col_def.primary_key)
-            .map(|(_, value)| value)
-            .cloned()?;
+        let mut current_pk = row_key(&row)?;
 
         // apply all operations for this primary key to the row, tracking PK changes
         let mut current_row = Some(row);
@@ -176,15 +173,16 @@ impl TableOverlay {
                 continue;
             }
             current_row = self.apply_operation(current_row, op);
-            // If an update changed the PK column, track the new value
+            // If an update changed a PK column, track the new value
             if let (Some(patched), Operation::Update(_, updates)) = (&current_row, op)
-                && let Some((_, new_pk)) = updates.iter().find(|(name, _)| {
+                && updates.iter().any(|(name, _)| {
                     patched
                         .iter()
                         .any(|(col_def, _)| col_def.primary_key && col_def.name == *name)
                 })
+                && let Some(new_pk) = row_key(patched)
             {
-                current_pk = new_pk.clone();
+                current_pk = new_pk;
             }
         }
 
@@ -250,6 +248,7 @@ mod tests {
             foreign_key: None,
             default: None,
             renamed_from: &[],
+            compress: false,
         }
     }
 
@@ -300,6 +299,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 pk.clone(),
             ),
@@ -314,6 +314,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text("Alice".to_string().into()),
             ),
@@ -328,6 +329,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Uint32(24.into()),
             ),
@@ -366,6 +368,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     pk.clone(),
                 ),
@@ -380,6 +383,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text("Bob".to_string().into()),
                 ),
@@ -394,6 +398,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Uint32(30.into()),
                 ),
@@ -419,6 +424,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     first_pk.clone(),
                 ),
@@ -433,6 +439,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text("Alice".to_string().into()),
                 ),
@@ -447,6 +454,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Uint32(24.into()),
                 ),
@@ -467,6 +475,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     second_pk.clone(),
                 ),
@@ -481,6 +490,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text("Bob".to_string().into()),
                 ),
@@ -495,6 +505,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Uint32(32.into()),
                 ),
@@ -514,6 +525,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 second_pk.clone(),
             ),
@@ -528,6 +540,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text("Bob".to_string().into()),
             ),
@@ -542,6 +555,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Uint32(32.into()),
             ),
@@ -568,6 +582,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     third_pk.clone(),
                 ),
@@ -582,6 +597,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text("Charlie".to_string().into()),
                 ),
@@ -596,6 +612,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Uint32(28.into()),
                 ),
@@ -615,6 +632,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 third_pk.clone(),
             ),
@@ -629,6 +647,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Text("Charlie".to_string().into()),
             ),
@@ -643,6 +662,7 @@ mod tests {
                     foreign_key: None,
                     default: None,
                     renamed_from: &[],
+                    compress: false,
                 },
                 Value::Uint32(28.into()),
             ),
@@ -677,6 +697,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     first_pk.clone(),
                 ),
@@ -691,6 +712,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text("Alice".to_string().into()),
                 ),
@@ -705,6 +727,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Uint32(24.into()),
                 ),
@@ -724,6 +747,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     second_pk.clone(),
                 ),
@@ -738,6 +762,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Text("Robert".to_string().into()), // patched name
                 ),
@@ -752,6 +777,7 @@ mod tests {
                         foreign_key: None,
                         default: None,
                         renamed_from: &[],
+                        compress: false,
                     },
                     Value::Uint32(33.into()), // patched age
                 ),
@@ -2,11 +2,14 @@
 
 //! Transaction session storage.
 //!
-//! Tracks active transactions and their ownership by identity.
+//! Tracks active transactions, their ownership by identity, their expiry and
+//! the number of transactions each identity may keep open.
 
 use std::collections::HashMap;
 
-use wasm_dbms_api::prelude::{DbmsError, DbmsResult, QueryError, TransactionId};
+use wasm_dbms_api::prelude::{
+    DbmsError, DbmsResult, QueryError, SavepointId, TransactionError, TransactionId,
+};
 
 use super::Transaction;
 
@@ -17,12 +20,56 @@ pub struct TransactionSession {
     transactions: HashMap<TransactionId, Transaction>,
     /// Map between transaction IDs and their owner identity bytes.
     owners: HashMap<TransactionId, Vec<u8>>,
+    /// Start time and TTL of the transactions begun with
+    /// [`Self::begin_transaction_with_ttl`].
+    clocks: HashMap<TransactionId, TransactionClock>,
+    /// Owners of the transactions dropped by [`Self::expire_transactions`],
+    /// kept until the owner commits or rolls back, so that it gets
+    /// [`TransactionError::TransactionExpired`] instead of a missing
+    /// transaction.
+    expired: HashMap<TransactionId, Vec<u8>>,
+    /// Maximum number of transactions an owner may keep open, enforced by
+    /// [`Self::begin_transaction_with_ttl`]. `None` means unlimited.
+    max_open_transactions: Option<u32>,
     /// Next transaction ID to allocate.
     next_transaction_id: TransactionId,
 }
 
+/// Start time and time-to-live of a transaction, in nanoseconds.
+#[derive(Debug, Clone, Copy)]
+struct TransactionClock {
+    started_at: u64,
+    ttl: Option<u64>,
+}
+
+impl TransactionClock {
+    /// Returns whether the transaction is past its TTL at `now`.
+    fn is_expired(&self, now: u64) -> bool {
+        self.ttl
+            .is_some_and(|ttl| now.saturating_sub(self.started_at) >= ttl)
+    }
+}
+
+/// Metadata of an active transaction, as reported by
+/// [`TransactionSession::list_active_transactions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionInfo {
+    /// ID of the transaction.
+    pub id: TransactionId,
+    /// Identity bytes of the transaction owner.
+    pub owner: Vec<u8>,
+    /// Time the transaction was begun at, in nanoseconds; `None` if it was
+    /// begun with [`TransactionSession::begin_transaction`], which takes no
+    /// clock.
+    pub started_at: Option<u64>,
+    /// Number of operations recorded in the transaction.
+    pub operation_count: usize,
+}
+
 impl TransactionSession {
     /// Begins a new transaction for the given owner identity and returns its ID.
+    ///
+    /// The transaction never expires; see [`Self::begin_transaction_with_ttl`].
     pub fn begin_transaction(&mut self, owner: Vec<u8>) -> TransactionId {
         let transaction_id = self.next_transaction_id;
         self.next_transaction_id += 1;
@@ -34,6 +81,115 @@ impl TransactionSession {
         transaction_id
     }
 
+    /// Begins a new transaction created at `now` that expires `ttl`
+    /// nanoseconds later, and returns its ID.
+    ///
+    /// Expired transactions are dropped by [`Self::expire_transactions`]. A
+    /// `None` TTL never expires.
+    ///
+    /// Unlike [`Self::begin_transaction`], this enforces the limit set with
+    /// [`Self::set_max_open_transactions`], failing with
+    /// [`TransactionError::TooManyOpenTransactions`] when the owner already
+    /// holds that many open transactions.
+    pub fn begin_transaction_with_ttl(
+        &mut self,
+        owner: Vec<u8>,
+        now: u64,
+        ttl: Option<u64>,
+    ) -> Result<TransactionId, TransactionError> {
+        if let Some(limit) = self.max_open_transactions
+            && self.open_transactions(&owner) >= limit as usize
+        {
+            return Err(TransactionError::TooManyOpenTransactions { limit });
+        }
+
+        let transaction_id = self.begin_transaction(owner);
+        self.clocks.insert(
+            transaction_id,
+            TransactionClock {
+                started_at: now,
+                ttl,
+            },
+        );
+
+        Ok(transaction_id)
+    }
+
+    /// Sets the maximum number of transactions an owner may keep open; `None`
+    /// removes the limit.
+    ///
+    /// Lowering the limit does not close the transactions already open.
+    pub fn set_max_open_transactions(&mut self, limit: Option<u32>) {
+        self.max_open_transactions = limit;
+    }
+
+    /// Returns the number of transactions currently open by the given owner.
+    pub fn open_transactions(&self, owner: &[u8]) -> usize {
+        self.owners
+            .values()
+            .filter(|candidate| candidate.as_slice() == owner)
+            .count()
+    }
+
+    /// Drops every transaction past its TTL at `now`, reclaiming its overlay,
+    /// and returns the IDs of the dropped transactions.
+    ///
+    /// Further accesses to a dropped transaction fail with
+    /// [`TransactionError::TransactionExpired`].
+    pub fn expire_transactions(&mut self, now: u64) -> Vec<TransactionId> {
+        let mut expired: Vec<TransactionId> = self
+            .clocks
+            .iter()
+            .filter(|(_, clock)| clock.is_expired(now))
+            .map(|(id, _)| *id)
+            .collect();
+        expired.sort_unstable();
+
+        for transaction_id in &expired {
+            self.clocks.remove(transaction_id);
+            self.transactions.remove(transaction_id);
+            if let Some(owner) = self.owners.remove(transaction_id) {
+                self.expired.insert(*transaction_id, owner);
+            }
+        }
+
+        expired
+    }
+
+    /// Returns the metadata of every active transaction, sorted by ID.
+    pub fn list_active_transactions(&self) -> Vec<TransactionInfo> {
+        let mut transactions: Vec<TransactionInfo> = self
+            .transactions
+            .iter()
+            .map(|(id, transaction)| TransactionInfo {
+                id: *id,
+                owner: self.owners.get(id).cloned().unwrap_or_default(),
+                started_at: self.clocks.get(id).map(|clock| clock.started_at),
+                operation_count: transaction.operations.len(),
+            })
+            .collect();
+        transactions.sort_unstable_by_key(|info| info.id);
+
+        transactions
+    }
+
+    /// Checks whether the transaction was dropped by the expiry sweep and was
+    /// owned by the given identity.
+    pub fn has_expired_transaction(&self, transaction_id: &TransactionId, caller: &[u8]) -> bool {
+        self.expired
+            .get(transaction_id)
+            .is_some_and(|owner| owner.as_slice() == caller)
+    }
+
+    /// Returns the error for a transaction that is not active.
+    fn missing_transaction(&self, transaction_id: &TransactionId) -> DbmsError {
+        if self.expired.contains_key(transaction_id) {
+            DbmsError::Transaction(TransactionError::TransactionExpired(*transaction_id))
+        } else {
+            DbmsError::Query(QueryError::TransactionNotFound)
+        }
+    }
+
     /// Checks whether a transaction exists and is owned by the given identity.
     pub fn has_transaction(&self, transaction_id: &TransactionId, caller: &[u8]) -> bool {
         self.owners
@@ -45,16 +201,20 @@ impl TransactionSession {
     pub fn get_transaction(&self, transaction_id: &TransactionId) -> DbmsResult<&Transaction> {
         self.transactions
             .get(transaction_id)
-            .ok_or(DbmsError::Query(QueryError::TransactionNotFound))
+            .ok_or_else(|| self.missing_transaction(transaction_id))
     }
 
     /// Removes and returns the transaction (used during commit).
+    ///
+    /// Taking an expired transaction reports the expiry once, then forgets it.
     pub fn take_transaction(&mut self, transaction_id: &TransactionId) -> DbmsResult<Transaction> {
-        let transaction = self
-            .transactions
-            .remove(transaction_id)
-            .ok_or(DbmsError::Query(QueryError::TransactionNotFound))?;
+        let Some(transaction) = self.transactions.remove(transaction_id) else {
+            let err = self.missing_transaction(transaction_id);
+            self.expired.remove(transaction_id);
+            return Err(err);
+        };
         self.owners.remove(transaction_id);
+        self.clocks.remove(transaction_id);
 
         Ok(transaction)
     }
@@ -63,6 +223,8 @@ impl TransactionSession {
     pub fn close_transaction(&mut self, transaction_id: &TransactionId) {
         self.transactions.remove(transaction_id);
         self.owners.remove(transaction_id);
+        self.clocks.remove(transaction_id);
+        self.expired.remove(transaction_id);
     }
 
     /// Retrieves a mutable reference to the transaction.
@@ -70,16 +232,44 @@ impl TransactionSession {
         &mut self,
         transaction_id: &TransactionId,
     ) -> DbmsResult<&mut Transaction> {
-        self.transactions
+        if !self.transactions.contains_key(transaction_id) {
+            return Err(self.missing_transaction(transaction_id));
+        }
+        Ok(self
+            .transactions
             .get_mut(transaction_id)
-            .ok_or(DbmsError::Query(QueryError::TransactionNotFound))
+            .expect("transaction exists"))
+    }
+
+    /// Creates a named savepoint in the transaction and returns its ID.
+    pub fn create_savepoint(
+        &mut self,
+        transaction_id: &TransactionId,
+        name: impl Into<String>,
+    ) -> DbmsResult<SavepointId> {
+        let transaction = self.get_transaction_mut(transaction_id)?;
+        Ok(transaction.create_savepoint(name))
+    }
+
+    /// Rolls the transaction back to the given savepoint, discarding every
+    /// operation recorded after it.
+    pub fn rollback_to_savepoint(
+        &mut self,
+        transaction_id: &TransactionId,
+        savepoint: SavepointId,
+    ) -> DbmsResult<()> {
+        let transaction = self.get_transaction_mut(transaction_id)?;
+        transaction.rollback_to_savepoint(savepoint)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
+    use wasm_dbms_api::prelude::DeleteBehavior;
+
     use super::*;
+    use crate::transaction::TransactionOp;
 
     #[test]
     fn test_should_begin_transaction() {
@@ -139,4 +329,217 @@ mod tests {
             .get_transaction(&transaction_id)
             .expect("failed to get tx");
     }
+
+    #[test]
+    fn test_should_create_and_rollback_to_savepoint() {
+        let mut session = TransactionSession::default();
+        let transaction_id = session.begin_transaction(vec![1, 2, 3]);
+
+        let first = session
+            .create_savepoint(&transaction_id, "first")
+            .expect("failed to create savepoint");
+        let second = session
+            .create_savepoint(&transaction_id, "second")
+            .expect("failed to create savepoint");
+        assert_ne!(first, second);
+        assert_eq!(
+            session
+                .get_transaction(&transaction_id)
+                .unwrap()
+                .savepoint_name(second),
+            Some("second")
+        );
+
+        session
+            .rollback_to_savepoint(&transaction_id, first)
+            .expect("failed to rollback to savepoint");
+
+        // savepoints created after the target are discarded
+        assert!(matches!(
+            session.rollback_to_savepoint(&transaction_id, second),
+            Err(DbmsError::Transaction(
+                wasm_dbms_api::prelude::TransactionError::SavepointNotFound(_)
+            ))
+        ));
+        // the target savepoint survives
+        assert!(
+            session
+                .rollback_to_savepoint(&transaction_id, first)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_should_expire_transactions_past_their_ttl() {
+        let mut session = TransactionSession::default();
+        let alice = vec![1, 2, 3];
+        let short = session
+            .begin_transaction_with_ttl(alice.clone(), 1_000, Some(500))
+            .unwrap();
+        let long = session
+            .begin_transaction_with_ttl(alice.clone(), 1_000, Some(5_000))
+            .unwrap();
+        let forever = session
+            .begin_transaction_with_ttl(alice.clone(), 1_000, None)
+            .unwrap();
+
+        assert!(session.expire_transactions(1_499).is_empty());
+        assert_eq!(session.expire_transactions(1_500), vec![short]);
+        assert_eq!(session.expire_transactions(u64::MAX), vec![long]);
+
+        assert!(session.has_transaction(&forever, &alice));
+        assert!(session.get_transaction(&forever).is_ok());
+
+        assert!(!session.has_transaction(&short, &alice));
+        assert!(session.has_expired_transaction(&short, &alice));
+        assert!(!session.has_expired_transaction(&short, &[4, 5, 6]));
+        assert!(!session.transactions.contains_key(&short));
+        assert!(matches!(
+            session.get_transaction_mut(&short),
+            Err(DbmsError::Transaction(TransactionError::TransactionExpired(id))) if id == short
+        ));
+        assert!(matches!(
+            session.create_savepoint(&short, "sp"),
+            Err(DbmsError::Transaction(
+                TransactionError::TransactionExpired(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_should_report_expiry_once_on_take() {
+        let mut session = TransactionSession::default();
+        let alice = vec![1, 2, 3];
+        let transaction_id = session
+            .begin_transaction_with_ttl(alice.clone(), 0, Some(10))
+            .unwrap();
+        session.expire_transactions(10);
+
+        assert!(matches!(
+            session.take_transaction(&transaction_id),
+            Err(DbmsError::Transaction(
+                TransactionError::TransactionExpired(_)
+            ))
+        ));
+        assert!(!session.has_expired_transaction(&transaction_id, &alice));
+        assert!(matches!(
+            session.take_transaction(&transaction_id),
+            Err(DbmsError::Query(QueryError::TransactionNotFound))
+        ));
+    }
+
+    #[test]
+    fn test_should_forget_ttl_of_closed_transactions() {
+        let mut session = TransactionSession::default();
+        let transaction_id = session
+            .begin_transaction_with_ttl(vec![1, 2, 3], 0, Some(10))
+            .unwrap();
+        session.close_transaction(&transaction_id);
+
+        assert!(session.expire_transactions(u64::MAX).is_empty());
+        assert!(session.clocks.is_empty());
+        assert!(session.expired.is_empty());
+    }
+
+    #[test]
+    fn test_should_limit_open_transactions_per_owner() {
+        let mut session = TransactionSession::default();
+        let alice = vec![1, 2, 3];
+        let bob = vec![4, 5, 6];
+        session.set_max_open_transactions(Some(2));
+
+        let first = session
+            .begin_transaction_with_ttl(alice.clone(), 0, None)
+            .unwrap();
+        let second = session
+            .begin_transaction_with_ttl(alice.clone(), 0, Some(10))
+            .unwrap();
+        assert_eq!(session.open_transactions(&alice), 2);
+        assert!(matches!(
+            session.begin_transaction_with_ttl(alice.clone(), 0, None),
+            Err(TransactionError::TooManyOpenTransactions { limit: 2 })
+        ));
+        // the limit is per owner
+        assert!(session.begin_transaction_with_ttl(bob, 0, None).is_ok());
+
+        // committing, rolling back and expiring free a slot
+        session.take_transaction(&first).unwrap();
+        let third = session
+            .begin_transaction_with_ttl(alice.clone(), 0, None)
+            .unwrap();
+        session.close_transaction(&third);
+        let _fourth = session
+            .begin_transaction_with_ttl(alice.clone(), 0, None)
+            .unwrap();
+        session.expire_transactions(10);
+        assert!(!session.has_transaction(&second, &alice));
+        assert!(
+            session
+                .begin_transaction_with_ttl(alice.clone(), 0, None)
+                .is_ok()
+        );
+
+        session.set_max_open_transactions(None);
+        assert!(
+            session
+                .begin_transaction_with_ttl(alice.clone(), 0, None)
+                .is_ok()
+        );
+        assert_eq!(session.open_transactions(&alice), 3);
+    }
+
+    #[test]
+    fn test_should_list_active_transactions() {
+        let mut session = TransactionSession::default();
+        let alice = vec![1, 2, 3];
+        let bob = vec![4, 5, 6];
+        let first = session
+            .begin_transaction_with_ttl(alice.clone(), 100, Some(10))
+            .unwrap();
+        let second = session.begin_transaction(bob.clone());
+        let closed = session
+            .begin_transaction_with_ttl(alice.clone(), 200, None)
+            .unwrap();
+        session.close_transaction(&closed);
+        session
+            .get_transaction_mut(&second)
+            .unwrap()
+            .operations
+            .push(TransactionOp::Delete {
+                table: "items",
+                behaviour: DeleteBehavior::Restrict,
+                filter: None,
+            });
+
+        assert_eq!(
+            session.list_active_transactions(),
+            vec![
+                TransactionInfo {
+                    id: first,
+                    owner: alice,
+                    started_at: Some(100),
+                    operation_count: 0,
+                },
+                TransactionInfo {
+                    id: second,
+                    owner: bob,
+                    started_at: None,
+                    operation_count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_should_not_create_savepoint_on_unknown_transaction() {
+        let mut session = TransactionSession::default();
+        assert!(matches!(
+            session.create_savepoint(&42, "sp"),
+            Err(DbmsError::Query(QueryError::TransactionNotFound))
+        ));
+        assert!(matches!(
+            session.rollback_to_savepoint(&42, 0),
+            Err(DbmsError::Query(QueryError::TransactionNotFound))
+        ));
+    }
 }